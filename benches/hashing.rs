@@ -0,0 +1,71 @@
+//! Compares the frame hashing algorithms and hash sizes.
+//!
+//! For each, times hashing 720p frames of synthetic slides and reports how far
+//! apart, as a share of the hash's bits, it puts a slide and the same slide
+//! with compression noise, with one more line of text, and a different slide.
+//! Frames are kept when that share reaches `1 - sensitivity`, 0.1 by default,
+//! so a good hasher keeps noise well under it and changes well over it.
+//!
+//! Run with `cargo bench --bench hashing`.
+
+use image::{DynamicImage, ImageBuffer, Rgb, RgbImage};
+use std::time::Instant;
+use videodocparser::frame_analyzer::{FrameHasher, HASH_ALGORITHMS, HashAlgorithm};
+
+/// Frames hashed for each timing.
+const FRAMES: usize = 20;
+/// Hash sizes compared.
+const SIZES: [usize; 4] = [8, 12, 16, 32];
+
+/// A white 720p slide with a title bar and `lines` lines of text, indented by
+/// `indent` pixels.
+fn slide(lines: u32, indent: u32) -> RgbImage {
+    ImageBuffer::from_fn(1280, 720, |x, y| {
+        let title = (80..140).contains(&y) && (80..800).contains(&x);
+        let line = y >= 200 && (y - 200) / 56 < lines && (y - 200) % 56 < 24 && (indent..1120).contains(&x);
+        if title || line { Rgb([30, 30, 30]) } else { Rgb([250, 250, 250]) }
+    })
+}
+
+/// The frame with a little deterministic noise, as left by video compression.
+fn noisy(frame: &RgbImage) -> RgbImage {
+    let mut noisy = frame.clone();
+    for (x, y, pixel) in noisy.enumerate_pixels_mut() {
+        let offset = ((x * 7 + y * 13) % 11) as i32 - 5;
+        *pixel = Rgb(pixel.0.map(|channel| (channel as i32 + offset).clamp(0, 255) as u8));
+    }
+    noisy
+}
+
+fn main() {
+    let base = DynamicImage::ImageRgb8(slide(5, 160));
+    let cases = [
+        ("noise", DynamicImage::ImageRgb8(noisy(&slide(5, 160)))),
+        ("one more line", DynamicImage::ImageRgb8(slide(6, 160))),
+        ("other slide", DynamicImage::ImageRgb8(slide(8, 480))),
+    ];
+
+    let (noise, line, other) = (cases[0].0, cases[1].0, cases[2].0);
+    println!("{:<10} {:>5} {:>12} {:>8} {:>14} {:>12}", "algorithm", "size", "ms per frame", noise, line, other);
+    for name in HASH_ALGORITHMS {
+        let algorithm = HashAlgorithm::from_name(name).unwrap();
+        for size in SIZES {
+            let hasher = FrameHasher::new(algorithm, size);
+            let start = Instant::now();
+            for _ in 0..FRAMES {
+                std::hint::black_box(hasher.hash(std::hint::black_box(&base)));
+            }
+            let millis = start.elapsed().as_secs_f64() * 1000.0 / FRAMES as f64;
+
+            let hash = hasher.hash(&base);
+            let shares: Vec<f64> = cases
+                .iter()
+                .map(|(_, frame)| hash.distance(&hasher.hash(frame)).unwrap() as f64 / hasher.bits() as f64)
+                .collect();
+            println!(
+                "{:<10} {:>5} {:>12.2} {:>8.3} {:>14.3} {:>12.3}",
+                name, size, millis, shares[0], shares[1], shares[2]
+            );
+        }
+    }
+}
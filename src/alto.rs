@@ -0,0 +1,129 @@
+//! ALTO Module
+//!
+//! Handles the `alto` output format: one ALTO 4.x XML file per page describing
+//! its text lines and words with pixel coordinates and confidences, next to the
+//! page image it refers to, as expected by library and archive ingest pipelines.
+
+use crate::document_builder::{self, Page};
+use crate::layout;
+use crate::ocr::OcrWord;
+use crate::xmp::escape;
+use anyhow::{Context, Result};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+const ALTO_NAMESPACE: &str = "http://www.loc.gov/standards/alto/ns-v4#";
+const ALTO_SCHEMA: &str = "http://www.loc.gov/standards/alto/v4/alto-4-4.xsd";
+
+/// Path of the ALTO file for page `index`, relative to the output directory.
+pub fn alto_path(index: usize) -> String {
+    format!("alto/page_{:03}.xml", index + 1)
+}
+
+/// Writes every page image under `images/` and its ALTO file at [`alto_path`],
+/// both relative to `base_dir`.
+pub fn build_alto(pages: &[Page], base_dir: &Path) -> Result<()> {
+    fs::create_dir_all(base_dir.join("alto")).context("Failed to create alto directory")?;
+    let image_paths = document_builder::page_image_sources(pages, false, base_dir)?;
+    for (i, (page, image_path)) in pages.iter().zip(image_paths).enumerate() {
+        let alto_file = base_dir.join(alto_path(i));
+        // The ALTO files sit one directory below the images folder.
+        let xml = page_xml(i, page, &format!("../{}", image_path))?;
+        fs::write(&alto_file, xml)
+            .with_context(|| format!("Failed to write ALTO file {:?}", alto_file))?;
+    }
+    Ok(())
+}
+
+/// Builds the ALTO document for page `index`, whose image is at `image_path`.
+fn page_xml(index: usize, page: &Page, image_path: &str) -> Result<String> {
+    let (width, height) = page.image.dimensions();
+    let mut xml = String::new();
+    writeln!(xml, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+    writeln!(
+        xml,
+        "<alto xmlns=\"{ns}\" xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\" \
+         xsi:schemaLocation=\"{ns} {schema}\">",
+        ns = ALTO_NAMESPACE,
+        schema = ALTO_SCHEMA
+    )?;
+    writeln!(xml, "  <Description>")?;
+    writeln!(xml, "    <MeasurementUnit>pixel</MeasurementUnit>")?;
+    writeln!(xml, "    <sourceImageInformation>")?;
+    writeln!(xml, "      <fileName>{}</fileName>", escape(image_path))?;
+    writeln!(xml, "    </sourceImageInformation>")?;
+    writeln!(xml, "    <OCRProcessing ID=\"OCR_0\">")?;
+    writeln!(xml, "      <ocrProcessingStep>")?;
+    writeln!(xml, "        <processingSoftware>")?;
+    writeln!(xml, "          <softwareName>videodocparser</softwareName>")?;
+    writeln!(xml, "          <softwareVersion>{}</softwareVersion>", env!("CARGO_PKG_VERSION"))?;
+    writeln!(xml, "        </processingSoftware>")?;
+    writeln!(xml, "      </ocrProcessingStep>")?;
+    writeln!(xml, "    </OCRProcessing>")?;
+    writeln!(xml, "  </Description>")?;
+    writeln!(xml, "  <Layout>")?;
+    writeln!(
+        xml,
+        "    <Page ID=\"PAGE_{n}\" PHYSICAL_IMG_NR=\"{n}\" WIDTH=\"{w}\" HEIGHT=\"{h}\">",
+        n = index + 1,
+        w = width,
+        h = height
+    )?;
+    writeln!(xml, "      <PrintSpace HPOS=\"0\" VPOS=\"0\" WIDTH=\"{}\" HEIGHT=\"{}\">", width, height)?;
+
+    let lines = page.ocr.map(layout::group_line_words).unwrap_or_default();
+    if !lines.is_empty() {
+        let block = bounding_box(lines.iter().flatten().copied());
+        writeln!(xml, "        <TextBlock ID=\"BLOCK_1\" {}>", position(block))?;
+        for (l, words) in lines.iter().enumerate() {
+            let line_box = bounding_box(words.iter().copied());
+            writeln!(xml, "          <TextLine ID=\"LINE_{}\" {}>", l + 1, position(line_box))?;
+            for (w, word) in words.iter().enumerate() {
+                if w > 0 {
+                    writeln!(xml, "            <SP/>")?;
+                }
+                writeln!(
+                    xml,
+                    "            <String ID=\"WORD_{}_{}\" CONTENT=\"{}\" {} WC=\"{:.2}\"/>",
+                    l + 1,
+                    w + 1,
+                    escape(&word.text),
+                    position(word.bbox),
+                    (word.confidence / 100.0).clamp(0.0, 1.0)
+                )?;
+            }
+            writeln!(xml, "          </TextLine>")?;
+        }
+        writeln!(xml, "        </TextBlock>")?;
+    }
+
+    writeln!(xml, "      </PrintSpace>")?;
+    writeln!(xml, "    </Page>")?;
+    writeln!(xml, "  </Layout>")?;
+    writeln!(xml, "</alto>")?;
+    Ok(xml)
+}
+
+/// The box covering all the given words: (x1, y1, x2, y2).
+fn bounding_box<'a>(words: impl Iterator<Item = &'a OcrWord>) -> (i32, i32, i32, i32) {
+    words.fold((i32::MAX, i32::MAX, i32::MIN, i32::MIN), |acc, word| {
+        (
+            acc.0.min(word.bbox.0),
+            acc.1.min(word.bbox.1),
+            acc.2.max(word.bbox.2),
+            acc.3.max(word.bbox.3),
+        )
+    })
+}
+
+/// Formats a box as ALTO position attributes.
+fn position((x1, y1, x2, y2): (i32, i32, i32, i32)) -> String {
+    format!(
+        "HPOS=\"{}\" VPOS=\"{}\" WIDTH=\"{}\" HEIGHT=\"{}\"",
+        x1,
+        y1,
+        (x2 - x1).max(0),
+        (y2 - y1).max(0)
+    )
+}
@@ -0,0 +1,122 @@
+//! Audio Hints Module
+//!
+//! Handles `--audio-hints`: the audio track is scanned for long silences and
+//! applause, the moments speakers usually move to the next slide, and frame
+//! analysis then cuts more readily near them and less readily in the middle of
+//! speech, so camera shake or lighting changes do not split a slide.
+
+/// Sample rate the audio is decoded at; plenty for energy and zero crossings.
+pub const SAMPLE_RATE: u32 = 8_000;
+/// Length of the analysis windows, in seconds.
+const WINDOW_SECONDS: f64 = 0.05;
+/// Shortest silence, in seconds, that counts as a pause.
+pub const MIN_SILENCE_SECONDS: f64 = 0.4;
+/// Shortest applause, in seconds, that counts as a pause.
+pub const MIN_APPLAUSE_SECONDS: f64 = 1.0;
+/// Seconds around a pause in which frames still count as near it.
+pub const PAUSE_MARGIN_SECONDS: f64 = 0.5;
+/// Factor applied to the change threshold near a pause.
+pub const PAUSE_SCALE: f64 = 0.6;
+/// Factor applied to the change threshold during speech.
+pub const SPEECH_SCALE: f64 = 1.25;
+
+/// RMS level, relative to the median of the audible windows, below which a
+/// window is silent.
+const SILENCE_RATIO: f32 = 0.2;
+/// RMS level below which a window is silent whatever the recording level.
+const SILENCE_FLOOR: f32 = 0.001;
+/// Fraction of sign changes above which a loud window sounds like noise
+/// (applause) rather than voiced speech.
+const APPLAUSE_ZERO_CROSSINGS: f32 = 0.3;
+
+/// A stretch of silence or applause, in seconds from the start of the video.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pause {
+    pub start: f64,
+    pub end: f64,
+}
+
+/// Finds the silences of at least [`MIN_SILENCE_SECONDS`] and the applause of
+/// at least [`MIN_APPLAUSE_SECONDS`] in mono `samples`, ordered by start.
+pub fn find_pauses(samples: &[f32], sample_rate: u32) -> Vec<Pause> {
+    let window = ((WINDOW_SECONDS * sample_rate as f64) as usize).max(1);
+    let windows: Vec<(f32, f32)> = samples
+        .chunks_exact(window)
+        .map(|chunk| {
+            let rms = (chunk.iter().map(|s| s * s).sum::<f32>() / chunk.len() as f32).sqrt();
+            let crossings = chunk.windows(2).filter(|pair| (pair[0] >= 0.0) != (pair[1] >= 0.0)).count();
+            (rms, crossings as f32 / chunk.len() as f32)
+        })
+        .collect();
+
+    let mut audible: Vec<f32> = windows.iter().map(|&(rms, _)| rms).filter(|&rms| rms >= SILENCE_FLOOR).collect();
+    if audible.is_empty() {
+        return Vec::new();
+    }
+    audible.sort_by(f32::total_cmp);
+    let median = audible[audible.len() / 2];
+    let silence_level = (median * SILENCE_RATIO).max(SILENCE_FLOOR);
+
+    let silent: Vec<bool> = windows.iter().map(|&(rms, _)| rms < silence_level).collect();
+    let applause: Vec<bool> =
+        windows.iter().map(|&(rms, crossings)| rms >= median && crossings > APPLAUSE_ZERO_CROSSINGS).collect();
+
+    let seconds = |windows: usize| windows as f64 * window as f64 / sample_rate as f64;
+    let mut pauses: Vec<Pause> = runs(&silent, MIN_SILENCE_SECONDS / seconds(1))
+        .into_iter()
+        .chain(runs(&applause, MIN_APPLAUSE_SECONDS / seconds(1)))
+        .map(|(start, end)| Pause { start: seconds(start), end: seconds(end) })
+        .collect();
+    pauses.sort_by(|a, b| a.start.total_cmp(&b.start));
+    pauses
+}
+
+/// Start and end (exclusive) of every run of `true` at least `min_len` long.
+fn runs(flags: &[bool], min_len: f64) -> Vec<(usize, usize)> {
+    let mut runs = Vec::new();
+    let mut start = None;
+    for (i, &flag) in flags.iter().chain([&false]).enumerate() {
+        match (flag, start) {
+            (true, None) => start = Some(i),
+            (false, Some(first)) => {
+                if (i - first) as f64 >= min_len {
+                    runs.push((first, i));
+                }
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    runs
+}
+
+/// The pauses of a video, looked up by frame index during frame analysis.
+#[derive(Debug, Clone)]
+pub struct AudioHints {
+    pauses: Vec<Pause>,
+    frame_rate: f64,
+}
+
+impl AudioHints {
+    pub fn new(pauses: Vec<Pause>, frame_rate: f64) -> Self {
+        Self { pauses, frame_rate }
+    }
+
+    pub fn pauses(&self) -> &[Pause] {
+        &self.pauses
+    }
+
+    /// Factor applied to the change threshold at frame `index`: lower near a
+    /// pause, higher during speech, and neutral when no pause was found.
+    pub fn threshold_scale(&self, index: usize) -> f64 {
+        if self.pauses.is_empty() {
+            return 1.0;
+        }
+        let time = index as f64 / self.frame_rate;
+        let near_pause = self
+            .pauses
+            .iter()
+            .any(|pause| time >= pause.start - PAUSE_MARGIN_SECONDS && time <= pause.end + PAUSE_MARGIN_SECONDS);
+        if near_pause { PAUSE_SCALE } else { SPEECH_SCALE }
+    }
+}
@@ -0,0 +1,84 @@
+//! BK-tree Module
+//!
+//! Handles finding the frame hashes closest to a new one among many, for
+//! recognizing a slide shown again long after it was first kept. A BK-tree
+//! files every hash under its parent by their Hamming distance, so by the
+//! triangle inequality a search only descends into the children whose
+//! distance to their parent is within the search radius of the query's.
+
+use crate::frame_analyzer::FrameHash;
+use anyhow::Result;
+
+struct Node<T> {
+    hash: FrameHash,
+    value: T,
+    /// Indices of the child nodes, with their distance to this node.
+    children: Vec<(u32, usize)>,
+}
+
+/// Frame hashes with a value each, searchable by Hamming distance.
+pub struct BkTree<T> {
+    nodes: Vec<Node<T>>,
+}
+
+impl<T> Default for BkTree<T> {
+    fn default() -> Self {
+        Self { nodes: Vec::new() }
+    }
+}
+
+impl<T> BkTree<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Adds a hash with its value. Fails if the hash is not as long as the others.
+    pub fn insert(&mut self, hash: FrameHash, value: T) -> Result<()> {
+        let index = self.nodes.len();
+        if index > 0 {
+            let mut parent = 0;
+            loop {
+                let distance = self.nodes[parent].hash.distance(&hash)?;
+                match self.nodes[parent].children.iter().find(|(d, _)| *d == distance) {
+                    Some(&(_, child)) => parent = child,
+                    None => {
+                        self.nodes[parent].children.push((distance, index));
+                        break;
+                    }
+                }
+            }
+        }
+        self.nodes.push(Node { hash, value, children: Vec::new() });
+        Ok(())
+    }
+
+    /// The value of the hash closest to `hash` at most `radius` bits from it,
+    /// with its distance; the first inserted among equally close ones.
+    pub fn nearest(&self, hash: &FrameHash, radius: u32) -> Result<Option<(&T, u32)>> {
+        let mut best: Option<(usize, u32)> = None;
+        let mut pending = if self.nodes.is_empty() { Vec::new() } else { vec![0] };
+        while let Some(index) = pending.pop() {
+            let node = &self.nodes[index];
+            let distance = node.hash.distance(hash)?;
+            if distance <= radius && best.is_none_or(|(b, d)| (distance, index) < (d, b)) {
+                best = Some((index, distance));
+            }
+            let radius = best.map_or(radius, |(_, d)| d.min(radius));
+            pending.extend(
+                node.children
+                    .iter()
+                    .filter(|(d, _)| d.abs_diff(distance) <= radius)
+                    .map(|&(_, child)| child),
+            );
+        }
+        Ok(best.map(|(index, distance)| (&self.nodes[index].value, distance)))
+    }
+}
@@ -0,0 +1,70 @@
+//! Builds Module
+//!
+//! Handles slides that are built up step by step, such as bullet lists that
+//! appear one point at a time: finding the text each page adds to the page
+//! before it, and the pages that are only an intermediate state of the next.
+
+use crate::document_builder::Page;
+use crate::layout;
+use crate::ocr::OcrFrameResult;
+
+/// The lines of a page's OCR text, in reading order.
+fn page_lines(page: &Page) -> Vec<String> {
+    page.ocr
+        .map(|ocr| layout::group_lines(ocr).into_iter().map(|line| line.text).collect())
+        .unwrap_or_default()
+}
+
+/// For each page, the lines of its OCR text that were not on the page before
+/// it, in reading order.
+pub fn new_lines(pages: &[Page]) -> Vec<Vec<String>> {
+    let mut previous: Vec<String> = Vec::new();
+    pages
+        .iter()
+        .map(|page| {
+            let lines = page_lines(page);
+            let fresh = lines.iter().filter(|line| !previous.contains(line)).cloned().collect();
+            previous = lines;
+            fresh
+        })
+        .collect()
+}
+
+/// Whether each page is the final state of its slide.
+///
+/// A page is an intermediate build when the page after it still shows every
+/// one of its lines and adds at least one; pages without text are always
+/// final.
+pub fn final_builds(pages: &[Page]) -> Vec<bool> {
+    let lines: Vec<Vec<String>> = pages.iter().map(page_lines).collect();
+    (0..lines.len())
+        .map(|i| {
+            let Some(next) = lines.get(i + 1) else {
+                return true;
+            };
+            lines[i].is_empty() || next.len() <= lines[i].len() || !lines[i].iter().all(|line| next.contains(line))
+        })
+        .collect()
+}
+
+/// Drops the OCR results of the pages whose entry in `keep` is false, and
+/// renumbers the rest to the pages' places once the others are gone.
+pub fn keep_results(results: Vec<OcrFrameResult>, keep: &[bool]) -> Vec<OcrFrameResult> {
+    // The page number of every kept page once the others are gone.
+    let positions: Vec<usize> = keep
+        .iter()
+        .scan(0, |next, &kept| {
+            let position = *next;
+            *next += kept as usize;
+            Some(position)
+        })
+        .collect();
+    results
+        .into_iter()
+        .filter(|result| keep.get(result.frame_index) != Some(&false))
+        .map(|mut result| {
+            result.frame_index = positions.get(result.frame_index).copied().unwrap_or(result.frame_index);
+            result
+        })
+        .collect()
+}
@@ -0,0 +1,79 @@
+//! Bundle Module
+//!
+//! Handles packaging the results of a run (generated documents, frames, OCR
+//! report, analysis log, and index) into a single ZIP archive with a manifest,
+//! so they can be handed over from CI or batch jobs as one file.
+
+use crate::paths::collect_files;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use zip::CompressionMethod;
+use zip::write::SimpleFileOptions;
+
+/// Name of the manifest at the root of the archive.
+pub const MANIFEST_NAME: &str = "manifest.json";
+// Formats that are already compressed gain nothing from deflate.
+const STORED_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "pdf", "mp4", "webm", "zip"];
+
+/// Describes the run and every file in the archive.
+#[derive(Debug, Serialize)]
+pub struct Manifest {
+    /// File name of the input video.
+    pub source: String,
+    /// Output format of the run.
+    pub format: String,
+    pub generator: String,
+    pub files: Vec<ManifestEntry>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ManifestEntry {
+    /// Path inside the archive, using forward slashes.
+    pub path: String,
+    pub size: u64,
+}
+
+/// Writes `zip_path` with every file under the given paths (files or directories,
+/// relative to `base_dir`) plus a [`MANIFEST_NAME`] listing them. Missing paths
+/// are skipped. `manifest.files` is filled in here.
+pub fn write_zip(base_dir: &Path, paths: &[&str], mut manifest: Manifest, zip_path: &Path) -> Result<()> {
+    let mut files = Vec::new();
+    for path in paths {
+        let full = base_dir.join(path);
+        if full.exists() {
+            collect_files(&full, &mut files)
+                .with_context(|| format!("Failed to list files in {:?}", full))?;
+        }
+    }
+    files.sort();
+
+    let archive = File::create(zip_path)
+        .with_context(|| format!("Failed to create bundle {:?}", zip_path))?;
+    let mut zip = zip::ZipWriter::new(BufWriter::new(archive));
+    for file in &files {
+        let name = file
+            .strip_prefix(base_dir)
+            .unwrap_or(file)
+            .components()
+            .map(|part| part.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        let stored = file
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| STORED_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()));
+        let method = if stored { CompressionMethod::Stored } else { CompressionMethod::Deflated };
+        zip.start_file(name.as_str(), SimpleFileOptions::default().compression_method(method))?;
+        let size = io::copy(&mut File::open(file)?, &mut zip)
+            .with_context(|| format!("Failed to add {:?} to the bundle", file))?;
+        manifest.files.push(ManifestEntry { path: name, size });
+    }
+
+    zip.start_file(MANIFEST_NAME, SimpleFileOptions::default())?;
+    zip.write_all(serde_json::to_string_pretty(&manifest)?.as_bytes())?;
+    zip.finish()?.flush()?;
+    Ok(())
+}
@@ -0,0 +1,168 @@
+//! Cache Module
+//!
+//! Defines the on-disk format shared by every checkpoint and cache file the
+//! pipeline writes. Each file is a JSON envelope: a header describing what the
+//! payload is and what it was computed from, followed by the payload itself.
+//!
+//! Invalidation policy: a cache entry is only reused when its magic, kind,
+//! format version, input fingerprint, and settings all match the current run.
+//! Entries written by any other format version, older or newer, are discarded
+//! and recomputed: they are cheap to rebuild compared with keeping conversions
+//! between every pair of layouts.
+
+use anyhow::{Context, Result};
+use log::{debug, info, warn};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+/// Current version of the envelope and payload layouts. Bump it whenever a payload
+/// changes shape, so entries in the old layout are recomputed.
+pub const FORMAT_VERSION: u32 = 1;
+
+const MAGIC: &str = "videodocparser-cache";
+
+/// Identifies the input video a cache entry was computed from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InputFingerprint {
+    pub file_name: String,
+    pub size: u64,
+    pub modified_secs: u64,
+}
+
+impl InputFingerprint {
+    /// Builds a cheap fingerprint from the file's name, size, and modification time.
+    pub fn of(path: &Path) -> Result<Self> {
+        let metadata = fs::metadata(path)
+            .with_context(|| format!("Failed to read metadata of {:?}", path))?;
+        let modified_secs = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map(|duration| duration.as_secs())
+            .unwrap_or(0);
+        Ok(Self {
+            file_name: path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            size: metadata.len(),
+            modified_secs,
+        })
+    }
+}
+
+/// Header stored at the top of every cache file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheHeader {
+    pub magic: String,
+    pub format_version: u32,
+    pub tool_version: String,
+    pub kind: String,
+    pub input: InputFingerprint,
+    /// The parameters that influenced the payload (hash size, OCR language, ...).
+    pub settings: Value,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Envelope<T> {
+    header: CacheHeader,
+    payload: T,
+}
+
+/// Writes a payload to `path` inside a versioned envelope.
+///
+/// The file is written to a temporary sibling first and then renamed, so an
+/// interrupted run never leaves a truncated cache behind.
+pub fn write<T: Serialize>(
+    path: &Path,
+    kind: &str,
+    input: &InputFingerprint,
+    settings: Value,
+    payload: &T,
+) -> Result<()> {
+    let envelope = Envelope {
+        header: CacheHeader {
+            magic: MAGIC.to_string(),
+            format_version: FORMAT_VERSION,
+            tool_version: env!("CARGO_PKG_VERSION").to_string(),
+            kind: kind.to_string(),
+            input: input.clone(),
+            settings,
+        },
+        payload,
+    };
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).context("Failed to create cache directory")?;
+    }
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, serde_json::to_vec(&envelope)?)
+        .with_context(|| format!("Failed to write cache file {:?}", tmp_path))?;
+    fs::rename(&tmp_path, path)
+        .with_context(|| format!("Failed to move cache file into place at {:?}", path))?;
+    Ok(())
+}
+
+/// Reads a payload from `path` if it is still valid for this input and these settings.
+///
+/// Returns `Ok(None)` when the file is missing or has been invalidated; only
+/// I/O errors other than "not found" are reported as errors.
+pub fn read<T: DeserializeOwned>(
+    path: &Path,
+    kind: &str,
+    input: &InputFingerprint,
+    settings: &Value,
+) -> Result<Option<T>> {
+    let bytes = match fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read cache file {:?}", path)),
+    };
+
+    let envelope: Envelope<Value> = match serde_json::from_slice(&bytes) {
+        Ok(envelope) => envelope,
+        Err(e) => {
+            warn!("Ignoring unreadable cache file {:?}: {}", path, e);
+            return Ok(None);
+        }
+    };
+    let header = envelope.header;
+
+    if header.magic != MAGIC || header.kind != kind {
+        warn!("Ignoring {:?}: it is not a '{}' cache file.", path, kind);
+        return Ok(None);
+    }
+    if header.format_version != FORMAT_VERSION {
+        info!(
+            "Cache file {:?} uses format version {}, not {}; recomputing.",
+            path, header.format_version, FORMAT_VERSION
+        );
+        return Ok(None);
+    }
+    if header.input != *input {
+        info!("Input video changed since {:?} was written; recomputing.", path);
+        return Ok(None);
+    }
+    if header.settings != *settings {
+        info!("Settings changed since {:?} was written; recomputing.", path);
+        return Ok(None);
+    }
+    if header.tool_version != env!("CARGO_PKG_VERSION") {
+        debug!(
+            "Reusing {:?} written by version {} of the tool.",
+            path, header.tool_version
+        );
+    }
+
+    match serde_json::from_value(envelope.payload) {
+        Ok(payload) => Ok(Some(payload)),
+        Err(e) => {
+            warn!("Ignoring cache file {:?} with an unexpected payload: {}", path, e);
+            Ok(None)
+        }
+    }
+}
@@ -0,0 +1,73 @@
+//! Calibration Module
+//!
+//! Handles `--sensitivity auto`: picking the sensitivity for a video from the
+//! Hamming distances between the hashes of its consecutive frames, since one
+//! fixed value never suits screen recordings, camera lectures, and videos of
+//! scanned pages alike. Most consecutive frames differ only by noise and a few
+//! by a change of slide; Otsu's method finds the distance that best separates
+//! the two. When the two classes it finds are not far apart, the distances are
+//! all noise, as in a video of a single slide, and none of them is a change.
+
+use crate::frame_analyzer::FrameHash;
+use anyhow::Result;
+
+/// Least and greatest share of the hash's bits the calibrated threshold may
+/// take, i.e. sensitivities from 0.98 down to 0.5.
+const MIN_THRESHOLD: f64 = 0.02;
+const MAX_THRESHOLD: f64 = 0.5;
+/// How many times the mean noise distance, counting at least one bit, the
+/// mean distance of the changes must be for them to be told apart.
+const MIN_SEPARATION: f64 = 2.0;
+
+/// The distances, in bits, between the hashes of consecutive frames.
+pub fn consecutive_distances(hash_series: &[FrameHash]) -> Result<Vec<u32>> {
+    hash_series.windows(2).map(|pair| pair[0].distance(&pair[1])).collect()
+}
+
+/// The distance Otsu's method splits `distances` at: the largest of the
+/// class of small ones, chosen to maximize the variance between the classes.
+/// `None` when all distances are equal.
+pub fn otsu_split(distances: &[u32]) -> Option<u32> {
+    let max = *distances.iter().max()?;
+    let mut histogram = vec![0usize; max as usize + 1];
+    for &distance in distances {
+        histogram[distance as usize] += 1;
+    }
+    let total = distances.len() as f64;
+    let sum: f64 = distances.iter().map(|&distance| distance as f64).sum();
+
+    let (mut below, mut below_sum) = (0.0, 0.0);
+    let mut best: Option<(u32, f64)> = None;
+    for (distance, &count) in histogram.iter().enumerate().take(max as usize) {
+        below += count as f64;
+        below_sum += distance as f64 * count as f64;
+        if below == 0.0 {
+            continue;
+        }
+        let above = total - below;
+        let spread = below / total * above / total * (below_sum / below - (sum - below_sum) / above).powi(2);
+        if best.is_none_or(|(_, best)| spread > best) {
+            best = Some((distance as u32, spread));
+        }
+    }
+    best.map(|(distance, _)| distance)
+}
+
+/// Picks the sensitivity for a hash series of `bits`-bit hashes, or `None`
+/// when the series is too short, or its frames all equally far apart.
+pub fn calibrate(hash_series: &[FrameHash], bits: u32) -> Result<Option<f64>> {
+    let distances = consecutive_distances(hash_series)?;
+    let Some(mut split) = otsu_split(&distances) else {
+        return Ok(None);
+    };
+    let mean = |class: Vec<u32>| class.iter().map(|&d| d as f64).sum::<f64>() / class.len() as f64;
+    let (noise, changes): (Vec<u32>, Vec<u32>) = distances.iter().partition(|&&distance| distance <= split);
+    if mean(changes) < MIN_SEPARATION * mean(noise).max(1.0) {
+        split = distances.iter().copied().max().unwrap_or(split);
+    }
+    // Frames at most `split` bits apart are unchanged; the threshold lies
+    // halfway to the next distance, as the analyzer keeps a frame whose share
+    // of differing bits reaches it.
+    let threshold = (split as f64 + 0.5) / bits as f64;
+    Ok(Some(1.0 - threshold.clamp(MIN_THRESHOLD, MAX_THRESHOLD)))
+}
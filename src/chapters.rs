@@ -0,0 +1,145 @@
+//! Chapters Module
+//!
+//! Handles `--chapters`: chapter lists built from the slide boundaries, as a
+//! YouTube description (`00:00 Intro`) and as an FFmpeg metadata file that can
+//! be muxed back into the video to make it navigable by chapter.
+
+use crate::document_builder::Page;
+use crate::video_processor::Chapter;
+use anyhow::{Context, Result};
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+
+/// File name of the YouTube-style chapter list in the result directory.
+pub const DESCRIPTION_NAME: &str = "chapters.txt";
+/// File name of the FFmpeg metadata file in the result directory.
+pub const FFMETADATA_NAME: &str = "chapters.ffmetadata";
+/// Shortest chapter YouTube accepts, in seconds; shorter ones are merged into
+/// the chapter before them.
+pub const MIN_CHAPTER_SECONDS: f64 = 10.0;
+
+/// One chapter of the video.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChapterMark {
+    /// Start and end in seconds from the beginning of the video.
+    pub start: f64,
+    pub end: f64,
+    pub title: String,
+}
+
+/// Builds the chapters from the segments of the pages, given the length of the
+/// video; pages without a timestamp are left out. The first chapter starts at
+/// 0 and each one ends where the next starts, the last where its last page left
+/// the screen, or with the video when that is unknown. Consecutive pages with
+/// the same title, such as the builds of one slide, form one chapter, and
+/// chapters shorter than [`MIN_CHAPTER_SECONDS`] are merged into the previous one.
+pub fn from_pages(pages: &[Page], duration: f64) -> Vec<ChapterMark> {
+    let mut chapters: Vec<ChapterMark> = Vec::new();
+    let mut last_end = duration;
+    for (i, page) in pages.iter().enumerate() {
+        let Some(start) = page.timestamp else { continue };
+        last_end = page.end.unwrap_or(duration);
+        let title = page.display_title(i);
+        if chapters.last().is_some_and(|last| last.title == title) {
+            continue;
+        }
+        let start = if chapters.is_empty() { 0.0 } else { start };
+        chapters.push(ChapterMark { start, end: start, title });
+    }
+    let ends: Vec<f64> = chapters.iter().skip(1).map(|chapter| chapter.start).chain([last_end]).collect();
+    for (chapter, end) in chapters.iter_mut().zip(ends) {
+        chapter.end = end.max(chapter.start);
+    }
+
+    let mut merged: Vec<ChapterMark> = Vec::with_capacity(chapters.len());
+    for chapter in chapters {
+        match merged.last_mut() {
+            // A short first chapter takes in the ones after it until it is long enough.
+            Some(last)
+                if chapter.end - chapter.start < MIN_CHAPTER_SECONDS
+                    || last.end - last.start < MIN_CHAPTER_SECONDS =>
+            {
+                last.end = chapter.end
+            }
+            _ => merged.push(chapter),
+        }
+    }
+    merged
+}
+
+/// Formats the chapters as YouTube expects them in a video description: one
+/// `MM:SS Title` line per chapter, or `H:MM:SS Title` for videos of an hour or more.
+pub fn youtube_description(chapters: &[ChapterMark]) -> String {
+    let hours = chapters.last().is_some_and(|last| last.start >= 3600.0);
+    let mut description = String::new();
+    for chapter in chapters {
+        let total = chapter.start.max(0.0) as u64;
+        let time = if hours {
+            format!("{}:{:02}:{:02}", total / 3600, total / 60 % 60, total % 60)
+        } else {
+            format!("{:02}:{:02}", total / 60, total % 60)
+        };
+        description.push_str(&format!("{} {}\n", time, chapter.title));
+    }
+    description
+}
+
+/// Formats the chapters as an FFmpeg metadata file, with times in milliseconds.
+pub fn ffmetadata(chapters: &[ChapterMark]) -> String {
+    let mut metadata = String::from(";FFMETADATA1\n");
+    for chapter in chapters {
+        metadata.push_str(&format!(
+            "\n[CHAPTER]\nTIMEBASE=1/1000\nSTART={}\nEND={}\ntitle={}\n",
+            (chapter.start * 1000.0).round() as u64,
+            (chapter.end * 1000.0).round() as u64,
+            escape_ffmetadata(&chapter.title)
+        ));
+    }
+    metadata
+}
+
+/// Escapes the characters FFmpeg metadata files treat specially.
+fn escape_ffmetadata(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '=' | ';' | '#' | '\\' | '\n') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Groups the pages by the chapter markers of the video, for `--split-by chapter`:
+/// each run of consecutive pages in the same chapter, with the index of that
+/// chapter in `chapters`. A page belongs to the last chapter that started by the
+/// time it appeared, so pages after the end of the last chapter stay in it, and
+/// pages before the first chapter go to the first one. Chapters without pages
+/// have no run. `None` when a page has no timestamp.
+pub fn split_pages(pages: &[Page], chapters: &[Chapter]) -> Option<Vec<(usize, Range<usize>)>> {
+    let timestamps = pages.iter().map(|page| page.timestamp).collect::<Option<Vec<f64>>>()?;
+    if chapters.is_empty() {
+        return Some(Vec::new());
+    }
+    let chapter_of = |timestamp: f64| chapters.iter().rposition(|c| c.start <= timestamp).unwrap_or(0);
+    let mut runs: Vec<(usize, Range<usize>)> = Vec::new();
+    for (i, &timestamp) in timestamps.iter().enumerate() {
+        let chapter = chapter_of(timestamp);
+        match runs.last_mut() {
+            Some((last, pages)) if *last == chapter => pages.end = i + 1,
+            _ => runs.push((chapter, i..i + 1)),
+        }
+    }
+    Some(runs)
+}
+
+/// Writes [`DESCRIPTION_NAME`] and [`FFMETADATA_NAME`] to `dir`.
+pub fn write_chapters(chapters: &[ChapterMark], dir: &Path) -> Result<()> {
+    let description_path = dir.join(DESCRIPTION_NAME);
+    fs::write(&description_path, youtube_description(chapters))
+        .with_context(|| format!("Failed to write chapter list to {:?}", description_path))?;
+    let metadata_path = dir.join(FFMETADATA_NAME);
+    fs::write(&metadata_path, ffmetadata(chapters))
+        .with_context(|| format!("Failed to write chapter metadata to {:?}", metadata_path))
+}
@@ -0,0 +1,492 @@
+//! Cloud OCR Module
+//!
+//! Handles the HTTP [`OcrEngine`] backends for Google Cloud Vision, Azure AI
+//! Vision, and AWS Textract, for handwriting and material that needs the best
+//! accuracy available, at the price of sending every frame to the provider.
+//! Failed requests are retried with exponential backoff, and the number of
+//! images sent can be capped to bound the bill.
+
+use crate::ocr::{OcrEngine, OcrWord, Recognition};
+use anyhow::{Context, Result, anyhow, bail};
+use base64::Engine as _;
+use hmac::{Hmac, Mac};
+use image::{DynamicImage, ImageOutputFormat};
+use log::{info, warn};
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+use std::io::Cursor;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+/// Environment variable holding the Google Cloud Vision API key.
+pub const GOOGLE_KEY_VAR: &str = "GOOGLE_VISION_API_KEY";
+/// Environment variable holding the Azure AI Vision resource key.
+pub const AZURE_KEY_VAR: &str = "AZURE_VISION_KEY";
+/// Environment variables holding the AWS credentials and region.
+pub const AWS_ACCESS_KEY_VAR: &str = "AWS_ACCESS_KEY_ID";
+pub const AWS_SECRET_KEY_VAR: &str = "AWS_SECRET_ACCESS_KEY";
+pub const AWS_SESSION_TOKEN_VAR: &str = "AWS_SESSION_TOKEN";
+pub const AWS_REGION_VARS: [&str; 2] = ["AWS_REGION", "AWS_DEFAULT_REGION"];
+/// Images sent in one request when `--cloud-batch-size` is not given.
+pub const DEFAULT_BATCH_SIZE: u32 = 8;
+/// Most images Google Cloud Vision accepts in one request.
+pub const GOOGLE_MAX_BATCH: usize = 16;
+/// Longest a single request may take.
+const TIMEOUT: Duration = Duration::from_secs(120);
+/// Wait before the first retry; it doubles with every further attempt.
+const FIRST_BACKOFF: Duration = Duration::from_secs(1);
+/// Longest wait between two attempts, whatever the provider asks for.
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+/// Textract's JSON protocol operation for reading printed and handwritten text.
+const TEXTRACT_TARGET: &str = "Textract.DetectDocumentText";
+
+/// A cloud OCR provider.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Provider {
+    Google,
+    Azure,
+    Textract,
+}
+
+impl Provider {
+    /// The provider of an `--ocr-engine` name, if it is a cloud one.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "google" => Some(Self::Google),
+            "azure" => Some(Self::Azure),
+            "textract" => Some(Self::Textract),
+            _ => None,
+        }
+    }
+}
+
+/// AWS credentials for signing Textract requests.
+struct AwsCredentials {
+    access_key: String,
+    secret_key: String,
+    session_token: Option<String>,
+    region: String,
+}
+
+/// An OCR engine backed by a cloud provider's HTTP API.
+pub struct CloudEngine {
+    provider: Provider,
+    agent: ureq::Agent,
+    endpoint: String,
+    key: Option<String>,
+    aws: Option<AwsCredentials>,
+    batch_size: usize,
+    retries: u32,
+    max_images: Option<usize>,
+    sent: AtomicUsize,
+}
+
+impl CloudEngine {
+    /// Creates a client for `provider`, reading its credentials from the
+    /// environment. `endpoint` replaces the provider's default one, and is
+    /// required for Azure, whose endpoint is the resource's own.
+    pub fn new(
+        provider: Provider,
+        endpoint: Option<&str>,
+        batch_size: usize,
+        retries: u32,
+        max_images: Option<usize>,
+    ) -> Result<Self> {
+        let env = |name: &str| std::env::var(name).ok().filter(|value| !value.is_empty());
+        let (key, aws) = match provider {
+            Provider::Google => (Some(env(GOOGLE_KEY_VAR).with_context(|| format!("Set {}", GOOGLE_KEY_VAR))?), None),
+            Provider::Azure => (Some(env(AZURE_KEY_VAR).with_context(|| format!("Set {}", AZURE_KEY_VAR))?), None),
+            Provider::Textract => {
+                let credentials = AwsCredentials {
+                    access_key: env(AWS_ACCESS_KEY_VAR).with_context(|| format!("Set {}", AWS_ACCESS_KEY_VAR))?,
+                    secret_key: env(AWS_SECRET_KEY_VAR).with_context(|| format!("Set {}", AWS_SECRET_KEY_VAR))?,
+                    session_token: env(AWS_SESSION_TOKEN_VAR),
+                    region: AWS_REGION_VARS
+                        .iter()
+                        .find_map(|name| env(name))
+                        .with_context(|| format!("Set {} to the Textract region", AWS_REGION_VARS[0]))?,
+                };
+                (None, Some(credentials))
+            }
+        };
+        let endpoint = match (provider, endpoint) {
+            (_, Some(endpoint)) => endpoint.trim_end_matches('/').to_string(),
+            (Provider::Google, None) => "https://vision.googleapis.com".to_string(),
+            (Provider::Azure, None) => {
+                bail!("The azure engine needs --cloud-endpoint, the Azure AI Vision resource URL")
+            }
+            (Provider::Textract, None) => {
+                format!("https://textract.{}.amazonaws.com", aws.as_ref().map_or("", |aws| aws.region.as_str()))
+            }
+        };
+        let batch_size = match provider {
+            Provider::Google => batch_size.clamp(1, GOOGLE_MAX_BATCH),
+            Provider::Azure | Provider::Textract => 1,
+        };
+        let config = ureq::Agent::config_builder().timeout_global(Some(TIMEOUT)).http_status_as_error(false).build();
+        Ok(Self {
+            provider,
+            agent: config.into(),
+            endpoint,
+            key,
+            aws,
+            batch_size,
+            retries,
+            max_images,
+            sent: AtomicUsize::new(0),
+        })
+    }
+
+    /// Counts `count` more images against `--cloud-max-images`, refusing
+    /// them if that would go over.
+    fn reserve(&self, count: usize) -> Result<()> {
+        let Some(max_images) = self.max_images else {
+            self.sent.fetch_add(count, Ordering::SeqCst);
+            return Ok(());
+        };
+        self.sent
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |sent| {
+                (sent + count <= max_images).then_some(sent + count)
+            })
+            .map(|_| ())
+            .map_err(|_| anyhow!("The --cloud-max-images limit of {} images is reached", max_images))
+    }
+
+    /// Sends a request built by `send` until it succeeds, retrying
+    /// transport errors, throttling, and server errors with backoff, and
+    /// returns the JSON reply.
+    fn call(&self, send: impl Fn() -> Result<ureq::http::Response<ureq::Body>, ureq::Error>) -> Result<Value> {
+        let mut attempt = 0;
+        loop {
+            let (error, retry_after) = match send() {
+                Ok(mut response) if response.status().is_success() => {
+                    return response.body_mut().read_json().context("Failed to parse the OCR response");
+                }
+                Ok(mut response) => {
+                    let status = response.status().as_u16();
+                    let retry_after = response
+                        .headers()
+                        .get("retry-after")
+                        .and_then(|value| value.to_str().ok())
+                        .and_then(|value| value.trim().parse::<u64>().ok());
+                    let body = response.body_mut().read_to_string().unwrap_or_default();
+                    let error = anyhow!("{} answered {}: {}", self.endpoint, status, body.trim());
+                    if !is_retryable(status) {
+                        return Err(error);
+                    }
+                    (error, retry_after)
+                }
+                Err(e) => (anyhow::Error::new(e).context(format!("Request to {} failed", self.endpoint)), None),
+            };
+            if attempt >= self.retries {
+                return Err(error);
+            }
+            let delay = backoff(attempt, retry_after);
+            warn!("{:#}; retrying in {:?}.", error, delay);
+            std::thread::sleep(delay);
+            attempt += 1;
+        }
+    }
+
+    fn recognize_google(&self, images: &[DynamicImage]) -> Result<Vec<Recognition>> {
+        let requests = images
+            .iter()
+            .map(|image| {
+                Ok(json!({
+                    "image": { "content": base64::engine::general_purpose::STANDARD.encode(encode_png(image)?) },
+                    "features": [{ "type": "DOCUMENT_TEXT_DETECTION" }],
+                }))
+            })
+            .collect::<Result<Vec<Value>>>()?;
+        let body = json!({ "requests": requests });
+        let url = format!("{}/v1/images:annotate", self.endpoint);
+        let key = self.key.as_deref().unwrap_or_default();
+        let reply = self.call(|| self.agent.post(&url).query("key", key).send_json(&body))?;
+        parse_google(&reply)
+    }
+
+    fn recognize_azure(&self, image: &DynamicImage) -> Result<Recognition> {
+        let png = encode_png(image)?;
+        let url = format!("{}/computervision/imageanalysis:analyze", self.endpoint);
+        let key = self.key.as_deref().unwrap_or_default();
+        let reply = self.call(|| {
+            self.agent
+                .post(&url)
+                .query("api-version", "2024-02-01")
+                .query("features", "read")
+                .header("Ocp-Apim-Subscription-Key", key)
+                .header("Content-Type", "application/octet-stream")
+                .send(&png[..])
+        })?;
+        parse_azure(&reply)
+    }
+
+    fn recognize_textract(&self, image: &DynamicImage) -> Result<Recognition> {
+        let aws = self.aws.as_ref().context("Missing AWS credentials")?;
+        let bytes = base64::engine::general_purpose::STANDARD.encode(encode_png(image)?);
+        let body = json!({ "Document": { "Bytes": bytes } }).to_string();
+        let host = self.endpoint.split("://").last().unwrap_or_default().split('/').next().unwrap_or_default();
+        let reply = self.call(|| {
+            // Signatures expire, so every attempt is signed anew.
+            let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default();
+            let amz_date = amz_date(now.as_secs());
+            let mut headers = vec![
+                ("content-type", "application/x-amz-json-1.1"),
+                ("host", host),
+                ("x-amz-date", amz_date.as_str()),
+                ("x-amz-target", TEXTRACT_TARGET),
+            ];
+            if let Some(token) = &aws.session_token {
+                headers.insert(3, ("x-amz-security-token", token.as_str()));
+            }
+            let authorization = sigv4_authorization(
+                &aws.access_key,
+                &aws.secret_key,
+                &aws.region,
+                "textract",
+                &headers,
+                body.as_bytes(),
+            );
+            let mut request = self.agent.post(&self.endpoint).header("Authorization", authorization);
+            for (name, value) in headers.iter().filter(|(name, _)| *name != "host") {
+                request = request.header(*name, *value);
+            }
+            request.send(body.as_bytes())
+        })?;
+        parse_textract(&reply, image.width(), image.height())
+    }
+}
+
+impl OcrEngine for CloudEngine {
+    fn name(&self) -> &'static str {
+        match self.provider {
+            Provider::Google => "google",
+            Provider::Azure => "azure",
+            Provider::Textract => "textract",
+        }
+    }
+
+    fn recognize(&self, image: &DynamicImage) -> Result<Recognition> {
+        self.recognize_batch(std::slice::from_ref(image))?.pop().context("The provider returned no result")
+    }
+
+    fn batch_size(&self) -> usize {
+        self.batch_size
+    }
+
+    fn recognize_batch(&self, images: &[DynamicImage]) -> Result<Vec<Recognition>> {
+        self.reserve(images.len())?;
+        match self.provider {
+            Provider::Google => self.recognize_google(images),
+            Provider::Azure => images.iter().map(|image| self.recognize_azure(image)).collect(),
+            Provider::Textract => images.iter().map(|image| self.recognize_textract(image)).collect(),
+        }
+    }
+}
+
+impl Drop for CloudEngine {
+    fn drop(&mut self) {
+        info!("Sent {} images to {}.", self.sent.load(Ordering::SeqCst), self.name());
+    }
+}
+
+/// Encodes an image as PNG for upload.
+fn encode_png(image: &DynamicImage) -> Result<Vec<u8>> {
+    let mut png = Vec::new();
+    image.write_to(&mut Cursor::new(&mut png), ImageOutputFormat::Png).context("Failed to encode the frame")?;
+    Ok(png)
+}
+
+/// Whether a request that got `status` is worth sending again: throttling,
+/// timeouts, and server errors.
+pub fn is_retryable(status: u16) -> bool {
+    matches!(status, 408 | 429) || status >= 500
+}
+
+/// Wait before retry number `attempt` (from 0): what the provider asked for
+/// in `Retry-After`, or one second doubled with every attempt, at most a minute.
+pub fn backoff(attempt: u32, retry_after: Option<u64>) -> Duration {
+    let delay = match retry_after {
+        Some(seconds) => Duration::from_secs(seconds),
+        None => FIRST_BACKOFF.saturating_mul(2u32.saturating_pow(attempt)),
+    };
+    delay.min(MAX_BACKOFF)
+}
+
+/// Bounding box of a polygon given as `[{ "x": .., "y": .. }]`; missing
+/// coordinates are 0, as Google leaves them out.
+fn polygon_bbox(vertices: &Value) -> (i32, i32, i32, i32) {
+    let points: Vec<(i32, i32)> = vertices
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|point| (point["x"].as_f64().unwrap_or(0.0) as i32, point["y"].as_f64().unwrap_or(0.0) as i32))
+        .collect();
+    let xs = points.iter().map(|p| p.0);
+    let ys = points.iter().map(|p| p.1);
+    (
+        xs.clone().min().unwrap_or(0),
+        ys.clone().min().unwrap_or(0),
+        xs.max().unwrap_or(0),
+        ys.max().unwrap_or(0),
+    )
+}
+
+/// Reads a Google Cloud Vision `images:annotate` reply, one recognition per
+/// image. Words and blocks come from the full text annotation, whose
+/// confidences run from 0 to 1. An error on any image fails them all.
+pub fn parse_google(reply: &Value) -> Result<Vec<Recognition>> {
+    let responses = reply["responses"].as_array().context("The Google Vision reply has no responses")?;
+    responses
+        .iter()
+        .map(|response| {
+            if let Some(message) = response["error"]["message"].as_str() {
+                bail!("Google Vision failed on an image: {}", message);
+            }
+            let mut recognition = Recognition::default();
+            let pages = response["fullTextAnnotation"]["pages"].as_array().into_iter().flatten();
+            for block in pages.flat_map(|page| page["blocks"].as_array().into_iter().flatten()) {
+                let mut block_words = Vec::new();
+                let paragraphs = block["paragraphs"].as_array().into_iter().flatten();
+                for word in paragraphs.flat_map(|paragraph| paragraph["words"].as_array().into_iter().flatten()) {
+                    let symbols = word["symbols"].as_array().into_iter().flatten();
+                    let text: String = symbols.filter_map(|symbol| symbol["text"].as_str()).collect();
+                    if text.is_empty() {
+                        continue;
+                    }
+                    let bbox = polygon_bbox(&word["boundingBox"]["vertices"]);
+                    let confidence = word["confidence"].as_f64().unwrap_or(0.0) as f32 * 100.0;
+                    block_words.push(OcrWord { text, bbox, confidence });
+                }
+                if block_words.is_empty() {
+                    continue;
+                }
+                recognition.blocks.push(OcrWord {
+                    text: block_words.iter().map(|word| word.text.as_str()).collect::<Vec<_>>().join(" "),
+                    bbox: polygon_bbox(&block["boundingBox"]["vertices"]),
+                    confidence: block["confidence"].as_f64().unwrap_or(0.0) as f32 * 100.0,
+                });
+                recognition.words.extend(block_words);
+            }
+            Ok(recognition)
+        })
+        .collect()
+}
+
+/// Reads an Azure AI Vision `imageanalysis:analyze` reply with the `read`
+/// feature: its lines and words, whose confidences run from 0 to 1.
+pub fn parse_azure(reply: &Value) -> Result<Recognition> {
+    let blocks = reply["readResult"]["blocks"].as_array().context("The Azure reply has no read result")?;
+    let mut recognition = Recognition::default();
+    for line in blocks.iter().flat_map(|block| block["lines"].as_array().into_iter().flatten()) {
+        let words: Vec<OcrWord> = line["words"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|word| {
+                Some(OcrWord {
+                    text: word["text"].as_str().filter(|text| !text.is_empty())?.to_string(),
+                    bbox: polygon_bbox(&word["boundingPolygon"]),
+                    confidence: word["confidence"].as_f64().unwrap_or(0.0) as f32 * 100.0,
+                })
+            })
+            .collect();
+        let Some(text) = line["text"].as_str().filter(|text| !text.is_empty()) else {
+            continue;
+        };
+        let confidence = words.iter().map(|word| word.confidence).sum::<f32>() / words.len().max(1) as f32;
+        let bbox = polygon_bbox(&line["boundingPolygon"]);
+        recognition.lines.push(OcrWord { text: text.to_string(), bbox, confidence });
+        recognition.words.extend(words);
+    }
+    Ok(recognition)
+}
+
+/// Reads an AWS Textract `DetectDocumentText` reply for an image of `width`
+/// by `height` pixels: its lines and words, whose boxes are given as shares
+/// of the image and confidences from 0 to 100.
+pub fn parse_textract(reply: &Value, width: u32, height: u32) -> Result<Recognition> {
+    let blocks = reply["Blocks"].as_array().context("The Textract reply has no blocks")?;
+    let (width, height) = (width as f64, height as f64);
+    let mut recognition = Recognition::default();
+    for block in blocks {
+        let Some(text) = block["Text"].as_str().filter(|text| !text.is_empty()) else {
+            continue;
+        };
+        let bbox = &block["Geometry"]["BoundingBox"];
+        let (left, top) = (bbox["Left"].as_f64().unwrap_or(0.0), bbox["Top"].as_f64().unwrap_or(0.0));
+        let (box_w, box_h) = (bbox["Width"].as_f64().unwrap_or(0.0), bbox["Height"].as_f64().unwrap_or(0.0));
+        let word = OcrWord {
+            text: text.to_string(),
+            bbox: (
+                (left * width).round() as i32,
+                (top * height).round() as i32,
+                ((left + box_w) * width).round() as i32,
+                ((top + box_h) * height).round() as i32,
+            ),
+            confidence: block["Confidence"].as_f64().unwrap_or(0.0) as f32,
+        };
+        match block["BlockType"].as_str() {
+            Some("WORD") => recognition.words.push(word),
+            Some("LINE") => recognition.lines.push(word),
+            _ => {}
+        }
+    }
+    Ok(recognition)
+}
+
+/// Formats seconds since the Unix epoch as the `YYYYMMDDTHHMMSSZ` timestamp
+/// AWS signatures use.
+pub fn amz_date(unix_seconds: u64) -> String {
+    crate::xmp::DateTime::from_unix_timestamp(unix_seconds)
+        .map(|date| {
+            format!(
+                "{:04}{:02}{:02}T{:02}{:02}{:02}Z",
+                date.year, date.month, date.day, date.hour, date.minute, date.second
+            )
+        })
+        .unwrap_or_default()
+}
+
+/// HMAC-SHA256 of `data` under `key`.
+pub fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// The AWS Signature Version 4 signing key of a day (`YYYYMMDD`), region, and service.
+pub fn signing_key(secret_key: &str, date: &str, region: &str, service: &str) -> Vec<u8> {
+    let key = hmac_sha256(format!("AWS4{}", secret_key).as_bytes(), date.as_bytes());
+    let key = hmac_sha256(&key, region.as_bytes());
+    let key = hmac_sha256(&key, service.as_bytes());
+    hmac_sha256(&key, b"aws4_request")
+}
+
+/// The `Authorization` header of an AWS Signature Version 4 signed `POST /`
+/// with `body`. `headers` are the signed ones, lowercase and sorted by name,
+/// and must include `host` and `x-amz-date`.
+pub fn sigv4_authorization(
+    access_key: &str,
+    secret_key: &str,
+    region: &str,
+    service: &str,
+    headers: &[(&str, &str)],
+    body: &[u8],
+) -> String {
+    let hex = |bytes: &[u8]| bytes.iter().map(|byte| format!("{:02x}", byte)).collect::<String>();
+    let amz_date = headers.iter().find(|(name, _)| *name == "x-amz-date").map_or("", |(_, value)| *value);
+    let date = amz_date.get(..8).unwrap_or_default();
+    let canonical_headers: String = headers.iter().map(|(name, value)| format!("{}:{}\n", name, value.trim())).collect();
+    let signed_headers = headers.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(";");
+    let canonical_request =
+        format!("POST\n/\n\n{}\n{}\n{}", canonical_headers, signed_headers, hex(&Sha256::digest(body)));
+    let scope = format!("{}/{}/{}/aws4_request", date, region, service);
+    let string_to_sign =
+        format!("AWS4-HMAC-SHA256\n{}\n{}\n{}", amz_date, scope, hex(&Sha256::digest(canonical_request.as_bytes())));
+    let signature = hex(&hmac_sha256(&signing_key(secret_key, date, region, service), string_to_sign.as_bytes()));
+    format!(
+        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+        access_key, scope, signed_headers, signature
+    )
+}
@@ -0,0 +1,135 @@
+//! Contact Sheet Module
+//!
+//! Handles the optional contact sheet: one grid image of every kept frame with
+//! its timestamp burned in, and an HTML page showing the same thumbnails with
+//! their page and frame numbers, so the deduplication can be checked at a glance.
+
+use crate::document_builder::{Page, format_timestamp};
+use crate::overlay;
+use crate::xmp::escape;
+use anyhow::{Context, Result};
+use image::imageops::{self, FilterType};
+use image::{ImageBuffer, ImageOutputFormat, Rgb};
+use rayon::prelude::*;
+use std::fmt::Write as _;
+use std::fs;
+use std::io::Cursor;
+use std::path::Path;
+
+/// File name of the grid image, relative to the output directory.
+pub const SHEET_IMAGE: &str = "contact_sheet.png";
+/// File name of the HTML page, relative to the output directory.
+pub const SHEET_HTML: &str = "contact_sheet.html";
+const THUMBNAIL_WIDTH: u32 = 240;
+const COLUMNS: u32 = 6;
+// Space between and around the thumbnails, in pixels.
+const GAP: u32 = 8;
+const BACKGROUND: Rgb<u8> = Rgb([32, 32, 32]);
+
+const HTML_STYLE: &str = "\
+body { font-family: sans-serif; margin: 1rem; background: #202020; color: #eee; }
+.grid { display: grid; grid-template-columns: repeat(auto-fill, minmax(240px, 1fr)); gap: 0.5rem; }
+figure { margin: 0; }
+figure img { display: block; width: 100%; height: auto; }
+figcaption { font-size: 0.8rem; padding: 0.25rem 0; }
+figcaption .meta { color: #aaa; }
+";
+
+type Thumbnail = ImageBuffer<Rgb<u8>, Vec<u8>>;
+
+/// Writes [`SHEET_IMAGE`] and [`SHEET_HTML`] to `base_dir`. `frame_indices` holds
+/// the decoding-order index of each page's frame.
+pub fn build_contact_sheet(
+    pages: &[Page],
+    frame_indices: &[usize],
+    document_title: &str,
+    base_dir: &Path,
+) -> Result<()> {
+    let thumbnails: Vec<Thumbnail> =
+        pages.par_iter().enumerate().map(|(i, page)| thumbnail(i, page)).collect();
+
+    let image_path = base_dir.join(SHEET_IMAGE);
+    grid(&thumbnails)
+        .save(&image_path)
+        .with_context(|| format!("Failed to save contact sheet to {:?}", image_path))?;
+
+    let html_path = base_dir.join(SHEET_HTML);
+    fs::write(&html_path, html(pages, frame_indices, &thumbnails, document_title)?)
+        .with_context(|| format!("Failed to write contact sheet to {:?}", html_path))
+}
+
+/// Scales the page image down to the thumbnail width and labels it with its
+/// timestamp, or its page number when the timestamp is unknown.
+fn thumbnail(index: usize, page: &Page) -> Thumbnail {
+    let (width, height) = page.image.dimensions();
+    let thumb_height = (height as u64 * THUMBNAIL_WIDTH as u64 / width.max(1) as u64).max(1);
+    let mut thumbnail =
+        imageops::resize(page.image, THUMBNAIL_WIDTH, thumb_height as u32, FilterType::Triangle);
+    let label = match page.timestamp {
+        Some(timestamp) => format_timestamp(timestamp),
+        None => (index + 1).to_string(),
+    };
+    overlay::draw_label(&mut thumbnail, &label);
+    thumbnail
+}
+
+/// Lays the thumbnails out left to right, top to bottom, in rows as tall as the
+/// tallest thumbnail.
+fn grid(thumbnails: &[Thumbnail]) -> Thumbnail {
+    let columns = COLUMNS.min(thumbnails.len().max(1) as u32);
+    let rows = (thumbnails.len() as u32).div_ceil(columns).max(1);
+    let cell_height = thumbnails.iter().map(|thumbnail| thumbnail.height()).max().unwrap_or(1);
+    let mut sheet = ImageBuffer::from_pixel(
+        GAP + columns * (THUMBNAIL_WIDTH + GAP),
+        GAP + rows * (cell_height + GAP),
+        BACKGROUND,
+    );
+    for (i, thumbnail) in thumbnails.iter().enumerate() {
+        let (column, row) = (i as u32 % columns, i as u32 / columns);
+        let x = GAP + column * (THUMBNAIL_WIDTH + GAP);
+        let y = GAP + row * (cell_height + GAP);
+        imageops::replace(&mut sheet, thumbnail, x as i64, y as i64);
+    }
+    sheet
+}
+
+/// Builds a self-contained page with the thumbnails embedded as PNG data URIs.
+fn html(
+    pages: &[Page],
+    frame_indices: &[usize],
+    thumbnails: &[Thumbnail],
+    document_title: &str,
+) -> Result<String> {
+    use base64::Engine as _;
+
+    let title = escape(document_title);
+    let mut html = String::new();
+    writeln!(html, "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">")?;
+    writeln!(html, "<meta name=\"generator\" content=\"videodocparser {}\">", env!("CARGO_PKG_VERSION"))?;
+    writeln!(html, "<title>{}</title>\n<style>\n{}</style>\n</head>\n<body>", title, HTML_STYLE)?;
+    writeln!(html, "<h1>{}</h1>\n<p>{} pages</p>\n<div class=\"grid\">", title, pages.len())?;
+    let entries = pages.iter().zip(frame_indices).zip(thumbnails);
+    for (i, ((page, frame_index), thumbnail)) in entries.enumerate() {
+        let mut png = Vec::new();
+        thumbnail.write_to(&mut Cursor::new(&mut png), ImageOutputFormat::Png)?;
+        writeln!(
+            html,
+            "<figure id=\"page-{}\">\n<img src=\"data:image/png;base64,{}\" alt=\"Page {}\">",
+            i + 1,
+            base64::engine::general_purpose::STANDARD.encode(png),
+            i + 1
+        )?;
+        write!(
+            html,
+            "<figcaption>{}<br><span class=\"meta\">Page {}",
+            escape(&page.display_title(i)),
+            i + 1
+        )?;
+        if let Some(timestamp) = page.timestamp {
+            write!(html, " · {}", format_timestamp(timestamp))?;
+        }
+        writeln!(html, " · frame {}</span></figcaption>\n</figure>", frame_index)?;
+    }
+    writeln!(html, "</div>\n</body>\n</html>")?;
+    Ok(html)
+}
@@ -0,0 +1,100 @@
+//! Crop Module
+//!
+//! Handles restricting the analysis and OCR to a region of the video frame, so
+//! a speaker's webcam overlay, player controls, or black bars neither trigger
+//! new pages nor end up in the extracted text. The region is given in pixels,
+//! or found automatically by trimming the black bars around the picture.
+
+use anyhow::{Context, Result, bail, ensure};
+use image::{ImageBuffer, Rgb};
+
+/// Number of frames, spread over the video, examined to find its black bars.
+pub const AUTO_SAMPLES: usize = 10;
+/// Brightest channel value a pixel of a black bar may have.
+const BLACK_LEVEL: u8 = 24;
+
+/// A region of the frame, in pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CropRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl CropRect {
+    /// Parses `x,y,w,h`: the region's top left corner, width, and height.
+    pub fn parse(spec: &str) -> Result<Self> {
+        let values = spec
+            .split(',')
+            .map(|value| value.trim().parse::<u32>())
+            .collect::<Result<Vec<u32>, _>>()
+            .with_context(|| format!("Invalid region '{}': expected x,y,w,h in pixels", spec))?;
+        let [x, y, width, height] = values[..] else {
+            bail!("Invalid region '{}': expected x,y,w,h in pixels", spec);
+        };
+        ensure!(width > 0 && height > 0, "The region '{}' is empty", spec);
+        Ok(Self { x, y, width, height })
+    }
+
+    /// Whether the pixel at (x, y) lies inside the region.
+    pub fn contains(&self, x: u32, y: u32) -> bool {
+        (self.x..self.x + self.width).contains(&x) && (self.y..self.y + self.height).contains(&y)
+    }
+
+    /// Cuts the region out of a frame, which it must fit inside.
+    pub fn apply(&self, frame: &ImageBuffer<Rgb<u8>, Vec<u8>>) -> Result<ImageBuffer<Rgb<u8>, Vec<u8>>> {
+        let (width, height) = frame.dimensions();
+        ensure!(
+            self.x + self.width <= width && self.y + self.height <= height,
+            "The crop region {},{},{},{} does not fit inside the {}x{} frame",
+            self.x,
+            self.y,
+            self.width,
+            self.height,
+            width,
+            height
+        );
+        Ok(image::imageops::crop_imm(frame, self.x, self.y, self.width, self.height).to_image())
+    }
+}
+
+/// Finds the picture inside the black bars of a video from sample frames: the
+/// smallest region holding every pixel that is not black on any of them.
+/// Returns `None` when there are no bars, or the samples are entirely black.
+pub fn detect_borders(frames: &[ImageBuffer<Rgb<u8>, Vec<u8>>]) -> Option<CropRect> {
+    let (width, height) = frames.first()?.dimensions();
+    let (mut left, mut top, mut right, mut bottom) = (width, height, 0, 0);
+    for frame in frames.iter().filter(|frame| frame.dimensions() == (width, height)) {
+        for (x, y, pixel) in frame.enumerate_pixels() {
+            if pixel.0.iter().any(|&channel| channel > BLACK_LEVEL) {
+                (left, top, right, bottom) = (left.min(x), top.min(y), right.max(x + 1), bottom.max(y + 1));
+            }
+        }
+    }
+    if left >= right || top >= bottom || (left, top, right, bottom) == (0, 0, width, height) {
+        return None;
+    }
+    Some(CropRect { x: left, y: top, width: right - left, height: bottom - top })
+}
+
+/// Decoding-order indices of the frames sampled for [`detect_borders`]: up to
+/// [`AUTO_SAMPLES`] spread evenly over `frame_count` frames, skipping the very
+/// start and end, which are often black.
+pub fn sample_indices(frame_count: usize) -> Vec<usize> {
+    let samples = AUTO_SAMPLES.min(frame_count);
+    let mut indices: Vec<usize> = (0..samples).map(|i| (2 * i + 1) * frame_count / (2 * samples)).collect();
+    indices.dedup();
+    indices
+}
+
+/// Cuts `region` out of a frame, or returns the frame as it is without one.
+pub fn crop_frame(
+    frame: ImageBuffer<Rgb<u8>, Vec<u8>>,
+    region: Option<&CropRect>,
+) -> Result<ImageBuffer<Rgb<u8>, Vec<u8>>> {
+    match region {
+        Some(region) => region.apply(&frame),
+        None => Ok(frame),
+    }
+}
@@ -0,0 +1,180 @@
+//! Database Module
+//!
+//! Handles the `sqlite` output format: a single SQLite file holding the page
+//! images, every recognized word with its bounding box and confidence, the
+//! frame analysis metrics, and an FTS5 full-text index over the page text.
+
+use crate::document_builder::{self, Page};
+use crate::frame_analyzer::AnalysisResult;
+use crate::layout;
+use anyhow::{Context, Result};
+use image::ImageOutputFormat;
+use rayon::prelude::*;
+use rusqlite::{Connection, params};
+use std::fs;
+use std::io::Cursor;
+use std::path::Path;
+
+const SCHEMA: &str = "
+CREATE TABLE document (
+    key TEXT PRIMARY KEY,
+    value TEXT
+);
+CREATE TABLE frames (
+    frame_index INTEGER PRIMARY KEY,
+    -- Hash distance to the last kept frame; NULL for the first frame.
+    difference INTEGER,
+    kept INTEGER NOT NULL
+);
+CREATE TABLE pages (
+    page INTEGER PRIMARY KEY,
+    frame_index INTEGER NOT NULL REFERENCES frames(frame_index),
+    timestamp REAL,
+    -- When the page left the screen, and the frames its segment spans.
+    end_timestamp REAL,
+    frame_count INTEGER,
+    title TEXT,
+    width INTEGER NOT NULL,
+    height INTEGER NOT NULL,
+    image BLOB,
+    image_path TEXT,
+    word_count INTEGER NOT NULL,
+    mean_confidence REAL
+);
+CREATE TABLE words (
+    page INTEGER NOT NULL REFERENCES pages(page),
+    position INTEGER NOT NULL,
+    text TEXT NOT NULL,
+    confidence REAL NOT NULL,
+    x1 INTEGER NOT NULL,
+    y1 INTEGER NOT NULL,
+    x2 INTEGER NOT NULL,
+    y2 INTEGER NOT NULL,
+    PRIMARY KEY (page, position)
+);
+CREATE VIRTUAL TABLE pages_fts USING fts5(title, text);
+";
+
+/// Options for the SQLite output.
+#[derive(Debug, Clone)]
+pub struct DatabaseOptions {
+    /// Title of the whole document, usually derived from the input file name.
+    pub document_title: String,
+    /// File name of the input video.
+    pub source: String,
+    /// Sensitivity the frames were analyzed with.
+    pub sensitivity: f64,
+    /// Write page images to an `images` directory next to the database and store
+    /// their paths instead of PNG blobs.
+    pub link_images: bool,
+}
+
+/// Builds a SQLite database with one row per page, its words, the per-frame
+/// analysis metrics, and a `pages_fts` full-text index whose rowid is the page
+/// number. An existing file at `output_path` is replaced.
+pub fn build_database(
+    pages: &[Page],
+    analysis: &AnalysisResult,
+    options: &DatabaseOptions,
+    output_path: &Path,
+) -> Result<()> {
+    if output_path.exists() {
+        fs::remove_file(output_path)
+            .with_context(|| format!("Failed to replace {:?}", output_path))?;
+    }
+    let images: Vec<(Option<Vec<u8>>, Option<String>)> = if options.link_images {
+        let base_dir = output_path.parent().unwrap_or(Path::new("."));
+        document_builder::page_image_sources(pages, false, base_dir)?
+            .into_iter()
+            .map(|path| (None, Some(path)))
+            .collect()
+    } else {
+        pages
+            .par_iter()
+            .map(|page| -> Result<_> {
+                let mut png = Vec::new();
+                page.image.write_to(&mut Cursor::new(&mut png), ImageOutputFormat::Png)?;
+                Ok((Some(png), None))
+            })
+            .collect::<Result<_>>()?
+    };
+
+    let mut conn = Connection::open(output_path)
+        .with_context(|| format!("Failed to create database {:?}", output_path))?;
+    conn.execute_batch(SCHEMA).context("Failed to create database schema")?;
+    let tx = conn.transaction()?;
+
+    {
+        let mut insert = tx.prepare("INSERT INTO document (key, value) VALUES (?1, ?2)")?;
+        let entries = [
+            ("title", options.document_title.clone()),
+            ("source", options.source.clone()),
+            ("generator", format!("videodocparser {}", env!("CARGO_PKG_VERSION"))),
+            ("sensitivity", options.sensitivity.to_string()),
+            ("total_frames", analysis.total_frames.to_string()),
+            ("kept_frames", analysis.kept_frames.len().to_string()),
+            ("removed_frames", analysis.removed_indices.len().to_string()),
+        ];
+        for (key, value) in entries {
+            insert.execute(params![key, value])?;
+        }
+
+        let mut insert =
+            tx.prepare("INSERT INTO frames (frame_index, difference, kept) VALUES (?1, ?2, ?3)")?;
+        let mut kept = analysis.kept_indices.iter().peekable();
+        for frame_index in 0..analysis.total_frames {
+            // Every frame after the first was compared to the last kept one.
+            let difference = frame_index.checked_sub(1).and_then(|i| analysis.differences.get(i));
+            let is_kept = kept.next_if_eq(&&frame_index).is_some();
+            insert.execute(params![frame_index as i64, difference, is_kept])?;
+        }
+
+        let mut insert_page = tx.prepare(
+            "INSERT INTO pages (page, frame_index, timestamp, end_timestamp, frame_count, title, width, height, \
+             image, image_path, word_count, mean_confidence) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+        )?;
+        let mut insert_word = tx.prepare(
+            "INSERT INTO words (page, position, text, confidence, x1, y1, x2, y2) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        )?;
+        let mut insert_text =
+            tx.prepare("INSERT INTO pages_fts (rowid, title, text) VALUES (?1, ?2, ?3)")?;
+        for (i, (page, (blob, path))) in pages.iter().zip(images).enumerate() {
+            let number = (i + 1) as i64;
+            insert_page.execute(params![
+                number,
+                analysis.kept_indices[i] as i64,
+                page.timestamp,
+                page.end,
+                analysis.segments.get(i).map(|segment| segment.frame_count as i64),
+                page.title,
+                page.image.width(),
+                page.image.height(),
+                blob,
+                path,
+                page.word_count() as i64,
+                page.mean_confidence(),
+            ])?;
+            let text = page.ocr.map(layout::page_text).unwrap_or_default();
+            insert_text.execute(params![number, page.title, text])?;
+            let Some(ocr) = page.ocr else { continue };
+            for (position, word) in ocr.words.iter().enumerate() {
+                let (x1, y1, x2, y2) = word.bbox;
+                insert_word.execute(params![
+                    number,
+                    position as i64,
+                    word.text,
+                    word.confidence,
+                    x1,
+                    y1,
+                    x2,
+                    y2
+                ])?;
+            }
+        }
+    }
+
+    tx.commit().context("Failed to write database")?;
+    Ok(())
+}
@@ -0,0 +1,90 @@
+//! Diarize Module
+//!
+//! Handles telling speakers apart in transcribed audio: the speech recognizer
+//! marks where the speaker changes, and each resulting turn is matched to the
+//! speakers heard so far by the median pitch of its voiced frames, or starts a
+//! new speaker when it matches none.
+
+/// Length of the analysis frames, in seconds.
+const FRAME_SECONDS: f64 = 0.04;
+/// Range of voice pitch searched, in Hz.
+const MIN_PITCH: f64 = 70.0;
+const MAX_PITCH: f64 = 400.0;
+/// Normalized autocorrelation a frame needs at its pitch period to be voiced.
+const VOICING_THRESHOLD: f32 = 0.5;
+/// RMS level below which a frame is treated as silence.
+const SILENCE_RMS: f32 = 0.01;
+/// Largest pitch difference, in octaves, between turns of the same speaker.
+pub const SAME_SPEAKER_OCTAVES: f64 = 0.25;
+
+/// Median pitch of the voiced frames of `samples`, in Hz, or `None` when too
+/// little of it is voiced.
+pub fn median_pitch(samples: &[f32], sample_rate: u32) -> Option<f64> {
+    let frame_len = (FRAME_SECONDS * sample_rate as f64) as usize;
+    let min_lag = (sample_rate as f64 / MAX_PITCH) as usize;
+    let max_lag = ((sample_rate as f64 / MIN_PITCH) as usize).min(frame_len.saturating_sub(1));
+    if min_lag == 0 || min_lag >= max_lag {
+        return None;
+    }
+
+    let mut pitches: Vec<f64> = samples
+        .chunks_exact(frame_len)
+        .filter_map(|frame| {
+            let energy: f32 = frame.iter().map(|s| s * s).sum();
+            if (energy / frame_len as f32).sqrt() < SILENCE_RMS {
+                return None;
+            }
+            let (lag, correlation) = (min_lag..=max_lag)
+                .map(|lag| {
+                    let correlation: f32 = frame[..frame_len - lag].iter().zip(&frame[lag..]).map(|(a, b)| a * b).sum();
+                    (lag, correlation / energy)
+                })
+                .max_by(|a, b| a.1.total_cmp(&b.1))?;
+            (correlation >= VOICING_THRESHOLD).then(|| sample_rate as f64 / lag as f64)
+        })
+        .collect();
+    if pitches.len() < 3 {
+        return None;
+    }
+    pitches.sort_by(f64::total_cmp);
+    Some(pitches[pitches.len() / 2])
+}
+
+/// Assigns a speaker number (from 0, in order of first appearance) to each
+/// turn, given as start and end seconds into `audio`. A turn whose pitch cannot
+/// be measured keeps the previous turn's speaker.
+pub fn label_turns(audio: &[f32], sample_rate: u32, turns: &[(f64, f64)]) -> Vec<usize> {
+    // Mean log2 pitch and number of turns of every speaker so far.
+    let mut speakers: Vec<(f64, usize)> = Vec::new();
+    let mut labels = Vec::with_capacity(turns.len());
+    for &(start, end) in turns {
+        let first = ((start * sample_rate as f64) as usize).min(audio.len());
+        let last = ((end * sample_rate as f64) as usize).clamp(first, audio.len());
+        let previous = labels.last().copied().unwrap_or(0);
+        let Some(pitch) = median_pitch(&audio[first..last], sample_rate) else {
+            labels.push(previous);
+            continue;
+        };
+        let pitch = pitch.log2();
+        let closest = speakers
+            .iter()
+            .enumerate()
+            .map(|(i, (mean, _))| (i, (mean - pitch).abs()))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .filter(|(_, distance)| *distance <= SAME_SPEAKER_OCTAVES);
+        let speaker = match closest {
+            Some((i, _)) => {
+                let (mean, count) = &mut speakers[i];
+                *mean = (*mean * *count as f64 + pitch) / (*count + 1) as f64;
+                *count += 1;
+                i
+            }
+            None => {
+                speakers.push((pitch, 1));
+                speakers.len() - 1
+            }
+        };
+        labels.push(speaker);
+    }
+    labels
+}
@@ -0,0 +1,333 @@
+//! Diff Module
+//!
+//! Handles comparing the pages of two processed runs (for example, this year's
+//! and last year's recording of the same lecture) by their OCR text. Each run's
+//! text is read from the OCR report its `run.json` records, JSON or CSV, and
+//! pages the run did not recognize, such as those `--skip-textless` left
+//! image-only, are reported as skipped instead of compared.
+
+use crate::i18n::{Msg, tr};
+use crate::layout;
+use crate::ocr::{OcrFrameResult, OcrWord};
+use crate::run_manifest::RUN_MANIFEST_NAME;
+use anyhow::{Context, Result, anyhow, bail};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+/// Whether a page was matched across the two runs, and how closely.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffStatus {
+    Unchanged,
+    Changed,
+    Added,
+    Removed,
+    /// The page has no OCR text to compare in one of the runs.
+    Skipped,
+}
+
+/// One line of the diff report. Page numbers are 1-based. A skipped page has
+/// the page number of the run it was skipped in.
+#[derive(Debug, Serialize)]
+pub struct DiffEntry {
+    pub status: DiffStatus,
+    pub old_page: Option<usize>,
+    pub new_page: Option<usize>,
+    /// Similarity of the OCR text of the matched pages, from 0.0 to 1.0.
+    pub similarity: f64,
+    pub title: Option<String>,
+}
+
+/// The result of comparing two runs.
+#[derive(Debug, Serialize)]
+pub struct DiffReport {
+    pub old_pages: usize,
+    pub new_pages: usize,
+    pub entries: Vec<DiffEntry>,
+}
+
+impl DiffReport {
+    /// Counts the entries with the given status.
+    pub fn count(&self, status: DiffStatus) -> usize {
+        self.entries.iter().filter(|e| e.status == status).count()
+    }
+
+    /// Prints a localized, human-readable version of the report.
+    pub fn print(&self) {
+        for entry in &self.entries {
+            let label = match entry.status {
+                DiffStatus::Unchanged => tr(Msg::DiffUnchanged),
+                DiffStatus::Changed => tr(Msg::DiffChanged),
+                DiffStatus::Added => tr(Msg::DiffAdded),
+                DiffStatus::Removed => tr(Msg::DiffRemoved),
+                DiffStatus::Skipped => tr(Msg::DiffSkipped),
+            };
+            let page = |p: Option<usize>| p.map_or_else(|| "-".to_string(), |p| p.to_string());
+            println!(
+                "  {:<12} {:>4} -> {:<4} ({:.2})  {}",
+                label,
+                page(entry.old_page),
+                page(entry.new_page),
+                entry.similarity,
+                entry.title.as_deref().unwrap_or("")
+            );
+        }
+        println!();
+        println!(
+            "  {}: {}, {}: {}, {}: {}, {}: {}, {}: {}",
+            tr(Msg::DiffUnchanged),
+            self.count(DiffStatus::Unchanged),
+            tr(Msg::DiffChanged),
+            self.count(DiffStatus::Changed),
+            tr(Msg::DiffAdded),
+            self.count(DiffStatus::Added),
+            tr(Msg::DiffRemoved),
+            self.count(DiffStatus::Removed),
+            tr(Msg::DiffSkipped),
+            self.count(DiffStatus::Skipped)
+        );
+    }
+}
+
+struct PageText {
+    page: usize,
+    words: HashSet<String>,
+    title: Option<String>,
+}
+
+/// The pages of a run: those with OCR text, and the numbers of those without.
+struct RunPages {
+    pages: Vec<PageText>,
+    skipped: Vec<usize>,
+}
+
+/// Compares the OCR results stored in two output directories.
+///
+/// Pages are paired greedily by descending text similarity; pairs scoring below
+/// `match_threshold` are reported as a removal plus an addition instead.
+pub fn diff_runs(old_dir: &Path, new_dir: &Path, match_threshold: f64) -> Result<DiffReport> {
+    let old_run = load_pages(old_dir)?;
+    let new_run = load_pages(new_dir)?;
+    let (old_pages, new_pages) = (&old_run.pages, &new_run.pages);
+
+    let mut candidates = Vec::new();
+    for (i, old) in old_pages.iter().enumerate() {
+        for (j, new) in new_pages.iter().enumerate() {
+            let score = similarity(&old.words, &new.words);
+            if score >= match_threshold {
+                candidates.push((score, i, j));
+            }
+        }
+    }
+    candidates.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    let mut old_match: HashMap<usize, (usize, f64)> = HashMap::new();
+    let mut new_matched = HashSet::new();
+    for (score, i, j) in candidates {
+        if old_match.contains_key(&i) || new_matched.contains(&j) {
+            continue;
+        }
+        old_match.insert(i, (j, score));
+        new_matched.insert(j);
+    }
+
+    let mut entries = Vec::new();
+    for (i, old) in old_pages.iter().enumerate() {
+        let entry = match old_match.get(&i) {
+            Some(&(j, score)) => DiffEntry {
+                status: if score >= 1.0 { DiffStatus::Unchanged } else { DiffStatus::Changed },
+                old_page: Some(old.page),
+                new_page: Some(new_pages[j].page),
+                similarity: score,
+                title: new_pages[j].title.clone().or_else(|| old.title.clone()),
+            },
+            None => DiffEntry {
+                status: DiffStatus::Removed,
+                old_page: Some(old.page),
+                new_page: None,
+                similarity: 0.0,
+                title: old.title.clone(),
+            },
+        };
+        entries.push(entry);
+    }
+    for (j, new) in new_pages.iter().enumerate() {
+        if !new_matched.contains(&j) {
+            entries.push(DiffEntry {
+                status: DiffStatus::Added,
+                old_page: None,
+                new_page: Some(new.page),
+                similarity: 0.0,
+                title: new.title.clone(),
+            });
+        }
+    }
+    let skipped = |old_page, new_page| DiffEntry {
+        status: DiffStatus::Skipped,
+        old_page,
+        new_page,
+        similarity: 0.0,
+        title: None,
+    };
+    entries.extend(old_run.skipped.iter().map(|&page| skipped(Some(page), None)));
+    entries.extend(new_run.skipped.iter().map(|&page| skipped(None, Some(page))));
+    // Order by position in the new run; removed pages follow in their old order.
+    entries.sort_by_key(|e| (e.new_page.unwrap_or(usize::MAX), e.old_page.unwrap_or(0)));
+
+    Ok(DiffReport {
+        old_pages: old_pages.len() + old_run.skipped.len(),
+        new_pages: new_pages.len() + new_run.skipped.len(),
+        entries,
+    })
+}
+
+/// Loads the per-page OCR text of a previous run from its output directory,
+/// from the OCR report its `run.json` records: `ocr_results.json`, or
+/// `ocr_results.csv` for runs made with `--ocr-report csv`. Runs without a
+/// manifest are read from whichever report they have, JSON first.
+///
+/// Pages without a result are skipped when the manifest gives the number of
+/// pages. The CSV report has no rows for pages without words, so those only
+/// count as skipped in runs made with `--skip-textless`; otherwise they are
+/// compared as empty pages.
+fn load_pages(dir: &Path) -> Result<RunPages> {
+    let manifest = read_manifest(dir)?;
+    let setting = |name: &str| manifest.as_ref().and_then(|manifest| manifest["config"].get(name).cloned());
+    let report = setting("ocr_report").and_then(|value| value.as_str().map(str::to_string));
+    let skip_textless = setting("skip_textless").and_then(|value| value.as_bool()).unwrap_or(false);
+    let page_count = manifest.as_ref().and_then(|manifest| manifest["pages"].as_u64()).map(|pages| pages as usize);
+
+    let ocr_dir = dir.join("ocr");
+    let (json_path, csv_path) = (ocr_dir.join("ocr_results.json"), ocr_dir.join("ocr_results.csv"));
+    let use_csv = match report.as_deref() {
+        Some("csv") => true,
+        Some(_) => false,
+        None => !json_path.exists() && csv_path.exists(),
+    };
+    let mut results = if use_csv { read_csv_report(&csv_path)? } else { read_json_report(&json_path)? };
+    results.sort_by_key(|r| r.frame_index);
+
+    let mut skipped = Vec::new();
+    if let Some(page_count) = page_count {
+        let recognized: HashSet<usize> = results.iter().map(|result| result.frame_index).collect();
+        for index in (0..page_count).filter(|index| !recognized.contains(index)) {
+            if use_csv && !skip_textless {
+                results.push(OcrFrameResult {
+                    frame_index: index,
+                    words: Vec::new(),
+                    blocks: Vec::new(),
+                    strategy: None,
+                    timestamp: None,
+                });
+            } else {
+                skipped.push(index + 1);
+            }
+        }
+        results.sort_by_key(|r| r.frame_index);
+    }
+
+    let pages = results
+        .iter()
+        .map(|result| PageText {
+            page: result.frame_index + 1,
+            words: result.words.iter().map(|w| w.text.to_lowercase()).collect(),
+            title: layout::detect_title(result),
+        })
+        .collect();
+    Ok(RunPages { pages, skipped })
+}
+
+/// Reads the `run.json` of a run, if it has one.
+fn read_manifest(dir: &Path) -> Result<Option<serde_json::Value>> {
+    let path = dir.join(RUN_MANIFEST_NAME);
+    let json = match fs::read_to_string(&path) {
+        Ok(json) => json,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read the run manifest {:?}", path)),
+    };
+    let manifest =
+        serde_json::from_str(&json).with_context(|| format!("Failed to parse the run manifest {:?}", path))?;
+    Ok(Some(manifest))
+}
+
+/// Reads `ocr_results.json`.
+fn read_json_report(path: &Path) -> Result<Vec<OcrFrameResult>> {
+    let json = fs::read_to_string(path).with_context(|| format!("Failed to read OCR results from {:?}", path))?;
+    serde_json::from_str(&json).with_context(|| format!("Failed to parse OCR results in {:?}", path))
+}
+
+/// Reads `ocr_results.csv`, one row per word, into one result per frame.
+fn read_csv_report(path: &Path) -> Result<Vec<OcrFrameResult>> {
+    let csv = fs::read_to_string(path).with_context(|| format!("Failed to read OCR results from {:?}", path))?;
+    let mut frames: Vec<OcrFrameResult> = Vec::new();
+    for (row, fields) in parse_csv(&csv).into_iter().enumerate().skip(1) {
+        let (frame_index, timestamp, word) =
+            parse_csv_row(&fields).map_err(|e| anyhow!("Invalid row {} in {:?}: {}", row + 1, path, e))?;
+        match frames.last_mut() {
+            Some(frame) if frame.frame_index == frame_index => frame.words.push(word),
+            _ => frames.push(OcrFrameResult {
+                frame_index,
+                words: vec![word],
+                blocks: Vec::new(),
+                strategy: None,
+                timestamp,
+            }),
+        }
+    }
+    Ok(frames)
+}
+
+/// Reads the frame index, timestamp, and word of a row of the CSV report.
+fn parse_csv_row(fields: &[String]) -> Result<(usize, Option<f64>, OcrWord)> {
+    let [frame_index, timestamp, text, x1, y1, x2, y2, confidence] = fields else {
+        bail!("expected 8 fields, found {}", fields.len());
+    };
+    let timestamp = if timestamp.is_empty() { None } else { Some(timestamp.parse()?) };
+    let word = OcrWord {
+        text: text.clone(),
+        bbox: (x1.parse()?, y1.parse()?, x2.parse()?, y2.parse()?),
+        confidence: confidence.parse()?,
+    };
+    Ok((frame_index.parse()?, timestamp, word))
+}
+
+/// Splits CSV text into rows of fields, unquoting quoted fields, which may
+/// hold separators, doubled quotes, and line breaks.
+fn parse_csv(text: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let (mut row, mut field) = (Vec::new(), String::new());
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => row.push(std::mem::take(&mut field)),
+            '\r' if !quoted => {}
+            '\n' if !quoted => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            c => field.push(c),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    rows
+}
+
+/// Jaccard similarity of two word sets. Two empty pages are considered identical.
+fn similarity(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 1.0;
+    }
+    a.intersection(b).count() as f64 / union as f64
+}
@@ -1,27 +1,300 @@
 //! Document Builder Module
 //!
-//! Handles the creation of the final output document, such as a searchable PDF.
-
-use crate::ocr::{OcrFrameResult};
-use anyhow::{Context, Result};
-use image::{ImageBuffer, ImageOutputFormat, Rgb};
-use log::info;
-use pdf_writer::{Content, Filter, Finish, Name, Pdf, Rect, Ref, Str};
-use std::collections::HashMap;
+//! Handles the creation of the final output document, such as a searchable PDF
+//! or a Markdown file with the page images alongside it.
+
+use crate::builds;
+use crate::figures;
+use crate::formulas::{self, Formula};
+use crate::keywords::{self, KeywordEntry};
+use crate::layout::{self, OcrLine, TextBlock};
+use crate::links;
+use crate::mrc::{self, PageColour};
+use crate::obsidian::yaml_string;
+use crate::ocr::{OcrFrameResult, OcrTextLine, OcrWord};
+use crate::pdf_encryption::{self, PdfEncryption};
+use crate::pdf_file::PdfFile;
+use crate::pdf_font::{self, GlyphlessFont};
+use crate::pdf_keywords;
+use crate::pdf_notes;
+use crate::pdf_tags::{self, StructureTree, TaggedPage};
+use crate::overlay;
+use crate::pdf_toc;
+use crate::pdfa;
+use crate::xmp;
+use anyhow::{Context, Result, bail};
+use image::imageops::{self, FilterType};
+use image::{ImageBuffer, ImageOutputFormat, PixelWithColorType, Rgb};
+use log::{info, warn};
+use pdf_writer::types::{ActionType, AnnotationFlags, AnnotationType, NumberingStyle, TabOrder};
+use pdf_writer::writers::{Catalog, PageLabel};
+use pdf_writer::{Chunk, Content, Date, Filter, Finish, Name, Pdf, Rect, Ref, Str, TextStr};
+use rayon::prelude::*;
+use std::borrow::Cow;
+use std::fmt::Write as _;
 use std::fs;
 use std::io::Cursor;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 // Standard PDF page sizes in points (1/72 inch).
-const A4_WIDTH_PT: f32 = 595.0;
-const A4_HEIGHT_PT: f32 = 842.0;
-
-/// Builds a searchable PDF from frames and their corresponding OCR results.
-pub fn build_pdf(
-    frames: &[ImageBuffer<Rgb<u8>, Vec<u8>>],
-    ocr_results: &[OcrFrameResult],
-    output_path: &Path, // Changed from output_dir to the full file path
-) -> Result<()> {
+pub(crate) const A4_WIDTH_PT: f32 = 595.0;
+pub(crate) const A4_HEIGHT_PT: f32 = 842.0;
+// Resource name of the text layer font on every page.
+const TEXT_FONT: Name<'static> = Name(b"OcrText");
+// Resource name of the timestamp label that links back to the source video.
+const SOURCE_LINK_IMAGE: Name<'static> = Name(b"SourceLink");
+// Folder next to a Markdown document holding its tables as CSV files.
+const TABLES_DIR: &str = "tables";
+/// JPEG quality used for page images unless configured otherwise.
+pub const DEFAULT_JPEG_QUALITY: u8 = 85;
+/// OCR confidence below which words are unsure unless configured otherwise.
+pub const DEFAULT_MIN_CONFIDENCE: f32 = 50.0;
+
+/// A single output page: the kept frame together with everything derived from it.
+pub struct Page<'a> {
+    pub image: &'a ImageBuffer<Rgb<u8>, Vec<u8>>,
+    pub ocr: Option<&'a OcrFrameResult>,
+    /// Title detected from the page's OCR text, if any.
+    pub title: Option<String>,
+    /// When the page first appeared in the source video, in seconds.
+    pub timestamp: Option<f64>,
+    /// When the page left the screen, in seconds: the end of its segment.
+    pub end: Option<f64>,
+}
+
+impl Page<'_> {
+    /// Number of words recognized on the page.
+    pub fn word_count(&self) -> usize {
+        self.ocr.map_or(0, |ocr| ocr.words.len())
+    }
+
+    /// Mean OCR confidence (0-100) of the page's words, if any were recognized.
+    pub fn mean_confidence(&self) -> Option<f32> {
+        let words = &self.ocr?.words;
+        (!words.is_empty())
+            .then(|| words.iter().map(|word| word.confidence).sum::<f32>() / words.len() as f32)
+    }
+
+    /// Returns the detected title, else the first line of OCR text, else a generic
+    /// label based on the page number.
+    pub fn display_title(&self, index: usize) -> String {
+        self.title
+            .clone()
+            .or_else(|| {
+                self.ocr
+                    .and_then(|ocr| layout::group_lines(ocr).into_iter().next())
+                    .map(|line| line.text)
+            })
+            .unwrap_or_else(|| format!("Page {}", index + 1))
+    }
+
+    /// Returns the bookmark label: the timestamp, when known, followed by the title.
+    pub fn outline_label(&self, index: usize) -> String {
+        match self.timestamp {
+            Some(timestamp) => {
+                format!("{} - {}", format_timestamp(timestamp), self.display_title(index))
+            }
+            None => self.display_title(index),
+        }
+    }
+
+    /// Returns the end of the page's time range for headings, `dash` followed
+    /// by the time the page left the screen, or nothing when it is unknown.
+    pub fn range_end(&self, dash: &str) -> String {
+        self.end.map(|end| format!("{}{}", dash, format_timestamp(end))).unwrap_or_default()
+    }
+}
+
+/// Formats a position in the video as `HH:MM:SS`.
+pub fn format_timestamp(seconds: f64) -> String {
+    let total = seconds.max(0.0) as u64;
+    format!("{:02}:{:02}:{:02}", total / 3600, total / 60 % 60, total % 60)
+}
+
+/// How page images are compressed inside the PDF.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImageCompression {
+    /// Lossy JPEG at the given quality (1-100).
+    Jpeg { quality: u8 },
+    /// Lossless Flate, which keeps text edges in screen captures sharp.
+    Flate,
+    /// Mixed raster content: a CCITT Group 4 text mask over a low-resolution
+    /// JPEG background at the given quality (1-100). See [`mrc`].
+    Mrc { quality: u8 },
+}
+
+impl Default for ImageCompression {
+    fn default() -> Self {
+        ImageCompression::Jpeg { quality: DEFAULT_JPEG_QUALITY }
+    }
+}
+
+/// What an output does with the words recognized below the confidence threshold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LowConfidence {
+    /// Leave them out, as if they had not been recognized.
+    #[default]
+    Drop,
+    /// Keep them, and list them as unsure where the output has room for it:
+    /// after the page's text in Markdown, and in a `low_confidence` field in
+    /// JSON Lines. The PDF text layer is invisible, so it keeps them unmarked.
+    Flag,
+    /// Keep them like any other word.
+    Keep,
+}
+
+/// How an output treats OCR words by their confidence.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConfidencePolicy {
+    /// Confidence (0-100) below which a word is unsure.
+    pub threshold: f32,
+    pub low_confidence: LowConfidence,
+}
+
+impl Default for ConfidencePolicy {
+    fn default() -> Self {
+        ConfidencePolicy { threshold: DEFAULT_MIN_CONFIDENCE, low_confidence: LowConfidence::Drop }
+    }
+}
+
+impl ConfidencePolicy {
+    /// Whether a word was recognized below the threshold.
+    pub fn is_unsure(&self, word: &OcrWord) -> bool {
+        word.confidence < self.threshold
+    }
+
+    /// Returns the OCR of a frame as the output shows it: without its unsure
+    /// words when they are dropped, else unchanged.
+    pub fn apply<'a>(&self, ocr: &'a OcrFrameResult) -> Cow<'a, OcrFrameResult> {
+        if self.low_confidence != LowConfidence::Drop || !ocr.words.iter().any(|word| self.is_unsure(word)) {
+            return Cow::Borrowed(ocr);
+        }
+        Cow::Owned(ocr.retain_words(|word| !self.is_unsure(word)))
+    }
+
+    /// Applies the policy to the OCR of every page; see [`Self::apply`].
+    pub fn apply_pages<'a>(&self, pages: &[Page<'a>]) -> Vec<Option<Cow<'a, OcrFrameResult>>> {
+        pages.iter().map(|page| page.ocr.map(|ocr| self.apply(ocr))).collect()
+    }
+
+    /// Returns the unsure words of a frame the output flags: none unless it
+    /// flags them.
+    pub fn flagged<'a>(&self, ocr: &'a OcrFrameResult) -> Vec<&'a OcrWord> {
+        if self.low_confidence != LowConfidence::Flag {
+            return Vec::new();
+        }
+        ocr.words.iter().filter(|word| !word.text.is_empty() && self.is_unsure(word)).collect()
+    }
+}
+
+/// The pages with the OCR `apply_pages` returned for them.
+fn with_ocr<'a>(pages: &[Page<'a>], ocr: &'a [Option<Cow<'_, OcrFrameResult>>]) -> Vec<Page<'a>> {
+    pages
+        .iter()
+        .zip(ocr)
+        .map(|(page, ocr)| Page {
+            image: page.image,
+            ocr: ocr.as_deref(),
+            title: page.title.clone(),
+            timestamp: page.timestamp,
+            end: page.end,
+        })
+        .collect()
+}
+
+/// Size of the PDF pages.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum PageSize {
+    /// A4, in the orientation of the frame, with the frame letterboxed onto it.
+    #[default]
+    A4,
+    /// Each page is exactly the size of its frame printed at the given resolution.
+    Native { dpi: f32 },
+}
+
+/// Options for the PDF output.
+#[derive(Debug, Clone, Default)]
+pub struct PdfOptions {
+    /// Written to the document information dictionary and the XMP metadata.
+    pub metadata: xmp::DocumentMetadata,
+    /// Emit PDF/A-2b conformant output for long-term archiving.
+    pub pdfa: bool,
+    pub page_size: PageSize,
+    pub image_compression: ImageCompression,
+    /// Downscale page images whose resolution on the page exceeds this many dots per inch.
+    pub max_image_dpi: Option<f32>,
+    /// Start the document with contents pages linking to every page.
+    pub table_of_contents: bool,
+    /// URL of the source video; each page then links to the moment it appeared.
+    pub video_url: Option<String>,
+    /// Password-protect the PDF. Not allowed with PDF/A.
+    pub encryption: Option<PdfEncryption>,
+    /// Emit a tagged, PDF/UA-1 conformant structure for screen readers.
+    pub tagged: bool,
+    /// Natural language of the text, as a BCP 47 tag such as `en`.
+    pub language: Option<String>,
+    /// Speech transcribed while each page was on screen, by page, printed on
+    /// pages after it. Empty for no transcript.
+    pub speech: Vec<String>,
+    /// Translation of each page's OCR text, by page, printed on pages after it
+    /// and before its transcript. Empty for no translation.
+    pub translations: Vec<String>,
+    /// End the document with an alphabetical index of significant terms.
+    pub keyword_index: bool,
+    /// Make the URLs recognized on each page clickable. Not available with PDF/UA.
+    pub url_links: bool,
+    /// Which words go into the text layer by their confidence.
+    pub confidence: ConfidencePolicy,
+    /// Leave the words of formulas out of the text layer.
+    pub formulas: bool,
+    /// Embed page images without colour in grayscale, and those of black
+    /// text on white with one bit per pixel. Not used with MRC compression.
+    pub auto_colour: bool,
+}
+
+/// The files a document builder wrote.
+#[derive(Debug, Clone)]
+pub struct WrittenFiles {
+    /// The document itself.
+    pub document: PathBuf,
+    /// The page images saved next to it, one per page; empty when they are
+    /// embedded in the document.
+    pub images: Vec<PathBuf>,
+}
+
+impl WrittenFiles {
+    /// A document with its images, given relative to `base_dir`.
+    pub(crate) fn new(document: &Path, base_dir: &Path, images: &[String]) -> Self {
+        WrittenFiles {
+            document: document.to_path_buf(),
+            images: images.iter().map(|image| base_dir.join(image)).collect(),
+        }
+    }
+}
+
+/// Creates the folder of `output_path` if needed and returns it; page images
+/// are saved relative to it.
+pub(crate) fn prepare_output_dir(output_path: &Path) -> Result<&Path> {
+    let base_dir = output_path
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    fs::create_dir_all(base_dir).with_context(|| format!("Failed to create directory {:?}", base_dir))?;
+    Ok(base_dir)
+}
+
+/// Builds a searchable PDF from the pages, with one bookmark per page.
+///
+/// The document-level objects are built in memory first; every page is then
+/// encoded and appended to the file on its own, so only one page image is
+/// held in memory at a time.
+pub fn build_pdf(pages: &[Page], options: &PdfOptions, output_path: &Path) -> Result<WrittenFiles> {
+    if options.pdfa && options.encryption.is_some() {
+        bail!("PDF/A does not allow encryption; drop the PDF passwords or use the standard profile");
+    }
+    prepare_output_dir(output_path)?;
+    let ocr = options.confidence.apply_pages(pages);
+    let pages = &with_ocr(pages, &ocr);
     let mut pdf = Pdf::new();
     let mut ref_counter = std::iter::successors(Some(1), |n| Some(n + 1));
 
@@ -29,66 +302,359 @@ pub fn build_pdf(
     let catalog_ref = Ref::new(ref_counter.next().unwrap());
     let page_tree_ref = Ref::new(ref_counter.next().unwrap());
     let font_ref = Ref::new(ref_counter.next().unwrap());
-    pdf.catalog(catalog_ref).pages(page_tree_ref);
-    pdf.type1_font(font_ref).base_font(Name(b"Helvetica"));
+    let outline_ref = Ref::new(ref_counter.next().unwrap());
+    let metadata_ref = Ref::new(ref_counter.next().unwrap());
+    let info_ref = Ref::new(ref_counter.next().unwrap());
+    let archival = options.pdfa.then(|| pdfa::ArchivalObjects::allocate(&mut ref_counter));
+    let mut structure = options.tagged.then(|| StructureTree::allocate(&mut ref_counter));
 
-    let ocr_map: HashMap<usize, &OcrFrameResult> =
-        ocr_results.iter().map(|r| (r.frame_index, r)).collect();
+    // The contents pages use a standard font that is not embedded, which PDF/A and
+    // PDF/UA forbid.
+    let conforming = options.pdfa || options.tagged;
+    if options.table_of_contents && conforming {
+        warn!("The table of contents is not available with PDF/A or PDF/UA output and will be skipped.");
+    }
+    let toc_pages = if options.table_of_contents && !conforming {
+        pdf_toc::page_count(pages.len())
+    } else {
+        0
+    };
+    // So do the transcript pages following each slide.
+    let has_speech = options.speech.iter().any(|speech| !speech.trim().is_empty());
+    if has_speech && conforming {
+        warn!("Transcript pages are not available with PDF/A or PDF/UA output and will be skipped.");
+    }
+    // And the translation pages, which come first.
+    let has_translations = options.translations.iter().any(|translation| !translation.trim().is_empty());
+    if has_translations && conforming {
+        warn!("Translation pages are not available with PDF/A or PDF/UA output and will be skipped.");
+    }
+    let paginate = |texts: &[String], i: usize| match texts.get(i) {
+        Some(text) if !conforming => pdf_notes::paginate(text),
+        _ => Vec::new(),
+    };
+    let translated: Vec<usize> = (0..pages.len()).map(|i| paginate(&options.translations, i).len()).collect();
+    let notes: Vec<Vec<Vec<String>>> = (0..pages.len())
+        .map(|i| [paginate(&options.translations, i), paginate(&options.speech, i)].concat())
+        .collect();
+    // Position of every slide page in the document, counting from 1.
+    let page_numbers: Vec<usize> = notes
+        .iter()
+        .scan(toc_pages + 1, |next, page_notes| {
+            let number = *next;
+            *next += 1 + page_notes.len();
+            Some(number)
+        })
+        .collect();
+    // And so does the keyword index at the end.
+    if options.keyword_index && conforming {
+        warn!("The keyword index is not available with PDF/A or PDF/UA output and will be skipped.");
+    }
+    let index_layout = if options.keyword_index && !conforming {
+        pdf_keywords::paginate(&keywords::build_keyword_index(pages), &page_numbers)
+    } else {
+        Vec::new()
+    };
+    // Link annotations would need structure elements of their own.
+    if options.url_links && options.tagged {
+        warn!("Clickable URLs are not available with PDF/UA output and will be skipped.");
+    }
+    let index_start = (!index_layout.is_empty())
+        .then(|| toc_pages + pages.len() + notes.iter().map(Vec::len).sum::<usize>());
+
+    let mut catalog = pdf.catalog(catalog_ref);
+    catalog.pages(page_tree_ref).outlines(outline_ref).metadata(metadata_ref);
+    if let Some(archival) = &archival {
+        archival.link(&mut catalog);
+    }
+    if let Some(structure) = &structure {
+        structure.link(&mut catalog);
+    }
+    if let Some(language) = &options.language {
+        catalog.lang(TextStr(language));
+    }
+    write_page_labels(&mut catalog, toc_pages, pages, &page_numbers, index_start);
+    catalog.finish();
+    if let Some(archival) = &archival {
+        archival.write(&mut pdf);
+    }
+    write_metadata(&mut pdf, metadata_ref, info_ref, options);
+
+    // The words separator is part of every text layer, see `draw_text_layer`.
+    let font = GlyphlessFont::new(
+        pages
+            .iter()
+            .filter_map(|page| page.ocr)
+            .flat_map(|ocr| &ocr.words)
+            .flat_map(|word| word.text.chars())
+            .chain([' ']),
+    );
+    font.write(&mut pdf, &mut ref_counter, font_ref);
 
     // Pre-allocate all page Refs
-    let page_refs: Vec<Ref> = (0..frames.len())
+    let page_refs: Vec<Ref> = (0..pages.len())
+        .map(|_| Ref::new(ref_counter.next().unwrap()))
+        .collect();
+
+    let toc_refs: Vec<Ref> = (0..toc_pages)
         .map(|_| Ref::new(ref_counter.next().unwrap()))
         .collect();
+    pdf_toc::write(
+        &mut pdf,
+        &mut ref_counter,
+        &toc_refs,
+        page_tree_ref,
+        &options.metadata.title,
+        pages,
+        &page_refs,
+        &page_numbers,
+    );
 
-    // The main loop is now much cleaner. It calls a helper to build each page.
-    for (i, frame) in frames.iter().enumerate() {
-        build_single_page(
-            &mut pdf,
+    let note_refs: Vec<Vec<Ref>> = notes
+        .iter()
+        .map(|page_notes| page_notes.iter().map(|_| Ref::new(ref_counter.next().unwrap())).collect())
+        .collect();
+    if note_refs.iter().any(|refs| !refs.is_empty()) {
+        let notes_font_ref = Ref::new(ref_counter.next().unwrap());
+        pdf_notes::write_font(&mut pdf, notes_font_ref);
+        for (i, page) in pages.iter().enumerate() {
+            let (translation_refs, speech_refs) = note_refs[i].split_at(translated[i]);
+            let (translation_pages, speech_pages) = notes[i].split_at(translated[i]);
+            for (refs, kind, note_pages) in
+                [(translation_refs, "Translation", translation_pages), (speech_refs, "Transcript", speech_pages)]
+            {
+                let heading = format!("{}: {}", kind, page.outline_label(i));
+                pdf_notes::write(&mut pdf, &mut ref_counter, refs, page_tree_ref, notes_font_ref, &heading, note_pages);
+            }
+        }
+    }
+
+    let index_refs: Vec<Ref> = index_layout
+        .iter()
+        .map(|_| Ref::new(ref_counter.next().unwrap()))
+        .collect();
+    pdf_keywords::write(
+        &mut pdf,
+        &mut ref_counter,
+        &index_refs,
+        page_tree_ref,
+        "Index",
+        &index_layout,
+        &page_refs,
+        &page_numbers,
+    );
+
+    // Write the page tree
+    let kids: Vec<Ref> = toc_refs
+        .iter()
+        .copied()
+        .chain(page_refs.iter().zip(&note_refs).flat_map(|(&page_ref, refs)| {
+            std::iter::once(page_ref).chain(refs.iter().copied())
+        }))
+        .chain(index_refs.iter().copied())
+        .collect();
+    pdf.pages(page_tree_ref).count(kids.len() as i32).kids(kids);
+
+    write_outline(&mut pdf, &mut ref_counter, outline_ref, pages, &page_refs);
+
+    // Write the file; `prepare_output_dir` created its directory.
+    info!("Writing PDF to {:?}", output_path);
+    let mut file = PdfFile::create(output_path)?;
+    file.write_document(pdf)?;
+
+    for (i, page) in pages.iter().enumerate() {
+        let tagged_page = build_single_page(
+            &mut file,
             &mut ref_counter,
+            i,
             page_refs[i],
             page_tree_ref,
             font_ref,
-            frame,
-            ocr_map.get(&i),
+            &font,
+            options,
+            page,
         )?;
+        if let Some(structure) = &mut structure {
+            structure.push(tagged_page);
+        }
     }
 
-    // Write the page tree
-    pdf.pages(page_tree_ref).kids(page_refs).count(frames.len() as i32);
+    // The structure tree refers to what was marked on every page, so it comes last.
+    if let Some(structure) = structure {
+        let mut pdf = Pdf::new();
+        structure.write(&mut pdf, &mut ref_counter);
+        file.write_document(pdf)?;
+    }
 
-    // Ensure parent directory exists and write the file
-    if let Some(parent_dir) = output_path.parent() {
-        fs::create_dir_all(parent_dir).context("Failed to create PDF parent directory")?;
+    file.finish(catalog_ref, info_ref)?;
+
+    if let Some(encryption) = &options.encryption {
+        info!("Encrypting PDF {:?}", output_path);
+        pdf_encryption::encrypt_file(output_path, encryption)?;
     }
-    info!("Writing PDF to {:?}", output_path);
-    fs::write(output_path, pdf.finish())
-        .context("Failed to write PDF file")?;
+    Ok(WrittenFiles::new(output_path, Path::new(""), &[]))
+}
 
-    Ok(())
+/// Labels each page with its video timestamp, so viewers show `00:12:34` instead
+/// of a page number. Contents pages are numbered in lowercase Roman numerals and
+/// pages without a timestamp keep their page number. Transcript pages share the
+/// label of their slide, whose position in the document is in `page_numbers`.
+/// Keyword index pages, starting at page `index_start`, are `Index 1`, `Index 2`...
+fn write_page_labels(
+    catalog: &mut Catalog,
+    toc_pages: usize,
+    pages: &[Page],
+    page_numbers: &[usize],
+    index_start: Option<usize>,
+) {
+    if toc_pages == 0 && index_start.is_none() && pages.iter().all(|page| page.timestamp.is_none()) {
+        return;
+    }
+    let mut labels = catalog.insert(Name(b"PageLabels")).dict();
+    let mut nums = labels.insert(Name(b"Nums")).array();
+    if toc_pages > 0 {
+        nums.item(0);
+        nums.push().start::<PageLabel>().style(NumberingStyle::LowerRoman);
+    }
+    for (i, page) in pages.iter().enumerate() {
+        nums.item(page_numbers[i] as i32 - 1);
+        let mut label = nums.push().start::<PageLabel>();
+        match page.timestamp {
+            Some(timestamp) => label.prefix(TextStr(&format_timestamp(timestamp))),
+            None => label.style(NumberingStyle::Arabic).offset(i as i32 + 1),
+        };
+    }
+    if let Some(start) = index_start {
+        nums.item(start as i32);
+        nums.push().start::<PageLabel>().prefix(TextStr("Index ")).style(NumberingStyle::Arabic);
+    }
 }
 
-/// Helper function that constructs all the objects for a single page.
+/// Writes the document information dictionary and the matching XMP metadata stream.
+fn write_metadata(pdf: &mut Pdf, metadata_ref: Ref, info_ref: Ref, options: &PdfOptions) {
+    let metadata = &options.metadata;
+    let packet = xmp::document_packet(metadata, options.pdfa.then_some((2, "B")), options.tagged.then_some(1));
+    pdf.metadata(metadata_ref, packet.as_bytes());
+
+    let creator = format!("videodocparser {}", env!("CARGO_PKG_VERSION"));
+    let mut info = pdf.document_info(info_ref);
+    info.title(TextStr(&metadata.title));
+    if let Some(author) = &metadata.author {
+        info.author(TextStr(author));
+    }
+    if let Some(keywords) = &metadata.keywords {
+        info.keywords(TextStr(keywords));
+    }
+    if let Some(date) = metadata.creation_date {
+        info.creation_date(
+            Date::new(date.year)
+                .month(date.month)
+                .day(date.day)
+                .hour(date.hour)
+                .minute(date.minute)
+                .second(date.second)
+                .utc_offset_hour(0)
+                .utc_offset_minute(0),
+        );
+    }
+    info.creator(TextStr(&creator));
+    info.producer(TextStr(&creator));
+}
+
+/// Writes a flat document outline with one bookmark per page, labeled with the
+/// page's timestamp and title.
+fn write_outline(
+    pdf: &mut Pdf,
+    ref_counter: &mut dyn Iterator<Item = i32>,
+    outline_ref: Ref,
+    pages: &[Page],
+    page_refs: &[Ref],
+) {
+    let item_refs: Vec<Ref> = (0..pages.len())
+        .map(|_| Ref::new(ref_counter.next().unwrap()))
+        .collect();
+
+    let mut outline = pdf.outline(outline_ref);
+    if let (Some(first), Some(last)) = (item_refs.first(), item_refs.last()) {
+        outline.first(*first).last(*last);
+    }
+    outline.count(item_refs.len() as i32);
+    outline.finish();
+
+    for (i, page) in pages.iter().enumerate() {
+        let title = page.outline_label(i);
+        let mut item = pdf.outline_item(item_refs[i]);
+        item.title(TextStr(&title)).parent(outline_ref);
+        if i > 0 {
+            item.prev(item_refs[i - 1]);
+        }
+        if let Some(next) = item_refs.get(i + 1) {
+            item.next(*next);
+        }
+        item.dest().page(page_refs[i]).fit();
+    }
+}
+
+/// Helper function that constructs all the objects for a single page and returns
+/// what was marked on it for the structure tree (used only in tagged output).
 #[allow(clippy::too_many_arguments)]
 fn build_single_page(
-    pdf: &mut Pdf,
+    file: &mut PdfFile,
     ref_counter: &mut dyn Iterator<Item = i32>,
+    index: usize,
     page_ref: Ref,
     page_tree_ref: Ref,
     font_ref: Ref,
-    frame: &ImageBuffer<Rgb<u8>, Vec<u8>>,
-    ocr_result: Option<&&OcrFrameResult>,
-) -> Result<()> {
+    font: &GlyphlessFont,
+    options: &PdfOptions,
+    source: &Page,
+) -> Result<TaggedPage> {
+    let frame = source.image;
     let content_ref = Ref::new(ref_counter.next().unwrap());
     let image_ref = Ref::new(ref_counter.next().unwrap());
     let image_name_str = format!("Frame{}", image_ref.get());
     let image_name = Name(image_name_str.as_bytes());
+    let mask_ref = matches!(options.image_compression, ImageCompression::Mrc { .. })
+        .then(|| Ref::new(ref_counter.next().unwrap()));
+    let mask_name_str = mask_ref.map(|mask_ref| format!("Mask{}", mask_ref.get())).unwrap_or_default();
+    let mask_name = Name(mask_name_str.as_bytes());
+
+    // A timestamp label linking back to the recording, when a video URL is known
+    let link = options
+        .video_url
+        .as_deref()
+        .zip(source.timestamp)
+        .map(|(url, timestamp)| SourceLink {
+            url: deep_link(url, timestamp),
+            description: format!("Open the video at {}", format_timestamp(timestamp)),
+            label: overlay::render_label(
+                &format_timestamp(timestamp),
+                overlay::label_scale(frame.height()),
+            ),
+            image_ref: Ref::new(ref_counter.next().unwrap()),
+            annotation_ref: Ref::new(ref_counter.next().unwrap()),
+        });
+
+    // Clickable areas over the URLs recognized on the page
+    let url_links: Vec<UrlLink> = match source.ocr {
+        Some(ocr) if options.url_links && !options.tagged => ocr
+            .words
+            .iter()
+            .filter_map(|word| links::normalize_url(&word.text).map(|url| (url, word.bbox)))
+            .map(|(url, bbox)| UrlLink { url, bbox, annotation_ref: Ref::new(ref_counter.next().unwrap()) })
+            .collect(),
+        _ => Vec::new(),
+    };
 
     // 1. Determine page orientation and scaling
     let (image_width, image_height) = frame.dimensions();
-    let (page_width, page_height) = if image_width > image_height {
-        (A4_HEIGHT_PT, A4_WIDTH_PT) // Landscape
-    } else {
-        (A4_WIDTH_PT, A4_HEIGHT_PT) // Portrait
+    let (page_width, page_height) = match options.page_size {
+        PageSize::A4 if image_width > image_height => (A4_HEIGHT_PT, A4_WIDTH_PT), // Landscape
+        PageSize::A4 => (A4_WIDTH_PT, A4_HEIGHT_PT),                                // Portrait
+        PageSize::Native { dpi } => (
+            image_width as f32 / dpi * 72.0,
+            image_height as f32 / dpi * 72.0,
+        ),
     };
     let scale_x = page_width / image_width as f32;
     let scale_y = page_height / image_height as f32;
@@ -99,57 +665,1194 @@ fn build_single_page(
     let offset_y = (page_height - scaled_height) / 2.0;
 
     // 2. Write the page object dictionary
-    let mut page = pdf.page(page_ref);
+    let mut chunk = Chunk::new();
+    let mut page = chunk.page(page_ref);
     page.media_box(Rect::new(0.0, 0.0, page_width, page_height));
     page.parent(page_tree_ref);
     page.contents(content_ref);
+    let annotation_refs: Vec<Ref> = link
+        .iter()
+        .map(|link| link.annotation_ref)
+        .chain(url_links.iter().map(|url_link| url_link.annotation_ref))
+        .collect();
+    if !annotation_refs.is_empty() {
+        page.annotations(annotation_refs);
+    }
+    if options.tagged {
+        page.struct_parents(StructureTree::page_key(index));
+        page.tab_order(TabOrder::StructureOrder);
+    }
     let mut resources = page.resources();
-    resources.fonts().pair(Name(b"Helvetica"), font_ref);
-    resources.x_objects().pair(image_name, image_ref);
+    resources.fonts().pair(TEXT_FONT, font_ref);
+    let mut x_objects = resources.x_objects();
+    x_objects.pair(image_name, image_ref);
+    if let Some(mask_ref) = mask_ref {
+        x_objects.pair(mask_name, mask_ref);
+    }
+    if let Some(link) = &link {
+        x_objects.pair(SOURCE_LINK_IMAGE, link.image_ref);
+    }
+    x_objects.finish();
     resources.finish();
     page.finish();
+    file.write_object(&chunk)?;
+
+    // The embedded image, downscaled if it exceeds the resolution limit
+    let downscaled = options
+        .max_image_dpi
+        .map(|dpi| (scaled_width / 72.0 * dpi).round() as u32)
+        .filter(|&max_width| max_width > 0 && max_width < image_width)
+        .map(|max_width| {
+            let height = ((image_height as u64 * max_width as u64) / image_width as u64).max(1);
+            imageops::resize(frame, max_width, height as u32, FilterType::Lanczos3)
+        });
+    let embedded = downscaled.as_ref().unwrap_or(frame);
+    let layers = mask_ref.map(|_| mrc::separate(embedded));
 
-    // 3. Prepare the image and content streams
+    // 3. Draw the image and the invisible text layer into the page's single content stream
+    // In tagged output the image, the label, and each text line are marked content
+    // with ids in that order.
+    let placement = Placement { scale_factor, offset_x, offset_y, image_height };
     let mut content = Content::new();
+    let mut next_mcid = options.tagged.then_some(pdf_tags::FIGURE_MCID);
+    begin_tag(&mut content, pdf_tags::FIGURE, &mut next_mcid);
+    draw_image(&mut content, image_name, scaled_width, scaled_height, offset_x, offset_y);
+    // With MRC the image above is only the background; the text mask is painted over it.
+    if let Some(layers) = &layers {
+        let [r, g, b] = layers.foreground.map(|c| c as f32 / 255.0);
+        content.save_state();
+        content.set_fill_rgb(r, g, b);
+        draw_image(&mut content, mask_name, scaled_width, scaled_height, offset_x, offset_y);
+        content.restore_state();
+    }
+    end_tag(&mut content, next_mcid);
+    let mut tagged_link = None;
+    if let Some(link) = &link {
+        let mcid = next_mcid;
+        begin_tag(&mut content, pdf_tags::LINK, &mut next_mcid);
+        link.write(file, &mut content, &placement, options.tagged.then(|| StructureTree::annotation_key(index)))?;
+        end_tag(&mut content, next_mcid);
+        tagged_link = mcid.map(|mcid| (mcid, link.annotation_ref));
+    }
+    let first_line = next_mcid.unwrap_or_default();
+    if let Some(ocr) = source.ocr {
+        let ocr = if options.formulas { formulas::strip(ocr) } else { Cow::Borrowed(ocr) };
+        draw_text_layer(&mut content, font, &ocr, &placement, &mut next_mcid);
+    }
+    let tagged_page = TaggedPage {
+        page_ref,
+        alt: source.display_title(index),
+        lines: first_line..next_mcid.unwrap_or_default(),
+        link: tagged_link,
+    };
+    let mut chunk = Chunk::new();
+    chunk.stream(content_ref, &content.finish());
+    file.write_object(&chunk)?;
+    for url_link in &url_links {
+        url_link.write(file, &placement)?;
+    }
+
+    // 4. Write the image XObjects
+    let embedded = layers.as_ref().map_or(embedded, |layers| &layers.background);
+    let colour = match layers {
+        None if options.auto_colour => mrc::page_colour(embedded),
+        _ => PageColour::Colour,
+    };
+    let grey = (colour == PageColour::Grayscale).then(|| imageops::grayscale(embedded));
+    let (encoded_bytes, filter) = match (colour, options.image_compression) {
+        (PageColour::Bilevel, _) => (mrc::encode_bilevel(embedded), Filter::CcittFaxDecode),
+        (_, ImageCompression::Jpeg { quality } | ImageCompression::Mrc { quality }) => match &grey {
+            Some(grey) => (encode_jpeg(grey, quality)?, Filter::DctDecode),
+            None => (encode_jpeg(embedded, quality)?, Filter::DctDecode),
+        },
+        (_, ImageCompression::Flate) => {
+            let raw = grey.as_ref().map_or(embedded.as_raw(), |grey| grey.as_raw());
+            (miniz_oxide::deflate::compress_to_vec_zlib(raw, 6), Filter::FlateDecode)
+        }
+    };
+
+    let (width, height) = (embedded.width() as i32, embedded.height() as i32);
+    let mut chunk = Chunk::with_capacity(encoded_bytes.len() + 256);
+    let mut image_xobject = chunk.image_xobject(image_ref, &encoded_bytes);
+    image_xobject.filter(filter);
+    if colour == PageColour::Bilevel {
+        image_xobject.decode_parms().k(-1).columns(width).rows(height);
+    }
+    image_xobject.width(width);
+    image_xobject.height(height);
+    match colour {
+        PageColour::Colour => image_xobject.color_space().device_rgb(),
+        _ => image_xobject.color_space().device_gray(),
+    }
+    image_xobject.bits_per_component(if colour == PageColour::Bilevel { 1 } else { 8 });
+    image_xobject.finish();
+    file.write_object(&chunk)?;
+
+    if let Some((mask_ref, layers)) = mask_ref.zip(layers) {
+        let mut chunk = Chunk::with_capacity(layers.mask.len() + 256);
+        let mut mask = chunk.image_xobject(mask_ref, &layers.mask);
+        mask.filter(Filter::CcittFaxDecode);
+        mask.decode_parms().k(-1).columns(layers.width as i32).rows(layers.height as i32);
+        mask.width(layers.width as i32);
+        mask.height(layers.height as i32);
+        mask.image_mask(true);
+        mask.bits_per_component(1);
+        mask.finish();
+        file.write_object(&chunk)?;
+    }
+    Ok(tagged_page)
+}
+
+/// Opens a marked-content sequence for a structure element when `mcid` holds the
+/// next marked-content id (tagged output), and advances it.
+fn begin_tag(content: &mut Content, tag: Name, mcid: &mut Option<i32>) {
+    if let Some(id) = mcid {
+        content.begin_marked_content_with_properties(tag).properties().identify(*id);
+        *id += 1;
+    }
+}
+
+/// Closes the sequence opened by [`begin_tag`].
+fn end_tag(content: &mut Content, mcid: Option<i32>) {
+    if mcid.is_some() {
+        content.end_marked_content();
+    }
+}
+
+/// A clickable timestamp label in the bottom-left corner of a page image that opens
+/// the source video at the moment the page appeared.
+struct SourceLink {
+    url: String,
+    /// Text alternative of the link, for assistive technology.
+    description: String,
+    label: ImageBuffer<Rgb<u8>, Vec<u8>>,
+    image_ref: Ref,
+    annotation_ref: Ref,
+}
+
+impl SourceLink {
+    /// Draws the label and writes its image and link annotation.
+    fn write(
+        &self,
+        file: &mut PdfFile,
+        content: &mut Content,
+        placement: &Placement,
+        struct_parent: Option<i32>,
+    ) -> Result<()> {
+        // Same inset as `overlay::draw_label`, in image pixels.
+        let inset = overlay::label_scale(placement.image_height) as f32 * 2.0;
+        let x = placement.offset_x + inset * placement.scale_factor;
+        let y = placement.offset_y + inset * placement.scale_factor;
+        let width = self.label.width() as f32 * placement.scale_factor;
+        let height = self.label.height() as f32 * placement.scale_factor;
+        draw_image(content, SOURCE_LINK_IMAGE, width, height, x, y);
+
+        let compressed = miniz_oxide::deflate::compress_to_vec_zlib(self.label.as_raw(), 6);
+        let mut chunk = Chunk::new();
+        let mut image = chunk.image_xobject(self.image_ref, &compressed);
+        image.filter(Filter::FlateDecode);
+        image.width(self.label.width() as i32);
+        image.height(self.label.height() as i32);
+        image.color_space().device_rgb();
+        image.bits_per_component(8);
+        image.finish();
+        file.write_object(&chunk)?;
+
+        let mut chunk = Chunk::new();
+        let mut annotation = chunk.annotation(self.annotation_ref);
+        annotation
+            .subtype(AnnotationType::Link)
+            .rect(Rect::new(x, y, x + width, y + height))
+            .border(0.0, 0.0, 0.0, None)
+            .flags(AnnotationFlags::PRINT)
+            .contents(TextStr(&self.description));
+        if let Some(key) = struct_parent {
+            annotation.struct_parent(key);
+        }
+        annotation
+            .action()
+            .action_type(ActionType::Uri)
+            .uri(Str(self.url.as_bytes()));
+        annotation.finish();
+        file.write_object(&chunk)
+    }
+}
+
+/// A clickable area over a word of the page image that spells a URL.
+struct UrlLink {
+    url: String,
+    /// Bounding box of the word, in image pixels.
+    bbox: (i32, i32, i32, i32),
+    annotation_ref: Ref,
+}
+
+impl UrlLink {
+    /// Writes the link annotation.
+    fn write(&self, file: &mut PdfFile, placement: &Placement) -> Result<()> {
+        let (x1, y1, x2, y2) = self.bbox;
+        let x = |px: i32| px as f32 * placement.scale_factor + placement.offset_x;
+        let y = |py: i32| (placement.image_height as i32 - py) as f32 * placement.scale_factor + placement.offset_y;
+        let mut chunk = Chunk::new();
+        let mut annotation = chunk.annotation(self.annotation_ref);
+        annotation
+            .subtype(AnnotationType::Link)
+            .rect(Rect::new(x(x1), y(y2), x(x2), y(y1)))
+            .border(0.0, 0.0, 0.0, None)
+            .flags(AnnotationFlags::PRINT)
+            .contents(TextStr(&self.url));
+        annotation.action().action_type(ActionType::Uri).uri(Str(self.url.as_bytes()));
+        annotation.finish();
+        file.write_object(&chunk)
+    }
+}
+
+/// Appends the position in seconds to a video URL as a `t` query parameter, the
+/// form understood by YouTube, Vimeo and most players, keeping any fragment last.
+pub fn deep_link(url: &str, seconds: f64) -> String {
+    let (base, fragment) = match url.find('#') {
+        Some(index) => url.split_at(index),
+        None => (url, ""),
+    };
+    let separator = if base.contains('?') { '&' } else { '?' };
+    format!("{}{}t={}{}", base, separator, seconds.max(0.0) as u64, fragment)
+}
+
+/// Maps image pixel coordinates onto the PDF page.
+struct Placement {
+    scale_factor: f32,
+    offset_x: f32,
+    offset_y: f32,
+    image_height: u32,
+}
+
+/// Paints the page image, scaled and centred on the page.
+fn draw_image(
+    content: &mut Content,
+    image_name: Name,
+    scaled_width: f32,
+    scaled_height: f32,
+    offset_x: f32,
+    offset_y: f32,
+) {
     content.save_state();
     content.transform([scaled_width, 0.0, 0.0, scaled_height, offset_x, offset_y]);
     content.x_object(image_name);
     content.restore_state();
+}
 
-    if let Some(ocr) = ocr_result {
-        // Create a temporary font object just for calculating text widths.
-        content.begin_text();
-        content.set_text_rendering_mode(pdf_writer::types::TextRenderingMode::Invisible);
-        for word in &ocr.words {
-            if word.confidence < 50.0 { continue; }
+/// Writes the OCR words as invisible text positioned over the image, so the page
+/// can be searched and text selected where it appears. Each paragraph the engine
+/// found is marked as one, else each line. Words of lines that run top to bottom
+/// are set running down their box, and words of right-to-left lines are written
+/// in the order they are read.
+fn draw_text_layer(
+    content: &mut Content,
+    font: &GlyphlessFont,
+    ocr: &OcrFrameResult,
+    placement: &Placement,
+    next_mcid: &mut Option<i32>,
+) {
+    content.begin_text();
+    content.set_text_rendering_mode(pdf_writer::types::TextRenderingMode::Invisible);
+    // The lines of each paragraph, with whether they run top to bottom.
+    let paragraphs: Vec<Vec<(Vec<&OcrWord>, bool)>> = if ocr.blocks.is_empty() {
+        layout::group_regions(ocr.words.iter())
+            .into_iter()
+            .flat_map(|region| layout::group_words_into_lines(region.into_iter()))
+            .map(|line| vec![(layout::logical_order(&line), false)])
+            .collect()
+    } else {
+        ocr.blocks
+            .iter()
+            .flat_map(|block| &block.paragraphs)
+            .map(|paragraph| {
+                let words = |line: &OcrTextLine| {
+                    let words: Vec<&OcrWord> = line.words.iter().filter_map(|&i| ocr.words.get(i)).collect();
+                    if line.vertical { words } else { layout::logical_order(&words) }
+                };
+                paragraph.lines.iter().map(|line| (words(line), line.vertical)).collect()
+            })
+            .collect()
+    };
+    for paragraph in paragraphs {
+        let words: Vec<(&OcrWord, bool)> = paragraph
+            .into_iter()
+            .flat_map(|(words, vertical)| words.into_iter().map(move |word| (word, vertical || word.is_vertical())))
+            .filter(|(word, _)| !word.text.is_empty())
+            .collect();
+        if words.is_empty() {
+            continue;
+        }
+        begin_tag(content, pdf_tags::PARAGRAPH, next_mcid);
+        for (word, vertical) in words {
+            draw_word(content, font, word, vertical, placement);
+        }
+        end_tag(content, *next_mcid);
+    }
+    content.end_text();
+}
 
-            let (x1, y1, _x2, y2) = word.bbox;
-            let scaled_font_size = (y2 - y1) as f32 * scale_factor;
-            let scaled_bbox_x1 = x1 as f32 * scale_factor + offset_x;
-            let original_flipped_y = image_height as i32 - y2;
-            let scaled_pdf_y = original_flipped_y as f32 * scale_factor + offset_y;
+/// Shows one word, stretched to fill its bounding box. A `vertical` word runs
+/// down from the top of its box, turned a quarter clockwise, and a right-to-left
+/// word runs leftwards from the right of its box, so its first character is
+/// where it is read first.
+fn draw_word(content: &mut Content, font: &GlyphlessFont, word: &OcrWord, vertical: bool, placement: &Placement) {
+    let char_count = word.text.chars().count();
 
-            content.set_font(Name(b"Helvetica"), scaled_font_size);
-            content.set_text_matrix([1.0, 0.0, 0.0, 1.0, scaled_bbox_x1, scaled_pdf_y]);
+    let (x1, y1, x2, y2) = word.bbox;
+    let (across, along) = if vertical { (x2 - x1, y2 - y1) } else { (y2 - y1, x2 - x1) };
+    let font_size = (across as f32 * placement.scale_factor).max(1.0);
+    let x = x1 as f32 * placement.scale_factor + placement.offset_x;
+    let y = |image_y: i32| {
+        (placement.image_height as i32 - image_y) as f32 * placement.scale_factor + placement.offset_y
+    };
+    let box_length = along as f32 * placement.scale_factor;
+    let natural_width = char_count as f32 * font_size * pdf_font::GLYPH_ADVANCE;
 
-            content.show(Str(word.text.as_bytes()));
-        }
-        content.end_text();
+    content.set_font(TEXT_FONT, font_size);
+    content.set_horizontal_scaling(box_length / natural_width * 100.0);
+    if vertical {
+        // The baseline runs down the left edge, with the glyphs' tops to the right.
+        content.set_text_matrix([0.0, -1.0, 1.0, 0.0, x, y(y1)]);
+    } else if layout::is_rtl(&word.text) {
+        // The glyphs are invisible, so mirroring them only turns their advance.
+        let right = x2 as f32 * placement.scale_factor + placement.offset_x;
+        content.set_text_matrix([-1.0, 0.0, 0.0, 1.0, right, y(y2)]);
+    } else {
+        content.set_text_matrix([1.0, 0.0, 0.0, 1.0, x, y(y2)]);
     }
-    pdf.stream(content_ref, &content.finish());
+    // A trailing space keeps words apart when text is extracted or copied;
+    // Chinese and Japanese are written without them.
+    let separator = if word.text.ends_with(layout::is_cjk) { "" } else { " " };
+    content.show(Str(&font.encode(&format!("{}{}", word.text, separator))));
+}
 
-    // 4. Write the image XObject with JPEG compression
+/// Encodes a frame as JPEG at the given quality.
+pub(crate) fn encode_jpeg<P>(frame: &ImageBuffer<P, Vec<u8>>, quality: u8) -> Result<Vec<u8>>
+where
+    P: PixelWithColorType<Subpixel = u8>,
+{
     let mut encoded_bytes = Vec::new();
-    let mut cursor = Cursor::new(&mut encoded_bytes);
-    frame.write_to(&mut cursor, ImageOutputFormat::Jpeg(85))?;
-    
-    let mut image_xobject = pdf.image_xobject(image_ref, &encoded_bytes);
-    image_xobject.filter(Filter::DctDecode);
-    image_xobject.width(image_width as i32);
-    image_xobject.height(image_height as i32);
-    image_xobject.color_space().device_rgb();
-    image_xobject.bits_per_component(8);
-    image_xobject.finish();
+    frame.write_to(&mut Cursor::new(&mut encoded_bytes), ImageOutputFormat::Jpeg(quality))?;
+    Ok(encoded_bytes)
+}
+
+/// Path of the CSV file written for table `table` of page `index` by the Markdown
+/// output, relative to the document and using forward slashes.
+pub fn table_csv_path(index: usize, table: usize) -> String {
+    format!("{}/page_{:03}_table_{}.csv", TABLES_DIR, index + 1, table + 1)
+}
+
+/// Path of the image written for page `index` by the Markdown and linked-image
+/// HTML outputs, relative to the document and using forward slashes.
+pub fn page_image_path(index: usize) -> String {
+    format!("images/page_{:03}.png", index + 1)
+}
+
+/// Saves every page image at its `page_image_path` under `base_dir` and returns
+/// those paths.
+fn save_page_images(pages: &[Page], base_dir: &Path) -> Result<Vec<String>> {
+    fs::create_dir_all(base_dir.join("images")).context("Failed to create images directory")?;
+
+    let image_paths: Vec<String> = (0..pages.len()).map(page_image_path).collect();
+    pages.par_iter().zip(&image_paths).try_for_each(|(page, path)| -> Result<()> {
+        let image_path = base_dir.join(path);
+        page.image
+            .save(&image_path)
+            .with_context(|| format!("Failed to save page image to {:?}", image_path))
+    })?;
+    Ok(image_paths)
+}
+
+/// Returns the `src` of every page image: base64 JPEG data URIs when `embed` is
+/// set, otherwise the relative paths of images saved under `base_dir`.
+pub(crate) fn page_image_sources(pages: &[Page], embed: bool, base_dir: &Path) -> Result<Vec<String>> {
+    use base64::Engine as _;
+
+    if !embed {
+        return save_page_images(pages, base_dir);
+    }
+    pages
+        .par_iter()
+        .map(|page| -> Result<String> {
+            let jpeg = encode_jpeg(page.image, DEFAULT_JPEG_QUALITY)?;
+            Ok(format!(
+                "data:image/jpeg;base64,{}",
+                base64::engine::general_purpose::STANDARD.encode(jpeg)
+            ))
+        })
+        .collect()
+}
+
+/// Crops the figures of page `index` and returns how a document refers to
+/// them: as PNG data URIs when `embed` is set, otherwise as the paths of crops
+/// saved at their `figure_path` under `base_dir`.
+fn figure_sources(page: &Page, index: usize, embed: bool, base_dir: &Path) -> Result<Vec<String>> {
+    let rects = figures::detect(page.image, page.ocr);
+    if !embed && !rects.is_empty() {
+        fs::create_dir_all(base_dir.join(figures::FIGURES_DIR)).context("Failed to create figures directory")?;
+    }
+    rects
+        .iter()
+        .enumerate()
+        .map(|(n, &rect)| {
+            let path = figures::figure_path(index, n);
+            crop_source(&figures::crop(page.image, rect), embed, base_dir, path)
+        })
+        .collect()
+}
+
+/// Returns where an HTML or Markdown document finds a cropped image: a data URI
+/// when embedding, else `path`, relative to `base_dir`, after saving it there.
+fn crop_source(crop: &ImageBuffer<Rgb<u8>, Vec<u8>>, embed: bool, base_dir: &Path, path: String) -> Result<String> {
+    use base64::Engine as _;
+
+    if embed {
+        let mut png = Vec::new();
+        crop.write_to(&mut std::io::Cursor::new(&mut png), ImageOutputFormat::Png).context("Failed to encode crop")?;
+        Ok(format!("data:image/png;base64,{}", base64::engine::general_purpose::STANDARD.encode(png)))
+    } else {
+        let file = base_dir.join(&path);
+        crop.save(&file).with_context(|| format!("Failed to save crop to {:?}", file))?;
+        Ok(path)
+    }
+}
+
+/// Crops formula `n` of page `index` and returns its source, like
+/// [`crop_source`], creating the formulas directory when linking.
+fn formula_source(
+    page: &Page,
+    index: usize,
+    n: usize,
+    formula: &Formula,
+    embed: bool,
+    base_dir: &Path,
+) -> Result<String> {
+    if !embed {
+        fs::create_dir_all(base_dir.join(formulas::FORMULAS_DIR)).context("Failed to create formulas directory")?;
+    }
+    crop_source(&formulas::crop(page.image, formula), embed, base_dir, formulas::formula_path(index, n))
+}
+
+/// Alternative text of a formula: its LaTeX source when read, else its OCR
+/// text on one line.
+fn formula_alt(formula: &Formula) -> String {
+    formula.latex.clone().unwrap_or_else(|| formula.text.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+/// Options for the Markdown output.
+#[derive(Debug, Clone)]
+pub struct MarkdownOptions {
+    /// Heading level (1-6) of the per-page sections. The document title, when
+    /// there is room for it, goes one level above.
+    pub heading_level: usize,
+    /// Title of the whole document, usually derived from the input file name.
+    pub document_title: String,
+    /// URL of the source video; section timestamps then link to the recording.
+    pub video_url: Option<String>,
+    /// Speech transcribed while each page was on screen, by page, quoted after
+    /// its OCR text. Empty for no transcript.
+    pub speech: Vec<String>,
+    /// End the document with an alphabetical index of significant terms,
+    /// linking to anchors placed before every section.
+    pub keyword_index: bool,
+    /// Tags of each page, by page, listed in a front matter block at the top
+    /// of the document. Empty for no front matter.
+    pub tags: Vec<Vec<String>>,
+    /// Turn the URLs in the OCR text into links.
+    pub url_links: bool,
+    /// Crop the figures of each page into a `figures` directory and show them
+    /// after its text.
+    pub figures: bool,
+    /// Crop the formulas of each page into a `formulas` directory and show
+    /// them in their place in the text.
+    pub formulas: bool,
+    /// Leave out the text a page shares with the page before it: prose lines
+    /// it already showed, and code blocks and tables it already had.
+    pub new_content_only: bool,
+    /// Translation of each page's OCR text, by page, written after it. Empty
+    /// for no translation.
+    pub translations: Vec<String>,
+    /// Which words the text shows by their confidence, and whether the unsure
+    /// ones are listed after it.
+    pub confidence: ConfidencePolicy,
+}
+
+/// Builds a Markdown document with one section per page.
+///
+/// Page images are written to an `images` directory next to the Markdown file
+/// and referenced with relative paths; the OCR text of each page follows its
+/// image, then what was said while it was shown.
+pub fn build_markdown(pages: &[Page], options: &MarkdownOptions, output_path: &Path) -> Result<WrittenFiles> {
+    let base_dir = prepare_output_dir(output_path)?;
+    let image_paths = save_page_images(pages, base_dir)?;
+    let ocr = options.confidence.apply_pages(pages);
+    let pages = &with_ocr(pages, &ocr);
+
+    let level = options.heading_level.clamp(1, 6);
+    let mut markdown = String::new();
+    if !options.tags.is_empty() {
+        markdown.push_str(&tags_front_matter(pages, &options.document_title, &options.tags));
+    }
+    if level > 1 {
+        writeln!(markdown, "{} {}\n", "#".repeat(level - 1), escape_markdown(&options.document_title))?;
+    }
+    let new_lines = if options.new_content_only { builds::new_lines(pages) } else { Vec::new() };
+    let mut previous_blocks = Vec::new();
+    for (i, page) in pages.iter().enumerate() {
+        let mut heading = escape_markdown(&page.display_title(i));
+        match (page.timestamp, &options.video_url) {
+            (Some(timestamp), Some(url)) => write!(
+                heading,
+                " ([{}](<{}>){})",
+                format_timestamp(timestamp),
+                deep_link(url, timestamp),
+                page.range_end("–")
+            )?,
+            (Some(timestamp), None) => {
+                write!(heading, " ({}{})", format_timestamp(timestamp), page.range_end("–"))?
+            }
+            (None, _) => {}
+        }
+        if options.keyword_index {
+            writeln!(markdown, "<a id=\"{}\"></a>\n", page_anchor(i))?;
+        }
+        let heading = format!("{} {}", "#".repeat(level), heading);
+        writeln!(markdown, "{}\n", rtl_markdown(&page.display_title(i), heading))?;
+        writeln!(markdown, "![Page {}]({})\n", i + 1, image_paths[i])?;
+
+        let group = if options.formulas { layout::group_blocks_with_formulas } else { layout::group_blocks };
+        let blocks = page.ocr.map(group).unwrap_or_default();
+        let body_height = prose_body_height(&blocks);
+        let (mut tables, mut formula_count) = (0, 0);
+        let escape_line = |line: &OcrLine| {
+            if options.url_links { link_markdown(&line.text) } else { escape_markdown(&line.text) }
+        };
+        for block in &blocks {
+            match block {
+                TextBlock::Prose(lines) => {
+                    let fresh: Vec<OcrLine>;
+                    let lines = match new_lines.get(i) {
+                        Some(new_lines) => {
+                            fresh = lines.iter().filter(|line| new_lines.contains(&line.text)).cloned().collect();
+                            &fresh
+                        }
+                        None => lines,
+                    };
+                    for run in heading_runs(lines, body_height) {
+                        match run {
+                            // The slide's title already heads the section.
+                            TextRun::Heading(_, line) if Some(&line.text) == page.title.as_ref() => {}
+                            TextRun::Heading(depth, line) => {
+                                let hashes = "#".repeat((level + depth).min(6));
+                                let heading = format!("{} {}", hashes, escape_line(line));
+                                writeln!(markdown, "{}\n", rtl_markdown(&line.text, heading))?
+                            }
+                            TextRun::Body(lines) => {
+                                let text: Vec<&str> = lines.iter().map(|line| line.text.as_str()).collect();
+                                let lines: Vec<String> = lines.iter().map(escape_line).collect();
+                                // Two trailing spaces keep the slide's line breaks as hard breaks.
+                                writeln!(markdown, "{}\n", rtl_markdown(&text.join(" "), lines.join("  \n")))?;
+                            }
+                        }
+                    }
+                }
+                TextBlock::Code(_) | TextBlock::Table(_) | TextBlock::Formula(_)
+                    if options.new_content_only && previous_blocks.contains(block) => {}
+                TextBlock::Code(lines) => writeln!(markdown, "{}", fenced_code(lines))?,
+                TextBlock::Table(rows) => {
+                    let csv_path = table_csv_path(i, tables);
+                    let csv_file = base_dir.join(&csv_path);
+                    fs::create_dir_all(base_dir.join(TABLES_DIR)).context("Failed to create tables directory")?;
+                    fs::write(&csv_file, csv_table(rows))
+                        .with_context(|| format!("Failed to write table to {:?}", csv_file))?;
+                    tables += 1;
+                    writeln!(markdown, "{}\n\n[CSV]({})\n", markdown_table(rows), csv_path)?;
+                }
+                TextBlock::Formula(formula) => {
+                    let source = formula_source(page, i, formula_count, formula, false, base_dir)?;
+                    formula_count += 1;
+                    writeln!(markdown, "![{}]({})\n", escape_markdown(&formula_alt(formula)), source)?;
+                    if let Some(latex) = &formula.latex {
+                        writeln!(markdown, "$$\n{}\n$$\n", latex)?;
+                    }
+                }
+            }
+        }
+        previous_blocks = blocks;
+        let unsure = page.ocr.map(|ocr| options.confidence.flagged(ocr)).unwrap_or_default();
+        if !unsure.is_empty() {
+            let words: Vec<String> = unsure.iter().map(|word| escape_markdown(&word.text)).collect();
+            writeln!(markdown, "*Low confidence:* {}\n", words.join(", "))?;
+        }
+        if let Some(translation) = options.translations.get(i).filter(|translation| !translation.trim().is_empty()) {
+            let lines: Vec<String> = translation.lines().map(escape_markdown).collect();
+            writeln!(markdown, "*Translation:*  \n{}\n", lines.join("  \n"))?;
+        }
+        if options.figures {
+            for (n, source) in figure_sources(page, i, false, base_dir)?.iter().enumerate() {
+                writeln!(markdown, "![Figure {}]({})\n", n + 1, source)?;
+            }
+        }
+        if let Some(speech) = options.speech.get(i).filter(|speech| !speech.trim().is_empty()) {
+            writeln!(markdown, "> {}\n", escape_markdown(speech.trim()))?;
+        }
+    }
+    if options.keyword_index {
+        write_markdown_index(&mut markdown, &keywords::build_keyword_index(pages), level)?;
+    }
+
+    info!("Writing Markdown to {:?}", output_path);
+    fs::write(output_path, markdown).context("Failed to write Markdown file")?;
+    Ok(WrittenFiles::new(output_path, base_dir, &image_paths))
+}
+
+/// Builds a YAML front matter block with the tags of the whole document, in
+/// order of first appearance, and the title and tags of every page.
+fn tags_front_matter(pages: &[Page], document_title: &str, tags: &[Vec<String>]) -> String {
+    let yaml_list = |tags: &[String]| {
+        let quoted: Vec<String> = tags.iter().map(|tag| yaml_string(tag)).collect();
+        format!("[{}]", quoted.join(", "))
+    };
+    let mut all_tags: Vec<String> = Vec::new();
+    for tag in tags.iter().flatten() {
+        if !all_tags.contains(tag) {
+            all_tags.push(tag.clone());
+        }
+    }
+
+    let mut yaml = String::from("---\n");
+    yaml.push_str(&format!("title: {}\n", yaml_string(document_title)));
+    yaml.push_str(&format!("tags: {}\n", yaml_list(&all_tags)));
+    yaml.push_str("pages:\n");
+    for (i, (page, page_tags)) in pages.iter().zip(tags).enumerate() {
+        yaml.push_str(&format!("  - page: {}\n", i + 1));
+        yaml.push_str(&format!("    title: {}\n", yaml_string(&page.display_title(i))));
+        yaml.push_str(&format!("    tags: {}\n", yaml_list(page_tags)));
+    }
+    yaml.push_str("---\n\n");
+    yaml
+}
+
+/// Escapes a line of OCR text like [`escape_markdown`], turning the words that
+/// spell a URL into links.
+fn link_markdown(text: &str) -> String {
+    let words: Vec<&str> = text.split(' ').collect();
+    if !words.iter().any(|word| links::normalize_url(word).is_some()) {
+        return escape_markdown(text);
+    }
+    words
+        .iter()
+        .map(|word| match links::normalize_url(word) {
+            Some(url) => format!("[{}](<{}>)", escape_markdown(word), url),
+            None => escape_markdown(word),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Height of the body text of a page, from the lines of its prose blocks.
+fn prose_body_height(blocks: &[TextBlock]) -> Option<i32> {
+    let lines: Vec<OcrLine> = blocks
+        .iter()
+        .filter_map(|block| match block {
+            TextBlock::Prose(lines) => Some(lines.iter().cloned()),
+            TextBlock::Code(_) | TextBlock::Table(_) | TextBlock::Formula(_) => None,
+        })
+        .flatten()
+        .collect();
+    layout::body_height(&lines)
+}
+
+/// A heading line of a prose block, with its level below the page heading, or
+/// a run of body lines between headings.
+enum TextRun<'a> {
+    Heading(usize, &'a OcrLine),
+    Body(&'a [OcrLine]),
+}
+
+/// Splits prose lines into headings and the body text between them.
+fn heading_runs(lines: &[OcrLine], body_height: Option<i32>) -> Vec<TextRun<'_>> {
+    let levels: Vec<Option<usize>> = lines
+        .iter()
+        .map(|line| body_height.and_then(|height| layout::heading_level(line, height)))
+        .collect();
+    let mut runs = Vec::new();
+    let mut start = 0;
+    for (i, level) in levels.iter().enumerate() {
+        if let Some(level) = level {
+            if start < i {
+                runs.push(TextRun::Body(&lines[start..i]));
+            }
+            runs.push(TextRun::Heading(*level, &lines[i]));
+            start = i + 1;
+        }
+    }
+    if start < lines.len() {
+        runs.push(TextRun::Body(&lines[start..]));
+    }
+    runs
+}
 
+/// Formats the rows of a table as a Markdown table, the first row as its header.
+fn markdown_table(rows: &[Vec<String>]) -> String {
+    let row_line = |row: &[String]| {
+        let cells: Vec<String> = row.iter().map(|cell| format!(" {} ", escape_markdown(cell))).collect();
+        format!("|{}|", cells.join("|"))
+    };
+    let Some((header, body)) = rows.split_first() else {
+        return String::new();
+    };
+    let mut lines = vec![row_line(header), format!("|{}|", vec![" --- "; header.len()].join("|"))];
+    lines.extend(body.iter().map(|row| row_line(row)));
+    lines.join("\n")
+}
+
+/// Formats the rows of a table as CSV, quoting cells as RFC 4180 requires.
+fn csv_table(rows: &[Vec<String>]) -> String {
+    let mut csv = String::new();
+    for row in rows {
+        let cells: Vec<String> = row
+            .iter()
+            .map(|cell| {
+                if cell.contains([',', '"', '\n', '\r']) {
+                    format!("\"{}\"", cell.replace('"', "\"\""))
+                } else {
+                    cell.clone()
+                }
+            })
+            .collect();
+        csv.push_str(&cells.join(","));
+        csv.push_str("\r\n");
+    }
+    csv
+}
+
+/// Wraps lines of code in a Markdown code fence, longer than any run of
+/// backticks inside it.
+fn fenced_code(lines: &[String]) -> String {
+    let longest_run = lines
+        .iter()
+        .flat_map(|line| line.split(|c| c != '`'))
+        .map(str::len)
+        .max()
+        .unwrap_or(0);
+    let fence = "`".repeat((longest_run + 1).max(3));
+    format!("{}\n{}\n{}\n", fence, lines.join("\n"), fence)
+}
+
+/// Anchor of the Markdown section of page `index`.
+fn page_anchor(index: usize) -> String {
+    format!("page-{}", index + 1)
+}
+
+/// Appends the keyword index as a section at `level`, one list item per term
+/// with links to the pages it appears on.
+fn write_markdown_index(markdown: &mut String, entries: &[KeywordEntry], level: usize) -> Result<()> {
+    if entries.is_empty() {
+        return Ok(());
+    }
+    writeln!(markdown, "{} Index\n", "#".repeat(level))?;
+    for entry in entries {
+        let links: Vec<String> =
+            entry.pages.iter().map(|&page| format!("[{}](#{})", page + 1, page_anchor(page))).collect();
+        writeln!(markdown, "- **{}**: {}", escape_markdown(&entry.term), links.join(", "))?;
+    }
+    writeln!(markdown)?;
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Wraps a block of Markdown in a right-to-left `<div>` when `text` is
+/// written right to left, with blank lines so its content is still read as
+/// Markdown.
+fn rtl_markdown(text: &str, block: String) -> String {
+    if layout::is_rtl(text) { format!("<div dir=\"rtl\">\n\n{}\n\n</div>", block) } else { block }
+}
+
+/// ` dir="rtl"` for HTML elements holding text written right to left.
+fn html_dir(text: &str) -> &'static str {
+    if layout::is_rtl(text) { " dir=\"rtl\"" } else { "" }
+}
+
+/// Escapes characters that Markdown would otherwise treat as formatting.
+pub fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for (i, c) in text.chars().enumerate() {
+        match c {
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '|' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '#' | '-' | '+' | '=' if i == 0 => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            c => escaped.push(c),
+        }
+    }
+    // "1. text" at the start of a line would become a numbered list.
+    if let Some(dot) = escaped.find(". ")
+        && dot > 0
+        && escaped[..dot].chars().all(|c| c.is_ascii_digit())
+    {
+        escaped.insert(dot, '\\');
+    }
+    escaped
+}
+
+/// Writes the OCR text of all pages, in order, as a plain-text transcript.
+///
+/// Lines that already appeared on the previous page are dropped, so slides that
+/// build up progressively or repeat a header only contribute their new text.
+/// Pages left without new text are skipped; the rest are separated by blank lines.
+pub fn build_transcript(pages: &[Page], output_path: &Path) -> Result<WrittenFiles> {
+    prepare_output_dir(output_path)?;
+    let mut transcript = String::new();
+    for new_lines in builds::new_lines(pages) {
+        if !new_lines.is_empty() {
+            if !transcript.is_empty() {
+                transcript.push('\n');
+            }
+            for line in new_lines {
+                transcript.push_str(&line);
+                transcript.push('\n');
+            }
+        }
+    }
+
+    info!("Writing transcript to {:?}", output_path);
+    fs::write(output_path, transcript).context("Failed to write transcript file")?;
+    Ok(WrittenFiles::new(output_path, Path::new(""), &[]))
+}
+
+/// Options for the JSON Lines output.
+#[derive(Debug, Clone)]
+pub struct JsonlOptions {
+    /// Give each page's `text` as only the lines that were not on the page
+    /// before it.
+    pub new_content_only: bool,
+    /// Which words `text` and `confidence` take in by their confidence, and
+    /// whether the unsure ones are listed in `low_confidence`.
+    pub confidence: ConfidencePolicy,
+}
+
+impl Default for JsonlOptions {
+    fn default() -> Self {
+        JsonlOptions {
+            new_content_only: false,
+            confidence: ConfidencePolicy { low_confidence: LowConfidence::Keep, ..ConfidencePolicy::default() },
+        }
+    }
+}
+
+/// Writes one JSON object per line and page, the shape retrieval and embedding
+/// pipelines ingest: `timestamp` and `end` in seconds, OCR `text`, `image_path`
+/// relative to the output file, and mean OCR `confidence` (`null` when unknown).
+/// The page images are saved next to it under `images/`. When unsure words are
+/// flagged, each record lists them in `low_confidence`.
+pub fn build_jsonl(pages: &[Page], options: &JsonlOptions, output_path: &Path) -> Result<WrittenFiles> {
+    let base_dir = prepare_output_dir(output_path)?;
+    let image_paths = save_page_images(pages, base_dir)?;
+    let ocr = options.confidence.apply_pages(pages);
+    let pages = &with_ocr(pages, &ocr);
+    let new_lines = if options.new_content_only { builds::new_lines(pages) } else { Vec::new() };
+    let mut jsonl = String::new();
+    for (i, (page, image_path)) in pages.iter().zip(&image_paths).enumerate() {
+        let text = match new_lines.get(i) {
+            Some(lines) => lines.join("\n"),
+            None => page.ocr.map(layout::page_text).unwrap_or_default(),
+        };
+        let mut record = serde_json::json!({
+            "timestamp": page.timestamp,
+            "end": page.end,
+            "text": text,
+            "image_path": image_path,
+            "confidence": page.mean_confidence().map(|c| (f64::from(c) * 10.0).round() / 10.0),
+        });
+        if options.confidence.low_confidence == LowConfidence::Flag {
+            let unsure = page.ocr.map(|ocr| options.confidence.flagged(ocr)).unwrap_or_default();
+            record["low_confidence"] = unsure.iter().map(|word| word.text.as_str()).collect();
+        }
+        jsonl.push_str(&record.to_string());
+        jsonl.push('\n');
+    }
+
+    info!("Writing JSON lines to {:?}", output_path);
+    fs::write(output_path, jsonl).context("Failed to write JSON lines file")?;
+    Ok(WrittenFiles::new(output_path, base_dir, &image_paths))
+}
+
+/// Options for the LaTeX output.
+#[derive(Debug, Clone)]
+pub struct LatexOptions {
+    /// Title of the whole document, usually derived from the input file name.
+    pub document_title: String,
+    pub author: Option<String>,
+    /// URL of the source video; section timestamps then link to the recording.
+    pub video_url: Option<String>,
+}
+
+/// Builds a LaTeX document with one `\section` per page.
+///
+/// Page images are written to an `images` directory next to the `.tex` file and
+/// included with `\includegraphics`; the OCR text of each page follows its image.
+pub fn build_latex(pages: &[Page], options: &LatexOptions, output_path: &Path) -> Result<WrittenFiles> {
+    let base_dir = prepare_output_dir(output_path)?;
+    let image_paths = save_page_images(pages, base_dir)?;
+
+    let mut latex = String::new();
+    writeln!(latex, "\\documentclass{{article}}")?;
+    writeln!(latex, "\\usepackage[T1]{{fontenc}}")?;
+    writeln!(latex, "\\usepackage[utf8]{{inputenc}}")?;
+    writeln!(latex, "\\usepackage{{graphicx}}")?;
+    writeln!(latex, "\\usepackage[hidelinks]{{hyperref}}")?;
+    writeln!(latex, "\\title{{{}}}", escape_latex(&options.document_title))?;
+    writeln!(latex, "\\author{{{}}}", escape_latex(options.author.as_deref().unwrap_or("")))?;
+    writeln!(latex, "\\date{{}}")?;
+    writeln!(latex, "\n\\begin{{document}}\n\\maketitle")?;
+
+    for (i, (page, image_path)) in pages.iter().zip(&image_paths).enumerate() {
+        let mut heading = escape_latex(&page.display_title(i));
+        match (page.timestamp, &options.video_url) {
+            (Some(timestamp), Some(url)) => write!(
+                heading,
+                " (\\href{{{}}}{{{}}}{})",
+                escape_latex_url(&deep_link(url, timestamp)),
+                format_timestamp(timestamp),
+                page.range_end("--")
+            )?,
+            (Some(timestamp), None) => {
+                write!(heading, " ({}{})", format_timestamp(timestamp), page.range_end("--"))?
+            }
+            (None, _) => {}
+        }
+        writeln!(latex, "\n\\section{{{}}}\n", heading)?;
+        writeln!(latex, "\\begin{{center}}")?;
+        writeln!(latex, "\\includegraphics[width=\\linewidth]{{{}}}", image_path)?;
+        writeln!(latex, "\\end{{center}}")?;
+
+        let lines: Vec<String> = page
+            .ocr
+            .map(|ocr| layout::group_lines(ocr).iter().map(|line| escape_latex(&line.text)).collect())
+            .unwrap_or_default();
+        if !lines.is_empty() {
+            writeln!(latex, "\n{}", lines.join("\\\\\n"))?;
+        }
+    }
+    writeln!(latex, "\n\\end{{document}}")?;
+
+    info!("Writing LaTeX to {:?}", output_path);
+    fs::write(output_path, latex).context("Failed to write LaTeX file")?;
+    Ok(WrittenFiles::new(output_path, base_dir, &image_paths))
+}
+
+/// Escapes the characters LaTeX treats specially in running text.
+fn escape_latex(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\textbackslash{}"),
+            '~' => escaped.push_str("\\textasciitilde{}"),
+            '^' => escaped.push_str("\\textasciicircum{}"),
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            c if c.is_control() => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Escapes a URL for `\href`, which takes most characters literally.
+fn escape_latex_url(url: &str) -> String {
+    let mut escaped = String::with_capacity(url.len());
+    for c in url.chars() {
+        if matches!(c, '\\' | '#' | '%' | '{' | '}') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Options for the HTML output.
+#[derive(Debug, Clone)]
+pub struct HtmlOptions {
+    /// Title of the whole document, usually derived from the input file name.
+    pub document_title: String,
+    /// URL of the source video; section timestamps then link to the recording.
+    pub video_url: Option<String>,
+    /// Embed the page images as base64 data URIs; otherwise they are written to an
+    /// `images` directory next to the HTML file and linked.
+    pub embed_images: bool,
+    /// Show the figures of each page, cropped, after its text; they are embedded
+    /// or linked like the page images.
+    pub figures: bool,
+    /// Show the formulas of each page, cropped, in their place in the text,
+    /// embedded or linked like the page images, and leave their words out of
+    /// the text layer.
+    pub formulas: bool,
+    /// Speech transcribed while each page was on screen, by page, shown as
+    /// speaker notes at the end of its section. Empty for no transcript.
+    pub speech: Vec<String>,
+}
+
+const HTML_STYLE: &str = "\
+body { font-family: sans-serif; margin: 0; display: flex; }
+nav { position: sticky; top: 0; height: 100vh; overflow-y: auto; width: 16rem; flex-shrink: 0; padding: 1rem; box-sizing: border-box; background: #f4f4f4; }
+nav ol { padding-left: 1.5rem; }
+nav .ts, h2 .ts { color: #666; font-weight: normal; font-size: 0.85em; }
+main { flex: 1; padding: 1rem 2rem; max-width: 72rem; }
+.frame { position: relative; container-type: inline-size; }
+.frame img { display: block; width: 100%; height: auto; }
+.text-layer span { position: absolute; color: transparent; white-space: pre; line-height: 1; }
+.text-layer span::selection { background: rgba(0, 120, 215, 0.35); }
+.ocr-text { white-space: pre-wrap; background: #fafafa; border-left: 3px solid #ccc; padding: 0.5rem 1rem; }
+.ocr-table { border-collapse: collapse; margin: 0.5rem 0; }
+.ocr-table th, .ocr-table td { border: 1px solid #ccc; padding: 0.25rem 0.5rem; text-align: left; }
+.ocr-code { background: #f4f4f4; border-left: 3px solid #999; padding: 0.5rem 1rem; overflow-x: auto; }
+.ocr-figure { margin: 0.5rem 0; }
+.ocr-figure img { max-width: 100%; height: auto; }
+.ocr-formula { margin: 0.5rem 0; text-align: center; }
+.ocr-formula img { max-width: 100%; height: auto; }
+.notes { white-space: pre-wrap; color: #444; font-style: italic; border-left: 3px solid #8ab; padding: 0.5rem 1rem; margin: 0.5rem 0; }
+";
+
+/// Builds a single HTML file with a navigation list and one section per page.
+///
+/// Each page image carries an invisible, selectable text layer positioned over
+/// the recognized words, and the OCR text is repeated as plain text below it,
+/// followed by the page's speaker notes when there is a transcript.
+pub fn build_html(pages: &[Page], options: &HtmlOptions, output_path: &Path) -> Result<WrittenFiles> {
+    use crate::xmp::escape;
+
+    let base_dir = prepare_output_dir(output_path)?;
+    let image_sources = page_image_sources(pages, options.embed_images, base_dir)?;
+
+    let title = escape(&options.document_title);
+    let mut html = String::new();
+    writeln!(html, "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">")?;
+    writeln!(html, "<meta name=\"generator\" content=\"videodocparser {}\">", env!("CARGO_PKG_VERSION"))?;
+    writeln!(html, "<title>{}</title>\n<style>\n{}</style>\n</head>\n<body>", title, HTML_STYLE)?;
+
+    writeln!(html, "<nav>\n<h1>{}</h1>\n<ol>", title)?;
+    for (i, page) in pages.iter().enumerate() {
+        write!(html, "<li><a href=\"#page-{}\">{}</a>", i + 1, escape(&page.display_title(i)))?;
+        if let Some(timestamp) = page.timestamp {
+            write!(html, " <span class=\"ts\">{}</span>", format_timestamp(timestamp))?;
+        }
+        writeln!(html, "</li>")?;
+    }
+    writeln!(html, "</ol>\n</nav>\n<main>")?;
+
+    for (i, page) in pages.iter().enumerate() {
+        writeln!(html, "<section id=\"page-{}\">", i + 1)?;
+        let title = page.display_title(i);
+        write!(html, "<h2{}>{}", html_dir(&title), escape(&title))?;
+        match (page.timestamp, &options.video_url) {
+            (Some(timestamp), Some(url)) => write!(
+                html,
+                " <span class=\"ts\">(<a href=\"{}\">{}</a>{})</span>",
+                escape(&deep_link(url, timestamp)),
+                format_timestamp(timestamp),
+                page.range_end("–")
+            )?,
+            (Some(timestamp), None) => write!(
+                html,
+                " <span class=\"ts\">({}{})</span>",
+                format_timestamp(timestamp),
+                page.range_end("–")
+            )?,
+            (None, _) => {}
+        }
+        writeln!(html, "</h2>")?;
+
+        writeln!(html, "<div class=\"frame\">")?;
+        writeln!(html, "<img src=\"{}\" alt=\"Page {}\">", image_sources[i], i + 1)?;
+        if let Some(ocr) = page.ocr {
+            let ocr = if options.formulas { formulas::strip(ocr) } else { Cow::Borrowed(ocr) };
+            writeln!(html, "<div class=\"text-layer\">")?;
+            let (width, height) = (page.image.width() as f32, page.image.height() as f32);
+            for word in &ocr.words {
+                let (x1, y1, x2, y2) = word.bbox;
+                writeln!(
+                    html,
+                    "<span{} style=\"left:{:.2}%;top:{:.2}%;width:{:.2}%;font-size:{:.2}cqw\">{}</span>",
+                    html_dir(&word.text),
+                    x1 as f32 / width * 100.0,
+                    y1 as f32 / height * 100.0,
+                    (x2 - x1) as f32 / width * 100.0,
+                    (y2 - y1) as f32 / width * 100.0,
+                    escape(&word.text)
+                )?;
+            }
+            writeln!(html, "</div>")?;
+        }
+        writeln!(html, "</div>")?;
+
+        let group = if options.formulas { layout::group_blocks_with_formulas } else { layout::group_blocks };
+        let blocks = page.ocr.map(group).unwrap_or_default();
+        let body_height = prose_body_height(&blocks);
+        let mut formula_count = 0;
+        for block in &blocks {
+            match block {
+                TextBlock::Prose(lines) => {
+                    for run in heading_runs(lines, body_height) {
+                        match run {
+                            // The slide's title already heads the section.
+                            TextRun::Heading(_, line) if Some(&line.text) == page.title.as_ref() => {}
+                            // Sections are headed by <h2>.
+                            TextRun::Heading(depth, line) => {
+                                let (dir, text) = (html_dir(&line.text), escape(&line.text));
+                                writeln!(html, "<h{level}{dir}>{text}</h{level}>", level = 2 + depth)?
+                            }
+                            TextRun::Body(lines) => {
+                                let text: Vec<&str> = lines.iter().map(|line| line.text.as_str()).collect();
+                                let text = text.join("\n");
+                                writeln!(html, "<div class=\"ocr-text\"{}>{}</div>", html_dir(&text), escape(&text))?;
+                            }
+                        }
+                    }
+                }
+                TextBlock::Code(lines) => {
+                    writeln!(html, "<pre class=\"ocr-code\"><code>{}</code></pre>", escape(&lines.join("\n")))?
+                }
+                TextBlock::Table(rows) => {
+                    writeln!(html, "<table class=\"ocr-table\"{}>", html_dir(&rows.concat().join(" ")))?;
+                    for (r, row) in rows.iter().enumerate() {
+                        let tag = if r == 0 { "th" } else { "td" };
+                        let cells: Vec<String> =
+                            row.iter().map(|cell| format!("<{tag}>{}</{tag}>", escape(cell))).collect();
+                        writeln!(html, "<tr>{}</tr>", cells.concat())?;
+                    }
+                    writeln!(html, "</table>")?;
+                }
+                TextBlock::Formula(formula) => {
+                    let source = formula_source(page, i, formula_count, formula, options.embed_images, base_dir)?;
+                    formula_count += 1;
+                    let image = format!("<img src=\"{}\" alt=\"{}\">", source, escape(&formula_alt(formula)));
+                    writeln!(html, "<figure class=\"ocr-formula\">{}</figure>", image)?;
+                }
+            }
+        }
+        if options.figures {
+            for (n, source) in figure_sources(page, i, options.embed_images, base_dir)?.iter().enumerate() {
+                let image = format!("<img src=\"{}\" alt=\"Figure {}\">", source, n + 1);
+                writeln!(html, "<figure class=\"ocr-figure\">{}</figure>", image)?;
+            }
+        }
+        if let Some(speech) = options.speech.get(i).map(|speech| speech.trim()).filter(|speech| !speech.is_empty()) {
+            let (dir, text) = (html_dir(speech), escape(speech));
+            writeln!(html, "<aside class=\"notes\" aria-label=\"Speaker notes\"{dir}>{text}</aside>")?;
+        }
+        writeln!(html, "</section>")?;
+    }
+    writeln!(html, "</main>\n</body>\n</html>")?;
+
+    info!("Writing HTML to {:?}", output_path);
+    fs::write(output_path, html).context("Failed to write HTML file")?;
+    let images: &[String] = if options.embed_images { &[] } else { &image_sources };
+    Ok(WrittenFiles::new(output_path, base_dir, images))
+}
@@ -0,0 +1,176 @@
+//! Figures Module
+//!
+//! Handles finding the non-text regions of a slide (charts, diagrams, photos)
+//! so they can be cropped out as images of their own. The frame is divided
+//! into small cells; cells that differ from the slide's background and are not
+//! covered by recognized words are joined into regions, and regions large
+//! enough to be a picture are kept.
+
+use crate::ocr::OcrFrameResult;
+use image::{ImageBuffer, Rgb};
+
+/// Folder, next to the document, that figure crops are written to.
+pub const FIGURES_DIR: &str = "figures";
+/// Side of the square cells the frame is analysed in, in pixels.
+const CELL_SIZE: u32 = 8;
+/// Summed per-channel difference from the background above which a pixel is ink.
+const BACKGROUND_TOLERANCE: u32 = 60;
+/// Share of a cell's pixels that must be ink for the cell to count.
+const MIN_INK_SHARE: f32 = 0.1;
+/// Distance, in cells, across which ink cells still belong to one region, so
+/// that the separate strokes of a chart or diagram form a single figure.
+const JOIN_CELLS: usize = 2;
+/// Smallest share of the frame's area a figure's bounding box may cover.
+const MIN_AREA_SHARE: f32 = 0.02;
+/// Smallest share of the frame's width and height a figure may span, which
+/// leaves out rules and underlines.
+const MIN_SIDE_SHARE: f32 = 0.05;
+/// Largest share of the frame's area a figure may cover; a picture filling the
+/// slide is the page image itself.
+const MAX_AREA_SHARE: f32 = 0.9;
+/// Share of a region's cells above which a region of a single colour is taken
+/// for a solid bar or box rather than a picture.
+const SOLID_FILL: f32 = 0.9;
+
+/// Where a figure is on its frame, as (left, top, width, height) in pixels.
+pub type FigureRect = (u32, u32, u32, u32);
+
+/// Path of the crop written for figure `figure` of page `index`, relative to
+/// the document and using forward slashes.
+pub fn figure_path(index: usize, figure: usize) -> String {
+    format!("{}/page_{:03}_figure_{}.png", FIGURES_DIR, index + 1, figure + 1)
+}
+
+/// Finds the figures on a frame, top to bottom and then left to right.
+///
+/// The background is the median colour of the frame's border. Words the OCR
+/// recognized, with a margin of half their height, are not ink, so text
+/// blocks do not turn into figures while labels inside a diagram stay part of
+/// its crop.
+pub fn detect(frame: &ImageBuffer<Rgb<u8>, Vec<u8>>, ocr: Option<&OcrFrameResult>) -> Vec<FigureRect> {
+    let (width, height) = frame.dimensions();
+    let (columns, rows) = (width.div_ceil(CELL_SIZE) as usize, height.div_ceil(CELL_SIZE) as usize);
+    if columns == 0 || rows == 0 {
+        return Vec::new();
+    }
+    let background = border_colour(frame);
+    let is_ink = |pixel: &Rgb<u8>| {
+        let diff: u32 = pixel.0.iter().zip(&background).map(|(&a, &b)| (a as i32 - b as i32).unsigned_abs()).sum();
+        diff > BACKGROUND_TOLERANCE
+    };
+
+    let mut ink = vec![false; columns * rows];
+    for (row, column) in (0..rows).flat_map(|row| (0..columns).map(move |column| (row, column))) {
+        let (x1, y1) = (column as u32 * CELL_SIZE, row as u32 * CELL_SIZE);
+        let (x2, y2) = ((x1 + CELL_SIZE).min(width), (y1 + CELL_SIZE).min(height));
+        let pixels = (y1..y2).flat_map(|y| (x1..x2).map(move |x| (x, y)));
+        let inked = pixels.filter(|&(x, y)| is_ink(frame.get_pixel(x, y))).count();
+        ink[row * columns + column] = inked as f32 >= ((x2 - x1) * (y2 - y1)) as f32 * MIN_INK_SHARE;
+    }
+    for word in ocr.map(|ocr| ocr.words.as_slice()).unwrap_or_default() {
+        let (x1, y1, x2, y2) = word.bbox;
+        let margin = (y2 - y1).max(0) / 2;
+        let cell = |v: i32, cells: usize| ((v.max(0) as u32 / CELL_SIZE) as usize).min(cells - 1);
+        for row in cell(y1 - margin, rows)..=cell(y2 + margin, rows) {
+            for column in cell(x1 - margin, columns)..=cell(x2 + margin, columns) {
+                ink[row * columns + column] = false;
+            }
+        }
+    }
+
+    let frame_area = (width * height) as f32;
+    let mut figures = Vec::new();
+    for (bounds, cells) in regions(&ink, columns, rows) {
+        let (c1, r1, c2, r2) = bounds;
+        let x = c1 as u32 * CELL_SIZE;
+        let y = r1 as u32 * CELL_SIZE;
+        let w = ((c2 + 1) as u32 * CELL_SIZE).min(width) - x;
+        let h = ((r2 + 1) as u32 * CELL_SIZE).min(height) - y;
+        let area = (w * h) as f32;
+        if area < frame_area * MIN_AREA_SHARE
+            || area > frame_area * MAX_AREA_SHARE
+            || (w as f32) < width as f32 * MIN_SIDE_SHARE
+            || (h as f32) < height as f32 * MIN_SIDE_SHARE
+        {
+            continue;
+        }
+        let fill = cells as f32 / ((c2 - c1 + 1) * (r2 - r1 + 1)) as f32;
+        if fill >= SOLID_FILL && is_single_colour(frame, (x, y, w, h)) {
+            continue;
+        }
+        figures.push((x, y, w, h));
+    }
+    figures.sort_by_key(|&(x, y, _, _)| (y, x));
+    figures
+}
+
+/// Crops a figure out of its frame.
+pub fn crop(frame: &ImageBuffer<Rgb<u8>, Vec<u8>>, (x, y, w, h): FigureRect) -> ImageBuffer<Rgb<u8>, Vec<u8>> {
+    image::imageops::crop_imm(frame, x, y, w, h).to_image()
+}
+
+/// Median colour of the pixels along the frame's edges.
+pub fn border_colour(frame: &ImageBuffer<Rgb<u8>, Vec<u8>>) -> [u8; 3] {
+    let (width, height) = frame.dimensions();
+    let mut border: Vec<[u8; 3]> = Vec::new();
+    for x in 0..width {
+        border.push(frame.get_pixel(x, 0).0);
+        border.push(frame.get_pixel(x, height - 1).0);
+    }
+    for y in 0..height {
+        border.push(frame.get_pixel(0, y).0);
+        border.push(frame.get_pixel(width - 1, y).0);
+    }
+    [0, 1, 2].map(|channel| {
+        let mut values: Vec<u8> = border.iter().map(|pixel| pixel[channel]).collect();
+        values.sort_unstable();
+        values[values.len() / 2]
+    })
+}
+
+/// Whether nearly every pixel of a rectangle has the median colour of its edges.
+fn is_single_colour(frame: &ImageBuffer<Rgb<u8>, Vec<u8>>, rect: FigureRect) -> bool {
+    let region = crop(frame, rect);
+    let colour = border_colour(&region);
+    let same = region
+        .pixels()
+        .filter(|pixel| {
+            let diff: u32 = pixel.0.iter().zip(&colour).map(|(&a, &b)| (a as i32 - b as i32).unsigned_abs()).sum();
+            diff <= BACKGROUND_TOLERANCE
+        })
+        .count();
+    same as f32 >= region.pixels().len() as f32 * SOLID_FILL
+}
+
+/// Groups the ink cells into regions of cells at most [`JOIN_CELLS`] apart, as
+/// each region's bounds in cells (first column, first row, last column, last
+/// row) and its number of ink cells.
+fn regions(ink: &[bool], columns: usize, rows: usize) -> Vec<((usize, usize, usize, usize), usize)> {
+    let mut seen = vec![false; ink.len()];
+    let mut regions = Vec::new();
+    for start in 0..ink.len() {
+        if !ink[start] || seen[start] {
+            continue;
+        }
+        seen[start] = true;
+        let mut stack = vec![start];
+        let mut bounds = (start % columns, start / columns, start % columns, start / columns);
+        let mut cells = 0;
+        while let Some(index) = stack.pop() {
+            let (column, row) = (index % columns, index / columns);
+            cells += 1;
+            bounds = (bounds.0.min(column), bounds.1.min(row), bounds.2.max(column), bounds.3.max(row));
+            for r in row.saturating_sub(JOIN_CELLS)..=(row + JOIN_CELLS).min(rows - 1) {
+                for c in column.saturating_sub(JOIN_CELLS)..=(column + JOIN_CELLS).min(columns - 1) {
+                    let next = r * columns + c;
+                    if ink[next] && !seen[next] {
+                        seen[next] = true;
+                        stack.push(next);
+                    }
+                }
+            }
+        }
+        regions.push((bounds, cells));
+    }
+    regions
+}
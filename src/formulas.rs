@@ -0,0 +1,158 @@
+//! Formulas Module
+//!
+//! Handles finding the lines of a slide that hold mathematical formulas, which
+//! OCR turns into strings of stray symbols and fragments. Such lines are told
+//! apart by how dense they are in mathematical symbols, or by the short,
+//! unsure fragments Tesseract reads on them, and are kept as cropped images
+//! instead of text: left out of the text layers and shown inline in the
+//! Markdown and HTML outputs. A formula can carry its LaTeX source, for a
+//! LaTeX-OCR model to fill in.
+
+use crate::layout::{self, TextBlock};
+use crate::ocr::{OcrFrameResult, OcrWord};
+use image::{ImageBuffer, Rgb};
+use std::borrow::Cow;
+
+/// Folder, next to the document, that formula crops are written to.
+pub const FORMULAS_DIR: &str = "formulas";
+/// Smallest share of a line's characters that must be mathematical symbols.
+const MIN_SYMBOL_SHARE: f32 = 0.15;
+/// Mean confidence below which a line of short fragments is taken for a
+/// formula Tesseract failed to read.
+const FAILED_CONFIDENCE: f32 = 60.0;
+/// Smallest share of short fragments, of at most two characters, on a line
+/// Tesseract failed to read.
+const MIN_FRAGMENT_SHARE: f32 = 0.5;
+/// Letters in a row from which a word is a word of prose or code; function
+/// names such as `sin` and `log` are shorter.
+const WORD_LETTERS: usize = 4;
+/// Largest share of a formula's words that may be words of prose or code.
+const MAX_WORDY_SHARE: f32 = 0.25;
+/// Margin around a formula's crop, in pixels, so strokes outside the word
+/// boxes, such as fraction bars and tall brackets, are kept.
+const CROP_MARGIN: i32 = 8;
+
+/// The lines of a formula on a frame.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Formula {
+    /// Bounding box covering every word of the formula: (x1, y1, x2, y2)
+    pub bbox: (i32, i32, i32, i32),
+    /// What OCR read on the formula, one line per line.
+    pub text: String,
+    /// LaTeX source of the formula, when a LaTeX-OCR model has read it.
+    pub latex: Option<String>,
+}
+
+impl Formula {
+    /// The formula made of `lines` of words.
+    pub fn from_lines(lines: &[Vec<&OcrWord>]) -> Self {
+        let words = lines.iter().flatten();
+        let bbox = words.fold((i32::MAX, i32::MAX, i32::MIN, i32::MIN), |acc, w| {
+            (acc.0.min(w.bbox.0), acc.1.min(w.bbox.1), acc.2.max(w.bbox.2), acc.3.max(w.bbox.3))
+        });
+        let text: Vec<String> =
+            lines.iter().map(|line| layout::join_words(line.iter().map(|w| w.text.as_str()))).collect();
+        Self { bbox, text: text.join("\n"), latex: None }
+    }
+
+    /// Whether the centre of a word lies inside the formula.
+    fn contains(&self, word: &OcrWord) -> bool {
+        let (x, y) = ((word.bbox.0 + word.bbox.2) / 2, (word.bbox.1 + word.bbox.3) / 2);
+        (self.bbox.0..=self.bbox.2).contains(&x) && (self.bbox.1..=self.bbox.3).contains(&y)
+    }
+}
+
+/// Whether a character is a mathematical symbol: an operator, relation, or
+/// bracket, a Greek letter, a super- or subscript, or one of Unicode's
+/// mathematical symbols and alphanumerics.
+fn is_math_symbol(c: char) -> bool {
+    matches!(c, '=' | '+' | '-' | '*' | '/' | '^' | '_' | '<' | '>' | '|' | '(' | ')' | '[' | ']' | '{' | '}')
+        || is_strong_math_symbol(c)
+}
+
+/// Whether a character is a mathematical symbol seldom seen outside formulas,
+/// unlike the hyphens of dates and the slashes of page numbers.
+fn is_strong_math_symbol(c: char) -> bool {
+    matches!(
+        c,
+        '=' | '+'
+            | '^'
+            | '<'
+            | '>'
+            | '±'
+            | '×'
+            | '÷'
+            | '·'
+            | '¹'
+            | '²'
+            | '³'
+            | '\u{0391}'..='\u{03C9}'
+            | '\u{2070}'..='\u{209F}'
+            | '\u{2190}'..='\u{21FF}'
+            | '\u{2200}'..='\u{22FF}'
+            | '\u{27C0}'..='\u{27EF}'
+            | '\u{2980}'..='\u{2AFF}'
+            | '\u{1D400}'..='\u{1D7FF}'
+    )
+}
+
+/// Whether a word has [`WORD_LETTERS`] letters in a row.
+fn is_wordy(word: &str) -> bool {
+    word.split(|c: char| !c.is_alphabetic() || is_strong_math_symbol(c)).any(|run| run.chars().count() >= WORD_LETTERS)
+}
+
+/// Whether a line of words looks like a formula: it has a symbol seldom seen
+/// outside formulas and at least two letters or digits, few words of prose or
+/// code, and either a high share of mathematical symbols or the short, unsure
+/// fragments Tesseract reads on formulas it cannot make out.
+pub fn is_formula(line: &[&OcrWord]) -> bool {
+    let chars: Vec<char> = line.iter().flat_map(|word| word.text.chars()).filter(|c| !c.is_whitespace()).collect();
+    if !chars.iter().any(|&c| is_strong_math_symbol(c)) || chars.iter().filter(|c| c.is_alphanumeric()).count() < 2 {
+        return false;
+    }
+    let share = |count: usize, of: usize| count as f32 / of.max(1) as f32;
+    if share(line.iter().filter(|word| is_wordy(&word.text)).count(), line.len()) > MAX_WORDY_SHARE {
+        return false;
+    }
+    let symbols = chars.iter().filter(|&&c| is_math_symbol(c)).count();
+    let confidence = line.iter().map(|word| word.confidence).sum::<f32>() / line.len().max(1) as f32;
+    let fragments = line.iter().filter(|word| word.text.chars().count() <= 2).count();
+    share(symbols, chars.len()) >= MIN_SYMBOL_SHARE
+        || (confidence < FAILED_CONFIDENCE && share(fragments, line.len()) >= MIN_FRAGMENT_SHARE)
+}
+
+/// Finds the formulas on a frame, in reading order; see
+/// [`layout::group_blocks_with_formulas`].
+pub fn detect(ocr: &OcrFrameResult) -> Vec<Formula> {
+    layout::group_blocks_with_formulas(ocr)
+        .into_iter()
+        .filter_map(|block| match block {
+            TextBlock::Formula(formula) => Some(formula),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Returns the OCR of a frame without the words of its formulas.
+pub fn strip(ocr: &OcrFrameResult) -> Cow<'_, OcrFrameResult> {
+    let formulas = detect(ocr);
+    if formulas.is_empty() {
+        return Cow::Borrowed(ocr);
+    }
+    Cow::Owned(ocr.retain_words(|word| !formulas.iter().any(|formula| formula.contains(word))))
+}
+
+/// Crops a formula out of its frame, with a margin of [`CROP_MARGIN`].
+pub fn crop(frame: &ImageBuffer<Rgb<u8>, Vec<u8>>, formula: &Formula) -> ImageBuffer<Rgb<u8>, Vec<u8>> {
+    let (width, height) = (frame.width() as i32, frame.height() as i32);
+    let (x1, y1, x2, y2) = formula.bbox;
+    let (x1, y1) = ((x1 - CROP_MARGIN).clamp(0, width), (y1 - CROP_MARGIN).clamp(0, height));
+    let (x2, y2) = ((x2 + CROP_MARGIN).clamp(x1, width), (y2 + CROP_MARGIN).clamp(y1, height));
+    image::imageops::crop_imm(frame, x1 as u32, y1 as u32, (x2 - x1) as u32, (y2 - y1) as u32).to_image()
+}
+
+/// Path of the crop written for formula `formula` of page `index`, relative
+/// to the document and using forward slashes.
+pub fn formula_path(index: usize, formula: usize) -> String {
+    format!("{}/page_{:03}_formula_{}.png", FORMULAS_DIR, index + 1, formula + 1)
+}
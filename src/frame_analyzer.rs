@@ -1,97 +1,745 @@
+use crate::audio_hints::AudioHints;
+use crate::bk_tree::BkTree;
+use crate::crop::CropRect;
+use crate::mask::{self, IgnoreMask};
+use crate::quality;
+use crate::video_processor::FrameMeta;
+use image::imageops::FilterType;
 use image::{DynamicImage, ImageBuffer, Rgb};
-use imagehash::{PerceptualHash, Hash};
+use imagehash::{AverageHash, DifferenceHash, PerceptualHash};
 use anyhow::{anyhow, Result};
 use log::info;
+use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::time::Instant;
 
 // Controls the precision of the perceptual hash. A larger size is more
 // precise but slower.
-const HASH_SIZE: (usize, usize) = (16, 16); // 256-bit hash
+pub const HASH_SIZE: usize = 16; // 256-bit hash
+/// Names of the hashing algorithms, as given in `Config::hash_algorithm`.
+pub const HASH_ALGORITHMS: [&str; 4] = ["phash", "dhash", "ahash", "whash"];
+/// Levels of the Haar wavelet transform of the wavelet hash; frames are
+/// shrunk to `2^WAVELET_LEVELS` times the hash size first.
+const WAVELET_LEVELS: u32 = 2;
+
+/// Cache kind used for the persisted per-frame hash series.
+pub const HASH_SERIES_KIND: &str = "hash_series";
+/// Cache kind used for the persisted timing of every analyzed frame.
+pub const FRAME_META_KIND: &str = "frame_meta";
+
+/// A packed perceptual hash of a single frame, serialized as a hex string.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub struct FrameHash(Vec<u8>);
+
+impl FrameHash {
+    /// Calculates the Hamming distance between two hashes.
+    pub fn distance(&self, other: &FrameHash) -> Result<u32> {
+        if self.0.len() != other.0.len() {
+            return Err(anyhow!("Cannot compare hashes of different lengths."));
+        }
+        Ok(self
+            .0
+            .iter()
+            .zip(other.0.iter())
+            .map(|(a, b)| (a ^ b).count_ones())
+            .sum())
+    }
+}
+
+impl From<FrameHash> for String {
+    fn from(hash: FrameHash) -> String {
+        hash.0.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+}
+
+impl TryFrom<String> for FrameHash {
+    type Error = String;
+
+    fn try_from(hex: String) -> Result<Self, Self::Error> {
+        if !hex.is_ascii() || !hex.len().is_multiple_of(2) {
+            return Err(format!("Invalid hash string: {:?}", hex));
+        }
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|e| e.to_string()))
+            .collect::<Result<Vec<u8>, String>>()
+            .map(FrameHash)
+    }
+}
+
+/// Algorithm frames are hashed with to tell slides apart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashAlgorithm {
+    /// pHash: the low frequencies of the frame's discrete cosine transform,
+    /// split at their mean. Robust to noise and small shifts, and barely moved
+    /// by a line of text being added to a slide.
+    Perceptual,
+    /// dHash: whether each pixel is brighter than the one to its left. Tells
+    /// layouts apart, but large hashes see mostly plain background.
+    Difference,
+    /// aHash: whether each pixel is brighter than the frame's mean. Moved the
+    /// most by added text, and by changes in lighting.
+    Average,
+    /// wHash: the low-frequency band of a Haar wavelet transform of the frame,
+    /// split at its mean. Moved by added text almost as much as aHash.
+    Wavelet,
+}
+
+impl HashAlgorithm {
+    /// The algorithm named in [`HASH_ALGORITHMS`].
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "phash" => Some(Self::Perceptual),
+            "dhash" => Some(Self::Difference),
+            "ahash" => Some(Self::Average),
+            "whash" => Some(Self::Wavelet),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Perceptual => "phash",
+            Self::Difference => "dhash",
+            Self::Average => "ahash",
+            Self::Wavelet => "whash",
+        }
+    }
+}
+
+/// What becomes of a frame showing a slide kept earlier than the previous
+/// page, such as when the presenter flips back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RevisitPolicy {
+    /// The frame is dropped.
+    Drop,
+    /// The frame is dropped and listed among the revisits of the page that
+    /// first showed the slide.
+    Merge,
+}
+
+impl RevisitPolicy {
+    /// The policy named "drop" or "merge", as given in `Config::revisits`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "drop" => Some(Self::Drop),
+            "merge" => Some(Self::Merge),
+            _ => None,
+        }
+    }
+}
+
+/// Hashes frames with an algorithm into `size` x `size` bits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameHasher {
+    pub algorithm: HashAlgorithm,
+    pub size: usize,
+}
+
+impl Default for FrameHasher {
+    fn default() -> Self {
+        Self { algorithm: HashAlgorithm::Perceptual, size: HASH_SIZE }
+    }
+}
+
+impl FrameHasher {
+    pub fn new(algorithm: HashAlgorithm, size: usize) -> Self {
+        Self { algorithm, size }
+    }
+
+    /// Nominal number of bits of a hash, which distances are a share of.
+    /// pHash leaves out the constant term of each row of the transform, so
+    /// its hashes have `size` fewer bits.
+    pub fn bits(&self) -> u32 {
+        (self.size * self.size) as u32
+    }
+
+    /// Hashes a frame.
+    pub fn hash(&self, image: &DynamicImage) -> FrameHash {
+        let size = self.size;
+        let bits = match self.algorithm {
+            HashAlgorithm::Perceptual => {
+                PerceptualHash::new().with_image_size(size, size).with_hash_size(size, size).hash(image).bits
+            }
+            HashAlgorithm::Difference => {
+                DifferenceHash::new().with_image_size(size + 1, size).with_hash_size(size, size).hash(image).bits
+            }
+            HashAlgorithm::Average => {
+                AverageHash::new().with_image_size(size, size).with_hash_size(size, size).hash(image).bits
+            }
+            HashAlgorithm::Wavelet => wavelet_bits(image, size),
+        };
+        FrameHash(imagehash::Hash::from(bits).to_bytes())
+    }
+}
+
+/// The bits of the wavelet hash: the frame in grayscale, shrunk to
+/// `2^WAVELET_LEVELS` times `size`, is decomposed with [`WAVELET_LEVELS`]
+/// levels of the Haar wavelet, and each coefficient of the remaining
+/// low-frequency band is compared with the band's mean. The mean rather than
+/// the median, which on a slide of mostly plain background is the background
+/// itself, so compression noise would flip half the bits.
+fn wavelet_bits(image: &DynamicImage, size: usize) -> Vec<bool> {
+    let mut width = size << WAVELET_LEVELS;
+    let shrunk = image.grayscale().resize_exact(width as u32, width as u32, FilterType::Lanczos3).into_luma8();
+    let mut band: Vec<f32> = shrunk.pixels().map(|pixel| pixel.0[0] as f32 / 255.0).collect();
+    for _ in 0..WAVELET_LEVELS {
+        let half = width / 2;
+        let at = |x: usize, y: usize| band[y * width + x];
+        // The approximation coefficients of the orthonormal Haar transform.
+        band = (0..half * half)
+            .map(|i| {
+                let (x, y) = (i % half * 2, i / half * 2);
+                (at(x, y) + at(x + 1, y) + at(x, y + 1) + at(x + 1, y + 1)) / 2.0
+            })
+            .collect();
+        width = half;
+    }
+    let mean = band.iter().sum::<f32>() / band.len() as f32;
+    band.iter().map(|&coefficient| coefficient > mean).collect()
+}
+
+/// The hashing parameters a persisted hash series depends on: the hasher, and
+/// the crop region, ignore mask, and profile applied to the frames first.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HashSettings<'a> {
+    pub hasher: FrameHasher,
+    pub crop: Option<&'a CropRect>,
+    pub ignore_mask: Option<&'a IgnoreMask>,
+    pub profile: Option<&'a str>,
+}
+
+impl HashSettings<'_> {
+    /// The settings as stored with a hash series. The crop region, ignore mask,
+    /// and profile are only listed when there are any, so series cached before
+    /// they existed stay valid.
+    pub fn to_json(&self) -> serde_json::Value {
+        let mut settings = serde_json::json!({
+            "algorithm": self.hasher.algorithm.name(),
+            "hash_size": [self.hasher.size, self.hasher.size],
+        });
+        if let Some(crop) = self.crop {
+            settings["crop"] = serde_json::json!([crop.x, crop.y, crop.width, crop.height]);
+        }
+        if let Some(ignore_mask) = self.ignore_mask {
+            settings["ignore"] = ignore_mask.fingerprint();
+        }
+        if let Some(profile) = self.profile {
+            settings["profile"] = serde_json::json!(profile);
+        }
+        settings
+    }
+}
+
+/// The run of frames one page was on screen for.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Segment {
+    /// Decoding-order index of the segment's first frame, the kept frame.
+    pub start_index: usize,
+    /// Decoding-order index of the frame after its last: the next change that
+    /// started a page or went back to an earlier one, or the end of the video.
+    pub end_index: usize,
+    /// Decoding-order index of the frame the page's image was taken from.
+    pub representative_index: usize,
+    pub frame_count: usize,
+    /// When the segment starts and ends, in seconds, if the frames' timestamps
+    /// or the frame rate are known.
+    pub start: Option<f64>,
+    pub end: Option<f64>,
+}
 
 /// Holds the final results of the frame analysis.
 pub struct AnalysisResult {
+    /// The sensitivity the frames were compared with.
+    pub sensitivity: f64,
+    pub total_frames: usize,
     pub kept_frames: Vec<ImageBuffer<Rgb<u8>, Vec<u8>>>,
     pub differences: Vec<u32>,
     pub removed_indices: Vec<usize>,
+    /// The hash of every analyzed frame, in decoding order.
+    pub hash_series: Vec<FrameHash>,
+    /// The timing of every analyzed frame, in decoding order.
+    pub frame_meta: Vec<FrameMeta>,
+    /// The decoding-order index of each kept frame.
+    pub kept_indices: Vec<usize>,
+    /// The decoding-order index of the frame each kept frame's image was taken
+    /// from: the kept frame itself, the frame its content settled on, or with
+    /// best-frame selection the best of its stable segment.
+    pub source_indices: Vec<usize>,
+    /// When each kept frame appears in the video, in seconds: its presentation
+    /// timestamp, else its index over the frame rate, if either is known.
+    pub kept_timestamps: Vec<Option<f64>>,
+    /// The decoding-order indices of the frames where each kept frame's slide
+    /// was shown again, with [`RevisitPolicy::Merge`].
+    pub revisits: Vec<Vec<usize>>,
+    /// When each kept frame's slide was shown again, in seconds, if the frame
+    /// rate is known.
+    pub revisit_timestamps: Vec<Vec<f64>>,
+    /// The segment of the video each kept frame's page covers.
+    pub segments: Vec<Segment>,
+}
+
+impl AnalysisResult {
+    /// Length of the video in seconds: where the last of the segments ends, from
+    /// the frames' presentation timestamps, if known.
+    pub fn duration(&self) -> Option<f64> {
+        self.segments.iter().filter_map(|segment| segment.end).reduce(f64::max)
+    }
+
+    /// Drops the kept frames whose entry in `keep` is false, counting them as
+    /// removed.
+    pub fn retain_frames(&mut self, keep: &[bool]) {
+        fn retained<T>(items: Vec<T>, keep: &[bool]) -> Vec<T> {
+            items.into_iter().enumerate().filter(|(i, _)| keep.get(*i) != Some(&false)).map(|(_, item)| item).collect()
+        }
+        let dropped = self.kept_indices.iter().zip(keep).filter(|(_, keep)| !**keep).map(|(&index, _)| index);
+        self.removed_indices.extend(dropped);
+        self.removed_indices.sort_unstable();
+        self.kept_frames = retained(std::mem::take(&mut self.kept_frames), keep);
+        self.kept_timestamps = retained(std::mem::take(&mut self.kept_timestamps), keep);
+        self.revisits = retained(std::mem::take(&mut self.revisits), keep);
+        self.revisit_timestamps = retained(std::mem::take(&mut self.revisit_timestamps), keep);
+        self.kept_indices = retained(std::mem::take(&mut self.kept_indices), keep);
+        self.source_indices = retained(std::mem::take(&mut self.source_indices), keep);
+        self.segments = retained(std::mem::take(&mut self.segments), keep);
+    }
+
+    /// Picks up to `count - 1` additional frames, spread evenly over the stable
+    /// segment of each kept frame, for use as extra OCR samples.
+    ///
+    /// Samples come from the page's [`Segment`], from its first frame up to (but
+    /// excluding) its `end_index`, so frames of a slide shown in between, or of
+    /// this slide shown again later, are never taken. The segment's first frame
+    /// and the frame the page's image was taken from are left out.
+    pub fn segment_samples(&self, count: usize) -> Vec<Vec<usize>> {
+        self.segments
+            .iter()
+            .map(|segment| {
+                let (start, end) = (segment.start_index, segment.end_index);
+                let len = end.saturating_sub(start);
+                let mut picks: Vec<usize> = (1..count).map(|k| start + len * k / count).collect();
+                picks.dedup();
+                picks.retain(|&index| index > start && index < end && index != segment.representative_index);
+                picks
+            })
+            .collect()
+    }
+}
+
+/// A change of slide waiting for the content to settle before it starts a page.
+struct Pending {
+    /// Decoding-order index and hash of the frame the change starts at.
+    index: usize,
+    hash: FrameHash,
+    /// Frames the content has stayed unchanged for, the first included.
+    frames: usize,
+    /// Decoding-order index of the latest of those frames.
+    last: usize,
+    /// Index and score of the frame the page's image is taken from: the
+    /// latest, or with best-frame selection the best by [`quality::score`].
+    best: (usize, Option<f32>),
+}
+
+/// What becomes of the image of an observed frame.
+enum Outcome {
+    Drop,
+    /// It is the best image of the current page so far, replacing its image.
+    Page,
+    /// It is the image of the pending change for now.
+    Pending,
 }
 
 /// A stateful analyzer that processes frames one at a time to keep memory usage low.
 pub struct FrameAnalyzer {
     sensitivity: f64,
     output_dir: PathBuf,
+    frame_rate: Option<f64>,
+    audio_hints: Option<AudioHints>,
+    ignore_mask: Option<IgnoreMask>,
     start_time: Instant,
     frame_index: usize,
-    hasher: PerceptualHash,
-    max_distance: u32,
-    last_hash: Option<Hash>,
+    hasher: FrameHasher,
+    revisit_policy: Option<RevisitPolicy>,
+    last_hash: Option<FrameHash>,
+    /// The hash of every kept frame, with its position among them.
+    kept_hashes: BkTree<usize>,
+    hash_series: Vec<FrameHash>,
+    frame_meta: Vec<FrameMeta>,
+    /// The [`quality::score`] of every analyzed frame, with best-frame selection.
+    quality_series: Vec<f32>,
+    best_frame: bool,
+    /// The score of the best frame of the current stable segment, if one is open.
+    segment_best: Option<f32>,
+    kept_indices: Vec<usize>,
+    source_indices: Vec<usize>,
+    revisits: Vec<Vec<usize>>,
+    /// Decoding-order index of every change that started a page or went back
+    /// to an earlier one, where the segment before it ends.
+    changes: Vec<usize>,
+    /// Frames the content must stay unchanged for before a change starts a page.
+    settle_frames: usize,
+    /// Frames content must be on screen for to start a page at all.
+    min_frames: usize,
+    pending: Option<Pending>,
+    pending_image: Option<ImageBuffer<Rgb<u8>, Vec<u8>>>,
     kept_frames: Vec<ImageBuffer<Rgb<u8>, Vec<u8>>>,
     differences: Vec<u32>,
-    removed_indices: Vec<usize>,
 }
 
 impl FrameAnalyzer {
-    /// Creates a new, initialized FrameAnalyzer.
+    /// Creates a new, initialized FrameAnalyzer, hashing frames with the
+    /// default [`FrameHasher`].
     pub fn new(sensitivity: f64, output_dir: &Path) -> Result<Self> {
-        let hasher = PerceptualHash::new()
-            .with_image_size(HASH_SIZE.0, HASH_SIZE.1)
-            .with_hash_size(HASH_SIZE.0, HASH_SIZE.1);
-        let max_distance = (HASH_SIZE.0 * HASH_SIZE.1) as u32;
-
         Ok(FrameAnalyzer {
             sensitivity,
             output_dir: output_dir.to_path_buf(),
+            frame_rate: None,
+            audio_hints: None,
+            ignore_mask: None,
             start_time: Instant::now(),
             frame_index: 0,
-            hasher,
-            max_distance,
+            hasher: FrameHasher::default(),
+            revisit_policy: None,
             last_hash: None,
+            kept_hashes: BkTree::new(),
+            hash_series: Vec::new(),
+            frame_meta: Vec::new(),
+            quality_series: Vec::new(),
+            best_frame: false,
+            segment_best: None,
+            kept_indices: Vec::new(),
+            source_indices: Vec::new(),
+            revisits: Vec::new(),
+            changes: Vec::new(),
+            settle_frames: 1,
+            min_frames: 1,
+            pending: None,
+            pending_image: None,
             kept_frames: Vec::new(),
             differences: Vec::new(),
-            removed_indices: Vec::new(),
         })
     }
 
-    /// Processes a single frame, comparing it to the previous one.
+    /// Gives the timing of the frames of a hash series about to be replayed,
+    /// as [`AnalysisResult::frame_meta`] had it.
+    pub fn set_frame_meta(&mut self, frame_meta: Vec<FrameMeta>) {
+        self.frame_meta = frame_meta;
+    }
+
+    /// Replays the keep/drop decisions over a previously computed hash series,
+    /// without decoding or hashing any frame, with the settings given so far.
+    ///
+    /// With a series of frame scores, the best frame of each stable segment is
+    /// selected from them. The kept frames themselves must then be supplied
+    /// with [`Self::push_kept_frame`], in the order given by
+    /// [`Self::source_indices`].
+    pub fn replay(&mut self, hash_series: Vec<FrameHash>, quality_series: Option<Vec<f32>>) -> Result<()> {
+        self.best_frame = quality_series.is_some();
+        let quality_series = quality_series.unwrap_or_default();
+        for (i, hash) in hash_series.iter().enumerate() {
+            self.observe(hash.clone(), quality_series.get(i).copied())?;
+        }
+        self.flush_pending()?;
+        self.hash_series = hash_series;
+        self.quality_series = quality_series;
+        Ok(())
+    }
+
+    /// Sets the frame rate used to turn kept frame indices into timestamps.
+    pub fn set_frame_rate(&mut self, fps: f64) {
+        self.frame_rate = Some(fps);
+    }
+
+    /// Biases the keep/drop decisions of the following frames toward the pauses
+    /// of the audio.
+    pub fn set_audio_hints(&mut self, hints: AudioHints) {
+        self.audio_hints = Some(hints);
+    }
+
+    /// Hashes every following frame with `hasher`.
+    pub fn set_hasher(&mut self, hasher: FrameHasher) {
+        self.hasher = hasher;
+    }
+
+    /// Compares every following frame that differs from the previous one with
+    /// all the frames kept so far, and treats one matching any as `policy` says.
+    pub fn set_revisit_policy(&mut self, policy: RevisitPolicy) {
+        self.revisit_policy = Some(policy);
+    }
+
+    /// Keeps the best frame of each stable segment, by [`quality::score`],
+    /// instead of its first.
+    pub fn set_best_frame(&mut self, enabled: bool) {
+        self.best_frame = enabled;
+    }
+
+    /// Only starts a page once the content has stayed unchanged for `frames`
+    /// frames, taking its image from the last of them, so the frames of a fade
+    /// or an animation in progress are passed over. A change still settling
+    /// when the video ends starts a page too.
+    pub fn set_settle_frames(&mut self, frames: usize) {
+        self.settle_frames = frames.max(1);
+    }
+
+    /// Never starts a page with content on screen for fewer than `frames`
+    /// frames, such as a notification or a quick switch to another window; the
+    /// page it interrupted goes on when it is gone.
+    pub fn set_min_frames(&mut self, frames: usize) {
+        self.min_frames = frames.max(1);
+    }
+
+    /// Paints the ignored regions over every following frame before it is hashed.
+    pub fn set_ignore_mask(&mut self, ignore_mask: IgnoreMask) {
+        self.ignore_mask = Some(ignore_mask);
+    }
+
+    /// The decoding-order indices of the frames that were kept so far.
+    pub fn kept_indices(&self) -> &[usize] {
+        &self.kept_indices
+    }
+
+    /// The decoding-order indices of the frames whose images were kept so far.
+    pub fn source_indices(&self) -> &[usize] {
+        &self.source_indices
+    }
+
+    /// The score of every frame analyzed so far, with best-frame selection.
+    pub fn quality_series(&self) -> &[f32] {
+        &self.quality_series
+    }
+
+    /// Stores the image of a frame that was already selected by the decision logic.
+    pub fn push_kept_frame(&mut self, frame: ImageBuffer<Rgb<u8>, Vec<u8>>) {
+        self.kept_frames.push(frame);
+    }
+
+    /// Processes a single frame, comparing it to the previous one. Its time is
+    /// only known from the frame rate.
     pub fn process_frame(&mut self, frame: ImageBuffer<Rgb<u8>, Vec<u8>>) -> Result<()> {
-        let dyn_img = DynamicImage::ImageRgb8(frame);
-        let hash = self.hasher.hash(&dyn_img);
+        self.process_timed_frame(frame, FrameMeta::untimed(self.frame_index))
+    }
+
+    /// Processes a frame with its timing as decoded, which the times of the
+    /// pages and their segments are taken from.
+    pub fn process_timed_frame(&mut self, frame: ImageBuffer<Rgb<u8>, Vec<u8>>, meta: FrameMeta) -> Result<()> {
+        self.frame_meta.push(meta);
+        let best_frame = self.best_frame;
+        let (hash, score, frame) = match &self.ignore_mask {
+            Some(ignore_mask) => {
+                ignore_mask.check_size(frame.width(), frame.height())?;
+                let masked = DynamicImage::ImageRgb8(ignore_mask.apply(&frame, mask::HASH_FILL));
+                (self.hasher.hash(&masked), best_frame.then(|| quality::score(&masked)), frame)
+            }
+            None => {
+                let dyn_img = DynamicImage::ImageRgb8(frame);
+                let score = best_frame.then(|| quality::score(&dyn_img));
+                (self.hasher.hash(&dyn_img), score, dyn_img.into_rgb8())
+            }
+        };
+        self.hash_series.push(hash.clone());
+        self.quality_series.extend(score);
 
+        match self.observe(hash, score)? {
+            Outcome::Page => {
+                if let Some(last) = self.kept_frames.last_mut() {
+                    *last = frame;
+                }
+            }
+            Outcome::Pending => self.pending_image = Some(frame),
+            Outcome::Drop => {}
+        }
+        self.store_confirmed_image();
+        Ok(())
+    }
+
+    /// Moves the image of a change that just started a page to the kept
+    /// frames, and lets go of it once the change is no longer pending.
+    fn store_confirmed_image(&mut self) {
+        if self.kept_frames.len() < self.kept_indices.len()
+            && let Some(image) = self.pending_image.take()
+        {
+            self.kept_frames.push(image);
+        }
+        if self.pending.is_none() {
+            self.pending_image = None;
+        }
+    }
+
+    /// The share of a hash's bits two frames must differ by for the later,
+    /// frame `index`, to show a change.
+    fn threshold(&self, index: usize) -> f64 {
+        let scale = self.audio_hints.as_ref().map_or(1.0, |hints| hints.threshold_scale(index));
+        (1.0 - self.sensitivity) * scale
+    }
+
+    /// Applies the keep/drop decision to the next hash of the series, with the
+    /// frame's score under best-frame selection, and tells what becomes of
+    /// the frame's image.
+    fn observe(&mut self, hash: FrameHash, score: Option<f32>) -> Result<Outcome> {
+        let index = self.frame_index;
+        self.frame_index += 1;
+
+        let threshold = self.threshold(index);
+        let bits = self.hasher.bits() as f64;
+        let reference = self.last_hash.as_ref().or(self.pending.as_ref().map(|pending| &pending.hash));
+        if let Some(reference) = reference {
+            self.differences.push(reference.distance(&hash)?);
+        }
         if let Some(prev) = &self.last_hash {
-            let dist = hamming_distance(prev, &hash)?;
-            self.differences.push(dist);
+            let diff_ratio = prev.distance(&hash)? as f64 / bits;
 
-            let diff_ratio = dist as f64 / self.max_distance as f64;
+            if diff_ratio < threshold {
+                // The content went back to the page before a change settled.
+                self.pending = None;
+                // An unchanged frame replaces the page's image if it is better.
+                if let (Some(score), Some(best)) = (score, self.segment_best)
+                    && score > best
+                    && let Some(source) = self.source_indices.last_mut()
+                {
+                    self.segment_best = Some(score);
+                    *source = index;
+                    return Ok(Outcome::Page);
+                }
+                return Ok(Outcome::Drop); // Drop frame
+            }
+        }
 
-            if diff_ratio < (1.0 - self.sensitivity) {
-                self.removed_indices.push(self.frame_index);
-                self.frame_index += 1;
-                return Ok(()); // Drop frame
+        // The frame shows a change: it settles the pending one further, or starts another.
+        let mut outcome = Outcome::Drop;
+        match &mut self.pending {
+            Some(pending) if (pending.hash.distance(&hash)? as f64 / bits) < threshold => {
+                pending.frames += 1;
+                pending.last = index;
+                // The image is the frame the content settled on, or the best one.
+                let better = match (pending.best.1, score) {
+                    (Some(best), Some(score)) => score > best,
+                    _ => pending.frames <= self.settle_frames,
+                };
+                if better {
+                    pending.best = (index, score);
+                    outcome = Outcome::Pending;
+                }
             }
+            _ => {
+                self.pending = Some(Pending { index, hash, frames: 1, last: index, best: (index, score) });
+                outcome = Outcome::Pending;
+            }
+        }
+        let needed = self.settle_frames.max(self.min_frames);
+        if self.pending.as_ref().is_some_and(|pending| pending.frames >= needed) {
+            self.confirm_pending()?;
         }
+        Ok(outcome)
+    }
 
-        self.kept_frames.push(dyn_img.to_rgb8());
-        self.last_hash = Some(hash);
-        self.frame_index += 1;
+    /// Ends the analysis with the change still settling, if any: it starts a
+    /// page if its content was on screen for long enough.
+    fn flush_pending(&mut self) -> Result<()> {
+        if self.pending.as_ref().is_some_and(|pending| pending.frames < self.min_frames) {
+            self.pending = None;
+        }
+        self.confirm_pending()
+    }
+
+    /// Starts a page with the pending change, at the frame it starts at but
+    /// with the image of its latest or best frame, unless it shows a slide kept
+    /// before and a revisit policy is set.
+    fn confirm_pending(&mut self) -> Result<()> {
+        let Some(pending) = self.pending.take() else {
+            return Ok(());
+        };
+        self.changes.push(pending.index);
+
+        // A revisit is as close to a kept frame as an unchanged frame is to the
+        // previous one; the frames after it are compared with it in turn.
+        let radius = (self.threshold(pending.index) * self.hasher.bits() as f64).ceil() as u32;
+        if let Some(policy) = self.revisit_policy.filter(|_| radius > 0) {
+            if let Some((&page, _)) = self.kept_hashes.nearest(&pending.hash, radius - 1)? {
+                if policy == RevisitPolicy::Merge {
+                    self.revisits[page].push(pending.index);
+                }
+                self.last_hash = Some(pending.hash);
+                self.segment_best = None;
+                return Ok(());
+            }
+            self.kept_hashes.insert(pending.hash.clone(), self.kept_indices.len())?;
+        }
+
+        self.kept_indices.push(pending.index);
+        self.source_indices.push(pending.best.0);
+        self.revisits.push(Vec::new());
+        self.segment_best = pending.best.1;
+        self.last_hash = Some(pending.hash);
         Ok(())
     }
 
+    /// When frame `index` appears in the video, in seconds: its presentation
+    /// timestamp, else its index over the frame rate. An index past the last
+    /// frame stands for the end of the video, when the last frame stops showing.
+    fn time_of(&self, index: usize) -> Option<f64> {
+        let by_rate = self.frame_rate.map(|fps| index as f64 / fps);
+        match self.frame_meta.get(index) {
+            Some(meta) => meta.pts.or(by_rate),
+            None => index
+                .checked_sub(1)
+                .and_then(|last| self.frame_meta.get(last))
+                .and_then(|last| Some(last.pts? + last.duration.or(self.frame_rate.map(|fps| 1.0 / fps))?))
+                .or(by_rate),
+        }
+    }
+
     /// Finalizes the analysis, writes reports, and returns the results.
-    pub fn finish(self) -> Result<AnalysisResult> {
+    pub fn finish(mut self) -> Result<AnalysisResult> {
         let elapsed = self.start_time.elapsed();
+        self.flush_pending()?;
+        self.store_confirmed_image();
+        // Every frame that did not start a page was dropped.
+        let mut kept = self.kept_indices.iter().peekable();
+        let removed_indices: Vec<usize> =
+            (0..self.frame_index).filter(|index| kept.next_if_eq(&index).is_none()).collect();
 
         // Save analysis log
         let stats_dir = self.output_dir.join("analysis");
         fs::create_dir_all(&stats_dir)?;
         let stats_path = stats_dir.join("frame_analysis.json");
 
+        let kept_timestamps: Vec<Option<f64>> = self.kept_indices.iter().map(|&index| self.time_of(index)).collect();
+        let revisit_timestamps: Vec<Vec<f64>> = self
+            .revisits
+            .iter()
+            .map(|indices| indices.iter().filter_map(|&index| self.time_of(index)).collect())
+            .collect();
+        let segments: Vec<Segment> = self
+            .kept_indices
+            .iter()
+            .zip(&self.source_indices)
+            .map(|(&start_index, &representative_index)| {
+                let next = self.changes.partition_point(|&index| index <= start_index);
+                let end_index = self.changes.get(next).copied().unwrap_or(self.frame_index);
+                Segment {
+                    start_index,
+                    end_index,
+                    representative_index,
+                    frame_count: end_index - start_index,
+                    start: self.time_of(start_index),
+                    end: self.time_of(end_index),
+                }
+            })
+            .collect();
+
         let report = serde_json::json!({
+            "sensitivity": self.sensitivity,
             "total_frames": self.frame_index,
-            "removed": self.removed_indices.len(),
+            "removed": removed_indices.len(),
             "kept": self.kept_frames.len(),
-            "removed_indices": self.removed_indices,
+            "removed_indices": removed_indices,
             "differences": self.differences,
+            "kept_timestamps": kept_timestamps,
+            "source_indices": self.source_indices,
+            "revisits": self.revisits,
+            "segments": segments,
         });
 
         fs::write(stats_path, serde_json::to_string_pretty(&report)?)?;
@@ -101,30 +749,24 @@ impl FrameAnalyzer {
             elapsed,
             self.frame_index,
             self.kept_frames.len(),
-            self.removed_indices.len()
+            removed_indices.len()
         );
 
         Ok(AnalysisResult {
+            sensitivity: self.sensitivity,
+            total_frames: self.frame_index,
             kept_frames: self.kept_frames,
             differences: self.differences,
-            removed_indices: self.removed_indices,
+            removed_indices,
+            hash_series: self.hash_series,
+            frame_meta: self.frame_meta,
+            kept_indices: self.kept_indices,
+            source_indices: self.source_indices,
+            kept_timestamps,
+            revisits: self.revisits,
+            revisit_timestamps,
+            segments,
         })
     }
 }
 
-
-/// Calculates the Hamming distance between two perceptual hashes.
-fn hamming_distance(a: &Hash, b: &Hash) -> Result<u32> {
-    let a_bits = &a.bits;
-    let b_bits = &b.bits;
-    if a_bits.len() != b_bits.len() {
-        return Err(anyhow!("Cannot compare hashes of different lengths."));
-    }
-
-    let distance = a_bits.iter()
-        .zip(b_bits.iter())
-        .filter(|(x, y)| x != y)
-        .count() as u32;
-
-    Ok(distance)
-}
\ No newline at end of file
@@ -0,0 +1,222 @@
+//! Handwriting Module
+//!
+//! Handles reading whiteboard and chalkboard lectures, whose handwriting
+//! Tesseract cannot read. A handwriting engine, a cloud OCR service or a
+//! handwriting recognition model run by ONNX Runtime, either reads every
+//! frame or takes over the frames the printed-text engine reads with
+//! collapsing confidence. The model reads one line at a time; lines are found
+//! as the bands of ink standing out from the board.
+
+use crate::ocr::{OcrEngine, Recognition};
+use anyhow::Result;
+use image::{DynamicImage, GrayImage};
+use log::debug;
+#[cfg(feature = "onnx")]
+use crate::ocr::OcrWord;
+#[cfg(feature = "onnx")]
+use crate::paddle;
+#[cfg(feature = "onnx")]
+use anyhow::{Context, anyhow, ensure};
+#[cfg(feature = "onnx")]
+use image::imageops::{self, FilterType};
+#[cfg(feature = "onnx")]
+use ort::session::Session;
+#[cfg(feature = "onnx")]
+use ort::value::Tensor;
+#[cfg(feature = "onnx")]
+use std::path::Path;
+#[cfg(feature = "onnx")]
+use std::sync::Mutex;
+
+/// Smallest difference in brightness from the board that counts as ink.
+const INK_CONTRAST: u8 = 48;
+/// Fewest ink pixels a row needs to be part of a line.
+const MIN_ROW_INK: usize = 2;
+/// Widest run of rows without ink inside a line, in pixels, so that the gaps
+/// between the strokes of a letter do not split it.
+const MAX_LINE_GAP: u32 = 3;
+/// Shortest a line may be, in pixels; shorter bands are specks.
+const MIN_LINE_HEIGHT: u32 = 8;
+/// Widest gap between the ink of a line, relative to its height, before the
+/// line is split in two, as writing on two halves of a board is.
+const MAX_WORD_GAP_RATIO: f32 = 3.0;
+/// Height lines are scaled to for the handwriting model.
+#[cfg(feature = "onnx")]
+const RECOGNITION_HEIGHT: u32 = 64;
+/// Widest a scaled line may be; longer lines are squeezed.
+#[cfg(feature = "onnx")]
+const RECOGNITION_MAX_WIDTH: u32 = 2048;
+
+/// Brightness of the board: the median of the image.
+fn board_level(image: &GrayImage) -> u8 {
+    let mut levels: Vec<u8> = image.pixels().map(|pixel| pixel.0[0]).collect();
+    if levels.is_empty() {
+        return u8::MAX;
+    }
+    let middle = levels.len() / 2;
+    *levels.select_nth_unstable(middle).1
+}
+
+/// Finds the lines of writing on an image, as boxes (x1, y1, x2, y2) in its
+/// pixels, the second corner excluded, from top to bottom and left to right.
+///
+/// Ink is any pixel standing out from the board by [`INK_CONTRAST`], dark on
+/// a whiteboard or light on a chalkboard. Lines are the bands of rows with
+/// ink, each split where its ink leaves a gap much wider than it is tall.
+pub fn ink_lines(image: &GrayImage) -> Vec<(i32, i32, i32, i32)> {
+    let board = board_level(image);
+    let is_ink = |x: u32, y: u32| image.get_pixel(x, y).0[0].abs_diff(board) >= INK_CONTRAST;
+    let (width, height) = image.dimensions();
+    let inked_rows: Vec<bool> =
+        (0..height).map(|y| (0..width).filter(|&x| is_ink(x, y)).count() >= MIN_ROW_INK).collect();
+
+    let mut bands: Vec<(u32, u32)> = Vec::new();
+    for y in (0..height).filter(|&y| inked_rows[y as usize]) {
+        match bands.last_mut() {
+            Some(band) if y - band.1 <= MAX_LINE_GAP => band.1 = y + 1,
+            _ => bands.push((y, y + 1)),
+        }
+    }
+
+    let mut lines = Vec::new();
+    for (top, bottom) in bands.into_iter().filter(|&(top, bottom)| bottom - top >= MIN_LINE_HEIGHT) {
+        let max_gap = ((bottom - top) as f32 * MAX_WORD_GAP_RATIO) as u32;
+        let mut segment: Option<(u32, u32)> = None;
+        for x in (0..width).filter(|&x| (top..bottom).any(|y| is_ink(x, y))) {
+            segment = match segment {
+                Some((left, right)) if x - right <= max_gap => Some((left, x + 1)),
+                Some((left, right)) => {
+                    lines.push((left as i32, top as i32, right as i32, bottom as i32));
+                    Some((x, x + 1))
+                }
+                None => Some((x, x + 1)),
+            };
+        }
+        if let Some((left, right)) = segment {
+            lines.push((left as i32, top as i32, right as i32, bottom as i32));
+        }
+    }
+    lines
+}
+
+/// Reads frames with a printed-text engine, and again with a handwriting
+/// engine when the words found have a mean confidence below a threshold, or
+/// there are none, keeping the handwriting engine's reading.
+pub struct HandwritingFallback<'a> {
+    printed: Box<dyn OcrEngine + 'a>,
+    handwriting: Box<dyn OcrEngine + 'a>,
+    below: f32,
+}
+
+impl<'a> HandwritingFallback<'a> {
+    pub fn new(printed: Box<dyn OcrEngine + 'a>, handwriting: Box<dyn OcrEngine + 'a>, below: f32) -> Self {
+        Self { printed, handwriting, below }
+    }
+
+    /// The handwriting engine's reading of `image` when `printed` collapsed.
+    fn fall_back(&self, image: &DynamicImage, printed: Recognition) -> Result<Recognition> {
+        if printed.mean_confidence().is_some_and(|confidence| confidence >= self.below) {
+            return Ok(printed);
+        }
+        let (printed, handwriting) = (self.printed.name(), self.handwriting.name());
+        debug!("{} read a frame with low confidence; reading it with {}.", printed, handwriting);
+        self.handwriting.recognize(image)
+    }
+}
+
+impl OcrEngine for HandwritingFallback<'_> {
+    fn name(&self) -> &'static str {
+        self.printed.name()
+    }
+
+    fn recognize(&self, image: &DynamicImage) -> Result<Recognition> {
+        self.fall_back(image, self.printed.recognize(image)?)
+    }
+
+    fn batch_size(&self) -> usize {
+        self.printed.batch_size()
+    }
+
+    fn recognize_batch(&self, images: &[DynamicImage]) -> Result<Vec<Recognition>> {
+        let printed = self.printed.recognize_batch(images)?;
+        images.iter().zip(printed).map(|(image, printed)| self.fall_back(image, printed)).collect()
+    }
+
+    fn alternatives(&self) -> Vec<String> {
+        self.printed.alternatives()
+    }
+
+    fn recognize_alternative(&self, image: &DynamicImage, alternative: &str) -> Result<Recognition> {
+        self.fall_back(image, self.printed.recognize_alternative(image, alternative)?)
+    }
+}
+
+/// A handwriting recognition model in ONNX format, reading one line at a
+/// time: it takes grayscale lines [`RECOGNITION_HEIGHT`] pixels tall, dark
+/// ink on white from 0 to 1, and gives the probabilities of the characters of
+/// its dictionary at each time step, decoded like PaddleOCR's.
+#[cfg(feature = "onnx")]
+pub struct HandwritingEngine {
+    recognizer: Mutex<Session>,
+    dictionary: Vec<String>,
+}
+
+#[cfg(feature = "onnx")]
+impl HandwritingEngine {
+    /// Loads the model and its dictionary, starting ONNX Runtime if needed.
+    pub fn new(model: &Path, dictionary: &Path) -> Result<Self> {
+        let recognizer = Session::builder()
+            .and_then(|mut builder| builder.commit_from_file(model))
+            .with_context(|| format!("Failed to load the ONNX model {:?}", model))?;
+        Ok(Self { recognizer: Mutex::new(recognizer), dictionary: paddle::load_dictionary(dictionary)? })
+    }
+
+    /// Reads the line of writing in `line` of an image, as its words. On a
+    /// dark board, the line is inverted first.
+    fn read_line(&self, image: &GrayImage, line: (i32, i32, i32, i32), dark_board: bool) -> Result<Vec<OcrWord>> {
+        let (x1, y1, x2, y2) = line;
+        let (line_w, line_h) = ((x2 - x1).max(1) as u32, (y2 - y1).max(1) as u32);
+        let crop = imageops::crop_imm(image, x1 as u32, y1 as u32, line_w, line_h).to_image();
+        let input_w = ((line_w as f32 * RECOGNITION_HEIGHT as f32 / line_h as f32).ceil() as u32)
+            .clamp(1, RECOGNITION_MAX_WIDTH);
+        let resized = imageops::resize(&crop, input_w, RECOGNITION_HEIGHT, FilterType::Triangle);
+        let data: Vec<f32> = resized
+            .pixels()
+            .map(|pixel| pixel.0[0] as f32 / 255.0)
+            .map(|level| if dark_board { 1.0 - level } else { level })
+            .collect();
+        let input = Tensor::from_array(([1usize, 1, RECOGNITION_HEIGHT as usize, input_w as usize], data))?;
+        let mut recognizer = self.recognizer.lock().map_err(|_| anyhow!("The handwriting model is unusable"))?;
+        let outputs = recognizer.run(ort::inputs![input])?;
+        let (shape, probabilities) = outputs[0].try_extract_tensor::<f32>()?;
+        ensure!(shape.len() == 3, "Unexpected handwriting model output of shape {:?}", &shape[..]);
+        let (steps, classes) = (shape[1].max(1) as usize, shape[2] as usize);
+
+        let chars = paddle::ctc_decode(probabilities, classes, &self.dictionary);
+        Ok(paddle::group_words(&chars, line, line_w as f32 / steps as f32))
+    }
+}
+
+#[cfg(feature = "onnx")]
+impl OcrEngine for HandwritingEngine {
+    fn name(&self) -> &'static str {
+        "handwriting"
+    }
+
+    fn recognize(&self, image: &DynamicImage) -> Result<Recognition> {
+        let image = image.to_luma8();
+        let dark_board = board_level(&image) < 128;
+        let mut recognition = Recognition::default();
+        for line in ink_lines(&image) {
+            let words = self.read_line(&image, line, dark_board)?;
+            if words.is_empty() {
+                continue;
+            }
+            let text = words.iter().map(|word| word.text.as_str()).collect::<Vec<_>>().join(" ");
+            let confidence = words.iter().map(|word| word.confidence).sum::<f32>() / words.len() as f32;
+            recognition.lines.push(OcrWord { text, bbox: line, confidence });
+            recognition.words.extend(words);
+        }
+        Ok(recognition)
+    }
+}
@@ -0,0 +1,627 @@
+//! Localization Module
+//!
+//! Provides translated strings for the user-facing parts of the CLI: argument
+//! help, progress labels, and the end-of-run summary. Log messages are kept in
+//! English so that bug reports stay readable for maintainers.
+
+use std::sync::OnceLock;
+
+static CURRENT: OnceLock<Lang> = OnceLock::new();
+
+/// Languages available for the user interface.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Lang {
+    #[default]
+    En,
+    Es,
+    Pt,
+    De,
+    Fr,
+}
+
+impl Lang {
+    /// Parses a language code such as `pt` or `pt_BR.UTF-8`.
+    pub fn from_code(code: &str) -> Option<Lang> {
+        let code = code.trim().to_ascii_lowercase();
+        match code.get(..2)? {
+            "en" => Some(Lang::En),
+            "es" => Some(Lang::Es),
+            "pt" => Some(Lang::Pt),
+            "de" => Some(Lang::De),
+            "fr" => Some(Lang::Fr),
+            _ => None,
+        }
+    }
+
+    /// Finds the language of messages from the POSIX locale variables, read
+    /// with `var`: the first of `LC_ALL`, `LC_MESSAGES`, and `LANG` that is
+    /// set and not empty decides it, so `LC_ALL=C` gives English whatever
+    /// `LANG` says.
+    pub fn from_locale(var: impl Fn(&str) -> Option<String>) -> Lang {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .into_iter()
+            .filter_map(var)
+            .find(|value| !value.is_empty())
+            .as_deref()
+            .and_then(Lang::from_code)
+            .unwrap_or_default()
+    }
+}
+
+/// Identifiers for every translatable message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Msg {
+    About,
+    AnalyzingFrames,
+    FramesProcessed,
+    AnalyzedFrames,
+    RunningOcr,
+    OcrComplete,
+    SummaryTitle,
+    SummaryFramesAnalyzed,
+    SummaryPagesKept,
+    SummaryOutputFormat,
+    SummaryOutputDir,
+    SummaryElapsed,
+    SummaryNoPages,
+    DiffUnchanged,
+    DiffChanged,
+    DiffAdded,
+    DiffRemoved,
+    DiffSkipped,
+    DownloadLanguagePacks,
+}
+
+/// Sets the interface language for the rest of the process. Only the first call has an effect.
+pub fn set_lang(lang: Lang) {
+    let _ = CURRENT.set(lang);
+}
+
+/// Returns the interface language selected with [`set_lang`], or English.
+pub fn current() -> Lang {
+    CURRENT.get().copied().unwrap_or_default()
+}
+
+/// Translates a message into the current interface language.
+pub fn tr(msg: Msg) -> &'static str {
+    translate(current(), msg)
+}
+
+/// Returns the translated help text for a CLI argument, if a translation exists.
+///
+/// English help comes from the doc comments on the argument definitions, so
+/// `None` is returned for `Lang::En` and for arguments without a translation.
+pub fn arg_help(lang: Lang, id: &str) -> Option<&'static str> {
+    let help = match (lang, id) {
+        (Lang::Es, "input") => "Ruta al archivo de vídeo de entrada (p. ej., clase.mp4)",
+        (Lang::Es, "output") => "Directorio donde guardar los archivos de salida",
+        (Lang::Es, "format") => "Formato de salida",
+        (Lang::Es, "sensitivity") => "Umbral de sensibilidad de comparación entre fotogramas (0.0 a 1.0), o \"auto\" para elegir uno para el vídeo a partir de las distancias entre sus fotogramas consecutivos",
+        (Lang::Es, "hash_algorithm") => "Algoritmo con el que se resumen los fotogramas para distinguir las diapositivas: phash resiste el ruido y los desplazamientos pero apenas nota una línea de texto añadida, dhash distingue las composiciones, ahash y whash son los que más notan el texto añadido",
+        (Lang::Es, "hash_size") => "Lado del hash de los fotogramas en bits (4 a 32)",
+        (Lang::Es, "revisits") => "Comparar cada diapositiva nueva con todas las páginas conservadas, no solo con la anterior, y descartar una diapositiva que se vuelve a mostrar o fusionarla con la página que la mostró primero, que indica cuándo se volvió a mostrar",
+        (Lang::Es, "best_frame") => "Tomar la imagen de cada página del fotograma más nítido y bien expuesto de la serie de fotogramas casi idénticos que inicia, en lugar del primero, que puede estar a mitad de una transición o movido",
+        (Lang::Es, "settle_frames") => "Empezar una página solo cuando el contenido se haya mantenido igual durante este número de fotogramas, para no conservar como páginas los fotogramas a medio dibujar de fundidos, transiciones y animaciones",
+        (Lang::Es, "settle_ms") => "Como --settle-frames, como duración en milisegundos del vídeo",
+        (Lang::Es, "min_duration") => "No hacer nunca una página de contenido que esté en pantalla menos de este tiempo, como avisos emergentes y cambios rápidos a otra ventana, p. ej. \"2s\" o \"500ms\"",
+        (Lang::Es, "audio_hints") => "Favorecer los cambios de diapositiva en las pausas y aplausos del audio y evitarlos en medio del discurso",
+        (Lang::Es, "lang") => "Idioma del OCR (p. ej., \"eng\" para inglés, \"spa\" para español); une varios con \"+\" para diapositivas mixtas, p. ej. \"eng+deu\"",
+        (Lang::Es, "tessdata_dir") => "Directorio con los paquetes de idioma .traineddata de Tesseract (por defecto: TESSDATA_PREFIX, la caché de tesseract-rs y luego los directorios tessdata del sistema)",
+        (Lang::Es, "psm") => "Modo de segmentación de página de Tesseract, 0-13 (p. ej. 11 para texto disperso de diapositivas, 4 para una sola columna, 3 para páginas completas; por defecto: el de Tesseract, 3)",
+        (Lang::Es, "oem") => "Modo del motor OCR de Tesseract: 0 heredado, 1 LSTM, 2 heredado y LSTM, 3 por defecto (0 y 2 necesitan traineddata con el motor heredado)",
+        (Lang::Es, "tess_config") => "Variable de Tesseract fijada antes del reconocimiento, como clave=valor (p. ej. preserve_interword_spaces=1); repetible",
+        (Lang::Es, "char_whitelist") => "Reconocer solo estos caracteres (p. ej. \"0123456789.,%\" para paneles numéricos)",
+        (Lang::Es, "char_blacklist") => "No reconocer nunca estos caracteres (p. ej. \"|~\" para diapositivas de código)",
+        (Lang::Es, "user_words") => "Lista de palabras, una por línea, que Tesseract prefiere al leer (p. ej. términos médicos o jurídicos)",
+        (Lang::Es, "preprocess") => "Limpia cada fotograma antes del OCR, p. ej. para grabaciones de pizarras con el móvil: pasos separados por comas, o all",
+        (Lang::Es, "profile") => "Limpia los fotogramas de un tipo de grabación al decodificarlos, antes de la detección de páginas, el OCR y los documentos: whiteboard iguala la iluminación de una pizarra blanca filmada, deja la pizarra en blanco y refuerza los trazos de rotulador; paper rellena los reflejos de páginas de papel filmadas e iguala sus sombras",
+        (Lang::Es, "rectify") => "Corrige la perspectiva de documentos y pantallas de proyector filmados en ángulo, enderezando cada uno en un rectángulo antes del OCR y en los documentos",
+        (Lang::Es, "upscale") => "Amplía cada fotograma 2 o 4 veces con un modelo de superresolución antes del OCR, para grabaciones de baja resolución de diapositivas densas: Real-ESRGAN (realesrgan-ncnn-vulkan en el PATH) o --upscale-model",
+        (Lang::Es, "upscale_model") => "Modelo de superresolución ESRGAN (ONNX) usado por --upscale en lugar de Real-ESRGAN",
+        (Lang::Es, "crop") => "Analizar y reconocer solo esta región del fotograma, como x,y,w,h en píxeles (p. ej. para excluir la cámara del ponente o los controles del reproductor), o auto para recortar las bandas negras",
+        (Lang::Es, "ignore_regions") => "Ignorar esta región, como x,y,w,h en píxeles del fotograma analizado, al detectar cambios de página y en el OCR (p. ej. un reloj o subtítulos en directo); repetible",
+        (Lang::Es, "ignore_mask") => "Máscara PNG del tamaño del fotograma analizado cuyos píxeles blancos se ignoran al detectar cambios de página y en el OCR",
+        (Lang::Es, "download_lang") => "Descarga sin preguntar los paquetes de idioma de --lang que falten en --tessdata-dir o la caché de tesseract-rs, verificando sus sumas de comprobación",
+        (Lang::Es, "tessdata_source") => "Repositorio del que se descargan los paquetes de idioma que faltan: tessdata_fast (más pequeño y rápido) o tessdata_best (más preciso)",
+        (Lang::Es, "ocr_engine") => "Motor de OCR: tesseract; paddle para ejecutar modelos de PaddleOCR con ONNX Runtime, que lee mejor el texto pequeño de los vídeos y las fuentes estilizadas; o google, azure o textract para enviar los fotogramas a un servicio de OCR en la nube",
+        (Lang::Es, "paddle_det_model") => "Modelo de detección de texto de PaddleOCR (ONNX) usado por --ocr-engine paddle",
+        (Lang::Es, "paddle_rec_model") => "Modelo de reconocimiento de texto de PaddleOCR (ONNX) usado por --ocr-engine paddle",
+        (Lang::Es, "paddle_dict") => "Diccionario de caracteres de --paddle-rec-model, un carácter por línea",
+        (Lang::Es, "paddle_devices") => "GPU CUDA, por su número, en la que --ocr-engine paddle ejecuta sus modelos; repetible para repartir los lotes de fotogramas entre varias GPU. Sin ella, los modelos se ejecutan en la CPU",
+        (Lang::Es, "cloud_endpoint") => "Punto de acceso del servicio de OCR en la nube, en lugar del predeterminado del proveedor; obligatorio para azure (la URL del recurso), también como motor de --handwriting",
+        (Lang::Es, "cloud_batch_size") => "Fotogramas enviados a Google Cloud Vision en cada solicitud (16 como máximo)",
+        (Lang::Es, "cloud_retries") => "Veces que se reintenta, con espera exponencial, una solicitud de OCR en la nube que falló, fue limitada o expiró",
+        (Lang::Es, "cloud_max_images") => "Máximo de fotogramas enviados al servicio de OCR en la nube en una ejecución, para acotar su coste; los fotogramas que pasen del límite quedan sin reconocer",
+        (Lang::Es, "handwriting") => "Motor para la escritura a mano, como en clases en pizarra blanca o de tiza: onnx para ejecutar --handwriting-model con ONNX Runtime, o google, azure o textract para enviar los fotogramas a un servicio de OCR en la nube. Lee todos los fotogramas salvo que se indique --handwriting-below",
+        (Lang::Es, "handwriting_model") => "Modelo de reconocimiento de escritura a mano (ONNX) usado por --handwriting onnx: lee líneas en escala de grises de 64 píxeles de alto, con salida CTC",
+        (Lang::Es, "handwriting_dict") => "Diccionario de caracteres de --handwriting-model, un carácter por línea",
+        (Lang::Es, "handwriting_below") => "Confianza media de las palabras (0 a 100) por debajo de la cual un fotograma leído con --ocr-engine se vuelve a leer con el motor de --handwriting, cuya lectura se conserva",
+        (Lang::Es, "index") => "Generar un archivo de índice JSON opcional con metadatos",
+        (Lang::Es, "bundle") => "Empaquetar los resultados (documentos, fotogramas, informe OCR, índice) en un único archivo con un manifiesto",
+        (Lang::Es, "contact_sheet") => "Generar una imagen en cuadrícula y una página HTML con todos los fotogramas conservados y sus marcas de tiempo, para revisar la deduplicación",
+        (Lang::Es, "chapters") => "Escribir listas de capítulos a partir de los cambios de diapositiva: chapters.txt para la descripción de YouTube y chapters.ffmetadata para incrustarlos en el vídeo",
+        (Lang::Es, "review_report") => "Escribir un informe de las palabras reconocidas por debajo del umbral de revisión, cada una junto a un recorte del fotograma",
+        (Lang::Es, "review_threshold") => "Confianza de OCR (0 a 100) por debajo de la cual las palabras aparecen en el informe de revisión",
+        (Lang::Es, "search_index") => "Crear un índice de búsqueda de texto completo sobre el texto OCR, consultado con el subcomando search",
+        (Lang::Es, "title_filenames") => "Añadir el título detectado de cada página a los nombres de las imágenes (formato img)",
+        (Lang::Es, "ocr_report") => "Formato del informe OCR por palabra en la carpeta ocr",
+        (Lang::Es, "consensus_frames") => "Aplicar OCR a este número de fotogramas de cada diapositiva estable y combinar los resultados por votación de palabras (1 lo desactiva)",
+        (Lang::Es, "skip_textless") => "Omitir el OCR en los fotogramas en los que un detector rápido basado en bordes no encuentra texto; quedan como páginas solo de imagen",
+        (Lang::Es, "ocr_threads") => "Número de fotogramas reconocidos a la vez, cada uno con su propio motor OCR (por defecto, uno por núcleo de CPU)",
+        (Lang::Es, "retry_below") => "Confianza media de las palabras (0 a 100) por debajo de la cual un fotograma se vuelve a reconocer con otros modos de segmentación, invertido y ampliado al doble, conservando la mejor lectura",
+        (Lang::Es, "min_confidence") => "Confianza de OCR (0 a 100) por debajo de la cual las palabras se consideran dudosas en las salidas pdf, md y jsonl",
+        (Lang::Es, "low_confidence") => "Qué hacer con las palabras dudosas: omitirlas, conservarlas y listarlas como dudosas (md y jsonl) o conservarlas (por defecto se omiten en pdf y se conservan en md y jsonl)",
+        (Lang::Es, "ocr_debug") => "Escribir el texto OCR de cada página y una imagen de sus cajas de palabras coloreadas por confianza en ocr/debug, para ajustar la configuración de OCR",
+        (Lang::Es, "md_heading_level") => "Nivel de encabezado (1-6) de las secciones de cada página; el título del documento va un nivel por encima (formato md)",
+        (Lang::Es, "title") => "Título del documento (por defecto, el nombre del archivo de entrada)",
+        (Lang::Es, "author") => "Autor del documento, escrito en los metadatos del PDF y en el documento LaTeX",
+        (Lang::Es, "keywords") => "Palabras clave separadas por comas, escritas en los metadatos del PDF",
+        (Lang::Es, "video_url") => "URL donde se puede ver la grabación; las páginas enlazan a ella en su marca de tiempo (formatos pdf, md, html, tex, obsidian)",
+        (Lang::Es, "pdf_profile") => "Perfil de conformidad del PDF generado (formato pdf)",
+        (Lang::Es, "pdf_page_size") => "Tamaño de las páginas del PDF: A4 con el fotograma centrado, o el tamaño propio del fotograma (formato pdf)",
+        (Lang::Es, "pdf_page_dpi") => "Resolución con la que se colocan los fotogramas en páginas de tamaño nativo (formato pdf)",
+        (Lang::Es, "pdf_image_format") => "Compresión de las imágenes en el PDF; flate no tiene pérdidas y mantiene nítidos los bordes del texto, mrc separa el texto del fondo para escaneos compactos (formato pdf)",
+        (Lang::Es, "pdf_jpeg_quality") => "Calidad JPEG de las imágenes en el PDF, de 1 a 100 (formato pdf)",
+        (Lang::Es, "pdf_max_dpi") => "Reducir las imágenes cuya resolución en la página del PDF supere estos DPI (formato pdf)",
+        (Lang::Es, "pdf_auto_colour") => "Incrustar en escala de grises las imágenes de página sin color, y las páginas de texto negro sobre blanco como imágenes CCITT G4 de 1 bit, reduciendo los documentos escaneados; no se usa con --pdf-image-format mrc (formato pdf)",
+        (Lang::Es, "pdf_toc") => "Comenzar el PDF con un índice enlazado a cada página (formato pdf)",
+        (Lang::Es, "pdf_password") => "Contraseña necesaria para abrir el PDF; lo cifra con AES-256 (formato pdf)",
+        (Lang::Es, "pdf_owner_password") => "Contraseña que da acceso completo al PDF; sin ella, los lectores solo pueden imprimir y copiar texto (formato pdf)",
+        (Lang::Es, "html_link_images") => "Guardar las imágenes en una carpeta images y enlazarlas en lugar de incrustarlas (formato html)",
+        (Lang::Es, "sqlite_link_images") => "Guardar las imágenes en una carpeta images y almacenar sus rutas en lugar de blobs PNG (formato sqlite)",
+        (Lang::Es, "iiif_base_url") => "URL desde la que se servirá la carpeta iiif, base de todos los identificadores del manifiesto (formato iiif)",
+        (Lang::Es, "keyword_index") => "Terminar el documento con un índice alfabético de los términos significativos del OCR y sus páginas (formatos pdf y md)",
+        (Lang::Es, "slide_tags") => "Etiquetar cada diapositiva con sus términos de OCR más distintivos, en index.json y en el front matter de las notas md y obsidian",
+        (Lang::Es, "links") => "Hacer clicables las URL de las diapositivas (formatos pdf y md) y listarlas con sus páginas y marcas de tiempo en links.json",
+        (Lang::Es, "figures") => "Recortar los gráficos, diagramas y fotos de cada diapositiva como imágenes propias, mostradas tras el texto de la diapositiva (formatos md y html)",
+        (Lang::Es, "formulas") => "Conservar las fórmulas de cada diapositiva como imágenes recortadas en su lugar del texto (formatos md y html), dejando su texto OCR ilegible fuera de la capa de texto (formatos pdf y html)",
+        (Lang::Es, "new_content_only") => "Escribir solo las líneas que cada diapositiva añade a la anterior, para diapositivas construidas paso a paso (formatos md y jsonl; txt siempre lo hace)",
+        (Lang::Es, "final_builds_only") => "Conservar solo el estado final de las diapositivas construidas paso a paso, descartando las páginas que muestran solo una parte",
+        (Lang::Es, "transcript") => "Transcripción SRT o WebVTT del audio (p. ej. de Whisper); el discurso se coloca después de cada diapositiva (formatos pdf, md y html)",
+        (Lang::Es, "use_subtitles") => "Usar los subtítulos propios del vídeo como transcripción: un archivo .srt o .vtt junto a él o, si no, su primera pista de subtítulos de texto (formatos pdf, md y html)",
+        (Lang::Es, "whisper_model") => "Transcribir el audio con este modelo de Whisper (archivo ggml) y colocar el discurso después de cada diapositiva (formatos pdf, md y html)",
+        (Lang::Es, "diarize") => "Etiquetar los turnos de palabra de la transcripción como \"Speaker 1:\", \"Speaker 2:\", ... (requiere un modelo de Whisper tinydiarize)",
+        (Lang::Es, "llm_url") => "API compatible con OpenAI (p. ej. https://api.openai.com/v1) que limpia el texto OCR de cada página para index.json; la clave se lee de OPENAI_API_KEY",
+        (Lang::Es, "llm_model") => "Modelo consultado por --llm-url",
+        (Lang::Es, "llm_summaries") => "Resumir también cada página con --llm-url, guardando el resumen junto a su texto en index.json",
+        (Lang::Es, "translate_to") => "Traducir el texto OCR de cada página a este idioma (p. ej. English) con --llm-url, guardándolo junto a él en index.json e imprimiéndolo tras cada diapositiva (formatos pdf y md)",
+        (Lang::Es, "spell_dictionary") => "Diccionario con el que corregir el texto OCR antes de generar los documentos: una lista de palabras, opcionalmente con frecuencias, o un archivo .dic de Hunspell; los cambios se listan en corrections.json",
+        (Lang::Es, "spell_wordlist") => "Lista de palabras del dominio (un término por línea) añadida al diccionario ortográfico, con prioridad sobre él",
+        (Lang::Es, "template") => "Plantilla Handlebars que define la estructura del documento (formatos md y html)",
+        (Lang::Es, "recap_seconds") => "Segundos que se muestra cada página en el vídeo resumen (formato video)",
+        (Lang::Es, "recap_timestamps") => "Incrustar la marca de tiempo de origen de cada página en el vídeo resumen (formato video)",
+        (Lang::Es, "recap_container") => "Contenedor del vídeo resumen (formato video)",
+        (Lang::Es, "split_by") => "Escribir un documento por cada capítulo del vídeo en lugar de uno solo (formatos pdf y md)",
+        (Lang::Es, "subtitle_text") => "Mostrar el texto OCR de cada diapositiva o solo su título en los subtítulos (formatos srt y vtt)",
+        (Lang::Es, "name_template") => "Ruta de las salidas dentro de la carpeta result, p. ej. \"{stem}/{date}/slide_{index:04}\" (formatos pdf, md, img e informe de revisión)",
+        (Lang::Es, "log_level") => "Nivel de detalle del registro",
+        (Lang::Es, "ui_lang") => "Idioma de la interfaz (ayuda, progreso y resumen)",
+
+        (Lang::Pt, "input") => "Caminho do arquivo de vídeo de entrada (ex.: aula.mp4)",
+        (Lang::Pt, "output") => "Diretório onde salvar os arquivos de saída",
+        (Lang::Pt, "format") => "Formato de saída",
+        (Lang::Pt, "sensitivity") => "Limiar de sensibilidade da comparação entre quadros (0.0 a 1.0), ou \"auto\" para escolher um para o vídeo a partir das distâncias entre os seus quadros consecutivos",
+        (Lang::Pt, "hash_algorithm") => "Algoritmo com que os quadros são resumidos para distinguir os slides: phash resiste ao ruído e a deslocamentos mas quase não nota uma linha de texto acrescentada, dhash distingue os layouts, ahash e whash são os que mais notam o texto acrescentado",
+        (Lang::Pt, "hash_size") => "Lado do hash dos quadros em bits (4 a 32)",
+        (Lang::Pt, "revisits") => "Comparar cada slide novo com todas as páginas mantidas, não só com a anterior, e descartar um slide mostrado de novo ou fundi-lo com a página que o mostrou primeiro, que indica quando foi mostrado de novo",
+        (Lang::Pt, "best_frame") => "Tirar a imagem de cada página do quadro mais nítido e bem exposto da sequência de quadros quase idênticos que ela inicia, em vez do primeiro, que pode estar no meio de uma transição ou tremido",
+        (Lang::Pt, "settle_frames") => "Só iniciar uma página quando o conteúdo tiver ficado igual durante este número de quadros, para não manter como páginas os quadros meio desenhados de fades, transições e animações",
+        (Lang::Pt, "settle_ms") => "Como --settle-frames, como duração em milissegundos do vídeo",
+        (Lang::Pt, "min_duration") => "Nunca fazer uma página de conteúdo que fique na tela menos do que este tempo, como notificações e trocas rápidas para outra janela, p. ex. \"2s\" ou \"500ms\"",
+        (Lang::Pt, "audio_hints") => "Favorecer as mudanças de slide nas pausas e aplausos do áudio e evitá-las no meio da fala",
+        (Lang::Pt, "lang") => "Idioma do OCR (ex.: \"eng\" para inglês, \"por\" para português); junte vários com \"+\" para slides mistos, ex.: \"eng+deu\"",
+        (Lang::Pt, "tessdata_dir") => "Diretório com os pacotes de idioma .traineddata do Tesseract (padrão: TESSDATA_PREFIX, o cache do tesseract-rs e depois os diretórios tessdata do sistema)",
+        (Lang::Pt, "psm") => "Modo de segmentação de página do Tesseract, 0-13 (ex.: 11 para texto esparso de slides, 4 para uma só coluna, 3 para páginas inteiras; padrão: o do Tesseract, 3)",
+        (Lang::Pt, "oem") => "Modo do motor OCR do Tesseract: 0 legado, 1 LSTM, 2 legado e LSTM, 3 padrão (0 e 2 precisam de traineddata com o motor legado)",
+        (Lang::Pt, "tess_config") => "Variável do Tesseract definida antes do reconhecimento, como chave=valor (ex.: preserve_interword_spaces=1); repetível",
+        (Lang::Pt, "char_whitelist") => "Reconhecer apenas estes caracteres (ex.: \"0123456789.,%\" para painéis numéricos)",
+        (Lang::Pt, "char_blacklist") => "Nunca reconhecer estes caracteres (ex.: \"|~\" para slides de código)",
+        (Lang::Pt, "user_words") => "Lista de palavras, uma por linha, que o Tesseract prefere ao ler (ex.: termos médicos ou jurídicos)",
+        (Lang::Pt, "preprocess") => "Limpa cada quadro antes do OCR, ex.: para gravações de quadros brancos com o celular: passos separados por vírgulas, ou all",
+        (Lang::Pt, "profile") => "Limpa os quadros de um tipo de gravação ao decodificá-los, antes da detecção de páginas, do OCR e dos documentos: whiteboard iguala a iluminação de um quadro branco filmado, deixa o quadro branco e reforça os traços de caneta; paper preenche os reflexos de páginas de papel filmadas e iguala suas sombras",
+        (Lang::Pt, "rectify") => "Corrige a perspectiva de documentos e telas de projetor filmados em ângulo, endireitando cada um num retângulo antes do OCR e nos documentos",
+        (Lang::Pt, "upscale") => "Amplia cada quadro 2 ou 4 vezes com um modelo de super-resolução antes do OCR, para gravações de baixa resolução de slides densos: Real-ESRGAN (realesrgan-ncnn-vulkan no PATH) ou --upscale-model",
+        (Lang::Pt, "upscale_model") => "Modelo de super-resolução ESRGAN (ONNX) usado por --upscale no lugar do Real-ESRGAN",
+        (Lang::Pt, "crop") => "Analisar e reconhecer apenas esta região do quadro, como x,y,w,h em pixels (ex.: para excluir a webcam do apresentador ou os controles do player), ou auto para cortar as faixas pretas",
+        (Lang::Pt, "ignore_regions") => "Ignorar esta região, como x,y,w,h em pixels do quadro analisado, ao detectar mudanças de página e no OCR (ex.: um relógio ou legendas ao vivo); repetível",
+        (Lang::Pt, "ignore_mask") => "Máscara PNG do tamanho do quadro analisado cujos pixels brancos são ignorados ao detectar mudanças de página e no OCR",
+        (Lang::Pt, "download_lang") => "Baixa sem perguntar os pacotes de idioma de --lang que faltam em --tessdata-dir ou no cache do tesseract-rs, verificando suas somas de verificação",
+        (Lang::Pt, "tessdata_source") => "Repositório de onde os pacotes de idioma que faltam são baixados: tessdata_fast (menor e mais rápido) ou tessdata_best (mais preciso)",
+        (Lang::Pt, "ocr_engine") => "Motor de OCR: tesseract; paddle para executar modelos do PaddleOCR com o ONNX Runtime, que lê melhor textos pequenos de vídeo e fontes estilizadas; ou google, azure ou textract para enviar os quadros a um serviço de OCR na nuvem",
+        (Lang::Pt, "paddle_det_model") => "Modelo de detecção de texto do PaddleOCR (ONNX) usado por --ocr-engine paddle",
+        (Lang::Pt, "paddle_rec_model") => "Modelo de reconhecimento de texto do PaddleOCR (ONNX) usado por --ocr-engine paddle",
+        (Lang::Pt, "paddle_dict") => "Dicionário de caracteres de --paddle-rec-model, um caractere por linha",
+        (Lang::Pt, "paddle_devices") => "GPU CUDA, pelo seu número, em que --ocr-engine paddle executa seus modelos; repetível para distribuir os lotes de quadros entre várias GPUs. Sem ela, os modelos rodam na CPU",
+        (Lang::Pt, "cloud_endpoint") => "Endpoint do serviço de OCR na nuvem, no lugar do padrão do provedor; obrigatório para azure (a URL do recurso), também como motor de --handwriting",
+        (Lang::Pt, "cloud_batch_size") => "Quadros enviados ao Google Cloud Vision em cada requisição (no máximo 16)",
+        (Lang::Pt, "cloud_retries") => "Vezes que uma requisição de OCR na nuvem que falhou, foi limitada ou expirou é repetida, com espera exponencial",
+        (Lang::Pt, "cloud_max_images") => "Máximo de quadros enviados ao serviço de OCR na nuvem em uma execução, para limitar seu custo; os quadros além do limite ficam sem reconhecimento",
+        (Lang::Pt, "handwriting") => "Motor para escrita à mão, como em aulas em quadro branco ou quadro-negro: onnx para executar --handwriting-model com o ONNX Runtime, ou google, azure ou textract para enviar os quadros a um serviço de OCR na nuvem. Lê todos os quadros, a menos que --handwriting-below seja informado",
+        (Lang::Pt, "handwriting_model") => "Modelo de reconhecimento de escrita à mão (ONNX) usado por --handwriting onnx: lê linhas em tons de cinza com 64 pixels de altura, com saída CTC",
+        (Lang::Pt, "handwriting_dict") => "Dicionário de caracteres de --handwriting-model, um caractere por linha",
+        (Lang::Pt, "handwriting_below") => "Confiança média das palavras (0 a 100) abaixo da qual um quadro lido com --ocr-engine é lido novamente com o motor de --handwriting, cuja leitura é mantida",
+        (Lang::Pt, "index") => "Gerar um arquivo de índice JSON opcional com metadados",
+        (Lang::Pt, "bundle") => "Empacotar os resultados (documentos, quadros, relatório OCR, índice) em um único arquivo com um manifesto",
+        (Lang::Pt, "contact_sheet") => "Gerar uma imagem em grade e uma página HTML com todos os quadros mantidos e seus carimbos de tempo, para revisar a deduplicação",
+        (Lang::Pt, "chapters") => "Escrever listas de capítulos a partir das mudanças de slide: chapters.txt para a descrição do YouTube e chapters.ffmetadata para incorporá-los ao vídeo",
+        (Lang::Pt, "review_report") => "Gravar um relatório das palavras reconhecidas abaixo do limite de revisão, cada uma ao lado de um recorte do quadro",
+        (Lang::Pt, "review_threshold") => "Confiança de OCR (0 a 100) abaixo da qual as palavras entram no relatório de revisão",
+        (Lang::Pt, "search_index") => "Criar um índice de busca de texto completo sobre o texto OCR, consultado com o subcomando search",
+        (Lang::Pt, "title_filenames") => "Acrescentar o título detectado de cada página aos nomes das imagens (formato img)",
+        (Lang::Pt, "ocr_report") => "Formato do relatório OCR por palavra na pasta ocr",
+        (Lang::Pt, "consensus_frames") => "Aplicar OCR a este número de quadros de cada slide estável e combinar os resultados por votação de palavras (1 desativa)",
+        (Lang::Pt, "skip_textless") => "Pular o OCR nos quadros em que um detector rápido baseado em bordas não encontra texto; eles viram páginas só de imagem",
+        (Lang::Pt, "ocr_threads") => "Número de quadros reconhecidos ao mesmo tempo, cada um com seu próprio motor de OCR (padrão: um por núcleo de CPU)",
+        (Lang::Pt, "retry_below") => "Confiança média das palavras (0 a 100) abaixo da qual um quadro é reconhecido de novo com outros modos de segmentação, invertido e ampliado 2x, mantendo a melhor leitura",
+        (Lang::Pt, "min_confidence") => "Confiança de OCR (0 a 100) abaixo da qual as palavras são consideradas duvidosas nas saídas pdf, md e jsonl",
+        (Lang::Pt, "low_confidence") => "O que fazer com as palavras duvidosas: omiti-las, mantê-las e listá-las como duvidosas (md e jsonl) ou mantê-las (por padrão são omitidas no pdf e mantidas no md e jsonl)",
+        (Lang::Pt, "ocr_debug") => "Gravar o texto OCR de cada página e uma imagem das suas caixas de palavras coloridas por confiança em ocr/debug, para ajustar as configurações de OCR",
+        (Lang::Pt, "md_heading_level") => "Nível de título (1-6) das seções de cada página; o título do documento fica um nível acima (formato md)",
+        (Lang::Pt, "title") => "Título do documento (por padrão, o nome do arquivo de entrada)",
+        (Lang::Pt, "author") => "Autor do documento, gravado nos metadados do PDF e no documento LaTeX",
+        (Lang::Pt, "keywords") => "Palavras-chave separadas por vírgulas, gravadas nos metadados do PDF",
+        (Lang::Pt, "video_url") => "URL onde a gravação pode ser assistida; as páginas apontam para ela no seu instante (formatos pdf, md, html, tex, obsidian)",
+        (Lang::Pt, "pdf_profile") => "Perfil de conformidade do PDF gerado (formato pdf)",
+        (Lang::Pt, "pdf_page_size") => "Tamanho das páginas do PDF: A4 com o quadro centralizado, ou o tamanho do próprio quadro (formato pdf)",
+        (Lang::Pt, "pdf_page_dpi") => "Resolução com que os quadros são dispostos em páginas de tamanho nativo (formato pdf)",
+        (Lang::Pt, "pdf_image_format") => "Compressão das imagens no PDF; flate não tem perdas e mantém nítidas as bordas do texto, mrc separa o texto do fundo para digitalizações compactas (formato pdf)",
+        (Lang::Pt, "pdf_jpeg_quality") => "Qualidade JPEG das imagens no PDF, de 1 a 100 (formato pdf)",
+        (Lang::Pt, "pdf_max_dpi") => "Reduzir as imagens cuja resolução na página do PDF exceda estes DPI (formato pdf)",
+        (Lang::Pt, "pdf_auto_colour") => "Incorporar em tons de cinza as imagens de página sem cor, e as páginas de texto preto sobre branco como imagens CCITT G4 de 1 bit, reduzindo documentos digitalizados; não é usado com --pdf-image-format mrc (formato pdf)",
+        (Lang::Pt, "pdf_toc") => "Iniciar o PDF com um sumário com links para cada página (formato pdf)",
+        (Lang::Pt, "pdf_password") => "Senha necessária para abrir o PDF; criptografa-o com AES-256 (formato pdf)",
+        (Lang::Pt, "pdf_owner_password") => "Senha que dá acesso completo ao PDF; sem ela, os leitores só podem imprimir e copiar texto (formato pdf)",
+        (Lang::Pt, "html_link_images") => "Salvar as imagens em uma pasta images e vinculá-las em vez de incorporá-las (formato html)",
+        (Lang::Pt, "sqlite_link_images") => "Salvar as imagens em uma pasta images e armazenar seus caminhos em vez de blobs PNG (formato sqlite)",
+        (Lang::Pt, "iiif_base_url") => "URL a partir da qual a pasta iiif será servida, base de todos os identificadores do manifesto (formato iiif)",
+        (Lang::Pt, "keyword_index") => "Terminar o documento com um índice alfabético dos termos significativos do OCR e suas páginas (formatos pdf e md)",
+        (Lang::Pt, "slide_tags") => "Etiquetar cada slide com seus termos de OCR mais distintivos, no index.json e no front matter das notas md e obsidian",
+        (Lang::Pt, "links") => "Tornar clicáveis as URLs dos slides (formatos pdf e md) e listá-las com suas páginas e marcas de tempo em links.json",
+        (Lang::Pt, "figures") => "Recortar os gráficos, diagramas e fotos de cada slide como imagens próprias, exibidas após o texto do slide (formatos md e html)",
+        (Lang::Pt, "formulas") => "Manter as fórmulas de cada slide como imagens recortadas em seu lugar no texto (formatos md e html), deixando seu texto OCR ilegível fora da camada de texto (formatos pdf e html)",
+        (Lang::Pt, "new_content_only") => "Escrever apenas as linhas que cada slide acrescenta ao anterior, para slides construídos passo a passo (formatos md e jsonl; txt sempre o faz)",
+        (Lang::Pt, "final_builds_only") => "Manter apenas o estado final dos slides construídos passo a passo, descartando as páginas que mostram só uma parte",
+        (Lang::Pt, "transcript") => "Transcrição SRT ou WebVTT do áudio (p. ex. do Whisper); a fala é colocada depois de cada slide (formatos pdf, md e html)",
+        (Lang::Pt, "use_subtitles") => "Usar as legendas do próprio vídeo como transcrição: um arquivo .srt ou .vtt ao lado dele ou, senão, sua primeira faixa de legendas de texto (formatos pdf, md e html)",
+        (Lang::Pt, "whisper_model") => "Transcrever o áudio com este modelo do Whisper (arquivo ggml) e colocar a fala depois de cada slide (formatos pdf, md e html)",
+        (Lang::Pt, "diarize") => "Rotular os turnos de fala da transcrição como \"Speaker 1:\", \"Speaker 2:\", ... (requer um modelo do Whisper tinydiarize)",
+        (Lang::Pt, "llm_url") => "API compatível com OpenAI (p. ex. https://api.openai.com/v1) que limpa o texto OCR de cada página para o index.json; a chave é lida de OPENAI_API_KEY",
+        (Lang::Pt, "llm_model") => "Modelo consultado por --llm-url",
+        (Lang::Pt, "llm_summaries") => "Resumir também cada página com --llm-url, guardando o resumo junto ao seu texto no index.json",
+        (Lang::Pt, "translate_to") => "Traduzir o texto OCR de cada página para este idioma (ex.: English) com --llm-url, guardando-o junto a ele no index.json e imprimindo-o após cada slide (formatos pdf e md)",
+        (Lang::Pt, "spell_dictionary") => "Dicionário com o qual corrigir o texto OCR antes de gerar os documentos: uma lista de palavras, opcionalmente com frequências, ou um arquivo .dic do Hunspell; as alterações são listadas em corrections.json",
+        (Lang::Pt, "spell_wordlist") => "Lista de palavras do domínio (um termo por linha) adicionada ao dicionário ortográfico, com prioridade sobre ele",
+        (Lang::Pt, "template") => "Modelo Handlebars que define a estrutura do documento (formatos md e html)",
+        (Lang::Pt, "recap_seconds") => "Segundos que cada página é exibida no vídeo resumo (formato video)",
+        (Lang::Pt, "recap_timestamps") => "Gravar o horário de origem de cada página no vídeo resumo (formato video)",
+        (Lang::Pt, "recap_container") => "Contêiner do vídeo resumo (formato video)",
+        (Lang::Pt, "split_by") => "Gravar um documento por capítulo do vídeo em vez de um único (formatos pdf e md)",
+        (Lang::Pt, "subtitle_text") => "Mostrar o texto OCR de cada slide ou apenas o título nas legendas (formatos srt e vtt)",
+        (Lang::Pt, "name_template") => "Caminho das saídas dentro da pasta result, p. ex. \"{stem}/{date}/slide_{index:04}\" (formatos pdf, md, img e relatório de revisão)",
+        (Lang::Pt, "log_level") => "Nível de detalhe do log",
+        (Lang::Pt, "ui_lang") => "Idioma da interface (ajuda, progresso e resumo)",
+
+        (Lang::De, "input") => "Pfad zur Eingabevideodatei (z. B. vorlesung.mp4)",
+        (Lang::De, "output") => "Verzeichnis für die Ausgabedateien",
+        (Lang::De, "format") => "Ausgabeformat",
+        (Lang::De, "sensitivity") => "Empfindlichkeitsschwelle für den Bildvergleich (0.0 bis 1.0), oder \"auto\", um sie für das Video aus den Abständen zwischen aufeinanderfolgenden Bildern zu bestimmen",
+        (Lang::De, "hash_algorithm") => "Verfahren, mit dem Bilder gehasht werden, um Folien zu unterscheiden: phash ist robust gegen Rauschen und Verschiebungen, bemerkt aber kaum eine hinzugefügte Textzeile, dhash unterscheidet Layouts, ahash und whash bemerken hinzugefügten Text am stärksten",
+        (Lang::De, "hash_size") => "Seitenlänge des Bild-Hashes in Bits (4 bis 32)",
+        (Lang::De, "revisits") => "Jede neue Folie mit allen bisher behaltenen Seiten vergleichen, nicht nur mit der vorherigen, und eine erneut gezeigte Folie verwerfen oder mit der Seite zusammenführen, die sie zuerst zeigte und die festhält, wann sie erneut gezeigt wurde",
+        (Lang::De, "best_frame") => "Das Bild jeder Seite aus dem schärfsten, gut belichteten Bild der Folge fast gleicher Bilder nehmen, die sie beginnt, statt aus dem ersten, das mitten in einem Übergang oder verwackelt sein kann",
+        (Lang::De, "settle_frames") => "Eine Seite erst beginnen, wenn der Inhalt so viele Bilder lang unverändert geblieben ist, damit halb gezeichnete Bilder von Überblendungen, Folienübergängen und Animationen nicht als Seiten behalten werden",
+        (Lang::De, "settle_ms") => "Wie --settle-frames, als Dauer in Millisekunden des Videos",
+        (Lang::De, "min_duration") => "Nie eine Seite aus Inhalten machen, die kürzer als diese Dauer zu sehen sind, etwa Benachrichtigungen und kurze Wechsel in ein anderes Fenster, z. B. \"2s\" oder \"500ms\"",
+        (Lang::De, "audio_hints") => "Folienwechsel an Pausen und Applaus im Ton ausrichten und mitten im Gesprochenen vermeiden",
+        (Lang::De, "lang") => "OCR-Sprache (z. B. \"eng\" für Englisch, \"deu\" für Deutsch); mehrere mit \"+\" verbinden für gemischte Folien, z. B. \"eng+deu\"",
+        (Lang::De, "tessdata_dir") => "Verzeichnis mit den .traineddata-Sprachpaketen von Tesseract (Standard: TESSDATA_PREFIX, der tesseract-rs-Cache, dann die tessdata-Verzeichnisse des Systems)",
+        (Lang::De, "psm") => "Seitensegmentierungsmodus von Tesseract, 0-13 (z. B. 11 für verstreuten Folientext, 4 für eine Spalte, 3 für ganze Seiten; Standard: der von Tesseract, 3)",
+        (Lang::De, "oem") => "OCR-Engine-Modus von Tesseract: 0 Legacy, 1 LSTM, 2 Legacy und LSTM, 3 Standard (0 und 2 brauchen traineddata mit Legacy-Engine)",
+        (Lang::De, "tess_config") => "Tesseract-Variable, die vor der Erkennung gesetzt wird, als Schlüssel=Wert (z. B. preserve_interword_spaces=1); wiederholbar",
+        (Lang::De, "char_whitelist") => "Nur diese Zeichen erkennen (z. B. \"0123456789.,%\" für Zahlen-Dashboards)",
+        (Lang::De, "char_blacklist") => "Diese Zeichen nie erkennen (z. B. \"|~\" für Code-Folien)",
+        (Lang::De, "user_words") => "Wortliste, ein Wort pro Zeile, die Tesseract beim Lesen bevorzugt (z. B. medizinische oder juristische Begriffe)",
+        (Lang::De, "preprocess") => "Jedes Bild vor der OCR aufbereiten, z. B. für Handyaufnahmen von Whiteboards: kommagetrennte Schritte oder all",
+        (Lang::De, "profile") => "Bilder einer Art von Aufnahme beim Dekodieren aufbereiten, vor der Seitenerkennung, der OCR und den Dokumenten: whiteboard gleicht die Beleuchtung eines gefilmten Whiteboards aus, macht die Tafel weiß und verstärkt die Markerstriche; paper füllt Spiegelungen auf gefilmten Papierseiten auf und gleicht ihre Schatten aus",
+        (Lang::De, "rectify") => "Perspektive schräg gefilmter Dokumente und Projektionsflächen korrigieren und jedes vor der OCR und in den Dokumenten zu einem geraden Rechteck entzerren",
+        (Lang::De, "upscale") => "Jedes Bild vor der OCR mit einem Super-Resolution-Modell 2- oder 4-fach vergrößern, für niedrig aufgelöste Aufnahmen dichter Folien: Real-ESRGAN (realesrgan-ncnn-vulkan im PATH) oder --upscale-model",
+        (Lang::De, "upscale_model") => "ESRGAN-Super-Resolution-Modell (ONNX), das --upscale statt Real-ESRGAN verwendet",
+        (Lang::De, "crop") => "Nur diesen Bereich des Bildes analysieren und erkennen, als x,y,w,h in Pixeln (z. B. um eine Webcam-Einblendung oder Player-Steuerelemente auszulassen), oder auto, um schwarze Balken abzuschneiden",
+        (Lang::De, "ignore_regions") => "Diesen Bereich, als x,y,w,h in Pixeln des analysierten Bildes, bei der Erkennung von Seitenwechseln und bei der OCR ignorieren (z. B. eine Uhr oder Live-Untertitel); wiederholbar",
+        (Lang::De, "ignore_mask") => "PNG-Maske in der Größe des analysierten Bildes, deren weiße Pixel bei der Erkennung von Seitenwechseln und bei der OCR ignoriert werden",
+        (Lang::De, "download_lang") => "Fehlende Sprachpakete von --lang ohne Rückfrage nach --tessdata-dir oder in den tesseract-rs-Cache herunterladen und ihre Prüfsummen kontrollieren",
+        (Lang::De, "tessdata_source") => "Repository, aus dem fehlende Sprachpakete geladen werden: tessdata_fast (kleiner, schneller) oder tessdata_best (genauer)",
+        (Lang::De, "ocr_engine") => "OCR-Engine: tesseract; paddle, um PaddleOCR-Modelle mit ONNX Runtime auszuführen, die kleine Videotexte und stilisierte Schriften besser lesen; oder google, azure oder textract, um die Frames an einen OCR-Clouddienst zu senden",
+        (Lang::De, "paddle_det_model") => "PaddleOCR-Modell zur Texterkennung im Bild (ONNX), verwendet von --ocr-engine paddle",
+        (Lang::De, "paddle_rec_model") => "PaddleOCR-Modell zum Lesen der Textzeilen (ONNX), verwendet von --ocr-engine paddle",
+        (Lang::De, "paddle_dict") => "Zeichenwörterbuch von --paddle-rec-model, ein Zeichen pro Zeile",
+        (Lang::De, "paddle_devices") => "CUDA-GPU, nach ihrer Nummer, auf der --ocr-engine paddle seine Modelle ausführt; wiederholbar, um die Frame-Stapel auf mehrere GPUs zu verteilen. Ohne diese Option laufen die Modelle auf der CPU",
+        (Lang::De, "cloud_endpoint") => "Endpunkt des OCR-Clouddienstes anstelle des Standard-Endpunkts des Anbieters; für azure erforderlich (die Ressourcen-URL), auch als --handwriting-Engine",
+        (Lang::De, "cloud_batch_size") => "Pro Anfrage an Google Cloud Vision gesendete Frames (höchstens 16)",
+        (Lang::De, "cloud_retries") => "Wie oft eine fehlgeschlagene, gedrosselte oder abgelaufene Cloud-OCR-Anfrage mit exponentiellem Backoff wiederholt wird",
+        (Lang::De, "cloud_max_images") => "Höchstzahl der pro Lauf an den OCR-Clouddienst gesendeten Frames, um die Kosten zu begrenzen; Frames über dem Limit bleiben unerkannt",
+        (Lang::De, "handwriting") => "Engine für Handschrift, etwa bei Vorlesungen an Whiteboard oder Kreidetafel: onnx, um --handwriting-model mit ONNX Runtime auszuführen, oder google, azure oder textract, um die Frames an einen OCR-Clouddienst zu senden. Liest jeden Frame, sofern --handwriting-below nicht angegeben ist",
+        (Lang::De, "handwriting_model") => "Handschrifterkennungsmodell (ONNX) für --handwriting onnx: liest 64 Pixel hohe Graustufenzeilen, mit CTC-Ausgabe",
+        (Lang::De, "handwriting_dict") => "Zeichenwörterbuch von --handwriting-model, ein Zeichen pro Zeile",
+        (Lang::De, "handwriting_below") => "Mittlere Wortkonfidenz (0 bis 100), unter der ein mit --ocr-engine gelesener Frame erneut mit der --handwriting-Engine gelesen wird, deren Ergebnis behalten wird",
+        (Lang::De, "index") => "Optionale JSON-Indexdatei mit Metadaten erzeugen",
+        (Lang::De, "bundle") => "Ergebnisse (Dokumente, Frames, OCR-Bericht, Index) mit einem Manifest in ein einziges Archiv packen",
+        (Lang::De, "contact_sheet") => "Rasterbild und HTML-Seite aller behaltenen Frames mit Zeitstempeln erzeugen, um die Duplikaterkennung zu prüfen",
+        (Lang::De, "chapters") => "Kapitellisten aus den Folienwechseln schreiben: chapters.txt für eine YouTube-Beschreibung und chapters.ffmetadata zum Einbetten ins Video",
+        (Lang::De, "review_report") => "Bericht der unter der Prüfschwelle erkannten Wörter schreiben, jeweils neben einem Ausschnitt des Frames",
+        (Lang::De, "review_threshold") => "OCR-Konfidenz (0 bis 100), unter der Wörter im Prüfbericht aufgeführt werden",
+        (Lang::De, "search_index") => "Volltext-Suchindex über den OCR-Text erstellen, abfragbar mit dem Unterbefehl search",
+        (Lang::De, "title_filenames") => "Den erkannten Folientitel jeder Seite an die Bilddateinamen anhängen (Format img)",
+        (Lang::De, "ocr_report") => "Format des wortweisen OCR-Berichts im Ordner ocr",
+        (Lang::De, "consensus_frames") => "So viele Bilder jeder stabilen Folie per OCR erkennen und die Ergebnisse wortweise per Abstimmung zusammenführen (1 deaktiviert)",
+        (Lang::De, "skip_textless") => "OCR für Bilder überspringen, auf denen ein schneller kantenbasierter Detektor keinen Text findet; sie werden zu reinen Bildseiten",
+        (Lang::De, "ocr_threads") => "Anzahl gleichzeitig erkannter Bilder, jedes mit eigener OCR-Engine (Standard: eines pro CPU-Kern)",
+        (Lang::De, "retry_below") => "Mittlere Wortkonfidenz (0 bis 100), unter der ein Bild erneut mit anderen Segmentierungsmodi, invertiert und doppelt vergrößert erkannt wird; das beste Ergebnis bleibt",
+        (Lang::De, "min_confidence") => "OCR-Konfidenz (0 bis 100), unter der Wörter in den Ausgaben pdf, md und jsonl als unsicher gelten",
+        (Lang::De, "low_confidence") => "Umgang mit unsicheren Wörtern: weglassen, behalten und als unsicher auflisten (md und jsonl) oder behalten (standardmäßig bei pdf weggelassen, bei md und jsonl behalten)",
+        (Lang::De, "ocr_debug") => "Den OCR-Text jeder Seite und ein Bild ihrer nach Konfidenz eingefärbten Wortrahmen in ocr/debug schreiben, um die OCR-Einstellungen abzustimmen",
+        (Lang::De, "md_heading_level") => "Überschriftenebene (1-6) der Seitenabschnitte; der Dokumenttitel steht eine Ebene darüber (Format md)",
+        (Lang::De, "title") => "Dokumenttitel (standardmäßig der Name der Eingabedatei)",
+        (Lang::De, "author") => "Autor des Dokuments, in die PDF-Metadaten und das LaTeX-Dokument geschrieben",
+        (Lang::De, "keywords") => "Kommagetrennte Schlüsselwörter, in die PDF-Metadaten geschrieben",
+        (Lang::De, "video_url") => "URL, unter der die Aufnahme angesehen werden kann; Seiten verlinken zu ihrem Zeitpunkt (Formate pdf, md, html, tex, obsidian)",
+        (Lang::De, "pdf_profile") => "Konformitätsprofil der PDF-Ausgabe (Format pdf)",
+        (Lang::De, "pdf_page_size") => "Größe der PDF-Seiten: A4 mit eingepasstem Bild oder die Größe des Bildes selbst (Format pdf)",
+        (Lang::De, "pdf_page_dpi") => "Auflösung, mit der Bilder auf Seiten in nativer Größe gesetzt werden (Format pdf)",
+        (Lang::De, "pdf_image_format") => "Komprimierung der Seitenbilder im PDF; flate ist verlustfrei und hält Textkanten scharf, mrc trennt Text und Hintergrund für kompakte Scans (Format pdf)",
+        (Lang::De, "pdf_jpeg_quality") => "JPEG-Qualität der Seitenbilder im PDF, von 1 bis 100 (Format pdf)",
+        (Lang::De, "pdf_max_dpi") => "Seitenbilder verkleinern, deren Auflösung auf der PDF-Seite diese DPI überschreitet (Format pdf)",
+        (Lang::De, "pdf_auto_colour") => "Seitenbilder ohne Farbe in Graustufen einbetten und Seiten mit schwarzem Text auf Weiß als 1-Bit-CCITT-G4-Bilder, was gescannte Dokumente verkleinert; nicht mit --pdf-image-format mrc (Format pdf)",
+        (Lang::De, "pdf_toc") => "Das PDF mit einem Inhaltsverzeichnis beginnen, das auf jede Seite verlinkt (Format pdf)",
+        (Lang::De, "pdf_password") => "Passwort zum Öffnen der PDF; verschlüsselt sie mit AES-256 (Format pdf)",
+        (Lang::De, "pdf_owner_password") => "Passwort mit vollem Zugriff auf die PDF; ohne es können Leser nur drucken und Text kopieren (Format pdf)",
+        (Lang::De, "html_link_images") => "Seitenbilder in einen Ordner images schreiben und verlinken statt einbetten (Format html)",
+        (Lang::De, "sqlite_link_images") => "Seitenbilder in einen Ordner images schreiben und ihre Pfade statt PNG-Blobs speichern (Format sqlite)",
+        (Lang::De, "iiif_base_url") => "URL, unter der der Ordner iiif bereitgestellt wird, Basis aller IDs im Manifest (Format iiif)",
+        (Lang::De, "keyword_index") => "Dokument mit einem alphabetischen Register der wichtigen OCR-Begriffe und ihrer Seiten abschließen (Formate pdf und md)",
+        (Lang::De, "slide_tags") => "Jede Folie mit ihren markantesten OCR-Begriffen versehen, in index.json und im Front Matter der md- und obsidian-Notizen",
+        (Lang::De, "links") => "URLs auf den Folien anklickbar machen (Formate pdf und md) und mit ihren Seiten und Zeitstempeln in links.json auflisten",
+        (Lang::De, "figures") => "Diagramme, Schaubilder und Fotos jeder Folie als eigene Bilder ausschneiden und nach dem Folientext zeigen (Formate md und html)",
+        (Lang::De, "formulas") => "Formeln jeder Folie als ausgeschnittene Bilder an ihrer Stelle im Text behalten (Formate md und html) und ihren unleserlichen OCR-Text aus der Textebene weglassen (Formate pdf und html)",
+        (Lang::De, "new_content_only") => "Nur die Zeilen schreiben, die jede Folie zur vorherigen hinzufügt, für schrittweise aufgebaute Folien (Formate md und jsonl; txt tut es immer)",
+        (Lang::De, "final_builds_only") => "Nur den Endzustand schrittweise aufgebauter Folien behalten und die Seiten verwerfen, die nur einen Teil davon zeigen",
+        (Lang::De, "transcript") => "SRT- oder WebVTT-Transkript des Tons (z. B. von Whisper); das Gesprochene folgt auf jede Folie (Formate pdf, md und html)",
+        (Lang::De, "use_subtitles") => "Die eigenen Untertitel des Videos als Transkript verwenden: eine .srt- oder .vtt-Datei daneben, sonst seine erste Text-Untertitelspur (Formate pdf, md und html)",
+        (Lang::De, "whisper_model") => "Den Ton mit diesem Whisper-Modell (ggml-Datei) transkribieren und das Gesprochene nach jeder Folie einfügen (Formate pdf, md und html)",
+        (Lang::De, "diarize") => "Sprecherwechsel im Transkript als \"Speaker 1:\", \"Speaker 2:\", ... kennzeichnen (erfordert ein tinydiarize-Whisper-Modell)",
+        (Lang::De, "llm_url") => "OpenAI-kompatible API (z. B. https://api.openai.com/v1), die den OCR-Text jeder Seite für index.json bereinigt; der Schlüssel wird aus OPENAI_API_KEY gelesen",
+        (Lang::De, "llm_model") => "Von --llm-url verwendetes Modell",
+        (Lang::De, "llm_summaries") => "Zusätzlich jede Seite mit --llm-url zusammenfassen und die Zusammenfassung neben ihrem Text in index.json speichern",
+        (Lang::De, "translate_to") => "Den OCR-Text jeder Seite mit --llm-url in diese Sprache übersetzen (z. B. English), neben ihm in index.json speichern und nach jeder Folie ausgeben (Formate pdf und md)",
+        (Lang::De, "spell_dictionary") => "Wörterbuch, gegen das der OCR-Text vor dem Erstellen der Dokumente korrigiert wird: eine Wortliste, optional mit Häufigkeiten, oder eine Hunspell-.dic-Datei; Änderungen werden in corrections.json aufgeführt",
+        (Lang::De, "spell_wordlist") => "Fachwortliste (ein Begriff pro Zeile), die dem Rechtschreibwörterbuch hinzugefügt wird und Vorrang vor ihm hat",
+        (Lang::De, "template") => "Handlebars-Vorlage, die den Aufbau des Dokuments bestimmt (Formate md und html)",
+        (Lang::De, "recap_seconds") => "Sekunden, die jede Seite im Zusammenfassungsvideo angezeigt wird (Format video)",
+        (Lang::De, "recap_timestamps") => "Den Quellzeitstempel jeder Seite in das Zusammenfassungsvideo einblenden (Format video)",
+        (Lang::De, "recap_container") => "Container des Zusammenfassungsvideos (Format video)",
+        (Lang::De, "split_by") => "Ein Dokument pro Kapitelmarke des Videos statt eines einzigen schreiben (Formate pdf und md)",
+        (Lang::De, "subtitle_text") => "OCR-Text jeder Folie oder nur ihren Titel in den Untertiteln anzeigen (Formate srt und vtt)",
+        (Lang::De, "name_template") => "Pfad der Ausgaben im Ordner result, z. B. \"{stem}/{date}/slide_{index:04}\" (Formate pdf, md, img und Prüfbericht)",
+        (Lang::De, "log_level") => "Ausführlichkeit der Protokollierung",
+        (Lang::De, "ui_lang") => "Sprache der Oberfläche (Hilfe, Fortschritt und Zusammenfassung)",
+
+        (Lang::Fr, "input") => "Chemin du fichier vidéo d'entrée (ex. : cours.mp4)",
+        (Lang::Fr, "output") => "Répertoire où enregistrer les fichiers de sortie",
+        (Lang::Fr, "format") => "Format de sortie",
+        (Lang::Fr, "sensitivity") => "Seuil de sensibilité de la comparaison entre images (0.0 à 1.0), ou \"auto\" pour en choisir un pour la vidéo d'après les distances entre ses images consécutives",
+        (Lang::Fr, "hash_algorithm") => "Algorithme de hachage des images servant à distinguer les diapositives : phash résiste au bruit et aux décalages mais remarque à peine une ligne de texte ajoutée, dhash distingue les mises en page, ahash et whash remarquent le plus le texte ajouté",
+        (Lang::Fr, "hash_size") => "Côté du hachage des images en bits (4 à 32)",
+        (Lang::Fr, "revisits") => "Comparer chaque nouvelle diapositive à toutes les pages conservées, pas seulement à la précédente, et écarter une diapositive montrée à nouveau ou la fusionner avec la page qui l'a montrée en premier, qui indique quand elle a été remontrée",
+        (Lang::Fr, "best_frame") => "Prendre l'image de chaque page dans l'image la plus nette et bien exposée de la suite d'images presque identiques qu'elle commence, plutôt que dans la première, qui peut être prise en pleine transition ou floue",
+        (Lang::Fr, "settle_frames") => "Ne commencer une page que lorsque le contenu est resté inchangé pendant ce nombre d'images, pour ne pas garder comme pages les images à moitié dessinées des fondus, transitions et animations",
+        (Lang::Fr, "settle_ms") => "Comme --settle-frames, sous forme de durée en millisecondes de la vidéo",
+        (Lang::Fr, "min_duration") => "Ne jamais faire de page d'un contenu affiché moins longtemps que cette durée, comme les notifications et les passages rapides à une autre fenêtre, p. ex. \"2s\" ou \"500ms\"",
+        (Lang::Fr, "audio_hints") => "Favoriser les changements de diapositive aux pauses et applaudissements de l'audio et les éviter au milieu de la parole",
+        (Lang::Fr, "lang") => "Langue de l'OCR (ex. : \"eng\" pour l'anglais, \"fra\" pour le français) ; joignez-en plusieurs avec \"+\" pour des diapositives mixtes, ex. : \"eng+deu\"",
+        (Lang::Fr, "tessdata_dir") => "Répertoire contenant les paquets de langue .traineddata de Tesseract (par défaut : TESSDATA_PREFIX, le cache de tesseract-rs, puis les répertoires tessdata du système)",
+        (Lang::Fr, "psm") => "Mode de segmentation de page de Tesseract, 0-13 (ex. : 11 pour le texte clairsemé des diapositives, 4 pour une seule colonne, 3 pour des pages entières ; par défaut : celui de Tesseract, 3)",
+        (Lang::Fr, "oem") => "Mode du moteur OCR de Tesseract : 0 ancien, 1 LSTM, 2 ancien et LSTM, 3 par défaut (0 et 2 nécessitent des traineddata avec l'ancien moteur)",
+        (Lang::Fr, "tess_config") => "Variable de Tesseract définie avant la reconnaissance, sous la forme clé=valeur (ex. : preserve_interword_spaces=1) ; répétable",
+        (Lang::Fr, "char_whitelist") => "Ne reconnaître que ces caractères (ex. : \"0123456789.,%\" pour les tableaux de bord chiffrés)",
+        (Lang::Fr, "char_blacklist") => "Ne jamais reconnaître ces caractères (ex. : \"|~\" pour les diapositives de code)",
+        (Lang::Fr, "user_words") => "Liste de mots, un par ligne, que Tesseract privilégie à la lecture (ex. : termes médicaux ou juridiques)",
+        (Lang::Fr, "preprocess") => "Nettoie chaque image avant l'OCR, ex. : pour les vidéos de tableaux blancs filmées au téléphone : étapes séparées par des virgules, ou all",
+        (Lang::Fr, "profile") => "Nettoyer les images d'un type d'enregistrement au décodage, avant la détection des pages, l'OCR et les documents : whiteboard égalise l'éclairage d'un tableau blanc filmé, rend le tableau blanc et renforce les traits de feutre ; paper comble les reflets des pages papier filmées et égalise leurs ombres",
+        (Lang::Fr, "rectify") => "Corrige la perspective des documents et écrans de projection filmés de biais, en redressant chacun en rectangle avant l'OCR et dans les documents",
+        (Lang::Fr, "upscale") => "Agrandir chaque image 2 ou 4 fois avec un modèle de super-résolution avant l'OCR, pour les enregistrements basse résolution de diapositives denses : Real-ESRGAN (realesrgan-ncnn-vulkan dans le PATH) ou --upscale-model",
+        (Lang::Fr, "upscale_model") => "Modèle de super-résolution ESRGAN (ONNX) utilisé par --upscale à la place de Real-ESRGAN",
+        (Lang::Fr, "crop") => "N'analyser et reconnaître que cette région de l'image, sous la forme x,y,w,h en pixels (ex. : pour exclure la webcam de l'orateur ou les commandes du lecteur), ou auto pour supprimer les bandes noires",
+        (Lang::Fr, "ignore_regions") => "Ignorer cette région, sous la forme x,y,w,h en pixels de l'image analysée, lors de la détection des changements de page et de l'OCR (ex. : une horloge ou des sous-titres en direct) ; répétable",
+        (Lang::Fr, "ignore_mask") => "Masque PNG de la taille de l'image analysée dont les pixels blancs sont ignorés lors de la détection des changements de page et de l'OCR",
+        (Lang::Fr, "download_lang") => "Télécharge sans demander les paquets de langue de --lang manquants dans --tessdata-dir ou le cache de tesseract-rs, en vérifiant leurs sommes de contrôle",
+        (Lang::Fr, "tessdata_source") => "Dépôt d'où sont téléchargés les paquets de langue manquants : tessdata_fast (plus petit, plus rapide) ou tessdata_best (plus précis)",
+        (Lang::Fr, "ocr_engine") => "Moteur d'OCR : tesseract ; paddle pour exécuter des modèles PaddleOCR avec ONNX Runtime, qui lisent mieux le petit texte des vidéos et les polices stylisées ; ou google, azure ou textract pour envoyer les images à un service d'OCR dans le cloud",
+        (Lang::Fr, "paddle_det_model") => "Modèle de détection de texte PaddleOCR (ONNX) utilisé par --ocr-engine paddle",
+        (Lang::Fr, "paddle_rec_model") => "Modèle de reconnaissance de texte PaddleOCR (ONNX) utilisé par --ocr-engine paddle",
+        (Lang::Fr, "paddle_dict") => "Dictionnaire de caractères de --paddle-rec-model, un caractère par ligne",
+        (Lang::Fr, "paddle_devices") => "GPU CUDA, par son numéro, sur lequel --ocr-engine paddle exécute ses modèles ; répétable pour répartir les lots d'images entre plusieurs GPU. Sans elle, les modèles s'exécutent sur le CPU",
+        (Lang::Fr, "cloud_endpoint") => "Point de terminaison du service d'OCR dans le cloud, à la place de celui du fournisseur ; obligatoire pour azure (l'URL de la ressource), y compris comme moteur --handwriting",
+        (Lang::Fr, "cloud_batch_size") => "Images envoyées à Google Cloud Vision par requête (16 au plus)",
+        (Lang::Fr, "cloud_retries") => "Nombre de nouvelles tentatives, avec attente exponentielle, d'une requête d'OCR dans le cloud qui a échoué, a été limitée ou a expiré",
+        (Lang::Fr, "cloud_max_images") => "Nombre maximal d'images envoyées au service d'OCR dans le cloud par exécution, pour en borner le coût ; les images au-delà restent non reconnues",
+        (Lang::Fr, "handwriting") => "Moteur pour l'écriture manuscrite, comme dans les cours au tableau blanc ou noir : onnx pour exécuter --handwriting-model avec ONNX Runtime, ou google, azure ou textract pour envoyer les images à un service d'OCR dans le cloud. Lit toutes les images sauf si --handwriting-below est indiqué",
+        (Lang::Fr, "handwriting_model") => "Modèle de reconnaissance de l'écriture manuscrite (ONNX) utilisé par --handwriting onnx : il lit des lignes en niveaux de gris de 64 pixels de haut, avec une sortie CTC",
+        (Lang::Fr, "handwriting_dict") => "Dictionnaire de caractères de --handwriting-model, un caractère par ligne",
+        (Lang::Fr, "handwriting_below") => "Confiance moyenne des mots (0 à 100) en dessous de laquelle une image lue avec --ocr-engine est relue avec le moteur de --handwriting, dont la lecture est conservée",
+        (Lang::Fr, "index") => "Générer un fichier d'index JSON optionnel avec des métadonnées",
+        (Lang::Fr, "bundle") => "Regrouper les résultats (documents, images, rapport OCR, index) dans une seule archive avec un manifeste",
+        (Lang::Fr, "contact_sheet") => "Générer une image en grille et une page HTML de toutes les images conservées avec leurs horodatages, pour vérifier la déduplication",
+        (Lang::Fr, "chapters") => "Écrire des listes de chapitres à partir des changements de diapositive : chapters.txt pour une description YouTube et chapters.ffmetadata à intégrer dans la vidéo",
+        (Lang::Fr, "review_report") => "Écrire un rapport des mots reconnus sous le seuil de relecture, chacun à côté d'un extrait de l'image",
+        (Lang::Fr, "review_threshold") => "Confiance OCR (0 à 100) sous laquelle les mots figurent dans le rapport de relecture",
+        (Lang::Fr, "search_index") => "Construire un index de recherche plein texte sur le texte OCR, interrogé avec la sous-commande search",
+        (Lang::Fr, "title_filenames") => "Ajouter le titre détecté de chaque page aux noms des images (format img)",
+        (Lang::Fr, "ocr_report") => "Format du rapport OCR par mot dans le dossier ocr",
+        (Lang::Fr, "consensus_frames") => "Appliquer l'OCR à ce nombre d'images de chaque diapositive stable et fusionner les résultats par vote mot à mot (1 le désactive)",
+        (Lang::Fr, "skip_textless") => "Ignorer l'OCR sur les images où un détecteur rapide fondé sur les contours ne trouve pas de texte ; elles deviennent des pages image seule",
+        (Lang::Fr, "ocr_threads") => "Nombre d'images reconnues à la fois, chacune avec son propre moteur OCR (par défaut, une par cœur de processeur)",
+        (Lang::Fr, "retry_below") => "Confiance moyenne des mots (0 à 100) sous laquelle une image est reconnue à nouveau avec d'autres modes de segmentation, inversée et agrandie 2x, en gardant la meilleure lecture",
+        (Lang::Fr, "min_confidence") => "Confiance OCR (0 à 100) sous laquelle les mots sont jugés incertains dans les sorties pdf, md et jsonl",
+        (Lang::Fr, "low_confidence") => "Que faire des mots incertains : les omettre, les garder en les listant comme incertains (md et jsonl) ou les garder (par défaut omis en pdf et gardés en md et jsonl)",
+        (Lang::Fr, "ocr_debug") => "Écrire le texte OCR de chaque page et une image de ses cadres de mots colorés selon la confiance dans ocr/debug, pour régler les paramètres OCR",
+        (Lang::Fr, "md_heading_level") => "Niveau de titre (1-6) des sections de chaque page ; le titre du document est placé un niveau au-dessus (format md)",
+        (Lang::Fr, "title") => "Titre du document (par défaut, le nom du fichier d'entrée)",
+        (Lang::Fr, "author") => "Auteur du document, écrit dans les métadonnées du PDF et le document LaTeX",
+        (Lang::Fr, "keywords") => "Mots-clés séparés par des virgules, écrits dans les métadonnées du PDF",
+        (Lang::Fr, "video_url") => "URL où l'enregistrement peut être visionné ; les pages y renvoient à leur horodatage (formats pdf, md, html, tex, obsidian)",
+        (Lang::Fr, "pdf_profile") => "Profil de conformité de la sortie PDF (format pdf)",
+        (Lang::Fr, "pdf_page_size") => "Taille des pages PDF : A4 avec l'image centrée, ou la taille propre de l'image (format pdf)",
+        (Lang::Fr, "pdf_page_dpi") => "Résolution à laquelle les images sont placées sur les pages de taille native (format pdf)",
+        (Lang::Fr, "pdf_image_format") => "Compression des images dans le PDF ; flate est sans perte et garde les contours du texte nets, mrc sépare le texte du fond pour des numérisations compactes (format pdf)",
+        (Lang::Fr, "pdf_jpeg_quality") => "Qualité JPEG des images dans le PDF, de 1 à 100 (format pdf)",
+        (Lang::Fr, "pdf_max_dpi") => "Réduire les images dont la résolution sur la page PDF dépasse ces DPI (format pdf)",
+        (Lang::Fr, "pdf_auto_colour") => "Intégrer en niveaux de gris les images de page sans couleur, et les pages de texte noir sur blanc comme images CCITT G4 à 1 bit, ce qui réduit les documents numérisés ; sans effet avec --pdf-image-format mrc (format pdf)",
+        (Lang::Fr, "pdf_toc") => "Commencer le PDF par une table des matières liée à chaque page (format pdf)",
+        (Lang::Fr, "pdf_password") => "Mot de passe requis pour ouvrir le PDF ; le chiffre en AES-256 (format pdf)",
+        (Lang::Fr, "pdf_owner_password") => "Mot de passe donnant un accès complet au PDF ; sans lui, les lecteurs peuvent seulement imprimer et copier le texte (format pdf)",
+        (Lang::Fr, "html_link_images") => "Enregistrer les images dans un dossier images et les lier au lieu de les intégrer (format html)",
+        (Lang::Fr, "sqlite_link_images") => "Enregistrer les images dans un dossier images et stocker leurs chemins au lieu de blobs PNG (format sqlite)",
+        (Lang::Fr, "iiif_base_url") => "URL depuis laquelle le dossier iiif sera servi, base de tous les identifiants du manifeste (format iiif)",
+        (Lang::Fr, "keyword_index") => "Terminer le document par un index alphabétique des termes significatifs de l'OCR et de leurs pages (formats pdf et md)",
+        (Lang::Fr, "slide_tags") => "Étiqueter chaque diapositive avec ses termes OCR les plus distinctifs, dans index.json et le front matter des notes md et obsidian",
+        (Lang::Fr, "links") => "Rendre cliquables les URL des diapositives (formats pdf et md) et les lister avec leurs pages et horodatages dans links.json",
+        (Lang::Fr, "figures") => "Découper les graphiques, schémas et photos de chaque diapositive en images distinctes, affichées après le texte de la diapositive (formats md et html)",
+        (Lang::Fr, "formulas") => "Conserver les formules de chaque diapositive comme images découpées à leur place dans le texte (formats md et html), en laissant leur texte OCR illisible hors de la couche de texte (formats pdf et html)",
+        (Lang::Fr, "new_content_only") => "N'écrire que les lignes que chaque diapositive ajoute à la précédente, pour les diapositives construites pas à pas (formats md et jsonl ; txt le fait toujours)",
+        (Lang::Fr, "final_builds_only") => "Ne garder que l'état final des diapositives construites pas à pas, en écartant les pages qui n'en montrent qu'une partie",
+        (Lang::Fr, "transcript") => "Transcription SRT ou WebVTT de l'audio (p. ex. de Whisper) ; la parole est placée après chaque diapositive (formats pdf, md et html)",
+        (Lang::Fr, "use_subtitles") => "Utiliser les sous-titres de la vidéo comme transcription : un fichier .srt ou .vtt à côté d'elle, sinon sa première piste de sous-titres texte (formats pdf, md et html)",
+        (Lang::Fr, "whisper_model") => "Transcrire l'audio avec ce modèle Whisper (fichier ggml) et placer la parole après chaque diapositive (formats pdf, md et html)",
+        (Lang::Fr, "diarize") => "Étiqueter les tours de parole de la transcription en \"Speaker 1:\", \"Speaker 2:\", ... (nécessite un modèle Whisper tinydiarize)",
+        (Lang::Fr, "llm_url") => "API compatible OpenAI (p. ex. https://api.openai.com/v1) qui nettoie le texte OCR de chaque page pour index.json ; la clé est lue dans OPENAI_API_KEY",
+        (Lang::Fr, "llm_model") => "Modèle interrogé par --llm-url",
+        (Lang::Fr, "llm_summaries") => "Résumer aussi chaque page avec --llm-url, le résumé étant stocké à côté de son texte dans index.json",
+        (Lang::Fr, "translate_to") => "Traduire le texte OCR de chaque page dans cette langue (p. ex. English) avec --llm-url, stocké à côté de lui dans index.json et imprimé après chaque diapositive (formats pdf et md)",
+        (Lang::Fr, "spell_dictionary") => "Dictionnaire servant à corriger le texte OCR avant la génération des documents : une liste de mots, éventuellement avec fréquences, ou un fichier .dic Hunspell ; les modifications sont listées dans corrections.json",
+        (Lang::Fr, "spell_wordlist") => "Liste de mots du domaine (un terme par ligne) ajoutée au dictionnaire orthographique, prioritaire sur lui",
+        (Lang::Fr, "template") => "Modèle Handlebars qui définit la structure du document (formats md et html)",
+        (Lang::Fr, "recap_seconds") => "Durée d'affichage de chaque page dans la vidéo récapitulative, en secondes (format video)",
+        (Lang::Fr, "recap_timestamps") => "Incruster l'horodatage source de chaque page dans la vidéo récapitulative (format video)",
+        (Lang::Fr, "recap_container") => "Conteneur de la vidéo récapitulative (format video)",
+        (Lang::Fr, "split_by") => "Écrire un document par chapitre de la vidéo au lieu d'un seul (formats pdf et md)",
+        (Lang::Fr, "subtitle_text") => "Afficher le texte OCR de chaque diapositive ou seulement son titre dans les sous-titres (formats srt et vtt)",
+        (Lang::Fr, "name_template") => "Chemin des sorties dans le dossier result, p. ex. \"{stem}/{date}/slide_{index:04}\" (formats pdf, md, img et rapport de relecture)",
+        (Lang::Fr, "log_level") => "Niveau de détail de la journalisation",
+        (Lang::Fr, "ui_lang") => "Langue de l'interface (aide, progression et résumé)",
+
+        _ => return None,
+    };
+    Some(help)
+}
+
+/// Translates a message into an explicit language.
+pub fn translate(lang: Lang, msg: Msg) -> &'static str {
+    use Msg::*;
+    match lang {
+        Lang::En => match msg {
+            About => "A command-line tool that converts video recordings of documents into searchable digital formats.",
+            AnalyzingFrames => "Analyzing frames",
+            FramesProcessed => "frames processed",
+            AnalyzedFrames => "Analyzed frames",
+            RunningOcr => "Running OCR",
+            OcrComplete => "OCR complete",
+            SummaryTitle => "Summary",
+            SummaryFramesAnalyzed => "Frames analyzed",
+            SummaryPagesKept => "Pages kept",
+            SummaryOutputFormat => "Output format",
+            SummaryOutputDir => "Output directory",
+            SummaryElapsed => "Elapsed time",
+            SummaryNoPages => "No unique frames were found; no document was written.",
+            DiffUnchanged => "Unchanged",
+            DiffChanged => "Changed",
+            DiffAdded => "Added",
+            DiffRemoved => "Removed",
+            DiffSkipped => "Skipped",
+            DownloadLanguagePacks => "Download the missing language packs",
+        },
+        Lang::Es => match msg {
+            About => "Herramienta de línea de comandos que convierte grabaciones en vídeo de documentos en formatos digitales con búsqueda.",
+            AnalyzingFrames => "Analizando fotogramas",
+            FramesProcessed => "fotogramas procesados",
+            AnalyzedFrames => "Fotogramas analizados",
+            RunningOcr => "Ejecutando OCR",
+            OcrComplete => "OCR completado",
+            SummaryTitle => "Resumen",
+            SummaryFramesAnalyzed => "Fotogramas analizados",
+            SummaryPagesKept => "Páginas conservadas",
+            SummaryOutputFormat => "Formato de salida",
+            SummaryOutputDir => "Directorio de salida",
+            SummaryElapsed => "Tiempo transcurrido",
+            SummaryNoPages => "No se encontraron fotogramas únicos; no se generó ningún documento.",
+            DiffUnchanged => "Sin cambios",
+            DiffChanged => "Modificada",
+            DiffAdded => "Añadida",
+            DiffRemoved => "Eliminada",
+            DiffSkipped => "Omitida",
+            DownloadLanguagePacks => "Descargar los paquetes de idioma que faltan",
+        },
+        Lang::Pt => match msg {
+            About => "Ferramenta de linha de comando que converte gravações em vídeo de documentos em formatos digitais pesquisáveis.",
+            AnalyzingFrames => "Analisando quadros",
+            FramesProcessed => "quadros processados",
+            AnalyzedFrames => "Quadros analisados",
+            RunningOcr => "Executando OCR",
+            OcrComplete => "OCR concluído",
+            SummaryTitle => "Resumo",
+            SummaryFramesAnalyzed => "Quadros analisados",
+            SummaryPagesKept => "Páginas mantidas",
+            SummaryOutputFormat => "Formato de saída",
+            SummaryOutputDir => "Diretório de saída",
+            SummaryElapsed => "Tempo decorrido",
+            SummaryNoPages => "Nenhum quadro único foi encontrado; nenhum documento foi gerado.",
+            DiffUnchanged => "Inalterada",
+            DiffChanged => "Alterada",
+            DiffAdded => "Adicionada",
+            DiffRemoved => "Removida",
+            DiffSkipped => "Ignorada",
+            DownloadLanguagePacks => "Baixar os pacotes de idioma que faltam",
+        },
+        Lang::De => match msg {
+            About => "Ein Kommandozeilenwerkzeug, das Videoaufnahmen von Dokumenten in durchsuchbare digitale Formate umwandelt.",
+            AnalyzingFrames => "Analysiere Bilder",
+            FramesProcessed => "Bilder verarbeitet",
+            AnalyzedFrames => "Bilder analysiert",
+            RunningOcr => "Führe OCR aus",
+            OcrComplete => "OCR abgeschlossen",
+            SummaryTitle => "Zusammenfassung",
+            SummaryFramesAnalyzed => "Analysierte Bilder",
+            SummaryPagesKept => "Behaltene Seiten",
+            SummaryOutputFormat => "Ausgabeformat",
+            SummaryOutputDir => "Ausgabeverzeichnis",
+            SummaryElapsed => "Verstrichene Zeit",
+            SummaryNoPages => "Keine eindeutigen Bilder gefunden; es wurde kein Dokument erstellt.",
+            DiffUnchanged => "Unverändert",
+            DiffChanged => "Geändert",
+            DiffAdded => "Hinzugefügt",
+            DiffRemoved => "Entfernt",
+            DiffSkipped => "Übersprungen",
+            DownloadLanguagePacks => "Fehlende Sprachpakete herunterladen",
+        },
+        Lang::Fr => match msg {
+            About => "Un outil en ligne de commande qui convertit des enregistrements vidéo de documents en formats numériques consultables.",
+            AnalyzingFrames => "Analyse des images",
+            FramesProcessed => "images traitées",
+            AnalyzedFrames => "Images analysées",
+            RunningOcr => "OCR en cours",
+            OcrComplete => "OCR terminé",
+            SummaryTitle => "Résumé",
+            SummaryFramesAnalyzed => "Images analysées",
+            SummaryPagesKept => "Pages conservées",
+            SummaryOutputFormat => "Format de sortie",
+            SummaryOutputDir => "Répertoire de sortie",
+            SummaryElapsed => "Temps écoulé",
+            SummaryNoPages => "Aucune image unique n'a été trouvée ; aucun document n'a été généré.",
+            DiffUnchanged => "Inchangée",
+            DiffChanged => "Modifiée",
+            DiffAdded => "Ajoutée",
+            DiffRemoved => "Supprimée",
+            DiffSkipped => "Ignorée",
+            DownloadLanguagePacks => "Télécharger les paquets de langue manquants",
+        },
+    }
+}
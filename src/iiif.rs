@@ -0,0 +1,198 @@
+//! IIIF Module
+//!
+//! Handles the `iiif` output format for archives and digital libraries: every
+//! page image is cut into static IIIF Image API 3.0 level-0 tiles with their
+//! `info.json`, and a IIIF Presentation API 3.0 manifest lists one canvas per
+//! page with its OCR text lines as annotations, so the recording can be browsed
+//! in any IIIF viewer once the folder is served from the base URL.
+
+use crate::document_builder::{self, Page};
+use crate::layout;
+use anyhow::{Context, Result};
+use rayon::prelude::*;
+use serde_json::{Value, json};
+use std::fs;
+use std::path::Path;
+
+/// Folder holding the manifest and the images, relative to the output directory.
+pub const IIIF_DIR: &str = "iiif";
+/// File name of the manifest inside [`IIIF_DIR`].
+pub const MANIFEST_NAME: &str = "manifest.json";
+/// Default URL the [`IIIF_DIR`] folder is expected to be served from.
+pub const DEFAULT_BASE_URL: &str = "http://localhost:8000";
+/// Width and height of the tiles, in pixels of the scaled image.
+pub const TILE_SIZE: u32 = 512;
+
+const PRESENTATION_CONTEXT: &str = "http://iiif.io/api/presentation/3/context.json";
+const IMAGE_CONTEXT: &str = "http://iiif.io/api/image/3/context.json";
+
+/// Options for the IIIF output.
+#[derive(Debug, Clone)]
+pub struct IiifOptions {
+    /// URL the [`IIIF_DIR`] folder will be served from; every IIIF id starts with it.
+    pub base_url: String,
+    /// Title of the whole document, the manifest label.
+    pub document_title: String,
+    /// File name of the input video.
+    pub source: String,
+}
+
+/// Folder of the image service of page `index`, relative to [`IIIF_DIR`].
+pub fn image_dir(index: usize) -> String {
+    format!("images/page_{:03}", index + 1)
+}
+
+/// Writes the tiles and `info.json` of every page and the manifest under
+/// `base_dir/iiif`.
+pub fn build_iiif(pages: &[Page], options: &IiifOptions, base_dir: &Path) -> Result<()> {
+    let iiif_dir = base_dir.join(IIIF_DIR);
+    let base_url = options.base_url.trim_end_matches('/');
+    pages.par_iter().enumerate().try_for_each(|(i, page)| -> Result<()> {
+        let service_id = format!("{}/{}", base_url, image_dir(i));
+        write_image_service(page, &service_id, &iiif_dir.join(image_dir(i)))
+    })?;
+
+    let manifest = manifest(pages, options, base_url);
+    let manifest_path = iiif_dir.join(MANIFEST_NAME);
+    fs::write(&manifest_path, serde_json::to_string_pretty(&manifest)?)
+        .with_context(|| format!("Failed to write IIIF manifest to {:?}", manifest_path))
+}
+
+/// Scale factors of the tile pyramid, from full size down to the level where
+/// the whole image fits in one tile.
+pub fn scale_factors(width: u32, height: u32) -> Vec<u32> {
+    let mut factors = vec![1];
+    while TILE_SIZE * factors.last().unwrap() < width.max(height) {
+        factors.push(factors.last().unwrap() * 2);
+    }
+    factors
+}
+
+/// Writes the level-0 tiles, the full image, and `info.json` of one page.
+fn write_image_service(page: &Page, service_id: &str, dir: &Path) -> Result<()> {
+    let image = page.image;
+    let (width, height) = image.dimensions();
+    let factors = scale_factors(width, height);
+
+    let write_jpeg = |path: String, region: &image::ImageBuffer<image::Rgb<u8>, Vec<u8>>| -> Result<()> {
+        let path = dir.join(path);
+        fs::create_dir_all(path.parent().unwrap_or(dir))
+            .with_context(|| format!("Failed to create IIIF directory for {:?}", path))?;
+        let jpeg = document_builder::encode_jpeg(region, document_builder::DEFAULT_JPEG_QUALITY)?;
+        fs::write(&path, jpeg).with_context(|| format!("Failed to write IIIF image {:?}", path))
+    };
+
+    // Full size, as both `max` and the explicit size listed in `sizes`.
+    write_jpeg("full/max/0/default.jpg".to_string(), image)?;
+    write_jpeg(format!("full/{},{}/0/default.jpg", width, height), image)?;
+
+    for &factor in &factors {
+        let region_size = TILE_SIZE * factor;
+        for y in (0..height).step_by(region_size as usize) {
+            for x in (0..width).step_by(region_size as usize) {
+                let (region_width, region_height) = (region_size.min(width - x), region_size.min(height - y));
+                let (tile_width, tile_height) = (region_width.div_ceil(factor), region_height.div_ceil(factor));
+                let region = image::imageops::crop_imm(image, x, y, region_width, region_height).to_image();
+                let tile = if factor == 1 {
+                    region
+                } else {
+                    image::imageops::resize(&region, tile_width, tile_height, image::imageops::FilterType::Triangle)
+                };
+                write_jpeg(
+                    format!(
+                        "{},{},{},{}/{},{}/0/default.jpg",
+                        x, y, region_width, region_height, tile_width, tile_height
+                    ),
+                    &tile,
+                )?;
+            }
+        }
+    }
+
+    let info = json!({
+        "@context": IMAGE_CONTEXT,
+        "id": service_id,
+        "type": "ImageService3",
+        "protocol": "http://iiif.io/api/image",
+        "profile": "level0",
+        "width": width,
+        "height": height,
+        "sizes": [{ "width": width, "height": height }],
+        "tiles": [{ "width": TILE_SIZE, "scaleFactors": factors }],
+    });
+    let info_path = dir.join("info.json");
+    fs::write(&info_path, serde_json::to_string_pretty(&info)?)
+        .with_context(|| format!("Failed to write {:?}", info_path))
+}
+
+/// Builds the Presentation API manifest: one canvas per page painted with its
+/// image, and its OCR lines as `supplementing` annotations targeting their boxes.
+fn manifest(pages: &[Page], options: &IiifOptions, base_url: &str) -> Value {
+    let canvases: Vec<Value> = pages
+        .iter()
+        .enumerate()
+        .map(|(i, page)| {
+            let (width, height) = page.image.dimensions();
+            let canvas_id = format!("{}/canvas/{}", base_url, i + 1);
+            let service_id = format!("{}/{}", base_url, image_dir(i));
+            let mut canvas = json!({
+                "id": canvas_id,
+                "type": "Canvas",
+                "label": { "none": [page.outline_label(i)] },
+                "width": width,
+                "height": height,
+                "items": [{
+                    "id": format!("{}/page", canvas_id),
+                    "type": "AnnotationPage",
+                    "items": [{
+                        "id": format!("{}/page/image", canvas_id),
+                        "type": "Annotation",
+                        "motivation": "painting",
+                        "body": {
+                            "id": format!("{}/full/max/0/default.jpg", service_id),
+                            "type": "Image",
+                            "format": "image/jpeg",
+                            "width": width,
+                            "height": height,
+                            "service": [{ "id": service_id, "type": "ImageService3", "profile": "level0" }],
+                        },
+                        "target": canvas_id,
+                    }],
+                }],
+            });
+            let lines = page.ocr.map(layout::group_lines).unwrap_or_default();
+            if !lines.is_empty() {
+                let annotations: Vec<Value> = lines
+                    .iter()
+                    .enumerate()
+                    .map(|(n, line)| {
+                        let (x1, y1, x2, y2) = line.bbox;
+                        json!({
+                            "id": format!("{}/ocr/{}", canvas_id, n + 1),
+                            "type": "Annotation",
+                            "motivation": "supplementing",
+                            "body": { "type": "TextualBody", "value": line.text, "format": "text/plain" },
+                            "target": format!("{}#xywh={},{},{},{}", canvas_id, x1, y1, x2 - x1, y2 - y1),
+                        })
+                    })
+                    .collect();
+                canvas["annotations"] = json!([{
+                    "id": format!("{}/ocr", canvas_id),
+                    "type": "AnnotationPage",
+                    "items": annotations,
+                }]);
+            }
+            canvas
+        })
+        .collect();
+
+    json!({
+        "@context": PRESENTATION_CONTEXT,
+        "id": format!("{}/{}", base_url, MANIFEST_NAME),
+        "type": "Manifest",
+        "label": { "none": [options.document_title] },
+        "metadata": [{ "label": { "en": ["Source"] }, "value": { "none": [options.source] } }],
+        "summary": { "en": [format!("{} pages captured from {}", pages.len(), options.source)] },
+        "items": canvases,
+    })
+}
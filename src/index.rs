@@ -0,0 +1,96 @@
+//! Index Module
+//!
+//! Handles the optional `index.json` manifest, which describes every kept frame
+//! and the files generated for it so downstream tooling can consume the results
+//! without parsing the documents themselves.
+
+use crate::document_builder::Page;
+use crate::frame_analyzer::Segment;
+use crate::postprocess::ProcessedText;
+use crate::transcript::Cue;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+/// The whole manifest.
+#[derive(Debug, Serialize)]
+pub struct Index {
+    /// File name of the input video.
+    pub source: String,
+    /// Output format the results were generated in.
+    pub format: String,
+    pub pages: Vec<IndexEntry>,
+}
+
+/// One kept frame and the output generated for it.
+#[derive(Debug, Serialize)]
+pub struct IndexEntry {
+    /// Page number in the generated output, starting at 1.
+    pub page: usize,
+    /// Decoding-order index of the frame in the video.
+    pub frame_index: usize,
+    /// When the frame appears in the video, in seconds, if the frame rate is known.
+    pub timestamp: Option<f64>,
+    pub title: Option<String>,
+    /// Files holding this page, relative to the index file.
+    pub outputs: Vec<String>,
+    pub word_count: usize,
+    /// Mean OCR confidence (0-100) of the page's words, if any were recognized.
+    pub mean_confidence: Option<f32>,
+    /// Transcript cues spoken while the page was on screen, if a transcript was used.
+    pub speech: Vec<Cue>,
+    /// Most distinctive OCR terms of the page, if tags were requested.
+    pub tags: Vec<String>,
+    /// OCR text after post-processing, if a post-processor was used.
+    pub clean_text: Option<String>,
+    /// Summary of the page written by the post-processor, if requested.
+    pub summary: Option<String>,
+    /// The page's OCR text translated by the translation stage, if one was used.
+    pub translation: Option<String>,
+    /// When the page's slide was shown again later, in seconds, with `--revisits merge`.
+    pub revisits: Vec<f64>,
+    /// The run of frames the page was on screen for.
+    pub segment: Option<Segment>,
+}
+
+impl IndexEntry {
+    /// Describes page `i`, kept from frame `frame_index` and written to `outputs`,
+    /// with the transcript cues aligned to it, its tags, and its post-processed text.
+    pub fn new(
+        i: usize,
+        page: &Page,
+        frame_index: usize,
+        outputs: Vec<String>,
+        speech: Vec<Cue>,
+        tags: Vec<String>,
+        processed: Option<ProcessedText>,
+    ) -> Self {
+        let (clean_text, summary) = match processed {
+            Some(processed) => (Some(processed.clean_text), processed.summary),
+            None => (None, None),
+        };
+        Self {
+            page: i + 1,
+            frame_index,
+            timestamp: page.timestamp,
+            title: page.title.clone(),
+            outputs,
+            word_count: page.word_count(),
+            mean_confidence: page.mean_confidence(),
+            speech,
+            tags,
+            clean_text,
+            summary,
+            translation: None,
+            revisits: Vec::new(),
+            segment: None,
+        }
+    }
+}
+
+/// Writes the manifest as pretty-printed JSON.
+pub fn write_index(index: &Index, path: &Path) -> Result<()> {
+    let json = serde_json::to_string_pretty(index).context("Failed to serialize index")?;
+    fs::write(path, json).with_context(|| format!("Failed to write index to {:?}", path))
+}
@@ -0,0 +1,166 @@
+//! Keywords Module
+//!
+//! Handles the back-of-document keyword index: the significant terms of the
+//! OCR text, in alphabetical order, each with the pages it appears on. Short
+//! words, numbers, common function words, and terms found on most pages (such
+//! as a running header) are left out. The same terms, ranked by TF-IDF, also
+//! give every page a few tags for navigating long lectures.
+
+use crate::document_builder::Page;
+use std::collections::{BTreeSet, HashMap};
+
+// Terms shorter than this are rarely worth looking up.
+const MIN_TERM_CHARS: usize = 4;
+// A term must occur at least this often across the document.
+const MIN_OCCURRENCES: usize = 2;
+// Words recognized below this confidence are too unreliable to index.
+const MIN_CONFIDENCE: f32 = 50.0;
+// Upper bound on the number of entries, keeping the most frequent terms.
+const MAX_TERMS: usize = 300;
+// Documents with at least this many pages drop terms found on more than half of them.
+const MIN_PAGES_FOR_RATIO: usize = 4;
+// Upper bound on the number of tags of a single page.
+const MAX_PAGE_TAGS: usize = 5;
+
+// Frequent words of the OCR languages the CLI offers translations for.
+const STOPWORDS: &[&str] = &[
+    // English
+    "about", "after", "also", "been", "before", "being", "both", "could", "does", "each", "from",
+    "have", "here", "into", "just", "more", "most", "much", "must", "only", "other", "over", "same",
+    "should", "some", "such", "than", "that", "their", "them", "then", "there", "these", "they",
+    "this", "those", "through", "very", "were", "what", "when", "where", "which", "while", "will",
+    "with", "would", "your",
+    // Spanish and Portuguese
+    "como", "este", "esta", "esto", "estos", "estas", "para", "pero", "porque", "sobre", "todo",
+    "todos", "unos", "unas", "entre", "cuando", "donde", "também", "mais", "isso", "isto", "esse",
+    "essa", "pelo", "pela", "seus", "suas", "umas", "depois", "antes", "ainda", "quando", "onde",
+    // German
+    "aber", "auch", "dann", "dass", "dies", "diese", "dieser", "durch", "eine", "einem", "einen",
+    "einer", "eines", "haben", "hier", "immer", "noch", "nicht", "oder", "sein", "sich", "sind",
+    "über", "unter", "werden", "wird", "wenn",
+    // French
+    "alors", "aussi", "avec", "cette", "comme", "dans", "depuis", "donc", "elle", "elles", "leur",
+    "leurs", "mais", "nous", "pour", "sans", "sont", "sous", "tous", "tout", "toute", "toutes",
+    "très", "vous", "être", "avoir",
+];
+
+/// One entry of the index.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeywordEntry {
+    /// The term as it is most often written.
+    pub term: String,
+    /// Indices of the pages it appears on, in order.
+    pub pages: Vec<usize>,
+}
+
+/// Collects the significant terms of the pages' OCR text, sorted alphabetically.
+pub fn build_keyword_index(pages: &[Page]) -> Vec<KeywordEntry> {
+    struct Term {
+        pages: BTreeSet<usize>,
+        count: usize,
+        forms: HashMap<String, usize>,
+    }
+    let mut terms: HashMap<String, Term> = HashMap::new();
+    for (i, page) in pages.iter().enumerate() {
+        for (key, form) in page_terms(page) {
+            let term = terms
+                .entry(key)
+                .or_insert_with(|| Term { pages: BTreeSet::new(), count: 0, forms: HashMap::new() });
+            term.pages.insert(i);
+            term.count += 1;
+            *term.forms.entry(form.to_string()).or_default() += 1;
+        }
+    }
+
+    let mut kept: Vec<(String, Term)> = terms
+        .into_iter()
+        .filter(|(_, term)| term.count >= MIN_OCCURRENCES && !too_common(term.pages.len(), pages.len()))
+        .collect();
+    kept.sort_by(|a, b| b.1.count.cmp(&a.1.count).then_with(|| a.0.cmp(&b.0)));
+    kept.truncate(MAX_TERMS);
+    kept.sort_by(|a, b| a.0.cmp(&b.0));
+
+    kept.into_iter()
+        .map(|(key, term)| {
+            // The most frequent spelling, ties going to the lowercase one.
+            let term_text = term
+                .forms
+                .into_iter()
+                .max_by(|a, b| {
+                    a.1.cmp(&b.1)
+                        .then_with(|| (a.0 == key).cmp(&(b.0 == key)))
+                        .then_with(|| b.0.cmp(&a.0))
+                })
+                .map(|(form, _)| form)
+                .unwrap_or(key);
+            KeywordEntry { term: term_text, pages: term.pages.into_iter().collect() }
+        })
+        .collect()
+}
+
+/// Picks up to five tags for every page: its terms weighted by TF-IDF, so that
+/// words frequent on the page but rare in the rest of the document come first.
+/// Tags are lowercase and follow the same rules as the keyword index, except
+/// that a term seen once on a single page still qualifies.
+pub fn page_tags(pages: &[Page]) -> Vec<Vec<String>> {
+    let page_counts: Vec<HashMap<String, usize>> = pages
+        .iter()
+        .map(|page| {
+            let mut counts = HashMap::new();
+            for (key, _) in page_terms(page) {
+                *counts.entry(key).or_default() += 1;
+            }
+            counts
+        })
+        .collect();
+    let mut document_frequency: HashMap<&str, usize> = HashMap::new();
+    for counts in &page_counts {
+        for key in counts.keys() {
+            *document_frequency.entry(key).or_default() += 1;
+        }
+    }
+
+    let page_total = pages.len() as f64;
+    page_counts
+        .iter()
+        .map(|counts| {
+            let total: usize = counts.values().sum();
+            let mut scored: Vec<(&str, f64)> = counts
+                .iter()
+                .filter(|(key, _)| !too_common(document_frequency[key.as_str()], pages.len()))
+                .map(|(key, &count)| {
+                    // Smoothed IDF, so a single-page document still ranks by frequency.
+                    let idf = ((1.0 + page_total) / (1.0 + document_frequency[key.as_str()] as f64)).ln() + 1.0;
+                    (key.as_str(), count as f64 / total as f64 * idf)
+                })
+                .collect();
+            scored.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+            scored.into_iter().take(MAX_PAGE_TAGS).map(|(key, _)| key.to_string()).collect()
+        })
+        .collect()
+}
+
+/// Candidate terms of a page's OCR text, as lowercase key and written form.
+fn page_terms<'a>(page: &Page<'a>) -> Vec<(String, &'a str)> {
+    let Some(ocr) = page.ocr else {
+        return Vec::new();
+    };
+    ocr.words
+        .iter()
+        .filter(|word| word.confidence >= MIN_CONFIDENCE)
+        .filter_map(|word| {
+            let form = word.text.trim_matches(|c: char| !c.is_alphanumeric());
+            if form.chars().count() < MIN_TERM_CHARS || !form.chars().any(char::is_alphabetic) {
+                return None;
+            }
+            let key = form.to_lowercase();
+            (!STOPWORDS.contains(&key.as_str())).then_some((key, form))
+        })
+        .collect()
+}
+
+/// Whether a term found on `term_pages` of `total_pages` is too common to
+/// tell the pages apart, such as a running header.
+fn too_common(term_pages: usize, total_pages: usize) -> bool {
+    total_pages >= MIN_PAGES_FOR_RATIO && term_pages * 2 > total_pages
+}
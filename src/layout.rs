@@ -0,0 +1,662 @@
+//! Layout Module
+//!
+//! Handles reconstructing text structure from the words produced by OCR:
+//! grouping words into lines and paragraphs, picking a title for each page,
+//! telling headings apart from body text by their size, and telling code set
+//! in a monospaced font and tables apart from prose. Lines and paragraphs come
+//! from the engine when it found them, and are rebuilt from the word boxes
+//! otherwise, reading side-by-side columns one after the other.
+
+use crate::formulas::{self, Formula};
+use crate::ocr::{self, OcrFrameResult, OcrWord};
+
+// Lines below this average confidence are too unreliable to use as a title.
+const MIN_TITLE_CONFIDENCE: f32 = 60.0;
+// Longer lines are almost always body text rather than a heading.
+const MAX_TITLE_CHARS: usize = 80;
+// Lines whose height is within this fraction of the tallest line count as equally large.
+const TITLE_HEIGHT_TOLERANCE: f32 = 0.1;
+// Lines at least this many times as tall as the body text are top-level headings.
+const HEADING_1_RATIO: f32 = 1.6;
+// Lines at least this many times as tall as the body text are second-level headings.
+const HEADING_2_RATIO: f32 = 1.25;
+// Share of a line's characters that must be code punctuation for it to start a code block.
+const MIN_CODE_SYMBOL_RATIO: f32 = 0.08;
+// Fewest lines a code block has; a single symbol-laden line is usually a formula or a URL.
+const MIN_CODE_LINES: usize = 2;
+// Largest spread (coefficient of variation) of the per-character width of the
+// words of a code block; proportional fonts vary much more.
+const MAX_CODE_WIDTH_SPREAD: f32 = 0.15;
+// Largest difference, as a fraction, between the per-character width of a word
+// joining a code block and the block's.
+const MAX_CODE_WIDTH_DEVIATION: f32 = 0.15;
+// Shortest word whose box width says anything about its font; OCR boxes hug the
+// ink, so `i` or `}` are narrower than their advance.
+const MIN_MEASURED_CHARS: usize = 3;
+// Gap between words, relative to the line height, that separates table cells;
+// spaces between words are well under half the line height.
+const MIN_CELL_GAP_RATIO: f32 = 1.5;
+// Fewest rows a table has.
+const MIN_TABLE_ROWS: usize = 3;
+// Narrowest gap between columns, relative to the median word height.
+const MIN_COLUMN_GAP_RATIO: f32 = 1.0;
+// Fewest lines in a column.
+const MIN_COLUMN_LINES: usize = 2;
+// Fewest words per line, on average, in a column; the columns of a table hold
+// a word or two per row and are read row by row.
+const MIN_COLUMN_WORDS_PER_LINE: f32 = 2.0;
+// Characters common in source code and rare in prose.
+const CODE_SYMBOLS: &[char] = &['{', '}', '[', ']', '(', ')', '<', '>', '=', ';', '_', '|', '&', '*', '\\', '$', '"'];
+
+/// A line of text reconstructed from OCR words.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OcrLine {
+    pub text: String,
+    /// Bounding box covering every word in the line: (x1, y1, x2, y2)
+    pub bbox: (i32, i32, i32, i32),
+    /// Mean confidence of the words in the line.
+    pub confidence: f32,
+    /// Whether the line runs top to bottom, as vertical Chinese and Japanese do.
+    pub vertical: bool,
+}
+
+impl OcrLine {
+    /// Size of the line's characters: the height of its box, or the width
+    /// when it runs top to bottom.
+    pub fn height(&self) -> i32 {
+        if self.vertical { self.bbox.2 - self.bbox.0 } else { self.bbox.3 - self.bbox.1 }
+    }
+
+    fn from_words(words: &[&OcrWord]) -> Self {
+        let words = logical_order(words);
+        let text = join_words(words.iter().map(|w| w.text.as_str()));
+        let bbox = words.iter().fold((i32::MAX, i32::MAX, i32::MIN, i32::MIN), |acc, w| {
+            (
+                acc.0.min(w.bbox.0),
+                acc.1.min(w.bbox.1),
+                acc.2.max(w.bbox.2),
+                acc.3.max(w.bbox.3),
+            )
+        });
+        let confidence = words.iter().map(|w| w.confidence).sum::<f32>() / words.len() as f32;
+        let vertical = ocr::runs_down(&text, bbox);
+        Self { text, bbox, confidence, vertical }
+    }
+}
+
+/// Whether a character belongs to a script written without spaces between
+/// words: Chinese characters, Japanese kana, and their punctuation and
+/// full-width forms. Korean separates words with spaces, so Hangul does not.
+pub fn is_cjk(c: char) -> bool {
+    matches!(
+        c,
+        '\u{3000}'..='\u{30FF}'
+            | '\u{3400}'..='\u{4DBF}'
+            | '\u{4E00}'..='\u{9FFF}'
+            | '\u{F900}'..='\u{FAFF}'
+            | '\u{FF00}'..='\u{FFEF}'
+            | '\u{20000}'..='\u{2FA1F}'
+    )
+}
+
+/// Whether a character belongs to a script written right to left: Hebrew,
+/// Arabic, Syriac, Thaana, and their presentation forms.
+fn is_rtl_char(c: char) -> bool {
+    matches!(c, '\u{0590}'..='\u{08FF}' | '\u{FB1D}'..='\u{FDFF}' | '\u{FE70}'..='\u{FEFF}')
+}
+
+/// Whether text is written right to left: its Hebrew and Arabic letters
+/// outnumber its letters of other scripts, so that a Latin name or a number
+/// in an Arabic sentence leaves it right to left.
+pub fn is_rtl(text: &str) -> bool {
+    let (rtl, ltr) = text
+        .chars()
+        .filter(|c| c.is_alphabetic())
+        .fold((0, 0), |(rtl, ltr), c| if is_rtl_char(c) { (rtl + 1, ltr) } else { (rtl, ltr + 1) });
+    rtl > ltr
+}
+
+/// Puts the words of a line in the order they are read: right to left when
+/// the line is, whichever order they were found in, and as given otherwise.
+pub fn logical_order<'a>(words: &[&'a OcrWord]) -> Vec<&'a OcrWord> {
+    let mut words = words.to_vec();
+    if is_rtl(&join_words(words.iter().map(|w| w.text.as_str()))) {
+        words.sort_by_key(|w| std::cmp::Reverse(w.bbox.2));
+    }
+    words
+}
+
+/// Joins the words of a line with spaces, except between Chinese or Japanese
+/// characters, which OCR splits into words that were never spaced apart.
+pub fn join_words<'a>(words: impl IntoIterator<Item = &'a str>) -> String {
+    let mut text = String::new();
+    for word in words {
+        let spaced = match (text.chars().last(), word.chars().next()) {
+            (Some(last), Some(first)) => !(is_cjk(last) && is_cjk(first)),
+            _ => false,
+        };
+        if spaced {
+            text.push(' ');
+        }
+        text.push_str(word);
+    }
+    text
+}
+
+/// Groups the words of a frame into lines, in reading order.
+///
+/// Without the engine's lines, the words are split into regions by
+/// [`group_regions`], and the lines of each region are grouped by
+/// [`group_words_into_lines`].
+pub fn group_lines(ocr: &OcrFrameResult) -> Vec<OcrLine> {
+    group_line_words(ocr)
+        .iter()
+        .map(|line| OcrLine::from_words(line))
+        .collect()
+}
+
+/// Groups the words of a frame into lines like [`group_lines`], keeping the
+/// words of each line in reading order.
+pub fn group_line_words(ocr: &OcrFrameResult) -> Vec<Vec<&OcrWord>> {
+    match engine_paragraphs(ocr) {
+        Some(paragraphs) => paragraphs.into_iter().flatten().collect(),
+        None => group_regions(ocr.words.iter())
+            .into_iter()
+            .flat_map(|region| group_words_into_lines(region.into_iter()))
+            .collect(),
+    }
+}
+
+/// Groups the words of a frame into the paragraphs of lines the engine found,
+/// across all its blocks in reading order, or `None` when it found no lines.
+pub fn engine_paragraphs(ocr: &OcrFrameResult) -> Option<Vec<Vec<Vec<&OcrWord>>>> {
+    if ocr.blocks.is_empty() {
+        return None;
+    }
+    let paragraphs = ocr
+        .blocks
+        .iter()
+        .flat_map(|block| &block.paragraphs)
+        .map(|paragraph| {
+            paragraph
+                .lines
+                .iter()
+                .map(|line| line.words.iter().filter_map(|&i| ocr.words.get(i)).collect::<Vec<_>>())
+                .filter(|line| !line.is_empty())
+                .collect::<Vec<_>>()
+        })
+        .filter(|paragraph| !paragraph.is_empty())
+        .collect();
+    Some(paragraphs)
+}
+
+/// Splits any selection of words into regions, in reading order: columns left
+/// to right, with the parts above and below them that span the columns, such
+/// as a title, on their own. Words that are not in columns make one region.
+///
+/// Columns are cut at the widest vertical gap, at least as wide as the median
+/// word height, that leaves at least two lines of two or more words on average
+/// on each side, so that table columns are not read one after the other. When
+/// text spans the gap, the run of rows between horizontal gaps with the most
+/// words in columns is cut out first.
+pub fn group_regions<'a>(words: impl Iterator<Item = &'a OcrWord>) -> Vec<Vec<&'a OcrWord>> {
+    let words: Vec<&OcrWord> = words.collect();
+    let mut heights: Vec<i32> = words.iter().map(|word| word.bbox.3 - word.bbox.1).collect();
+    heights.sort_unstable();
+    let Some(&height) = heights.get(heights.len() / 2) else {
+        return Vec::new();
+    };
+    let mut regions = Vec::new();
+    split_regions(words, height as f32 * MIN_COLUMN_GAP_RATIO, &mut regions);
+    regions
+}
+
+/// Appends the regions of `words` to `regions`, as described in [`group_regions`].
+fn split_regions<'a>(words: Vec<&'a OcrWord>, min_gap: f32, regions: &mut Vec<Vec<&'a OcrWord>>) {
+    if let Some((left, right)) = split_columns(&words, min_gap) {
+        split_regions(left, min_gap, regions);
+        split_regions(right, min_gap, regions);
+        return;
+    }
+
+    // Text spanning the gap, such as a title or a footer, hides the columns:
+    // find the run of rows with the most words in columns, and read it
+    // between the rows above and below it.
+    let cuts = row_gaps(&words);
+    let rows: Vec<usize> = words.iter().map(|word| cuts.iter().filter(|&&y| word.bbox.3 > y).count()).collect();
+    let mut best: Option<(usize, usize, usize)> = None;
+    for first in 0..=cuts.len() {
+        for last in first..=cuts.len() {
+            if (first, last) == (0, cuts.len()) {
+                continue;
+            }
+            let run: Vec<&OcrWord> = words
+                .iter()
+                .zip(&rows)
+                .filter(|(_, row)| (first..=last).contains(row))
+                .map(|(word, _)| *word)
+                .collect();
+            if best.is_none_or(|(count, ..)| run.len() > count) && split_columns(&run, min_gap).is_some() {
+                best = Some((run.len(), first, last));
+            }
+        }
+    }
+    let Some((_, first, last)) = best else {
+        regions.push(words);
+        return;
+    };
+    let (mut above, mut run, mut below) = (Vec::new(), Vec::new(), Vec::new());
+    for (word, row) in words.into_iter().zip(rows) {
+        match row {
+            row if row < first => above.push(word),
+            row if row > last => below.push(word),
+            _ => run.push(word),
+        }
+    }
+    for part in [above, run, below] {
+        if !part.is_empty() {
+            split_regions(part, min_gap, regions);
+        }
+    }
+}
+
+/// Splits words into a left and a right column at the widest vertical gap that
+/// makes both sides columns.
+fn split_columns<'a>(words: &[&'a OcrWord], min_gap: f32) -> Option<(Vec<&'a OcrWord>, Vec<&'a OcrWord>)> {
+    let mut spans: Vec<(i32, i32)> = words.iter().map(|word| (word.bbox.0, word.bbox.2)).collect();
+    spans.sort_unstable();
+    let mut gaps = Vec::new();
+    let mut right = spans.first()?.1;
+    for &(x1, x2) in &spans[1..] {
+        if (x1 - right) as f32 >= min_gap {
+            gaps.push((x1 - right, x1));
+        }
+        right = right.max(x2);
+    }
+    gaps.sort_unstable_by(|a, b| b.cmp(a));
+    gaps.into_iter().find_map(|(_, x)| {
+        let (left, right): (Vec<&OcrWord>, Vec<&OcrWord>) = words.iter().partition(|word| word.bbox.2 < x);
+        (is_column(&left) && is_column(&right)).then_some((left, right))
+    })
+}
+
+/// Whether words are enough lines of running text to be a column.
+fn is_column(words: &[&OcrWord]) -> bool {
+    let lines = group_words_into_lines(words.iter().copied()).len();
+    lines >= MIN_COLUMN_LINES && words.len() as f32 / lines as f32 >= MIN_COLUMN_WORDS_PER_LINE
+}
+
+/// The heights at which words can be cut across: the bottom of each run of
+/// vertically overlapping words that has more words below it.
+fn row_gaps(words: &[&OcrWord]) -> Vec<i32> {
+    let mut spans: Vec<(i32, i32)> = words.iter().map(|word| (word.bbox.1, word.bbox.3)).collect();
+    spans.sort_unstable();
+    let mut gaps = Vec::new();
+    let Some(&(_, mut bottom)) = spans.first() else {
+        return gaps;
+    };
+    for &(y1, y2) in &spans[1..] {
+        if y1 > bottom {
+            gaps.push(bottom);
+        }
+        bottom = bottom.max(y2);
+    }
+    gaps
+}
+
+/// Groups any selection of words into lines, ordered top to bottom and left to
+/// right: a word joins the current line when its vertical center falls inside
+/// the line's vertical extent, and otherwise starts a new line.
+pub fn group_words_into_lines<'a>(words: impl Iterator<Item = &'a OcrWord>) -> Vec<Vec<&'a OcrWord>> {
+    let mut words: Vec<&OcrWord> = words.collect();
+    words.sort_by_key(|w| (w.bbox.1, w.bbox.0));
+
+    let mut lines: Vec<Vec<&OcrWord>> = Vec::new();
+    let mut extent = (0, 0);
+    for word in words {
+        let center = (word.bbox.1 + word.bbox.3) / 2;
+        match lines.last_mut() {
+            Some(line) if center >= extent.0 && center <= extent.1 => {
+                line.push(word);
+                extent = (extent.0.min(word.bbox.1), extent.1.max(word.bbox.3));
+            }
+            _ => {
+                lines.push(vec![word]);
+                extent = (word.bbox.1, word.bbox.3);
+            }
+        }
+    }
+
+    for line in &mut lines {
+        line.sort_by_key(|w| w.bbox.0);
+    }
+    lines
+}
+
+/// Returns the page text with one reconstructed line per text line.
+pub fn page_text(ocr: &OcrFrameResult) -> String {
+    group_lines(ocr)
+        .into_iter()
+        .map(|line| line.text)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Height of the body text among `lines`: the lower median line height, so a
+/// few large headings do not raise it.
+pub fn body_height(lines: &[OcrLine]) -> Option<i32> {
+    let mut heights: Vec<i32> = lines.iter().map(OcrLine::height).collect();
+    heights.sort_unstable();
+    heights.get(heights.len().checked_sub(1)? / 2).copied().filter(|&height| height > 0)
+}
+
+/// Heading level of a line relative to the body text: 1 for lines much taller
+/// than `body_height`, 2 for moderately taller ones, and `None` for body text.
+/// Lines too long or too unreliable to be a title are never headings.
+pub fn heading_level(line: &OcrLine, body_height: i32) -> Option<usize> {
+    let ratio = line.height() as f32 / body_height as f32;
+    match ratio {
+        _ if !is_title_like(line) => None,
+        ratio if ratio >= HEADING_1_RATIO => Some(1),
+        ratio if ratio >= HEADING_2_RATIO => Some(2),
+        _ => None,
+    }
+}
+
+/// A run of consecutive lines of a page.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TextBlock {
+    /// Lines of ordinary text.
+    Prose(Vec<OcrLine>),
+    /// Lines of code set in a monospaced font, with the indentation and
+    /// spacing of the slide rebuilt as spaces.
+    Code(Vec<String>),
+    /// Rows of a table, the first usually its header; every row has the same
+    /// number of cells, empty where the slide left one blank.
+    Table(Vec<Vec<String>>),
+    /// Lines of a formula, set apart by [`group_blocks_with_formulas`].
+    Formula(Formula),
+}
+
+/// Groups the lines of a frame into prose, code, and table blocks, in reading order.
+/// Prose blocks follow the engine's paragraphs when it found them, else the
+/// regions of [`group_regions`].
+///
+/// A code block starts from lines dense in code punctuation and takes in the
+/// neighbouring lines set in the same monospaced font, such as `return x`, or
+/// indented further than it. It needs at least two lines whose words all have
+/// about the same width per character. Other runs of at least three lines split
+/// into cells by wide gaps become a table when their cells line up in columns.
+pub fn group_blocks(ocr: &OcrFrameResult) -> Vec<TextBlock> {
+    let (lines, paragraphs) = block_lines(ocr);
+    let mut blocks = Vec::new();
+    push_blocks(&mut blocks, &lines, &paragraphs);
+    blocks
+}
+
+/// Groups the lines of a frame like [`group_blocks`], setting runs of lines
+/// that look like formulas (see [`formulas::is_formula`]) apart as formula
+/// blocks, with the lines between them grouped as usual.
+pub fn group_blocks_with_formulas(ocr: &OcrFrameResult) -> Vec<TextBlock> {
+    let (lines, paragraphs) = block_lines(ocr);
+    let is_formula: Vec<bool> = lines.iter().map(|line| formulas::is_formula(line)).collect();
+    let mut blocks = Vec::new();
+    let mut start = 0;
+    while start < lines.len() {
+        let end = (start..lines.len()).find(|&i| is_formula[i] != is_formula[start]).unwrap_or(lines.len());
+        if is_formula[start] {
+            blocks.push(TextBlock::Formula(Formula::from_lines(&lines[start..end])));
+        } else {
+            push_blocks(&mut blocks, &lines[start..end], &paragraphs[start..end]);
+        }
+        start = end;
+    }
+    blocks
+}
+
+/// The lines of a frame in reading order, each with the index of its
+/// paragraph. Without the engine's paragraphs, the lines of each region
+/// belong to one.
+fn block_lines(ocr: &OcrFrameResult) -> (Vec<Vec<&OcrWord>>, Vec<usize>) {
+    let paragraphs = engine_paragraphs(ocr).unwrap_or_else(|| {
+        group_regions(ocr.words.iter()).into_iter().map(|region| group_words_into_lines(region.into_iter())).collect()
+    });
+    paragraphs
+        .into_iter()
+        .enumerate()
+        .flat_map(|(paragraph, lines)| lines.into_iter().map(move |line| (line, paragraph)))
+        .unzip()
+}
+
+/// Appends lines as code, table, and prose blocks. `paragraphs[i]` is the
+/// paragraph of `lines[i]`.
+fn push_blocks(blocks: &mut Vec<TextBlock>, lines: &[Vec<&OcrWord>], paragraphs: &[usize]) {
+    let mut is_code: Vec<bool> = lines.iter().map(|line| code_symbol_ratio(line) >= MIN_CODE_SYMBOL_RATIO).collect();
+
+    // Grow every run of code-like lines over adjacent lines in the same font.
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for i in 0..lines.len() {
+            if is_code[i] {
+                continue;
+            }
+            let neighbours = [i.checked_sub(1), Some(i + 1)];
+            let joins = neighbours.into_iter().flatten().filter(|&j| j < lines.len() && is_code[j]).any(|j| {
+                let run = &lines[code_run(&is_code, j)];
+                let Some(width) = median_char_width(run) else {
+                    return false;
+                };
+                let measured: Vec<f32> =
+                    lines[i].iter().filter(|word| measurable(word)).map(|word| char_width(word)).collect();
+                if measured.is_empty() {
+                    let left = run.iter().flatten().map(|word| word.bbox.0).min().unwrap_or(0);
+                    return lines[i].first().is_some_and(|word| word.bbox.0 as f32 >= left as f32 + width);
+                }
+                measured.iter().all(|w| (w - width).abs() <= width * MAX_CODE_WIDTH_DEVIATION)
+            });
+            if joins {
+                is_code[i] = true;
+                changed = true;
+            }
+        }
+    }
+
+    // Lines between code blocks are laid out together, so that a lone
+    // code-like line does not split a table.
+    let mut plain_start = 0;
+    let mut start = 0;
+    while start < lines.len() {
+        let end = (start..lines.len()).find(|&i| is_code[i] != is_code[start]).unwrap_or(lines.len());
+        let run = &lines[start..end];
+        let words: Vec<&OcrWord> = run.iter().flatten().copied().collect();
+        let code = is_code[start] && run.len() >= MIN_CODE_LINES && width_spread(&words) <= MAX_CODE_WIDTH_SPREAD;
+        if code {
+            push_prose_and_tables(blocks, &lines[plain_start..start], &paragraphs[plain_start..start]);
+            blocks.push(TextBlock::Code(code_lines(run)));
+            plain_start = end;
+        }
+        start = end;
+    }
+    push_prose_and_tables(blocks, &lines[plain_start..], &paragraphs[plain_start..]);
+}
+
+/// Appends lines that are not code, as tables where runs of them line up in
+/// columns and as prose elsewhere, starting a new prose block with every
+/// paragraph. `paragraphs[i]` is the paragraph of `lines[i]`.
+fn push_prose_and_tables(blocks: &mut Vec<TextBlock>, lines: &[Vec<&OcrWord>], paragraphs: &[usize]) {
+    let is_row: Vec<bool> = lines.iter().map(|line| cells(line).len() >= 2).collect();
+    let mut start = 0;
+    while start < lines.len() {
+        let end = (start..lines.len()).find(|&i| is_row[i] != is_row[start]).unwrap_or(lines.len());
+        let run = &lines[start..end];
+        let table = (is_row[start] && run.len() >= MIN_TABLE_ROWS).then(|| table_rows(run)).flatten();
+        match table {
+            Some(rows) => blocks.push(TextBlock::Table(rows)),
+            None => {
+                for i in start..end {
+                    let line = OcrLine::from_words(&lines[i]);
+                    let continues = i > 0 && paragraphs[i - 1] == paragraphs[i];
+                    match blocks.last_mut() {
+                        Some(TextBlock::Prose(previous)) if continues => previous.push(line),
+                        _ => blocks.push(TextBlock::Prose(vec![line])),
+                    }
+                }
+            }
+        }
+        start = end;
+    }
+}
+
+/// Splits a line into cells at gaps much wider than a space, as the cell's
+/// horizontal extent and text, from left to right.
+fn cells(line: &[&OcrWord]) -> Vec<(i32, i32, String)> {
+    let height = line.iter().map(|word| word.bbox.3 - word.bbox.1).max().unwrap_or(0);
+    let mut words = line.to_vec();
+    words.sort_by_key(|word| word.bbox.0);
+    let mut cells: Vec<(i32, i32, Vec<&OcrWord>)> = Vec::new();
+    for word in words {
+        match cells.last_mut() {
+            Some(cell) if ((word.bbox.0 - cell.1) as f32) < height as f32 * MIN_CELL_GAP_RATIO => {
+                cell.1 = cell.1.max(word.bbox.2);
+                cell.2.push(word);
+            }
+            _ => cells.push((word.bbox.0, word.bbox.2, vec![word])),
+        }
+    }
+    cells
+        .into_iter()
+        .map(|(x1, x2, words)| (x1, x2, join_words(logical_order(&words).iter().map(|w| w.text.as_str()))))
+        .collect()
+}
+
+/// Lays out the cells of lines as a grid, or returns `None` when they do not
+/// line up: the columns are the merged extents of all cells, and no row may
+/// have two cells in one column. Cells a row lacks are left empty. The
+/// columns of right-to-left tables are listed from the right.
+fn table_rows(lines: &[Vec<&OcrWord>]) -> Option<Vec<Vec<String>>> {
+    let rows: Vec<Vec<(i32, i32, String)>> = lines.iter().map(|line| cells(line)).collect();
+    let mut extents: Vec<(i32, i32)> = rows.iter().flatten().map(|&(x1, x2, _)| (x1, x2)).collect();
+    extents.sort_unstable();
+    let mut columns: Vec<(i32, i32)> = Vec::new();
+    for (x1, x2) in extents {
+        match columns.last_mut() {
+            Some(column) if x1 <= column.1 => column.1 = column.1.max(x2),
+            _ => columns.push((x1, x2)),
+        }
+    }
+    if columns.len() < 2 {
+        return None;
+    }
+
+    let mut grid = Vec::with_capacity(rows.len());
+    for row in rows {
+        let mut cells = vec![String::new(); columns.len()];
+        for (x1, _, text) in row {
+            let column = columns.iter().position(|&(start, end)| x1 >= start && x1 <= end)?;
+            if !cells[column].is_empty() {
+                return None;
+            }
+            cells[column] = text;
+        }
+        grid.push(cells);
+    }
+    if is_rtl(&grid.concat().join(" ")) {
+        grid.iter_mut().for_each(|row| row.reverse());
+    }
+    Some(grid)
+}
+
+/// Range of the run of code-like lines containing line `index`.
+fn code_run(is_code: &[bool], index: usize) -> std::ops::Range<usize> {
+    let start = (0..index).rev().take_while(|&i| is_code[i]).last().unwrap_or(index);
+    let end = (index..is_code.len()).find(|&i| !is_code[i]).unwrap_or(is_code.len());
+    start..end
+}
+
+/// Share of the non-space characters of a line that are code punctuation.
+fn code_symbol_ratio(line: &[&OcrWord]) -> f32 {
+    let chars: Vec<char> = line.iter().flat_map(|word| word.text.chars()).filter(|c| !c.is_whitespace()).collect();
+    if chars.is_empty() {
+        return 0.0;
+    }
+    chars.iter().filter(|c| CODE_SYMBOLS.contains(c)).count() as f32 / chars.len() as f32
+}
+
+/// Whether a word is long enough for its width to tell the font.
+fn measurable(word: &OcrWord) -> bool {
+    word.text.chars().count() >= MIN_MEASURED_CHARS
+}
+
+/// Median width per character of the measurable words of some lines.
+fn median_char_width(lines: &[Vec<&OcrWord>]) -> Option<f32> {
+    let mut widths: Vec<f32> =
+        lines.iter().flatten().filter(|word| measurable(word)).map(|word| char_width(word)).collect();
+    widths.sort_by(f32::total_cmp);
+    widths.get(widths.len() / 2).copied().filter(|&width| width > 0.0)
+}
+
+/// Width per character of a word, in pixels.
+fn char_width(word: &OcrWord) -> f32 {
+    (word.bbox.2 - word.bbox.0) as f32 / word.text.chars().count().max(1) as f32
+}
+
+/// Coefficient of variation of the per-character width of the measurable words,
+/// which is close to zero in a monospaced font.
+fn width_spread(words: &[&OcrWord]) -> f32 {
+    let widths: Vec<f32> = words.iter().filter(|word| measurable(word)).map(|word| char_width(word)).collect();
+    if widths.len() < 2 {
+        return f32::INFINITY;
+    }
+    let mean = widths.iter().sum::<f32>() / widths.len() as f32;
+    let variance = widths.iter().map(|w| (w - mean).powi(2)).sum::<f32>() / widths.len() as f32;
+    if mean <= 0.0 { f32::INFINITY } else { variance.sqrt() / mean }
+}
+
+/// Rebuilds the lines of a code block, turning the horizontal offsets of the
+/// words into spaces of the font's character width.
+fn code_lines(lines: &[Vec<&OcrWord>]) -> Vec<String> {
+    let width = median_char_width(lines).unwrap_or(1.0).max(1.0);
+    let left = lines.iter().flatten().map(|word| word.bbox.0).min().unwrap_or(0);
+    let spaces = |gap: i32| " ".repeat((gap as f32 / width).round().max(0.0) as usize);
+
+    lines
+        .iter()
+        .map(|line| {
+            let mut text = String::new();
+            let mut x = left;
+            for (i, word) in line.iter().enumerate() {
+                let gap = spaces(word.bbox.0 - x);
+                // Words are at least one space apart.
+                text.push_str(if i > 0 && gap.is_empty() { " " } else { &gap });
+                text.push_str(&word.text);
+                x = word.bbox.2;
+            }
+            text
+        })
+        .collect()
+}
+
+/// Picks a title for a page: the largest line with decent confidence, preferring
+/// the top-most one when several lines are about equally large.
+pub fn detect_title(ocr: &OcrFrameResult) -> Option<String> {
+    let candidates: Vec<OcrLine> = group_lines(ocr)
+        .into_iter()
+        .filter(is_title_like)
+        .collect();
+
+    let tallest = candidates.iter().map(OcrLine::height).max()?;
+    let threshold = tallest as f32 * (1.0 - TITLE_HEIGHT_TOLERANCE);
+    candidates
+        .into_iter()
+        .filter(|line| line.height() as f32 >= threshold)
+        .min_by_key(|line| line.bbox.1)
+        .map(|line| line.text)
+}
+
+/// Whether a line is short and reliable enough to be a title or heading.
+fn is_title_like(line: &OcrLine) -> bool {
+    line.confidence >= MIN_TITLE_CONFIDENCE
+        && line.text.chars().count() <= MAX_TITLE_CHARS
+        && line.text.chars().filter(|c| c.is_alphanumeric()).count() >= 2
+}
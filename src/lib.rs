@@ -4,31 +4,336 @@
 //! the different modules to perform video processing, analysis, OCR, and
 //! document generation.
 
+use crate::document_builder::Page;
 use crate::frame_analyzer::AnalysisResult;
+use crate::i18n::{Msg, tr};
 use crate::ocr::OcrFrameResult;
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, ensure};
 use image::{ImageBuffer, Rgb};
 use indicatif::{ProgressBar, ProgressStyle};
 use log::{info, warn};
 use rayon::prelude::*;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
 // Define modules for different functionalities
+pub mod alto;
+pub mod audio_hints;
+pub mod bk_tree;
+pub mod builds;
+#[cfg(feature = "bundle")]
+pub mod bundle;
+pub mod cache;
+pub mod calibration;
+pub mod chapters;
+#[cfg(feature = "cloud")]
+pub mod cloud_ocr;
+pub mod contact_sheet;
+pub mod crop;
+#[cfg(feature = "sqlite")]
+pub mod database;
+pub mod diarize;
+pub mod diff;
 pub mod document_builder;
+pub mod figures;
+pub mod formulas;
 pub mod frame_analyzer;
+pub mod handwriting;
+pub mod i18n;
+pub mod iiif;
+pub mod index;
+pub mod keywords;
+pub mod layout;
+pub mod links;
+#[cfg(feature = "llm")]
+pub mod llm;
+pub mod mask;
+pub mod mrc;
+pub mod naming;
+pub mod obsidian;
 pub mod ocr;
+pub mod ocr_debug;
+pub mod overlay;
+#[cfg(feature = "onnx")]
+pub mod paddle;
+pub mod paper;
+pub mod paths;
+pub mod pdf_encryption;
+pub mod pdf_file;
+pub mod pdf_font;
+pub mod pdf_keywords;
+pub mod pdf_notes;
+pub mod pdf_tags;
+pub mod pdf_toc;
+pub mod pdfa;
+pub mod perspective;
+pub mod postprocess;
+pub mod preprocess;
+pub mod quality;
+pub mod recap;
+pub mod review;
+pub mod run_manifest;
+#[cfg(feature = "search")]
+pub mod search;
+#[cfg(feature = "spellcheck")]
+pub mod spelling;
+pub mod subtitles;
+#[cfg(feature = "templates")]
+pub mod template;
+pub mod text_presence;
+#[cfg(feature = "whisper")]
+pub mod transcribe;
+#[cfg(feature = "download")]
+pub mod tessdata;
+pub mod transcript;
+pub mod translate;
+pub mod upscale;
 pub mod video_processor;
+pub mod whiteboard;
+pub mod xmp;
 
 /// Application configuration structure.
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct Config {
     pub input_file: PathBuf,
     pub output_dir: PathBuf,
     pub output_format: String,
     pub sensitivity: f64,
+    /// Pick the sensitivity for the video from the distances between its
+    /// consecutive frames, instead of `sensitivity`.
+    pub auto_sensitivity: bool,
+    /// Algorithm frames are hashed with: "phash", "dhash", "ahash", or "whash".
+    pub hash_algorithm: String,
+    /// Side of the hash in bits, which has `hash_size` squared bits.
+    pub hash_size: usize,
+    /// Compares new slides with every page kept so far and treats one shown
+    /// again as "drop" or "merge" says; only with the previous page when unset.
+    pub revisits: Option<String>,
+    /// Take each page's image from the sharpest, well-exposed frame of its
+    /// stable segment instead of the first.
+    pub best_frame: bool,
+    /// Frames the content must stay unchanged for before a change starts a
+    /// page, passing over transitions and animations; 1 when unset.
+    pub settle_frames: Option<usize>,
+    /// The same as `settle_frames`, in milliseconds of the video.
+    pub settle_ms: Option<f64>,
+    /// Seconds content must be on screen for to become a page at all.
+    pub min_duration: Option<f64>,
+    /// Bias slide boundaries toward pauses and applause in the audio.
+    pub audio_hints: bool,
     pub lang: String,
+    /// Directory holding the Tesseract language packs; searched for when unset.
+    pub tessdata_dir: Option<PathBuf>,
+    /// Tesseract page segmentation mode (0-13); Tesseract's default when unset.
+    pub psm: Option<u8>,
+    /// Tesseract OCR engine mode (0-3); Tesseract's default when unset.
+    pub oem: Option<u8>,
+    /// Tesseract variables set on every engine, as (name, value).
+    pub tess_config: Vec<(String, String)>,
+    /// Only these characters are recognized.
+    pub char_whitelist: Option<String>,
+    /// These characters are never recognized.
+    pub char_blacklist: Option<String>,
+    /// Word list (one per line) Tesseract prefers when reading.
+    pub user_words: Option<PathBuf>,
+    /// Cleanup steps run on each frame before OCR: "contrast", "denoise", "deskew", "binarize".
+    pub preprocess: Vec<String>,
+    /// Cleanup of frames as they are decoded, for a kind of footage: "whiteboard" or "paper".
+    pub profile: Option<String>,
+    /// Warp pages and screens filmed at an angle to upright rectangles before OCR and in the documents.
+    pub rectify: bool,
+    /// Factor, 2 or 4, frames are enlarged by with a super-resolution model before OCR.
+    pub upscale: Option<u32>,
+    /// ESRGAN model, in ONNX format, used to upscale instead of Real-ESRGAN.
+    #[cfg(feature = "onnx")]
+    pub upscale_model: Option<PathBuf>,
+    /// Region of the frame analyzed and recognized, as "x,y,w,h" in pixels, or "auto" to trim black bars.
+    pub crop: Option<String>,
+    /// Regions ignored when hashing and recognizing frames, each as "x,y,w,h" in pixels of the analyzed frame.
+    pub ignore_regions: Vec<String>,
+    /// PNG mask whose white pixels are ignored when hashing and recognizing frames.
+    pub ignore_mask: Option<PathBuf>,
+    /// Download missing language packs without asking.
+    #[cfg(feature = "download")]
+    pub download_lang: bool,
+    /// Repository missing language packs are downloaded from: "fast" or "best".
+    #[cfg(feature = "download")]
+    pub tessdata_source: String,
+    /// OCR engine: "tesseract", "paddle", "google", "azure", or "textract".
+    pub ocr_engine: String,
+    /// PaddleOCR text detection model, in ONNX format.
+    #[cfg(feature = "onnx")]
+    pub paddle_det_model: Option<PathBuf>,
+    /// PaddleOCR text recognition model, in ONNX format.
+    #[cfg(feature = "onnx")]
+    pub paddle_rec_model: Option<PathBuf>,
+    /// Character dictionary of the PaddleOCR recognition model.
+    #[cfg(feature = "onnx")]
+    pub paddle_dict: Option<PathBuf>,
+    /// CUDA devices the PaddleOCR models run on; empty runs them on the CPU.
+    #[cfg(feature = "onnx")]
+    pub paddle_devices: Vec<i32>,
+    /// URL replacing the cloud OCR provider's default endpoint; required for Azure.
+    #[cfg(feature = "cloud")]
+    pub cloud_endpoint: Option<String>,
+    /// Images sent to Google Cloud Vision in one request.
+    #[cfg(feature = "cloud")]
+    pub cloud_batch_size: usize,
+    /// Times a failed cloud OCR request is retried.
+    #[cfg(feature = "cloud")]
+    pub cloud_retries: u32,
+    /// Most images sent to the cloud OCR provider in a run.
+    #[cfg(feature = "cloud")]
+    pub cloud_max_images: Option<usize>,
+    /// Engine handwriting is read with: "onnx", "google", "azure", or "textract".
+    #[cfg(any(feature = "onnx", feature = "cloud"))]
+    pub handwriting: Option<String>,
+    /// Handwriting recognition model, in ONNX format.
+    #[cfg(feature = "onnx")]
+    pub handwriting_model: Option<PathBuf>,
+    /// Character dictionary of the handwriting recognition model.
+    #[cfg(feature = "onnx")]
+    pub handwriting_dict: Option<PathBuf>,
+    /// Mean word confidence below which `ocr_engine`'s reading of a frame is
+    /// replaced by the handwriting engine's; `None` reads every frame with it.
+    #[cfg(any(feature = "onnx", feature = "cloud"))]
+    pub handwriting_below: Option<f32>,
+    /// Write `index.json` describing every kept frame and its output files.
     pub generate_index: bool,
+    /// Package the results into a single archive: "zip".
+    #[cfg(feature = "bundle")]
+    pub bundle: Option<String>,
+    /// Write a grid image and HTML page of every kept frame with its timestamp.
+    pub contact_sheet: bool,
+    /// Write chapter lists from the slide boundaries: a YouTube description and an ffmetadata file.
+    pub chapters: bool,
+    /// Write a report of the words recognized below `review_threshold`: "md" or "pdf".
+    pub review_report: Option<String>,
+    /// Confidence (0-100) below which words go into the review report.
+    pub review_threshold: f32,
+    /// Build a full-text search index over the OCR text of every page.
+    #[cfg(feature = "search")]
+    pub search_index: bool,
+    /// Append each page's detected title to the image file names (`img` format).
+    pub title_filenames: bool,
+    /// Format of the word-level OCR report: "json", "csv", or "both".
+    pub ocr_report: String,
+    /// Number of frames per stable segment to OCR and merge by voting (1 disables consensus).
+    pub consensus_frames: usize,
+    /// Skip OCR on frames the text presence detector finds no text on.
+    pub skip_textless: bool,
+    /// Number of threads recognizing frames at once; one per CPU core when unset.
+    pub ocr_threads: Option<usize>,
+    /// Mean word confidence below which a frame is recognized again with other settings.
+    pub retry_below: Option<f32>,
+    /// OCR confidence (0-100) below which words are unsure in the output documents.
+    pub min_confidence: f32,
+    /// What the output does with unsure words: "drop", "flag", or "keep"; unset
+    /// drops them from the PDF text layer and keeps them in Markdown and JSON Lines.
+    pub low_confidence: Option<String>,
+    /// Write the OCR text and an overlay of the word boxes of every page next to the OCR report.
+    pub ocr_debug: bool,
+    /// Heading level of the per-page sections in Markdown output.
+    pub md_heading_level: usize,
+    /// Document title; defaults to the input file name.
+    pub title: Option<String>,
+    /// Document author, written to the PDF metadata and the LaTeX document.
+    pub author: Option<String>,
+    /// Comma-separated keywords, written to the PDF metadata.
+    pub keywords: Option<String>,
+    /// URL where the recording can be watched; pages link back to it at their timestamp.
+    pub video_url: Option<String>,
+    /// Conformance profile of the PDF output: "standard", "pdfa-2b", or "pdfua-1".
+    pub pdf_profile: String,
+    /// Size of PDF pages: "a4" or "native" (the frame size at `pdf_page_dpi`).
+    pub pdf_page_size: String,
+    /// Resolution at which frames are laid out on native-size PDF pages.
+    pub pdf_page_dpi: f32,
+    /// Compression of PDF page images: "jpeg" or "flate" (lossless).
+    pub pdf_image_format: String,
+    /// JPEG quality (1-100) of PDF page images.
+    pub pdf_jpeg_quality: u8,
+    /// Downscale PDF page images above this resolution, in dots per inch.
+    pub pdf_max_dpi: Option<f32>,
+    /// Embed PDF page images without colour in grayscale, and black text on white with one bit per pixel.
+    pub pdf_auto_colour: bool,
+    /// Start the PDF with contents pages linking to every page.
+    pub pdf_toc: bool,
+    /// Password needed to open the PDF; encrypts the output.
+    #[serde(serialize_with = "redact")]
+    pub pdf_password: Option<String>,
+    /// Password granting full access to the PDF; encrypts the output.
+    #[serde(serialize_with = "redact")]
+    pub pdf_owner_password: Option<String>,
+    /// Write page images next to the HTML file instead of embedding them (`html` format).
+    pub html_link_images: bool,
+    /// Store paths of page images written next to the database instead of PNG blobs (`sqlite` format).
+    #[cfg(feature = "sqlite")]
+    pub sqlite_link_images: bool,
+    /// URL the `iiif` folder is served from, the base of every IIIF id.
+    pub iiif_base_url: String,
+    /// End the document with an alphabetical index of significant OCR terms (`pdf` and `md` formats).
+    pub keyword_index: bool,
+    /// Tag every page with its most distinctive OCR terms (index.json, `md` and `obsidian` formats).
+    pub slide_tags: bool,
+    /// Make the URLs in the OCR text clickable (`pdf` and `md` formats) and list them in links.json.
+    pub links: bool,
+    /// Crop the charts, diagrams and photos on each slide into images of their own (`md` and `html` formats).
+    pub figures: bool,
+    /// Keep formulas as cropped images in place of their OCR text (`md` and `html` formats) and leave
+    /// them out of the text layer (`pdf` and `html` formats).
+    pub formulas: bool,
+    /// Write only the lines each page adds to the page before it (`md` and `jsonl` formats; `txt` always does).
+    pub new_content_only: bool,
+    /// Keep only the final state of slides that are built up step by step, dropping the pages before it.
+    pub final_builds_only: bool,
+    /// SRT or WebVTT transcript of the audio, placed after each slide (`pdf` and `md` formats).
+    pub transcript: Option<PathBuf>,
+    /// Use the video's own captions as the transcript: a sidecar `.srt`/`.vtt` or a text subtitle stream.
+    pub use_subtitles: bool,
+    /// Whisper model used to transcribe the audio when no transcript is given (`pdf` and `md` formats).
+    #[cfg(feature = "whisper")]
+    pub whisper_model: Option<PathBuf>,
+    /// Label the speaker turns of the Whisper transcript (needs a tinydiarize model).
+    #[cfg(feature = "whisper")]
+    pub diarize: bool,
+    /// OpenAI-compatible API that cleans the OCR text of every page for index.json.
+    #[cfg(feature = "llm")]
+    pub llm_url: Option<String>,
+    /// Model asked by the post-processing API.
+    #[cfg(feature = "llm")]
+    pub llm_model: String,
+    /// Also have the post-processing API summarize every page.
+    #[cfg(feature = "llm")]
+    pub llm_summaries: bool,
+    /// Language to translate the OCR text of every page into with the `--llm-url` backend (index.json, `pdf` and `md` formats).
+    #[cfg(feature = "llm")]
+    pub translate_to: Option<String>,
+    /// Language dictionary the OCR text is spell-checked against before the outputs are built.
+    #[cfg(feature = "spellcheck")]
+    pub spell_dictionary: Option<PathBuf>,
+    /// Domain word list added to the spell-checking dictionary, taking precedence over it.
+    #[cfg(feature = "spellcheck")]
+    pub spell_wordlist: Option<PathBuf>,
+    /// Handlebars template that replaces the built-in layout (`md` and `html` formats).
+    #[cfg(feature = "templates")]
+    pub template: Option<PathBuf>,
+    /// Seconds each page is shown in the recap video (`video` format).
+    pub recap_seconds: f64,
+    /// Burn source timestamps into the recap video frames.
+    pub recap_timestamps: bool,
+    /// Container of the recap video: "mp4" or "webm".
+    pub recap_container: String,
+    /// Write one document per chapter of the video: "none" or "chapter" (`pdf` and `md` formats).
+    pub split_by: String,
+    /// What subtitle cues show: "text" (the OCR text) or "title" (`srt` and `vtt` formats).
+    pub subtitle_text: String,
+    /// Template of output paths inside the result directory, such as
+    /// `{stem}/{date}/slide_{index:04}` (`pdf`, `md`, and `img` formats and the review report).
+    pub name_template: Option<String>,
 }
 
 /// The main entry point that constructs and runs the processing pipeline.
@@ -40,25 +345,51 @@ pub fn run(config: Config) -> Result<()> {
 struct Pipeline {
     config: Config,
     result_dir: PathBuf,
+    name_template: Option<naming::NameTemplate>,
+    ignore_mask: Option<mask::IgnoreMask>,
 }
 
 impl Pipeline {
     /// Creates a new pipeline and sets up its initial state.
-    fn new(config: Config) -> Result<Self> {
+    fn new(mut config: Config) -> Result<Self> {
+        paths::to_utf8(&config.input_file).context("Unsupported input path")?;
+        config.output_dir = paths::prepare_dir(&config.output_dir)?;
         let result_dir = config.output_dir.join("result");
-        Ok(Self { config, result_dir })
+        let name_template = config
+            .name_template
+            .as_deref()
+            .map(naming::NameTemplate::parse)
+            .transpose()
+            .context("Invalid --name-template")?;
+        if let Some(spec) = config.crop.as_deref().filter(|&spec| spec != "auto") {
+            crop::CropRect::parse(spec).context("Invalid --crop")?;
+        }
+        let ignore_mask = mask::IgnoreMask::load(&config.ignore_regions, config.ignore_mask.as_deref())
+            .context("Invalid --ignore-region or --ignore-mask")?;
+        Ok(Self { config, result_dir, name_template, ignore_mask })
     }
 
     /// Executes all stages of the pipeline in sequence.
     fn run(&self) -> Result<()> {
+        let start_time = Instant::now();
+        let started = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .ok()
+            .and_then(|now| xmp::DateTime::from_unix_timestamp(now.as_secs()))
+            .map(|date| date.to_iso8601())
+            .unwrap_or_default();
+        let mut stages = Vec::new();
         self.setup_directories().context("Failed to set up directories")?;
 
-        let analysis_result = self
-            .analyze_frames()
+        let crop = self.crop_region().context("Failed to find the crop region")?;
+        let mut analysis_result = timed(&mut stages, "analysis", || self.analyze_frames(crop.as_ref()))
             .context("Frame analysis failed")?;
 
         if analysis_result.kept_frames.is_empty() {
             warn!("No unique frames were found based on the sensitivity settings. Exiting.");
+            self.write_run_manifest(started, stages, 0, start_time.elapsed())
+                .context("Failed to write the run manifest")?;
+            self.print_summary(&analysis_result, start_time.elapsed());
             return Ok(());
         }
 
@@ -67,16 +398,51 @@ impl Pipeline {
             analysis_result.kept_frames.len()
         );
 
-        let ocr_results = self
-            .perform_ocr(&analysis_result.kept_frames)
+        let quads = if self.config.rectify {
+            timed(&mut stages, "rectification", || Ok(self.rectify_frames(&mut analysis_result)))?
+        } else {
+            Vec::new()
+        };
+        let ocr_results = timed(&mut stages, "ocr", || self.perform_ocr(&analysis_result, &quads, crop.as_ref()))
             .context("OCR processing failed")?;
+        let (analysis_result, ocr_results) = if self.config.final_builds_only {
+            self.keep_final_builds(analysis_result, ocr_results)
+        } else {
+            (analysis_result, ocr_results)
+        };
+        // Written once the dropped builds are gone, so it lists the pages of the documents.
+        ocr::save_report(&ocr_results, &self.config.output_dir, &self.config.ocr_report)?;
+        #[cfg(feature = "spellcheck")]
+        let ocr_results = self.correct_spelling(ocr_results).context("Spelling correction failed")?;
 
-        self.generate_output(&analysis_result.kept_frames, &ocr_results)
+        timed(&mut stages, "output", || self.generate_output(&analysis_result, &ocr_results))
             .context("Failed to generate output")?;
+        #[cfg(feature = "bundle")]
+        if self.config.bundle.as_deref() == Some("zip") {
+            timed(&mut stages, "bundle", || self.write_bundle()).context("Failed to bundle the results")?;
+        }
+        self.write_run_manifest(started, stages, analysis_result.kept_frames.len(), start_time.elapsed())
+            .context("Failed to write the run manifest")?;
 
+        self.print_summary(&analysis_result, start_time.elapsed());
         Ok(())
     }
 
+    /// Prints a short, localized report of what the run produced.
+    fn print_summary(&self, analysis: &AnalysisResult, elapsed: Duration) {
+        println!();
+        println!("{}", tr(Msg::SummaryTitle));
+        println!("  {}: {}", tr(Msg::SummaryFramesAnalyzed), analysis.total_frames);
+        println!("  {}: {}", tr(Msg::SummaryPagesKept), analysis.kept_frames.len());
+        if analysis.kept_frames.is_empty() {
+            println!("  {}", tr(Msg::SummaryNoPages));
+        } else {
+            println!("  {}: {}", tr(Msg::SummaryOutputFormat), self.config.output_format);
+            println!("  {}: {}", tr(Msg::SummaryOutputDir), self.result_dir.display());
+        }
+        println!("  {}: {:.1?}", tr(Msg::SummaryElapsed), elapsed);
+    }
+
     /// Creates or clears the necessary output directories.
     fn setup_directories(&self) -> Result<()> {
         if !self.config.output_dir.exists() {
@@ -90,34 +456,64 @@ impl Pipeline {
     }
 
     /// Runs the streaming video analysis stage.
-    fn analyze_frames(&self) -> Result<AnalysisResult> {
+    ///
+    /// When a hash series from a previous run of the same video is available,
+    /// only the keep/drop decisions are recomputed and just the kept frames are
+    /// converted, instead of hashing every frame again.
+    fn analyze_frames(&self, crop: Option<&crop::CropRect>) -> Result<AnalysisResult> {
+        let fingerprint = cache::InputFingerprint::of(&self.config.input_file)?;
+        let settings = frame_analyzer::HashSettings {
+            hasher: self.frame_hasher(),
+            crop,
+            ignore_mask: self.ignore_mask.as_ref(),
+            profile: self.config.profile.as_deref(),
+        }
+        .to_json();
+        let series_path = self.config.output_dir.join("analysis").join("hash_series.json");
+
+        let cached = cache::read::<Vec<frame_analyzer::FrameHash>>(
+            &series_path,
+            frame_analyzer::HASH_SERIES_KIND,
+            &fingerprint,
+            &settings,
+        )?;
+        let quality_path = self.config.output_dir.join("analysis").join("quality_series.json");
+        let cached_quality = if cached.is_some() && self.config.best_frame {
+            cache::read::<Vec<f32>>(&quality_path, quality::QUALITY_SERIES_KIND, &fingerprint, &settings)?
+        } else {
+            None
+        };
+        let meta_path = self.config.output_dir.join("analysis").join("frame_meta.json");
+        if let Some(hash_series) = cached {
+            if !self.config.best_frame || cached_quality.is_some() {
+                info!("Reusing {} cached frame hashes from {:?}", hash_series.len(), series_path);
+                let kind = frame_analyzer::FRAME_META_KIND;
+                let frame_meta =
+                    cache::read::<Vec<video_processor::FrameMeta>>(&meta_path, kind, &fingerprint, &settings)?;
+                let sensitivity = self.sensitivity_for(&hash_series)?;
+                if let Some(result) =
+                    self.replay_analysis(hash_series, cached_quality, frame_meta, sensitivity, crop)?
+                {
+                    return Ok(result);
+                }
+            } else {
+                info!("No cached frame scores for --best-frame; analyzing every frame again.");
+            }
+        }
+
         let mut analyzer =
             frame_analyzer::FrameAnalyzer::new(self.config.sensitivity, &self.config.output_dir)?;
+        self.configure_analyzer(&mut analyzer);
+        analyzer.set_best_frame(self.config.best_frame);
+        if let Some(ignore_mask) = &self.ignore_mask {
+            analyzer.set_ignore_mask(ignore_mask.clone());
+        }
 
-        let pb = match video_processor::get_frame_count(&self.config.input_file) {
-            Ok(count) if count > 0 => {
-                let bar = ProgressBar::new(count);
-                bar.set_style(
-                    ProgressStyle::default_bar()
-                        .template("{spinner:.green} Analyzing frames [{bar:40.cyan/blue}] {pos}/{len} ({percent}%) [{elapsed_precise}<{eta}]")?
-                        .progress_chars("##-"),
-                );
-                bar
-            }
-            _ => {
-                warn!("Could not determine total frame count. Using spinner as fallback.");
-                let bar = ProgressBar::new_spinner();
-                bar.set_style(
-                    ProgressStyle::default_spinner()
-                        .template("{spinner:.green} Analyzing frames... [{elapsed_precise}] {pos} frames processed")?,
-                );
-                bar
-            }
-        };
-        pb.enable_steady_tick(std::time::Duration::from_millis(100));
+        let frame_count = video_processor::get_frame_count(&self.config.input_file).ok();
+        let pb = analysis_progress_bar(frame_count.filter(|&count| count > 0))?;
 
-        let frame_handler = |frame| {
-            analyzer.process_frame(frame)?;
+        let frame_handler = |frame, meta| {
+            analyzer.process_timed_frame(self.source_frame(frame, crop)?, meta)?;
             pb.inc(1);
             Ok(())
         };
@@ -125,45 +521,1228 @@ impl Pipeline {
         video_processor::process_frames_stream(&self.config.input_file, frame_handler)?;
 
         let final_pos = pb.position();
-        pb.finish_with_message(format!("Analyzed {} frames", final_pos));
+        pb.finish_with_message(format!("{}: {}", tr(Msg::AnalyzedFrames), final_pos));
+
+        let quality_series = analyzer.quality_series().to_vec();
+        let result = analyzer.finish()?;
+        if self.config.best_frame {
+            let kind = quality::QUALITY_SERIES_KIND;
+            if let Err(e) = cache::write(&quality_path, kind, &fingerprint, settings.clone(), &quality_series) {
+                warn!("Could not persist the frame quality series: {:#}", e);
+            }
+        }
+        if let Err(e) = cache::write(
+            &series_path,
+            frame_analyzer::HASH_SERIES_KIND,
+            &fingerprint,
+            settings.clone(),
+            &result.hash_series,
+        ) {
+            warn!("Could not persist the frame hash series: {:#}", e);
+        }
+        let kind = frame_analyzer::FRAME_META_KIND;
+        if let Err(e) = cache::write(&meta_path, kind, &fingerprint, settings, &result.frame_meta) {
+            warn!("Could not persist the frame timestamps: {:#}", e);
+        }
+        if self.config.auto_sensitivity {
+            // The first pass only gathered the hashes; the pages come from the calibrated sensitivity.
+            let sensitivity = self.sensitivity_for(&result.hash_series)?;
+            let quality_series = self.config.best_frame.then_some(quality_series);
+            return self.replay_analysis(result.hash_series, quality_series, Some(result.frame_meta), sensitivity, crop);
+        }
+        Ok(result)
+    }
+
+    /// The sensitivity frames are compared with: `--sensitivity`, or with
+    /// `--sensitivity auto` the one calibrated on the hash series of the video.
+    fn sensitivity_for(&self, hash_series: &[frame_analyzer::FrameHash]) -> Result<f64> {
+        if !self.config.auto_sensitivity {
+            return Ok(self.config.sensitivity);
+        }
+        match calibration::calibrate(hash_series, self.frame_hasher().bits())? {
+            Some(sensitivity) => {
+                info!("Calibrated the sensitivity to {:.3} on {} frames.", sensitivity, hash_series.len());
+                Ok(sensitivity)
+            }
+            None => {
+                warn!("Too few distinct frames to calibrate the sensitivity; using {}.", self.config.sensitivity);
+                Ok(self.config.sensitivity)
+            }
+        }
+    }
+
+    /// Applies the settings the keep/drop decisions depend on, shared by the
+    /// analysis and its replay from a cached hash series.
+    fn configure_analyzer(&self, analyzer: &mut frame_analyzer::FrameAnalyzer) {
+        analyzer.set_hasher(self.frame_hasher());
+        if let Some(policy) = self.revisit_policy() {
+            analyzer.set_revisit_policy(policy);
+        }
+        let fps = self.frame_rate();
+        if let Some(fps) = fps {
+            analyzer.set_frame_rate(fps);
+        }
+        if let Some(hints) = self.audio_hints() {
+            analyzer.set_audio_hints(hints);
+        }
+        if let Some(frames) = self.settle_frames(fps) {
+            analyzer.set_settle_frames(frames);
+        }
+        if let Some(seconds) = self.config.min_duration {
+            match fps {
+                Some(fps) => analyzer.set_min_frames((seconds * fps).ceil() as usize),
+                None => warn!("--min-duration needs the frame rate of the video; ignoring it."),
+            }
+        }
+    }
+
+    /// Frames a change must settle for, from `--settle-frames`, or from
+    /// `--settle-ms` at the frame rate of the input.
+    fn settle_frames(&self, fps: Option<f64>) -> Option<usize> {
+        match (self.config.settle_frames, self.config.settle_ms) {
+            (Some(frames), _) => Some(frames),
+            (None, Some(ms)) => match fps {
+                Some(fps) => Some((ms * fps / 1000.0).ceil() as usize),
+                None => {
+                    warn!("--settle-ms needs the frame rate of the video; ignoring it.");
+                    None
+                }
+            },
+            (None, None) => None,
+        }
+    }
+
+    /// The hasher frames are compared with, from `--hash-algorithm` and `--hash-size`.
+    fn frame_hasher(&self) -> frame_analyzer::FrameHasher {
+        let algorithm = frame_analyzer::HashAlgorithm::from_name(&self.config.hash_algorithm)
+            .unwrap_or(frame_analyzer::HashAlgorithm::Perceptual);
+        frame_analyzer::FrameHasher::new(algorithm, self.config.hash_size)
+    }
+
+    /// What becomes of slides shown again, from `--revisits`.
+    fn revisit_policy(&self) -> Option<frame_analyzer::RevisitPolicy> {
+        self.config.revisits.as_deref().and_then(frame_analyzer::RevisitPolicy::from_name)
+    }
+
+    /// Reads the frame rate of the input, which page timestamps are derived from.
+    fn frame_rate(&self) -> Option<f64> {
+        video_processor::get_frame_rate(&self.config.input_file)
+            .map_err(|e| warn!("Page timestamps are unavailable: {:#}", e))
+            .ok()
+    }
+
+    /// Finds the pauses of the audio for `--audio-hints`. `None` when the option is
+    /// off, or when the frame rate or the audio track is unavailable.
+    fn audio_hints(&self) -> Option<audio_hints::AudioHints> {
+        if !self.config.audio_hints {
+            return None;
+        }
+        let Some(fps) = self.frame_rate() else {
+            warn!("--audio-hints needs the frame rate of the video; ignoring it.");
+            return None;
+        };
+        let samples = video_processor::extract_audio(&self.config.input_file, audio_hints::SAMPLE_RATE)
+            .map_err(|e| warn!("--audio-hints ignored: {:#}", e))
+            .ok()?;
+        let pauses = audio_hints::find_pauses(&samples, audio_hints::SAMPLE_RATE);
+        if pauses.is_empty() {
+            warn!("No pauses found in the audio; --audio-hints has no effect.");
+        } else {
+            info!("Found {} pauses in the audio to place slide boundaries at.", pauses.len());
+        }
+        Some(audio_hints::AudioHints::new(pauses, fps))
+    }
+
+    /// Recomputes the frame selection at `sensitivity` from a cached hash
+    /// series, and quality series with `--best-frame`, and extracts the kept
+    /// frames. Without the cached frame timestamps, pages are timed by the
+    /// frame rate. `None` when some of those frames could not be decoded, so the
+    /// video has to be analyzed again in full.
+    fn replay_analysis(
+        &self,
+        hash_series: Vec<frame_analyzer::FrameHash>,
+        quality_series: Option<Vec<f32>>,
+        frame_meta: Option<Vec<video_processor::FrameMeta>>,
+        sensitivity: f64,
+        crop: Option<&crop::CropRect>,
+    ) -> Result<Option<AnalysisResult>> {
+        let mut analyzer = frame_analyzer::FrameAnalyzer::new(sensitivity, &self.config.output_dir)?;
+        self.configure_analyzer(&mut analyzer);
+        if let Some(frame_meta) = frame_meta {
+            analyzer.set_frame_meta(frame_meta);
+        }
+        analyzer.replay(hash_series, quality_series)?;
+        let wanted = analyzer.source_indices().to_vec();
+
+        let pb = analysis_progress_bar(Some(wanted.len() as u64))?;
+        video_processor::process_selected_frames(&self.config.input_file, &wanted, |frame, _| {
+            analyzer.push_kept_frame(self.source_frame(frame, crop)?);
+            pb.inc(1);
+            Ok(())
+        })?;
+        pb.finish_with_message(format!("{}: {}", tr(Msg::AnalyzedFrames), pb.position()));
+
+        // The pages are laid out from the whole selection, so a missing frame
+        // would leave every page after it with another page's image.
+        if (pb.position() as usize) < wanted.len() {
+            warn!(
+                "Only {} of {} selected frames could be decoded; the cached hash series may be stale. \
+                 Analyzing every frame again.",
+                pb.position(),
+                wanted.len()
+            );
+            return Ok(None);
+        }
+        analyzer.finish().map(Some)
+    }
 
-        analyzer.finish()
+    /// A decoded frame as it is analyzed, recognized, and shown: cut to the
+    /// crop region, then cleaned up for the `--profile`.
+    fn source_frame(
+        &self,
+        frame: ImageBuffer<Rgb<u8>, Vec<u8>>,
+        crop: Option<&crop::CropRect>,
+    ) -> Result<ImageBuffer<Rgb<u8>, Vec<u8>>> {
+        let frame = crop::crop_frame(frame, crop)?;
+        Ok(match self.config.profile.as_deref() {
+            Some("whiteboard") => whiteboard::enhance(&frame),
+            Some("paper") => paper::enhance(&frame),
+            _ => frame,
+        })
+    }
+
+    /// The region of the frame to analyze and recognize: `--crop` as given, or
+    /// with `--crop auto` the picture inside the video's black bars.
+    fn crop_region(&self) -> Result<Option<crop::CropRect>> {
+        match self.config.crop.as_deref() {
+            None => Ok(None),
+            Some("auto") => {
+                let frame_count = video_processor::get_frame_count(&self.config.input_file)?;
+                let indices = crop::sample_indices(frame_count as usize);
+                let mut samples = Vec::new();
+                video_processor::process_selected_frames(&self.config.input_file, &indices, |frame, _| {
+                    samples.push(frame);
+                    Ok(())
+                })?;
+                let region = crop::detect_borders(&samples);
+                match &region {
+                    Some(r) => info!("Cropping the frames to the picture inside the black bars: {:?}", r),
+                    None => info!("No black bars found; the frames are not cropped."),
+                }
+                Ok(region)
+            }
+            Some(spec) => crop::CropRect::parse(spec).map(Some),
+        }
     }
 
     /// Runs the parallel OCR stage.
-    fn perform_ocr(&self, frames: &[ImageBuffer<Rgb<u8>, Vec<u8>>]) -> Result<Vec<OcrFrameResult>> {
-        ocr::perform_ocr_on_frames(frames, &self.config)
+    ///
+    /// With consensus OCR enabled, additional frames from each page's stable
+    /// segment are decoded again and recognized alongside the kept frame.
+    fn perform_ocr(
+        &self,
+        analysis: &AnalysisResult,
+        quads: &[Option<perspective::Quad>],
+        crop: Option<&crop::CropRect>,
+    ) -> Result<Vec<OcrFrameResult>> {
+        // A rectified page no longer lines up with the ignored regions, so they only apply to the others.
+        let masks: Vec<Option<&mask::IgnoreMask>> = (0..analysis.kept_frames.len())
+            .map(|page| self.ignore_mask.as_ref().filter(|_| quads.get(page).copied().flatten().is_none()))
+            .collect();
+        if self.config.consensus_frames <= 1 {
+            return ocr::perform_ocr_on_frames(
+                &analysis.kept_frames,
+                &masks,
+                &analysis.kept_timestamps,
+                &self.config,
+            );
+        }
+
+        let picks = analysis.segment_samples(self.config.consensus_frames);
+        let mut wanted: Vec<usize> = picks.iter().flatten().copied().collect();
+        wanted.sort_unstable();
+        wanted.dedup();
+        let mut decoded = HashMap::new();
+        video_processor::process_selected_frames(&self.config.input_file, &wanted, |frame, meta| {
+            decoded.insert(meta.index, self.source_frame(frame, crop)?);
+            Ok(())
+        })?;
+        if decoded.len() < wanted.len() {
+            warn!(
+                "Only {} of {} consensus samples could be decoded.",
+                decoded.len(),
+                wanted.len()
+            );
+        }
+
+        // Samples are warped with the corners found on their page's kept frame, so all
+        // of a page's votes share one set of coordinates.
+        let samples: Vec<Vec<ImageBuffer<Rgb<u8>, Vec<u8>>>> = picks
+            .iter()
+            .enumerate()
+            .map(|(page, page_picks)| {
+                let quad = quads.get(page).copied().flatten();
+                page_picks
+                    .iter()
+                    .filter_map(|i| decoded.remove(i))
+                    .map(|frame| match quad {
+                        Some(quad) => perspective::warp(&frame, &quad),
+                        None => frame,
+                    })
+                    .collect()
+            })
+            .collect();
+        ocr::perform_consensus_ocr(
+            &analysis.kept_frames,
+            &samples,
+            &masks,
+            &analysis.kept_timestamps,
+            &self.config,
+        )
     }
 
-    /// Generates the final output file(s) based on the format specified in the config.
-    fn generate_output(
+    /// Warps every kept frame that shows a page or screen at an angle to an
+    /// upright rectangle, and returns the corners found on each frame.
+    fn rectify_frames(&self, analysis: &mut AnalysisResult) -> Vec<Option<perspective::Quad>> {
+        let quads: Vec<Option<perspective::Quad>> =
+            analysis.kept_frames.par_iter().map(perspective::detect_quad).collect();
+        analysis.kept_frames.par_iter_mut().zip(&quads).for_each(|(frame, quad)| {
+            if let Some(quad) = quad {
+                *frame = perspective::warp(frame, quad);
+            }
+        });
+        let rectified = quads.iter().flatten().count();
+        info!("Corrected the perspective of {} of {} pages.", rectified, quads.len());
+        quads
+    }
+
+    /// File name of the input video.
+    fn source_name(&self) -> String {
+        self.config
+            .input_file
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+
+    /// Title for generated documents: the configured title, else the input file name.
+    fn document_title(&self) -> String {
+        self.config.title.clone().unwrap_or_else(|| {
+            self.config
+                .input_file
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_else(|| "Extracted Document".to_string())
+        })
+    }
+
+    /// Values of the `--name-template` placeholders shared by every output. The
+    /// date is the recording's, like the PDF creation date, else today's.
+    fn name_fields(&self) -> naming::NameFields {
+        let date = self.document_metadata().creation_date.or_else(|| {
+            let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).ok()?;
+            xmp::DateTime::from_unix_timestamp(now.as_secs())
+        });
+        naming::NameFields {
+            stem: self
+                .config
+                .input_file
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            title: self.document_title(),
+            date: date.map(|d| format!("{:04}-{:02}-{:02}", d.year, d.month, d.day)).unwrap_or_default(),
+            index: 1,
+        }
+    }
+
+    /// Paths of the documents of every part, relative to the result directory:
+    /// `document.<extension>`, in the chapter's folder when split, or the
+    /// rendered `--name-template`.
+    fn document_files(&self, parts: &[DocumentPart], extension: &str) -> Result<Vec<String>> {
+        let files: Vec<String> = match &self.name_template {
+            Some(template) => {
+                let fields = self.name_fields();
+                parts
+                    .iter()
+                    .enumerate()
+                    .map(|(n, part)| {
+                        let fields = naming::NameFields { title: part.title.clone(), index: n + 1, ..fields.clone() };
+                        format!("{}.{}", template.render(&fields), extension)
+                    })
+                    .collect()
+            }
+            None => parts.iter().map(|part| part.file(&format!("document.{}", extension))).collect(),
+        };
+        naming::ensure_unique(&files)?;
+        Ok(files)
+    }
+
+    /// Files holding each of `count` pages, relative to the result directory:
+    /// the document, and the page's image when it was saved separately.
+    fn page_files(&self, written: &document_builder::WrittenFiles, count: usize) -> Vec<Vec<String>> {
+        let relative = |path: &Path| {
+            let path = path.strip_prefix(&self.result_dir).unwrap_or(path);
+            path.to_string_lossy().replace('\\', "/")
+        };
+        let document = relative(&written.document);
+        (0..count)
+            .map(|i| {
+                let mut files = vec![document.clone()];
+                files.extend(written.images.get(i).map(|image| relative(image)));
+                files
+            })
+            .collect()
+    }
+
+    /// Passwords for the PDF output, if either was given.
+    fn pdf_encryption(&self) -> Option<pdf_encryption::PdfEncryption> {
+        let owner_password = self.config.pdf_owner_password.clone();
+        if self.config.pdf_password.is_none() && owner_password.is_none() {
+            return None;
+        }
+        Some(pdf_encryption::PdfEncryption {
+            user_password: self.config.pdf_password.clone().unwrap_or_default(),
+            owner_password,
+        })
+    }
+
+    /// Which OCR words the output documents keep by their confidence; `default`
+    /// is what the output does with unsure words unless configured otherwise.
+    fn confidence_policy(&self, default: document_builder::LowConfidence) -> document_builder::ConfidencePolicy {
+        document_builder::ConfidencePolicy {
+            threshold: self.config.min_confidence,
+            low_confidence: match self.config.low_confidence.as_deref() {
+                Some("drop") => document_builder::LowConfidence::Drop,
+                Some("flag") => document_builder::LowConfidence::Flag,
+                Some("keep") => document_builder::LowConfidence::Keep,
+                _ => default,
+            },
+        }
+    }
+
+    /// Document properties for the PDF, with the creation date read from the video.
+    /// `SOURCE_DATE_EPOCH` overrides that date, for reproducible output.
+    fn document_metadata(&self) -> xmp::DocumentMetadata {
+        let creation_date = source_date_epoch().or_else(|| {
+            video_processor::get_creation_time(&self.config.input_file)
+                .map_err(|e| warn!("Could not read the video creation time: {:#}", e))
+                .ok()
+                .flatten()
+                .and_then(|value| {
+                    let parsed = xmp::DateTime::parse_iso8601(&value);
+                    if parsed.is_none() {
+                        warn!("Ignoring unrecognized video creation time {:?}", value);
+                    }
+                    parsed
+                })
+        });
+        xmp::DocumentMetadata {
+            title: self.document_title(),
+            author: self.config.author.clone(),
+            keywords: self.config.keywords.clone(),
+            creation_date,
+        }
+    }
+
+    /// Splits the pages into the documents to write: one per chapter of the video
+    /// with `--split-by chapter`, otherwise a single document.
+    fn document_parts(&self, pages: &[Page]) -> Vec<DocumentPart> {
+        let whole = || {
+            vec![DocumentPart { dir: None, title: self.document_title(), pages: 0..pages.len() }]
+        };
+        if self.config.split_by != "chapter" {
+            return whole();
+        }
+        let chapters = match video_processor::get_chapters(&self.config.input_file) {
+            Ok(chapters) if !chapters.is_empty() => chapters,
+            Ok(_) => {
+                warn!("The video has no chapter markers; writing a single document.");
+                return whole();
+            }
+            Err(e) => {
+                warn!("Could not read chapter markers, writing a single document: {:#}", e);
+                return whole();
+            }
+        };
+        let Some(runs) = chapters::split_pages(pages, &chapters) else {
+            warn!("Page timestamps are unavailable; writing a single document.");
+            return whole();
+        };
+
+        let parts: Vec<DocumentPart> = runs
+            .into_iter()
+            .map(|(chapter, pages)| {
+                let name = chapters[chapter]
+                    .title
+                    .clone()
+                    .unwrap_or_else(|| format!("Chapter {}", chapter + 1));
+                DocumentPart {
+                    dir: Some(format!("chapter_{:02}_{}", chapter + 1, paths::sanitize_file_name(&name))),
+                    title: format!("{} - {}", self.document_title(), name),
+                    pages,
+                }
+            })
+            .collect();
+        info!("Splitting the output into {} chapter documents.", parts.len());
+        parts
+    }
+
+    /// Aligns the transcript, if one was given, to the pages: the cues heard while
+    /// each page was on screen. Empty when there is no transcript or no page timestamps.
+    fn aligned_speech(&self, pages: &[Page]) -> Result<Vec<Vec<transcript::Cue>>> {
+        let Some(cues) = self.speech_cues()? else {
+            return Ok(Vec::new());
+        };
+        let Some(timestamps) = pages.iter().map(|page| page.timestamp).collect::<Option<Vec<f64>>>() else {
+            warn!("Page timestamps are unavailable; the transcript will not be included.");
+            return Ok(Vec::new());
+        };
+        info!("Aligning {} transcript cues to their pages.", cues.len());
+        Ok(transcript::align(&cues, &timestamps))
+    }
+
+    /// Cues of the transcript file, of the video's own subtitles, or of the audio
+    /// transcribed with the Whisper model, in that order of preference; `None`
+    /// when none is available.
+    fn speech_cues(&self) -> Result<Option<Vec<transcript::Cue>>> {
+        if let Some(path) = &self.config.transcript {
+            return transcript::load(path).map(Some);
+        }
+        if self.config.use_subtitles {
+            if let Some(path) = transcript::find_sidecar(&self.config.input_file) {
+                info!("Using the subtitles in {:?} as the transcript.", path);
+                return transcript::load(&path).map(Some);
+            }
+            let cues = video_processor::extract_subtitles(&self.config.input_file)
+                .context("Failed to read the subtitle stream")?;
+            if !cues.is_empty() {
+                info!("Using the video's subtitle stream as the transcript.");
+                return Ok(Some(cues));
+            }
+            warn!("The video has no text subtitles and no .srt or .vtt file next to it.");
+        }
+        #[cfg(feature = "whisper")]
+        if let Some(model) = &self.config.whisper_model {
+            let language = transcribe::whisper_language(&self.config.lang);
+            let cues = transcribe::transcribe(&self.config.input_file, model, language, self.config.diarize)
+                .context("Failed to transcribe the audio")?;
+            return Ok(Some(cues));
+        }
+        Ok(None)
+    }
+
+    /// Renders the pages, titled `title`, through the user's template as HTML or
+    /// Markdown. `None` when no template was given.
+    #[cfg(feature = "templates")]
+    fn render_template(
         &self,
-        frames: &[ImageBuffer<Rgb<u8>, Vec<u8>>],
-        ocr_results: &[OcrFrameResult],
-    ) -> Result<()> {
+        pages: &[Page],
+        title: String,
+        html: bool,
+        embed_images: bool,
+        output_path: &Path,
+    ) -> Result<Option<document_builder::WrittenFiles>> {
+        let Some(template) = &self.config.template else {
+            return Ok(None);
+        };
+        let options = template::TemplateOptions {
+            document_title: title,
+            source: self.source_name(),
+            video_url: self.config.video_url.clone(),
+            html,
+            embed_images,
+        };
+        template::render(pages, &options, template, output_path).map(Some)
+    }
+
+    /// Without the `templates` feature there is no template to render.
+    #[cfg(not(feature = "templates"))]
+    fn render_template(
+        &self,
+        _pages: &[Page],
+        _title: String,
+        _html: bool,
+        _embed_images: bool,
+        _output_path: &Path,
+    ) -> Result<Option<document_builder::WrittenFiles>> {
+        Ok(None)
+    }
+
+    /// Pairs every kept frame with its OCR result, detected title, and source timestamp.
+    fn build_pages<'a>(
+        &self,
+        analysis: &'a AnalysisResult,
+        ocr_results: &'a [OcrFrameResult],
+    ) -> Vec<Page<'a>> {
+        let ocr_map: HashMap<usize, &OcrFrameResult> =
+            ocr_results.iter().map(|r| (r.frame_index, r)).collect();
+        analysis
+            .kept_frames
+            .iter()
+            .enumerate()
+            .map(|(i, image)| {
+                let ocr = ocr_map.get(&i).copied();
+                let timestamp = analysis.kept_timestamps.get(i).copied().flatten();
+                let end = analysis.segments.get(i).and_then(|segment| segment.end);
+                Page { image, ocr, title: ocr.and_then(layout::detect_title), timestamp, end }
+            })
+            .collect()
+    }
+
+    /// Drops the pages that are intermediate builds of the page after them, and
+    /// renumbers the OCR results of the rest.
+    fn keep_final_builds(
+        &self,
+        mut analysis: AnalysisResult,
+        ocr_results: Vec<OcrFrameResult>,
+    ) -> (AnalysisResult, Vec<OcrFrameResult>) {
+        let keep = builds::final_builds(&self.build_pages(&analysis, &ocr_results));
+        let ocr_results = builds::keep_results(ocr_results, &keep);
+        analysis.retain_frames(&keep);
+        info!("Kept the final builds of the slides: {} of {} pages.", analysis.kept_frames.len(), keep.len());
+        (analysis, ocr_results)
+    }
+
+    /// Corrects the OCR text against the `--spell-dictionary` and
+    /// `--spell-wordlist` words, listing every change in `corrections.json`.
+    /// The results are returned unchanged when neither is given.
+    #[cfg(feature = "spellcheck")]
+    fn correct_spelling(&self, mut ocr_results: Vec<OcrFrameResult>) -> Result<Vec<OcrFrameResult>> {
+        if self.config.spell_dictionary.is_none() && self.config.spell_wordlist.is_none() {
+            return Ok(ocr_results);
+        }
+        let mut checker = spelling::SpellChecker::new();
+        if let Some(path) = &self.config.spell_dictionary {
+            let words = checker.load_dictionary(path)?;
+            info!("Loaded {} dictionary words from {:?}", words, path);
+        }
+        if let Some(path) = &self.config.spell_wordlist {
+            let words = checker.load_wordlist(path)?;
+            info!("Loaded {} domain words from {:?}", words, path);
+        }
+        let corrections = checker.correct_pages(&mut ocr_results);
+        let corrections_path = self.result_dir.join(spelling::CORRECTIONS_NAME);
+        spelling::write_corrections(&corrections, &corrections_path)?;
+        info!("Corrected {} OCR words, listed in {:?}", corrections.len(), corrections_path);
+        Ok(ocr_results)
+    }
+
+    /// Generates the final output file(s) based on the format specified in the config.
+    fn generate_output(&self, analysis: &AnalysisResult, ocr_results: &[OcrFrameResult]) -> Result<()> {
         info!("Generating output in '{}' format.", self.config.output_format);
-        match self.config.output_format.as_str() {
+        let pages = self.build_pages(analysis, ocr_results);
+        // Transcript cues of each page, placed after it in pdf, md, and html and listed in index.json.
+        let uses_speech =
+            matches!(self.config.output_format.as_str(), "pdf" | "md" | "html") || self.config.generate_index;
+        let aligned_speech = if uses_speech { self.aligned_speech(&pages)? } else { Vec::new() };
+        let speech: Vec<String> = aligned_speech.iter().map(|cues| transcript::page_speech(cues)).collect();
+        let tags = if self.config.slide_tags { keywords::page_tags(&pages) } else { Vec::new() };
+        #[cfg(feature = "llm")]
+        let translations = self.translate(&pages);
+        #[cfg(not(feature = "llm"))]
+        let translations: Vec<Option<String>> = Vec::new();
+        // Translations as printed after each page in pdf and md.
+        let translated: Vec<String> = translations.iter().map(|text| text.clone().unwrap_or_default()).collect();
+        let titled = pages.iter().filter(|page| page.title.is_some()).count();
+        info!("Detected titles for {} of {} pages.", titled, pages.len());
+        #[cfg(feature = "templates")]
+        if self.config.template.is_some() && !matches!(self.config.output_format.as_str(), "md" | "html") {
+            warn!("--template only applies to the md and html formats; ignoring it.");
+        }
+        if self.config.split_by != "none" && !matches!(self.config.output_format.as_str(), "pdf" | "md") {
+            warn!("--split-by only applies to the pdf and md formats; ignoring it.");
+        }
+        if self.config.new_content_only && !matches!(self.config.output_format.as_str(), "md" | "jsonl" | "txt") {
+            warn!("--new-content-only only applies to the md, jsonl, and txt formats; ignoring it.");
+        }
+        if self.config.low_confidence.is_some()
+            && !matches!(self.config.output_format.as_str(), "pdf" | "md" | "jsonl")
+        {
+            warn!("--low-confidence only applies to the pdf, md, and jsonl formats; ignoring it.");
+        }
+        if self.config.keyword_index && !matches!(self.config.output_format.as_str(), "pdf" | "md") {
+            warn!("--keyword-index only applies to the pdf and md formats; ignoring it.");
+        }
+        if self.config.slide_tags
+            && !matches!(self.config.output_format.as_str(), "md" | "obsidian")
+            && !self.config.generate_index
+        {
+            warn!("--slide-tags only applies to the md and obsidian formats and index.json; ignoring it.");
+        }
+        #[cfg(feature = "llm")]
+        if self.config.llm_url.is_some() && !self.config.generate_index && self.config.translate_to.is_none() {
+            warn!("--llm-url only applies to index.json; ignoring it.");
+        }
+        #[cfg(feature = "llm")]
+        if self.config.translate_to.is_some()
+            && !matches!(self.config.output_format.as_str(), "pdf" | "md")
+            && !self.config.generate_index
+        {
+            warn!("--translate-to only applies to the pdf and md formats and index.json; ignoring it.");
+        }
+        if self.config.use_subtitles
+            && !matches!(self.config.output_format.as_str(), "pdf" | "md" | "html")
+            && !self.config.generate_index
+        {
+            warn!("--use-subtitles only applies to the pdf, md, and html formats and index.json; ignoring it.");
+        }
+        if self.config.transcript.is_some()
+            && !matches!(self.config.output_format.as_str(), "pdf" | "md" | "html")
+            && !self.config.generate_index
+        {
+            warn!("--transcript only applies to the pdf, md, and html formats and index.json; ignoring it.");
+        }
+        #[cfg(feature = "whisper")]
+        if self.config.whisper_model.is_some()
+            && !matches!(self.config.output_format.as_str(), "pdf" | "md" | "html")
+            && !self.config.generate_index
+        {
+            warn!("--whisper-model only applies to the pdf, md, and html formats and index.json; ignoring it.");
+        }
+        if self.config.name_template.is_some()
+            && !matches!(self.config.output_format.as_str(), "pdf" | "md" | "img")
+            && self.config.review_report.is_none()
+        {
+            warn!("--name-template only applies to the pdf, md, and img formats and the review report; ignoring it.");
+        }
+        // Files holding each page, relative to the result directory.
+        let outputs: Vec<Vec<String>> = match self.config.output_format.as_str() {
             "pdf" => {
                 info!("Building searchable PDF document...");
-                let pdf_path = self.result_dir.join("document.pdf");
-                document_builder::build_pdf(frames, ocr_results, &pdf_path)?;
-                info!("Successfully created PDF: {:?}", pdf_path);
+                let mut options = document_builder::PdfOptions {
+                    metadata: self.document_metadata(),
+                    pdfa: self.config.pdf_profile == "pdfa-2b",
+                    tagged: self.config.pdf_profile == "pdfua-1",
+                    language: Some(ocr::language_tag(&self.config.lang)),
+                    page_size: match self.config.pdf_page_size.as_str() {
+                        "native" => document_builder::PageSize::Native {
+                            dpi: self.config.pdf_page_dpi,
+                        },
+                        _ => document_builder::PageSize::A4,
+                    },
+                    image_compression: match self.config.pdf_image_format.as_str() {
+                        "flate" => document_builder::ImageCompression::Flate,
+                        "mrc" => document_builder::ImageCompression::Mrc {
+                            quality: self.config.pdf_jpeg_quality,
+                        },
+                        _ => document_builder::ImageCompression::Jpeg {
+                            quality: self.config.pdf_jpeg_quality,
+                        },
+                    },
+                    max_image_dpi: self.config.pdf_max_dpi,
+                    table_of_contents: self.config.pdf_toc,
+                    video_url: self.config.video_url.clone(),
+                    encryption: self.pdf_encryption(),
+                    speech: Vec::new(),
+                    translations: Vec::new(),
+                    keyword_index: self.config.keyword_index,
+                    url_links: self.config.links,
+                    confidence: self.confidence_policy(document_builder::LowConfidence::Drop),
+                    formulas: self.config.formulas,
+                    auto_colour: self.config.pdf_auto_colour,
+                };
+                let mut outputs = Vec::with_capacity(pages.len());
+                let parts = self.document_parts(&pages);
+                let files = self.document_files(&parts, "pdf")?;
+                for (part, pdf_file) in parts.into_iter().zip(files) {
+                    let pdf_path = self.result_dir.join(&pdf_file);
+                    options.metadata.title = part.title;
+                    options.speech = speech.get(part.pages.clone()).unwrap_or_default().to_vec();
+                    options.translations = translated.get(part.pages.clone()).unwrap_or_default().to_vec();
+                    let written = document_builder::build_pdf(&pages[part.pages.clone()], &options, &pdf_path)?;
+                    info!("Successfully created PDF: {:?}", written.document);
+                    outputs.extend(self.page_files(&written, part.pages.len()));
+                }
+                outputs
             }
             "md" => {
-                info!("Markdown generation is not yet implemented.");
+                info!("Building Markdown document...");
+                let mut outputs = Vec::with_capacity(pages.len());
+                let parts = self.document_parts(&pages);
+                let files = self.document_files(&parts, "md")?;
+                // Each document saves its images to the `images` folder next to it.
+                let dirs: HashSet<_> = files.iter().map(|file| Path::new(file).parent()).collect();
+                ensure!(
+                    dirs.len() == files.len(),
+                    "Markdown documents need a folder each for their images; include {{index}} or {{title}} \
+                     in a folder of --name-template"
+                );
+                for (part, md_file) in parts.into_iter().zip(files) {
+                    let md_path = self.result_dir.join(&md_file);
+                    let part_pages = &pages[part.pages.clone()];
+                    let templated = self.render_template(part_pages, part.title.clone(), false, false, &md_path)?;
+                    let written = if let Some(written) = templated {
+                        written
+                    } else {
+                        let options = document_builder::MarkdownOptions {
+                            heading_level: self.config.md_heading_level,
+                            document_title: part.title.clone(),
+                            video_url: self.config.video_url.clone(),
+                            speech: speech.get(part.pages.clone()).unwrap_or_default().to_vec(),
+                            keyword_index: self.config.keyword_index,
+                            tags: tags.get(part.pages.clone()).unwrap_or_default().to_vec(),
+                            url_links: self.config.links,
+                            figures: self.config.figures,
+                            formulas: self.config.formulas,
+                            new_content_only: self.config.new_content_only,
+                            translations: translated.get(part.pages.clone()).unwrap_or_default().to_vec(),
+                            confidence: self.confidence_policy(document_builder::LowConfidence::Keep),
+                        };
+                        document_builder::build_markdown(part_pages, &options, &md_path)?
+                    };
+                    info!("Successfully created Markdown: {:?}", written.document);
+                    outputs.extend(self.page_files(&written, part_pages.len()));
+                }
+                outputs
+            }
+            "txt" => {
+                info!("Building transcript...");
+                let txt_path = self.result_dir.join("transcript.txt");
+                let written = document_builder::build_transcript(&pages, &txt_path)?;
+                info!("Successfully created transcript: {:?}", written.document);
+                self.page_files(&written, pages.len())
+            }
+            "jsonl" => {
+                info!("Building JSON lines export...");
+                let jsonl_path = self.result_dir.join("document.jsonl");
+                let options = document_builder::JsonlOptions {
+                    new_content_only: self.config.new_content_only,
+                    confidence: self.confidence_policy(document_builder::LowConfidence::Keep),
+                };
+                let written = document_builder::build_jsonl(&pages, &options, &jsonl_path)?;
+                info!("Successfully created JSON lines: {:?}", written.document);
+                self.page_files(&written, pages.len())
+            }
+            "html" => {
+                info!("Building HTML document...");
+                let html_path = self.result_dir.join("document.html");
+                let embed_images = !self.config.html_link_images;
+                let templated = self.render_template(&pages, self.document_title(), true, embed_images, &html_path)?;
+                let written = if let Some(written) = templated {
+                    written
+                } else {
+                    let options = document_builder::HtmlOptions {
+                        document_title: self.document_title(),
+                        video_url: self.config.video_url.clone(),
+                        embed_images: !self.config.html_link_images,
+                        figures: self.config.figures,
+                        formulas: self.config.formulas,
+                        speech: speech.clone(),
+                    };
+                    document_builder::build_html(&pages, &options, &html_path)?
+                };
+                info!("Successfully created HTML: {:?}", written.document);
+                self.page_files(&written, pages.len())
+            }
+            "tex" => {
+                info!("Building LaTeX document...");
+                let tex_path = self.result_dir.join("document.tex");
+                let options = document_builder::LatexOptions {
+                    document_title: self.document_title(),
+                    author: self.config.author.clone(),
+                    video_url: self.config.video_url.clone(),
+                };
+                let written = document_builder::build_latex(&pages, &options, &tex_path)?;
+                info!("Successfully created LaTeX: {:?}", written.document);
+                self.page_files(&written, pages.len())
+            }
+            "srt" | "vtt" => {
+                info!("Building subtitles...");
+                let format = self.config.output_format.as_str();
+                let subtitles_path = self.result_dir.join(format!("slides.{}", format));
+                let options = subtitles::SubtitleOptions {
+                    format: match format {
+                        "vtt" => subtitles::SubtitleFormat::Vtt,
+                        _ => subtitles::SubtitleFormat::Srt,
+                    },
+                    titles_only: self.config.subtitle_text == "title",
+                    duration: analysis.duration().unwrap_or(0.0),
+                };
+                subtitles::build_subtitles(&pages, &options, &subtitles_path)?;
+                info!("Successfully created subtitles: {:?}", subtitles_path);
+                vec![vec![format!("slides.{}", format)]; pages.len()]
+            }
+            "alto" => {
+                info!("Building ALTO XML files...");
+                alto::build_alto(&pages, &self.result_dir)?;
+                info!("Successfully created {} ALTO files in {:?}", pages.len(), self.result_dir.join("alto"));
+                (0..pages.len())
+                    .map(|i| vec![alto::alto_path(i), document_builder::page_image_path(i)])
+                    .collect()
+            }
+            #[cfg(feature = "sqlite")]
+            "sqlite" => {
+                info!("Building SQLite database...");
+                let db_path = self.result_dir.join("document.db");
+                let options = database::DatabaseOptions {
+                    document_title: self.document_title(),
+                    source: self.source_name(),
+                    sensitivity: analysis.sensitivity,
+                    link_images: self.config.sqlite_link_images,
+                };
+                database::build_database(&pages, analysis, &options, &db_path)?;
+                info!("Successfully created database: {:?}", db_path);
+                (0..pages.len())
+                    .map(|i| {
+                        let mut files = vec!["document.db".to_string()];
+                        if self.config.sqlite_link_images {
+                            files.push(document_builder::page_image_path(i));
+                        }
+                        files
+                    })
+                    .collect()
             }
             "img" => {
                 info!("Saving unique frames as images to {:?}", self.result_dir);
-                frames.par_iter().enumerate().try_for_each(|(i, frame)| -> Result<()> {
-                    let frame_path = self.result_dir.join(format!("frame_{:05}.png", i));
-                    frame.save(&frame_path)
-                         .with_context(|| format!("Failed to save frame to {:?}", frame_path))?;
+                let source_name = self.source_name();
+                let fields = self.name_template.as_ref().map(|_| self.name_fields());
+                let file_names: Vec<String> = pages
+                    .iter()
+                    .enumerate()
+                    .map(|(i, page)| {
+                        let stem = match self.name_template.as_ref().zip(fields.as_ref()) {
+                            Some((template, fields)) => {
+                                template.render(&naming::NameFields { index: i + 1, ..fields.clone() })
+                            }
+                            None => format!("frame_{:05}", i),
+                        };
+                        match &page.title {
+                            Some(title) if self.config.title_filenames => {
+                                format!("{}_{}.png", stem, paths::sanitize_file_name(title))
+                            }
+                            _ => format!("{}.png", stem),
+                        }
+                    })
+                    .collect();
+                naming::ensure_unique(&file_names)?;
+                let dirs: HashSet<_> = file_names.iter().filter_map(|name| Path::new(name).parent()).collect();
+                for dir in dirs {
+                    fs::create_dir_all(self.result_dir.join(dir))
+                        .with_context(|| format!("Failed to create directory {:?}", dir))?;
+                }
+                pages.par_iter().zip(&file_names).enumerate().try_for_each(|(i, (page, file_name))| -> Result<()> {
+                    let frame_path = self.result_dir.join(file_name);
+                    let metadata = xmp::PageMetadata {
+                        title: page.title.clone(),
+                        text: page.ocr.map(layout::page_text).unwrap_or_default(),
+                        source: source_name.clone(),
+                        page: i + 1,
+                        timestamp: page.timestamp,
+                    };
+                    xmp::save_png_with_xmp(page.image, &frame_path, &xmp::packet(&metadata))
+                        .with_context(|| format!("Failed to save frame to {:?}", frame_path))?;
                     Ok(())
                 })?;
-                info!("Successfully saved {} frames to {:?}", frames.len(), self.result_dir);
+                info!("Successfully saved {} frames to {:?}", pages.len(), self.result_dir);
+                file_names.into_iter().map(|name| vec![name]).collect()
+            }
+            "iiif" => {
+                info!("Building IIIF manifest and image tiles...");
+                let options = iiif::IiifOptions {
+                    base_url: self.config.iiif_base_url.clone(),
+                    document_title: self.document_title(),
+                    source: self.source_name(),
+                };
+                iiif::build_iiif(&pages, &options, &self.result_dir)?;
+                info!("Successfully created IIIF manifest in {:?}", self.result_dir.join(iiif::IIIF_DIR));
+                let manifest = format!("{}/{}", iiif::IIIF_DIR, iiif::MANIFEST_NAME);
+                (0..pages.len())
+                    .map(|i| vec![manifest.clone(), format!("{}/{}/info.json", iiif::IIIF_DIR, iiif::image_dir(i))])
+                    .collect()
+            }
+            "obsidian" => {
+                info!("Building Obsidian vault...");
+                let options = obsidian::VaultOptions {
+                    document_title: self.document_title(),
+                    source: self.source_name(),
+                    video_url: self.config.video_url.clone(),
+                    tags: tags.clone(),
+                };
+                let notes = obsidian::build_vault(&pages, &options, &self.result_dir)?;
+                info!("Successfully created {} notes in {:?}", notes.len(), self.result_dir);
+                notes
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, note)| vec![note, obsidian::attachment_path(i)])
+                    .collect()
+            }
+            "video" => {
+                info!("Encoding recap video...");
+                let video_name = format!("recap.{}", self.config.recap_container);
+                let video_path = self.result_dir.join(&video_name);
+                let options = recap::RecapOptions {
+                    page_seconds: self.config.recap_seconds,
+                    burn_timestamps: self.config.recap_timestamps,
+                };
+                recap::build_recap_video(&pages, &options, &video_path)?;
+                info!("Successfully created recap video: {:?}", video_path);
+                vec![vec![video_name]; pages.len()]
             }
             _ => unreachable!(),
+        };
+
+        #[cfg(feature = "search")]
+        if self.config.search_index {
+            let index_dir = self.result_dir.join(search::INDEX_DIR);
+            search::build_index(&pages, &analysis.kept_indices, &index_dir)?;
+            info!("Successfully created search index: {:?}", index_dir);
+        }
+        if self.config.contact_sheet {
+            let title = self.document_title();
+            contact_sheet::build_contact_sheet(&pages, &analysis.kept_indices, &title, &self.result_dir)?;
+            let sheet_path = self.result_dir.join(contact_sheet::SHEET_HTML);
+            info!("Successfully created contact sheet: {:?}", sheet_path);
+        }
+        if self.config.ocr_debug {
+            ocr_debug::write_debug(&pages, &self.config.output_dir)?;
+            info!("Wrote OCR debug files to {:?}", self.config.output_dir.join(ocr_debug::DEBUG_DIR));
+        }
+        if self.config.chapters {
+            match pages.iter().map(|page| page.timestamp).collect::<Option<Vec<f64>>>() {
+                Some(timestamps) => {
+                    // Without the end of the video, the last chapter ends where its page appeared.
+                    let duration = analysis.duration().or(timestamps.last().copied()).unwrap_or(0.0);
+                    let marks = chapters::from_pages(&pages, duration);
+                    if marks.len() < 3 {
+                        warn!("Only {} chapters were found; YouTube needs at least 3 to show them.", marks.len());
+                    }
+                    chapters::write_chapters(&marks, &self.result_dir)?;
+                    let list_path = self.result_dir.join(chapters::DESCRIPTION_NAME);
+                    info!("Successfully created {} chapters: {:?}", marks.len(), list_path);
+                }
+                None => warn!("Page timestamps are unavailable; --chapters will not be written."),
+            }
+        }
+        if self.config.links {
+            let entries = links::collect_links(&pages);
+            let links_path = self.result_dir.join(links::LINKS_NAME);
+            links::write_links(&entries, &links_path)?;
+            info!("Listed {} URLs in {:?}", entries.len(), links_path);
+        }
+        if let Some(format) = &self.config.review_report {
+            let options = review::ReviewOptions {
+                format: match format.as_str() {
+                    "pdf" => review::ReviewFormat::Pdf,
+                    _ => review::ReviewFormat::Markdown,
+                },
+                threshold: self.config.review_threshold,
+                document_title: self.document_title(),
+                name: self
+                    .name_template
+                    .as_ref()
+                    .map(|template| format!("{}_review", template.render(&self.name_fields()))),
+            };
+            let (review_file, words) = review::build_review(&pages, &options, &self.result_dir)?;
+            info!("Listed {} low-confidence words for review in {:?}", words, self.result_dir.join(review_file));
+        }
+        if self.config.generate_index {
+            #[cfg(feature = "llm")]
+            let processed = self.post_process(&pages);
+            #[cfg(not(feature = "llm"))]
+            let processed = Vec::new();
+            self.write_index(analysis, &pages, outputs, aligned_speech, tags, processed, translations)?;
         }
         Ok(())
     }
-}
\ No newline at end of file
+
+    /// Packages the result directory, OCR report, and analysis log into `bundle.zip`
+    /// in the output directory.
+    #[cfg(feature = "bundle")]
+    fn write_bundle(&self) -> Result<()> {
+        let zip_path = self.config.output_dir.join("bundle.zip");
+        let manifest = bundle::Manifest {
+            source: self.source_name(),
+            format: self.config.output_format.clone(),
+            generator: format!("videodocparser {}", env!("CARGO_PKG_VERSION")),
+            files: Vec::new(),
+        };
+        bundle::write_zip(
+            &self.config.output_dir,
+            &["result", "ocr", "analysis/frame_analysis.json"],
+            manifest,
+            &zip_path,
+        )?;
+        info!("Successfully created bundle: {:?}", zip_path);
+        Ok(())
+    }
+
+    /// Writes `run.json` to the output directory, recording the configuration,
+    /// the input, the number of pages, the stage timings, and every file the
+    /// run produced.
+    fn write_run_manifest(
+        &self,
+        started: String,
+        stages: Vec<run_manifest::StageTiming>,
+        pages: usize,
+        elapsed: Duration,
+    ) -> Result<()> {
+        let input = &self.config.input_file;
+        let manifest = run_manifest::RunManifest {
+            manifest_version: run_manifest::MANIFEST_VERSION,
+            tool: env!("CARGO_PKG_NAME").to_string(),
+            tool_version: env!("CARGO_PKG_VERSION").to_string(),
+            started,
+            config: serde_json::to_value(&self.config)?,
+            input: run_manifest::FileRecord::of(input, input.to_string_lossy().into_owned())?,
+            pages,
+            stages,
+            total_seconds: elapsed.as_secs_f64(),
+            artifacts: run_manifest::collect_artifacts(
+                &self.config.output_dir,
+                &["result", "ocr", "analysis", "bundle.zip"],
+            )?,
+        };
+        let manifest_path = self.config.output_dir.join(run_manifest::RUN_MANIFEST_NAME);
+        run_manifest::write(&manifest, &manifest_path)?;
+        info!("Successfully created run manifest: {:?}", manifest_path);
+        Ok(())
+    }
+
+    /// Cleans and, if asked, summarizes the OCR text of every page with the
+    /// `--llm-url` backend; empty when no backend is configured.
+    #[cfg(feature = "llm")]
+    fn post_process(&self, pages: &[Page]) -> Vec<Option<postprocess::ProcessedText>> {
+        let Some(url) = &self.config.llm_url else {
+            return Vec::new();
+        };
+        info!("Post-processing the OCR text with {} at {}...", self.config.llm_model, url);
+        let backend = llm::ChatBackend::new(url, &self.config.llm_model);
+        let texts: Vec<String> =
+            pages.iter().map(|page| page.ocr.map(layout::page_text).unwrap_or_default()).collect();
+        postprocess::process_pages(&backend, &texts, self.config.llm_summaries)
+    }
+
+    /// Translates the OCR text of every page into `--translate-to` with the
+    /// `--llm-url` backend; empty when no translation was asked for or no
+    /// output uses it.
+    #[cfg(feature = "llm")]
+    fn translate(&self, pages: &[Page]) -> Vec<Option<String>> {
+        let (Some(url), Some(language)) = (&self.config.llm_url, &self.config.translate_to) else {
+            return Vec::new();
+        };
+        if !matches!(self.config.output_format.as_str(), "pdf" | "md") && !self.config.generate_index {
+            return Vec::new();
+        }
+        info!("Translating the OCR text into {} with {} at {}...", language, self.config.llm_model, url);
+        let backend = llm::ChatBackend::new(url, &self.config.llm_model);
+        let texts: Vec<String> =
+            pages.iter().map(|page| page.ocr.map(layout::page_text).unwrap_or_default()).collect();
+        translate::translate_pages(&backend, &texts, language)
+    }
+
+    /// Writes `index.json` next to the generated output.
+    #[allow(clippy::too_many_arguments)]
+    fn write_index(
+        &self,
+        analysis: &AnalysisResult,
+        pages: &[Page],
+        outputs: Vec<Vec<String>>,
+        speech: Vec<Vec<transcript::Cue>>,
+        tags: Vec<Vec<String>>,
+        processed: Vec<Option<postprocess::ProcessedText>>,
+        translations: Vec<Option<String>>,
+    ) -> Result<()> {
+        let mut speech = speech.into_iter();
+        let mut tags = tags.into_iter();
+        let mut processed = processed.into_iter();
+        let mut translations = translations.into_iter();
+        let index = index::Index {
+            source: self.source_name(),
+            format: self.config.output_format.clone(),
+            pages: pages
+                .iter()
+                .zip(outputs)
+                .enumerate()
+                .map(|(i, (page, files))| {
+                    let cues = speech.next().unwrap_or_default();
+                    let page_tags = tags.next().unwrap_or_default();
+                    let text = processed.next().flatten();
+                    let mut entry =
+                        index::IndexEntry::new(i, page, analysis.kept_indices[i], files, cues, page_tags, text);
+                    entry.translation = translations.next().flatten();
+                    entry.revisits = analysis.revisit_timestamps.get(i).cloned().unwrap_or_default();
+                    entry.segment = analysis.segments.get(i).cloned();
+                    entry
+                })
+                .collect(),
+        };
+        let index_path = self.result_dir.join("index.json");
+        index::write_index(&index, &index_path)?;
+        info!("Successfully created index: {:?}", index_path);
+        Ok(())
+    }
+}
+
+/// A run of consecutive pages written as one document.
+struct DocumentPart {
+    /// Subdirectory of the result directory holding the document, if split.
+    dir: Option<String>,
+    title: String,
+    pages: std::ops::Range<usize>,
+}
+
+impl DocumentPart {
+    /// Path of a file of this part, relative to the result directory.
+    fn file(&self, name: &str) -> String {
+        match &self.dir {
+            Some(dir) => format!("{}/{}", dir, name),
+            None => name.to_string(),
+        }
+    }
+}
+
+/// Runs one stage of the pipeline and records how long it took.
+fn timed<T>(
+    stages: &mut Vec<run_manifest::StageTiming>,
+    name: &str,
+    stage: impl FnOnce() -> Result<T>,
+) -> Result<T> {
+    let start = Instant::now();
+    let result = stage();
+    stages.push(run_manifest::StageTiming { name: name.to_string(), seconds: start.elapsed().as_secs_f64() });
+    result
+}
+
+/// Serializes a secret as `"<redacted>"` when it is set, so the run manifest
+/// shows that it was used without revealing it.
+fn redact<S: serde::Serializer>(value: &Option<String>, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+    value.as_ref().map(|_| "<redacted>").serialize(serializer)
+}
+
+/// Reads the `SOURCE_DATE_EPOCH` environment variable (seconds since the Unix
+/// epoch), which fixes the creation date of generated documents.
+fn source_date_epoch() -> Option<xmp::DateTime> {
+    let value = std::env::var("SOURCE_DATE_EPOCH").ok()?;
+    let date = value.trim().parse().ok().and_then(xmp::DateTime::from_unix_timestamp);
+    if date.is_none() {
+        warn!("Ignoring invalid SOURCE_DATE_EPOCH {:?}", value);
+    }
+    date
+}
+
+/// Creates the progress bar for the analysis stage, or a spinner when the length is unknown.
+fn analysis_progress_bar(len: Option<u64>) -> Result<ProgressBar> {
+    let pb = match len {
+        Some(count) => {
+            let bar = ProgressBar::new(count);
+            bar.set_style(
+                ProgressStyle::default_bar()
+                    .template(&format!("{{spinner:.green}} {} [{{bar:40.cyan/blue}}] {{pos}}/{{len}} ({{percent}}%) [{{elapsed_precise}}<{{eta}}]", tr(Msg::AnalyzingFrames)))?
+                    .progress_chars("##-"),
+            );
+            bar
+        }
+        None => {
+            warn!("Could not determine total frame count. Using spinner as fallback.");
+            let bar = ProgressBar::new_spinner();
+            bar.set_style(
+                ProgressStyle::default_spinner()
+                    .template(&format!("{{spinner:.green}} {}... [{{elapsed_precise}}] {{pos}} {}", tr(Msg::AnalyzingFrames), tr(Msg::FramesProcessed)))?,
+            );
+            bar
+        }
+    };
+    pb.enable_steady_tick(std::time::Duration::from_millis(100));
+    Ok(pb)
+}
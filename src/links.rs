@@ -0,0 +1,114 @@
+//! Links Module
+//!
+//! Handles the URLs recognized in the OCR text: each word that spells a web
+//! address is validated and normalized, made clickable in the PDF and Markdown
+//! outputs, and listed in `links.json` with the pages it appeared on.
+
+use crate::document_builder::Page;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+/// File name of the link list in the result directory.
+pub const LINKS_NAME: &str = "links.json";
+
+/// Top-level domains accepted without a scheme or `www.` prefix. Other bare
+/// words with a dot, such as `node.js` or `slides.pdf`, are too often not links.
+const BARE_DOMAIN_TLDS: &[&str] = &[
+    "ai", "app", "com", "de", "dev", "edu", "es", "eu", "fr", "gov", "info", "io", "me", "net", "org", "pt", "uk",
+];
+
+/// One URL and where it appeared.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LinkEntry {
+    pub url: String,
+    pub occurrences: Vec<LinkOccurrence>,
+}
+
+/// A page showing a URL.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LinkOccurrence {
+    /// Page number, starting at 1.
+    pub page: usize,
+    /// When the page appears in the video, in seconds, if the frame rate is known.
+    pub timestamp: Option<f64>,
+}
+
+/// Returns the normalized URL an OCR word spells, or `None` when it is not a
+/// web address. Surrounding punctuation is dropped, addresses without a scheme
+/// get `https://`, and the scheme and host are lowercased.
+pub fn normalize_url(word: &str) -> Option<String> {
+    let mut candidate = word.trim_start_matches(['(', '<', '[', '"', '\'']);
+    candidate = candidate.trim_end_matches(['.', ',', ';', ':', '!', '?', '>', ']', '"', '\'']);
+    // A closing parenthesis belongs to the URL only if it opens one too.
+    while candidate.ends_with(')') && candidate.matches('(').count() < candidate.matches(')').count() {
+        candidate = candidate[..candidate.len() - 1].trim_end_matches(['.', ',', ';', ':', '!', '?']);
+    }
+
+    let lower = candidate.to_ascii_lowercase();
+    let (scheme, rest, bare) = if lower.starts_with("https://") || lower.starts_with("http://") {
+        let split = candidate.find("://")?;
+        (lower[..split].to_string(), &candidate[split + 3..], false)
+    } else if lower.starts_with("www.") {
+        ("https".to_string(), candidate, false)
+    } else {
+        ("https".to_string(), candidate, true)
+    };
+
+    let host_end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+    let (authority, path) = rest.split_at(host_end);
+    let (host, port) = match authority.rsplit_once(':') {
+        Some((host, port)) if !port.is_empty() && port.bytes().all(|b| b.is_ascii_digit()) => (host, Some(port)),
+        Some(_) => return None,
+        None => (authority, None),
+    };
+    let host = host.to_ascii_lowercase();
+    let labels: Vec<&str> = host.split('.').collect();
+    let valid_label = |label: &&str| {
+        !label.is_empty()
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-')
+    };
+    let tld = labels.last()?;
+    let valid_tld = tld.len() >= 2 && tld.bytes().all(|b| b.is_ascii_alphabetic());
+    if labels.len() < 2 || !labels.iter().all(valid_label) || !valid_tld {
+        return None;
+    }
+    if bare && (port.is_some() || !BARE_DOMAIN_TLDS.contains(tld)) {
+        return None;
+    }
+
+    let port = port.map(|port| format!(":{}", port)).unwrap_or_default();
+    Some(format!("{}://{}{}{}", scheme, host, port, path))
+}
+
+/// Lists every URL on the pages, in order of first appearance, with each page
+/// it appears on once.
+pub fn collect_links(pages: &[Page]) -> Vec<LinkEntry> {
+    let mut entries: Vec<LinkEntry> = Vec::new();
+    for (i, page) in pages.iter().enumerate() {
+        let Some(ocr) = page.ocr else { continue };
+        for url in ocr.words.iter().filter_map(|word| normalize_url(&word.text)) {
+            let position = match entries.iter().position(|entry| entry.url == url) {
+                Some(position) => position,
+                None => {
+                    entries.push(LinkEntry { url, occurrences: Vec::new() });
+                    entries.len() - 1
+                }
+            };
+            let occurrences = &mut entries[position].occurrences;
+            if occurrences.last().is_none_or(|last| last.page != i + 1) {
+                occurrences.push(LinkOccurrence { page: i + 1, timestamp: page.timestamp });
+            }
+        }
+    }
+    entries
+}
+
+/// Writes the link list as pretty-printed JSON.
+pub fn write_links(entries: &[LinkEntry], path: &Path) -> Result<()> {
+    let json = serde_json::to_string_pretty(entries).context("Failed to serialize link list")?;
+    fs::write(path, json).with_context(|| format!("Failed to write link list to {:?}", path))
+}
@@ -0,0 +1,94 @@
+//! LLM Module
+//!
+//! Handles the OpenAI-compatible [`TextPostProcessor`] and [`Translator`]
+//! backend: every request is a chat completion sent to
+//! `<base URL>/chat/completions`, so the OpenAI API and local servers that
+//! mimic it (llama.cpp, Ollama, vLLM) all work.
+
+use crate::postprocess::TextPostProcessor;
+use crate::translate::Translator;
+use anyhow::{Context, Result};
+use serde_json::{Value, json};
+use std::time::Duration;
+
+/// Model asked when `--llm-model` is not given.
+pub const DEFAULT_MODEL: &str = "gpt-4o-mini";
+/// Environment variable holding the API key, sent as a bearer token when set.
+pub const API_KEY_VAR: &str = "OPENAI_API_KEY";
+/// Longest a single request may take, generation included.
+const TIMEOUT: Duration = Duration::from_secs(120);
+
+const CLEAN_INSTRUCTIONS: &str = "You clean up text recognized by OCR from a presentation slide. \
+    Fix misrecognized characters and words, remove stray symbols and fragments that are recognition noise, \
+    and rejoin words split across lines. Keep the wording, language, and line structure otherwise unchanged. \
+    Reply with the cleaned text only.";
+const TRANSLATE_INSTRUCTIONS: &str = "You translate text recognized by OCR from a presentation slide into {language}. \
+    Keep the line structure, and leave code, names, and URLs as they are. Reply with the translation only.";
+const SUMMARY_INSTRUCTIONS: &str = "You summarize the text of a presentation slide in one or two sentences, \
+    in the language of the slide. Reply with the summary only.";
+
+/// Chat-completion client for an OpenAI-compatible API.
+pub struct ChatBackend {
+    agent: ureq::Agent,
+    endpoint: String,
+    model: String,
+    api_key: Option<String>,
+}
+
+impl ChatBackend {
+    /// Creates a client for the API at `base_url` (e.g. `https://api.openai.com/v1`),
+    /// reading the API key from [`API_KEY_VAR`].
+    pub fn new(base_url: &str, model: &str) -> Self {
+        let config = ureq::Agent::config_builder().timeout_global(Some(TIMEOUT)).build();
+        Self {
+            agent: config.into(),
+            endpoint: format!("{}/chat/completions", base_url.trim_end_matches('/')),
+            model: model.to_string(),
+            api_key: std::env::var(API_KEY_VAR).ok().filter(|key| !key.is_empty()),
+        }
+    }
+
+    /// Sends `text` with `instructions` as the system message and returns the reply.
+    fn complete(&self, instructions: &str, text: &str) -> Result<String> {
+        let body = json!({
+            "model": self.model,
+            "temperature": 0,
+            "messages": [
+                { "role": "system", "content": instructions },
+                { "role": "user", "content": text },
+            ],
+        });
+        let mut request = self.agent.post(&self.endpoint);
+        if let Some(key) = &self.api_key {
+            request = request.header("Authorization", format!("Bearer {}", key));
+        }
+        let mut response =
+            request.send_json(&body).with_context(|| format!("Request to {} failed", self.endpoint))?;
+        let reply: Value = response.body_mut().read_json().context("Failed to parse the chat completion")?;
+        reply_text(&reply)
+    }
+}
+
+impl TextPostProcessor for ChatBackend {
+    fn clean(&self, text: &str) -> Result<String> {
+        self.complete(CLEAN_INSTRUCTIONS, text)
+    }
+
+    fn summarize(&self, text: &str) -> Result<String> {
+        self.complete(SUMMARY_INSTRUCTIONS, text)
+    }
+}
+
+impl Translator for ChatBackend {
+    fn translate(&self, text: &str, language: &str) -> Result<String> {
+        self.complete(&TRANSLATE_INSTRUCTIONS.replace("{language}", language), text)
+    }
+}
+
+/// Extracts the text of the first choice of a chat completion.
+pub fn reply_text(reply: &Value) -> Result<String> {
+    let content = reply["choices"][0]["message"]["content"]
+        .as_str()
+        .with_context(|| format!("Chat completion has no message content: {}", reply))?;
+    Ok(content.trim().to_string())
+}
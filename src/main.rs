@@ -9,13 +9,33 @@
 static ALLOC: dhat::Alloc = dhat::Alloc;
 
 use std::ops::RangeInclusive;
-use clap::Parser;
+use clap::{CommandFactory, FromArgMatches, Parser};
 use log::{error, info};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use videodocparser::diff;
+use videodocparser::i18n::{self, Lang, Msg};
 use videodocparser::run;
 
 const SENSITIVITY_RANGE: RangeInclusive<f64> = 0.0..=1.0;
 
+/// A `--sensitivity` value: a threshold, or `auto` to calibrate one per video.
+#[derive(Clone, Copy, Debug)]
+enum Sensitivity {
+    Auto,
+    Fixed(f64),
+}
+
+/// The sensitivity the first pass of `--sensitivity auto` hashes frames with,
+/// and falls back to when the video cannot be calibrated.
+const DEFAULT_SENSITIVITY: f64 = 0.9;
+
+fn sensitivity_or_auto(s: &str) -> Result<Sensitivity, String> {
+    match s {
+        "auto" => Ok(Sensitivity::Auto),
+        _ => sensitivity_in_range(s).map(Sensitivity::Fixed).map_err(|e| format!("{}, or \"auto\"", e)),
+    }
+}
+
 fn sensitivity_in_range(s: &str) -> Result<f64, String> {
     match s.parse::<f64>() {
         Ok(val) if SENSITIVITY_RANGE.contains(&val) => Ok(val),
@@ -27,44 +47,703 @@ fn sensitivity_in_range(s: &str) -> Result<f64, String> {
     }
 }
 
+fn upscale_factor(s: &str) -> Result<u32, String> {
+    match s.parse::<u32>() {
+        Ok(val) if videodocparser::upscale::FACTORS.contains(&val) => Ok(val),
+        _ => Err("Upscale factor must be 2 or 4".to_string()),
+    }
+}
+
+fn positive_seconds(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(val) if val > 0.0 && val.is_finite() => Ok(val),
+        _ => Err("Duration must be a positive number of seconds".to_string()),
+    }
+}
+
+fn positive_milliseconds(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(val) if val > 0.0 && val.is_finite() => Ok(val),
+        _ => Err("Duration must be a positive number of milliseconds".to_string()),
+    }
+}
+
+/// A duration such as "2s" or "500ms"; plain numbers are seconds.
+fn duration_seconds(s: &str) -> Result<f64, String> {
+    let (number, scale) = match s.strip_suffix("ms") {
+        Some(ms) => (ms, 0.001),
+        None => (s.strip_suffix('s').unwrap_or(s), 1.0),
+    };
+    match number.trim().parse::<f64>() {
+        Ok(val) if val > 0.0 && val.is_finite() => Ok(val * scale),
+        _ => Err("Duration must be positive, in seconds (\"2s\") or milliseconds (\"500ms\")".to_string()),
+    }
+}
+
+fn positive_dpi(s: &str) -> Result<f32, String> {
+    match s.parse::<f32>() {
+        Ok(val) if val > 0.0 && val.is_finite() => Ok(val),
+        _ => Err("Resolution must be a positive number of dots per inch".to_string()),
+    }
+}
+
+fn confidence_in_range(s: &str) -> Result<f32, String> {
+    match s.parse::<f32>() {
+        Ok(val) if (0.0..=100.0).contains(&val) => Ok(val),
+        _ => Err("Confidence must be a number from 0 to 100".to_string()),
+    }
+}
+
+fn key_value(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((key, value)) if !key.trim().is_empty() => Ok((key.trim().to_string(), value.trim().to_string())),
+        _ => Err("Expected a key=value pair".to_string()),
+    }
+}
+
+fn http_url(s: &str) -> Result<String, String> {
+    if s.starts_with("http://") || s.starts_with("https://") {
+        Ok(s.to_string())
+    } else {
+        Err("URL must start with http:// or https://".to_string())
+    }
+}
+
 /// A command-line tool that converts video recordings of documents into searchable digital formats.
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Path to the input video file (e.g., lecture.mp4)
-    #[arg(short, long)]
-    input: PathBuf,
+    #[arg(short, long, required = true)]
+    input: Option<PathBuf>,
 
     /// Directory to save the output files
-    #[arg(short, long)]
-    output: PathBuf,
+    #[arg(short, long, required = true)]
+    output: Option<PathBuf>,
 
     /// Output format
     #[arg(short, long, value_enum, default_value_t = OutputFormat::Pdf)]
     format: OutputFormat,
 
-    /// Frame-to-frame comparison sensitivity threshold (0.0 to 1.0)
-    #[arg(short, long, default_value_t = 0.9, value_parser = sensitivity_in_range)]
-    sensitivity: f64,
+    /// Frame-to-frame comparison sensitivity threshold (0.0 to 1.0), or "auto" to pick one for the video from the distances between its consecutive frames
+    #[arg(short, long, default_value = "0.9", value_parser = sensitivity_or_auto)]
+    sensitivity: Sensitivity,
+
+    /// Algorithm frames are hashed with to tell slides apart: phash is robust to noise and shifts but barely notices a line of text being added, dhash tells layouts apart, ahash and whash notice added text the most
+    #[arg(long, value_enum, default_value_t = HashAlgorithm::Phash)]
+    hash_algorithm: HashAlgorithm,
+
+    /// Side of the frame hash in bits (4 to 32)
+    #[arg(long, default_value_t = 16, value_parser = clap::value_parser!(u8).range(4..=32))]
+    hash_size: u8,
+
+    /// Compare each new slide with every page kept so far, not just the previous one, and drop a slide shown again or merge it into the page that first showed it, which lists when it was shown again
+    #[arg(long, value_enum)]
+    revisits: Option<Revisits>,
+
+    /// Take each page's image from the sharpest, well-exposed frame of the run of near-identical frames it starts, rather than the first, which may be caught mid-transition or motion-blurred
+    #[arg(long, default_value_t = false)]
+    best_frame: bool,
+
+    /// Only start a page once the content has stayed unchanged for this many frames, so the half-drawn frames of fades, slide transitions, and animations are not kept as pages
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..), conflicts_with = "settle_ms")]
+    settle_frames: Option<u32>,
 
-    /// OCR language (e.g., "eng" for English, "spa" for Spanish)
+    /// Like --settle-frames, as a duration in milliseconds of the video
+    #[arg(long, value_parser = positive_milliseconds)]
+    settle_ms: Option<f64>,
+
+    /// Never make a page of content on screen for less than this long, such as notification popups and quick switches to another window, e.g. "2s" or "500ms"
+    #[arg(long, value_parser = duration_seconds)]
+    min_duration: Option<f64>,
+
+    /// Bias slide boundaries toward pauses and applause in the audio, and away from the middle of speech
+    #[arg(long, default_value_t = false)]
+    audio_hints: bool,
+
+    /// OCR language (e.g., "eng" for English, "spa" for Spanish); join several with "+" for mixed slides, e.g. "eng+deu"
     #[arg(short, long, default_value_t = String::from("eng"))]
     lang: String,
 
+    /// Directory holding the Tesseract .traineddata language packs (default: TESSDATA_PREFIX, the tesseract-rs cache, then the system tessdata directories)
+    #[arg(long)]
+    tessdata_dir: Option<PathBuf>,
+
+    /// Tesseract page segmentation mode, 0-13 (e.g. 11 for sparse slide text, 4 for a single column, 3 for full pages; default: Tesseract's, 3)
+    #[arg(long, value_parser = clap::value_parser!(u8).range(0..=13))]
+    psm: Option<u8>,
+
+    /// Tesseract OCR engine mode: 0 legacy, 1 LSTM, 2 legacy and LSTM, 3 default (0 and 2 need traineddata with the legacy engine)
+    #[arg(long, value_parser = clap::value_parser!(u8).range(0..=3))]
+    oem: Option<u8>,
+
+    /// Tesseract variable set before recognition, as key=value (e.g. preserve_interword_spaces=1); repeatable
+    #[arg(long, value_parser = key_value)]
+    tess_config: Vec<(String, String)>,
+
+    /// Only recognize these characters (e.g. "0123456789.,%" for numeric dashboards)
+    #[arg(long)]
+    char_whitelist: Option<String>,
+
+    /// Never recognize these characters (e.g. "|~" for code slides)
+    #[arg(long)]
+    char_blacklist: Option<String>,
+
+    /// Word list, one per line, that Tesseract prefers when reading (e.g. medical or legal terms)
+    #[arg(long)]
+    user_words: Option<PathBuf>,
+
+    /// Clean up each frame before OCR, e.g. for phone-camera recordings of whiteboards: comma-separated steps, or all
+    #[arg(long, value_enum, value_delimiter = ',')]
+    preprocess: Vec<PreprocessStep>,
+
+    /// Clean up frames of a kind of footage as they are decoded, before page detection, OCR, and the documents: whiteboard evens out the lighting of a filmed whiteboard, makes the board white, and strengthens the marker strokes; paper fills in the glare on filmed paper pages and evens out their shadows
+    #[arg(long, value_enum)]
+    profile: Option<Profile>,
+
+    /// Correct the perspective of documents and projector screens filmed at an angle, warping each to an upright rectangle before OCR and in the documents
+    #[arg(long, default_value_t = false)]
+    rectify: bool,
+
+    /// Enlarge each frame 2 or 4 times with a super-resolution model before OCR, for low-resolution recordings of dense slides: Real-ESRGAN (realesrgan-ncnn-vulkan on the PATH), or --upscale-model
+    #[arg(long, value_parser = upscale_factor)]
+    upscale: Option<u32>,
+
+    /// ESRGAN super-resolution model (ONNX) used by --upscale instead of Real-ESRGAN
+    #[cfg(feature = "onnx")]
+    #[arg(long, requires = "upscale")]
+    upscale_model: Option<PathBuf>,
+
+    /// Only analyze and recognize this region of the frame, as x,y,w,h in pixels (e.g. to leave out a webcam overlay or player controls), or auto to trim black bars
+    #[arg(long)]
+    crop: Option<String>,
+
+    /// Ignore this region, as x,y,w,h in pixels of the analyzed frame, when detecting page changes and in OCR (e.g. a clock or live captions); repeatable
+    #[arg(long = "ignore-region")]
+    ignore_regions: Vec<String>,
+
+    /// PNG mask the size of the analyzed frame whose white pixels are ignored when detecting page changes and in OCR
+    #[arg(long)]
+    ignore_mask: Option<PathBuf>,
+
+    /// Download missing --lang language packs into --tessdata-dir or the tesseract-rs cache without asking, verifying their checksums
+    #[cfg(feature = "download")]
+    #[arg(long, default_value_t = false)]
+    download_lang: bool,
+
+    /// Repository missing language packs are downloaded from: tessdata_fast (smaller, faster) or tessdata_best (more accurate)
+    #[cfg(feature = "download")]
+    #[arg(long, value_enum, default_value_t = TessdataSource::Fast)]
+    tessdata_source: TessdataSource,
+
+    /// OCR engine: tesseract; paddle to run PaddleOCR models with ONNX Runtime, which reads small video text and stylized fonts better; or google, azure, or textract to send the frames to a cloud OCR service
+    #[arg(long, value_enum, default_value_t = OcrEngine::Tesseract)]
+    ocr_engine: OcrEngine,
+
+    /// PaddleOCR text detection model (ONNX) used by --ocr-engine paddle
+    #[cfg(feature = "onnx")]
+    #[arg(long, required_if_eq("ocr_engine", "paddle"))]
+    paddle_det_model: Option<PathBuf>,
+
+    /// PaddleOCR text recognition model (ONNX) used by --ocr-engine paddle
+    #[cfg(feature = "onnx")]
+    #[arg(long, required_if_eq("ocr_engine", "paddle"))]
+    paddle_rec_model: Option<PathBuf>,
+
+    /// Character dictionary of --paddle-rec-model, one character per line
+    #[cfg(feature = "onnx")]
+    #[arg(long, required_if_eq("ocr_engine", "paddle"))]
+    paddle_dict: Option<PathBuf>,
+
+    /// CUDA GPU, by its number, --ocr-engine paddle runs its models on; repeatable to spread the batches of frames across several GPUs. Without it, the models run on the CPU
+    #[cfg(feature = "onnx")]
+    #[arg(long = "paddle-device")]
+    paddle_devices: Vec<i32>,
+
+    /// Endpoint of the cloud OCR service, replacing the provider's default one; required for azure (the resource URL), also as the --handwriting engine
+    #[cfg(feature = "cloud")]
+    #[arg(long, required_if_eq_any([("ocr_engine", "azure"), ("handwriting", "azure")]))]
+    cloud_endpoint: Option<String>,
+
+    /// Frames sent to Google Cloud Vision in one request (at most 16)
+    #[cfg(feature = "cloud")]
+    #[arg(long, default_value_t = videodocparser::cloud_ocr::DEFAULT_BATCH_SIZE, value_parser = clap::value_parser!(u32).range(1..=16))]
+    cloud_batch_size: u32,
+
+    /// Times a cloud OCR request that failed, was throttled, or timed out is retried, with exponential backoff
+    #[cfg(feature = "cloud")]
+    #[arg(long, default_value_t = 3)]
+    cloud_retries: u32,
+
+    /// Most frames sent to the cloud OCR service in a run, to bound its cost; frames past the limit are left unrecognized
+    #[cfg(feature = "cloud")]
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    cloud_max_images: Option<u32>,
+
+    /// Engine for handwriting, as on whiteboard and chalkboard lectures: onnx to run --handwriting-model with ONNX Runtime, or google, azure, or textract to send the frames to a cloud OCR service. Every frame is read with it unless --handwriting-below is given
+    #[cfg(any(feature = "onnx", feature = "cloud"))]
+    #[arg(long, value_enum)]
+    handwriting: Option<HandwritingEngine>,
+
+    /// Handwriting recognition model (ONNX) used by --handwriting onnx: it reads grayscale lines 64 pixels tall, with CTC output
+    #[cfg(feature = "onnx")]
+    #[arg(long, required_if_eq("handwriting", "onnx"))]
+    handwriting_model: Option<PathBuf>,
+
+    /// Character dictionary of --handwriting-model, one character per line
+    #[cfg(feature = "onnx")]
+    #[arg(long, required_if_eq("handwriting", "onnx"))]
+    handwriting_dict: Option<PathBuf>,
+
+    /// Mean word confidence (0 to 100) below which a frame read with --ocr-engine is read again with the --handwriting engine, whose reading is kept
+    #[cfg(any(feature = "onnx", feature = "cloud"))]
+    #[arg(long, value_parser = confidence_in_range, requires = "handwriting")]
+    handwriting_below: Option<f32>,
+
     /// Generate an optional JSON index file with metadata
     #[arg(long, default_value_t = false)]
     index: bool,
 
+    /// Package the results (documents, frames, OCR report, index) into one archive with a manifest
+    #[cfg(feature = "bundle")]
+    #[arg(long, value_enum)]
+    bundle: Option<Bundle>,
+
+    /// Write a grid image and HTML page of all kept frames with their timestamps, to check the deduplication
+    #[arg(long, default_value_t = false)]
+    contact_sheet: bool,
+
+    /// Write chapter lists from the slide boundaries: chapters.txt for a YouTube description and chapters.ffmetadata to mux into the video
+    #[arg(long, default_value_t = false)]
+    chapters: bool,
+
+    /// Write a report of the words recognized below the review threshold, each next to a crop of the frame
+    #[arg(long, value_enum)]
+    review_report: Option<ReviewReport>,
+
+    /// OCR confidence (0 to 100) below which words are listed in the review report
+    #[arg(long, default_value_t = videodocparser::review::DEFAULT_THRESHOLD, value_parser = confidence_in_range)]
+    review_threshold: f32,
+
+    /// Build a full-text search index over the OCR text, queried with the search subcommand
+    #[cfg(feature = "search")]
+    #[arg(long, default_value_t = false)]
+    search_index: bool,
+
+    /// Append each page's detected slide title to the image file names (img format)
+    #[arg(long, default_value_t = false)]
+    title_filenames: bool,
+
+    /// Format of the word-level OCR report in the ocr folder
+    #[arg(long, value_enum, default_value_t = OcrReport::Json)]
+    ocr_report: OcrReport,
+
+    /// OCR this many frames of each stable slide and merge the results by voting per word (1 disables it)
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..=16))]
+    consensus_frames: u32,
+
+    /// Skip OCR on frames a quick edge-based detector finds no text on; they become image-only pages
+    #[arg(long, default_value_t = false)]
+    skip_textless: bool,
+
+    /// Number of frames recognized at once, each with its own OCR engine (defaults to one per CPU core)
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..=1024))]
+    ocr_threads: Option<u32>,
+
+    /// Mean word confidence (0 to 100) below which a frame is OCRed again with other page segmentation modes, inverted, and scaled 2x, keeping the best reading
+    #[arg(long, value_parser = confidence_in_range)]
+    retry_below: Option<f32>,
+
+    /// OCR confidence (0 to 100) below which words are unsure in the pdf, md, and jsonl outputs
+    #[arg(
+        long,
+        default_value_t = videodocparser::document_builder::DEFAULT_MIN_CONFIDENCE,
+        value_parser = confidence_in_range
+    )]
+    min_confidence: f32,
+
+    /// What to do with unsure words: leave them out, keep them and list them as unsure (md and jsonl), or keep them (defaults to drop for pdf and keep for md and jsonl)
+    #[arg(long, value_enum)]
+    low_confidence: Option<LowConfidence>,
+
+    /// Write each page's OCR text and an image of its word boxes coloured by confidence to ocr/debug, to tune the OCR settings
+    #[arg(long, default_value_t = false)]
+    ocr_debug: bool,
+
+    /// Heading level (1-6) of the per-page sections; the document title goes one level above (md format)
+    #[arg(long, default_value_t = 2, value_parser = clap::value_parser!(u8).range(1..=6))]
+    md_heading_level: u8,
+
+    /// Document title (defaults to the input file name)
+    #[arg(long)]
+    title: Option<String>,
+
+    /// Document author, written to the PDF metadata and the LaTeX document
+    #[arg(long)]
+    author: Option<String>,
+
+    /// Comma-separated keywords, written to the PDF metadata
+    #[arg(long)]
+    keywords: Option<String>,
+
+    /// URL where the recording can be watched; pages link back to it at their timestamp (pdf, md, html, tex, obsidian formats)
+    #[arg(long, value_parser = http_url)]
+    video_url: Option<String>,
+
+    /// Conformance profile of the PDF output (pdf format)
+    #[arg(long, value_enum, default_value_t = PdfProfile::Standard)]
+    pdf_profile: PdfProfile,
+
+    /// Size of PDF pages: A4 with the frame letterboxed, or the frame's own size (pdf format)
+    #[arg(long, value_enum, default_value_t = PdfPageSize::A4)]
+    pdf_page_size: PdfPageSize,
+
+    /// Resolution at which frames are laid out on native-size PDF pages (pdf format)
+    #[arg(long, default_value_t = 96.0, value_parser = positive_dpi)]
+    pdf_page_dpi: f32,
+
+    /// Compression of page images in the PDF; flate is lossless and keeps text edges sharp, mrc splits text from background for compact scans (pdf format)
+    #[arg(long, value_enum, default_value_t = PdfImageFormat::Jpeg)]
+    pdf_image_format: PdfImageFormat,
+
+    /// JPEG quality of page images in the PDF, from 1 to 100 (pdf format)
+    #[arg(long, default_value_t = 85, value_parser = clap::value_parser!(u8).range(1..=100))]
+    pdf_jpeg_quality: u8,
+
+    /// Downscale page images whose resolution on the PDF page exceeds this DPI (pdf format)
+    #[arg(long, value_parser = positive_dpi)]
+    pdf_max_dpi: Option<f32>,
+
+    /// Embed page images without colour in grayscale, and pages of black text on white as 1-bit CCITT G4 images, shrinking document scans; not used with --pdf-image-format mrc (pdf format)
+    #[arg(long, default_value_t = false)]
+    pdf_auto_colour: bool,
+
+    /// Start the PDF with a table of contents linking to every page (pdf format)
+    #[arg(long, default_value_t = false)]
+    pdf_toc: bool,
+
+    /// Password required to open the PDF; encrypts it with AES-256 (pdf format)
+    #[arg(long)]
+    pdf_password: Option<String>,
+
+    /// Password granting full access to the PDF; without it, readers can only print and copy text (pdf format)
+    #[arg(long)]
+    pdf_owner_password: Option<String>,
+
+    /// Write page images to an images folder and link them instead of embedding them (html format)
+    #[arg(long, default_value_t = false)]
+    html_link_images: bool,
+
+    /// Write page images to an images folder and store their paths instead of PNG blobs (sqlite format)
+    #[cfg(feature = "sqlite")]
+    #[arg(long, default_value_t = false)]
+    sqlite_link_images: bool,
+
+    /// URL the iiif folder will be served from, the base of every id in the manifest (iiif format)
+    #[arg(long, value_parser = http_url, default_value = videodocparser::iiif::DEFAULT_BASE_URL)]
+    iiif_base_url: String,
+
+    /// End the document with an alphabetical index of significant OCR terms and their pages (pdf and md formats)
+    #[arg(long, default_value_t = false)]
+    keyword_index: bool,
+
+    /// Tag every slide with its most distinctive OCR terms, in index.json and the front matter of md and obsidian notes
+    #[arg(long, default_value_t = false)]
+    slide_tags: bool,
+
+    /// Make the URLs in the slides clickable (pdf and md formats) and list them with their pages and timestamps in links.json
+    #[arg(long, default_value_t = false)]
+    links: bool,
+
+    /// Crop the charts, diagrams and photos on each slide into images of their own, shown after the slide's text (md and html formats)
+    #[arg(long, default_value_t = false)]
+    figures: bool,
+
+    /// Keep the formulas on each slide as cropped images in their place in the text (md and html formats), leaving their garbled OCR text out of the text layer (pdf and html formats)
+    #[arg(long, default_value_t = false)]
+    formulas: bool,
+
+    /// Write only the lines each slide adds to the one before it, for slides built up step by step (md and jsonl formats; txt always does)
+    #[arg(long, default_value_t = false)]
+    new_content_only: bool,
+
+    /// Keep only the final state of slides built up step by step, dropping the pages that only show part of it
+    #[arg(long, default_value_t = false)]
+    final_builds_only: bool,
+
+    /// SRT or WebVTT transcript of the audio (e.g. from Whisper); the speech is placed after each slide (pdf, md, and html formats)
+    #[arg(long)]
+    transcript: Option<PathBuf>,
+
+    /// Use the video's own captions as the transcript: a .srt or .vtt file next to it, else its first text subtitle stream (pdf, md, and html formats)
+    #[arg(long, default_value_t = false, conflicts_with = "transcript")]
+    use_subtitles: bool,
+
+    /// Transcribe the audio with this Whisper model (ggml file) and place the speech after each slide (pdf, md, and html formats)
+    #[cfg(feature = "whisper")]
+    #[arg(long, conflicts_with = "transcript")]
+    whisper_model: Option<PathBuf>,
+
+    /// Label the speaker turns of the transcript as "Speaker 1:", "Speaker 2:", ... (needs a tinydiarize Whisper model)
+    #[cfg(feature = "whisper")]
+    #[arg(long, default_value_t = false, requires = "whisper_model")]
+    diarize: bool,
+
+    /// OpenAI-compatible API (e.g. https://api.openai.com/v1) that cleans the OCR text of every page for index.json; the key is read from OPENAI_API_KEY
+    #[cfg(feature = "llm")]
+    #[arg(long, value_parser = http_url)]
+    llm_url: Option<String>,
+
+    /// Model asked by --llm-url
+    #[cfg(feature = "llm")]
+    #[arg(long, default_value = videodocparser::llm::DEFAULT_MODEL, requires = "llm_url")]
+    llm_model: String,
+
+    /// Also summarize every page with --llm-url, stored next to its text in index.json
+    #[cfg(feature = "llm")]
+    #[arg(long, default_value_t = false, requires = "llm_url")]
+    llm_summaries: bool,
+
+    /// Translate the OCR text of every page into this language (e.g. English) with --llm-url, stored next to it in index.json and printed after each slide (pdf and md formats)
+    #[cfg(feature = "llm")]
+    #[arg(long, requires = "llm_url")]
+    translate_to: Option<String>,
+
+    /// Dictionary to correct the OCR text against before the documents are built: a word list, optionally with frequencies, or a Hunspell .dic file; changes are listed in corrections.json
+    #[cfg(feature = "spellcheck")]
+    #[arg(long)]
+    spell_dictionary: Option<PathBuf>,
+
+    /// Domain word list (one term per line) added to the spelling dictionary, taking precedence over it
+    #[cfg(feature = "spellcheck")]
+    #[arg(long)]
+    spell_wordlist: Option<PathBuf>,
+
+    /// Handlebars template that lays out the document (md and html formats)
+    #[cfg(feature = "templates")]
+    #[arg(long)]
+    template: Option<PathBuf>,
+
+    /// Seconds each page is shown in the recap video (video format)
+    #[arg(long, default_value_t = 2.0, value_parser = positive_seconds)]
+    recap_seconds: f64,
+
+    /// Burn each page's source timestamp into the recap video (video format)
+    #[arg(long, default_value_t = false)]
+    recap_timestamps: bool,
+
+    /// Container of the recap video (video format)
+    #[arg(long, value_enum, default_value_t = RecapContainer::Mp4)]
+    recap_container: RecapContainer,
+
+    /// Write one document per chapter marker of the video instead of a single one (pdf and md formats)
+    #[arg(long, value_enum, default_value_t = SplitBy::None)]
+    split_by: SplitBy,
+
+    /// Show each slide's OCR text or only its title in subtitle cues (srt and vtt formats)
+    #[arg(long, value_enum, default_value_t = SubtitleText::Text)]
+    subtitle_text: SubtitleText,
+
+    /// Path of the outputs inside the result folder, e.g. "{stem}/{date}/slide_{index:04}" (pdf, md, img formats and review report)
+    #[arg(long)]
+    name_template: Option<String>,
+
     /// Logging verbosity level
     #[arg(long, value_enum, default_value_t = LogLevel::Info)]
     log_level: LogLevel,
+
+    /// Interface language for help, progress labels, and the summary (defaults to the system locale)
+    #[arg(long, value_enum)]
+    ui_lang: Option<UiLang>,
+}
+
+#[derive(clap::Subcommand, Debug)]
+enum Command {
+    /// Compare the pages and OCR text of two previous runs
+    Diff {
+        /// Output directory of the older run
+        old: PathBuf,
+
+        /// Output directory of the newer run
+        new: PathBuf,
+
+        /// Minimum text similarity (0.0 to 1.0) for two pages to count as the same slide
+        #[arg(long, default_value_t = 0.5, value_parser = sensitivity_in_range)]
+        match_threshold: f64,
+
+        /// Print the report as JSON instead of a table
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+    /// Search the pages of a previous run built with --search-index
+    #[cfg(feature = "search")]
+    Search {
+        /// Words or phrases to look for (tantivy query syntax)
+        query: String,
+
+        /// Output directory of the run
+        #[arg(default_value = ".")]
+        dir: PathBuf,
+
+        /// Maximum number of pages to list
+        #[arg(long, default_value_t = 10)]
+        limit: usize,
+
+        /// Print the hits as JSON instead of a table
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
 }
 
 #[derive(clap::ValueEnum, Clone, Debug)]
 enum OutputFormat {
     Pdf,
     Md,
+    Txt,
     Img,
+    Html,
+    Video,
+    #[cfg(feature = "sqlite")]
+    Sqlite,
+    Alto,
+    Srt,
+    Vtt,
+    Tex,
+    Obsidian,
+    Iiif,
+    Jsonl,
+}
+
+#[cfg(feature = "download")]
+#[derive(clap::ValueEnum, Clone, Debug)]
+enum TessdataSource {
+    Fast,
+    Best,
+}
+
+#[derive(clap::ValueEnum, Clone, Debug)]
+enum OcrEngine {
+    Tesseract,
+    #[cfg(feature = "onnx")]
+    Paddle,
+    #[cfg(feature = "cloud")]
+    Google,
+    #[cfg(feature = "cloud")]
+    Azure,
+    #[cfg(feature = "cloud")]
+    Textract,
+}
+
+#[cfg(any(feature = "onnx", feature = "cloud"))]
+#[derive(clap::ValueEnum, Clone, Debug, PartialEq)]
+enum HandwritingEngine {
+    #[cfg(feature = "onnx")]
+    Onnx,
+    #[cfg(feature = "cloud")]
+    Google,
+    #[cfg(feature = "cloud")]
+    Azure,
+    #[cfg(feature = "cloud")]
+    Textract,
+}
+
+#[derive(clap::ValueEnum, Clone, Debug, PartialEq)]
+enum PreprocessStep {
+    Contrast,
+    Denoise,
+    Deskew,
+    Binarize,
+    All,
+}
+
+#[derive(clap::ValueEnum, Clone, Debug)]
+enum HashAlgorithm {
+    Phash,
+    Dhash,
+    Ahash,
+    Whash,
+}
+
+#[derive(clap::ValueEnum, Clone, Debug)]
+enum Revisits {
+    Drop,
+    Merge,
+}
+
+#[derive(clap::ValueEnum, Clone, Debug)]
+enum Profile {
+    Whiteboard,
+    Paper,
+}
+
+#[cfg(feature = "bundle")]
+#[derive(clap::ValueEnum, Clone, Debug)]
+enum Bundle {
+    Zip,
+}
+
+#[derive(clap::ValueEnum, Clone, Debug)]
+enum ReviewReport {
+    Md,
+    Pdf,
+}
+
+#[derive(clap::ValueEnum, Clone, Debug)]
+enum SplitBy {
+    None,
+    Chapter,
+}
+
+#[derive(clap::ValueEnum, Clone, Debug)]
+enum SubtitleText {
+    Text,
+    Title,
+}
+
+#[derive(clap::ValueEnum, Clone, Debug)]
+enum LowConfidence {
+    Drop,
+    Flag,
+    Keep,
+}
+
+#[derive(clap::ValueEnum, Clone, Debug)]
+enum OcrReport {
+    Json,
+    Csv,
+    Both,
+}
+
+#[derive(clap::ValueEnum, Clone, Debug)]
+enum PdfProfile {
+    Standard,
+    #[value(name = "pdfa-2b")]
+    PdfA2b,
+    #[value(name = "pdfua-1")]
+    PdfUa1,
+}
+
+#[derive(clap::ValueEnum, Clone, Debug)]
+enum PdfPageSize {
+    A4,
+    Native,
+}
+
+#[derive(clap::ValueEnum, Clone, Debug)]
+enum PdfImageFormat {
+    Jpeg,
+    Flate,
+    Mrc,
+}
+
+#[derive(clap::ValueEnum, Clone, Debug)]
+enum RecapContainer {
+    Mp4,
+    Webm,
 }
 
 #[derive(clap::ValueEnum, Clone, Debug)]
@@ -74,11 +753,79 @@ enum LogLevel {
     Debug,
 }
 
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum UiLang {
+    En,
+    Es,
+    Pt,
+    De,
+    Fr,
+}
+
+impl From<UiLang> for Lang {
+    fn from(lang: UiLang) -> Self {
+        match lang {
+            UiLang::En => Lang::En,
+            UiLang::Es => Lang::Es,
+            UiLang::Pt => Lang::Pt,
+            UiLang::De => Lang::De,
+            UiLang::Fr => Lang::Fr,
+        }
+    }
+}
+
+/// Names the selected preprocessing steps, expanding `all` to every step.
+fn preprocess_steps(steps: &[PreprocessStep]) -> Vec<String> {
+    if steps.contains(&PreprocessStep::All) {
+        return videodocparser::preprocess::STEPS.iter().map(|step| step.to_string()).collect();
+    }
+    steps
+        .iter()
+        .map(|step| match step {
+            PreprocessStep::Contrast => "contrast".to_string(),
+            PreprocessStep::Denoise => "denoise".to_string(),
+            PreprocessStep::Deskew => "deskew".to_string(),
+            PreprocessStep::Binarize | PreprocessStep::All => "binarize".to_string(),
+        })
+        .collect()
+}
+
+/// Determines the interface language before the arguments are parsed, so that
+/// `--help` itself can be shown in that language.
+fn detect_ui_lang() -> Lang {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let value = match arg.strip_prefix("--ui-lang") {
+            Some("") => args.next(),
+            Some(rest) => rest.strip_prefix('=').map(str::to_string),
+            None => continue,
+        };
+        if let Some(lang) = value.as_deref().and_then(Lang::from_code) {
+            return lang;
+        }
+    }
+    Lang::from_locale(|name| std::env::var(name).ok())
+}
+
+/// Parses the command line using help texts in the given language.
+fn parse_args(lang: Lang) -> Args {
+    let mut cmd = Args::command().about(i18n::translate(lang, Msg::About));
+    let ids: Vec<String> = cmd.get_arguments().map(|arg| arg.get_id().to_string()).collect();
+    for id in ids {
+        if let Some(help) = i18n::arg_help(lang, &id) {
+            cmd = cmd.mut_arg(id, |arg| arg.help(help));
+        }
+    }
+    Args::from_arg_matches(&cmd.get_matches()).unwrap_or_else(|e| e.exit())
+}
+
 fn main() {
     #[cfg(feature = "dhat-heap")]
     let _profiler = dhat::Profiler::new_heap();
     
-    let args = Args::parse();
+    let detected_lang = detect_ui_lang();
+    let args = parse_args(detected_lang);
+    i18n::set_lang(args.ui_lang.map(Lang::from).unwrap_or(detected_lang));
 
     // 1. Initialize Logger
     let log_level = match args.log_level {
@@ -88,26 +835,248 @@ fn main() {
     };
     env_logger::Builder::from_env(env_logger::Env::default().default_filter_or(log_level)).init();
 
+    match args.command {
+        Some(Command::Diff { old, new, match_threshold, json }) => run_diff(&old, &new, match_threshold, json),
+        #[cfg(feature = "search")]
+        Some(Command::Search { query, dir, limit, json }) => run_search(&query, &dir, limit, json),
+        None => {}
+    }
+
     info!("Starting VideoDocParser...");
 
     // 2. Validate input path
-    if !args.input.exists() {
-        error!("Input file does not exist: {:?}", args.input);
+    let (Some(input), Some(output)) = (args.input, args.output) else {
+        error!("--input and --output are required without a subcommand");
+        std::process::exit(1);
+    };
+    if !input.exists() {
+        error!("Input file does not exist: {:?}", input);
+        std::process::exit(1);
+    }
+    #[cfg(feature = "templates")]
+    if let Some(template) = &args.template
+        && !template.is_file()
+    {
+        error!("Template file does not exist: {:?}", template);
         std::process::exit(1);
     }
     
     // 3. Create a configuration object from arguments
     let config = videodocparser::Config {
-        input_file: args.input,
-        output_dir: args.output,
+        input_file: input,
+        output_dir: output,
         output_format: match args.format {
             OutputFormat::Pdf => "pdf".to_string(),
             OutputFormat::Md => "md".to_string(),
+            OutputFormat::Txt => "txt".to_string(),
             OutputFormat::Img => "img".to_string(),
+            OutputFormat::Html => "html".to_string(),
+            OutputFormat::Video => "video".to_string(),
+            #[cfg(feature = "sqlite")]
+            OutputFormat::Sqlite => "sqlite".to_string(),
+            OutputFormat::Alto => "alto".to_string(),
+            OutputFormat::Srt => "srt".to_string(),
+            OutputFormat::Vtt => "vtt".to_string(),
+            OutputFormat::Tex => "tex".to_string(),
+            OutputFormat::Obsidian => "obsidian".to_string(),
+            OutputFormat::Iiif => "iiif".to_string(),
+            OutputFormat::Jsonl => "jsonl".to_string(),
         },
-        sensitivity: args.sensitivity,
+        sensitivity: match args.sensitivity {
+            Sensitivity::Fixed(sensitivity) => sensitivity,
+            Sensitivity::Auto => DEFAULT_SENSITIVITY,
+        },
+        auto_sensitivity: matches!(args.sensitivity, Sensitivity::Auto),
+        hash_algorithm: match args.hash_algorithm {
+            HashAlgorithm::Phash => "phash".to_string(),
+            HashAlgorithm::Dhash => "dhash".to_string(),
+            HashAlgorithm::Ahash => "ahash".to_string(),
+            HashAlgorithm::Whash => "whash".to_string(),
+        },
+        hash_size: args.hash_size as usize,
+        revisits: args.revisits.map(|revisits| match revisits {
+            Revisits::Drop => "drop".to_string(),
+            Revisits::Merge => "merge".to_string(),
+        }),
+        best_frame: args.best_frame,
+        settle_frames: args.settle_frames.map(|frames| frames as usize),
+        settle_ms: args.settle_ms,
+        min_duration: args.min_duration,
+        audio_hints: args.audio_hints,
         lang: args.lang,
+        tessdata_dir: args.tessdata_dir,
+        psm: args.psm,
+        oem: args.oem,
+        tess_config: args.tess_config,
+        char_whitelist: args.char_whitelist,
+        char_blacklist: args.char_blacklist,
+        user_words: args.user_words,
+        preprocess: preprocess_steps(&args.preprocess),
+        profile: args.profile.map(|profile| match profile {
+            Profile::Whiteboard => "whiteboard".to_string(),
+            Profile::Paper => "paper".to_string(),
+        }),
+        rectify: args.rectify,
+        upscale: args.upscale,
+        #[cfg(feature = "onnx")]
+        upscale_model: args.upscale_model,
+        crop: args.crop,
+        ignore_regions: args.ignore_regions,
+        ignore_mask: args.ignore_mask,
+        #[cfg(feature = "download")]
+        download_lang: args.download_lang,
+        #[cfg(feature = "download")]
+        tessdata_source: match args.tessdata_source {
+            TessdataSource::Fast => "fast".to_string(),
+            TessdataSource::Best => "best".to_string(),
+        },
+        ocr_engine: match args.ocr_engine {
+            OcrEngine::Tesseract => "tesseract".to_string(),
+            #[cfg(feature = "onnx")]
+            OcrEngine::Paddle => "paddle".to_string(),
+            #[cfg(feature = "cloud")]
+            OcrEngine::Google => "google".to_string(),
+            #[cfg(feature = "cloud")]
+            OcrEngine::Azure => "azure".to_string(),
+            #[cfg(feature = "cloud")]
+            OcrEngine::Textract => "textract".to_string(),
+        },
+        #[cfg(feature = "onnx")]
+        paddle_det_model: args.paddle_det_model,
+        #[cfg(feature = "onnx")]
+        paddle_rec_model: args.paddle_rec_model,
+        #[cfg(feature = "onnx")]
+        paddle_dict: args.paddle_dict,
+        #[cfg(feature = "onnx")]
+        paddle_devices: args.paddle_devices,
+        #[cfg(feature = "cloud")]
+        cloud_endpoint: args.cloud_endpoint,
+        #[cfg(feature = "cloud")]
+        cloud_batch_size: args.cloud_batch_size as usize,
+        #[cfg(feature = "cloud")]
+        cloud_retries: args.cloud_retries,
+        #[cfg(feature = "cloud")]
+        cloud_max_images: args.cloud_max_images.map(|n| n as usize),
+        #[cfg(any(feature = "onnx", feature = "cloud"))]
+        handwriting: args.handwriting.map(|engine| match engine {
+            #[cfg(feature = "onnx")]
+            HandwritingEngine::Onnx => "onnx".to_string(),
+            #[cfg(feature = "cloud")]
+            HandwritingEngine::Google => "google".to_string(),
+            #[cfg(feature = "cloud")]
+            HandwritingEngine::Azure => "azure".to_string(),
+            #[cfg(feature = "cloud")]
+            HandwritingEngine::Textract => "textract".to_string(),
+        }),
+        #[cfg(feature = "onnx")]
+        handwriting_model: args.handwriting_model,
+        #[cfg(feature = "onnx")]
+        handwriting_dict: args.handwriting_dict,
+        #[cfg(any(feature = "onnx", feature = "cloud"))]
+        handwriting_below: args.handwriting_below,
         generate_index: args.index,
+        #[cfg(feature = "bundle")]
+        bundle: args.bundle.map(|bundle| match bundle {
+            Bundle::Zip => "zip".to_string(),
+        }),
+        contact_sheet: args.contact_sheet,
+        chapters: args.chapters,
+        review_report: args.review_report.map(|format| match format {
+            ReviewReport::Md => "md".to_string(),
+            ReviewReport::Pdf => "pdf".to_string(),
+        }),
+        review_threshold: args.review_threshold,
+        #[cfg(feature = "search")]
+        search_index: args.search_index,
+        title_filenames: args.title_filenames,
+        ocr_report: match args.ocr_report {
+            OcrReport::Json => "json".to_string(),
+            OcrReport::Csv => "csv".to_string(),
+            OcrReport::Both => "both".to_string(),
+        },
+        consensus_frames: args.consensus_frames as usize,
+        skip_textless: args.skip_textless,
+        ocr_threads: args.ocr_threads.map(|threads| threads as usize),
+        retry_below: args.retry_below,
+        min_confidence: args.min_confidence,
+        low_confidence: args.low_confidence.map(|policy| match policy {
+            LowConfidence::Drop => "drop".to_string(),
+            LowConfidence::Flag => "flag".to_string(),
+            LowConfidence::Keep => "keep".to_string(),
+        }),
+        ocr_debug: args.ocr_debug,
+        md_heading_level: args.md_heading_level as usize,
+        title: args.title,
+        author: args.author,
+        keywords: args.keywords,
+        video_url: args.video_url,
+        pdf_profile: match args.pdf_profile {
+            PdfProfile::Standard => "standard".to_string(),
+            PdfProfile::PdfA2b => "pdfa-2b".to_string(),
+            PdfProfile::PdfUa1 => "pdfua-1".to_string(),
+        },
+        pdf_page_size: match args.pdf_page_size {
+            PdfPageSize::A4 => "a4".to_string(),
+            PdfPageSize::Native => "native".to_string(),
+        },
+        pdf_page_dpi: args.pdf_page_dpi,
+        pdf_image_format: match args.pdf_image_format {
+            PdfImageFormat::Jpeg => "jpeg".to_string(),
+            PdfImageFormat::Flate => "flate".to_string(),
+            PdfImageFormat::Mrc => "mrc".to_string(),
+        },
+        pdf_jpeg_quality: args.pdf_jpeg_quality,
+        pdf_max_dpi: args.pdf_max_dpi,
+        pdf_auto_colour: args.pdf_auto_colour,
+        pdf_toc: args.pdf_toc,
+        pdf_password: args.pdf_password,
+        pdf_owner_password: args.pdf_owner_password,
+        html_link_images: args.html_link_images,
+        #[cfg(feature = "sqlite")]
+        sqlite_link_images: args.sqlite_link_images,
+        iiif_base_url: args.iiif_base_url,
+        keyword_index: args.keyword_index,
+        slide_tags: args.slide_tags,
+        links: args.links,
+        figures: args.figures,
+        formulas: args.formulas,
+        new_content_only: args.new_content_only,
+        final_builds_only: args.final_builds_only,
+        transcript: args.transcript,
+        use_subtitles: args.use_subtitles,
+        #[cfg(feature = "whisper")]
+        whisper_model: args.whisper_model,
+        #[cfg(feature = "whisper")]
+        diarize: args.diarize,
+        #[cfg(feature = "llm")]
+        llm_url: args.llm_url,
+        #[cfg(feature = "llm")]
+        llm_model: args.llm_model,
+        #[cfg(feature = "llm")]
+        llm_summaries: args.llm_summaries,
+        #[cfg(feature = "llm")]
+        translate_to: args.translate_to,
+        #[cfg(feature = "spellcheck")]
+        spell_dictionary: args.spell_dictionary,
+        #[cfg(feature = "spellcheck")]
+        spell_wordlist: args.spell_wordlist,
+        #[cfg(feature = "templates")]
+        template: args.template,
+        recap_seconds: args.recap_seconds,
+        recap_timestamps: args.recap_timestamps,
+        recap_container: match args.recap_container {
+            RecapContainer::Mp4 => "mp4".to_string(),
+            RecapContainer::Webm => "webm".to_string(),
+        },
+        split_by: match args.split_by {
+            SplitBy::None => "none".to_string(),
+            SplitBy::Chapter => "chapter".to_string(),
+        },
+        subtitle_text: match args.subtitle_text {
+            SubtitleText::Text => "text".to_string(),
+            SubtitleText::Title => "title".to_string(),
+        },
+        name_template: args.name_template,
     };
 
     // 4. Run the main application logic
@@ -120,4 +1089,71 @@ fn main() {
     std::process::exit(0);
 }
 
+/// Runs the `search` subcommand and exits with the matching status code.
+#[cfg(feature = "search")]
+fn run_search(query: &str, dir: &Path, limit: usize, json: bool) -> ! {
+    use videodocparser::search;
 
+    let index_dir = dir.join("result").join(search::INDEX_DIR);
+    if !index_dir.is_dir() {
+        error!("No search index in {:?}; run with --search-index first", dir);
+        std::process::exit(1);
+    }
+    let hits = match search::search(&index_dir, query, limit) {
+        Ok(hits) => hits,
+        Err(e) => {
+            error!("Search failed: {:#}", e);
+            std::process::exit(2);
+        }
+    };
+    if json {
+        match serde_json::to_string_pretty(&hits) {
+            Ok(json) => println!("{}", json),
+            Err(e) => {
+                error!("Failed to serialize search results: {}", e);
+                std::process::exit(3);
+            }
+        }
+    } else {
+        for hit in &hits {
+            let timestamp = hit
+                .timestamp
+                .map(videodocparser::document_builder::format_timestamp)
+                .unwrap_or_else(|| "--:--:--".to_string());
+            println!("{:>4}  {}  {}", hit.page, timestamp, hit.title.as_deref().unwrap_or(""));
+            if !hit.snippet.is_empty() {
+                println!("      {}", hit.snippet);
+            }
+        }
+    }
+    std::process::exit(0);
+}
+
+/// Runs the `diff` subcommand and exits with the matching status code.
+fn run_diff(old: &Path, new: &Path, match_threshold: f64, json: bool) -> ! {
+    for dir in [old, new] {
+        if !dir.is_dir() {
+            error!("Run directory does not exist: {:?}", dir);
+            std::process::exit(1);
+        }
+    }
+    let report = match diff::diff_runs(old, new, match_threshold) {
+        Ok(report) => report,
+        Err(e) => {
+            error!("Diff failed: {:#}", e);
+            std::process::exit(2);
+        }
+    };
+    if json {
+        match serde_json::to_string_pretty(&report) {
+            Ok(json) => println!("{}", json),
+            Err(e) => {
+                error!("Failed to serialize diff report: {}", e);
+                std::process::exit(3);
+            }
+        }
+    } else {
+        report.print();
+    }
+    std::process::exit(0);
+}
@@ -0,0 +1,95 @@
+//! Mask Module
+//!
+//! Handles the parts of the frame that change all the time without being part
+//! of the slide: clocks, live captions, a presenter's cursor. Ignored regions
+//! are given as rectangles, as a PNG mask, or both, in pixels of the analyzed
+//! frame (after `--crop`). They are painted over with a flat colour before a
+//! frame is hashed, so they never start a new page, and before it is
+//! recognized, so they add nothing to the text; the page images keep them.
+
+use crate::crop::CropRect;
+use anyhow::{Context, Result, ensure};
+use image::{GrayImage, ImageBuffer, Rgb};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+
+/// Grey level from which a pixel of a PNG mask marks an ignored pixel.
+const MASK_LEVEL: u8 = 128;
+/// Colour ignored regions are painted with before hashing.
+pub const HASH_FILL: Rgb<u8> = Rgb([0, 0, 0]);
+
+/// The regions of the frame to ignore.
+#[derive(Debug, Clone)]
+pub struct IgnoreMask {
+    regions: Vec<CropRect>,
+    image: Option<GrayImage>,
+}
+
+impl IgnoreMask {
+    /// A mask ignoring `regions` and, if given, the light pixels of `image`.
+    pub fn new(regions: Vec<CropRect>, image: Option<GrayImage>) -> Self {
+        Self { regions, image }
+    }
+
+    /// Builds the mask from `x,y,w,h` region specs and a PNG mask whose white
+    /// pixels mark what to ignore. Returns `None` if there are neither.
+    pub fn load(specs: &[String], image_path: Option<&Path>) -> Result<Option<Self>> {
+        if specs.is_empty() && image_path.is_none() {
+            return Ok(None);
+        }
+        let regions = specs.iter().map(|spec| CropRect::parse(spec)).collect::<Result<Vec<_>>>()?;
+        let image = image_path
+            .map(|path| image::open(path).with_context(|| format!("Failed to read the ignore mask {:?}", path)))
+            .transpose()?
+            .map(|image| image.into_luma8());
+        Ok(Some(Self::new(regions, image)))
+    }
+
+    /// Whether the pixel at (x, y) is ignored.
+    pub fn is_ignored(&self, x: u32, y: u32) -> bool {
+        self.regions.iter().any(|region| region.contains(x, y))
+            || self.image.as_ref().is_some_and(|image| {
+                x < image.width() && y < image.height() && image.get_pixel(x, y)[0] >= MASK_LEVEL
+            })
+    }
+
+    /// Checks that a PNG mask has the size of the frames it is applied to.
+    pub fn check_size(&self, width: u32, height: u32) -> Result<()> {
+        if let Some(image) = &self.image {
+            ensure!(
+                image.dimensions() == (width, height),
+                "The ignore mask is {}x{} but the analyzed frames are {}x{}",
+                image.width(),
+                image.height(),
+                width,
+                height
+            );
+        }
+        Ok(())
+    }
+
+    /// A copy of the frame with the ignored pixels painted `fill`.
+    pub fn apply(&self, frame: &ImageBuffer<Rgb<u8>, Vec<u8>>, fill: Rgb<u8>) -> ImageBuffer<Rgb<u8>, Vec<u8>> {
+        let mut masked = frame.clone();
+        for (x, y, pixel) in masked.enumerate_pixels_mut() {
+            if self.is_ignored(x, y) {
+                *pixel = fill;
+            }
+        }
+        masked
+    }
+
+    /// Describes the mask for the settings of a cached hash series: the regions
+    /// and a digest of the PNG mask.
+    pub fn fingerprint(&self) -> serde_json::Value {
+        let regions: Vec<[u32; 4]> = self.regions.iter().map(|r| [r.x, r.y, r.width, r.height]).collect();
+        let image = self.image.as_ref().map(|image| {
+            let mut hasher = Sha256::new();
+            hasher.update(image.width().to_le_bytes());
+            hasher.update(image.height().to_le_bytes());
+            hasher.update(image.as_raw());
+            hasher.finalize().iter().map(|byte| format!("{:02x}", byte)).collect::<String>()
+        });
+        serde_json::json!({ "regions": regions, "image": image })
+    }
+}
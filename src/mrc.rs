@@ -0,0 +1,396 @@
+//! MRC Module
+//!
+//! Handles mixed raster content (MRC) compression of PDF page images. A frame
+//! is split into two layers: a full-resolution 1-bit mask of its text,
+//! compressed with CCITT Group 4 and painted in the text colour, over a
+//! low-resolution JPEG of the background with the text removed. Scanned pages
+//! and text slides come out several times smaller than as a full-page JPEG
+//! while the text stays sharp; photos lose detail, as with any MRC encoder.
+//! Pages without colour can instead be embedded in grayscale, and pages of
+//! black text on white as a single CCITT Group 4 image.
+
+use image::{ImageBuffer, Rgb};
+
+/// Factor by which the background layer is downscaled in each dimension.
+pub const BACKGROUND_SCALE: u32 = 3;
+/// Widest spread between the channels of a pixel that counts as grey.
+const GREY_SPREAD: u8 = 24;
+/// Largest share of coloured pixels on a page without colour, for stray
+/// compression noise.
+const MAX_COLOURED_SHARE: f64 = 0.002;
+/// Lumas between black and white, from the anti-aliased edges of text.
+const MID_TONES: std::ops::RangeInclusive<u8> = 64..=191;
+/// Largest share of mid-tone pixels on a page of black text on white.
+const MAX_MID_TONE_SHARE: f64 = 0.06;
+
+/// The fewest colours a page image can be embedded with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PageColour {
+    /// Black text on white: one bit per pixel.
+    Bilevel,
+    /// Shades of grey.
+    Grayscale,
+    Colour,
+}
+
+/// The layers of one frame.
+pub struct Layers {
+    /// The frame with its text painted over by the surrounding background,
+    /// downscaled by [`BACKGROUND_SCALE`].
+    pub background: ImageBuffer<Rgb<u8>, Vec<u8>>,
+    /// The text mask, CCITT Group 4 encoded with text as black (`K -1`, `BlackIs1` false).
+    pub mask: Vec<u8>,
+    /// Width of the mask, the frame's width.
+    pub width: u32,
+    /// Height of the mask, the frame's height.
+    pub height: u32,
+    /// Colour the mask is painted with: the mean colour of the text pixels.
+    pub foreground: [u8; 3],
+}
+
+/// Splits a frame into its layers.
+///
+/// Pixels are classified by a global Otsu threshold on their luma, and the
+/// smaller class is taken as the text, so both dark-on-light and light-on-dark
+/// slides work.
+pub fn separate(frame: &ImageBuffer<Rgb<u8>, Vec<u8>>) -> Layers {
+    let (width, height) = frame.dimensions();
+    let luma: Vec<u8> = frame.pixels().map(|p| luma(p.0)).collect();
+    let threshold = otsu_threshold(&luma);
+    let dark = luma.iter().filter(|&&l| l <= threshold).count();
+    let text_is_dark = dark * 2 <= luma.len();
+    let mask: Vec<bool> = luma.iter().map(|&l| (l <= threshold) == text_is_dark).collect();
+
+    let mut sum = [0u64; 3];
+    let mut count = 0u64;
+    for (pixel, _) in frame.pixels().zip(&mask).filter(|(_, text)| **text) {
+        for (s, &c) in sum.iter_mut().zip(&pixel.0) {
+            *s += c as u64;
+        }
+        count += 1;
+    }
+    let foreground = if count == 0 {
+        [0, 0, 0]
+    } else {
+        sum.map(|s| (s / count) as u8)
+    };
+
+    Layers {
+        background: background(frame, &mask),
+        mask: encode_g4(&mask, width as usize, height as usize),
+        width,
+        height,
+        foreground,
+    }
+}
+
+/// Tells how few colours a page image can be embedded with. It is grayscale
+/// when all but [`MAX_COLOURED_SHARE`] of its pixels are grey, and bilevel
+/// when, besides, all but [`MAX_MID_TONE_SHARE`] are near black or white,
+/// and the dark ones are fewer: black text on white.
+pub fn page_colour(frame: &ImageBuffer<Rgb<u8>, Vec<u8>>) -> PageColour {
+    let total = (frame.width() as u64 * frame.height() as u64).max(1) as f64;
+    let (mut coloured, mut mid_tones, mut dark) = (0u64, 0u64, 0u64);
+    for pixel in frame.pixels() {
+        let spread = pixel.0.iter().max().unwrap() - pixel.0.iter().min().unwrap();
+        coloured += (spread > GREY_SPREAD) as u64;
+        let luma = luma(pixel.0);
+        mid_tones += MID_TONES.contains(&luma) as u64;
+        dark += (luma < *MID_TONES.start()) as u64;
+    }
+    if coloured as f64 > total * MAX_COLOURED_SHARE {
+        PageColour::Colour
+    } else if mid_tones as f64 > total * MAX_MID_TONE_SHARE || dark as f64 * 2.0 >= total {
+        PageColour::Grayscale
+    } else {
+        PageColour::Bilevel
+    }
+}
+
+/// Encodes a page of black text on white as one bit per pixel, split at the
+/// Otsu threshold of its luma, with CCITT Group 4 (`K -1`, `BlackIs1` false).
+pub fn encode_bilevel(frame: &ImageBuffer<Rgb<u8>, Vec<u8>>) -> Vec<u8> {
+    let luma: Vec<u8> = frame.pixels().map(|p| luma(p.0)).collect();
+    let threshold = otsu_threshold(&luma);
+    let black: Vec<bool> = luma.iter().map(|&l| l <= threshold).collect();
+    encode_g4(&black, frame.width() as usize, frame.height() as usize)
+}
+
+fn luma([r, g, b]: [u8; 3]) -> u8 {
+    ((r as u32 * 299 + g as u32 * 587 + b as u32 * 114) / 1000) as u8
+}
+
+/// Threshold that best separates the luma histogram into two classes; values
+/// up to and including it form the dark class.
+fn otsu_threshold(luma: &[u8]) -> u8 {
+    let mut histogram = [0u64; 256];
+    for &l in luma {
+        histogram[l as usize] += 1;
+    }
+    let total = luma.len() as f64;
+    let sum_all: f64 = histogram.iter().enumerate().map(|(v, &n)| v as f64 * n as f64).sum();
+    let (mut weight_dark, mut sum_dark) = (0.0, 0.0);
+    let (mut best, mut best_variance) = (0u8, -1.0);
+    for (value, &n) in histogram.iter().enumerate() {
+        weight_dark += n as f64;
+        sum_dark += value as f64 * n as f64;
+        let weight_light = total - weight_dark;
+        if weight_dark == 0.0 || weight_light == 0.0 {
+            continue;
+        }
+        let mean_dark = sum_dark / weight_dark;
+        let mean_light = (sum_all - sum_dark) / weight_light;
+        let variance = weight_dark * weight_light * (mean_dark - mean_light).powi(2);
+        if variance > best_variance {
+            best = value as u8;
+            best_variance = variance;
+        }
+    }
+    best
+}
+
+/// Averages each `BACKGROUND_SCALE`-sized cell of the frame over the pixels away
+/// from the text, then fills the cells covered by text from their neighbours.
+fn background(frame: &ImageBuffer<Rgb<u8>, Vec<u8>>, mask: &[bool]) -> ImageBuffer<Rgb<u8>, Vec<u8>> {
+    let (width, height) = frame.dimensions();
+    let (cells_x, cells_y) = (width.div_ceil(BACKGROUND_SCALE), height.div_ceil(BACKGROUND_SCALE));
+    let is_text = |x: u32, y: u32| mask[(y * width + x) as usize];
+    // Anti-aliased text edges sit just outside the mask, so keep clear of them too.
+    let near_text = |x: u32, y: u32| {
+        is_text(x, y)
+            || (x > 0 && is_text(x - 1, y))
+            || (x + 1 < width && is_text(x + 1, y))
+            || (y > 0 && is_text(x, y - 1))
+            || (y + 1 < height && is_text(x, y + 1))
+    };
+
+    let mut cells: Vec<Option<[u8; 3]>> = Vec::with_capacity((cells_x * cells_y) as usize);
+    for cell_y in 0..cells_y {
+        for cell_x in 0..cells_x {
+            let mut sum = [0u32; 3];
+            let mut count = 0;
+            for y in cell_y * BACKGROUND_SCALE..((cell_y + 1) * BACKGROUND_SCALE).min(height) {
+                for x in cell_x * BACKGROUND_SCALE..((cell_x + 1) * BACKGROUND_SCALE).min(width) {
+                    if !near_text(x, y) {
+                        for (s, &c) in sum.iter_mut().zip(&frame.get_pixel(x, y).0) {
+                            *s += c as u32;
+                        }
+                        count += 1;
+                    }
+                }
+            }
+            cells.push((count > 0).then(|| sum.map(|s| (s / count) as u8)));
+        }
+    }
+
+    // Grow the known cells into the holes one ring at a time.
+    let index = |x: u32, y: u32| (y * cells_x + x) as usize;
+    while cells.iter().any(Option::is_none) {
+        let previous = cells.clone();
+        let mut filled = false;
+        for y in 0..cells_y {
+            for x in 0..cells_x {
+                if previous[index(x, y)].is_some() {
+                    continue;
+                }
+                let neighbours: Vec<[u8; 3]> = [(-1, 0), (1, 0), (0, -1), (0, 1)]
+                    .iter()
+                    .filter_map(|&(dx, dy)| {
+                        let (nx, ny) = (x as i64 + dx, y as i64 + dy);
+                        (nx >= 0 && ny >= 0 && nx < cells_x as i64 && ny < cells_y as i64)
+                            .then(|| previous[index(nx as u32, ny as u32)])
+                            .flatten()
+                    })
+                    .collect();
+                if !neighbours.is_empty() {
+                    let n = neighbours.len() as u32;
+                    let mean = |c: usize| (neighbours.iter().map(|p| p[c] as u32).sum::<u32>() / n) as u8;
+                    cells[index(x, y)] = Some([mean(0), mean(1), mean(2)]);
+                    filled = true;
+                }
+            }
+        }
+        if !filled {
+            // Nothing but text: any colour will do under the mask.
+            cells.iter_mut().for_each(|cell| *cell = cell.or(Some([255, 255, 255])));
+        }
+    }
+
+    ImageBuffer::from_fn(cells_x, cells_y, |x, y| Rgb(cells[index(x, y)].unwrap_or_default()))
+}
+
+/// Encodes a bilevel image, `true` being black, with CCITT Group 4 (ITU-T T.6),
+/// as read by the PDF `CCITTFaxDecode` filter with `K -1`, `Columns` and `Rows`
+/// set to the size, and the default `BlackIs1` false. Ends with an end-of-block.
+pub fn encode_g4(pixels: &[bool], width: usize, height: usize) -> Vec<u8> {
+    let mut writer = BitWriter::default();
+    let white_line = vec![false; width];
+    let mut reference: &[bool] = &white_line;
+    for line in pixels.chunks(width).take(height) {
+        encode_line(&mut writer, line, reference);
+        reference = line;
+    }
+    // EOFB: two end-of-line codes.
+    writer.push((0b000000000001, 12));
+    writer.push((0b000000000001, 12));
+    writer.finish()
+}
+
+/// Position of the first pixel at or after `start` whose colour differs from
+/// the pixel before it (white before the line), or the line's length.
+fn next_change(line: &[bool], start: usize) -> usize {
+    (start..line.len())
+        .find(|&x| line[x] != if x == 0 { false } else { line[x - 1] })
+        .unwrap_or(line.len())
+}
+
+/// Encodes one line against the line above it (two-dimensional coding).
+fn encode_line(writer: &mut BitWriter, line: &[bool], reference: &[bool]) {
+    let width = line.len();
+    // `a0` is -1, an imaginary white pixel before the line, until the first code.
+    let mut a0: Option<usize> = None;
+    let mut color = false;
+    loop {
+        let start = a0.map_or(0, |a0| a0 + 1);
+        let a1 = next_change(line, start);
+        let mut b1 = next_change(reference, start);
+        if b1 < width && reference[b1] == color {
+            b1 = next_change(reference, b1 + 1);
+        }
+        let b2 = if b1 < width { next_change(reference, b1 + 1) } else { width };
+
+        if b2 < a1 {
+            writer.push((0b0001, 4)); // pass
+            a0 = Some(b2);
+        } else if a1.abs_diff(b1) <= 3 {
+            writer.push(match a1 as isize - b1 as isize {
+                0 => (0b1, 1),
+                1 => (0b011, 3),
+                2 => (0b000011, 6),
+                3 => (0b0000011, 7),
+                -1 => (0b010, 3),
+                -2 => (0b000010, 6),
+                _ => (0b0000010, 7),
+            });
+            a0 = Some(a1);
+            color = !color;
+        } else {
+            let a2 = if a1 < width { next_change(line, a1 + 1) } else { width };
+            writer.push((0b001, 3)); // horizontal
+            writer.push_run(a1 - a0.unwrap_or(0), color);
+            writer.push_run(a2 - a1, !color);
+            a0 = Some(a2);
+        }
+        if a0.is_some_and(|a0| a0 >= width) {
+            break;
+        }
+    }
+}
+
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    current: u8,
+    used: u8,
+}
+
+impl BitWriter {
+    /// Appends a code given as its bits and their count, most significant first.
+    fn push(&mut self, (code, len): (u16, u8)) {
+        for i in (0..len).rev() {
+            self.current = (self.current << 1) | ((code >> i) & 1) as u8;
+            self.used += 1;
+            if self.used == 8 {
+                self.bytes.push(self.current);
+                self.current = 0;
+                self.used = 0;
+            }
+        }
+    }
+
+    /// Appends the codes of a run of `length` pixels, black when `black` is set.
+    fn push_run(&mut self, mut length: usize, black: bool) {
+        let (terminating, makeup) = if black {
+            (&BLACK_TERMINATING, &BLACK_MAKEUP)
+        } else {
+            (&WHITE_TERMINATING, &WHITE_MAKEUP)
+        };
+        while length >= 2560 {
+            self.push(EXTENDED_MAKEUP[EXTENDED_MAKEUP.len() - 1]);
+            length -= 2560;
+        }
+        if length >= 64 {
+            let steps = length / 64;
+            self.push(if steps <= makeup.len() { makeup[steps - 1] } else { EXTENDED_MAKEUP[steps - 28] });
+            length %= 64;
+        }
+        self.push(terminating[length]);
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.used > 0 {
+            self.bytes.push(self.current << (8 - self.used));
+        }
+        self.bytes
+    }
+}
+
+/// Codes of white runs of 0 to 63 pixels.
+const WHITE_TERMINATING: [(u16, u8); 64] = [
+    (0b00110101, 8), (0b000111, 6), (0b0111, 4), (0b1000, 4), (0b1011, 4), (0b1100, 4), (0b1110, 4),
+    (0b1111, 4), (0b10011, 5), (0b10100, 5), (0b00111, 5), (0b01000, 5), (0b001000, 6),
+    (0b000011, 6), (0b110100, 6), (0b110101, 6), (0b101010, 6), (0b101011, 6), (0b0100111, 7),
+    (0b0001100, 7), (0b0001000, 7), (0b0010111, 7), (0b0000011, 7), (0b0000100, 7), (0b0101000, 7),
+    (0b0101011, 7), (0b0010011, 7), (0b0100100, 7), (0b0011000, 7), (0b00000010, 8),
+    (0b00000011, 8), (0b00011010, 8), (0b00011011, 8), (0b00010010, 8), (0b00010011, 8),
+    (0b00010100, 8), (0b00010101, 8), (0b00010110, 8), (0b00010111, 8), (0b00101000, 8),
+    (0b00101001, 8), (0b00101010, 8), (0b00101011, 8), (0b00101100, 8), (0b00101101, 8),
+    (0b00000100, 8), (0b00000101, 8), (0b00001010, 8), (0b00001011, 8), (0b01010010, 8),
+    (0b01010011, 8), (0b01010100, 8), (0b01010101, 8), (0b00100100, 8), (0b00100101, 8),
+    (0b01011000, 8), (0b01011001, 8), (0b01011010, 8), (0b01011011, 8), (0b01001010, 8),
+    (0b01001011, 8), (0b00110010, 8), (0b00110011, 8), (0b00110100, 8),
+];
+/// Codes of white runs of 64 to 1728 pixels, in steps of 64.
+const WHITE_MAKEUP: [(u16, u8); 27] = [
+    (0b11011, 5), (0b10010, 5), (0b010111, 6), (0b0110111, 7), (0b00110110, 8), (0b00110111, 8),
+    (0b01100100, 8), (0b01100101, 8), (0b01101000, 8), (0b01100111, 8), (0b011001100, 9),
+    (0b011001101, 9), (0b011010010, 9), (0b011010011, 9), (0b011010100, 9), (0b011010101, 9),
+    (0b011010110, 9), (0b011010111, 9), (0b011011000, 9), (0b011011001, 9), (0b011011010, 9),
+    (0b011011011, 9), (0b010011000, 9), (0b010011001, 9), (0b010011010, 9), (0b011000, 6),
+    (0b010011011, 9),
+];
+/// Codes of black runs of 0 to 63 pixels.
+const BLACK_TERMINATING: [(u16, u8); 64] = [
+    (0b0000110111, 10), (0b010, 3), (0b11, 2), (0b10, 2), (0b011, 3), (0b0011, 4), (0b0010, 4),
+    (0b00011, 5), (0b000101, 6), (0b000100, 6), (0b0000100, 7), (0b0000101, 7), (0b0000111, 7),
+    (0b00000100, 8), (0b00000111, 8), (0b000011000, 9), (0b0000010111, 10), (0b0000011000, 10),
+    (0b0000001000, 10), (0b00001100111, 11), (0b00001101000, 11), (0b00001101100, 11),
+    (0b00000110111, 11), (0b00000101000, 11), (0b00000010111, 11), (0b00000011000, 11),
+    (0b000011001010, 12), (0b000011001011, 12), (0b000011001100, 12), (0b000011001101, 12),
+    (0b000001101000, 12), (0b000001101001, 12), (0b000001101010, 12), (0b000001101011, 12),
+    (0b000011010010, 12), (0b000011010011, 12), (0b000011010100, 12), (0b000011010101, 12),
+    (0b000011010110, 12), (0b000011010111, 12), (0b000001101100, 12), (0b000001101101, 12),
+    (0b000011011010, 12), (0b000011011011, 12), (0b000001010100, 12), (0b000001010101, 12),
+    (0b000001010110, 12), (0b000001010111, 12), (0b000001100100, 12), (0b000001100101, 12),
+    (0b000001010010, 12), (0b000001010011, 12), (0b000000100100, 12), (0b000000110111, 12),
+    (0b000000111000, 12), (0b000000100111, 12), (0b000000101000, 12), (0b000001011000, 12),
+    (0b000001011001, 12), (0b000000101011, 12), (0b000000101100, 12), (0b000001011010, 12),
+    (0b000001100110, 12), (0b000001100111, 12),
+];
+/// Codes of black runs of 64 to 1728 pixels, in steps of 64.
+const BLACK_MAKEUP: [(u16, u8); 27] = [
+    (0b0000001111, 10), (0b000011001000, 12), (0b000011001001, 12), (0b000001011011, 12),
+    (0b000000110011, 12), (0b000000110100, 12), (0b000000110101, 12), (0b0000001101100, 13),
+    (0b0000001101101, 13), (0b0000001001010, 13), (0b0000001001011, 13), (0b0000001001100, 13),
+    (0b0000001001101, 13), (0b0000001110010, 13), (0b0000001110011, 13), (0b0000001110100, 13),
+    (0b0000001110101, 13), (0b0000001110110, 13), (0b0000001110111, 13), (0b0000001010010, 13),
+    (0b0000001010011, 13), (0b0000001010100, 13), (0b0000001010101, 13), (0b0000001011010, 13),
+    (0b0000001011011, 13), (0b0000001100100, 13), (0b0000001100101, 13),
+];
+/// Codes of runs of 1792 to 2560 pixels of either colour, in steps of 64.
+const EXTENDED_MAKEUP: [(u16, u8); 13] = [
+    (0b00000001000, 11), (0b00000001100, 11), (0b00000001101, 11), (0b000000010010, 12),
+    (0b000000010011, 12), (0b000000010100, 12), (0b000000010101, 12), (0b000000010110, 12),
+    (0b000000010111, 12), (0b000000011100, 12), (0b000000011101, 12), (0b000000011110, 12),
+    (0b000000011111, 12),
+];
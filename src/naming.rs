@@ -0,0 +1,141 @@
+//! Naming Module
+//!
+//! Handles `--name-template`, which lays out the output files inside the result
+//! directory: a relative path with `{field}` placeholders, such as
+//! `{stem}/{date}/slide_{index:04}`, rendered for every document or page image,
+//! with the format's extension appended.
+
+use crate::paths;
+use anyhow::{bail, ensure, Result};
+use std::collections::HashSet;
+
+/// Placeholders a template may use.
+pub const FIELDS: [&str; 4] = ["stem", "title", "date", "index"];
+
+/// Name given to outputs whose template renders to an empty path.
+const FALLBACK_NAME: &str = "document";
+
+/// Values of the placeholders for one output.
+#[derive(Debug, Clone, Default)]
+pub struct NameFields {
+    /// Input file name without its extension.
+    pub stem: String,
+    /// Title of the document, or of the part with `--split-by`.
+    pub title: String,
+    /// Recording date as `YYYY-MM-DD`.
+    pub date: String,
+    /// Page number for page images, part number for documents; both start at 1.
+    pub index: usize,
+}
+
+/// A parsed `--name-template`.
+#[derive(Debug, Clone)]
+pub struct NameTemplate {
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone)]
+enum Segment {
+    Literal(String),
+    /// A placeholder, zero-padded to `width` digits when given (`index` only).
+    Field { name: String, width: usize },
+}
+
+impl NameTemplate {
+    /// Parses a template, rejecting unknown placeholders and paths that would
+    /// leave the result directory.
+    pub fn parse(template: &str) -> Result<Self> {
+        ensure!(!template.trim().is_empty(), "The name template is empty");
+        ensure!(
+            !template.starts_with('/') && !template.starts_with('\\'),
+            "The name template must be a relative path: {:?}",
+            template
+        );
+        ensure!(
+            !template.split(['/', '\\']).any(|component| component == ".."),
+            "The name template must not contain '..': {:?}",
+            template
+        );
+
+        let mut segments = Vec::new();
+        let mut rest = template;
+        while let Some(open) = rest.find(['{', '}']) {
+            if rest[open..].starts_with('}') {
+                bail!("Unmatched '}}' in name template {:?}", template);
+            }
+            if open > 0 {
+                segments.push(Segment::Literal(rest[..open].to_string()));
+            }
+            let Some(close) = rest[open..].find('}') else {
+                bail!("Unclosed '{{' in name template {:?}", template);
+            };
+            let placeholder = &rest[open + 1..open + close];
+            let (name, width) = match placeholder.split_once(':') {
+                Some((name, width)) => {
+                    let Ok(width) = width.parse::<usize>() else {
+                        bail!("Invalid width in {{{}}}; use a number of digits such as {{index:04}}", placeholder);
+                    };
+                    ensure!(name == "index", "Only {{index}} can be zero-padded, not {{{}}}", placeholder);
+                    (name, width)
+                }
+                None => (placeholder, 0),
+            };
+            ensure!(
+                FIELDS.contains(&name),
+                "Unknown placeholder {{{}}} in name template; available: {}",
+                name,
+                FIELDS.map(|field| format!("{{{}}}", field)).join(", ")
+            );
+            segments.push(Segment::Field { name: name.to_string(), width });
+            rest = &rest[open + close + 1..];
+        }
+        if !rest.is_empty() {
+            segments.push(Segment::Literal(rest.to_string()));
+        }
+        Ok(Self { segments })
+    }
+
+    /// Renders the path of one output, relative to the result directory and
+    /// without extension. Values cannot add directories, and every component is
+    /// made safe as a file name.
+    pub fn render(&self, fields: &NameFields) -> String {
+        let mut rendered = String::new();
+        for segment in &self.segments {
+            match segment {
+                Segment::Literal(text) => rendered.push_str(text),
+                Segment::Field { name, width } => {
+                    let value = match name.as_str() {
+                        "stem" => fields.stem.clone(),
+                        "title" => fields.title.clone(),
+                        "date" => fields.date.clone(),
+                        _ => format!("{:0width$}", fields.index, width = width),
+                    };
+                    rendered.push_str(&value.replace(['/', '\\'], "_"));
+                }
+            }
+        }
+        let components: Vec<String> = rendered
+            .split(['/', '\\'])
+            .filter(|component| !component.trim().is_empty() && *component != ".")
+            .map(paths::sanitize_file_name)
+            .collect();
+        if components.is_empty() {
+            FALLBACK_NAME.to_string()
+        } else {
+            components.join("/")
+        }
+    }
+}
+
+/// Fails when two outputs were given the same path.
+pub fn ensure_unique(files: &[String]) -> Result<()> {
+    let mut seen = HashSet::new();
+    for file in files {
+        ensure!(
+            seen.insert(file.as_str()),
+            "--name-template gives several outputs the path {:?}; include {{index}} or {{title}} in it",
+            file
+        );
+    }
+    Ok(())
+}
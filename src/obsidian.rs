@@ -0,0 +1,176 @@
+//! Obsidian Module
+//!
+//! Handles the `obsidian` output format: a folder that can be opened as (or
+//! dropped into) an Obsidian vault, with one Markdown note per page carrying
+//! YAML front matter, wiki-links to the previous and next page, the page image
+//! from an `attachments/` folder, and an overview note linking every page.
+
+use crate::document_builder::{Page, deep_link, format_timestamp};
+use crate::layout;
+use crate::paths;
+use anyhow::{Context, Result};
+use rayon::prelude::*;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+/// Folder of the page images, relative to the vault.
+pub const ATTACHMENTS_DIR: &str = "attachments";
+
+/// Options for the vault export.
+#[derive(Debug, Clone)]
+pub struct VaultOptions {
+    /// Title of the whole document, which names the overview note.
+    pub document_title: String,
+    /// File name of the input video.
+    pub source: String,
+    /// URL of the source video; notes then link to the recording at their timestamp.
+    pub video_url: Option<String>,
+    /// Tags of each page, by page, added to the `slide` tag of its note.
+    pub tags: Vec<Vec<String>>,
+}
+
+/// Name of the note of every page (without the `.md` extension): its number and
+/// title, made safe both as a file name and as a wiki-link target.
+pub fn note_names(pages: &[Page]) -> Vec<String> {
+    pages
+        .iter()
+        .enumerate()
+        .map(|(i, page)| {
+            let title: String = page
+                .display_title(i)
+                .chars()
+                .map(|c| if matches!(c, '#' | '^' | '[' | ']' | '|') { '_' } else { c })
+                .collect();
+            paths::sanitize_file_name(&format!("{:03} {}", i + 1, title))
+        })
+        .collect()
+}
+
+/// Path of the image of page `index`, relative to the vault.
+pub fn attachment_path(index: usize) -> String {
+    format!("{}/{}", ATTACHMENTS_DIR, attachment_name(index))
+}
+
+fn attachment_name(index: usize) -> String {
+    format!("page_{:03}.png", index + 1)
+}
+
+/// Writes the notes, the overview note, and the attachments to `base_dir`, and
+/// returns the path of every page's note relative to it.
+pub fn build_vault(pages: &[Page], options: &VaultOptions, base_dir: &Path) -> Result<Vec<String>> {
+    fs::create_dir_all(base_dir.join(ATTACHMENTS_DIR))
+        .context("Failed to create attachments directory")?;
+    pages.par_iter().enumerate().try_for_each(|(i, page)| -> Result<()> {
+        let image_path = base_dir.join(attachment_path(i));
+        page.image
+            .save(&image_path)
+            .with_context(|| format!("Failed to save page image to {:?}", image_path))
+    })?;
+
+    let names = note_names(pages);
+    let mut files = Vec::with_capacity(pages.len());
+    for (i, page) in pages.iter().enumerate() {
+        let file = format!("{}.md", names[i]);
+        let note_path = base_dir.join(&file);
+        fs::write(&note_path, note(i, page, &names, options)?)
+            .with_context(|| format!("Failed to write note {:?}", note_path))?;
+        files.push(file);
+    }
+
+    let mut overview = front_matter(&[
+        ("title", Some(yaml_string(&options.document_title))),
+        ("source", Some(yaml_string(&options.source))),
+        ("video_url", options.video_url.as_deref().map(yaml_string)),
+        ("pages", Some(pages.len().to_string())),
+    ]);
+    writeln!(overview, "# {}\n", options.document_title)?;
+    for (page, name) in pages.iter().zip(&names) {
+        match page.timestamp {
+            Some(timestamp) => writeln!(overview, "- [[{}]] ({})", name, format_timestamp(timestamp))?,
+            None => writeln!(overview, "- [[{}]]", name)?,
+        }
+    }
+    let overview_path = base_dir.join(format!("{}.md", overview_name(options)));
+    fs::write(&overview_path, overview)
+        .with_context(|| format!("Failed to write note {:?}", overview_path))?;
+    Ok(files)
+}
+
+/// Name of the overview note, after the document title.
+fn overview_name(options: &VaultOptions) -> String {
+    paths::sanitize_file_name(&options.document_title)
+}
+
+/// Builds the note of page `index`.
+fn note(index: usize, page: &Page, names: &[String], options: &VaultOptions) -> Result<String> {
+    let video_link =
+        options.video_url.as_deref().zip(page.timestamp).map(|(url, t)| deep_link(url, t));
+    let mut note = front_matter(&[
+        ("title", Some(yaml_string(&page.display_title(index)))),
+        ("page", Some((index + 1).to_string())),
+        ("timestamp", page.timestamp.map(|t| format!("{:.3}", t))),
+        ("time", page.timestamp.map(|t| yaml_string(&format_timestamp(t)))),
+        ("end", page.end.map(|t| format!("{:.3}", t))),
+        ("source", Some(yaml_string(&options.source))),
+        ("video_url", video_link.as_deref().map(yaml_string)),
+        ("confidence", page.mean_confidence().map(|c| format!("{:.1}", c))),
+        ("tags", Some(note_tags(options.tags.get(index).map(Vec::as_slice).unwrap_or_default()))),
+    ]);
+
+    writeln!(note, "# {}\n", page.display_title(index))?;
+    writeln!(note, "![[{}]]\n", attachment_name(index))?;
+    if let Some(ocr) = page.ocr {
+        let lines: Vec<String> = layout::group_lines(ocr).into_iter().map(|line| line.text).collect();
+        if !lines.is_empty() {
+            writeln!(note, "{}\n", lines.join("\n"))?;
+        }
+    }
+
+    let mut navigation = Vec::new();
+    if index > 0 {
+        navigation.push(format!("Previous: [[{}]]", names[index - 1]));
+    }
+    if let Some(next) = names.get(index + 1) {
+        navigation.push(format!("Next: [[{}]]", next));
+    }
+    navigation.push(format!("Up: [[{}]]", overview_name(options)));
+    writeln!(note, "---\n{}", navigation.join(" · "))?;
+    Ok(note)
+}
+
+/// YAML list of the tags of a note: `slide`, then the page's own tags.
+fn note_tags(tags: &[String]) -> String {
+    let quoted: Vec<String> = tags.iter().map(|tag| yaml_string(tag)).collect();
+    format!("[{}]", ["slide".to_string()].into_iter().chain(quoted).collect::<Vec<_>>().join(", "))
+}
+
+/// Builds a YAML front matter block from the fields that have a value, which
+/// must already be valid YAML scalars.
+fn front_matter(fields: &[(&str, Option<String>)]) -> String {
+    let mut yaml = String::from("---\n");
+    for (key, value) in fields {
+        if let Some(value) = value {
+            yaml.push_str(&format!("{}: {}\n", key, value));
+        }
+    }
+    yaml.push_str("---\n\n");
+    yaml
+}
+
+/// Quotes a string as a double-quoted YAML scalar.
+pub(crate) fn yaml_string(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
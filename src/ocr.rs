@@ -1,20 +1,41 @@
 //! OCR Module
 //!
-//! Handles text extraction from images using the tesseract-rs crate.
+//! Handles text extraction from images. Recognition goes through the
+//! [`OcrEngine`] trait, so engines are pluggable; the Tesseract one, using the
+//! tesseract-rs crate, is built in.
 
-use anyhow::{Context, Result};
-use image::{ImageBuffer, Rgb};
+use crate::i18n::{Msg, tr};
+use crate::mask::IgnoreMask;
+use crate::preprocess;
+use crate::text_presence;
+use anyhow::{Context, Result, bail, ensure};
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageBuffer, Rgb};
 use indicatif::{ProgressBar, ProgressStyle};
 use log::{info, warn};
 use rayon::prelude::*;
-use serde::Serialize; // Added for serialization
+use serde::{Deserialize, Serialize};
 use std::fs; // Added for file system operations
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 // Use the correct API and types from the provided source
-use tesseract_rs::{TessPageIteratorLevel, TesseractAPI};
+use tesseract_rs::{TessPageIteratorLevel, TessPageSegMode, TesseractAPI};
+
+// Minimum box overlap (intersection over union) for words on different samples to be merged.
+const CONSENSUS_MIN_OVERLAP: f32 = 0.5;
+// Page segmentation modes a low-confidence frame is retried with: a single
+// column, a single block, and sparse text.
+const RETRY_PSMS: [u8; 3] = [4, 6, 11];
+// Tesseract's page segmentation mode when none is configured.
+const DEFAULT_PSM: u8 = 3;
+// Tesseract's writing direction of vertical lines (`WRITING_DIRECTION_TOP_TO_BOTTOM`),
+// which `tesseract-rs` does not export.
+const WRITING_DIRECTION_TOP_TO_BOTTOM: i32 = 2;
+/// Name [`retry`] gives the first reading of a frame when it stays the best.
+pub const DEFAULT_STRATEGY: &str = "default";
 
 /// Represents a single recognized word with its metadata.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OcrWord {
     pub text: String,
     /// Bounding box as a tuple: (x1, y1, x2, y2)
@@ -22,118 +43,1072 @@ pub struct OcrWord {
     pub confidence: f32,
 }
 
+impl OcrWord {
+    /// Whether the element is shaped like text running top to bottom, as
+    /// vertical Chinese and Japanese do: at least two characters in a box at
+    /// least twice as tall as it is wide.
+    pub fn is_vertical(&self) -> bool {
+        runs_down(&self.text, self.bbox)
+    }
+}
+
+/// Whether `text` set in `bbox` runs top to bottom; see [`OcrWord::is_vertical`].
+pub fn runs_down(text: &str, (x1, y1, x2, y2): (i32, i32, i32, i32)) -> bool {
+    text.chars().filter(|c| !c.is_whitespace()).count() >= 2 && y2 - y1 >= 2 * (x2 - x1)
+}
+
 /// Holds all the recognized words from a single frame.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OcrFrameResult {
     pub frame_index: usize,
     pub words: Vec<OcrWord>,
+    /// Blocks of text found by the engine, in reading order. Empty when the
+    /// engine gives no lines, in which case layout is rebuilt from the word boxes.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub blocks: Vec<OcrBlock>,
+    /// Settings the words were read with when a low-confidence frame was
+    /// retried: "default" when the first reading stayed the best, else the
+    /// winning alternative. `None` for frames that were not retried.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strategy: Option<String>,
+    /// When the page's frame appears in the video, in seconds, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<f64>,
+}
+
+impl OcrFrameResult {
+    /// The frame with only the words `keep` accepts, left out of the engine's
+    /// lines as well.
+    pub fn retain_words(&self, keep: impl Fn(&OcrWord) -> bool) -> Self {
+        let keep: Vec<bool> = self.words.iter().map(keep).collect();
+        self.retain_marked(&keep)
+    }
+
+    /// The frame with only the words marked in `keep`, by position in `words`.
+    fn retain_marked(&self, keep: &[bool]) -> Self {
+        // Position of each kept word among the kept ones, for the engine's lines.
+        let mut kept_index = vec![None; self.words.len()];
+        let mut words = Vec::new();
+        for (i, word) in self.words.iter().enumerate() {
+            if keep.get(i).copied().unwrap_or(false) {
+                kept_index[i] = Some(words.len());
+                words.push(word.clone());
+            }
+        }
+        let mut blocks = self.blocks.clone();
+        let paragraphs = blocks.iter_mut().flat_map(|block| &mut block.paragraphs);
+        for line in paragraphs.flat_map(|paragraph| &mut paragraph.lines) {
+            line.words.retain_mut(|i| match kept_index.get(*i).copied().flatten() {
+                Some(kept) => {
+                    *i = kept;
+                    true
+                }
+                None => false,
+            });
+        }
+        Self { words, blocks, strategy: self.strategy.clone(), ..*self }
+    }
+}
+
+/// A block of text, such as a column or a text box, with its paragraphs in reading order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OcrBlock {
+    pub bbox: (i32, i32, i32, i32),
+    pub paragraphs: Vec<OcrParagraph>,
+}
+
+/// A paragraph of a block, with its lines in reading order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OcrParagraph {
+    pub bbox: (i32, i32, i32, i32),
+    pub lines: Vec<OcrTextLine>,
+}
+
+/// A line of a paragraph.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OcrTextLine {
+    pub bbox: (i32, i32, i32, i32),
+    /// Indices of the line's words in the frame's `words`, in reading order.
+    pub words: Vec<usize>,
+    /// Whether the line runs top to bottom, as vertical Chinese and Japanese do.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub vertical: bool,
 }
 
 /// Gets the default location where this version of `tesseract-rs` caches its data.
 /// The build script downloads language files here.
-fn get_tessdata_dir() -> Result<PathBuf> {
+pub fn tesseract_rs_cache_dir() -> Option<PathBuf> {
     let base_path = if cfg!(target_os = "macos") {
-        let home = std::env::var("HOME").context("HOME env var not set")?;
-        PathBuf::from(home)
+        PathBuf::from(std::env::var_os("HOME")?)
             .join("Library")
             .join("Application Support")
-    } else if cfg!(target_os = "linux") {
-        let home = std::env::var("HOME").context("HOME env var not set")?;
-        PathBuf::from(home).join(".tesseract-rs")
     } else if cfg!(target_os = "windows") {
-        let appdata = std::env::var("APPDATA").context("APPDATA env var not set")?;
-        PathBuf::from(appdata)
+        PathBuf::from(std::env::var_os("APPDATA")?)
     } else {
-        panic!("Unsupported operating system");
+        PathBuf::from(std::env::var_os("HOME")?).join(".tesseract-rs")
     };
-    Ok(base_path.join("tesseract-rs").join("tessdata"))
+    Some(base_path.join("tesseract-rs").join("tessdata"))
+}
+
+/// System-wide tessdata directories of common Tesseract packages.
+const SYSTEM_TESSDATA_DIRS: &[&str] = &[
+    "/usr/share/tesseract-ocr/5/tessdata",
+    "/usr/share/tesseract-ocr/4.00/tessdata",
+    "/usr/share/tessdata",
+    "/usr/local/share/tessdata",
+    "/opt/homebrew/share/tessdata",
+    "C:\\Program Files\\Tesseract-OCR\\tessdata",
+];
+
+/// Lists the directories searched for language packs when `--tessdata-dir` is
+/// not given, in order: `TESSDATA_PREFIX`, then its `tessdata` subdirectory as
+/// older Tesseract versions expected, the `tesseract-rs` cache, and the
+/// system-wide directories of common Tesseract packages.
+pub fn tessdata_candidates() -> Vec<PathBuf> {
+    let mut candidates = Vec::new();
+    if let Some(prefix) = std::env::var_os("TESSDATA_PREFIX").filter(|prefix| !prefix.is_empty()) {
+        let prefix = PathBuf::from(prefix);
+        candidates.push(prefix.clone());
+        candidates.push(prefix.join("tessdata"));
+    }
+    candidates.extend(tesseract_rs_cache_dir());
+    candidates.extend(SYSTEM_TESSDATA_DIRS.iter().map(PathBuf::from));
+    candidates
+}
+
+/// Finds the tessdata directory holding the packs for `lang`. An explicit
+/// directory is always used; otherwise the first of [`tessdata_candidates`]
+/// with every pack wins.
+pub fn find_tessdata_dir(explicit: Option<&Path>, lang: &str) -> Result<PathBuf> {
+    if let Some(dir) = explicit {
+        ensure!(dir.is_dir(), "The tessdata directory {:?} does not exist", dir);
+        check_language_packs(lang, dir)?;
+        return Ok(dir.to_path_buf());
+    }
+    let candidates = tessdata_candidates();
+    if let Some(dir) = candidates.iter().find(|dir| check_language_packs(lang, dir).is_ok()) {
+        return Ok(dir.clone());
+    }
+    let searched: Vec<String> = candidates.iter().map(|dir| dir.display().to_string()).collect();
+    bail!(
+        "No tessdata directory holds the language pack(s) for '{}'; searched {}. Set --tessdata-dir or TESSDATA_PREFIX",
+        lang,
+        searched.join(", ")
+    )
 }
 
 /// Performs OCR in parallel on a vector of image frames, extracting detailed word data.
+///
+/// `timestamps[i]` is when `frames[i]` appears in the video, given to its result.
+/// `masks[i]`, if any, is painted over `frames[i]` before it is recognized.
+/// Frames without text are left out with `config.skip_textless`.
 pub fn perform_ocr_on_frames(
     frames: &[ImageBuffer<Rgb<u8>, Vec<u8>>],
+    masks: &[Option<&IgnoreMask>],
+    timestamps: &[Option<f64>],
     config: &crate::Config,
 ) -> Result<Vec<OcrFrameResult>> {
-    let lang = &config.lang;
-    let output_dir = &config.output_dir;
-   
-    info!("Starting detailed OCR on {} frames using language '{}'...", frames.len(), lang);
+    let with_text = frames_with_text(frames, masks, config);
+    let pages: Vec<usize> = (0..frames.len()).filter(|&page| with_text[page]).collect();
+    let frame_refs: Vec<&ImageBuffer<Rgb<u8>, Vec<u8>>> = pages.iter().map(|&page| &frames[page]).collect();
+    let frame_masks: Vec<Option<&IgnoreMask>> =
+        pages.iter().map(|&page| masks.get(page).copied().flatten()).collect();
+    let engine = start_engine(frame_refs.len(), config)?;
+    let mut results = recognize_on_threads(
+        engine.as_ref(),
+        &frame_refs,
+        &frame_masks,
+        &config.preprocess,
+        config.retry_below,
+        config.ocr_threads,
+    )?;
+    for result in &mut results {
+        result.frame_index = pages[result.frame_index];
+        result.timestamp = timestamps.get(result.frame_index).copied().flatten();
+    }
+    info!("Successfully performed detailed OCR on {} frames.", results.len());
+    Ok(results)
+}
 
-    // Initialize one master API instance. It will be cloned for each thread.
+/// Performs OCR on several samples of each page and merges them by voting per word.
+///
+/// `samples[i]` holds additional frames from the same stable segment as `frames[i]`;
+/// the kept frame itself always takes part in the vote; see [`merge_by_vote`].
+/// `masks[i]`, if any, is painted over `frames[i]` and its samples before they are recognized.
+/// Pages whose kept frame has no text are left out with `config.skip_textless`.
+pub fn perform_consensus_ocr(
+    frames: &[ImageBuffer<Rgb<u8>, Vec<u8>>],
+    samples: &[Vec<ImageBuffer<Rgb<u8>, Vec<u8>>>],
+    masks: &[Option<&IgnoreMask>],
+    timestamps: &[Option<f64>],
+    config: &crate::Config,
+) -> Result<Vec<OcrFrameResult>> {
+    let mut frame_refs = Vec::new();
+    let mut frame_masks = Vec::new();
+    let mut owners = Vec::new();
+    let mut is_kept = Vec::new();
+    let with_text = frames_with_text(frames, masks, config);
+    for (page, frame) in frames.iter().enumerate().filter(|(page, _)| with_text[*page]) {
+        let mask = masks.get(page).copied().flatten();
+        frame_refs.push(frame);
+        frame_masks.push(mask);
+        owners.push(page);
+        is_kept.push(true);
+        for sample in samples.get(page).into_iter().flatten() {
+            frame_refs.push(sample);
+            frame_masks.push(mask);
+            owners.push(page);
+            is_kept.push(false);
+        }
+    }
+    info!(
+        "Running consensus OCR over {} samples for {} pages.",
+        frame_refs.len(),
+        frames.len()
+    );
+
+    // Each page's kept frame and its other samples.
+    let mut per_page: Vec<(Option<OcrFrameResult>, Vec<OcrFrameResult>)> =
+        (0..frames.len()).map(|_| (None, Vec::new())).collect();
+    let engine = start_engine(frame_refs.len(), config)?;
+    let results = recognize_on_threads(
+        engine.as_ref(),
+        &frame_refs,
+        &frame_masks,
+        &config.preprocess,
+        config.retry_below,
+        config.ocr_threads,
+    )?;
+    for result in results {
+        let (kept, others) = &mut per_page[owners[result.frame_index]];
+        if is_kept[result.frame_index] {
+            *kept = Some(result);
+        } else {
+            others.push(result);
+        }
+    }
+
+    let results: Vec<OcrFrameResult> = per_page
+        .into_iter()
+        .enumerate()
+        .filter_map(|(page, (kept, mut others))| {
+            // When the kept frame could not be read, its earliest sample stands in for it.
+            let kept = match kept {
+                Some(kept) => kept,
+                None if !others.is_empty() => others.remove(0),
+                None => return None,
+            };
+            let merged = merge_by_vote(kept, others);
+            Some(OcrFrameResult { frame_index: page, timestamp: timestamps.get(page).copied().flatten(), ..merged })
+        })
+        .collect();
+    info!("Successfully merged consensus OCR for {} pages.", results.len());
+    Ok(results)
+}
+
+/// Tells which frames may show text, their masks painted over them first. All
+/// of them do unless `config.skip_textless` is set.
+fn frames_with_text(
+    frames: &[ImageBuffer<Rgb<u8>, Vec<u8>>],
+    masks: &[Option<&IgnoreMask>],
+    config: &crate::Config,
+) -> Vec<bool> {
+    if !config.skip_textless {
+        return vec![true; frames.len()];
+    }
+    let with_text: Vec<bool> = frames
+        .par_iter()
+        .enumerate()
+        .map(|(page, frame)| match masks.get(page).copied().flatten() {
+            Some(mask) => text_presence::has_text(&mask.apply(frame, Rgb(crate::figures::border_colour(frame)))),
+            None => text_presence::has_text(frame),
+        })
+        .collect();
+    let skipped = with_text.iter().filter(|&&text| !text).count();
+    info!("Skipping OCR on {} of {} frames without text; they become image-only pages.", skipped, frames.len());
+    with_text
+}
+
+/// Creates the engine that recognizes `frame_count` frames.
+fn start_engine(frame_count: usize, config: &crate::Config) -> Result<Box<dyn OcrEngine + '_>> {
+    info!("Starting detailed OCR on {} frames using language '{}'...", frame_count, config.lang);
+    let engine = create_engine(config)?;
+    info!("Recognizing text with {}.", engine.name());
+    Ok(engine)
+}
+
+/// Merges the words recognized on several samples of the same page.
+///
+/// Words from different samples are treated as the same word when their boxes
+/// overlap enough. A word survives when it was seen on a majority of the samples
+/// (`kept` counts as one), and its text is the most frequent reading, ties going
+/// to the higher total confidence, then to the reading of `kept`, then to the
+/// earliest sample.
+///
+/// The result is the reading of the sample that agrees with the most surviving
+/// words, `kept` winning ties: its words that survived, with their voted text and
+/// mean confidence, and its blocks and strategy. When that sample misses some of
+/// the surviving words, they cannot be placed in its lines, so all of them are
+/// kept at the boxes of their first reading, without blocks. The frame index and
+/// timestamp are those of `kept`.
+pub fn merge_by_vote(kept: OcrFrameResult, mut samples: Vec<OcrFrameResult>) -> OcrFrameResult {
+    // Sample order is decoding order.
+    samples.sort_by_key(|r| r.frame_index);
+    let (frame_index, timestamp) = (kept.frame_index, kept.timestamp);
+    let candidates: Vec<OcrFrameResult> = std::iter::once(kept).chain(samples).collect();
+
+    struct Cluster {
+        bbox: (i32, i32, i32, i32),
+        // (candidate, word index) of each reading.
+        votes: Vec<(usize, usize)>,
+    }
+    let mut clusters: Vec<Cluster> = Vec::new();
+    for (candidate, result) in candidates.iter().enumerate() {
+        for (index, word) in result.words.iter().enumerate() {
+            let existing = clusters.iter_mut().find(|c| {
+                c.votes.iter().all(|(s, _)| *s != candidate)
+                    && intersection_over_union(c.bbox, word.bbox) >= CONSENSUS_MIN_OVERLAP
+            });
+            match existing {
+                Some(cluster) => cluster.votes.push((candidate, index)),
+                None => clusters.push(Cluster { bbox: word.bbox, votes: vec![(candidate, index)] }),
+            }
+        }
+    }
+
+    // The voted reading of each candidate word in a surviving cluster.
+    let mut voted: Vec<Vec<Option<OcrWord>>> = candidates.iter().map(|r| vec![None; r.words.len()]).collect();
+    let mut merged = Vec::new();
+    let mut covered = vec![0usize; candidates.len()];
+    let mut agreeing = vec![0usize; candidates.len()];
+    for cluster in clusters.iter().filter(|c| c.votes.len() * 2 > candidates.len()) {
+        // Readings in vote order, so the kept frame's and then the earliest come first.
+        let mut tally: Vec<(&str, usize, f32)> = Vec::new();
+        for &(candidate, index) in &cluster.votes {
+            let word = &candidates[candidate].words[index];
+            match tally.iter_mut().find(|(text, _, _)| *text == word.text) {
+                Some(entry) => {
+                    entry.1 += 1;
+                    entry.2 += word.confidence;
+                }
+                None => tally.push((&word.text, 1, word.confidence)),
+            }
+        }
+        // `max_by` keeps the last of equal elements, so the tally is searched from the end.
+        let Some(&(text, count, total)) =
+            tally.iter().rev().max_by(|a, b| a.1.cmp(&b.1).then(a.2.total_cmp(&b.2)))
+        else {
+            continue;
+        };
+        let confidence = total / count as f32;
+        for &(candidate, index) in &cluster.votes {
+            let word = &candidates[candidate].words[index];
+            covered[candidate] += 1;
+            if word.text == text {
+                agreeing[candidate] += 1;
+            }
+            voted[candidate][index] = Some(OcrWord { text: text.to_string(), bbox: word.bbox, confidence });
+        }
+        merged.push(OcrWord { text: text.to_string(), bbox: cluster.bbox, confidence });
+    }
+
+    // `max_by_key` keeps the last of equal elements, so candidates are searched from the end.
+    let winner = (0..candidates.len()).rev().max_by_key(|&c| agreeing[c]).unwrap_or(0);
+    let result = &candidates[winner];
+    if covered[winner] < merged.len() {
+        return OcrFrameResult { frame_index, words: merged, blocks: Vec::new(), strategy: None, timestamp };
+    }
+    let keep: Vec<bool> = voted[winner].iter().map(Option::is_some).collect();
+    let mut merged = result.retain_marked(&keep);
+    merged.words = voted.swap_remove(winner).into_iter().flatten().collect();
+    OcrFrameResult { frame_index, timestamp, ..merged }
+}
+
+fn intersection_over_union(a: (i32, i32, i32, i32), b: (i32, i32, i32, i32)) -> f32 {
+    let width = (a.2.min(b.2) - a.0.max(b.0)).max(0);
+    let height = (a.3.min(b.3) - a.1.max(b.1)).max(0);
+    let intersection = (width * height) as f32;
+    let area = |r: (i32, i32, i32, i32)| ((r.2 - r.0) * (r.3 - r.1)) as f32;
+    let union = area(a) + area(b) - intersection;
+    if union <= 0.0 { 0.0 } else { intersection / union }
+}
+
+/// Maps the first of Tesseract's `+`-separated language codes to a BCP 47 tag,
+/// e.g. `eng+spa` to `en`. Codes without a two-letter equivalent are kept as they are.
+pub fn language_tag(lang: &str) -> String {
+    let code = lang.split('+').next().unwrap_or_default();
+    let tag = match code {
+        "eng" => "en",
+        "spa" => "es",
+        "por" => "pt",
+        "deu" => "de",
+        "fra" => "fr",
+        "ita" => "it",
+        "nld" => "nl",
+        "rus" => "ru",
+        "jpn" => "ja",
+        "jpn_vert" => "ja",
+        "chi_sim" | "chi_sim_vert" => "zh-Hans",
+        "chi_tra" | "chi_tra_vert" => "zh-Hant",
+        "kor" | "kor_vert" => "ko",
+        other => other,
+    };
+    tag.to_string()
+}
+
+/// Splits a Tesseract language string such as `eng+deu+equ` into its codes.
+pub fn language_codes(lang: &str) -> Result<Vec<&str>> {
+    let codes: Vec<&str> = lang.split('+').map(str::trim).collect();
+    ensure!(
+        codes.iter().all(|code| !code.is_empty()),
+        "Invalid OCR language '{}': expected codes joined by '+', e.g. eng+deu",
+        lang
+    );
+    Ok(codes)
+}
+
+/// Checks that `tessdata_dir` holds a `<code>.traineddata` pack for every
+/// language in `lang`, naming all the missing ones otherwise.
+pub fn check_language_packs(lang: &str, tessdata_dir: &Path) -> Result<()> {
+    let missing: Vec<&str> = language_codes(lang)?
+        .into_iter()
+        .filter(|code| !tessdata_dir.join(format!("{}.traineddata", code)).is_file())
+        .collect();
+    if !missing.is_empty() {
+        bail!(
+            "Missing Tesseract language pack(s) {} in {:?}; download the matching .traineddata files there",
+            missing.join(", "),
+            tessdata_dir
+        );
+    }
+    Ok(())
+}
+
+/// Finds the tessdata directory for `config.lang`, downloading the missing
+/// packs into `--tessdata-dir` or the `tesseract-rs` cache when allowed.
+fn resolve_tessdata_dir(config: &crate::Config) -> Result<PathBuf> {
+    let found = find_tessdata_dir(config.tessdata_dir.as_deref(), &config.lang);
+    #[cfg(feature = "download")]
+    if found.is_err() {
+        let Some(dir) = config.tessdata_dir.clone().or_else(tesseract_rs_cache_dir) else {
+            return found;
+        };
+        let missing: Vec<&str> = language_codes(&config.lang)?
+            .into_iter()
+            .filter(|code| !dir.join(format!("{}.traineddata", code)).is_file())
+            .collect();
+        let source = &config.tessdata_source;
+        if config.download_lang || crate::tessdata::confirm_download(&missing, source) {
+            crate::tessdata::download_packs(&missing, source, &dir)?;
+            return find_tessdata_dir(Some(&dir), &config.lang);
+        }
+    }
+    found
+}
+
+/// File name of the Tesseract config file in the `ocr` output directory.
+pub const ENGINE_CONFIG_NAME: &str = "tesseract.config";
+/// Tesseract's `OEM_DEFAULT`, used when a config file is given without `--oem`.
+const DEFAULT_OEM: i32 = 3;
+
+/// Writes the Tesseract variables that only take effect when the engine starts
+/// (the `--user-words` list) to a config file in `dir`, and returns its path,
+/// or `None` if there are none.
+pub fn write_engine_config(user_words: Option<&Path>, dir: &Path) -> Result<Option<PathBuf>> {
+    let Some(user_words) = user_words else {
+        return Ok(None);
+    };
+    let user_words = fs::canonicalize(user_words)
+        .with_context(|| format!("Failed to find the user words file {:?}", user_words))?;
+    let user_words = crate::paths::to_utf8(&user_words).context("Unsupported user words path")?;
+    fs::create_dir_all(dir).with_context(|| format!("Failed to create {:?}", dir))?;
+    let path = dir.join(ENGINE_CONFIG_NAME);
+    fs::write(&path, format!("user_words_file {}\n", user_words))
+        .with_context(|| format!("Failed to write the Tesseract config file {:?}", path))?;
+    Ok(Some(path))
+}
+
+/// Creates a Tesseract engine for `config.lang` with the engine mode, page
+/// segmentation mode, and variables the configuration asks for. `init_config`
+/// is a config file read while the engine starts.
+fn new_engine(tessdata_path: &str, init_config: Option<&str>, config: &crate::Config) -> Result<TesseractAPI> {
     let api = TesseractAPI::new();
-    let tessdata_dir = get_tessdata_dir().context("Could not determine tessdata directory")?;
-    api.init(tessdata_dir.to_str().unwrap(), lang)
-        .context(format!("Failed to initialize Tesseract with language '{}'", lang))?;
+    match (init_config, config.oem) {
+        (Some(file), oem) => {
+            let oem = oem.map_or(DEFAULT_OEM, i32::from);
+            api.init_4(tessdata_path, &config.lang, oem, &[file])
+        }
+        (None, Some(oem)) => api.init_2(tessdata_path, &config.lang, i32::from(oem)),
+        (None, None) => api.init(tessdata_path, &config.lang),
+    }
+    .with_context(|| format!("Failed to initialize Tesseract with language '{}'", config.lang))?;
+    if let Some(psm) = config.psm {
+        api.set_page_seg_mode(TessPageSegMode::from_int(psm as i32))
+            .with_context(|| format!("Failed to set the page segmentation mode {}", psm))?;
+    }
+    let characters = [
+        ("tessedit_char_whitelist", &config.char_whitelist),
+        ("tessedit_char_blacklist", &config.char_blacklist),
+    ];
+    for (name, value) in characters {
+        if let Some(value) = value {
+            api.set_variable(name, value).with_context(|| format!("Failed to set {}", name))?;
+        }
+    }
+    for (name, value) in &config.tess_config {
+        api.set_variable(name, value)
+            .with_context(|| format!("Tesseract rejected the variable {}={}", name, value))?;
+    }
+    Ok(api)
+}
 
+/// What an engine recognized on one image. Lines, paragraphs, and blocks are
+/// given like words, with the text of the whole element, in reading order.
+/// Engines leave out the levels they do not find.
+#[derive(Debug, Default)]
+pub struct Recognition {
+    pub words: Vec<OcrWord>,
+    pub lines: Vec<OcrWord>,
+    pub paragraphs: Vec<OcrWord>,
+    pub blocks: Vec<OcrWord>,
+    /// Whether each of `lines` runs top to bottom, for engines that tell; lines
+    /// without an entry are judged by their shape.
+    pub vertical_lines: Vec<bool>,
+}
+
+impl Recognition {
+    /// Moves the boxes of every element with `f`.
+    pub fn map_boxes(self, f: impl Fn((i32, i32, i32, i32)) -> (i32, i32, i32, i32)) -> Self {
+        let map = |elements: Vec<OcrWord>| -> Vec<OcrWord> {
+            elements.into_iter().map(|e| OcrWord { bbox: f(e.bbox), ..e }).collect()
+        };
+        Self {
+            words: map(self.words),
+            lines: map(self.lines),
+            paragraphs: map(self.paragraphs),
+            blocks: map(self.blocks),
+            vertical_lines: self.vertical_lines,
+        }
+    }
+
+    /// Mean confidence of the words, if there are any.
+    pub fn mean_confidence(&self) -> Option<f32> {
+        (!self.words.is_empty())
+            .then(|| self.words.iter().map(|word| word.confidence).sum::<f32>() / self.words.len() as f32)
+    }
+
+    /// Nests the words into their lines, paragraphs, and blocks. Each element
+    /// goes to the first element of the level above that holds its centre, else
+    /// to the nearest one; a missing paragraph or block level takes the
+    /// elements of the level below. Without lines there is no structure.
+    pub fn structure(&self) -> Vec<OcrBlock> {
+        if self.lines.is_empty() {
+            return Vec::new();
+        }
+        let paragraphs = if self.paragraphs.is_empty() { &self.lines } else { &self.paragraphs };
+        let blocks = if self.blocks.is_empty() { paragraphs } else { &self.blocks };
+
+        let mut line_words: Vec<Vec<usize>> = vec![Vec::new(); self.lines.len()];
+        for (index, word) in self.words.iter().enumerate() {
+            if let Some(line) = parent(word.bbox, &self.lines) {
+                line_words[line].push(index);
+            }
+        }
+        let mut paragraph_lines: Vec<Vec<OcrTextLine>> = vec![Vec::new(); paragraphs.len()];
+        for (i, (line, words)) in self.lines.iter().zip(line_words).enumerate() {
+            if let Some(paragraph) = parent(line.bbox, paragraphs).filter(|_| !words.is_empty()) {
+                let vertical = self.vertical_lines.get(i).copied().unwrap_or(false) || line.is_vertical();
+                paragraph_lines[paragraph].push(OcrTextLine { bbox: line.bbox, words, vertical });
+            }
+        }
+        let mut block_paragraphs: Vec<Vec<OcrParagraph>> = vec![Vec::new(); blocks.len()];
+        for (paragraph, lines) in paragraphs.iter().zip(paragraph_lines) {
+            if let Some(block) = parent(paragraph.bbox, blocks).filter(|_| !lines.is_empty()) {
+                block_paragraphs[block].push(OcrParagraph { bbox: paragraph.bbox, lines });
+            }
+        }
+        blocks
+            .iter()
+            .zip(block_paragraphs)
+            .filter(|(_, paragraphs)| !paragraphs.is_empty())
+            .map(|(block, paragraphs)| OcrBlock { bbox: block.bbox, paragraphs })
+            .collect()
+    }
+}
+
+/// Index of the first of `parents` whose box holds the centre of `bbox`, else
+/// of the one whose centre is nearest.
+fn parent(bbox: (i32, i32, i32, i32), parents: &[OcrWord]) -> Option<usize> {
+    let centre = |(x1, y1, x2, y2): (i32, i32, i32, i32)| ((x1 + x2) / 2, (y1 + y2) / 2);
+    let (x, y) = centre(bbox);
+    parents
+        .iter()
+        .position(|parent| (parent.bbox.0..=parent.bbox.2).contains(&x) && (parent.bbox.1..=parent.bbox.3).contains(&y))
+        .or_else(|| {
+            (0..parents.len()).min_by_key(|&i| {
+                let (px, py) = centre(parents[i].bbox);
+                (px - x).abs() + (py - y).abs()
+            })
+        })
+}
+
+/// A text recognition backend. Engines are shared between the threads
+/// recognizing frames, so each call must stand on its own.
+pub trait OcrEngine: Sync {
+    /// Short name of the engine, used in logs.
+    fn name(&self) -> &'static str;
+
+    /// Recognizes the text on an image, with boxes in its pixels.
+    fn recognize(&self, image: &DynamicImage) -> Result<Recognition>;
+
+    /// Most images the engine recognizes in one go.
+    fn batch_size(&self) -> usize {
+        1
+    }
+
+    /// Recognizes up to [`Self::batch_size`] images in one go, in order. An
+    /// error fails the whole batch.
+    fn recognize_batch(&self, images: &[DynamicImage]) -> Result<Vec<Recognition>> {
+        images.iter().map(|image| self.recognize(image)).collect()
+    }
+
+    /// Names of other settings of the engine worth trying on an image read
+    /// with low confidence.
+    fn alternatives(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Recognizes an image with one of [`Self::alternatives`].
+    fn recognize_alternative(&self, image: &DynamicImage, _alternative: &str) -> Result<Recognition> {
+        self.recognize(image)
+    }
+}
+
+/// Creates the OCR engine for a run. Other backends are chosen here, each
+/// behind its own feature. With a handwriting engine, it reads every frame,
+/// or only those `config.ocr_engine` reads with low confidence. With
+/// `config.upscale`, frames are enlarged by a super-resolution model first.
+pub fn create_engine(config: &crate::Config) -> Result<Box<dyn OcrEngine + '_>> {
+    let engine = reading_engine(config)?;
+    let Some(factor) = config.upscale else {
+        return Ok(engine);
+    };
+    let upscaler = crate::upscale::create_upscaler(config, factor)?;
+    info!("Frames are upscaled {} times with {} before OCR.", factor, upscaler.name());
+    Ok(Box::new(crate::upscale::Upscaled::new(engine, upscaler, factor)))
+}
+
+/// Creates the engine reading the frames: `config.ocr_engine`, or the
+/// handwriting engine alone or as its fallback.
+fn reading_engine(config: &crate::Config) -> Result<Box<dyn OcrEngine + '_>> {
+    #[cfg(any(feature = "onnx", feature = "cloud"))]
+    if let Some(name) = &config.handwriting {
+        let handwriting = engine_named(name, config)?;
+        let Some(below) = config.handwriting_below else {
+            return Ok(handwriting);
+        };
+        info!("Frames read below {}% confidence are read again with {}.", below, handwriting.name());
+        let printed = engine_named(&config.ocr_engine, config)?;
+        return Ok(Box::new(crate::handwriting::HandwritingFallback::new(printed, handwriting, below)));
+    }
+    engine_named(&config.ocr_engine, config)
+}
+
+/// Creates the engine called `name`: an `--ocr-engine` or, with the `onnx`
+/// feature, `onnx` for the handwriting model.
+fn engine_named<'a>(name: &str, config: &'a crate::Config) -> Result<Box<dyn OcrEngine + 'a>> {
+    #[cfg(feature = "onnx")]
+    if name == "onnx" {
+        let (Some(model), Some(dictionary)) = (&config.handwriting_model, &config.handwriting_dict) else {
+            bail!("The onnx handwriting engine needs --handwriting-model and --handwriting-dict");
+        };
+        return Ok(Box::new(crate::handwriting::HandwritingEngine::new(model, dictionary)?));
+    }
+    #[cfg(feature = "onnx")]
+    if name == "paddle" {
+        let (Some(detection), Some(recognition), Some(dictionary)) =
+            (&config.paddle_det_model, &config.paddle_rec_model, &config.paddle_dict)
+        else {
+            bail!("The paddle engine needs --paddle-det-model, --paddle-rec-model, and --paddle-dict");
+        };
+        return Ok(Box::new(crate::paddle::PaddleEngine::new(detection, recognition, dictionary, &config.paddle_devices)?));
+    }
+    #[cfg(feature = "cloud")]
+    if let Some(provider) = crate::cloud_ocr::Provider::from_name(name) {
+        let engine = crate::cloud_ocr::CloudEngine::new(
+            provider,
+            config.cloud_endpoint.as_deref(),
+            config.cloud_batch_size,
+            config.cloud_retries,
+            config.cloud_max_images,
+        )?;
+        return Ok(Box::new(engine));
+    }
+    ensure!(name == "tesseract", "Unknown OCR engine {}", name);
+    Ok(Box::new(TesseractEngine::new(config)?))
+}
+
+/// The Tesseract backend, through the tesseract-rs crate.
+pub struct TesseractEngine<'a> {
+    tessdata_path: String,
+    init_config: Option<String>,
+    config: &'a crate::Config,
+    /// Started engines not in use. A thread takes one for each image and puts
+    /// it back after, so there are never more engines than OCR threads, and
+    /// each is started only once.
+    idle: Mutex<Vec<TesseractAPI>>,
+}
+
+impl<'a> TesseractEngine<'a> {
+    /// Finds the language packs for `config.lang` and writes the engine's
+    /// config file. One engine is started here so that a bad language or
+    /// variable fails the run rather than every frame.
+    pub fn new(config: &'a crate::Config) -> Result<Self> {
+        let tessdata_dir = resolve_tessdata_dir(config)?;
+        info!("Using the Tesseract language packs in {:?}", tessdata_dir);
+        let tessdata_path =
+            crate::paths::to_utf8(&tessdata_dir).context("Unsupported tessdata directory")?.to_string();
+        let init_config = write_engine_config(config.user_words.as_deref(), &config.output_dir.join("ocr"))?;
+        let init_config = init_config
+            .as_deref()
+            .map(|path| crate::paths::to_utf8(path).map(str::to_string))
+            .transpose()
+            .context("Unsupported output path")?;
+        let first = new_engine(&tessdata_path, init_config.as_deref(), config)?;
+        Ok(Self { tessdata_path, init_config, config, idle: Mutex::new(vec![first]) })
+    }
+
+    /// Takes an idle engine, or starts one when all are in use.
+    fn take_engine(&self) -> Result<TesseractAPI> {
+        let idle = self.idle.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).pop();
+        match idle {
+            Some(api) => Ok(api),
+            None => new_engine(&self.tessdata_path, self.init_config.as_deref(), self.config),
+        }
+    }
+}
+
+impl OcrEngine for TesseractEngine<'_> {
+    fn name(&self) -> &'static str {
+        "tesseract"
+    }
+
+    fn recognize(&self, image: &DynamicImage) -> Result<Recognition> {
+        self.recognize_in_mode(image, None)
+    }
+
+    /// The page segmentation modes of [`RETRY_PSMS`] other than the configured one, as `psm-N`.
+    fn alternatives(&self) -> Vec<String> {
+        let configured = self.config.psm.unwrap_or(DEFAULT_PSM);
+        RETRY_PSMS.iter().filter(|&&psm| psm != configured).map(|psm| format!("psm-{}", psm)).collect()
+    }
+
+    fn recognize_alternative(&self, image: &DynamicImage, alternative: &str) -> Result<Recognition> {
+        let psm = alternative
+            .strip_prefix("psm-")
+            .and_then(|psm| psm.parse().ok())
+            .with_context(|| format!("Unknown Tesseract alternative {}", alternative))?;
+        self.recognize_in_mode(image, Some(psm))
+    }
+}
+
+impl TesseractEngine<'_> {
+    /// Recognizes an image, in page segmentation mode `psm` if given and in
+    /// the configured one otherwise.
+    fn recognize_in_mode(&self, image: &DynamicImage, psm: Option<u8>) -> Result<Recognition> {
+        // Engines are reused rather than cloned, because cloning drops the
+        // engine and segmentation modes.
+        let api = self.take_engine()?;
+        if let Some(psm) = psm {
+            api.set_page_seg_mode(TessPageSegMode::from_int(psm as i32))
+                .with_context(|| format!("Failed to set the page segmentation mode {}", psm))?;
+        }
+        let converted;
+        let (raw, bytes_per_pixel) = match image {
+            DynamicImage::ImageLuma8(gray) => (gray.as_raw(), 1),
+            DynamicImage::ImageRgb8(rgb) => (rgb.as_raw(), 3),
+            other => {
+                converted = other.to_rgb8();
+                (converted.as_raw(), 3)
+            }
+        };
+        let (width, height) = (image.width() as i32, image.height() as i32);
+        api.set_image(raw, width, height, bytes_per_pixel, width * bytes_per_pixel)
+            .context("Tesseract failed to set the image")?;
+        api.recognize().context("Tesseract failed to recognize the text")?;
+        let (lines, vertical_lines) = read_lines(&api)?;
+        let recognition = Recognition {
+            words: read_level(&api, TessPageIteratorLevel::RIL_WORD)?,
+            lines,
+            paragraphs: read_level(&api, TessPageIteratorLevel::RIL_PARA)?,
+            blocks: read_level(&api, TessPageIteratorLevel::RIL_BLOCK)?,
+            vertical_lines,
+        };
+        // Only engines that got this far are put back; one that failed is
+        // dropped, in case it was left in a bad state.
+        api.clear().context("Tesseract failed to clear the image")?;
+        if psm.is_some() {
+            let configured = self.config.psm.unwrap_or(DEFAULT_PSM);
+            api.set_page_seg_mode(TessPageSegMode::from_int(configured as i32))
+                .context("Failed to restore the page segmentation mode")?;
+        }
+        self.idle.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).push(api);
+        Ok(recognition)
+    }
+}
+
+/// Reads the text, box, and confidence of every non-empty element of a level
+/// of the last recognition.
+fn read_level(api: &TesseractAPI, level: TessPageIteratorLevel) -> Result<Vec<OcrWord>> {
+    let iter = api.get_iterator().context("Failed to get the Tesseract result iterator")?;
+    let mut elements = Vec::new();
+    loop {
+        if let (Ok(text), Ok(bbox), Ok(confidence)) =
+            (iter.get_utf8_text(level), iter.get_bounding_box(level), iter.confidence(level))
+        {
+            let text = text.trim().to_string();
+            if !text.is_empty() {
+                elements.push(OcrWord { text, bbox, confidence });
+            }
+        }
+        if !iter.next(level).unwrap_or(false) {
+            break;
+        }
+    }
+    Ok(elements)
+}
+
+/// Reads the lines of the last recognition like [`read_level`], with whether
+/// each runs top to bottom, as the vertical language packs such as `jpn_vert`
+/// and `chi_tra_vert` read them.
+fn read_lines(api: &TesseractAPI) -> Result<(Vec<OcrWord>, Vec<bool>)> {
+    let level = TessPageIteratorLevel::RIL_TEXTLINE;
+    let iter = api.get_iterator().context("Failed to get the Tesseract result iterator")?;
+    // The layout iterator walks the same lines, and knows their direction.
+    let layout = api.get_page_iterator().context("Failed to get the Tesseract page iterator")?;
+    let (mut lines, mut vertical) = (Vec::new(), Vec::new());
+    loop {
+        if let (Ok(text), Ok(bbox), Ok(confidence)) =
+            (iter.get_utf8_text(level), iter.get_bounding_box(level), iter.confidence(level))
+        {
+            let text = text.trim().to_string();
+            if !text.is_empty() {
+                let direction = layout.orientation().ok().map(|(_, direction, _, _)| direction as i32);
+                vertical.push(direction == Some(WRITING_DIRECTION_TOP_TO_BOTTOM));
+                lines.push(OcrWord { text, bbox, confidence });
+            }
+        }
+        let more = iter.next(level).unwrap_or(false);
+        if !more || !layout.next(level) {
+            break;
+        }
+    }
+    Ok((lines, vertical))
+}
+
+/// Recognizes the words on each frame in parallel with `engine`. `masks[i]`,
+/// if any, is painted over `frames[i]` first, and the `preprocess` steps are
+/// applied before recognition; boxes are given in the pixels of the frame.
+/// Frames whose words have a mean confidence below `retry_below` are read
+/// again by [`retry`]. Frames that fail are skipped with a warning.
+pub fn recognize_frames(
+    engine: &dyn OcrEngine,
+    frames: &[&ImageBuffer<Rgb<u8>, Vec<u8>>],
+    masks: &[Option<&IgnoreMask>],
+    preprocess: &[String],
+    retry_below: Option<f32>,
+) -> Vec<OcrFrameResult> {
     let pb = ProgressBar::new(frames.len() as u64);
     pb.set_style(
         ProgressStyle::default_bar()
-            .template("{spinner:.green} Running OCR [{bar:40.cyan/blue}] {pos}/{len} ({eta})")
+            .template(&format!("{{spinner:.green}} {} [{{bar:40.cyan/blue}}] {{pos}}/{{len}} ({{eta}})", tr(Msg::RunningOcr)))
             .unwrap()
             .progress_chars("##-"),
     );
 
-    let results: Vec<OcrFrameResult> = frames
-        .par_iter()
-        .enumerate()
-        .filter_map(|(index, frame)| {
-            pb.inc(1);
-            let api_clone = api.clone();
-            if let Err(e) = api_clone.set_image(
-                frame.as_raw(),
-                frame.width() as i32,
-                frame.height() as i32,
-                3,
-                (frame.width() * 3) as i32,
-            ) {
-                warn!("Tesseract failed to set image for frame {}: {}. Skipping.", index, e);
-                return None;
-            }
-            if api_clone.recognize().is_err() {
-                 warn!("Tesseract failed to recognize text for frame {}. Skipping.", index);
-                 return None;
-            }
-            let iter = match api_clone.get_iterator() {
-                Ok(iter) => iter,
-                Err(_) => {
-                    warn!("Failed to get result iterator for frame {}. Skipping.", index);
-                    return None;
+    let indices: Vec<usize> = (0..frames.len()).collect();
+    let results: Vec<OcrFrameResult> = indices
+        .par_chunks(engine.batch_size().max(1))
+        .flat_map_iter(|batch| {
+            let (images, skews): (Vec<DynamicImage>, Vec<f32>) = batch
+                .iter()
+                .map(|&index| prepare_image(frames[index], masks.get(index).copied().flatten(), preprocess))
+                .unzip();
+            let recognitions = engine.recognize_batch(&images).and_then(|recognitions| {
+                ensure!(recognitions.len() == batch.len(), "{} results for {} images", recognitions.len(), batch.len());
+                Ok(recognitions)
+            });
+            pb.inc(batch.len() as u64);
+            let recognitions = match recognitions {
+                Ok(recognitions) => recognitions,
+                Err(e) => {
+                    for index in batch {
+                        warn!("{} failed on frame {}: {:#}. Skipping.", engine.name(), index, e);
+                    }
+                    Vec::new()
                 }
             };
-            let mut words = Vec::new();
-            while iter.next(TessPageIteratorLevel::RIL_WORD).unwrap_or(false) {
-                let word_text = match iter.get_utf8_text(TessPageIteratorLevel::RIL_WORD) {
-                    Ok(text) => text.trim().to_string(),
-                    Err(_) => continue,
+            let read = recognitions.into_iter().zip(images).zip(batch).zip(skews);
+            read.map(move |(((recognition, image), &index), skew)| {
+                let low = |recognition: &Recognition| {
+                    retry_below.is_some_and(|threshold| recognition.mean_confidence().is_some_and(|c| c < threshold))
                 };
-                if !word_text.is_empty() {
-                    if let (Ok(bbox), Ok(confidence)) = (
-                        iter.get_bounding_box(TessPageIteratorLevel::RIL_WORD),
-                        iter.confidence(TessPageIteratorLevel::RIL_WORD),
-                    ) {
-                        words.push(OcrWord { text: word_text, bbox, confidence });
+                let (recognition, strategy) = match recognition {
+                    recognition if low(&recognition) => {
+                        let (recognition, strategy) = retry(engine, &image, recognition);
+                        (recognition, Some(strategy))
                     }
-                }
-            }
-            Some(OcrFrameResult { frame_index: index, words })
+                    recognition => (recognition, None),
+                };
+                let (width, height) = frames[index].dimensions();
+                let recognition = recognition.map_boxes(|bbox| preprocess::unrotate_bbox(bbox, skew, width, height));
+                let blocks = recognition.structure();
+                OcrFrameResult { frame_index: index, words: recognition.words, blocks, strategy, timestamp: None }
+            })
         })
         .collect();
+    let retried: Vec<&str> = results.iter().filter_map(|result| result.strategy.as_deref()).collect();
+    if !retried.is_empty() {
+        let improved = retried.iter().filter(|&&strategy| strategy != DEFAULT_STRATEGY).count();
+        info!("Retried {} low-confidence frames; other settings read {} of them better.", retried.len(), improved);
+    }
 
-    pb.finish_with_message("OCR complete");
-    info!("Successfully performed detailed OCR on {} frames.", results.len());
+    pb.finish_with_message(tr(Msg::OcrComplete));
+    results
+}
+
+/// Reads an image recognized with low confidence again with the engine's
+/// [`OcrEngine::alternatives`], inverted, and scaled to twice its size, and
+/// keeps the reading whose words have the highest mean confidence among those
+/// finding at least half as many words as the first. Returns it with the name
+/// of its settings, [`DEFAULT_STRATEGY`] when the first reading stays.
+pub fn retry(engine: &dyn OcrEngine, image: &DynamicImage, first: Recognition) -> (Recognition, String) {
+    let min_words = first.words.len().div_ceil(2);
+    let mut best = (first, DEFAULT_STRATEGY.to_string());
+    let mut strategies = engine.alternatives();
+    strategies.extend(["invert", "scale-2x"].map(str::to_string));
+    for strategy in strategies {
+        let attempt = match strategy.as_str() {
+            "invert" => {
+                let mut inverted = image.clone();
+                inverted.invert();
+                engine.recognize(&inverted)
+            }
+            "scale-2x" => {
+                let scaled = image.resize_exact(image.width() * 2, image.height() * 2, FilterType::CatmullRom);
+                engine.recognize(&scaled).map(|recognition| {
+                    recognition.map_boxes(|(x1, y1, x2, y2)| (x1 / 2, y1 / 2, (x2 + 1) / 2, (y2 + 1) / 2))
+                })
+            }
+            alternative => engine.recognize_alternative(image, alternative),
+        };
+        match attempt {
+            Ok(recognition)
+                if recognition.words.len() >= min_words
+                    && recognition.mean_confidence() > best.0.mean_confidence() =>
+            {
+                best = (recognition, strategy)
+            }
+            Ok(_) => {}
+            Err(e) => warn!("{} failed to retry with {}: {:#}", engine.name(), strategy, e),
+        }
+    }
+    best
+}
+
+/// Runs [`recognize_frames`] on `threads` threads, or on one per CPU core
+/// when `None`.
+pub fn recognize_on_threads(
+    engine: &dyn OcrEngine,
+    frames: &[&ImageBuffer<Rgb<u8>, Vec<u8>>],
+    masks: &[Option<&IgnoreMask>],
+    preprocess: &[String],
+    retry_below: Option<f32>,
+    threads: Option<usize>,
+) -> Result<Vec<OcrFrameResult>> {
+    let Some(threads) = threads else {
+        return Ok(recognize_frames(engine, frames, masks, preprocess, retry_below));
+    };
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .thread_name(|i| format!("ocr-{}", i))
+        .build()
+        .context("Failed to start the OCR threads")?;
+    info!("Running OCR on {} threads.", threads);
+    Ok(pool.install(|| recognize_frames(engine, frames, masks, preprocess, retry_below)))
+}
 
-    // Save results to a JSON file
+/// The image an engine is given for a frame: the frame with `mask` painted
+/// over it, then preprocessed, with the angle it was deskewed by.
+fn prepare_image(
+    frame: &ImageBuffer<Rgb<u8>, Vec<u8>>,
+    mask: Option<&IgnoreMask>,
+    preprocess: &[String],
+) -> (DynamicImage, f32) {
+    // Ignored regions take the page's background colour, so they read as empty paper.
+    let masked = mask.map(|mask| mask.apply(frame, Rgb(crate::figures::border_colour(frame))));
+    let frame = masked.as_ref().unwrap_or(frame);
+    if preprocess.is_empty() {
+        (DynamicImage::ImageRgb8(frame.clone()), 0.0)
+    } else {
+        let prepared = preprocess::prepare(frame, preprocess);
+        (DynamicImage::ImageLuma8(prepared.image), prepared.skew)
+    }
+}
+
+/// Saves the OCR results to `ocr/ocr_results.json` and/or `ocr/ocr_results.csv`
+/// in `output_dir`, as selected by `selection` (`json`, `csv`, or `both`).
+pub fn save_report(results: &[OcrFrameResult], output_dir: &Path, selection: &str) -> Result<()> {
     let ocr_dir = output_dir.join("ocr");
     fs::create_dir_all(&ocr_dir).context("Failed to create ocr output directory")?;
-    let report_path = ocr_dir.join("ocr_results.json");
-    
-    let report_json =
-        serde_json::to_string_pretty(&results).context("Failed to serialize OCR results")?;
-    
-    fs::write(&report_path, report_json)
-        .with_context(|| format!("Failed to write OCR report to {:?}", report_path))?;
-    
-    info!("OCR results saved to {:?}", report_path);
+    let formats: &[&str] = match selection {
+        "csv" => &["csv"],
+        "both" => &["json", "csv"],
+        _ => &["json"],
+    };
 
-    Ok(results)
-}
\ No newline at end of file
+    for &format in formats {
+        let report_path = ocr_dir.join(format!("ocr_results.{}", format));
+        let report = match format {
+            "csv" => words_csv(results),
+            _ => serde_json::to_string_pretty(&results).context("Failed to serialize OCR results")?,
+        };
+        fs::write(&report_path, report)
+            .with_context(|| format!("Failed to write OCR report to {:?}", report_path))?;
+        info!("OCR results saved to {:?}", report_path);
+    }
+    Ok(())
+}
+
+/// Formats the results as CSV with one row per word. The timestamp, in seconds,
+/// is left empty when it is unknown.
+pub fn words_csv(results: &[OcrFrameResult]) -> String {
+    let mut csv = String::from("frame_index,timestamp,text,x1,y1,x2,y2,confidence\n");
+    for result in results {
+        let timestamp = result.timestamp.map(|seconds| format!("{:.3}", seconds)).unwrap_or_default();
+        for word in &result.words {
+            let (x1, y1, x2, y2) = word.bbox;
+            csv.push_str(&format!(
+                "{},{},{},{},{},{},{},{:.2}\n",
+                result.frame_index,
+                timestamp,
+                csv_field(&word.text),
+                x1,
+                y1,
+                x2,
+                y2,
+                word.confidence
+            ));
+        }
+    }
+    csv
+}
+
+/// Quotes a CSV field when it contains a separator, quote, or line break.
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
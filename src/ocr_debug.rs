@@ -0,0 +1,72 @@
+//! OCR Debug Module
+//!
+//! Handles the optional debug artifacts written next to the OCR report: the
+//! text of every kept frame as a plain text file, and the frame with each word
+//! box drawn over it, coloured from red for unsure words to green for sure
+//! ones, so the effect of the language, page segmentation, and preprocessing
+//! settings can be seen frame by frame.
+
+use crate::document_builder::Page;
+use crate::layout;
+use anyhow::{Context, Result};
+use image::{ImageBuffer, Rgb};
+use rayon::prelude::*;
+use std::fs;
+use std::path::Path;
+
+/// Folder of the debug artifacts, relative to the output directory.
+pub const DEBUG_DIR: &str = "ocr/debug";
+/// Thickness of the box outlines, in pixels.
+const OUTLINE: i32 = 2;
+/// Share of the box colour mixed into the frame inside each box.
+const FILL_OPACITY: f32 = 0.25;
+
+/// Colour of a word box with the given confidence (0-100): red below 50,
+/// through yellow, to green at 100.
+pub fn confidence_colour(confidence: f32) -> Rgb<u8> {
+    let share = (confidence / 100.0).clamp(0.0, 1.0);
+    let red = ((1.0 - share) * 2.0).min(1.0);
+    let green = (share * 2.0).min(1.0);
+    Rgb([(red * 255.0).round() as u8, (green * 255.0).round() as u8, 0])
+}
+
+/// Draws the word boxes of a page over a copy of its frame.
+pub fn overlay(page: &Page) -> ImageBuffer<Rgb<u8>, Vec<u8>> {
+    let mut image = page.image.clone();
+    let (width, height) = (image.width() as i32, image.height() as i32);
+    for word in page.ocr.iter().flat_map(|ocr| &ocr.words) {
+        let colour = confidence_colour(word.confidence);
+        let (x1, y1, x2, y2) = word.bbox;
+        for y in y1.max(0)..y2.min(height) {
+            for x in x1.max(0)..x2.min(width) {
+                let pixel = image.get_pixel_mut(x as u32, y as u32);
+                let edge = x - x1 < OUTLINE || x2 - 1 - x < OUTLINE || y - y1 < OUTLINE || y2 - 1 - y < OUTLINE;
+                if edge {
+                    *pixel = colour;
+                } else {
+                    for (channel, tint) in pixel.0.iter_mut().zip(colour.0) {
+                        *channel = (*channel as f32 * (1.0 - FILL_OPACITY) + tint as f32 * FILL_OPACITY).round() as u8;
+                    }
+                }
+            }
+        }
+    }
+    image
+}
+
+/// Writes `page_NNN.txt` with the OCR text and `page_NNN_overlay.png` with the
+/// word boxes of every page to [`DEBUG_DIR`] in `output_dir`. Pages that were
+/// not recognized get an empty text file and their plain frame.
+pub fn write_debug(pages: &[Page], output_dir: &Path) -> Result<()> {
+    let debug_dir = output_dir.join(DEBUG_DIR);
+    fs::create_dir_all(&debug_dir).context("Failed to create OCR debug directory")?;
+    pages.par_iter().enumerate().try_for_each(|(i, page)| -> Result<()> {
+        let text_path = debug_dir.join(format!("page_{:03}.txt", i + 1));
+        let text = page.ocr.map(layout::page_text).unwrap_or_default();
+        fs::write(&text_path, text).with_context(|| format!("Failed to write OCR text to {:?}", text_path))?;
+        let overlay_path = debug_dir.join(format!("page_{:03}_overlay.png", i + 1));
+        overlay(page)
+            .save(&overlay_path)
+            .with_context(|| format!("Failed to save OCR overlay to {:?}", overlay_path))
+    })
+}
@@ -0,0 +1,94 @@
+//! Overlay Module
+//!
+//! Handles drawing short labels, such as timestamps, onto frames with a small
+//! built-in bitmap font, so no font files or rendering libraries are needed.
+
+use image::{ImageBuffer, Rgb};
+
+const GLYPH_WIDTH: u32 = 5;
+const GLYPH_HEIGHT: u32 = 7;
+
+/// Returns the 5x7 bitmap for a character, one byte per row, most significant
+/// of the low five bits on the left. Unsupported characters render as blanks.
+fn glyph(c: char) -> [u8; 7] {
+    match c {
+        '0' => [0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E],
+        '1' => [0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        '2' => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F],
+        '3' => [0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E],
+        '4' => [0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02],
+        '5' => [0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E],
+        '6' => [0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E],
+        '7' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08],
+        '8' => [0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E],
+        '9' => [0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C],
+        ':' => [0x00, 0x0C, 0x0C, 0x00, 0x0C, 0x0C, 0x00],
+        '.' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x0C],
+        '-' => [0x00, 0x00, 0x00, 0x1F, 0x00, 0x00, 0x00],
+        '/' => [0x01, 0x01, 0x02, 0x04, 0x08, 0x10, 0x10],
+        _ => [0; 7],
+    }
+}
+
+/// Draws `text` as white characters on a black box in the bottom-left corner.
+///
+/// The glyphs are scaled with the image height so the label stays readable on
+/// both small and large frames. Only digits and `:.-/` are supported.
+pub fn draw_label(image: &mut ImageBuffer<Rgb<u8>, Vec<u8>>, text: &str) {
+    let scale = label_scale(image.height());
+    let label = render_label(text, scale);
+    let padding = scale * 2;
+    if label.width() + padding > image.width() || label.height() + padding > image.height() {
+        return;
+    }
+    let origin_y = image.height() - label.height() - padding;
+    image::imageops::replace(image, &label, padding as i64, origin_y as i64);
+}
+
+/// Glyph scale that keeps labels readable on a frame of the given height.
+pub fn label_scale(image_height: u32) -> u32 {
+    (image_height / 160).max(2)
+}
+
+/// Renders `text` as white characters on a black box with the glyphs scaled by `scale`.
+pub fn render_label(text: &str, scale: u32) -> ImageBuffer<Rgb<u8>, Vec<u8>> {
+    let padding = scale * 2;
+    let advance = (GLYPH_WIDTH + 1) * scale;
+    let box_width = advance * text.chars().count() as u32 + padding * 2 - scale;
+    let box_height = GLYPH_HEIGHT * scale + padding * 2;
+    let mut label = ImageBuffer::from_pixel(box_width, box_height, Rgb([0, 0, 0]));
+
+    for (i, c) in text.chars().enumerate() {
+        let glyph_x = padding + i as u32 * advance;
+        for (row, bits) in glyph(c).iter().enumerate() {
+            for col in 0..GLYPH_WIDTH {
+                if bits & (0x10 >> col) != 0 {
+                    fill_rect(
+                        &mut label,
+                        glyph_x + col * scale,
+                        padding + row as u32 * scale,
+                        scale,
+                        scale,
+                        Rgb([255, 255, 255]),
+                    );
+                }
+            }
+        }
+    }
+    label
+}
+
+fn fill_rect(
+    image: &mut ImageBuffer<Rgb<u8>, Vec<u8>>,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+    color: Rgb<u8>,
+) {
+    for py in y..(y + height).min(image.height()) {
+        for px in x..(x + width).min(image.width()) {
+            image.put_pixel(px, py, color);
+        }
+    }
+}
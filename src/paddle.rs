@@ -0,0 +1,380 @@
+//! Paddle Module
+//!
+//! Handles text recognition with PaddleOCR models run by ONNX Runtime, for the
+//! material Tesseract reads poorly: small, blurry video text and stylized slide
+//! fonts. A detection model finds the text lines on a frame, a recognition
+//! model reads each line, and the characters it reads are grouped into words
+//! placed by where the model saw them. Frames are read in batches, the lines of
+//! a batch going through the recognition model together, and batches are
+//! spread across the CUDA devices the models are loaded on. ONNX Runtime itself
+//! is loaded when the engine starts, from `ORT_DYLIB_PATH` or the library
+//! search path.
+
+use crate::ocr::{OcrEngine, OcrWord, Recognition};
+use anyhow::{Context, Result, anyhow, ensure};
+use image::imageops::{self, FilterType};
+use image::{DynamicImage, RgbImage};
+use ort::execution_providers::CUDAExecutionProvider;
+use ort::session::Session;
+use ort::value::Tensor;
+use std::collections::VecDeque;
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Longest side, in pixels, frames are scaled down to for detection.
+const DETECTION_MAX_SIDE: u32 = 960;
+/// The sides of the detection input are multiples of this.
+const DETECTION_STRIDE: u32 = 32;
+/// Probability from which a pixel of the detection map is text.
+const TEXT_THRESHOLD: f32 = 0.3;
+/// Mean probability a detected region must reach to be read.
+const BOX_THRESHOLD: f32 = 0.6;
+/// How far a detected region is grown, as a multiple of its area over its
+/// perimeter; the detection model marks a shrunk core of each line.
+const UNCLIP_RATIO: f32 = 1.5;
+/// Shortest side, in detection map pixels, a region may have.
+const MIN_REGION_SIDE: u32 = 3;
+/// Height text lines are scaled to for recognition.
+const RECOGNITION_HEIGHT: u32 = 48;
+/// Widest a scaled line may be; longer lines are squeezed.
+const RECOGNITION_MAX_WIDTH: u32 = 3200;
+/// Frames recognized in one go; the text lines found on them are read together.
+const FRAME_BATCH: usize = 8;
+/// Most text lines read in one run of the recognition model.
+const RECOGNITION_BATCH: usize = 32;
+/// Per-channel mean and standard deviation the detection model expects.
+const DETECTION_MEAN: [f32; 3] = [0.485, 0.456, 0.406];
+const DETECTION_STD: [f32; 3] = [0.229, 0.224, 0.225];
+
+/// A character read by the recognition model.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedChar {
+    pub text: String,
+    /// Time step of the model's output at which it was read; steps run from
+    /// the left of the line to its right.
+    pub step: usize,
+    /// Probability of the character, from 0 to 1.
+    pub confidence: f32,
+}
+
+/// Reads a PaddleOCR character dictionary: one character per line, in the
+/// order of the model's classes after the CTC blank. A space is added as the
+/// last class, as PaddleOCR models are trained with.
+pub fn load_dictionary(path: &Path) -> Result<Vec<String>> {
+    let text = fs::read_to_string(path).with_context(|| format!("Failed to read the dictionary {:?}", path))?;
+    let mut dictionary: Vec<String> = text.lines().map(str::to_string).collect();
+    ensure!(!dictionary.is_empty(), "The dictionary {:?} is empty", path);
+    dictionary.push(" ".to_string());
+    Ok(dictionary)
+}
+
+/// Size a frame is scaled to for detection: at most [`DETECTION_MAX_SIDE`]
+/// on its longer side, each side rounded to a multiple of [`DETECTION_STRIDE`].
+pub fn detection_size(width: u32, height: u32) -> (u32, u32) {
+    let scale = (DETECTION_MAX_SIDE as f32 / width.max(height) as f32).min(1.0);
+    let side = |length: u32| {
+        let scaled = (length as f32 * scale).round() as u32;
+        ((scaled + DETECTION_STRIDE / 2) / DETECTION_STRIDE).max(1) * DETECTION_STRIDE
+    };
+    (side(width), side(height))
+}
+
+/// Finds the text lines on a detection map of `width` by `height`
+/// probabilities, as boxes (x1, y1, x2, y2) in map pixels, the second corner
+/// excluded, from top to bottom and left to right.
+///
+/// Every connected region of text pixels whose mean probability is high
+/// enough becomes a box, grown by [`UNCLIP_RATIO`] to cover the whole line.
+pub fn text_boxes(map: &[f32], width: u32, height: u32) -> Vec<(u32, u32, u32, u32)> {
+    let is_text = |i: usize| map[i] > TEXT_THRESHOLD;
+    let mut seen = vec![false; map.len().min((width * height) as usize)];
+    let mut boxes = Vec::new();
+    for start in 0..seen.len() {
+        if seen[start] || !is_text(start) {
+            continue;
+        }
+        seen[start] = true;
+        let (mut x1, mut y1, mut x2, mut y2) = (width, height, 0, 0);
+        let (mut count, mut sum) = (0usize, 0f32);
+        let mut queue = VecDeque::from([start]);
+        while let Some(i) = queue.pop_front() {
+            let (x, y) = (i as u32 % width, i as u32 / width);
+            (x1, y1, x2, y2) = (x1.min(x), y1.min(y), x2.max(x + 1), y2.max(y + 1));
+            count += 1;
+            sum += map[i];
+            let neighbours = [
+                (x > 0).then(|| i - 1),
+                (x + 1 < width).then(|| i + 1),
+                (y > 0).then(|| i - width as usize),
+                (y + 1 < height).then(|| i + width as usize),
+            ];
+            for next in neighbours.into_iter().flatten() {
+                if !seen[next] && is_text(next) {
+                    seen[next] = true;
+                    queue.push_back(next);
+                }
+            }
+        }
+        let (region_w, region_h) = (x2 - x1, y2 - y1);
+        if region_w < MIN_REGION_SIDE || region_h < MIN_REGION_SIDE || sum / (count as f32) < BOX_THRESHOLD {
+            continue;
+        }
+        let distance = (region_w * region_h) as f32 * UNCLIP_RATIO / (2 * (region_w + region_h)) as f32;
+        let grow = distance.round() as u32;
+        boxes.push((x1.saturating_sub(grow), y1.saturating_sub(grow), (x2 + grow).min(width), (y2 + grow).min(height)));
+    }
+    boxes.sort_by_key(|&(x1, y1, _, _)| (y1, x1));
+    boxes
+}
+
+/// Decodes the output of the recognition model, `classes` probabilities per
+/// time step, by taking the likeliest class at each step, merging repeats,
+/// and dropping the CTC blank (class 0).
+pub fn ctc_decode(probabilities: &[f32], classes: usize, dictionary: &[String]) -> Vec<DecodedChar> {
+    let mut chars = Vec::new();
+    let mut previous = 0;
+    for (step, row) in probabilities.chunks_exact(classes.max(1)).enumerate() {
+        let Some((best, &confidence)) = row.iter().enumerate().max_by(|a, b| a.1.total_cmp(b.1)) else {
+            continue;
+        };
+        if best != 0
+            && best != previous
+            && let Some(text) = dictionary.get(best - 1)
+        {
+            chars.push(DecodedChar { text: text.clone(), step, confidence });
+        }
+        previous = best;
+    }
+    chars
+}
+
+/// Groups the characters read on a line into words at its spaces. The line's
+/// box is `line`, in frame pixels, and each time step of the model covers
+/// `step_width` frame pixels from its left edge. Confidences are percentages,
+/// like Tesseract's.
+pub fn group_words(chars: &[DecodedChar], line: (i32, i32, i32, i32), step_width: f32) -> Vec<OcrWord> {
+    let (x1, y1, x2, y2) = line;
+    chars
+        .split(|c| c.text.trim().is_empty())
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let (first, last) = (&word[0], &word[word.len() - 1]);
+            let left = x1 + (first.step as f32 * step_width).floor() as i32;
+            let right = x1 + ((last.step + 1) as f32 * step_width).ceil() as i32;
+            let confidence = word.iter().map(|c| c.confidence).sum::<f32>() / word.len() as f32 * 100.0;
+            OcrWord {
+                text: word.iter().map(|c| c.text.as_str()).collect(),
+                bbox: (left.min(x2), y1, right.min(x2), y2),
+                confidence,
+            }
+        })
+        .collect()
+}
+
+/// Orders the text lines of a batch of frames by `widths`, the widths they are
+/// scaled to for recognition, and splits them into runs of the recognition
+/// model of at most `size` lines, so the lines read together need little
+/// padding to the widest of them. Returns the indices of the lines in each run.
+pub fn recognition_batches(widths: &[u32], size: usize) -> Vec<Vec<usize>> {
+    let mut order: Vec<usize> = (0..widths.len()).collect();
+    order.sort_by_key(|&i| widths[i]);
+    order.chunks(size.max(1)).map(<[usize]>::to_vec).collect()
+}
+
+/// Frame pixels each time step of the recognition model covers, for a line
+/// `line_width` pixels wide scaled to `input_width` and padded to the
+/// `batch_width` of the widest line read with it, when the model outputs
+/// `steps` time steps.
+pub fn step_width(line_width: u32, input_width: u32, batch_width: u32, steps: usize) -> f32 {
+    batch_width as f32 / steps.max(1) as f32 * line_width as f32 / input_width.max(1) as f32
+}
+
+/// The detection and recognition models, loaded on one device.
+struct Models {
+    detector: Session,
+    recognizer: Session,
+}
+
+/// The PaddleOCR backend: a detection and a recognition model in ONNX format
+/// with the recognition model's character dictionary, loaded once per device.
+/// Each batch of frames runs on the next device in turn, and the text lines
+/// found on its frames are read together, up to 32 in one run of the model.
+pub struct PaddleEngine {
+    devices: Vec<Mutex<Models>>,
+    next: AtomicUsize,
+    dictionary: Vec<String>,
+}
+
+impl PaddleEngine {
+    /// Loads the models and dictionary, starting ONNX Runtime if needed. The
+    /// models are loaded on each CUDA device of `devices`, or on the CPU when
+    /// it is empty.
+    pub fn new(detection_model: &Path, recognition_model: &Path, dictionary: &Path, devices: &[i32]) -> Result<Self> {
+        let load = |path: &Path, device: Option<i32>| -> Result<Session> {
+            let providers: Vec<_> = device
+                .map(|id| CUDAExecutionProvider::default().with_device_id(id).build().error_on_failure())
+                .into_iter()
+                .collect();
+            let session = Session::builder()?.with_execution_providers(providers)?.commit_from_file(path);
+            session.with_context(|| match device {
+                Some(id) => format!("Failed to load the ONNX model {:?} on CUDA device {}", path, id),
+                None => format!("Failed to load the ONNX model {:?}", path),
+            })
+        };
+        let devices: Vec<Option<i32>> =
+            if devices.is_empty() { vec![None] } else { devices.iter().copied().map(Some).collect() };
+        let devices = devices
+            .into_iter()
+            .map(|device| {
+                let detector = load(detection_model, device)?;
+                let recognizer = load(recognition_model, device)?;
+                Ok(Mutex::new(Models { detector, recognizer }))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { devices, next: AtomicUsize::new(0), dictionary: load_dictionary(dictionary)? })
+    }
+}
+
+/// Finds the text lines on an image, as boxes in its pixels.
+fn detect(detector: &mut Session, image: &RgbImage) -> Result<Vec<(i32, i32, i32, i32)>> {
+    let (width, height) = image.dimensions();
+    let (input_w, input_h) = detection_size(width, height);
+    let resized = imageops::resize(image, input_w, input_h, FilterType::Triangle);
+    let plane = (input_w * input_h) as usize;
+    let mut data = vec![0f32; 3 * plane];
+    for (i, pixel) in resized.pixels().enumerate() {
+        for c in 0..3 {
+            data[c * plane + i] = (pixel[c] as f32 / 255.0 - DETECTION_MEAN[c]) / DETECTION_STD[c];
+        }
+    }
+    let input = Tensor::from_array(([1usize, 3, input_h as usize, input_w as usize], data))?;
+    let outputs = detector.run(ort::inputs![input])?;
+    let (shape, map) = outputs[0].try_extract_tensor::<f32>()?;
+    ensure!(shape.len() >= 2, "Unexpected detection output of shape {:?}", &shape[..]);
+    let (map_w, map_h) = (shape[shape.len() - 1] as u32, shape[shape.len() - 2] as u32);
+    ensure!(map.len() >= (map_w * map_h) as usize, "Unexpected detection output of shape {:?}", &shape[..]);
+
+    let (sx, sy) = (width as f32 / map_w as f32, height as f32 / map_h as f32);
+    Ok(text_boxes(map, map_w, map_h)
+        .into_iter()
+        .map(|(x1, y1, x2, y2)| {
+            (
+                (x1 as f32 * sx) as i32,
+                (y1 as f32 * sy) as i32,
+                ((x2 as f32 * sx).ceil() as i32).min(width as i32),
+                ((y2 as f32 * sy).ceil() as i32).min(height as i32),
+            )
+        })
+        .collect())
+}
+
+/// Width a text line `line_w` by `line_h` pixels is scaled to for recognition.
+fn recognition_width(line_w: u32, line_h: u32) -> u32 {
+    ((line_w as f32 * RECOGNITION_HEIGHT as f32 / line_h as f32).ceil() as u32).clamp(1, RECOGNITION_MAX_WIDTH)
+}
+
+/// Reads text lines, each an image and its box in the frame, in one run of
+/// the recognition model, as the words of each line. The lines are scaled to
+/// [`RECOGNITION_HEIGHT`] and padded on the right to the widest of them.
+fn read_lines(
+    recognizer: &mut Session,
+    dictionary: &[String],
+    lines: &[(&RgbImage, (i32, i32, i32, i32))],
+) -> Result<Vec<Vec<OcrWord>>> {
+    if lines.is_empty() {
+        return Ok(Vec::new());
+    }
+    let widths: Vec<u32> =
+        lines.iter().map(|(crop, _)| recognition_width(crop.width().max(1), crop.height().max(1))).collect();
+    let batch_w = widths.iter().copied().max().unwrap_or(1);
+    let plane = (batch_w * RECOGNITION_HEIGHT) as usize;
+    let mut data = vec![0f32; lines.len() * 3 * plane];
+    for (n, ((crop, _), &input_w)) in lines.iter().zip(&widths).enumerate() {
+        let resized = imageops::resize(*crop, input_w, RECOGNITION_HEIGHT, FilterType::Triangle);
+        for (x, y, pixel) in resized.enumerate_pixels() {
+            let i = (y * batch_w + x) as usize;
+            for c in 0..3 {
+                data[(n * 3 + c) * plane + i] = pixel[c] as f32 / 127.5 - 1.0;
+            }
+        }
+    }
+    let shape = [lines.len(), 3, RECOGNITION_HEIGHT as usize, batch_w as usize];
+    let input = Tensor::from_array((shape, data))?;
+    let outputs = recognizer.run(ort::inputs![input])?;
+    let (shape, probabilities) = outputs[0].try_extract_tensor::<f32>()?;
+    ensure!(
+        shape.len() == 3 && shape[0] as usize == lines.len(),
+        "Unexpected recognition output of shape {:?}",
+        &shape[..]
+    );
+    let (steps, classes) = (shape[1].max(1) as usize, shape[2] as usize);
+
+    Ok(lines
+        .iter()
+        .zip(&widths)
+        .zip(probabilities.chunks_exact((steps * classes).max(1)))
+        .map(|(((crop, line), &input_w), probabilities)| {
+            let chars = ctc_decode(probabilities, classes, dictionary);
+            group_words(&chars, *line, step_width(crop.width().max(1), input_w, batch_w, steps))
+        })
+        .collect())
+}
+
+impl OcrEngine for PaddleEngine {
+    fn name(&self) -> &'static str {
+        "paddle"
+    }
+
+    fn recognize(&self, image: &DynamicImage) -> Result<Recognition> {
+        self.recognize_batch(std::slice::from_ref(image))?.pop().context("The engine returned no result")
+    }
+
+    fn batch_size(&self) -> usize {
+        FRAME_BATCH
+    }
+
+    fn recognize_batch(&self, images: &[DynamicImage]) -> Result<Vec<Recognition>> {
+        let device = self.next.fetch_add(1, Ordering::Relaxed) % self.devices.len();
+        let mut models =
+            self.devices[device].lock().map_err(|_| anyhow!("The models on device {} are unusable", device))?;
+        let Models { detector, recognizer } = &mut *models;
+
+        let images: Vec<RgbImage> = images.iter().map(DynamicImage::to_rgb8).collect();
+        let mut lines = Vec::new();
+        for (index, image) in images.iter().enumerate() {
+            lines.extend(detect(detector, image)?.into_iter().map(|line| (index, line)));
+        }
+        let crops: Vec<RgbImage> = lines
+            .iter()
+            .map(|&(index, (x1, y1, x2, y2))| {
+                let (line_w, line_h) = ((x2 - x1).max(1) as u32, (y2 - y1).max(1) as u32);
+                imageops::crop_imm(&images[index], x1 as u32, y1 as u32, line_w, line_h).to_image()
+            })
+            .collect();
+
+        // Lines are batched by the width they are scaled to, which the padding follows.
+        let widths: Vec<u32> = crops.iter().map(|crop| recognition_width(crop.width(), crop.height())).collect();
+        let mut read = vec![Vec::new(); lines.len()];
+        for batch in recognition_batches(&widths, RECOGNITION_BATCH) {
+            let inputs: Vec<_> = batch.iter().map(|&i| (&crops[i], lines[i].1)).collect();
+            for (i, words) in batch.into_iter().zip(read_lines(recognizer, &self.dictionary, &inputs)?) {
+                read[i] = words;
+            }
+        }
+
+        let mut recognitions: Vec<Recognition> = images.iter().map(|_| Recognition::default()).collect();
+        for ((index, line), words) in lines.into_iter().zip(read) {
+            if words.is_empty() {
+                continue;
+            }
+            let text = words.iter().map(|word| word.text.as_str()).collect::<Vec<_>>().join(" ");
+            let confidence = words.iter().map(|word| word.confidence).sum::<f32>() / words.len() as f32;
+            let recognition = &mut recognitions[index];
+            recognition.lines.push(OcrWord { text, bbox: line, confidence });
+            recognition.words.extend(words);
+        }
+        Ok(recognitions)
+    }
+}
@@ -0,0 +1,122 @@
+//! Paper Module
+//!
+//! Handles cleaning up handheld recordings of paper pages, which are lit
+//! unevenly, shaded by the hand or phone filming them, and washed out where
+//! glossy paper reflects a lamp. The highlights are found as the clipped
+//! white spots far brighter than the page and filled in from their
+//! surroundings, then the shadows are evened out the way a whiteboard's
+//! lighting is, by dividing each pixel by the paper's brightness around it.
+
+use crate::whiteboard;
+use image::{Rgb, RgbImage};
+
+/// Brightness from which a pixel may be a highlight.
+const GLARE_LEVEL: u8 = 245;
+/// Amount a highlight must be brighter than the page's median brightness,
+/// so that evenly lit white paper is not taken for one.
+const GLARE_MARGIN: u8 = 30;
+/// Widest spread between the channels of a highlight, which is white.
+const GLARE_SPREAD: u8 = 24;
+/// Pixels highlights are grown by, to cover their blurred edges.
+const GLARE_GROWTH: usize = 2;
+
+/// Luma of a pixel.
+fn brightness(pixel: &Rgb<u8>) -> u8 {
+    let [r, g, b] = pixel.0;
+    (0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32).round() as u8
+}
+
+/// Finds the highlights of a frame: the white pixels at least
+/// [`GLARE_LEVEL`] bright and [`GLARE_MARGIN`] brighter than the median,
+/// grown by [`GLARE_GROWTH`] pixels. Returns whether each pixel is one, row
+/// by row.
+pub fn glare_mask(frame: &RgbImage) -> Vec<bool> {
+    let (width, height) = (frame.width() as usize, frame.height() as usize);
+    let mut levels: Vec<u8> = frame.pixels().map(brightness).collect();
+    if levels.is_empty() {
+        return Vec::new();
+    }
+    let middle = levels.len() / 2;
+    let median = *levels.select_nth_unstable(middle).1;
+    let threshold = GLARE_LEVEL.max(median.saturating_add(GLARE_MARGIN));
+    let mut glare: Vec<bool> = frame
+        .pixels()
+        .map(|pixel| {
+            let spread = pixel.0.iter().max().unwrap() - pixel.0.iter().min().unwrap();
+            brightness(pixel) >= threshold && spread <= GLARE_SPREAD
+        })
+        .collect();
+    for _ in 0..GLARE_GROWTH {
+        let grown: Vec<bool> = (0..width * height)
+            .map(|i| {
+                let (x, y) = (i % width, i / width);
+                glare[i]
+                    || (x > 0 && glare[i - 1])
+                    || (x + 1 < width && glare[i + 1])
+                    || (y > 0 && glare[i - width])
+                    || (y + 1 < height && glare[i + width])
+            })
+            .collect();
+        glare = grown;
+    }
+    glare
+}
+
+/// Fills the pixels of `mask` in from the outside in: each takes the mean of
+/// its neighbours already known, layer by layer, so a highlight ends up
+/// shaded like the paper around it. A frame that is all highlight is kept.
+pub fn inpaint(frame: &RgbImage, mask: &[bool]) -> RgbImage {
+    let (width, height) = (frame.width() as usize, frame.height() as usize);
+    let mut filled = frame.clone();
+    let mut known: Vec<bool> = mask.iter().map(|&masked| !masked).collect();
+    let neighbours = |i: usize| {
+        let (x, y) = (i % width, i / width);
+        [
+            (x > 0).then(|| i - 1),
+            (x + 1 < width).then(|| i + 1),
+            (y > 0).then(|| i - width),
+            (y + 1 < height).then(|| i + width),
+        ]
+        .into_iter()
+        .flatten()
+    };
+    let mut layer: Vec<usize> =
+        (0..width * height).filter(|&i| !known[i] && neighbours(i).any(|n| known[n])).collect();
+    while !layer.is_empty() {
+        let values: Vec<(usize, Rgb<u8>)> = layer
+            .iter()
+            .map(|&i| {
+                let around: Vec<[u8; 3]> = neighbours(i)
+                    .filter(|&n| known[n])
+                    .map(|n| filled.get_pixel((n % width) as u32, (n / width) as u32).0)
+                    .collect();
+                let mean = |channel: usize| {
+                    (around.iter().map(|pixel| pixel[channel] as u32).sum::<u32>() / around.len() as u32) as u8
+                };
+                (i, Rgb([mean(0), mean(1), mean(2)]))
+            })
+            .collect();
+        for &(i, value) in &values {
+            filled.put_pixel((i % width) as u32, (i / width) as u32, value);
+            known[i] = true;
+        }
+        let mut next: Vec<usize> =
+            values.iter().flat_map(|&(i, _)| neighbours(i)).filter(|&n| !known[n]).collect();
+        next.sort_unstable();
+        next.dedup();
+        layer = next;
+    }
+    filled
+}
+
+/// Cleans up a frame of a paper page: fills in its highlights, then evens
+/// out its lighting with [`whiteboard::even_out`], keeping the colours of the
+/// print.
+pub fn enhance(frame: &RgbImage) -> RgbImage {
+    let glare = glare_mask(frame);
+    if glare.iter().any(|&glare| glare) {
+        whiteboard::even_out(&inpaint(frame, &glare))
+    } else {
+        whiteboard::even_out(frame)
+    }
+}
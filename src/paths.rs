@@ -0,0 +1,122 @@
+//! Path Handling Module
+//!
+//! Helpers for dealing with user-supplied and derived paths across platforms:
+//! UTF-8 validation for the native libraries, file name sanitization, and
+//! extended-length paths on Windows.
+
+use anyhow::{anyhow, Context, Result};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// Keeps derived names well below the 255-byte component limit of common file systems.
+const MAX_FILE_NAME_BYTES: usize = 120;
+
+// Device names that Windows refuses to use as file names, with or without an extension.
+const WINDOWS_RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Returns the path as UTF-8, or a descriptive error.
+///
+/// FFmpeg and Tesseract only accept UTF-8 paths (the bindings panic otherwise),
+/// so every path handed to them goes through this check first.
+pub fn to_utf8(path: &Path) -> Result<&str> {
+    path.to_str()
+        .ok_or_else(|| anyhow!("Path is not valid UTF-8 and cannot be passed to the native libraries: {:?}", path))
+}
+
+/// Turns a user-supplied directory into an absolute path suitable for deep output trees.
+///
+/// On Windows the result uses the extended-length (`\\?\`) form, so outputs nested
+/// in long or UNC paths do not hit the legacy `MAX_PATH` limit.
+pub fn prepare_dir(path: &Path) -> Result<PathBuf> {
+    let absolute = std::path::absolute(path)
+        .with_context(|| format!("Failed to resolve absolute path for {:?}", path))?;
+    Ok(extended_length(absolute))
+}
+
+/// Makes an arbitrary string safe to use as a single file name on every platform.
+///
+/// Path separators, characters reserved on Windows, and control characters are
+/// replaced with `_`; trailing dots and spaces are removed; reserved device names
+/// get a suffix; and the result is truncated to a conservative length.
+pub fn sanitize_file_name(name: &str) -> String {
+    let mut sanitized: String = name
+        .chars()
+        .map(|c| match c {
+            '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+
+    if sanitized.len() > MAX_FILE_NAME_BYTES {
+        let mut end = MAX_FILE_NAME_BYTES;
+        while !sanitized.is_char_boundary(end) {
+            end -= 1;
+        }
+        sanitized.truncate(end);
+    }
+
+    let trimmed = sanitized.trim().trim_end_matches(['.', ' ']);
+    if trimmed.is_empty() {
+        return "untitled".to_string();
+    }
+
+    let stem = trimmed.split('.').next().unwrap_or(trimmed);
+    if WINDOWS_RESERVED_NAMES.iter().any(|reserved| stem.eq_ignore_ascii_case(reserved)) {
+        return format!("{}_", trimmed);
+    }
+    trimmed.to_string()
+}
+
+/// Appends `path` if it is a file, or every file below it if it is a directory.
+pub fn collect_files(path: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    if !path.is_dir() {
+        files.push(path.to_path_buf());
+        return Ok(());
+    }
+    for entry in fs::read_dir(path)? {
+        collect_files(&entry?.path(), files)?;
+    }
+    Ok(())
+}
+
+#[cfg(windows)]
+fn extended_length(path: PathBuf) -> PathBuf {
+    use std::ffi::OsString;
+    use std::os::windows::ffi::{OsStrExt, OsStringExt};
+
+    let wide: Vec<u16> = path.as_os_str().encode_wide().collect();
+    let encode = |s: &str| s.encode_utf16().collect::<Vec<u16>>();
+
+    // Already verbatim or a device path: leave untouched.
+    if wide.starts_with(&encode(r"\\?\")) || wide.starts_with(&encode(r"\\.\")) {
+        return path;
+    }
+
+    let mut extended = if wide.starts_with(&encode(r"\\")) {
+        // UNC path: \\server\share\... becomes \\?\UNC\server\share\...
+        let mut prefix = encode(r"\\?\UNC\");
+        prefix.extend_from_slice(&wide[2..]);
+        prefix
+    } else {
+        let mut prefix = encode(r"\\?\");
+        prefix.extend_from_slice(&wide);
+        prefix
+    };
+    // Verbatim paths are not normalized by Windows, so forward slashes must go.
+    for unit in extended.iter_mut() {
+        if *unit == u16::from(b'/') {
+            *unit = u16::from(b'\\');
+        }
+    }
+    PathBuf::from(OsString::from_wide(&extended))
+}
+
+#[cfg(not(windows))]
+fn extended_length(path: PathBuf) -> PathBuf {
+    path
+}
@@ -0,0 +1,72 @@
+//! PDF Encryption Module
+//!
+//! Handles password protection of generated PDFs with the standard security
+//! handler (AES-256, PDF 2.0). The finished file is reloaded, every string and
+//! stream is encrypted, and the file is written again.
+
+use anyhow::{Context, Result, anyhow};
+use lopdf::encryption::crypt_filters::{Aes256CryptFilter, CryptFilter};
+use lopdf::{Document, EncryptionState, EncryptionVersion, Permissions};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Arc;
+
+// Name of the crypt filter used for both strings and streams.
+const CRYPT_FILTER: &[u8] = b"StdCF";
+
+/// Passwords protecting a PDF.
+#[derive(Debug, Clone, Default)]
+pub struct PdfEncryption {
+    /// Needed to open the document. Empty lets anyone open it, with the
+    /// permissions restricted by the owner password.
+    pub user_password: String,
+    /// Grants full access, including editing. When absent, the user password
+    /// doubles as the owner password.
+    pub owner_password: Option<String>,
+}
+
+impl PdfEncryption {
+    /// What readers without the owner password may do: print and copy text,
+    /// but not edit, annotate, or reassemble the document.
+    fn permissions(&self) -> Permissions {
+        if self.owner_password.is_none() {
+            return Permissions::all();
+        }
+        Permissions::PRINTABLE
+            | Permissions::PRINTABLE_IN_HIGH_QUALITY
+            | Permissions::COPYABLE
+            | Permissions::COPYABLE_FOR_ACCESSIBILITY
+    }
+}
+
+/// Encrypts the PDF at `path` in place.
+///
+/// Unlike writing the PDF, this holds the whole document in memory.
+pub fn encrypt_file(path: &Path, encryption: &PdfEncryption) -> Result<()> {
+    let mut document = Document::load(path)
+        .with_context(|| format!("Failed to read PDF {:?} for encryption", path))?;
+
+    let mut file_encryption_key = [0u8; 32];
+    getrandom::fill(&mut file_encryption_key)
+        .map_err(|e| anyhow!("Failed to generate a PDF encryption key: {}", e))?;
+    let crypt_filter: Arc<dyn CryptFilter> = Arc::new(Aes256CryptFilter);
+    let version = EncryptionVersion::V5 {
+        encrypt_metadata: true,
+        crypt_filters: BTreeMap::from([(CRYPT_FILTER.to_vec(), crypt_filter)]),
+        file_encryption_key: &file_encryption_key,
+        stream_filter: CRYPT_FILTER.to_vec(),
+        string_filter: CRYPT_FILTER.to_vec(),
+        owner_password: encryption
+            .owner_password
+            .as_deref()
+            .unwrap_or(&encryption.user_password),
+        user_password: &encryption.user_password,
+        permissions: encryption.permissions(),
+    };
+    let state = EncryptionState::try_from(version).context("Invalid PDF password")?;
+    document.encrypt(&state).context("Failed to encrypt PDF")?;
+    document
+        .save(path)
+        .with_context(|| format!("Failed to write encrypted PDF {:?}", path))?;
+    Ok(())
+}
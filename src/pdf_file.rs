@@ -0,0 +1,143 @@
+//! PDF File Module
+//!
+//! Handles writing a PDF to disk incrementally: objects are appended to the file
+//! as soon as they are built and only their offsets are kept, so the encoded
+//! page images of a long video never have to be held in memory all at once.
+//! The file identifier is a hash of everything written, so the same input
+//! always produces the same bytes.
+
+use anyhow::{Context, Result, bail, ensure};
+use pdf_writer::{Chunk, Pdf, Ref};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+// Same header as `Pdf::finish`, including the binary comment marking the file as binary.
+const HEADER: &[u8] = b"%PDF-1.7\n%\x80\x80\x80\x80\n\n";
+// Every cross-reference entry is exactly this long, including its `\r\n`.
+const XREF_ENTRY_LEN: usize = 20;
+// 128-bit FNV-1a parameters, used for the file identifier.
+const FNV_OFFSET_BASIS: u128 = 0x6c62272e07bb014262b821756295c58d;
+const FNV_PRIME: u128 = 0x0000000001000000000000000000013b;
+
+/// A PDF being written to a file, object by object.
+pub struct PdfFile {
+    out: BufWriter<File>,
+    position: usize,
+    offsets: Vec<(Ref, usize)>,
+    hash: u128,
+}
+
+impl PdfFile {
+    /// Creates the file at `path` and writes the PDF header.
+    pub fn create(path: &Path) -> Result<Self> {
+        let file = File::create(path)
+            .with_context(|| format!("Failed to create PDF file {:?}", path))?;
+        let mut pdf_file = Self {
+            out: BufWriter::new(file),
+            position: 0,
+            offsets: Vec::new(),
+            hash: FNV_OFFSET_BASIS,
+        };
+        pdf_file.write_bytes(HEADER)?;
+        Ok(pdf_file)
+    }
+
+    /// Appends a chunk holding exactly one indirect object.
+    pub fn write_object(&mut self, chunk: &Chunk) -> Result<()> {
+        let mut refs = chunk.refs();
+        let (Some(id), None) = (refs.next(), refs.next()) else {
+            bail!("Expected exactly one PDF object per chunk, found {}", chunk.refs().len());
+        };
+        self.offsets.push((id, self.position));
+        self.write_bytes(chunk.as_bytes())
+    }
+
+    /// Appends every object of a document built in memory. Its trailer is dropped;
+    /// the catalog and information dictionary are given to [`PdfFile::finish`] instead.
+    pub fn write_document(&mut self, pdf: Pdf) -> Result<()> {
+        let bytes = pdf.finish();
+        let (xref_offset, offsets) =
+            parse_xref(&bytes).context("Malformed PDF cross-reference table")?;
+        ensure!(bytes.starts_with(HEADER), "Unexpected PDF header");
+        let base = self.position - HEADER.len();
+        self.offsets.extend(offsets.into_iter().map(|(id, offset)| (id, base + offset)));
+        self.write_bytes(&bytes[HEADER.len()..xref_offset])
+    }
+
+    /// Writes the cross-reference table and the trailer, and flushes the file.
+    pub fn finish(mut self, catalog: Ref, info: Ref) -> Result<()> {
+        self.offsets.sort();
+        let size = self.offsets.last().map_or(0, |(id, _)| id.get()) + 1;
+        let mut in_use = vec![None; size as usize];
+        for &(id, offset) in &self.offsets {
+            let previous = in_use[id.get() as usize].replace(offset);
+            ensure!(previous.is_none(), "Duplicate PDF object id {}", id.get());
+        }
+
+        // Unused numbers form the free list, which starts and ends at object 0.
+        let mut free = (1..in_use.len()).filter(|&id| in_use[id].is_none()).peekable();
+        let mut tail = format!("xref\n0 {}\n", size);
+        for (id, offset) in in_use.iter().enumerate() {
+            match offset {
+                Some(offset) => tail.push_str(&format!("{:010} 00000 n\r\n", offset)),
+                None => {
+                    if id > 0 {
+                        free.next();
+                    }
+                    let next = free.peek().copied().unwrap_or(0);
+                    let generation = if id == 0 { 65535 } else { 0 };
+                    tail.push_str(&format!("{:010} {:05} f\r\n", next, generation));
+                }
+            }
+        }
+
+        tail.push_str(&format!(
+            "trailer\n<<\n  /Size {}\n  /Root {} 0 R\n  /Info {} 0 R\n",
+            size,
+            catalog.get(),
+            info.get()
+        ));
+        // Both identifiers are the same, as for a file that was never updated.
+        tail.push_str(&format!("  /ID [<{0:032X}> <{0:032X}>]\n", self.hash));
+        tail.push_str(&format!(">>\nstartxref\n{}\n%%EOF", self.position));
+        self.write_bytes(tail.as_bytes())?;
+        self.out.flush().context("Failed to write PDF file")
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<()> {
+        self.out.write_all(bytes).context("Failed to write PDF file")?;
+        self.position += bytes.len();
+        for &byte in bytes {
+            self.hash = (self.hash ^ byte as u128).wrapping_mul(FNV_PRIME);
+        }
+        Ok(())
+    }
+}
+
+/// Reads the cross-reference table written by `Pdf::finish`, returning where it
+/// starts and the offset of every object in use.
+fn parse_xref(bytes: &[u8]) -> Option<(usize, Vec<(Ref, usize)>)> {
+    let start = bytes.windows(10).rposition(|window| window == b"startxref\n")? + 10;
+    let xref_offset: usize = std::str::from_utf8(&bytes[start..])
+        .ok()?
+        .lines()
+        .next()?
+        .parse()
+        .ok()?;
+
+    let table = bytes.get(xref_offset..)?.strip_prefix(b"xref\n0 ")?;
+    let header_len = table.iter().position(|&byte| byte == b'\n')?;
+    let count: usize = std::str::from_utf8(&table[..header_len]).ok()?.parse().ok()?;
+    let entries = table.get(header_len + 1..)?;
+
+    let mut offsets = Vec::new();
+    for id in 0..count {
+        let entry = entries.get(id * XREF_ENTRY_LEN..(id + 1) * XREF_ENTRY_LEN)?;
+        if entry[17] == b'n' {
+            let offset = std::str::from_utf8(&entry[..10]).ok()?.parse().ok()?;
+            offsets.push((Ref::new(id as i32), offset));
+        }
+    }
+    Some((xref_offset, offsets))
+}
@@ -0,0 +1,235 @@
+//! PDF Font Module
+//!
+//! Handles the font behind the invisible OCR text layer of generated PDFs. The
+//! font is generated on the fly with one blank glyph per character used in the
+//! document and embedded as a CID font with a ToUnicode map, so text in any
+//! script can be searched and copied without bundling real font files.
+
+use pdf_writer::types::{CidFontType, FontFlags, SystemInfo};
+use pdf_writer::{Finish, Name, Pdf, Rect, Ref, Str};
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Write as _;
+
+const FONT_NAME: Name<'static> = Name(b"GlyphLessFont");
+const UNITS_PER_EM: u16 = 1000;
+// A CMap section may hold at most 100 entries.
+const CMAP_SECTION_SIZE: usize = 100;
+/// Advance of every glyph, as a fraction of the font size.
+pub const GLYPH_ADVANCE: f32 = 0.5;
+
+/// A font whose glyphs draw nothing, covering a fixed set of characters.
+///
+/// Each character is assigned a two-byte code (CID) that doubles as its glyph id.
+pub struct GlyphlessFont {
+    chars: Vec<char>,
+    cids: HashMap<char, u16>,
+}
+
+impl GlyphlessFont {
+    /// Creates a font covering the given characters. Glyph 0 is reserved for
+    /// `.notdef`, so at most 65534 distinct characters are kept.
+    pub fn new(chars: impl IntoIterator<Item = char>) -> Self {
+        let chars: Vec<char> = chars
+            .into_iter()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .take(u16::MAX as usize - 1)
+            .collect();
+        let cids = chars
+            .iter()
+            .enumerate()
+            .map(|(i, c)| (*c, i as u16 + 1))
+            .collect();
+        Self { chars, cids }
+    }
+
+    /// Encodes text as big-endian CIDs. Characters outside the font are dropped.
+    pub fn encode(&self, text: &str) -> Vec<u8> {
+        text.chars()
+            .filter_map(|c| self.cids.get(&c))
+            .flat_map(|cid| cid.to_be_bytes())
+            .collect()
+    }
+
+    /// Writes the Type0 font, its descendant CID font, descriptor, font program and
+    /// ToUnicode map. Only `font_ref` needs to be referenced from page resources.
+    pub fn write(&self, pdf: &mut Pdf, ref_counter: &mut dyn Iterator<Item = i32>, font_ref: Ref) {
+        let cid_font_ref = Ref::new(ref_counter.next().unwrap());
+        let descriptor_ref = Ref::new(ref_counter.next().unwrap());
+        let font_file_ref = Ref::new(ref_counter.next().unwrap());
+        let to_unicode_ref = Ref::new(ref_counter.next().unwrap());
+        let system_info = SystemInfo {
+            registry: Str(b"Adobe"),
+            ordering: Str(b"Identity"),
+            supplement: 0,
+        };
+        let advance = GLYPH_ADVANCE * UNITS_PER_EM as f32;
+
+        pdf.type0_font(font_ref)
+            .base_font(FONT_NAME)
+            .encoding_predefined(Name(b"Identity-H"))
+            .descendant_font(cid_font_ref)
+            .to_unicode(to_unicode_ref);
+
+        pdf.cid_font(cid_font_ref)
+            .subtype(CidFontType::Type2)
+            .base_font(FONT_NAME)
+            .system_info(system_info)
+            .font_descriptor(descriptor_ref)
+            .default_width(advance)
+            .cid_to_gid_map_predefined(Name(b"Identity"));
+
+        pdf.font_descriptor(descriptor_ref)
+            .name(FONT_NAME)
+            .flags(FontFlags::SYMBOLIC)
+            .bbox(Rect::new(0.0, 0.0, advance, UNITS_PER_EM as f32))
+            .italic_angle(0.0)
+            .ascent(UNITS_PER_EM as f32)
+            .descent(0.0)
+            .cap_height(UNITS_PER_EM as f32)
+            .stem_v(80.0)
+            .font_file2(font_file_ref);
+
+        let program = truetype_program(self.chars.len() as u16 + 1);
+        pdf.stream(font_file_ref, &program)
+            .pair(Name(b"Length1"), program.len() as i32)
+            .finish();
+
+        pdf.stream(to_unicode_ref, self.to_unicode_cmap().as_bytes());
+    }
+
+    /// Builds the ToUnicode CMap mapping every CID back to its character.
+    fn to_unicode_cmap(&self) -> String {
+        let mut cmap = String::from(
+            "/CIDInit /ProcSet findresource begin\n\
+             12 dict begin\n\
+             begincmap\n\
+             /CIDSystemInfo << /Registry (Adobe) /Ordering (UCS) /Supplement 0 >> def\n\
+             /CMapName /Adobe-Identity-UCS def\n\
+             /CMapType 2 def\n\
+             1 begincodespacerange\n\
+             <0000> <FFFF>\n\
+             endcodespacerange\n",
+        );
+        for (section, chars) in self.chars.chunks(CMAP_SECTION_SIZE).enumerate() {
+            let _ = writeln!(cmap, "{} beginbfchar", chars.len());
+            for (i, c) in chars.iter().enumerate() {
+                let cid = section * CMAP_SECTION_SIZE + i + 1;
+                let utf16: String = c
+                    .encode_utf16(&mut [0; 2])
+                    .iter()
+                    .map(|unit| format!("{:04X}", unit))
+                    .collect();
+                let _ = writeln!(cmap, "<{:04X}> <{}>", cid, utf16);
+            }
+            cmap.push_str("endbfchar\n");
+        }
+        cmap.push_str(
+            "endcmap\n\
+             CMapName currentdict /CMap defineresource pop\n\
+             end\n\
+             end\n",
+        );
+        cmap
+    }
+}
+
+/// Builds a minimal TrueType font with `glyph_count` empty glyphs, all advancing
+/// by `GLYPH_ADVANCE` em.
+fn truetype_program(glyph_count: u16) -> Vec<u8> {
+    let advance = (GLYPH_ADVANCE * UNITS_PER_EM as f32) as u16;
+
+    let mut os2 = Vec::new();
+    push_u16(&mut os2, &[1, advance, 400, 5, 0]); // version, avg width, weight, width, fsType
+    os2.extend_from_slice(&[0; 22]); // sub/superscript and strikeout metrics, family class
+    os2.extend_from_slice(&[0; 10]); // PANOSE
+    os2.extend_from_slice(&[0; 16]); // Unicode ranges
+    os2.extend_from_slice(b"NONE");
+    push_u16(&mut os2, &[0x40, 0, 0xFFFF, UNITS_PER_EM, 0, 0, UNITS_PER_EM, 0]);
+    os2.extend_from_slice(&[0; 8]); // code page ranges
+
+    // A format 4 subtable with only the mandatory final segment: no character is
+    // mapped, the PDF addresses glyphs directly through CIDs.
+    let mut cmap = Vec::new();
+    push_u16(&mut cmap, &[0, 1, 3, 1, 0, 12]);
+    push_u16(&mut cmap, &[4, 24, 0, 2, 2, 0, 0, 0xFFFF, 0, 0xFFFF, 1, 0]);
+
+    let mut head = Vec::new();
+    push_u16(&mut head, &[1, 0, 1, 0, 0, 0, 0x5F0F, 0x3CF5, 0x000B, UNITS_PER_EM]);
+    head.extend_from_slice(&[0; 16]); // created, modified
+    push_u16(&mut head, &[0, 0, advance, UNITS_PER_EM, 0, 3, 2, 0, 0]);
+
+    let mut hhea = Vec::new();
+    push_u16(&mut hhea, &[1, 0, UNITS_PER_EM, 0, 0, advance, 0, 0, advance, 1]);
+    hhea.extend_from_slice(&[0; 14]); // caret run and offset, reserved, data format
+    push_u16(&mut hhea, &[1]);
+
+    let mut hmtx = Vec::new();
+    push_u16(&mut hmtx, &[advance, 0]);
+    hmtx.resize(hmtx.len() + (glyph_count as usize - 1) * 2, 0);
+
+    let loca = vec![0; (glyph_count as usize + 1) * 2];
+
+    let mut maxp = Vec::new();
+    push_u16(&mut maxp, &[1, 0, glyph_count, 0, 0, 0, 0, 2]);
+    maxp.extend_from_slice(&[0; 16]);
+
+    let mut name = Vec::new();
+    push_u16(&mut name, &[0, 0, 6]);
+
+    let mut post = Vec::new();
+    push_u16(&mut post, &[3, 0, 0, 0, (-100i16) as u16, 50, 0, 1]);
+    post.extend_from_slice(&[0; 16]);
+
+    // Table records must be sorted by tag.
+    let tables: [(&[u8; 4], Vec<u8>); 10] = [
+        (b"OS/2", os2),
+        (b"cmap", cmap),
+        (b"glyf", Vec::new()),
+        (b"head", head),
+        (b"hhea", hhea),
+        (b"hmtx", hmtx),
+        (b"loca", loca),
+        (b"maxp", maxp),
+        (b"name", name),
+        (b"post", post),
+    ];
+
+    let mut font = Vec::new();
+    push_u16(&mut font, &[1, 0, tables.len() as u16, 128, 3, tables.len() as u16 * 16 - 128]);
+    let mut offset = 12 + tables.len() * 16;
+    for (tag, data) in &tables {
+        font.extend_from_slice(*tag);
+        font.extend_from_slice(&checksum(data).to_be_bytes());
+        font.extend_from_slice(&(offset as u32).to_be_bytes());
+        font.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        offset += data.len().next_multiple_of(4);
+    }
+    let mut head_offset = 0;
+    for (tag, data) in &tables {
+        if *tag == b"head" {
+            head_offset = font.len();
+        }
+        font.extend_from_slice(data);
+        font.resize(font.len().next_multiple_of(4), 0);
+    }
+
+    let adjustment = 0xB1B0_AFBAu32.wrapping_sub(checksum(&font));
+    font[head_offset + 8..head_offset + 12].copy_from_slice(&adjustment.to_be_bytes());
+    font
+}
+
+fn push_u16(buffer: &mut Vec<u8>, values: &[u16]) {
+    for value in values {
+        buffer.extend_from_slice(&value.to_be_bytes());
+    }
+}
+
+/// Sums the data as big-endian 32-bit words, zero-padding the last one.
+fn checksum(data: &[u8]) -> u32 {
+    data.chunks(4).fold(0u32, |sum, chunk| {
+        let mut word = [0; 4];
+        word[..chunk.len()].copy_from_slice(chunk);
+        sum.wrapping_add(u32::from_be_bytes(word))
+    })
+}
@@ -0,0 +1,148 @@
+//! PDF Keyword Index Module
+//!
+//! Handles the optional index pages at the end of the PDF: one line per term
+//! of the keyword index, with the numbers of the pages it appears on, each
+//! number linking to its page.
+
+use crate::document_builder::{A4_HEIGHT_PT as PAGE_HEIGHT_PT, A4_WIDTH_PT as PAGE_WIDTH_PT};
+use crate::keywords::KeywordEntry;
+use crate::pdf_toc::{encode_win_ansi, truncate};
+use pdf_writer::types::{ActionType, AnnotationType};
+use pdf_writer::{Content, Finish, Name, Pdf, Rect, Ref, Str};
+
+const MARGIN_PT: f32 = 56.0;
+const HEADING_SIZE: f32 = 18.0;
+const ENTRY_SIZE: f32 = 10.0;
+const LINE_HEIGHT: f32 = 14.0;
+// Width of the term column; page numbers start right after it.
+const TERM_COLUMN_PT: f32 = 180.0;
+// Helvetica advances as a fraction of the font size: digits, and ", " together.
+const DIGIT_ADVANCE: f32 = 0.556;
+const SEPARATOR_ADVANCE: f32 = 0.556;
+const AVG_CHAR_ADVANCE: f32 = 0.5;
+const FONT: Name<'static> = Name(b"Helvetica");
+
+/// One line of an index page: the term, on the first line of its entry, and
+/// indices of the pages listed on this line.
+pub struct IndexLine {
+    term: Option<String>,
+    pages: Vec<usize>,
+}
+
+/// Lines that fit on one index page below the heading.
+fn lines_per_page() -> usize {
+    ((PAGE_HEIGHT_PT - 2.0 * MARGIN_PT - 2.0 * HEADING_SIZE) / LINE_HEIGHT) as usize
+}
+
+/// Lays the entries out in lines, wrapping long page lists, and the lines in
+/// pages. `page_numbers` holds the number printed for each page.
+pub fn paginate(entries: &[KeywordEntry], page_numbers: &[usize]) -> Vec<Vec<IndexLine>> {
+    let max_width = PAGE_WIDTH_PT - 2.0 * MARGIN_PT - TERM_COLUMN_PT;
+    let mut lines = Vec::new();
+    for entry in entries {
+        let mut line = IndexLine { term: Some(entry.term.clone()), pages: Vec::new() };
+        let mut width = 0.0;
+        for &page in &entry.pages {
+            let number_width = number_width(page_numbers[page]);
+            if !line.pages.is_empty() && width + SEPARATOR_ADVANCE * ENTRY_SIZE + number_width > max_width {
+                lines.push(std::mem::replace(&mut line, IndexLine { term: None, pages: Vec::new() }));
+                width = 0.0;
+            }
+            if !line.pages.is_empty() {
+                width += SEPARATOR_ADVANCE * ENTRY_SIZE;
+            }
+            width += number_width;
+            line.pages.push(page);
+        }
+        lines.push(line);
+    }
+    let mut pages = Vec::new();
+    let mut lines = lines.into_iter().peekable();
+    while lines.peek().is_some() {
+        pages.push(lines.by_ref().take(lines_per_page()).collect());
+    }
+    pages
+}
+
+fn number_width(number: usize) -> f32 {
+    number.to_string().len() as f32 * ENTRY_SIZE * DIGIT_ADVANCE
+}
+
+/// Writes the index pages `index_refs` with the lines laid out by [`paginate`].
+/// Page `i` is the page object `page_refs[i]`, printed as `page_numbers[i]`.
+#[allow(clippy::too_many_arguments)]
+pub fn write(
+    pdf: &mut Pdf,
+    ref_counter: &mut dyn Iterator<Item = i32>,
+    index_refs: &[Ref],
+    page_tree_ref: Ref,
+    heading: &str,
+    layout: &[Vec<IndexLine>],
+    page_refs: &[Ref],
+    page_numbers: &[usize],
+) {
+    if index_refs.is_empty() {
+        return;
+    }
+    let font_ref = Ref::new(ref_counter.next().unwrap());
+    pdf.type1_font(font_ref)
+        .base_font(FONT)
+        .encoding_predefined(Name(b"WinAnsiEncoding"));
+
+    let max_term_chars = ((TERM_COLUMN_PT - ENTRY_SIZE) / (ENTRY_SIZE * AVG_CHAR_ADVANCE)) as usize;
+    let numbers_x = MARGIN_PT + TERM_COLUMN_PT;
+    for (index_ref, lines) in index_refs.iter().zip(layout) {
+        let content_ref = Ref::new(ref_counter.next().unwrap());
+        let mut content = Content::new();
+        let mut annotation_refs = Vec::new();
+
+        content.begin_text();
+        content.set_font(FONT, HEADING_SIZE);
+        let heading_y = PAGE_HEIGHT_PT - MARGIN_PT - HEADING_SIZE;
+        content.set_text_matrix([1.0, 0.0, 0.0, 1.0, MARGIN_PT, heading_y]);
+        content.show(Str(&encode_win_ansi(heading)));
+
+        content.set_font(FONT, ENTRY_SIZE);
+        let mut y = PAGE_HEIGHT_PT - MARGIN_PT - 2.0 * HEADING_SIZE - LINE_HEIGHT;
+        for line in lines {
+            if let Some(term) = &line.term {
+                content.set_text_matrix([1.0, 0.0, 0.0, 1.0, MARGIN_PT, y]);
+                content.show(Str(&encode_win_ansi(&truncate(term, max_term_chars))));
+            }
+            let numbers: Vec<String> = line.pages.iter().map(|&page| page_numbers[page].to_string()).collect();
+            content.set_text_matrix([1.0, 0.0, 0.0, 1.0, numbers_x, y]);
+            content.show(Str(numbers.join(", ").as_bytes()));
+
+            let mut x = numbers_x;
+            for (&page, number) in line.pages.iter().zip(&numbers) {
+                let width = number.len() as f32 * ENTRY_SIZE * DIGIT_ADVANCE;
+                let annotation_ref = Ref::new(ref_counter.next().unwrap());
+                let mut annotation = pdf.annotation(annotation_ref);
+                annotation
+                    .subtype(AnnotationType::Link)
+                    .rect(Rect::new(x, y - 2.0, x + width, y + ENTRY_SIZE))
+                    .border(0.0, 0.0, 0.0, None);
+                annotation
+                    .action()
+                    .action_type(ActionType::GoTo)
+                    .destination()
+                    .page(page_refs[page])
+                    .fit();
+                annotation.finish();
+                annotation_refs.push(annotation_ref);
+                x += width + SEPARATOR_ADVANCE * ENTRY_SIZE;
+            }
+            y -= LINE_HEIGHT;
+        }
+        content.end_text();
+        pdf.stream(content_ref, &content.finish());
+
+        let mut index_page = pdf.page(*index_ref);
+        index_page.media_box(Rect::new(0.0, 0.0, PAGE_WIDTH_PT, PAGE_HEIGHT_PT));
+        index_page.parent(page_tree_ref);
+        index_page.contents(content_ref);
+        index_page.resources().fonts().pair(FONT, font_ref);
+        index_page.annotations(annotation_refs);
+        index_page.finish();
+    }
+}
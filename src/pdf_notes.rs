@@ -0,0 +1,96 @@
+//! PDF Notes Module
+//!
+//! Handles the transcript pages of the PDF: the speech spoken while a slide was
+//! on screen, wrapped onto A4 pages that directly follow the slide's page, so
+//! the document reads like lecture notes.
+
+use crate::document_builder::{A4_HEIGHT_PT as PAGE_HEIGHT_PT, A4_WIDTH_PT as PAGE_WIDTH_PT};
+use crate::pdf_toc::{encode_win_ansi, truncate};
+use pdf_writer::{Content, Finish, Name, Pdf, Rect, Ref, Str};
+
+const MARGIN_PT: f32 = 56.0;
+const HEADING_SIZE: f32 = 13.0;
+const TEXT_SIZE: f32 = 11.0;
+const LINE_HEIGHT: f32 = 15.0;
+// Approximate Helvetica advance as a fraction of the font size.
+const AVG_CHAR_ADVANCE: f32 = 0.5;
+const FONT: Name<'static> = Name(b"Helvetica");
+
+/// Lines of text that fit on one notes page below the heading.
+fn lines_per_page() -> usize {
+    ((PAGE_HEIGHT_PT - 2.0 * MARGIN_PT - 2.0 * HEADING_SIZE) / LINE_HEIGHT) as usize
+}
+
+/// Characters of text at `size` that fit between the margins.
+fn max_chars(size: f32) -> usize {
+    ((PAGE_WIDTH_PT - 2.0 * MARGIN_PT) / (size * AVG_CHAR_ADVANCE)) as usize
+}
+
+/// Wraps the speech or translated text of one slide into pages of lines,
+/// keeping its line breaks. Empty text needs no page.
+pub fn paginate(text: &str) -> Vec<Vec<String>> {
+    let max_chars = max_chars(TEXT_SIZE);
+    let mut lines: Vec<String> = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > max_chars {
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(word);
+        }
+        if !line.is_empty() {
+            lines.push(line);
+        }
+    }
+    lines.chunks(lines_per_page()).map(<[String]>::to_vec).collect()
+}
+
+/// Writes the standard font shared by all notes pages.
+pub fn write_font(pdf: &mut Pdf, font_ref: Ref) {
+    pdf.type1_font(font_ref)
+        .base_font(FONT)
+        .encoding_predefined(Name(b"WinAnsiEncoding"));
+}
+
+/// Writes the notes pages `note_refs` of one slide, each headed with `heading`.
+pub fn write(
+    pdf: &mut Pdf,
+    ref_counter: &mut dyn Iterator<Item = i32>,
+    note_refs: &[Ref],
+    page_tree_ref: Ref,
+    font_ref: Ref,
+    heading: &str,
+    pages: &[Vec<String>],
+) {
+    let heading = truncate(heading, max_chars(HEADING_SIZE));
+    for (note_ref, lines) in note_refs.iter().zip(pages) {
+        let content_ref = Ref::new(ref_counter.next().unwrap());
+        let mut content = Content::new();
+        content.begin_text();
+        content.set_font(FONT, HEADING_SIZE);
+        let mut y = PAGE_HEIGHT_PT - MARGIN_PT - HEADING_SIZE;
+        content.set_text_matrix([1.0, 0.0, 0.0, 1.0, MARGIN_PT, y]);
+        content.show(Str(&encode_win_ansi(&heading)));
+
+        content.set_font(FONT, TEXT_SIZE);
+        y -= HEADING_SIZE + LINE_HEIGHT;
+        for line in lines {
+            content.set_text_matrix([1.0, 0.0, 0.0, 1.0, MARGIN_PT, y]);
+            content.show(Str(&encode_win_ansi(line)));
+            y -= LINE_HEIGHT;
+        }
+        content.end_text();
+        pdf.stream(content_ref, &content.finish());
+
+        let mut page = pdf.page(*note_ref);
+        page.media_box(Rect::new(0.0, 0.0, PAGE_WIDTH_PT, PAGE_HEIGHT_PT));
+        page.parent(page_tree_ref);
+        page.contents(content_ref);
+        page.resources().fonts().pair(FONT, font_ref);
+        page.finish();
+    }
+}
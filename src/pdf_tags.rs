@@ -0,0 +1,156 @@
+//! PDF Tags Module
+//!
+//! Handles the logical structure of tagged PDF output (PDF/UA-1): one part per
+//! page holding a figure for the page image, a paragraph for every line of the
+//! OCR text layer, and a link for the timestamp label, so screen readers can
+//! follow the document in reading order.
+
+use pdf_writer::types::StructRole;
+use pdf_writer::writers::{Catalog, StructTreeRoot};
+use pdf_writer::{Finish, Name, Pdf, Ref, TextStr};
+use std::ops::Range;
+
+/// Marked-content tag of the page image.
+pub const FIGURE: Name<'static> = Name(b"Figure");
+/// Marked-content tag of a text layer line.
+pub const PARAGRAPH: Name<'static> = Name(b"P");
+/// Marked-content tag of the timestamp label.
+pub const LINK: Name<'static> = Name(b"Link");
+/// Marked-content id of the page image on every page.
+pub const FIGURE_MCID: i32 = 0;
+
+/// The marked content of one page, recorded while it is drawn.
+pub struct TaggedPage {
+    pub page_ref: Ref,
+    /// Alternative text of the page image.
+    pub alt: String,
+    /// Marked-content ids of the text layer lines, in reading order.
+    pub lines: Range<i32>,
+    /// Marked-content id of the timestamp label and its link annotation.
+    pub link: Option<(i32, Ref)>,
+}
+
+/// The structure tree, filled page by page and written once all pages are drawn.
+pub struct StructureTree {
+    root_ref: Ref,
+    pages: Vec<TaggedPage>,
+}
+
+impl StructureTree {
+    pub fn allocate(ref_counter: &mut dyn Iterator<Item = i32>) -> Self {
+        Self { root_ref: Ref::new(ref_counter.next().unwrap()), pages: Vec::new() }
+    }
+
+    /// Parent tree key of the content of page `index`, for its `/StructParents`.
+    pub fn page_key(index: usize) -> i32 {
+        2 * index as i32
+    }
+
+    /// Parent tree key of the link annotation of page `index`, for its `/StructParent`.
+    pub fn annotation_key(index: usize) -> i32 {
+        2 * index as i32 + 1
+    }
+
+    /// Marks the document as tagged and points the catalog at the structure tree.
+    pub fn link(&self, catalog: &mut Catalog) {
+        catalog.mark_info().marked(true);
+        catalog.pair(Name(b"StructTreeRoot"), self.root_ref);
+        catalog.viewer_preferences().display_doc_title(true);
+    }
+
+    pub fn push(&mut self, page: TaggedPage) {
+        self.pages.push(page);
+    }
+
+    /// Writes the structure elements and the parent tree mapping marked content
+    /// and annotations back to them.
+    pub fn write(self, pdf: &mut Pdf, ref_counter: &mut dyn Iterator<Item = i32>) {
+        let mut next_ref = || Ref::new(ref_counter.next().unwrap());
+        let document_ref = next_ref();
+        let part_refs: Vec<Ref> = self.pages.iter().map(|_| next_ref()).collect();
+
+        let mut parent_tree: Vec<(i32, Vec<Ref>)> = Vec::new();
+        for (i, (page, &part_ref)) in self.pages.iter().zip(&part_refs).enumerate() {
+            let figure_ref = next_ref();
+            let line_refs: Vec<Ref> = page.lines.clone().map(|_| next_ref()).collect();
+            let link_ref = page.link.map(|_| next_ref());
+
+            let mut part = pdf.struct_element(part_ref);
+            part.kind(StructRole::Part).parent(document_ref).page(page.page_ref);
+            let mut kids = part.children();
+            kids.struct_element(figure_ref);
+            for &line_ref in &line_refs {
+                kids.struct_element(line_ref);
+            }
+            if let Some(link_ref) = link_ref {
+                kids.struct_element(link_ref);
+            }
+            kids.finish();
+            part.finish();
+
+            pdf.struct_element(figure_ref)
+                .kind(StructRole::Figure)
+                .parent(part_ref)
+                .page(page.page_ref)
+                .alt(TextStr(&page.alt))
+                .marked_content_child()
+                .marked_content_id(FIGURE_MCID);
+            for (&line_ref, mcid) in line_refs.iter().zip(page.lines.clone()) {
+                pdf.struct_element(line_ref)
+                    .kind(StructRole::P)
+                    .parent(part_ref)
+                    .page(page.page_ref)
+                    .marked_content_child()
+                    .marked_content_id(mcid);
+            }
+
+            // Each page's marked content ids index its parent tree entry.
+            let len = page
+                .link
+                .map_or(0, |(mcid, _)| mcid + 1)
+                .max(page.lines.end)
+                .max(FIGURE_MCID + 1);
+            let mut content = vec![figure_ref; len as usize];
+            for (&line_ref, mcid) in line_refs.iter().zip(page.lines.clone()) {
+                content[mcid as usize] = line_ref;
+            }
+            if let (Some((mcid, annotation_ref)), Some(link_ref)) = (page.link, link_ref) {
+                let mut link = pdf.struct_element(link_ref);
+                link.kind(StructRole::Link).parent(part_ref).page(page.page_ref);
+                let mut kids = link.children();
+                kids.marked_content_id(mcid);
+                kids.object_ref().object(annotation_ref);
+                kids.finish();
+                link.finish();
+
+                content[mcid as usize] = link_ref;
+                parent_tree.push((Self::annotation_key(i), vec![link_ref]));
+            }
+            parent_tree.push((Self::page_key(i), content));
+        }
+        parent_tree.sort_by_key(|(key, _)| *key);
+
+        pdf.struct_element(document_ref)
+            .kind(StructRole::Document)
+            .parent(self.root_ref)
+            .children()
+            .items(part_refs.iter().copied());
+
+        let mut root = pdf.indirect(self.root_ref).start::<StructTreeRoot>();
+        root.child(document_ref);
+        let mut tree = root.insert(Name(b"ParentTree")).dict();
+        let mut nums = tree.insert(Name(b"Nums")).array();
+        for (key, refs) in &parent_tree {
+            nums.item(*key);
+            // Annotations map to a single element, page content to an array by id.
+            if *key % 2 == 1 {
+                nums.item(refs[0]);
+            } else {
+                nums.push().array().items(refs.iter().copied());
+            }
+        }
+        nums.finish();
+        tree.finish();
+        root.parent_tree_next_key(Self::page_key(self.pages.len()));
+    }
+}
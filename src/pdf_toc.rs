@@ -0,0 +1,140 @@
+//! PDF Table of Contents Module
+//!
+//! Handles the optional contents pages at the start of the PDF: one line per
+//! captured page with its timestamp, title and page number, each line linking
+//! to the page it describes.
+
+use crate::document_builder::{
+    self, A4_HEIGHT_PT as PAGE_HEIGHT_PT, A4_WIDTH_PT as PAGE_WIDTH_PT, Page,
+};
+use pdf_writer::types::{ActionType, AnnotationType};
+use pdf_writer::{Content, Finish, Name, Pdf, Rect, Ref, Str};
+
+const MARGIN_PT: f32 = 56.0;
+const HEADING_SIZE: f32 = 18.0;
+const ENTRY_SIZE: f32 = 11.0;
+const LINE_HEIGHT: f32 = 18.0;
+// Space reserved on the right for the page number column.
+const NUMBER_COLUMN_PT: f32 = 40.0;
+// Approximate Helvetica advances as a fraction of the font size.
+const AVG_CHAR_ADVANCE: f32 = 0.5;
+const DIGIT_ADVANCE: f32 = 0.556;
+const FONT: Name<'static> = Name(b"Helvetica");
+
+/// Entries that fit on one contents page below the heading.
+fn entries_per_page() -> usize {
+    ((PAGE_HEIGHT_PT - 2.0 * MARGIN_PT - 2.0 * HEADING_SIZE) / LINE_HEIGHT) as usize
+}
+
+/// Number of contents pages needed to list `page_count` pages.
+pub fn page_count(page_count: usize) -> usize {
+    page_count.div_ceil(entries_per_page())
+}
+
+/// Writes the contents pages `toc_refs`, listing `pages` whose page objects are
+/// `page_refs` and whose page numbers (counting the contents pages, which come
+/// first) are `page_numbers`.
+#[allow(clippy::too_many_arguments)]
+pub fn write(
+    pdf: &mut Pdf,
+    ref_counter: &mut dyn Iterator<Item = i32>,
+    toc_refs: &[Ref],
+    page_tree_ref: Ref,
+    heading: &str,
+    pages: &[Page],
+    page_refs: &[Ref],
+    page_numbers: &[usize],
+) {
+    if toc_refs.is_empty() {
+        return;
+    }
+    let font_ref = Ref::new(ref_counter.next().unwrap());
+    pdf.type1_font(font_ref)
+        .base_font(Name(b"Helvetica"))
+        .encoding_predefined(Name(b"WinAnsiEncoding"));
+
+    let title_width = PAGE_WIDTH_PT - 2.0 * MARGIN_PT - NUMBER_COLUMN_PT;
+    let max_title_chars = (title_width / (ENTRY_SIZE * AVG_CHAR_ADVANCE)) as usize;
+    let chunks = pages.iter().zip(page_refs).enumerate().collect::<Vec<_>>();
+
+    for (toc_ref, entries) in toc_refs.iter().zip(chunks.chunks(entries_per_page())) {
+        let content_ref = Ref::new(ref_counter.next().unwrap());
+        let mut content = Content::new();
+        let mut annotation_refs = Vec::new();
+
+        content.begin_text();
+        content.set_font(FONT, HEADING_SIZE);
+        let heading_y = PAGE_HEIGHT_PT - MARGIN_PT - HEADING_SIZE;
+        content.set_text_matrix([1.0, 0.0, 0.0, 1.0, MARGIN_PT, heading_y]);
+        content.show(Str(&encode_win_ansi(heading)));
+
+        let mut y = PAGE_HEIGHT_PT - MARGIN_PT - 2.0 * HEADING_SIZE - LINE_HEIGHT;
+        for &(i, (page, page_ref)) in entries {
+            let mut label = page.display_title(i);
+            if let Some(timestamp) = page.timestamp {
+                label = format!("{}   {}", document_builder::format_timestamp(timestamp), label);
+            }
+            let number = page_numbers[i].to_string();
+            let number_width = number.len() as f32 * ENTRY_SIZE * DIGIT_ADVANCE;
+            let number_x = PAGE_WIDTH_PT - MARGIN_PT - number_width;
+
+            content.set_font(FONT, ENTRY_SIZE);
+            content.set_text_matrix([1.0, 0.0, 0.0, 1.0, MARGIN_PT, y]);
+            content.show(Str(&encode_win_ansi(&truncate(&label, max_title_chars))));
+            content.set_text_matrix([1.0, 0.0, 0.0, 1.0, number_x, y]);
+            content.show(Str(number.as_bytes()));
+
+            let annotation_ref = Ref::new(ref_counter.next().unwrap());
+            let mut annotation = pdf.annotation(annotation_ref);
+            annotation
+                .subtype(AnnotationType::Link)
+                .rect(Rect::new(
+                    MARGIN_PT,
+                    y - 4.0,
+                    PAGE_WIDTH_PT - MARGIN_PT,
+                    y + ENTRY_SIZE + 2.0,
+                ))
+                .border(0.0, 0.0, 0.0, None);
+            annotation
+                .action()
+                .action_type(ActionType::GoTo)
+                .destination()
+                .page(*page_ref)
+                .fit();
+            annotation.finish();
+            annotation_refs.push(annotation_ref);
+
+            y -= LINE_HEIGHT;
+        }
+        content.end_text();
+        pdf.stream(content_ref, &content.finish());
+
+        let mut toc_page = pdf.page(*toc_ref);
+        toc_page.media_box(Rect::new(0.0, 0.0, PAGE_WIDTH_PT, PAGE_HEIGHT_PT));
+        toc_page.parent(page_tree_ref);
+        toc_page.contents(content_ref);
+        toc_page.resources().fonts().pair(FONT, font_ref);
+        toc_page.annotations(annotation_refs);
+        toc_page.finish();
+    }
+}
+
+/// Shortens text to at most `max_chars` characters, marking the cut with "...".
+pub fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let kept: String = text.chars().take(max_chars.saturating_sub(3)).collect();
+    format!("{}...", kept.trim_end())
+}
+
+/// Encodes text for the WinAnsi-encoded standard font. Latin-1 characters map to
+/// the same code; anything else becomes `?`.
+pub fn encode_win_ansi(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| match u8::try_from(u32::from(c)) {
+            Ok(b) if b >= 0x20 && !(0x7F..0xA0).contains(&b) => b,
+            _ => b'?',
+        })
+        .collect()
+}
@@ -0,0 +1,131 @@
+//! PDF/A Module
+//!
+//! Handles the objects a PDF needs on top of the regular output to conform to
+//! PDF/A-2b: an sRGB output intent for the DeviceRGB page images. The PDF/A
+//! identification itself lives in the XMP metadata, and every PDF already
+//! carries the file identifier it requires.
+
+use pdf_writer::types::OutputIntentSubtype;
+use pdf_writer::writers::Catalog;
+use pdf_writer::{Pdf, Ref, TextStr};
+
+const OUTPUT_CONDITION: &str = "sRGB IEC61966-2.1";
+
+/// References of the archival objects, allocated before the catalog is written.
+pub struct ArchivalObjects {
+    icc_profile_ref: Ref,
+}
+
+impl ArchivalObjects {
+    pub fn allocate(ref_counter: &mut dyn Iterator<Item = i32>) -> Self {
+        Self { icc_profile_ref: Ref::new(ref_counter.next().unwrap()) }
+    }
+
+    /// Adds the output intent to the document catalog.
+    pub fn link(&self, catalog: &mut Catalog) {
+        catalog
+            .output_intents()
+            .push()
+            .subtype(OutputIntentSubtype::PDFA)
+            .output_condition_identifier(TextStr(OUTPUT_CONDITION))
+            .info(TextStr(OUTPUT_CONDITION))
+            .dest_output_profile(self.icc_profile_ref);
+    }
+
+    /// Writes the ICC profile.
+    pub fn write(&self, pdf: &mut Pdf) {
+        pdf.icc_profile(self.icc_profile_ref, &srgb_icc_profile()).n(3);
+    }
+}
+
+/// Builds a version 2 display profile for sRGB: D50-adapted primaries and the
+/// sRGB tone curve sampled at 256 points.
+fn srgb_icc_profile() -> Vec<u8> {
+    let xyz = |values: [f64; 3]| {
+        let mut tag = b"XYZ \0\0\0\0".to_vec();
+        for value in values {
+            tag.extend_from_slice(&s15_fixed16(value));
+        }
+        tag
+    };
+
+    let mut curve = b"curv\0\0\0\0".to_vec();
+    curve.extend_from_slice(&256u32.to_be_bytes());
+    for i in 0..256 {
+        let encoded = i as f64 / 255.0;
+        let linear = if encoded <= 0.04045 {
+            encoded / 12.92
+        } else {
+            ((encoded + 0.055) / 1.055).powf(2.4)
+        };
+        curve.extend_from_slice(&((linear * 65535.0).round() as u16).to_be_bytes());
+    }
+
+    let description = b"sRGB IEC61966-2.1\0";
+    let mut desc = b"desc\0\0\0\0".to_vec();
+    desc.extend_from_slice(&(description.len() as u32).to_be_bytes());
+    desc.extend_from_slice(description);
+    // Empty Unicode and ScriptCode descriptions.
+    desc.extend_from_slice(&[0; 4 + 4 + 2 + 1 + 67]);
+
+    let mut copyright = b"text\0\0\0\0".to_vec();
+    copyright.extend_from_slice(b"No copyright, use freely\0");
+
+    let tags: [(&[u8; 4], Vec<u8>); 6] = [
+        (b"desc", desc),
+        (b"cprt", copyright),
+        (b"wtpt", xyz([0.9642, 1.0, 0.8249])),
+        (b"rXYZ", xyz([0.4361, 0.2225, 0.0139])),
+        (b"gXYZ", xyz([0.3851, 0.7169, 0.0971])),
+        (b"bXYZ", xyz([0.1431, 0.0606, 0.7141])),
+    ];
+    // The three tone curves share one data block.
+    let curve_tags: [&[u8; 4]; 3] = [b"rTRC", b"gTRC", b"bTRC"];
+
+    let table_len = 4 + (tags.len() + curve_tags.len()) * 12;
+    let mut data = Vec::new();
+    let mut table = Vec::new();
+    table.extend_from_slice(&((tags.len() + curve_tags.len()) as u32).to_be_bytes());
+    let add = |data: &mut Vec<u8>, tag_data: &[u8]| {
+        let offset = 128 + table_len + data.len();
+        data.extend_from_slice(tag_data);
+        data.resize(data.len().next_multiple_of(4), 0);
+        (offset as u32, tag_data.len() as u32)
+    };
+    for (signature, tag_data) in &tags {
+        let (offset, len) = add(&mut data, tag_data);
+        table.extend_from_slice(*signature);
+        table.extend_from_slice(&offset.to_be_bytes());
+        table.extend_from_slice(&len.to_be_bytes());
+    }
+    let (curve_offset, curve_len) = add(&mut data, &curve);
+    for signature in curve_tags {
+        table.extend_from_slice(signature);
+        table.extend_from_slice(&curve_offset.to_be_bytes());
+        table.extend_from_slice(&curve_len.to_be_bytes());
+    }
+
+    let size = 128 + table.len() + data.len();
+    let mut profile = Vec::with_capacity(size);
+    profile.extend_from_slice(&(size as u32).to_be_bytes());
+    profile.extend_from_slice(&[0; 4]); // preferred CMM
+    profile.extend_from_slice(&[2, 0x10, 0, 0]); // version 2.1
+    profile.extend_from_slice(b"mntrRGB XYZ ");
+    for part in [2024u16, 1, 1, 0, 0, 0] {
+        profile.extend_from_slice(&part.to_be_bytes());
+    }
+    profile.extend_from_slice(b"acsp");
+    profile.extend_from_slice(&[0; 24]); // platform, flags, manufacturer, model, attributes
+    profile.extend_from_slice(&[0; 4]); // perceptual rendering intent
+    for value in [0.9642, 1.0, 0.8249] {
+        profile.extend_from_slice(&s15_fixed16(value));
+    }
+    profile.resize(128, 0);
+    profile.extend_from_slice(&table);
+    profile.extend_from_slice(&data);
+    profile
+}
+
+fn s15_fixed16(value: f64) -> [u8; 4] {
+    ((value * 65536.0).round() as i32).to_be_bytes()
+}
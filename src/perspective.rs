@@ -0,0 +1,204 @@
+//! Perspective Module
+//!
+//! Handles keystone correction of documents and projector screens filmed at an
+//! angle. The page or screen is taken to be the largest bright region of the
+//! frame; its four corners are found, and the frame is warped so that the
+//! quadrilateral they span becomes an upright rectangle, which is then used for
+//! OCR and in the documents in place of the frame as recorded.
+
+use image::imageops::{self, FilterType};
+use image::{GrayImage, ImageBuffer, Rgb};
+use std::collections::VecDeque;
+
+/// Corners of a page or screen on a frame, in pixels: top left, top right,
+/// bottom right, bottom left.
+pub type Quad = [(f32, f32); 4];
+
+/// Longest side, in pixels, frames are scaled down to for finding the corners.
+const DETECTION_SIZE: u32 = 320;
+/// Smallest share of the frame the quadrilateral must cover.
+const MIN_AREA_SHARE: f32 = 0.2;
+/// Smallest share of the quadrilateral the bright region must fill; less means
+/// the region is not a four-sided page.
+const MIN_FILL: f32 = 0.85;
+/// Distance, as a share of the frame's diagonal, within which a corner counts
+/// as the frame's own corner.
+const CORNER_TOLERANCE: f32 = 0.03;
+
+/// Finds the page or screen on a frame, or `None` when there is no bright
+/// four-sided region large enough, or it already fills the frame.
+///
+/// The frame is scaled down and split at Otsu's threshold; the largest
+/// connected bright region is the candidate, and its corners are its extreme
+/// points along the two diagonals.
+pub fn detect_quad(frame: &ImageBuffer<Rgb<u8>, Vec<u8>>) -> Option<Quad> {
+    let (width, height) = frame.dimensions();
+    if width < 2 || height < 2 {
+        return None;
+    }
+    let scale = (DETECTION_SIZE as f32 / width.max(height) as f32).min(1.0);
+    let (small_w, small_h) = (((width as f32 * scale) as u32).max(2), ((height as f32 * scale) as u32).max(2));
+    let gray: GrayImage = imageops::resize(&imageops::grayscale(frame), small_w, small_h, FilterType::Triangle);
+    let threshold = crate::preprocess::otsu_threshold(&gray);
+
+    let region = largest_region(&gray, threshold);
+    if region.is_empty() {
+        return None;
+    }
+    let extreme = |key: &dyn Fn(u32, u32) -> i64| {
+        region.iter().copied().max_by_key(|&(x, y)| key(x, y)).map(|(x, y)| (x as f32 + 0.5, y as f32 + 0.5))
+    };
+    let quad: Quad = [
+        extreme(&|x, y| -(x as i64 + y as i64))?,
+        extreme(&|x, y| x as i64 - y as i64)?,
+        extreme(&|x, y| x as i64 + y as i64)?,
+        extreme(&|x, y| y as i64 - x as i64)?,
+    ];
+
+    let (area, filled) = (quad_area(&quad), region.len() as f32);
+    let frame_area = (small_w * small_h) as f32;
+    if !is_convex(&quad) || area < frame_area * MIN_AREA_SHARE || filled < area * MIN_FILL {
+        return None;
+    }
+    let frame_corners = [(0.0, 0.0), (small_w as f32, 0.0), (small_w as f32, small_h as f32), (0.0, small_h as f32)];
+    let tolerance = (small_w as f32).hypot(small_h as f32) * CORNER_TOLERANCE;
+    let fills_frame = quad.iter().zip(frame_corners).all(|(a, b)| (a.0 - b.0).hypot(a.1 - b.1) <= tolerance);
+    if fills_frame {
+        return None;
+    }
+    let (sx, sy) = (width as f32 / small_w as f32, height as f32 / small_h as f32);
+    Some(quad.map(|(x, y)| (x * sx, y * sy)))
+}
+
+/// Pixels of the largest 4-connected region brighter than `threshold`.
+fn largest_region(image: &GrayImage, threshold: u8) -> Vec<(u32, u32)> {
+    let (width, height) = image.dimensions();
+    let mut seen = vec![false; (width * height) as usize];
+    let mut largest = Vec::new();
+    for start in 0..seen.len() {
+        let (x, y) = (start as u32 % width, start as u32 / width);
+        if seen[start] || image.get_pixel(x, y)[0] <= threshold {
+            continue;
+        }
+        seen[start] = true;
+        let mut region = Vec::new();
+        let mut queue = VecDeque::from([(x, y)]);
+        while let Some((x, y)) = queue.pop_front() {
+            region.push((x, y));
+            let neighbours = [
+                (x.wrapping_sub(1), y),
+                (x + 1, y),
+                (x, y.wrapping_sub(1)),
+                (x, y + 1),
+            ];
+            for (nx, ny) in neighbours {
+                if nx >= width || ny >= height {
+                    continue;
+                }
+                let i = (ny * width + nx) as usize;
+                if !seen[i] && image.get_pixel(nx, ny)[0] > threshold {
+                    seen[i] = true;
+                    queue.push_back((nx, ny));
+                }
+            }
+        }
+        if region.len() > largest.len() {
+            largest = region;
+        }
+    }
+    largest
+}
+
+/// Area of a quadrilateral by the shoelace formula.
+fn quad_area(quad: &Quad) -> f32 {
+    let twice: f32 = (0..4).map(|i| quad[i].0 * quad[(i + 1) % 4].1 - quad[(i + 1) % 4].0 * quad[i].1).sum();
+    twice.abs() / 2.0
+}
+
+/// Whether the corners turn the same way all round.
+fn is_convex(quad: &Quad) -> bool {
+    let turns: Vec<f32> = (0..4)
+        .map(|i| {
+            let (a, b, c) = (quad[i], quad[(i + 1) % 4], quad[(i + 2) % 4]);
+            (b.0 - a.0) * (c.1 - b.1) - (b.1 - a.1) * (c.0 - b.0)
+        })
+        .collect();
+    turns.iter().all(|&turn| turn > 0.0) || turns.iter().all(|&turn| turn < 0.0)
+}
+
+/// Size of the upright rectangle a quadrilateral is warped to: its longer
+/// horizontal and vertical sides.
+pub fn output_size(quad: &Quad) -> (u32, u32) {
+    let length = |a: (f32, f32), b: (f32, f32)| (a.0 - b.0).hypot(a.1 - b.1);
+    let width = length(quad[0], quad[1]).max(length(quad[3], quad[2]));
+    let height = length(quad[0], quad[3]).max(length(quad[1], quad[2]));
+    (width.round().max(1.0) as u32, height.round().max(1.0) as u32)
+}
+
+/// Warps the quadrilateral of a frame to an upright rectangle of
+/// [`output_size`], sampling the frame bilinearly.
+pub fn warp(frame: &ImageBuffer<Rgb<u8>, Vec<u8>>, quad: &Quad) -> ImageBuffer<Rgb<u8>, Vec<u8>> {
+    let (width, height) = output_size(quad);
+    let rectangle = [(0.0, 0.0), (width as f32, 0.0), (width as f32, height as f32), (0.0, height as f32)];
+    let Some(homography) = homography(&rectangle, quad) else {
+        return frame.clone();
+    };
+    let (frame_w, frame_h) = frame.dimensions();
+    let channel = |x: i64, y: i64, c: usize| {
+        let x = x.clamp(0, frame_w as i64 - 1) as u32;
+        let y = y.clamp(0, frame_h as i64 - 1) as u32;
+        frame.get_pixel(x, y)[c] as f64
+    };
+    ImageBuffer::from_fn(width, height, |u, v| {
+        let (sx, sy) = apply(&homography, u as f64 + 0.5, v as f64 + 0.5);
+        let (sx, sy) = (sx - 0.5, sy - 0.5);
+        let (x0, y0) = (sx.floor() as i64, sy.floor() as i64);
+        let (fx, fy) = (sx - x0 as f64, sy - y0 as f64);
+        let mut pixel = [0u8; 3];
+        for (c, value) in pixel.iter_mut().enumerate() {
+            let top = channel(x0, y0, c) * (1.0 - fx) + channel(x0 + 1, y0, c) * fx;
+            let bottom = channel(x0, y0 + 1, c) * (1.0 - fx) + channel(x0 + 1, y0 + 1, c) * fx;
+            *value = (top * (1.0 - fy) + bottom * fy).round().clamp(0.0, 255.0) as u8;
+        }
+        Rgb(pixel)
+    })
+}
+
+/// The projective transform taking each point of `from` to the matching point
+/// of `to`, as the eight coefficients of its matrix with the last one fixed to 1.
+fn homography(from: &Quad, to: &Quad) -> Option<[f64; 8]> {
+    let mut rows = [[0f64; 9]; 8];
+    for (i, (&(u, v), &(x, y))) in from.iter().zip(to).enumerate() {
+        let (u, v, x, y) = (u as f64, v as f64, x as f64, y as f64);
+        rows[2 * i] = [u, v, 1.0, 0.0, 0.0, 0.0, -u * x, -v * x, x];
+        rows[2 * i + 1] = [0.0, 0.0, 0.0, u, v, 1.0, -u * y, -v * y, y];
+    }
+    // Gaussian elimination with partial pivoting.
+    for column in 0..8 {
+        let pivot = (column..8).max_by(|&a, &b| rows[a][column].abs().total_cmp(&rows[b][column].abs()))?;
+        if rows[pivot][column].abs() < 1e-9 {
+            return None;
+        }
+        rows.swap(column, pivot);
+        let pivot_row = rows[column];
+        for (i, row) in rows.iter_mut().enumerate() {
+            if i != column {
+                let factor = row[column] / pivot_row[column];
+                for (value, pivot_value) in row.iter_mut().zip(pivot_row).skip(column) {
+                    *value -= factor * pivot_value;
+                }
+            }
+        }
+    }
+    let mut h = [0f64; 8];
+    for (i, value) in h.iter_mut().enumerate() {
+        *value = rows[i][8] / rows[i][i];
+    }
+    Some(h)
+}
+
+/// Maps a point through a homography from [`homography`].
+fn apply(h: &[f64; 8], u: f64, v: f64) -> (f64, f64) {
+    let w = h[6] * u + h[7] * v + 1.0;
+    ((h[0] * u + h[1] * v + h[2]) / w, (h[3] * u + h[4] * v + h[5]) / w)
+}
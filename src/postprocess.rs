@@ -0,0 +1,80 @@
+//! Post-processing Module
+//!
+//! Handles the optional post-processing of the OCR text: a
+//! [`TextPostProcessor`] cleans each page's text of recognition noise and can
+//! summarize it, and the results are kept next to the raw text in `index.json`.
+//! Backends are pluggable; an OpenAI-compatible one lives in the `llm` module.
+
+use anyhow::Result;
+use log::{info, warn};
+
+/// A stage that rewrites the OCR text of a page.
+pub trait TextPostProcessor {
+    /// Returns `text` with recognition noise removed and line-broken words rejoined.
+    fn clean(&self, text: &str) -> Result<String>;
+    /// Returns a short summary of a page's cleaned text.
+    fn summarize(&self, text: &str) -> Result<String>;
+}
+
+/// What post-processing made of one page's text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcessedText {
+    pub clean_text: String,
+    /// Summary of the cleaned text, if summaries were requested.
+    pub summary: Option<String>,
+}
+
+/// Rejoins words hyphenated across lines (`recog-\nnition rate` becomes
+/// `recognition\nrate`) when the next line continues in lowercase, so a
+/// backend sees whole words. Hyphens before an uppercase letter or a digit are kept.
+pub fn dehyphenate(text: &str) -> String {
+    let mut lines: Vec<String> = text.lines().map(str::to_string).collect();
+    // Lines left empty by moving their only word up, dropped at the end.
+    let mut emptied = vec![false; lines.len()];
+    for i in 0..lines.len().saturating_sub(1) {
+        let next = lines[i + 1].trim_start();
+        let Some(head) = lines[i].strip_suffix('-') else { continue };
+        if !head.ends_with(char::is_alphabetic) || !next.starts_with(char::is_lowercase) {
+            continue;
+        }
+        let (tail, rest) = next.split_at(next.find(char::is_whitespace).unwrap_or(next.len()));
+        let (joined, rest) = (format!("{}{}", head, tail), rest.trim_start().to_string());
+        emptied[i + 1] = rest.is_empty();
+        lines[i] = joined;
+        lines[i + 1] = rest;
+    }
+    lines
+        .into_iter()
+        .zip(emptied)
+        .filter(|(_, emptied)| !emptied)
+        .map(|(line, _)| line)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Runs `processor` over the OCR text of every page. Pages without text are
+/// skipped, and a page the backend fails on is skipped with a warning, so one
+/// failed request does not lose the rest of the run.
+pub fn process_pages(
+    processor: &dyn TextPostProcessor,
+    texts: &[String],
+    summarize: bool,
+) -> Vec<Option<ProcessedText>> {
+    texts
+        .iter()
+        .enumerate()
+        .map(|(i, text)| {
+            if text.trim().is_empty() {
+                return None;
+            }
+            info!("Post-processing the text of page {} of {}...", i + 1, texts.len());
+            let processed = processor.clean(&dehyphenate(text)).and_then(|clean_text| {
+                let summary = if summarize { Some(processor.summarize(&clean_text)?) } else { None };
+                Ok(ProcessedText { clean_text, summary })
+            });
+            processed
+                .inspect_err(|e| warn!("Could not post-process the text of page {}: {:#}", i + 1, e))
+                .ok()
+        })
+        .collect()
+}
@@ -0,0 +1,307 @@
+//! Preprocess Module
+//!
+//! Handles cleaning up frames before they are handed to Tesseract, for footage
+//! such as phone-camera recordings of whiteboards: local contrast equalization
+//! (CLAHE), median denoising, deskewing by projection profile, and adaptive
+//! thresholding. Each step is optional and they always run in that order. Only
+//! the image given to the OCR engine changes; the page images keep the frame
+//! as recorded, and word boxes found on a deskewed image are mapped back onto it.
+
+use image::{GrayImage, ImageBuffer, Luma, Rgb};
+
+/// Names of the steps, in the order they run.
+pub const STEPS: [&str; 4] = ["contrast", "denoise", "deskew", "binarize"];
+/// Number of tiles CLAHE splits each side of the image into.
+const CLAHE_TILES: u32 = 8;
+/// How many times the average bin count a CLAHE histogram bin may hold before
+/// the excess is spread over all bins, which limits how far noise is amplified.
+const CLAHE_CLIP_LIMIT: f32 = 2.0;
+/// Largest skew searched for, in degrees either way.
+const MAX_SKEW_DEGREES: f32 = 10.0;
+/// Skews smaller than this, in degrees, are left alone.
+const MIN_SKEW_DEGREES: f32 = 0.1;
+/// Most ink pixels sampled when estimating the skew.
+const MAX_SKEW_SAMPLES: usize = 20_000;
+/// Amount, in grey levels, a pixel must be darker than its neighbourhood's mean
+/// to become ink when binarizing.
+const THRESHOLD_OFFSET: f32 = 10.0;
+
+/// A frame prepared for OCR.
+pub struct Prepared {
+    pub image: GrayImage,
+    /// Angle, in degrees, the frame was rotated by to straighten its text; zero
+    /// when it was not deskewed.
+    pub skew: f32,
+}
+
+/// Runs the selected `steps` (names from [`STEPS`]) over a frame.
+pub fn prepare(frame: &ImageBuffer<Rgb<u8>, Vec<u8>>, steps: &[String]) -> Prepared {
+    let enabled = |step: &str| steps.iter().any(|s| s == step);
+    let mut image = image::imageops::grayscale(frame);
+    if enabled("contrast") {
+        image = equalize_contrast(&image);
+    }
+    if enabled("denoise") {
+        image = denoise(&image);
+    }
+    let mut skew = 0.0;
+    if enabled("deskew") {
+        skew = estimate_skew(&image);
+        if skew != 0.0 {
+            image = rotate(&image, skew);
+        }
+    }
+    if enabled("binarize") {
+        image = binarize(&image);
+    }
+    Prepared { image, skew }
+}
+
+/// Contrast Limited Adaptive Histogram Equalization: equalizes the histogram of
+/// each tile, clipped so flat areas are not turned into noise, and blends the
+/// mappings of neighbouring tiles so no tile edges show.
+pub fn equalize_contrast(image: &GrayImage) -> GrayImage {
+    let (width, height) = image.dimensions();
+    if width == 0 || height == 0 {
+        return image.clone();
+    }
+    let (tiles_x, tiles_y) = (CLAHE_TILES.min(width), CLAHE_TILES.min(height));
+    let (tile_w, tile_h) = (width.div_ceil(tiles_x), height.div_ceil(tiles_y));
+    let mut maps = Vec::with_capacity((tiles_x * tiles_y) as usize);
+    for ty in 0..tiles_y {
+        for tx in 0..tiles_x {
+            let (x1, y1) = (tx * tile_w, ty * tile_h);
+            let (x2, y2) = ((x1 + tile_w).min(width), (y1 + tile_h).min(height));
+            let mut histogram = [0u32; 256];
+            for y in y1..y2 {
+                for x in x1..x2 {
+                    histogram[image.get_pixel(x, y)[0] as usize] += 1;
+                }
+            }
+            maps.push(clipped_mapping(&mut histogram, (x2 - x1) * (y2 - y1)));
+        }
+    }
+
+    // Position of a pixel among the tile centres: the tile before it and the weight of the one after.
+    let locate = |v: u32, size: u32, tiles: u32| {
+        let f = ((v as f32 + 0.5) / size as f32 - 0.5).max(0.0);
+        let before = (f as u32).min(tiles - 1);
+        (before, (before + 1).min(tiles - 1), (f - before as f32).clamp(0.0, 1.0))
+    };
+    ImageBuffer::from_fn(width, height, |x, y| {
+        let (x0, x1, wx) = locate(x, tile_w, tiles_x);
+        let (y0, y1, wy) = locate(y, tile_h, tiles_y);
+        let value = image.get_pixel(x, y)[0] as usize;
+        let map = |tx: u32, ty: u32| maps[(ty * tiles_x + tx) as usize][value] as f32;
+        let top = map(x0, y0) * (1.0 - wx) + map(x1, y0) * wx;
+        let bottom = map(x0, y1) * (1.0 - wx) + map(x1, y1) * wx;
+        Luma([(top * (1.0 - wy) + bottom * wy).round() as u8])
+    })
+}
+
+/// Grey level mapping of one CLAHE tile from its histogram.
+fn clipped_mapping(histogram: &mut [u32; 256], pixels: u32) -> [u8; 256] {
+    let limit = ((CLAHE_CLIP_LIMIT * pixels as f32 / 256.0) as u32).max(1);
+    let mut excess = 0;
+    for count in histogram.iter_mut() {
+        if *count > limit {
+            excess += *count - limit;
+            *count = limit;
+        }
+    }
+    let (share, remainder) = (excess / 256, (excess % 256) as usize);
+    for (i, count) in histogram.iter_mut().enumerate() {
+        *count += share + u32::from(i < remainder);
+    }
+    let mut mapping = [0u8; 256];
+    let mut cumulative = 0u64;
+    for (value, count) in histogram.iter().enumerate() {
+        cumulative += u64::from(*count);
+        mapping[value] = (cumulative * 255 / u64::from(pixels.max(1))).min(255) as u8;
+    }
+    mapping
+}
+
+/// Replaces every pixel with the median of its 3×3 neighbourhood, which removes
+/// speckles while keeping the edges of strokes.
+pub fn denoise(image: &GrayImage) -> GrayImage {
+    let (width, height) = image.dimensions();
+    ImageBuffer::from_fn(width, height, |x, y| {
+        let mut window = [0u8; 9];
+        let mut i = 0;
+        for dy in -1i64..=1 {
+            for dx in -1i64..=1 {
+                let nx = (x as i64 + dx).clamp(0, width as i64 - 1) as u32;
+                let ny = (y as i64 + dy).clamp(0, height as i64 - 1) as u32;
+                window[i] = image.get_pixel(nx, ny)[0];
+                i += 1;
+            }
+        }
+        window.sort_unstable();
+        Luma([window[4]])
+    })
+}
+
+/// Otsu's threshold: the grey level that best separates the image into two classes.
+pub fn otsu_threshold(image: &GrayImage) -> u8 {
+    let mut histogram = [0u64; 256];
+    for pixel in image.pixels() {
+        histogram[pixel[0] as usize] += 1;
+    }
+    let total: u64 = histogram.iter().sum();
+    let sum: f64 = histogram.iter().enumerate().map(|(v, &c)| v as f64 * c as f64).sum();
+    let (mut weight_below, mut sum_below) = (0u64, 0f64);
+    let (mut best, mut best_variance) = (0u8, -1f64);
+    for (value, &count) in histogram.iter().enumerate() {
+        weight_below += count;
+        sum_below += value as f64 * count as f64;
+        let weight_above = total - weight_below;
+        if weight_below == 0 || weight_above == 0 {
+            continue;
+        }
+        let mean_below = sum_below / weight_below as f64;
+        let mean_above = (sum - sum_below) / weight_above as f64;
+        let variance = weight_below as f64 * weight_above as f64 * (mean_below - mean_above).powi(2);
+        if variance > best_variance {
+            (best, best_variance) = (value as u8, variance);
+        }
+    }
+    best
+}
+
+/// Whether the image is light writing on a dark background: the side of the
+/// Otsu threshold holding fewer pixels is the ink.
+fn is_dark_background(image: &GrayImage) -> bool {
+    let threshold = otsu_threshold(image);
+    let dark = image.pixels().filter(|pixel| pixel[0] <= threshold).count();
+    dark * 2 > image.pixels().len()
+}
+
+/// Estimates, in degrees, how far the text lines are rotated, by finding the
+/// angle whose horizontal projection of the ink has the sharpest peaks (each
+/// line of text falling into as few rows as possible). Returns zero when there
+/// is too little ink or the skew is negligible.
+pub fn estimate_skew(image: &GrayImage) -> f32 {
+    let (width, height) = image.dimensions();
+    let threshold = otsu_threshold(image);
+    let dark_background = is_dark_background(image);
+    let ink: Vec<(f32, f32)> = image
+        .enumerate_pixels()
+        .filter(|(_, _, pixel)| (pixel[0] > threshold) == dark_background)
+        .map(|(x, y, _)| (x as f32, y as f32))
+        .collect();
+    if ink.len() < 50 {
+        return 0.0;
+    }
+    let step = ink.len().div_ceil(MAX_SKEW_SAMPLES);
+    let samples: Vec<(f32, f32)> = ink.into_iter().step_by(step).collect();
+    let (cx, cy) = (width as f32 / 2.0, height as f32 / 2.0);
+    let reach = (cx.hypot(cy)).ceil();
+    let score = |degrees: f32| {
+        let (sin, cos) = (-degrees).to_radians().sin_cos();
+        let rows = 2 * reach as usize + 2;
+        let last = rows as f32 - 1.0;
+        // Each sample is shared between the two rows nearest to it, so the score changes smoothly with the angle.
+        let mut rows = vec![0f32; rows];
+        for &(x, y) in &samples {
+            let offset = ((x - cx) * sin + (y - cy) * cos + reach).clamp(0.0, last - 1.0);
+            let (row, weight) = (offset.floor() as usize, offset.fract());
+            rows[row] += 1.0 - weight;
+            rows[row + 1] += weight;
+        }
+        rows.iter().map(|&count| count * count).sum::<f32>()
+    };
+    let best_of = |angles: &mut dyn Iterator<Item = f32>| {
+        angles.map(|a| (a, score(a))).fold((0.0, 0.0), |best, cur| if cur.1 > best.1 { cur } else { best }).0
+    };
+    let steps = (MAX_SKEW_DEGREES * 2.0) as i32;
+    let coarse = best_of(&mut (-steps..=steps).map(|i| i as f32 * 0.5));
+    let fine = best_of(&mut (-5..=5).map(|i| coarse + i as f32 * 0.1));
+    if fine.abs() < MIN_SKEW_DEGREES { 0.0 } else { fine }
+}
+
+/// Maps a point of an image rotated by `degrees` around its centre, as done by
+/// [`rotate`], back onto the original image.
+fn source_point(x: f32, y: f32, cx: f32, cy: f32, degrees: f32) -> (f32, f32) {
+    let (sin, cos) = degrees.to_radians().sin_cos();
+    let (dx, dy) = (x - cx, y - cy);
+    (cx + dx * cos - dy * sin, cy + dx * sin + dy * cos)
+}
+
+/// Rotates the image around its centre so text skewed by `degrees` becomes
+/// level, filling the uncovered corners with the background.
+pub fn rotate(image: &GrayImage, degrees: f32) -> GrayImage {
+    let (width, height) = image.dimensions();
+    let (cx, cy) = (width as f32 / 2.0, height as f32 / 2.0);
+    let background = if is_dark_background(image) { 0.0 } else { 255.0 };
+    let value = |x: i64, y: i64| {
+        if x < 0 || y < 0 || x >= width as i64 || y >= height as i64 {
+            background
+        } else {
+            image.get_pixel(x as u32, y as u32)[0] as f32
+        }
+    };
+    ImageBuffer::from_fn(width, height, |x, y| {
+        let (sx, sy) = source_point(x as f32 + 0.5, y as f32 + 0.5, cx, cy, degrees);
+        let (sx, sy) = (sx - 0.5, sy - 0.5);
+        let (x0, y0) = (sx.floor() as i64, sy.floor() as i64);
+        let (fx, fy) = (sx - x0 as f32, sy - y0 as f32);
+        let top = value(x0, y0) * (1.0 - fx) + value(x0 + 1, y0) * fx;
+        let bottom = value(x0, y0 + 1) * (1.0 - fx) + value(x0 + 1, y0 + 1) * fx;
+        Luma([(top * (1.0 - fy) + bottom * fy).round() as u8])
+    })
+}
+
+/// Maps a word box found on a frame rotated by `degrees` back onto the frame
+/// as recorded: the box around its rotated corners, kept within the frame.
+pub fn unrotate_bbox(bbox: (i32, i32, i32, i32), degrees: f32, width: u32, height: u32) -> (i32, i32, i32, i32) {
+    if degrees == 0.0 {
+        return bbox;
+    }
+    let (cx, cy) = (width as f32 / 2.0, height as f32 / 2.0);
+    let (x1, y1, x2, y2) = (bbox.0 as f32, bbox.1 as f32, bbox.2 as f32, bbox.3 as f32);
+    let corners = [(x1, y1), (x2, y1), (x1, y2), (x2, y2)].map(|(x, y)| source_point(x, y, cx, cy, degrees));
+    let min = |pick: fn(&(f32, f32)) -> f32| corners.iter().map(pick).fold(f32::MAX, f32::min);
+    let max = |pick: fn(&(f32, f32)) -> f32| corners.iter().map(pick).fold(f32::MIN, f32::max);
+    (
+        (min(|c| c.0).floor() as i32).clamp(0, width as i32),
+        (min(|c| c.1).floor() as i32).clamp(0, height as i32),
+        (max(|c| c.0).ceil() as i32).clamp(0, width as i32),
+        (max(|c| c.1).ceil() as i32).clamp(0, height as i32),
+    )
+}
+
+/// Adaptive thresholding: a pixel becomes black ink when it is clearly darker
+/// than the mean of its neighbourhood, so uneven lighting across a whiteboard
+/// does not swallow the writing. Light writing on a dark background is inverted
+/// first, giving dark text on white as Tesseract expects.
+pub fn binarize(image: &GrayImage) -> GrayImage {
+    let (width, height) = image.dimensions();
+    if width == 0 || height == 0 {
+        return image.clone();
+    }
+    let invert = is_dark_background(image);
+    let level = |x: u32, y: u32| {
+        let value = image.get_pixel(x, y)[0];
+        u64::from(if invert { 255 - value } else { value })
+    };
+    // Summed-area table with a zero row and column in front.
+    let stride = width as usize + 1;
+    let mut sums = vec![0u64; stride * (height as usize + 1)];
+    for y in 0..height as usize {
+        let mut row = 0;
+        for x in 0..width as usize {
+            row += level(x as u32, y as u32);
+            sums[(y + 1) * stride + x + 1] = sums[y * stride + x + 1] + row;
+        }
+    }
+    let radius = (width.min(height) / 40).max(7) as i64;
+    ImageBuffer::from_fn(width, height, |x, y| {
+        let (x1, y1) = ((x as i64 - radius).max(0) as usize, (y as i64 - radius).max(0) as usize);
+        let (x2, y2) =
+            ((x as i64 + radius + 1).min(width as i64) as usize, (y as i64 + radius + 1).min(height as i64) as usize);
+        let sum = sums[y2 * stride + x2] + sums[y1 * stride + x1] - sums[y1 * stride + x2] - sums[y2 * stride + x1];
+        let mean = sum as f32 / ((x2 - x1) * (y2 - y1)) as f32;
+        Luma([if (level(x, y) as f32) < mean - THRESHOLD_OFFSET { 0 } else { 255 }])
+    })
+}
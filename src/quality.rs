@@ -0,0 +1,72 @@
+//! Quality Module
+//!
+//! Handles scoring how well a frame shows its slide, for keeping the best
+//! frame of each stable segment rather than the first, which is often caught
+//! mid-transition or blurred by the camera moving. Sharpness is measured as
+//! the variance of the Laplacian of the frame's brightness, which crisp edges
+//! raise and blur lowers; frames that are badly exposed score nothing.
+
+use image::{DynamicImage, GrayImage};
+
+/// Cache kind used for the persisted per-frame quality series.
+pub const QUALITY_SERIES_KIND: &str = "quality_series";
+/// Mean brightness under which a frame is too dark to use, as in a fade to black.
+const DARK_LEVEL: f32 = 24.0;
+/// Least spread between the darkest and brightest pixels of a frame, under
+/// which it is washed out or blank.
+const MIN_CONTRAST: u8 = 32;
+/// Share, in thousandths, of a frame's pixels left out at each end when
+/// measuring its contrast, so stray pixels do not count but the few strokes of
+/// a slide with little text do.
+const CONTRAST_TAIL: usize = 5;
+
+/// Variance of the 4-neighbour Laplacian of an image's brightness.
+pub fn laplacian_variance(gray: &GrayImage) -> f32 {
+    let (width, height) = gray.dimensions();
+    if width < 3 || height < 3 {
+        return 0.0;
+    }
+    let at = |x: u32, y: u32| gray.get_pixel(x, y).0[0] as f32;
+    let (mut sum, mut sum_sq) = (0.0f64, 0.0f64);
+    for y in 1..height - 1 {
+        for x in 1..width - 1 {
+            let laplacian = (at(x - 1, y) + at(x + 1, y) + at(x, y - 1) + at(x, y + 1) - 4.0 * at(x, y)) as f64;
+            sum += laplacian;
+            sum_sq += laplacian * laplacian;
+        }
+    }
+    let count = ((width - 2) * (height - 2)) as f64;
+    let mean = sum / count;
+    (sum_sq / count - mean * mean) as f32
+}
+
+/// Whether a frame is lit well enough to read: not too dark on average, and
+/// with enough contrast between its darkest and brightest pixels.
+pub fn well_exposed(gray: &GrayImage) -> bool {
+    let mut histogram = [0usize; 256];
+    for pixel in gray.pixels() {
+        histogram[pixel.0[0] as usize] += 1;
+    }
+    let count = gray.pixels().len();
+    if count == 0 {
+        return false;
+    }
+    let mean = histogram.iter().enumerate().map(|(level, &n)| level * n).sum::<usize>() as f32 / count as f32;
+    let quantile = |per_mille: usize| {
+        let wanted = count * per_mille / 1000;
+        let mut seen = 0;
+        histogram.iter().position(|&n| {
+            seen += n;
+            seen > wanted
+        })
+    };
+    let (low, high) = (quantile(CONTRAST_TAIL).unwrap_or(0), quantile(1000 - CONTRAST_TAIL).unwrap_or(255));
+    mean >= DARK_LEVEL && high.saturating_sub(low) >= MIN_CONTRAST as usize
+}
+
+/// Scores how well a frame shows its slide: its [`laplacian_variance`], or 0
+/// when it is not [`well_exposed`].
+pub fn score(frame: &DynamicImage) -> f32 {
+    let gray = frame.to_luma8();
+    if well_exposed(&gray) { laplacian_variance(&gray) } else { 0.0 }
+}
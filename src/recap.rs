@@ -0,0 +1,89 @@
+//! Recap Video Module
+//!
+//! Handles encoding the kept pages into a short summary video, each page shown
+//! for a fixed time, as a fast visual recap of the recording.
+
+use crate::document_builder::{self, Page};
+use crate::overlay;
+use crate::video_processor::VideoEncoder;
+use anyhow::{anyhow, Result};
+use image::imageops::{self, FilterType};
+use image::{ImageBuffer, Rgb};
+use log::info;
+use std::path::Path;
+
+// Frame rate of the recap. Pages are still images, so a low rate keeps files small
+// while still allowing page durations in steps of a tenth of a second.
+const RECAP_FPS: i32 = 10;
+
+/// Options for the recap video.
+#[derive(Debug, Clone)]
+pub struct RecapOptions {
+    /// How long each page stays on screen, in seconds.
+    pub page_seconds: f64,
+    /// Burn each page's source timestamp into its bottom-left corner.
+    pub burn_timestamps: bool,
+}
+
+/// Encodes the pages into a video at `output_path`; the container follows its extension.
+///
+/// All pages are letterboxed onto a canvas the size of the first page.
+pub fn build_recap_video(pages: &[Page], options: &RecapOptions, output_path: &Path) -> Result<()> {
+    let first = pages.first().ok_or_else(|| anyhow!("No pages to encode"))?;
+    // YUV 4:2:0 needs even dimensions.
+    let width = first.image.width() & !1;
+    let height = first.image.height() & !1;
+    let repeats = ((options.page_seconds * RECAP_FPS as f64).round() as usize).max(1);
+
+    let mut encoder = VideoEncoder::create(output_path, width, height, RECAP_FPS)?;
+    for page in pages {
+        let canvas = render_page(page, options, width, height);
+        for _ in 0..repeats {
+            encoder.push_frame(&canvas)?;
+        }
+    }
+    encoder.finish()?;
+
+    info!(
+        "Recap video with {} pages ({:.1}s each) written to {:?}",
+        pages.len(),
+        repeats as f64 / RECAP_FPS as f64,
+        output_path
+    );
+    Ok(())
+}
+
+/// Draws the frame of the recap showing `page`: its image letterboxed onto a
+/// `width` x `height` canvas, with the time the page appeared in the source
+/// video burned in when asked for and known.
+pub fn render_page(page: &Page, options: &RecapOptions, width: u32, height: u32) -> ImageBuffer<Rgb<u8>, Vec<u8>> {
+    let mut canvas = fit_to_canvas(page.image, width, height);
+    if let Some(timestamp) = page.timestamp.filter(|_| options.burn_timestamps) {
+        overlay::draw_label(&mut canvas, &document_builder::format_timestamp(timestamp));
+    }
+    canvas
+}
+
+/// Scales an image to fit inside `width` x `height`, centred on a black background.
+fn fit_to_canvas(
+    image: &ImageBuffer<Rgb<u8>, Vec<u8>>,
+    width: u32,
+    height: u32,
+) -> ImageBuffer<Rgb<u8>, Vec<u8>> {
+    if image.dimensions() == (width, height) {
+        return image.clone();
+    }
+    let scale = (width as f32 / image.width() as f32).min(height as f32 / image.height() as f32);
+    let scaled_width = ((image.width() as f32 * scale).round() as u32).clamp(1, width);
+    let scaled_height = ((image.height() as f32 * scale).round() as u32).clamp(1, height);
+    let scaled = imageops::resize(image, scaled_width, scaled_height, FilterType::Triangle);
+
+    let mut canvas = ImageBuffer::from_pixel(width, height, Rgb([0, 0, 0]));
+    imageops::overlay(
+        &mut canvas,
+        &scaled,
+        ((width - scaled_width) / 2) as i64,
+        ((height - scaled_height) / 2) as i64,
+    );
+    canvas
+}
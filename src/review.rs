@@ -0,0 +1,267 @@
+//! Review Module
+//!
+//! Handles the optional review report: every OCR word recognized below a
+//! confidence threshold, listed page by page next to a crop of the frame around
+//! its bounding box, so a human can proofread the dubious words without
+//! rereading the whole document. The report is written as Markdown with the
+//! crops as PNG files, or as a single PDF.
+
+use crate::document_builder::{A4_HEIGHT_PT, A4_WIDTH_PT, Page, escape_markdown, format_timestamp};
+use crate::ocr::OcrWord;
+use crate::pdf_toc::{encode_win_ansi, truncate};
+use anyhow::{Context, Result};
+use image::{ImageBuffer, Rgb, imageops};
+use pdf_writer::{Content, Filter, Finish, Name, Pdf, Rect, Ref, Str};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+/// Directory of the Markdown report, relative to the output directory.
+pub const REVIEW_DIR: &str = "review";
+/// File name of the Markdown report inside [`REVIEW_DIR`].
+pub const REVIEW_MARKDOWN: &str = "review.md";
+/// File name of the PDF report, relative to the output directory.
+pub const REVIEW_PDF: &str = "review.pdf";
+/// Default confidence (0-100) below which a word is listed.
+pub const DEFAULT_THRESHOLD: f32 = 60.0;
+// Pixels of the frame kept around each word, so its neighbours give context.
+const CROP_MARGIN: u32 = 12;
+
+const MARGIN_PT: f32 = 56.0;
+const HEADING_SIZE: f32 = 18.0;
+const TEXT_SIZE: f32 = 11.0;
+const ROW_HEIGHT: f32 = 48.0;
+// Largest size of a crop on the PDF page.
+const CROP_MAX_WIDTH_PT: f32 = 220.0;
+const CROP_MAX_HEIGHT_PT: f32 = 40.0;
+// Approximate Helvetica advance as a fraction of the font size.
+const AVG_CHAR_ADVANCE: f32 = 0.5;
+const FONT: Name<'static> = Name(b"Helvetica");
+
+/// Report file format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReviewFormat {
+    Markdown,
+    Pdf,
+}
+
+/// Options for the review report.
+#[derive(Debug, Clone)]
+pub struct ReviewOptions {
+    pub format: ReviewFormat,
+    /// Words below this confidence (0-100) are listed.
+    pub threshold: f32,
+    /// Title of the whole document, shown in the report heading.
+    pub document_title: String,
+    /// Path of the report relative to the output directory, without extension,
+    /// from `--name-template`. By default the report is written to
+    /// `review/review.md` or `review.pdf`.
+    pub name: Option<String>,
+}
+
+/// A word to proofread and where it was found.
+struct Entry<'a> {
+    page: usize,
+    word: &'a OcrWord,
+    crop: ImageBuffer<Rgb<u8>, Vec<u8>>,
+}
+
+/// Writes the report to `base_dir` and returns its path relative to it, with
+/// the number of words listed.
+pub fn build_review(pages: &[Page], options: &ReviewOptions, base_dir: &Path) -> Result<(String, usize)> {
+    let entries = low_confidence_words(pages, options.threshold);
+    let file = match (options.format, &options.name) {
+        (ReviewFormat::Markdown, Some(name)) => format!("{}.md", name),
+        (ReviewFormat::Markdown, None) => format!("{}/{}", REVIEW_DIR, REVIEW_MARKDOWN),
+        (ReviewFormat::Pdf, Some(name)) => format!("{}.pdf", name),
+        (ReviewFormat::Pdf, None) => REVIEW_PDF.to_string(),
+    };
+    let path = base_dir.join(&file);
+    let dir = path.parent().unwrap_or(base_dir);
+    match options.format {
+        ReviewFormat::Markdown => {
+            fs::create_dir_all(dir.join("crops"))
+                .with_context(|| format!("Failed to create review directory {:?}", dir))?;
+            let markdown = markdown(pages, &entries, options, dir)?;
+            fs::write(&path, markdown).with_context(|| format!("Failed to write review to {:?}", path))?;
+        }
+        ReviewFormat::Pdf => {
+            fs::create_dir_all(dir).with_context(|| format!("Failed to create review directory {:?}", dir))?;
+            fs::write(&path, pdf(pages, &entries, options))
+                .with_context(|| format!("Failed to write review to {:?}", path))?;
+        }
+    }
+    Ok((file, entries.len()))
+}
+
+/// Collects the words below `threshold`, in page and reading order, each with
+/// its crop of the frame.
+fn low_confidence_words<'a>(pages: &[Page<'a>], threshold: f32) -> Vec<Entry<'a>> {
+    let mut entries = Vec::new();
+    for (i, page) in pages.iter().enumerate() {
+        let Some(ocr) = page.ocr else { continue };
+        for word in &ocr.words {
+            if word.confidence >= threshold || word.text.trim().is_empty() {
+                continue;
+            }
+            entries.push(Entry { page: i, word, crop: crop(page.image, word.bbox) });
+        }
+    }
+    entries
+}
+
+/// Cuts the word's bounding box, grown by [`CROP_MARGIN`], out of the frame.
+fn crop(image: &ImageBuffer<Rgb<u8>, Vec<u8>>, bbox: (i32, i32, i32, i32)) -> ImageBuffer<Rgb<u8>, Vec<u8>> {
+    let (width, height) = image.dimensions();
+    let clamp = |value: i32, margin: i32, max: u32| (value + margin).clamp(0, max as i32) as u32;
+    let margin = CROP_MARGIN as i32;
+    let (x1, y1) = (clamp(bbox.0, -margin, width), clamp(bbox.1, -margin, height));
+    let (x2, y2) = (clamp(bbox.2, margin, width), clamp(bbox.3, margin, height));
+    let x = x1.min(width.saturating_sub(1));
+    let y = y1.min(height.saturating_sub(1));
+    imageops::crop_imm(image, x, y, x2.saturating_sub(x).max(1), y2.saturating_sub(y).max(1)).to_image()
+}
+
+/// Heading of the report section of page `index`.
+fn page_heading(pages: &[Page], index: usize) -> String {
+    let page = &pages[index];
+    match page.timestamp {
+        Some(timestamp) => {
+            format!("Page {} ({}): {}", index + 1, format_timestamp(timestamp), page.display_title(index))
+        }
+        None => format!("Page {}: {}", index + 1, page.display_title(index)),
+    }
+}
+
+fn summary(entries: &[Entry], threshold: f32) -> String {
+    let mut pages: Vec<usize> = entries.iter().map(|entry| entry.page).collect();
+    pages.dedup();
+    format!(
+        "{} words below {}% confidence on {} pages.",
+        entries.len(),
+        threshold,
+        pages.len()
+    )
+}
+
+/// Builds the Markdown report, saving the crops under `dir/crops`.
+fn markdown(pages: &[Page], entries: &[Entry], options: &ReviewOptions, dir: &Path) -> Result<String> {
+    let mut markdown = String::new();
+    writeln!(markdown, "# Review: {}\n", escape_markdown(&options.document_title))?;
+    writeln!(markdown, "{}\n", summary(entries, options.threshold))?;
+    for (n, entry) in entries.iter().enumerate() {
+        if n == 0 || entries[n - 1].page != entry.page {
+            writeln!(markdown, "## {}\n", escape_markdown(&page_heading(pages, entry.page)))?;
+            writeln!(markdown, "| Crop | Word | Confidence |\n| --- | --- | --- |")?;
+        }
+        let crop_file = format!("crops/word_{:05}.png", n);
+        let crop_path = dir.join(&crop_file);
+        entry
+            .crop
+            .save(&crop_path)
+            .with_context(|| format!("Failed to save word crop to {:?}", crop_path))?;
+        writeln!(
+            markdown,
+            "| ![{0}]({1}) | {0} | {2:.0}% |",
+            escape_markdown(&entry.word.text),
+            crop_file,
+            entry.word.confidence
+        )?;
+        if entries.get(n + 1).is_none_or(|next| next.page != entry.page) {
+            writeln!(markdown)?;
+        }
+    }
+    Ok(markdown)
+}
+
+/// Builds the PDF report: A4 pages with one row per word, its crop on the left
+/// and its text and confidence on the right, under a heading per source page.
+fn pdf(pages: &[Page], entries: &[Entry], options: &ReviewOptions) -> Vec<u8> {
+    let mut ref_counter = 1..;
+    let mut next_ref = || Ref::new(ref_counter.next().unwrap());
+    let catalog_ref = next_ref();
+    let page_tree_ref = next_ref();
+    let font_ref = next_ref();
+    let mut pdf = Pdf::new();
+    pdf.type1_font(font_ref).base_font(FONT).encoding_predefined(Name(b"WinAnsiEncoding"));
+
+    let max_chars = ((A4_WIDTH_PT - 2.0 * MARGIN_PT) / (TEXT_SIZE * AVG_CHAR_ADVANCE)) as usize;
+    let text_x = MARGIN_PT + CROP_MAX_WIDTH_PT + 16.0;
+    // Content of every report page with the crops it shows.
+    let mut report_pages = vec![(Content::new(), Vec::<(String, Ref)>::new())];
+    let mut y = A4_HEIGHT_PT - MARGIN_PT - HEADING_SIZE;
+    let (content, _) = &mut report_pages[0];
+    let heading = format!("Review: {}", options.document_title);
+    show(content, HEADING_SIZE, MARGIN_PT, y, &truncate(&heading, max_chars));
+    y -= 2.0 * TEXT_SIZE;
+    show(content, TEXT_SIZE, MARGIN_PT, y, &summary(entries, options.threshold));
+    y -= TEXT_SIZE;
+
+    for (n, entry) in entries.iter().enumerate() {
+        let starts_page = n == 0 || entries[n - 1].page != entry.page;
+        let needed = ROW_HEIGHT + if starts_page { 2.0 * TEXT_SIZE } else { 0.0 };
+        if y - needed < MARGIN_PT {
+            report_pages.push((Content::new(), Vec::new()));
+            y = A4_HEIGHT_PT - MARGIN_PT;
+        }
+        let (content, images) = report_pages.last_mut().unwrap();
+        if starts_page {
+            y -= 2.0 * TEXT_SIZE;
+            show(content, TEXT_SIZE, MARGIN_PT, y, &truncate(&page_heading(pages, entry.page), max_chars));
+        }
+        y -= ROW_HEIGHT;
+
+        let (width, height) = entry.crop.dimensions();
+        let scale = (CROP_MAX_WIDTH_PT / width as f32).min(CROP_MAX_HEIGHT_PT / height as f32);
+        let (crop_width, crop_height) = (width as f32 * scale, height as f32 * scale);
+        let image_ref = next_ref();
+        let compressed = miniz_oxide::deflate::compress_to_vec_zlib(entry.crop.as_raw(), 6);
+        let mut image = pdf.image_xobject(image_ref, &compressed);
+        image.filter(Filter::FlateDecode);
+        image.width(width as i32);
+        image.height(height as i32);
+        image.color_space().device_rgb();
+        image.bits_per_component(8);
+        image.finish();
+
+        let name = format!("Crop{}", n);
+        let crop_y = y + (ROW_HEIGHT - crop_height) / 2.0;
+        content.save_state();
+        content.transform([crop_width, 0.0, 0.0, crop_height, MARGIN_PT, crop_y]);
+        content.x_object(Name(name.as_bytes()));
+        content.restore_state();
+        images.push((name, image_ref));
+
+        let label = format!("{}   ({:.0}%)", entry.word.text, entry.word.confidence);
+        show(content, TEXT_SIZE, text_x, y + (ROW_HEIGHT - TEXT_SIZE) / 2.0, &truncate(&label, max_chars / 2));
+    }
+
+    let page_refs: Vec<Ref> = report_pages.iter().map(|_| next_ref()).collect();
+    for ((content, images), &page_ref) in report_pages.into_iter().zip(&page_refs) {
+        let content_ref = next_ref();
+        pdf.stream(content_ref, &content.finish());
+        let mut page = pdf.page(page_ref);
+        page.media_box(Rect::new(0.0, 0.0, A4_WIDTH_PT, A4_HEIGHT_PT));
+        page.parent(page_tree_ref);
+        page.contents(content_ref);
+        let mut resources = page.resources();
+        resources.fonts().pair(FONT, font_ref);
+        resources
+            .x_objects()
+            .pairs(images.iter().map(|(name, image_ref)| (Name(name.as_bytes()), *image_ref)));
+        resources.finish();
+        page.finish();
+    }
+    pdf.pages(page_tree_ref).kids(page_refs.iter().copied()).count(page_refs.len() as i32);
+    pdf.catalog(catalog_ref).pages(page_tree_ref);
+    pdf.finish()
+}
+
+/// Shows one line of text in the standard font with its baseline at `y`.
+fn show(content: &mut Content, size: f32, x: f32, y: f32, text: &str) {
+    content.begin_text();
+    content.set_font(FONT, size);
+    content.set_text_matrix([1.0, 0.0, 0.0, 1.0, x, y]);
+    content.show(Str(&encode_win_ansi(text)));
+    content.end_text();
+}
@@ -0,0 +1,102 @@
+//! Run Manifest Module
+//!
+//! Handles `run.json`, the provenance record written to the output directory
+//! at the end of every run: the tool version, the effective configuration, a
+//! SHA-256 of the input video, the time spent in each stage, and every file
+//! the run produced with its size and SHA-256, for reproducible research and
+//! audit trails.
+
+use crate::paths;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::io::{self, BufReader};
+use std::path::Path;
+
+/// File name of the manifest in the output directory.
+pub const RUN_MANIFEST_NAME: &str = "run.json";
+/// Version of the manifest layout, bumped whenever a field changes meaning.
+pub const MANIFEST_VERSION: u32 = 1;
+
+/// Provenance of one run.
+#[derive(Debug, Serialize)]
+pub struct RunManifest {
+    pub manifest_version: u32,
+    pub tool: String,
+    pub tool_version: String,
+    /// Start of the run as an ISO 8601 UTC timestamp.
+    pub started: String,
+    /// The effective configuration, with passwords redacted.
+    pub config: Value,
+    pub input: FileRecord,
+    /// Pages in the output; the OCR report numbers them from 0 as `frame_index`.
+    pub pages: usize,
+    /// Wall-clock time of each stage, in the order they ran.
+    pub stages: Vec<StageTiming>,
+    pub total_seconds: f64,
+    /// Every file the run produced, relative to the output directory.
+    pub artifacts: Vec<FileRecord>,
+}
+
+/// A file with its size and digest.
+#[derive(Debug, Clone, Serialize)]
+pub struct FileRecord {
+    /// Path of the input as given, or of an artifact relative to the output
+    /// directory, using forward slashes.
+    pub path: String,
+    pub size: u64,
+    /// Lowercase hexadecimal SHA-256 of the contents.
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StageTiming {
+    pub name: String,
+    pub seconds: f64,
+}
+
+impl FileRecord {
+    /// Reads and hashes the file at `path`, recorded as `name`.
+    pub fn of(path: &Path, name: String) -> Result<Self> {
+        let mut hasher = Sha256::new();
+        let mut reader = BufReader::new(File::open(path).with_context(|| format!("Failed to open {:?}", path))?);
+        let size = io::copy(&mut reader, &mut hasher).with_context(|| format!("Failed to read {:?}", path))?;
+        let sha256 = hasher.finalize().iter().map(|byte| format!("{:02x}", byte)).collect();
+        Ok(Self { path: name, size, sha256 })
+    }
+}
+
+/// Records every file under the given paths (files or directories, relative to
+/// `base_dir`), sorted by path. Missing paths are skipped.
+pub fn collect_artifacts(base_dir: &Path, paths: &[&str]) -> Result<Vec<FileRecord>> {
+    let mut files = Vec::new();
+    for path in paths {
+        let full = base_dir.join(path);
+        if full.exists() {
+            paths::collect_files(&full, &mut files)
+                .with_context(|| format!("Failed to list files in {:?}", full))?;
+        }
+    }
+    files.sort();
+    files
+        .iter()
+        .map(|file| {
+            let name = file
+                .strip_prefix(base_dir)
+                .unwrap_or(file)
+                .components()
+                .map(|part| part.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            FileRecord::of(file, name)
+        })
+        .collect()
+}
+
+/// Writes the manifest as pretty-printed JSON.
+pub fn write(manifest: &RunManifest, path: &Path) -> Result<()> {
+    fs::write(path, serde_json::to_string_pretty(manifest)?)
+        .with_context(|| format!("Failed to write run manifest to {:?}", path))
+}
@@ -0,0 +1,125 @@
+//! Search Module
+//!
+//! Handles the optional full-text search index (the `search` feature): a tantivy
+//! index over the OCR text of every page, keyed by page number, frame index and
+//! timestamp, and the queries run against it by the `search` subcommand.
+
+use crate::document_builder::Page;
+use crate::layout;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use tantivy::collector::TopDocs;
+use tantivy::query::QueryParser;
+use tantivy::schema::{Field, INDEXED, STORED, Schema, TEXT, Value};
+use tantivy::snippet::SnippetGenerator;
+use tantivy::{Index, IndexWriter, TantivyDocument};
+
+/// Directory of the index inside a run's result directory.
+pub const INDEX_DIR: &str = "search_index";
+// Memory budget of the single indexing thread.
+const WRITER_MEMORY_BYTES: usize = 50_000_000;
+
+/// A page matching a search query.
+#[derive(Debug, Serialize)]
+pub struct SearchHit {
+    pub page: u64,
+    pub frame_index: u64,
+    /// When the page appears in the video, in seconds, if the frame rate was known.
+    pub timestamp: Option<f64>,
+    pub title: Option<String>,
+    /// The part of the page text that best matches the query.
+    pub snippet: String,
+    pub score: f32,
+}
+
+struct Fields {
+    page: Field,
+    frame_index: Field,
+    timestamp: Field,
+    title: Field,
+    text: Field,
+}
+
+impl Fields {
+    fn schema() -> (Schema, Self) {
+        let mut builder = Schema::builder();
+        let fields = Self {
+            page: builder.add_u64_field("page", INDEXED | STORED),
+            frame_index: builder.add_u64_field("frame_index", INDEXED | STORED),
+            timestamp: builder.add_f64_field("timestamp", STORED),
+            title: builder.add_text_field("title", TEXT | STORED),
+            text: builder.add_text_field("text", TEXT | STORED),
+        };
+        (builder.build(), fields)
+    }
+
+    fn of(schema: &Schema) -> Result<Self> {
+        Ok(Self {
+            page: schema.get_field("page")?,
+            frame_index: schema.get_field("frame_index")?,
+            timestamp: schema.get_field("timestamp")?,
+            title: schema.get_field("title")?,
+            text: schema.get_field("text")?,
+        })
+    }
+}
+
+/// Builds a search index in `index_dir` with one document per page.
+/// `frame_indices` holds the decoding-order index of each page's frame.
+pub fn build_index(pages: &[Page], frame_indices: &[usize], index_dir: &Path) -> Result<()> {
+    fs::create_dir_all(index_dir)
+        .with_context(|| format!("Failed to create search index directory {:?}", index_dir))?;
+    let (schema, fields) = Fields::schema();
+    let index = Index::create_in_dir(index_dir, schema).context("Failed to create search index")?;
+    let mut writer: IndexWriter = index.writer_with_num_threads(1, WRITER_MEMORY_BYTES)?;
+
+    for (i, (page, &frame_index)) in pages.iter().zip(frame_indices).enumerate() {
+        let mut document = TantivyDocument::default();
+        document.add_u64(fields.page, i as u64 + 1);
+        document.add_u64(fields.frame_index, frame_index as u64);
+        if let Some(timestamp) = page.timestamp {
+            document.add_f64(fields.timestamp, timestamp);
+        }
+        if let Some(title) = &page.title {
+            document.add_text(fields.title, title);
+        }
+        document.add_text(fields.text, page.ocr.map(layout::page_text).unwrap_or_default());
+        writer.add_document(document)?;
+    }
+    writer.commit().context("Failed to write search index")?;
+    Ok(())
+}
+
+/// Runs `query` (tantivy query syntax) over the page titles and text of the
+/// index in `index_dir` and returns at most `limit` hits, best first.
+pub fn search(index_dir: &Path, query: &str, limit: usize) -> Result<Vec<SearchHit>> {
+    let index = Index::open_in_dir(index_dir)
+        .with_context(|| format!("Failed to open search index {:?}", index_dir))?;
+    let fields = Fields::of(&index.schema())?;
+    let searcher = index.reader()?.searcher();
+    let parser = QueryParser::for_index(&index, vec![fields.title, fields.text]);
+    let query = parser.parse_query(query).context("Invalid search query")?;
+    let snippets = SnippetGenerator::create(&searcher, &*query, fields.text)?;
+
+    let top_docs = searcher.search(&query, &TopDocs::with_limit(limit.max(1)).order_by_score())?;
+    top_docs
+        .into_iter()
+        .map(|(score, address)| {
+            let document: TantivyDocument = searcher.doc(address)?;
+            let u64_of = |field| document.get_first(field).and_then(|value| value.as_u64());
+            Ok(SearchHit {
+                page: u64_of(fields.page).unwrap_or_default(),
+                frame_index: u64_of(fields.frame_index).unwrap_or_default(),
+                timestamp: document.get_first(fields.timestamp).and_then(|value| value.as_f64()),
+                title: document
+                    .get_first(fields.title)
+                    .and_then(|value| value.as_str())
+                    .map(str::to_string),
+                snippet: snippets.snippet_from_doc(&document).fragment().replace('\n', " "),
+                score,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,213 @@
+//! Spelling Module
+//!
+//! Handles the optional dictionary-based correction of the OCR text. A word
+//! missing from the dictionary is first matched against the character
+//! confusions OCR engines typically make (`rn` read for `m`, `1` for `l`, `0`
+//! for `o`, ...), and failing that looked up with SymSpell for a single
+//! dictionary word one edit away. A domain word list adds the terms a language
+//! dictionary lacks and wins over it. Every change is listed in
+//! `corrections.json`, so the original text is never lost.
+
+use crate::ocr::OcrFrameResult;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use symspell::{SymSpell, UnicodeStringStrategy, Verbosity};
+
+/// File name of the correction list in the result directory.
+pub const CORRECTIONS_NAME: &str = "corrections.json";
+
+/// Character sequences OCR reads in place of others, as (read, meant), for
+/// lowercase words.
+const CONFUSIONS: &[(&str, &str)] = &[
+    ("rn", "m"),
+    ("m", "rn"),
+    ("cl", "d"),
+    ("vv", "w"),
+    ("ii", "u"),
+    ("1", "l"),
+    ("|", "l"),
+    ("0", "o"),
+    ("5", "s"),
+    ("l", "i"),
+    ("i", "l"),
+];
+/// Letters OCR reads in place of digits, for words that are mostly digits.
+const DIGIT_CONFUSIONS: &[(char, char)] =
+    &[('O', '0'), ('o', '0'), ('D', '0'), ('l', '1'), ('I', '1'), ('|', '1'), ('S', '5'), ('B', '8')];
+/// Count given to domain words, above any frequency in a language dictionary.
+const DOMAIN_COUNT: i64 = 1_000_000_000_000;
+/// Shortest word looked up by edit distance; shorter words have too many
+/// neighbours one edit away.
+const MIN_LOOKUP_CHARS: usize = 5;
+
+/// A word the correction changed.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Correction {
+    /// Page number, starting at 1.
+    pub page: usize,
+    pub original: String,
+    pub corrected: String,
+}
+
+/// The known words and how to find the closest of them.
+pub struct SpellChecker {
+    /// Every known word in lowercase, with its frequency.
+    words: HashMap<String, i64>,
+    symspell: SymSpell<UnicodeStringStrategy>,
+}
+
+impl Default for SpellChecker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SpellChecker {
+    /// A checker that knows no words yet.
+    pub fn new() -> Self {
+        Self { words: HashMap::new(), symspell: SymSpell::default() }
+    }
+
+    /// Adds the words of a language dictionary: a word list with one word per
+    /// line, optionally followed by its frequency (`word 1234`), or a Hunspell
+    /// `.dic` file, whose leading word count and `/FLAGS` suffixes are ignored.
+    /// Returns how many words were read.
+    pub fn load_dictionary(&mut self, path: &Path) -> Result<usize> {
+        let text = fs::read_to_string(path).with_context(|| format!("Failed to read dictionary {:?}", path))?;
+        let mut count = 0;
+        for (i, line) in text.lines().enumerate() {
+            let mut fields = line.split_whitespace();
+            let Some(word) = fields.next().map(|field| field.split('/').next().unwrap_or(field)) else {
+                continue;
+            };
+            if word.starts_with('#') || (i == 0 && word.chars().all(|c| c.is_ascii_digit())) {
+                continue;
+            }
+            let frequency = fields.next().and_then(|field| field.parse().ok()).unwrap_or(1);
+            self.add(word, frequency);
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Adds the terms of a domain word list, one per line, which take precedence
+    /// over the language dictionary. Returns how many words were read.
+    pub fn load_wordlist(&mut self, path: &Path) -> Result<usize> {
+        let text = fs::read_to_string(path).with_context(|| format!("Failed to read word list {:?}", path))?;
+        let words: Vec<&str> = text.lines().map(str::trim).filter(|word| !word.is_empty()).collect();
+        for word in &words {
+            self.add(word, DOMAIN_COUNT);
+        }
+        Ok(words.len())
+    }
+
+    fn add(&mut self, word: &str, frequency: i64) {
+        let word = word.to_lowercase();
+        if word.contains(char::is_whitespace) {
+            return;
+        }
+        *self.words.entry(word.clone()).or_default() += frequency;
+        self.symspell.load_dictionary_line(&format!("{} {}", word, frequency), 0, 1, " ");
+    }
+
+    /// Returns the correction of an OCR word, or `None` when it is known, has
+    /// no likely correction, or is not a word. Punctuation around the word is
+    /// kept, and so is its capitalization.
+    pub fn correct(&self, word: &str) -> Option<String> {
+        let in_word = |c: char| c.is_alphanumeric() || c == '|';
+        let start = word.find(in_word)?;
+        let (last, c) = word.char_indices().rev().find(|&(_, c)| in_word(c))?;
+        let end = last + c.len_utf8();
+        let core = &word[start..end];
+        let lower = core.to_lowercase();
+        if self.words.contains_key(&lower) || core.chars().count() < 2 {
+            return None;
+        }
+        let digits = core.chars().filter(char::is_ascii_digit).count();
+        let fixed = if digits > 0 && digits * 2 >= core.chars().count() {
+            fix_number(core)?
+        } else {
+            let fixed = self.fix_confusions(&lower).or_else(|| self.lookup(&lower))?;
+            match_case(core, &fixed)
+        };
+        Some(format!("{}{}{}", &word[..start], fixed, &word[end..]))
+    }
+
+    /// The known word that undoing one confusion, at one place or everywhere,
+    /// turns `word` into; the most frequent if there are several.
+    fn fix_confusions(&self, word: &str) -> Option<String> {
+        let mut candidates = Vec::new();
+        for (read, meant) in CONFUSIONS {
+            for (at, _) in word.match_indices(read) {
+                candidates.push(format!("{}{}{}", &word[..at], meant, &word[at + read.len()..]));
+            }
+            candidates.push(word.replace(read, meant));
+        }
+        candidates
+            .into_iter()
+            .filter_map(|candidate| self.words.get(&candidate).map(|&count| (candidate, count)))
+            .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(&a.0)))
+            .map(|(candidate, _)| candidate)
+    }
+
+    /// The only known word one edit away from `word`, for words long enough and
+    /// made of letters only.
+    fn lookup(&self, word: &str) -> Option<String> {
+        if word.chars().count() < MIN_LOOKUP_CHARS || !word.chars().all(char::is_alphabetic) {
+            return None;
+        }
+        match self.symspell.lookup(word, Verbosity::Closest, 1).as_slice() {
+            [suggestion] if suggestion.distance == 1 => Some(suggestion.term.clone()),
+            _ => None,
+        }
+    }
+
+    /// Corrects the words of every OCR result in place and returns what was
+    /// changed, by page.
+    pub fn correct_pages(&self, results: &mut [OcrFrameResult]) -> Vec<Correction> {
+        let mut corrections = Vec::new();
+        for result in results {
+            for word in &mut result.words {
+                if let Some(corrected) = self.correct(&word.text) {
+                    let original = std::mem::replace(&mut word.text, corrected.clone());
+                    corrections.push(Correction { page: result.frame_index + 1, original, corrected });
+                }
+            }
+        }
+        corrections
+    }
+}
+
+/// Replaces the letters of a mostly numeric word that OCR reads for digits,
+/// if that leaves only digits and number punctuation.
+fn fix_number(word: &str) -> Option<String> {
+    let fixed: String = word
+        .chars()
+        .map(|c| DIGIT_CONFUSIONS.iter().find(|(read, _)| *read == c).map_or(c, |&(_, digit)| digit))
+        .collect();
+    let numeric = fixed.chars().all(|c| c.is_ascii_digit() || ".,:%".contains(c));
+    (numeric && fixed != word).then_some(fixed)
+}
+
+/// Gives `word` the capitalization of `original`: all capitals, a leading
+/// capital, or lowercase.
+fn match_case(original: &str, word: &str) -> String {
+    let letters: Vec<char> = original.chars().filter(|c| c.is_alphabetic()).collect();
+    if letters.len() > 1 && letters.iter().all(|c| c.is_uppercase()) {
+        return word.to_uppercase();
+    }
+    let mut chars = word.chars();
+    match (original.chars().next(), chars.next()) {
+        (Some(first), Some(c)) if first.is_uppercase() => c.to_uppercase().chain(chars).collect(),
+        _ => word.to_string(),
+    }
+}
+
+/// Writes the corrections to `path` as pretty-printed JSON.
+pub fn write_corrections(corrections: &[Correction], path: &Path) -> Result<()> {
+    let json = serde_json::to_string_pretty(corrections).context("Failed to serialize the corrections")?;
+    fs::write(path, json).with_context(|| format!("Failed to write corrections to {:?}", path))
+}
@@ -0,0 +1,97 @@
+//! Subtitles Module
+//!
+//! Handles the `srt` and `vtt` output formats: one subtitle cue per page that
+//! spans the time the page was on screen and shows its OCR text or title, so
+//! video players can display (and search) the slide text alongside the video.
+
+use crate::document_builder::Page;
+use crate::layout;
+use anyhow::{Context, Result, bail};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+/// Subtitle file format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubtitleFormat {
+    Srt,
+    Vtt,
+}
+
+/// Options for the subtitle output.
+#[derive(Debug, Clone)]
+pub struct SubtitleOptions {
+    pub format: SubtitleFormat,
+    /// Show each page's title instead of its full OCR text.
+    pub titles_only: bool,
+    /// Length of the video in seconds, where the last cue ends.
+    pub duration: f64,
+}
+
+/// Writes one cue per page, from its timestamp until the end of its segment, or
+/// when that is unknown until the next page's timestamp (or the end of the
+/// video). Fails when the pages have no timestamps.
+pub fn build_subtitles(pages: &[Page], options: &SubtitleOptions, output_path: &Path) -> Result<()> {
+    let mut subtitles = String::new();
+    if options.format == SubtitleFormat::Vtt {
+        subtitles.push_str("WEBVTT\n\n");
+    }
+
+    for (i, page) in pages.iter().enumerate() {
+        let Some(start) = page.timestamp else {
+            bail!("Page timestamps are unavailable, and subtitles need them");
+        };
+        let end = page
+            .end
+            .or_else(|| pages.get(i + 1).and_then(|next| next.timestamp))
+            .unwrap_or(options.duration)
+            .max(start);
+        let text = if options.titles_only {
+            page.display_title(i)
+        } else {
+            page.ocr.map(layout::page_text).unwrap_or_else(|| page.display_title(i))
+        };
+
+        if options.format == SubtitleFormat::Srt {
+            writeln!(subtitles, "{}", i + 1)?;
+        }
+        writeln!(
+            subtitles,
+            "{} --> {}",
+            cue_time(start, options.format),
+            cue_time(end, options.format)
+        )?;
+        writeln!(subtitles, "{}\n", cue_text(&text))?;
+    }
+
+    fs::write(output_path, subtitles)
+        .with_context(|| format!("Failed to write subtitles to {:?}", output_path))
+}
+
+/// Formats seconds as `HH:MM:SS,mmm` (SRT) or `HH:MM:SS.mmm` (WebVTT).
+fn cue_time(seconds: f64, format: SubtitleFormat) -> String {
+    let millis = (seconds.max(0.0) * 1000.0).round() as u64;
+    let separator = match format {
+        SubtitleFormat::Srt => ',',
+        SubtitleFormat::Vtt => '.',
+    };
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        millis / 3_600_000,
+        millis / 60_000 % 60,
+        millis / 1000 % 60,
+        separator,
+        millis % 1000
+    )
+}
+
+/// Drops blank lines, which would end the cue early, and arrows, which players
+/// read as a cue timing line.
+fn cue_text(text: &str) -> String {
+    let lines: Vec<String> = text
+        .lines()
+        .map(|line| line.trim().replace("-->", "->"))
+        .filter(|line| !line.is_empty())
+        .collect();
+    if lines.is_empty() { "…".to_string() } else { lines.join("\n") }
+}
@@ -0,0 +1,161 @@
+//! Template Module
+//!
+//! Handles user-supplied Handlebars templates for the Markdown and HTML outputs.
+//! The template receives the whole document as its context, so it controls the
+//! structure of the file: front matter, image sizing, callouts for pages with
+//! low OCR confidence, and so on.
+
+use crate::document_builder::{self, Page, WrittenFiles};
+use crate::layout;
+use anyhow::{Context, Result};
+use handlebars::Handlebars;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+/// Options for template-driven output.
+#[derive(Debug, Clone)]
+pub struct TemplateOptions {
+    /// Title of the whole document, usually derived from the input file name.
+    pub document_title: String,
+    /// File name of the input video.
+    pub source: String,
+    /// URL of the source video; pages then get a `video_link` at their timestamp.
+    pub video_url: Option<String>,
+    /// Escape values for HTML; Markdown output inserts them verbatim.
+    pub html: bool,
+    /// Embed the page images as base64 data URIs instead of writing them to an
+    /// `images` directory next to the output file.
+    pub embed_images: bool,
+}
+
+/// The data a template is rendered with.
+#[derive(Debug, Serialize)]
+struct DocumentContext<'a> {
+    title: &'a str,
+    source: &'a str,
+    generator: String,
+    video_url: Option<&'a str>,
+    pages: Vec<PageContext>,
+}
+
+#[derive(Debug, Serialize)]
+struct PageContext {
+    number: usize,
+    /// Detected title, if any.
+    title: Option<String>,
+    /// Detected title, else the first line of text, else "Page N".
+    heading: String,
+    /// Seconds into the video.
+    timestamp: Option<f64>,
+    /// The timestamp as HH:MM:SS.
+    time: Option<String>,
+    /// Seconds into the video when the page left the screen.
+    end: Option<f64>,
+    /// The end as HH:MM:SS.
+    end_time: Option<String>,
+    video_link: Option<String>,
+    image: String,
+    width: u32,
+    height: u32,
+    text: String,
+    word_count: usize,
+    mean_confidence: Option<f32>,
+    words: Vec<WordContext>,
+}
+
+#[derive(Debug, Serialize)]
+struct WordContext {
+    text: String,
+    confidence: f32,
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+}
+
+/// Renders `pages` through the Handlebars template at `template_path` and writes
+/// the result to `output_path`.
+pub fn render(
+    pages: &[Page],
+    options: &TemplateOptions,
+    template_path: &Path,
+    output_path: &Path,
+) -> Result<WrittenFiles> {
+    let template = fs::read_to_string(template_path)
+        .with_context(|| format!("Failed to read template {:?}", template_path))?;
+    let mut registry = Handlebars::new();
+    if !options.html {
+        registry.register_escape_fn(handlebars::no_escape);
+    }
+    registry
+        .register_template_string("document", template)
+        .with_context(|| format!("Invalid template {:?}", template_path))?;
+
+    let base_dir = document_builder::prepare_output_dir(output_path)?;
+    let image_sources = document_builder::page_image_sources(pages, options.embed_images, base_dir)?;
+    let context = DocumentContext {
+        title: &options.document_title,
+        source: &options.source,
+        generator: format!("videodocparser {}", env!("CARGO_PKG_VERSION")),
+        video_url: options.video_url.as_deref(),
+        pages: pages
+            .iter()
+            .zip(&image_sources)
+            .enumerate()
+            .map(|(i, (page, image))| page_context(i, page, image.clone(), options))
+            .collect(),
+    };
+
+    let rendered = registry
+        .render("document", &context)
+        .with_context(|| format!("Failed to render template {:?}", template_path))?;
+    fs::write(output_path, rendered)
+        .with_context(|| format!("Failed to write {:?}", output_path))?;
+    let images: &[String] = if options.embed_images { &[] } else { &image_sources };
+    Ok(WrittenFiles::new(output_path, base_dir, images))
+}
+
+fn page_context(i: usize, page: &Page, image: String, options: &TemplateOptions) -> PageContext {
+    let video_link = options
+        .video_url
+        .as_deref()
+        .zip(page.timestamp)
+        .map(|(url, timestamp)| document_builder::deep_link(url, timestamp));
+    let words = page
+        .ocr
+        .map(|ocr| {
+            ocr.words
+                .iter()
+                .map(|word| {
+                    let (x1, y1, x2, y2) = word.bbox;
+                    WordContext {
+                        text: word.text.clone(),
+                        confidence: word.confidence,
+                        x: x1,
+                        y: y1,
+                        width: x2 - x1,
+                        height: y2 - y1,
+                    }
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    PageContext {
+        number: i + 1,
+        title: page.title.clone(),
+        heading: page.display_title(i),
+        timestamp: page.timestamp,
+        time: page.timestamp.map(document_builder::format_timestamp),
+        end: page.end,
+        end_time: page.end.map(document_builder::format_timestamp),
+        video_link,
+        image,
+        width: page.image.width(),
+        height: page.image.height(),
+        text: page.ocr.map(layout::page_text).unwrap_or_default(),
+        word_count: page.word_count(),
+        mean_confidence: page.mean_confidence(),
+        words,
+    }
+}
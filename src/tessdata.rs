@@ -0,0 +1,104 @@
+//! Tessdata Module
+//!
+//! Handles downloading the Tesseract language packs a run needs but cannot
+//! find. Packs come from the `tessdata_fast` or `tessdata_best` repositories of
+//! the Tesseract project on GitHub, and each one is checked against the Git
+//! blob hash and size GitHub reports for it before it is cached, so a truncated
+//! or tampered download never reaches Tesseract.
+
+use anyhow::{Context, Result, bail, ensure};
+use log::info;
+use serde_json::Value;
+use sha1::{Digest, Sha1};
+use std::fs;
+use std::io::{BufRead, IsTerminal, Write};
+use std::path::Path;
+use std::time::Duration;
+
+/// Longest a single pack download may take.
+const TIMEOUT: Duration = Duration::from_secs(600);
+/// Largest pack accepted; the biggest `tessdata_best` packs are under 100 MB.
+const MAX_PACK_BYTES: u64 = 200 * 1024 * 1024;
+
+/// Name of the GitHub repository holding the packs of a `--tessdata-source`:
+/// "fast" or "best".
+pub fn repository(source: &str) -> Result<&'static str> {
+    match source {
+        "fast" => Ok("tessdata_fast"),
+        "best" => Ok("tessdata_best"),
+        other => bail!("Unknown tessdata source '{}': expected fast or best", other),
+    }
+}
+
+/// Git blob hash of `data`, as GitHub reports it for a file: the SHA-1 of
+/// `blob <size>\0` followed by the contents, in lowercase hex.
+pub fn git_blob_sha1(data: &[u8]) -> String {
+    let mut hasher = Sha1::new();
+    hasher.update(format!("blob {}\0", data.len()).as_bytes());
+    hasher.update(data);
+    hasher.finalize().iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Checks a downloaded pack against the size and blob hash its repository lists.
+pub fn verify_pack(data: &[u8], size: u64, sha: &str) -> Result<()> {
+    ensure!(data.len() as u64 == size, "Expected {} bytes but received {}", size, data.len());
+    let actual = git_blob_sha1(data);
+    ensure!(actual.eq_ignore_ascii_case(sha), "Checksum mismatch: expected {} but got {}", sha, actual);
+    Ok(())
+}
+
+/// Asks on the terminal whether to download `codes`. Without a terminal to ask
+/// on, the answer is no.
+pub fn confirm_download(codes: &[&str], source: &str) -> bool {
+    if !std::io::stdin().is_terminal() {
+        return false;
+    }
+    eprint!(
+        "{} {} (tessdata_{}) [y/N] ",
+        crate::i18n::tr(crate::i18n::Msg::DownloadLanguagePacks),
+        codes.join(", "),
+        source
+    );
+    std::io::stderr().flush().ok();
+    let mut answer = String::new();
+    if std::io::stdin().lock().read_line(&mut answer).is_err() {
+        return false;
+    }
+    matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+}
+
+/// Downloads the pack of every language in `codes` from the `source`
+/// repository into `dir`, verifying each before it is written.
+pub fn download_packs(codes: &[&str], source: &str, dir: &Path) -> Result<()> {
+    let repository = repository(source)?;
+    fs::create_dir_all(dir).with_context(|| format!("Failed to create the tessdata directory {:?}", dir))?;
+    let config = ureq::Agent::config_builder().timeout_global(Some(TIMEOUT)).build();
+    let agent: ureq::Agent = config.into();
+    for code in codes {
+        let name = format!("{}.traineddata", code);
+        let url = format!("https://api.github.com/repos/tesseract-ocr/{}/contents/{}", repository, name);
+        let mut response = agent.get(&url).call().with_context(|| {
+            format!("Could not find the language pack '{}' in tesseract-ocr/{}", code, repository)
+        })?;
+        let listing: Value = response.body_mut().read_json().context("Failed to parse the GitHub file listing")?;
+        let (Some(sha), Some(size), Some(download_url)) =
+            (listing["sha"].as_str(), listing["size"].as_u64(), listing["download_url"].as_str())
+        else {
+            bail!("The GitHub file listing of {} lacks its hash, size, or download URL", name);
+        };
+
+        info!("Downloading the '{}' language pack from tesseract-ocr/{} ({} bytes)...", code, repository, size);
+        let data = agent
+            .get(download_url)
+            .call()
+            .and_then(|mut response| response.body_mut().with_config().limit(MAX_PACK_BYTES).read_to_vec())
+            .with_context(|| format!("Failed to download {}", download_url))?;
+        verify_pack(&data, size, sha).with_context(|| format!("The download of {} is corrupt", name))?;
+
+        let partial = dir.join(format!("{}.part", name));
+        fs::write(&partial, &data).with_context(|| format!("Failed to write {:?}", partial))?;
+        fs::rename(&partial, dir.join(&name)).with_context(|| format!("Failed to save {} in {:?}", name, dir))?;
+        info!("Saved the '{}' language pack to {:?}", code, dir);
+    }
+    Ok(())
+}
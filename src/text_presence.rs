@@ -0,0 +1,60 @@
+//! Text Presence Module
+//!
+//! Handles telling frames that show text from those that show none, cheaply
+//! enough to run before OCR. The frame is divided into small cells; a cell
+//! holds strokes when enough of its pixels are sharp steps in brightness, both
+//! across and down, as the edges of letters are. Frames without such cells are
+//! left out of OCR.
+
+use image::{ImageBuffer, Rgb};
+
+/// Side of the square cells the frame is analysed in, in pixels.
+const CELL_SIZE: u32 = 16;
+/// Brightness step between neighbouring pixels above which the pair is an edge.
+const EDGE_STEP: i32 = 48;
+/// Share of a cell's pixels that must be edges, across and down alike, for the
+/// cell to hold strokes.
+const MIN_EDGE_SHARE: f32 = 0.03;
+/// Share of a cell's pixels above which its edges are noise or texture, such as
+/// static or foliage, rather than strokes.
+const MAX_EDGE_SHARE: f32 = 0.5;
+/// Fewest stroke cells on a frame with text; a lone cell is usually a speck or
+/// the mouse pointer.
+const MIN_TEXT_CELLS: usize = 2;
+
+/// Counts the cells of a frame that hold strokes.
+pub fn stroke_cells(frame: &ImageBuffer<Rgb<u8>, Vec<u8>>) -> usize {
+    let (width, height) = frame.dimensions();
+    let gray = image::imageops::grayscale(frame);
+    let luma = |x: u32, y: u32| gray.get_pixel(x, y).0[0] as i32;
+
+    let mut count = 0;
+    for top in (0..height).step_by(CELL_SIZE as usize) {
+        for left in (0..width).step_by(CELL_SIZE as usize) {
+            let (mut across, mut down) = (0, 0);
+            for y in top..(top + CELL_SIZE).min(height) {
+                for x in left..(left + CELL_SIZE).min(width) {
+                    let here = luma(x, y);
+                    if x + 1 < width && (luma(x + 1, y) - here).abs() > EDGE_STEP {
+                        across += 1;
+                    }
+                    if y + 1 < height && (luma(x, y + 1) - here).abs() > EDGE_STEP {
+                        down += 1;
+                    }
+                }
+            }
+            let pixels = (CELL_SIZE * CELL_SIZE) as f32;
+            let (across, down) = (across as f32 / pixels, down as f32 / pixels);
+            let strokes = across.min(down) >= MIN_EDGE_SHARE && across.max(down) <= MAX_EDGE_SHARE;
+            count += strokes as usize;
+        }
+    }
+    count
+}
+
+/// Whether a frame may show text. Pictures with sharp detail pass as well,
+/// so only frames that are plainly empty, such as blank slides, dark frames,
+/// or soft video, are left out.
+pub fn has_text(frame: &ImageBuffer<Rgb<u8>, Vec<u8>>) -> bool {
+    stroke_cells(frame) >= MIN_TEXT_CELLS
+}
@@ -0,0 +1,109 @@
+//! Transcribe Module
+//!
+//! Handles speech recognition of the input's audio track with a Whisper model
+//! (the `whisper` feature): the audio is decoded by `video_processor` and the
+//! recognized segments become transcript cues, placed after each slide exactly
+//! like the cues of a `--transcript` file. With diarization, a tinydiarize
+//! model marks the speaker turns and `diarize` tells the speakers apart.
+
+use crate::diarize;
+use crate::transcript::Cue;
+use crate::video_processor;
+use anyhow::{Context, Result, ensure};
+use log::info;
+use std::path::Path;
+use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
+
+/// Sample rate Whisper models are trained on.
+pub const SAMPLE_RATE: u32 = 16_000;
+
+/// Whisper language code for an OCR language (Tesseract codes such as `eng` or
+/// `por`). Several languages, or one Whisper does not know, give `auto`, which
+/// detects the language from the first seconds of speech.
+pub fn whisper_language(lang: &str) -> &'static str {
+    let mut codes = lang.split('+');
+    let (Some(code), None) = (codes.next(), codes.next()) else {
+        return "auto";
+    };
+    match code.trim() {
+        "eng" => "en",
+        "spa" => "es",
+        "por" => "pt",
+        "deu" => "de",
+        "fra" => "fr",
+        "ita" => "it",
+        "nld" => "nl",
+        "pol" => "pl",
+        "rus" => "ru",
+        "ukr" => "uk",
+        "tur" => "tr",
+        "swe" => "sv",
+        "ara" => "ar",
+        "hin" => "hi",
+        "jpn" | "jpn_vert" => "ja",
+        "kor" | "kor_vert" => "ko",
+        "chi_sim" | "chi_tra" | "chi_sim_vert" | "chi_tra_vert" => "zh",
+        _ => "auto",
+    }
+}
+
+/// Transcribes the audio of `video` with the ggml Whisper model at `model`.
+/// With `diarize`, which needs a tinydiarize (`-tdrz`) model, every cue is
+/// labelled with its speaker.
+pub fn transcribe(video: &Path, model: &Path, language: &str, diarize: bool) -> Result<Vec<Cue>> {
+    let audio = video_processor::extract_audio(video, SAMPLE_RATE)?;
+    ensure!(!audio.is_empty(), "The audio track of {:?} is empty", video);
+
+    let model_path = crate::paths::to_utf8(model)?;
+    let context = WhisperContext::new_with_params(model_path, WhisperContextParameters::default())
+        .with_context(|| format!("Failed to load Whisper model {:?}", model))?;
+    let mut state = context.create_state().context("Failed to create Whisper state")?;
+
+    let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+    params.set_language(Some(language));
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    params.set_n_threads(threads as i32);
+    params.set_print_special(false);
+    params.set_print_progress(false);
+    params.set_print_realtime(false);
+    params.set_print_timestamps(false);
+    params.set_tdrz_enable(diarize);
+
+    info!("Transcribing {:.1} s of audio (language: {})...", audio.len() as f64 / SAMPLE_RATE as f64, language);
+    state.full(params, &audio).context("Whisper transcription failed")?;
+
+    let segments = state.full_n_segments()?;
+    let mut cues = Vec::with_capacity(segments.max(0) as usize);
+    // Start and end of every speaker turn, and the turn of every cue.
+    let mut turns: Vec<(f64, f64)> = Vec::new();
+    let mut cue_turns = Vec::with_capacity(cues.capacity());
+    let mut turn_ended = true;
+    for segment in 0..segments {
+        let text = state.full_get_segment_text(segment)?;
+        // Segment times are in hundredths of a second.
+        let start = state.full_get_segment_t0(segment)? as f64 / 100.0;
+        let end = state.full_get_segment_t1(segment)? as f64 / 100.0;
+        if turn_ended {
+            turns.push((start, end));
+        } else if let Some(turn) = turns.last_mut() {
+            turn.1 = end;
+        }
+        turn_ended = state.full_get_segment_speaker_turn_next(segment);
+        let text = text.trim();
+        if !text.is_empty() {
+            cues.push(Cue { start, end, text: text.to_string(), speaker: None });
+            cue_turns.push(turns.len() - 1);
+        }
+    }
+    info!("Recognized {} speech segments.", cues.len());
+
+    if diarize {
+        let speakers = diarize::label_turns(&audio, SAMPLE_RATE, &turns);
+        for (cue, turn) in cues.iter_mut().zip(cue_turns) {
+            cue.speaker = Some(format!("Speaker {}", speakers[turn] + 1));
+        }
+        let count = speakers.iter().max().map_or(0, |max| max + 1);
+        info!("Found {} speaker turns from {} speakers.", turns.len(), count);
+    }
+    Ok(cues)
+}
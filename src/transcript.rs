@@ -0,0 +1,210 @@
+//! Transcript Module
+//!
+//! Handles reading a transcript of the video's audio from an SRT or WebVTT file
+//! (as written by Whisper or downloaded with the video) and splitting it by
+//! page: each cue goes to the page that was on screen when it started, so the
+//! spoken text can be placed right after its slide. Captions the video already
+//! carries, as a subtitle stream or a file next to it, are read the same way.
+
+use anyhow::{Context, Result, ensure};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Encoding of the packets of a text subtitle stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptionCodec {
+    /// SubRip or WebVTT: the cue text, possibly with HTML-like tags.
+    Text,
+    /// ASS or SSA: a dialogue event whose last field is the text.
+    Ass,
+    /// MP4 timed text: a 16-bit length, the text, then style boxes.
+    MovText,
+}
+
+/// One timed piece of speech.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Cue {
+    /// Start and end in seconds from the beginning of the video.
+    pub start: f64,
+    pub end: f64,
+    pub text: String,
+    /// Label of the speaker, such as `Speaker 2`, when the speech was diarized.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub speaker: Option<String>,
+}
+
+/// Reads the cues of an SRT or WebVTT file.
+pub fn load(path: &Path) -> Result<Vec<Cue>> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read transcript {:?}", path))?;
+    let cues = parse(&contents);
+    ensure!(!cues.is_empty(), "No subtitle cues found in transcript {:?}", path);
+    Ok(cues)
+}
+
+/// Parses SRT or WebVTT text. Blocks without a timing line (the WebVTT header,
+/// `NOTE` and `STYLE` blocks) are skipped, as are cues without text.
+pub fn parse(contents: &str) -> Vec<Cue> {
+    let contents = contents.trim_start_matches('\u{feff}').replace("\r\n", "\n");
+    let mut cues = Vec::new();
+    for block in contents.split("\n\n") {
+        let mut lines = block.lines().skip_while(|line| !line.contains("-->"));
+        let Some(timing) = lines.next() else { continue };
+        let Some((start, end)) = parse_timing(timing) else { continue };
+        let text = lines
+            .map(|line| strip_tags(line.trim()))
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        if !text.is_empty() {
+            cues.push(Cue { start, end, text, speaker: None });
+        }
+    }
+    cues
+}
+
+/// Finds a subtitle file next to `video` with the same name: `talk.srt`,
+/// `talk.vtt`, or one with a language tag such as `talk.en.srt`. An untagged
+/// file wins; otherwise the first in name order is used.
+pub fn find_sidecar(video: &Path) -> Option<PathBuf> {
+    let stem = video.file_stem()?.to_str()?;
+    let dir = video.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let mut candidates: Vec<PathBuf> = fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            let name = path.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+            let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or_default();
+            matches!(extension.to_ascii_lowercase().as_str(), "srt" | "vtt")
+                && name.strip_prefix(stem).is_some_and(|rest| rest.starts_with('.'))
+        })
+        .collect();
+    candidates.sort_by_key(|path| (path.file_stem().and_then(|s| s.to_str()) != Some(stem), path.clone()));
+    candidates.into_iter().next()
+}
+
+/// Text of one packet of a subtitle stream, on one line and without markup.
+pub fn packet_text(codec: CaptionCodec, data: &[u8]) -> String {
+    let text = match codec {
+        CaptionCodec::Text => String::from_utf8_lossy(data).into_owned(),
+        CaptionCodec::Ass => {
+            // ReadOrder, Layer, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+            let event = String::from_utf8_lossy(data);
+            let text = event.splitn(9, ',').nth(8).unwrap_or_default();
+            strip_overrides(text).replace("\\N", "\n").replace("\\n", "\n").replace("\\h", " ")
+        }
+        CaptionCodec::MovText => {
+            let length = data.get(..2).map_or(0, |bytes| u16::from_be_bytes([bytes[0], bytes[1]]) as usize);
+            let rest = data.get(2..).unwrap_or_default();
+            String::from_utf8_lossy(&rest[..length.min(rest.len())]).into_owned()
+        }
+    };
+    text.lines()
+        .map(|line| strip_tags(line.trim()))
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Reads `00:01:02,500 --> 00:01:04.000 align:start`; WebVTT settings after the
+/// end time are ignored.
+fn parse_timing(line: &str) -> Option<(f64, f64)> {
+    let (start, rest) = line.split_once("-->")?;
+    let end = rest.split_whitespace().next()?;
+    Some((parse_time(start.trim())?, parse_time(end)?))
+}
+
+/// Reads `HH:MM:SS,mmm`, `HH:MM:SS.mmm`, or `MM:SS.mmm` as seconds.
+fn parse_time(time: &str) -> Option<f64> {
+    let mut seconds = 0.0;
+    for part in time.split(':') {
+        let value: f64 = part.replace(',', ".").parse().ok()?;
+        seconds = seconds * 60.0 + value;
+    }
+    (seconds.is_finite() && seconds >= 0.0).then_some(seconds)
+}
+
+/// Drops WebVTT markup such as `<v Speaker>` or `<c.yellow>`.
+fn strip_tags(line: &str) -> String {
+    let mut text = String::with_capacity(line.len());
+    let mut in_tag = false;
+    for c in line.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.trim().to_string()
+}
+
+/// Drops ASS override blocks such as `{\i1}` or `{\an8}`.
+fn strip_overrides(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut in_block = false;
+    for c in text.chars() {
+        match c {
+            '{' => in_block = true,
+            '}' if in_block => in_block = false,
+            c if !in_block => stripped.push(c),
+            _ => {}
+        }
+    }
+    stripped
+}
+
+/// Assigns every cue to the page that was on screen for most of it, given the
+/// time each page appeared; a cue without duration goes to the page on screen
+/// when it started. Speech before the first page goes to the first page. A cue
+/// repeating the previous one, as rolling captions do, is kept only once.
+pub fn align(cues: &[Cue], timestamps: &[f64]) -> Vec<Vec<Cue>> {
+    let mut aligned = vec![Vec::new(); timestamps.len()];
+    if timestamps.is_empty() {
+        return aligned;
+    }
+    let mut previous: Option<&str> = None;
+    for cue in cues {
+        if previous == Some(cue.text.as_str()) {
+            continue;
+        }
+        previous = Some(&cue.text);
+        let first = timestamps.iter().rposition(|&start| start <= cue.start).unwrap_or(0);
+        let last = timestamps.iter().rposition(|&start| start < cue.end).unwrap_or(0).max(first);
+        // The first page also covers the time before it appeared.
+        let overlap = |page: usize| {
+            let start = if page == 0 { f64::NEG_INFINITY } else { timestamps[page] };
+            let end = timestamps.get(page + 1).copied().unwrap_or(f64::INFINITY);
+            cue.end.min(end) - cue.start.max(start)
+        };
+        // The earliest page wins ties.
+        let page = (first..=last).rev().max_by(|&a, &b| overlap(a).total_cmp(&overlap(b))).unwrap_or(first);
+        aligned[page].push(cue.clone());
+    }
+    aligned
+}
+
+/// Joins the cues of one page into a paragraph. Labelled cues are prefixed with
+/// `Speaker N: ` whenever the speaker changes, and at the start of the page.
+pub fn page_speech(cues: &[Cue]) -> String {
+    let mut text = String::new();
+    let mut current_speaker = None;
+    for cue in cues {
+        if !text.is_empty() {
+            text.push(' ');
+        }
+        let speaker = cue.speaker.as_deref();
+        if speaker.is_some() && current_speaker != speaker {
+            current_speaker = speaker;
+            text.push_str(&format!("{}: ", speaker.unwrap_or_default()));
+        }
+        text.push_str(&cue.text);
+    }
+    text
+}
+
+/// Joins the cues spoken while each page was on screen, as aligned by [`align`].
+pub fn speech_by_page(cues: &[Cue], timestamps: &[f64]) -> Vec<String> {
+    align(cues, timestamps).iter().map(|page| page_speech(page)).collect()
+}
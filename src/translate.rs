@@ -0,0 +1,37 @@
+//! Translation Module
+//!
+//! Handles the optional translation of the OCR text: a [`Translator`] turns
+//! each page's text into another language, which is kept next to the original
+//! in `index.json` and printed after it in the Markdown and PDF outputs.
+//! Backends are pluggable; an OpenAI-compatible one lives in the `llm` module.
+
+use crate::postprocess::dehyphenate;
+use anyhow::Result;
+use log::{info, warn};
+
+/// A stage that translates the OCR text of a page.
+pub trait Translator {
+    /// Returns `text` translated into `language`, given as a name or code such
+    /// as `English` or `en`, keeping its line structure.
+    fn translate(&self, text: &str, language: &str) -> Result<String>;
+}
+
+/// Runs `translator` over the OCR text of every page. Pages without text are
+/// skipped, and a page the backend fails on is skipped with a warning, so one
+/// failed request does not lose the rest of the run.
+pub fn translate_pages(translator: &dyn Translator, texts: &[String], language: &str) -> Vec<Option<String>> {
+    texts
+        .iter()
+        .enumerate()
+        .map(|(i, text)| {
+            if text.trim().is_empty() {
+                return None;
+            }
+            info!("Translating the text of page {} of {}...", i + 1, texts.len());
+            translator
+                .translate(&dehyphenate(text), language)
+                .inspect_err(|e| warn!("Could not translate the text of page {}: {:#}", i + 1, e))
+                .ok()
+        })
+        .collect()
+}
@@ -0,0 +1,219 @@
+//! Upscale Module
+//!
+//! Handles enlarging low-resolution frames with a super-resolution model
+//! before OCR, for 720p recordings of dense slides whose small text Tesseract
+//! cannot read even when scaled up with bicubic filtering. The model is either
+//! Real-ESRGAN, run through its `realesrgan-ncnn-vulkan` program, or an ESRGAN
+//! model in ONNX format. Only the image given to the OCR engine is enlarged;
+//! word boxes are mapped back onto the frame as recorded.
+
+use crate::ocr::{OcrEngine, Recognition};
+use anyhow::{Context, Result, bail, ensure};
+use image::imageops::FilterType;
+use image::{DynamicImage, RgbImage};
+use log::debug;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+#[cfg(feature = "onnx")]
+use anyhow::anyhow;
+#[cfg(feature = "onnx")]
+use ort::session::Session;
+#[cfg(feature = "onnx")]
+use ort::value::Tensor;
+#[cfg(feature = "onnx")]
+use std::path::Path;
+#[cfg(feature = "onnx")]
+use std::sync::Mutex;
+
+/// Scale factors frames can be enlarged by.
+pub const FACTORS: [u32; 2] = [2, 4];
+/// Program running Real-ESRGAN, looked up on the `PATH`.
+pub const REALESRGAN_PROGRAM: &str = "realesrgan-ncnn-vulkan";
+/// Real-ESRGAN model used, the one shipped with the program that enlarges by
+/// both factors.
+const REALESRGAN_MODEL: &str = "realesr-animevideov3";
+
+/// Creates the upscaler for a run: the `--upscale-model` ONNX model when
+/// given, else Real-ESRGAN, with its files in `ocr/upscale`.
+pub fn create_upscaler<'a>(config: &'a crate::Config, factor: u32) -> Result<Box<dyn Upscaler + 'a>> {
+    #[cfg(feature = "onnx")]
+    if let Some(model) = &config.upscale_model {
+        return Ok(Box::new(OnnxUpscaler::new(model)?));
+    }
+    Ok(Box::new(RealEsrgan::new(config.output_dir.join("ocr").join("upscale"), factor)?))
+}
+
+/// A super-resolution model.
+pub trait Upscaler: Sync {
+    /// Short name of the model, used in logs.
+    fn name(&self) -> &'static str;
+
+    /// Enlarges an image by the model's factor, or about it.
+    fn upscale(&self, image: &RgbImage) -> Result<RgbImage>;
+}
+
+/// Reads frames with an engine after enlarging them `factor` times with an
+/// [`Upscaler`], with boxes in the pixels of the frames given.
+pub struct Upscaled<'a> {
+    engine: Box<dyn OcrEngine + 'a>,
+    upscaler: Box<dyn Upscaler + 'a>,
+    factor: u32,
+}
+
+impl<'a> Upscaled<'a> {
+    pub fn new(engine: Box<dyn OcrEngine + 'a>, upscaler: Box<dyn Upscaler + 'a>, factor: u32) -> Self {
+        Self { engine, upscaler, factor }
+    }
+
+    /// The image enlarged to exactly `factor` times its size, grayscale again
+    /// if it was, as after the `--preprocess` steps; a model enlarging by
+    /// another factor is resized to it.
+    fn upscale(&self, image: &DynamicImage) -> Result<DynamicImage> {
+        let (width, height) = (image.width() * self.factor, image.height() * self.factor);
+        let mut upscaled = self.upscaler.upscale(&image.to_rgb8())?;
+        if upscaled.dimensions() != (width, height) {
+            let name = self.upscaler.name();
+            debug!("{} gave a {:?} image; resizing it to {}x{}.", name, upscaled.dimensions(), width, height);
+            upscaled = image::imageops::resize(&upscaled, width, height, FilterType::Lanczos3);
+        }
+        Ok(match image {
+            DynamicImage::ImageLuma8(_) => DynamicImage::ImageLuma8(image::imageops::grayscale(&upscaled)),
+            _ => DynamicImage::ImageRgb8(upscaled),
+        })
+    }
+
+    /// Maps the boxes of a reading of an enlarged image back onto the image.
+    fn shrink(&self, recognition: Recognition) -> Recognition {
+        let factor = self.factor as i32;
+        recognition.map_boxes(|(x1, y1, x2, y2)| {
+            (x1 / factor, y1 / factor, (x2 + factor - 1) / factor, (y2 + factor - 1) / factor)
+        })
+    }
+}
+
+impl OcrEngine for Upscaled<'_> {
+    fn name(&self) -> &'static str {
+        self.engine.name()
+    }
+
+    fn recognize(&self, image: &DynamicImage) -> Result<Recognition> {
+        Ok(self.shrink(self.engine.recognize(&self.upscale(image)?)?))
+    }
+
+    fn batch_size(&self) -> usize {
+        self.engine.batch_size()
+    }
+
+    fn recognize_batch(&self, images: &[DynamicImage]) -> Result<Vec<Recognition>> {
+        let upscaled = images.iter().map(|image| self.upscale(image)).collect::<Result<Vec<_>>>()?;
+        let recognitions = self.engine.recognize_batch(&upscaled)?;
+        Ok(recognitions.into_iter().map(|recognition| self.shrink(recognition)).collect())
+    }
+
+    fn alternatives(&self) -> Vec<String> {
+        self.engine.alternatives()
+    }
+
+    fn recognize_alternative(&self, image: &DynamicImage, alternative: &str) -> Result<Recognition> {
+        Ok(self.shrink(self.engine.recognize_alternative(&self.upscale(image)?, alternative)?))
+    }
+}
+
+/// Real-ESRGAN, run as [`REALESRGAN_PROGRAM`] on PNG files in a work
+/// directory, one image per run.
+pub struct RealEsrgan {
+    work_dir: PathBuf,
+    factor: u32,
+    /// Images enlarged so far, numbering the files of each run.
+    runs: AtomicUsize,
+}
+
+impl RealEsrgan {
+    /// Checks that the program starts, writing its files to `work_dir`.
+    pub fn new(work_dir: PathBuf, factor: u32) -> Result<Self> {
+        ensure!(FACTORS.contains(&factor), "Frames can only be upscaled {:?} times, not {}", FACTORS, factor);
+        Command::new(REALESRGAN_PROGRAM)
+            .arg("-h")
+            .output()
+            .with_context(|| format!("Failed to run {}; is it installed and on the PATH?", REALESRGAN_PROGRAM))?;
+        fs::create_dir_all(&work_dir).with_context(|| format!("Failed to create {:?}", work_dir))?;
+        Ok(Self { work_dir, factor, runs: AtomicUsize::new(0) })
+    }
+}
+
+impl Upscaler for RealEsrgan {
+    fn name(&self) -> &'static str {
+        "realesrgan"
+    }
+
+    fn upscale(&self, image: &RgbImage) -> Result<RgbImage> {
+        let run = self.runs.fetch_add(1, Ordering::Relaxed);
+        let input = self.work_dir.join(format!("frame_{}.png", run));
+        let output = self.work_dir.join(format!("frame_{}_x{}.png", run, self.factor));
+        image.save(&input).with_context(|| format!("Failed to write {:?}", input))?;
+        let result = Command::new(REALESRGAN_PROGRAM)
+            .arg("-i")
+            .arg(&input)
+            .arg("-o")
+            .arg(&output)
+            .args(["-n", REALESRGAN_MODEL, "-s", &self.factor.to_string(), "-f", "png"])
+            .output()
+            .with_context(|| format!("Failed to run {}", REALESRGAN_PROGRAM));
+        let upscaled = result.and_then(|result| {
+            if !result.status.success() {
+                bail!("{} failed: {}", REALESRGAN_PROGRAM, String::from_utf8_lossy(&result.stderr).trim());
+            }
+            Ok(image::open(&output).with_context(|| format!("Failed to read {:?}", output))?.to_rgb8())
+        });
+        let _ = fs::remove_file(&input);
+        let _ = fs::remove_file(&output);
+        upscaled
+    }
+}
+
+/// An ESRGAN model in ONNX format: it takes RGB images from 0 to 1, shaped
+/// (1, 3, height, width), and gives them enlarged in the same layout.
+#[cfg(feature = "onnx")]
+pub struct OnnxUpscaler {
+    session: Mutex<Session>,
+}
+
+#[cfg(feature = "onnx")]
+impl OnnxUpscaler {
+    /// Loads the model, starting ONNX Runtime if needed.
+    pub fn new(model: &Path) -> Result<Self> {
+        let session = Session::builder()
+            .and_then(|mut builder| builder.commit_from_file(model))
+            .with_context(|| format!("Failed to load the ONNX model {:?}", model))?;
+        Ok(Self { session: Mutex::new(session) })
+    }
+}
+
+#[cfg(feature = "onnx")]
+impl Upscaler for OnnxUpscaler {
+    fn name(&self) -> &'static str {
+        "onnx"
+    }
+
+    fn upscale(&self, image: &RgbImage) -> Result<RgbImage> {
+        let (width, height) = (image.width() as usize, image.height() as usize);
+        let mut data = vec![0.0f32; 3 * width * height];
+        for (x, y, pixel) in image.enumerate_pixels() {
+            for channel in 0..3 {
+                data[(channel * height + y as usize) * width + x as usize] = pixel.0[channel] as f32 / 255.0;
+            }
+        }
+        let input = Tensor::from_array(([1usize, 3, height, width], data))?;
+        let mut session = self.session.lock().map_err(|_| anyhow!("The upscaling model is unusable"))?;
+        let outputs = session.run(ort::inputs![input])?;
+        let (shape, values) = outputs[0].try_extract_tensor::<f32>()?;
+        ensure!(shape.len() == 4 && shape[1] == 3, "Unexpected upscaling model output of shape {:?}", &shape[..]);
+        let (out_h, out_w) = (shape[2] as usize, shape[3] as usize);
+        Ok(RgbImage::from_fn(out_w as u32, out_h as u32, |x, y| {
+            let value = |channel: usize| values[(channel * out_h + y as usize) * out_w + x as usize];
+            image::Rgb([0, 1, 2].map(|channel| (value(channel).clamp(0.0, 1.0) * 255.0).round() as u8))
+        }))
+    }
+}
@@ -1,25 +1,54 @@
 //! Video Processing Module
 //!
-//! Handles the decoding of video files and extraction of individual frames
-//! using the ffmpeg-next crate.
+//! Handles the decoding of video files and extraction of individual frames, of
+//! the audio track, and of text subtitles, and the encoding of frames into new
+//! videos, using the ffmpeg-next crate.
 
 use ffmpeg_next as ffmpeg;
-use ffmpeg::format::{input, Pixel};
+use ffmpeg::ChannelLayout;
+use ffmpeg::codec::Id as CodecId;
+use ffmpeg::format::{input, output, Pixel, Sample};
+use ffmpeg::format::sample::Type as SampleType;
 use ffmpeg::media::Type;
 use ffmpeg::software::scaling::{Context as ScalingContext, flag::Flags};
+use ffmpeg::util::frame::audio::Audio;
 use ffmpeg::util::frame::video::Video;
+use crate::transcript::{self, CaptionCodec, Cue};
 use image::{ImageBuffer, Rgb};
 use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
 use std::path::Path;
 use log::info;
 
+/// FFmpeg's `AV_NOPTS_VALUE`, for timestamps a stream does not give.
+const NO_TIMESTAMP: i64 = i64::MIN;
+
+/// Where a decoded frame sits in the video.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FrameMeta {
+    /// Decoding-order index of the frame.
+    pub index: usize,
+    /// Presentation timestamp in seconds from the start of the stream, if the
+    /// container gives one.
+    pub pts: Option<f64>,
+    /// How long the frame is shown for, in seconds, if known.
+    pub duration: Option<f64>,
+}
+
+impl FrameMeta {
+    /// A frame known only by its index.
+    pub fn untimed(index: usize) -> Self {
+        Self { index, pts: None, duration: None }
+    }
+}
+
 /// Attempts to get the total number of frames from video metadata.
 ///
 /// This function is much faster than decoding the whole video, but the
 /// result can be an estimate for variable frame rate (VFR) videos.
 pub fn get_frame_count(path: &Path) -> Result<u64> {
     ffmpeg::init().context("Failed to initialize FFmpeg")?;
-    let ictx = input(path).context("Failed to open input file for frame count")?;
+    let ictx = input(crate::paths::to_utf8(path)?).context("Failed to open input file for frame count")?;
     let stream = ictx
         .streams()
         .best(Type::Video)
@@ -46,23 +75,115 @@ pub fn get_frame_count(path: &Path) -> Result<u64> {
     Err(anyhow!("Could not determine frame count from video metadata"))
 }
 
+/// Returns the average frame rate of the video stream in frames per second.
+pub fn get_frame_rate(path: &Path) -> Result<f64> {
+    ffmpeg::init().context("Failed to initialize FFmpeg")?;
+    let ictx = input(crate::paths::to_utf8(path)?).context("Failed to open input file for frame rate")?;
+    let stream = ictx
+        .streams()
+        .best(Type::Video)
+        .ok_or_else(|| anyhow!("Could not find video stream in file"))?;
+
+    let rate = stream.avg_frame_rate();
+    if rate.0 > 0 && rate.1 > 0 {
+        Ok(rate.0 as f64 / rate.1 as f64)
+    } else {
+        Err(anyhow!("Video stream does not report a frame rate"))
+    }
+}
+
+/// Returns the `creation_time` tag of the container, or of the video stream when the
+/// container has none.
+pub fn get_creation_time(path: &Path) -> Result<Option<String>> {
+    ffmpeg::init().context("Failed to initialize FFmpeg")?;
+    let ictx = input(crate::paths::to_utf8(path)?).context("Failed to open input file for metadata")?;
+    if let Some(value) = ictx.metadata().get("creation_time") {
+        return Ok(Some(value.to_string()));
+    }
+    Ok(ictx
+        .streams()
+        .best(Type::Video)
+        .and_then(|stream| stream.metadata().get("creation_time").map(str::to_string)))
+}
+
+/// A chapter marker of the input container.
+#[derive(Debug, Clone)]
+pub struct Chapter {
+    pub title: Option<String>,
+    /// Start of the chapter in seconds.
+    pub start: f64,
+    /// End of the chapter in seconds.
+    pub end: f64,
+}
+
+/// Reads the chapter markers of the container, ordered by start time. Returns an
+/// empty list when the file has none.
+pub fn get_chapters(path: &Path) -> Result<Vec<Chapter>> {
+    ffmpeg::init().context("Failed to initialize FFmpeg")?;
+    let ictx = input(crate::paths::to_utf8(path)?).context("Failed to open input file for chapters")?;
+    let mut chapters: Vec<Chapter> = ictx
+        .chapters()
+        .map(|chapter| {
+            let time_base = f64::from(chapter.time_base());
+            Chapter {
+                title: chapter
+                    .metadata()
+                    .get("title")
+                    .map(str::trim)
+                    .filter(|title| !title.is_empty())
+                    .map(str::to_string),
+                start: chapter.start() as f64 * time_base,
+                end: chapter.end() as f64 * time_base,
+            }
+        })
+        .collect();
+    chapters.sort_by(|a, b| a.start.total_cmp(&b.start));
+    Ok(chapters)
+}
+
 /// Processes video frames using a streaming approach.
 ///
 /// Instead of returning a Vec of all frames, this function decodes one frame at a time
 /// and passes it to the `on_frame` closure provided by the caller. This keeps memory
-/// usage low and constant.
-pub fn process_frames_stream<F>(path: &Path, mut on_frame: F) -> Result<()>
+/// usage low and constant. Each frame comes with its [`FrameMeta`], so its
+/// presentation timestamp is known even in variable frame rate videos.
+pub fn process_frames_stream<F>(path: &Path, on_frame: F) -> Result<()>
+where
+    F: FnMut(ImageBuffer<Rgb<u8>, Vec<u8>>, FrameMeta) -> Result<()>,
+{
+    decode_frames(path, None, on_frame)
+}
+
+/// Decodes the video but only converts and hands over the frames at `indices`.
+///
+/// `indices` are decoding-order frame numbers, sorted ascending. Every packet up
+/// to the last selected frame still has to be decoded, but the colour conversion
+/// and copy are skipped for all other frames, and decoding stops as soon as the
+/// last selected frame has been delivered.
+pub fn process_selected_frames<F>(path: &Path, indices: &[usize], on_frame: F) -> Result<()>
 where
-    F: FnMut(ImageBuffer<Rgb<u8>, Vec<u8>>) -> Result<()>,
+    F: FnMut(ImageBuffer<Rgb<u8>, Vec<u8>>, FrameMeta) -> Result<()>,
+{
+    if indices.is_empty() {
+        return Ok(());
+    }
+    decode_frames(path, Some(indices), on_frame)
+}
+
+fn decode_frames<F>(path: &Path, selection: Option<&[usize]>, mut on_frame: F) -> Result<()>
+where
+    F: FnMut(ImageBuffer<Rgb<u8>, Vec<u8>>, FrameMeta) -> Result<()>,
 {
     ffmpeg::init().context("Failed to initialize FFmpeg")?;
      
-    let mut ictx = input(path).context("Failed to open input file")?;
+    let mut ictx = input(crate::paths::to_utf8(path)?).context("Failed to open input file")?;
     let input = ictx
         .streams()
         .best(Type::Video)
         .context("Could not find video stream")?;
     let video_stream_index = input.index();
+    let time_base = f64::from(input.time_base());
+    let start_time = Some(input.start_time()).filter(|&start| start != NO_TIMESTAMP).unwrap_or(0);
 
     let context_decoder = ffmpeg::codec::context::Context::from_parameters(input.parameters())
         .context("Failed to create decoder context")?;
@@ -79,18 +200,27 @@ where
         Flags::BILINEAR,
     ).context("Failed to create scaler")?;
 
+    let last_selected = selection.and_then(|indices| indices.last().copied());
     let mut frame_count = 0;
+    let mut delivered = 0;
+    // Returns Ok(false) once every selected frame has been delivered.
     let mut receive_and_process_decoded_frames = 
-        |decoder: &mut ffmpeg::decoder::Video| -> Result<()> {
+        |decoder: &mut ffmpeg::decoder::Video| -> Result<bool> {
             let mut decoded = Video::empty();
             while decoder.receive_frame(&mut decoded).is_ok() {
+                let index = frame_count;
+                frame_count += 1;
+                if selection.is_some_and(|indices| indices.binary_search(&index).is_err()) {
+                    continue;
+                }
+
                 let mut rgb_frame = Video::empty();
                 scaler.run(&decoded, &mut rgb_frame).context("Scaler failed")?;
                 
                 let frame_data = rgb_frame.data(0);
                 let width = rgb_frame.width() as usize;
                 let height = rgb_frame.height() as usize;
-                let stride = rgb_frame.stride(0) as usize;
+                let stride = rgb_frame.stride(0);
 
                 if stride == 0 {
                     return Err(anyhow::anyhow!("Invalid frame stride"));
@@ -109,23 +239,260 @@ where
                 let img: ImageBuffer<Rgb<u8>, Vec<u8>> = 
                     ImageBuffer::from_vec(width as u32, height as u32, new_vec)
                         .context("Failed to create image buffer from frame data")?;
+                let meta = FrameMeta {
+                    index,
+                    pts: decoded.timestamp().map(|pts| (pts - start_time) as f64 * time_base),
+                    duration: Some(decoded.packet().duration)
+                        .filter(|&duration| duration > 0)
+                        .map(|duration| duration as f64 * time_base),
+                };
 
                 // Pass the processed frame to the callback instead of collecting it.
-                on_frame(img)?;
-                frame_count += 1;
+                on_frame(img, meta)?;
+                delivered += 1;
+                if last_selected == Some(index) {
+                    return Ok(false);
+                }
             }
-            Ok(())
+            Ok(true)
         };
 
+    let mut finished_early = false;
     for (stream, packet) in ictx.packets() {
         if stream.index() == video_stream_index {
             decoder.send_packet(&packet).context("Failed to send packet to decoder")?;
-            receive_and_process_decoded_frames(&mut decoder)?;
+            if !receive_and_process_decoded_frames(&mut decoder)? {
+                finished_early = true;
+                break;
+            }
         }
     }
-    decoder.send_eof()?;
-    receive_and_process_decoded_frames(&mut decoder)?;
+    if !finished_early {
+        decoder.send_eof()?;
+        receive_and_process_decoded_frames(&mut decoder)?;
+    }
 
-    info!("Finished processing {} frames from video stream.", frame_count);
+    info!("Finished processing {} frames from video stream ({} delivered).", frame_count, delivered);
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Decodes the best audio stream into mono 32-bit float samples at
+/// `sample_rate` Hz, the input speech recognizers expect.
+pub fn extract_audio(path: &Path, sample_rate: u32) -> Result<Vec<f32>> {
+    ffmpeg::init().context("Failed to initialize FFmpeg")?;
+    let mut ictx = input(crate::paths::to_utf8(path)?).context("Failed to open input file for audio")?;
+    let stream = ictx
+        .streams()
+        .best(Type::Audio)
+        .context("Could not find an audio stream")?;
+    let audio_stream_index = stream.index();
+
+    let context_decoder = ffmpeg::codec::context::Context::from_parameters(stream.parameters())
+        .context("Failed to create decoder context")?;
+    let mut decoder = context_decoder.decoder().audio()
+        .context("Failed to create audio decoder")?;
+    let mut resampler = decoder
+        .resampler(Sample::F32(SampleType::Packed), ChannelLayout::MONO, sample_rate)
+        .context("Failed to create resampler")?;
+
+    let mut samples = Vec::new();
+    let mut receive_and_resample = |decoder: &mut ffmpeg::decoder::Audio| -> Result<()> {
+        let mut decoded = Audio::empty();
+        while decoder.receive_frame(&mut decoded).is_ok() {
+            let mut resampled = Audio::empty();
+            resampler.run(&decoded, &mut resampled).context("Resampler failed")?;
+            samples.extend_from_slice(resampled.plane::<f32>(0));
+        }
+        Ok(())
+    };
+
+    for (stream, packet) in ictx.packets() {
+        if stream.index() == audio_stream_index {
+            decoder.send_packet(&packet).context("Failed to send packet to decoder")?;
+            receive_and_resample(&mut decoder)?;
+        }
+    }
+    decoder.send_eof()?;
+    receive_and_resample(&mut decoder)?;
+    // Samples still buffered in the resampler.
+    loop {
+        let mut resampled = Audio::empty();
+        resampler.flush(&mut resampled).context("Resampler failed")?;
+        if resampled.samples() == 0 {
+            break;
+        }
+        samples.extend_from_slice(resampled.plane::<f32>(0));
+    }
+
+    info!("Extracted {:.1} s of audio.", samples.len() as f64 / sample_rate as f64);
+    Ok(samples)
+}
+
+/// Reads the cues of the first text subtitle stream (SubRip, WebVTT, ASS/SSA,
+/// or MP4 timed text), ordered by start time. Returns an empty list when the
+/// file has none; image-based subtitles such as DVD or Blu-ray ones are skipped.
+pub fn extract_subtitles(path: &Path) -> Result<Vec<Cue>> {
+    ffmpeg::init().context("Failed to initialize FFmpeg")?;
+    let mut ictx = input(crate::paths::to_utf8(path)?).context("Failed to open input file for subtitles")?;
+    let Some((subtitle_stream_index, codec, time_base)) = ictx.streams().find_map(|stream| {
+        let codec = match stream.parameters().id() {
+            CodecId::SUBRIP | CodecId::SRT | CodecId::WEBVTT | CodecId::TEXT => CaptionCodec::Text,
+            CodecId::ASS | CodecId::SSA => CaptionCodec::Ass,
+            CodecId::MOV_TEXT => CaptionCodec::MovText,
+            _ => return None,
+        };
+        Some((stream.index(), codec, f64::from(stream.time_base())))
+    }) else {
+        return Ok(Vec::new());
+    };
+
+    let mut cues = Vec::new();
+    for (stream, packet) in ictx.packets() {
+        if stream.index() != subtitle_stream_index {
+            continue;
+        }
+        let (Some(pts), Some(data)) = (packet.pts(), packet.data()) else { continue };
+        let text = transcript::packet_text(codec, data);
+        if !text.is_empty() {
+            let start = pts as f64 * time_base;
+            cues.push(Cue { start, end: start + packet.duration() as f64 * time_base, text, speaker: None });
+        }
+    }
+    cues.sort_by(|a, b| a.start.total_cmp(&b.start));
+    info!("Read {} cues from the subtitle stream.", cues.len());
+    Ok(cues)
+}
+
+/// Encodes RGB frames into a new video file at a constant frame rate.
+///
+/// The container is chosen from the file extension (e.g. `.mp4`, `.webm`) and the
+/// codec is that container's default video codec. Frames must all have the size
+/// given to [`VideoEncoder::create`], with even width and height.
+pub struct VideoEncoder {
+    octx: ffmpeg::format::context::Output,
+    encoder: ffmpeg::encoder::video::Encoder,
+    scaler: ScalingContext,
+    stream_index: usize,
+    encoder_time_base: ffmpeg::Rational,
+    width: u32,
+    height: u32,
+    next_pts: i64,
+}
+
+impl VideoEncoder {
+    /// Creates the output file and writes its header.
+    pub fn create(path: &Path, width: u32, height: u32, fps: i32) -> Result<Self> {
+        ffmpeg::init().context("Failed to initialize FFmpeg")?;
+        if !width.is_multiple_of(2) || !height.is_multiple_of(2) {
+            return Err(anyhow!("Video dimensions must be even, got {}x{}", width, height));
+        }
+
+        let path_str = crate::paths::to_utf8(path)?;
+        let mut octx = output(path_str).context("Failed to create output video file")?;
+        let codec_id = octx.format().codec(path_str, Type::Video);
+        let codec = ffmpeg::encoder::find(codec_id)
+            .ok_or_else(|| anyhow!("No encoder available for {:?}", codec_id))?;
+        let global_header = octx.format().flags().contains(ffmpeg::format::Flags::GLOBAL_HEADER);
+
+        let mut encoder = ffmpeg::codec::context::Context::new_with_codec(codec)
+            .encoder()
+            .video()
+            .context("Failed to create video encoder")?;
+        let encoder_time_base = ffmpeg::Rational::new(1, fps);
+        encoder.set_width(width);
+        encoder.set_height(height);
+        encoder.set_format(Pixel::YUV420P);
+        encoder.set_time_base(encoder_time_base);
+        encoder.set_frame_rate(Some(ffmpeg::Rational::new(fps, 1)));
+        if global_header {
+            encoder.set_flags(ffmpeg::codec::Flags::GLOBAL_HEADER);
+        }
+        let encoder = encoder.open_as(codec).context("Failed to open video encoder")?;
+
+        let stream_index = {
+            let mut stream = octx.add_stream(codec).context("Failed to add video stream")?;
+            stream.set_time_base(encoder_time_base);
+            stream.set_parameters(&encoder);
+            stream.index()
+        };
+        octx.write_header().context("Failed to write video header")?;
+
+        let scaler = ScalingContext::get(
+            Pixel::RGB24,
+            width,
+            height,
+            Pixel::YUV420P,
+            width,
+            height,
+            Flags::BILINEAR,
+        )
+        .context("Failed to create scaler")?;
+
+        info!("Encoding {}x{} video at {} fps with {} to {:?}", width, height, fps, codec.name(), path);
+        Ok(Self {
+            octx,
+            encoder,
+            scaler,
+            stream_index,
+            encoder_time_base,
+            width,
+            height,
+            next_pts: 0,
+        })
+    }
+
+    /// Encodes one frame, shown for a single frame period.
+    pub fn push_frame(&mut self, image: &ImageBuffer<Rgb<u8>, Vec<u8>>) -> Result<()> {
+        if image.dimensions() != (self.width, self.height) {
+            return Err(anyhow!(
+                "Frame is {}x{} but the video is {}x{}",
+                image.width(),
+                image.height(),
+                self.width,
+                self.height
+            ));
+        }
+
+        let mut rgb_frame = Video::new(Pixel::RGB24, self.width, self.height);
+        let stride = rgb_frame.stride(0);
+        let row_len = self.width as usize * 3;
+        let data = rgb_frame.data_mut(0);
+        for (y, row) in image.as_raw().chunks_exact(row_len).enumerate() {
+            data[y * stride..y * stride + row_len].copy_from_slice(row);
+        }
+
+        let mut yuv_frame = Video::empty();
+        self.scaler.run(&rgb_frame, &mut yuv_frame).context("Scaler failed")?;
+        yuv_frame.set_pts(Some(self.next_pts));
+        self.next_pts += 1;
+
+        self.encoder.send_frame(&yuv_frame).context("Failed to send frame to encoder")?;
+        self.write_pending_packets()
+    }
+
+    /// Flushes the encoder and finalizes the file.
+    pub fn finish(mut self) -> Result<()> {
+        self.encoder.send_eof().context("Failed to flush encoder")?;
+        self.write_pending_packets()?;
+        self.octx.write_trailer().context("Failed to write video trailer")?;
+        info!("Finished encoding {} frames.", self.next_pts);
+        Ok(())
+    }
+
+    fn write_pending_packets(&mut self) -> Result<()> {
+        let stream_time_base = self
+            .octx
+            .stream(self.stream_index)
+            .map(|stream| stream.time_base())
+            .unwrap_or(self.encoder_time_base);
+        let mut packet = ffmpeg::Packet::empty();
+        while self.encoder.receive_packet(&mut packet).is_ok() {
+            packet.set_stream(self.stream_index);
+            packet.rescale_ts(self.encoder_time_base, stream_time_base);
+            packet
+                .write_interleaved(&mut self.octx)
+                .context("Failed to write video packet")?;
+        }
+        Ok(())
+    }
+}
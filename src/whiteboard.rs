@@ -0,0 +1,84 @@
+//! Whiteboard Module
+//!
+//! Handles cleaning up frames of whiteboards filmed in a room, like a
+//! document scanner app's whiteboard mode: the uneven lighting and glare are
+//! evened out by dividing each pixel by the board's brightness around it, the
+//! board is made plain white, and the marker strokes are darkened and their
+//! colours strengthened. Frames are cleaned up as they are decoded, so the
+//! cleaned frames are what is hashed, recognized, and shown in the documents.
+
+use image::imageops::{self, FilterType};
+use image::{ImageBuffer, Rgb, RgbImage};
+
+/// Factor frames are scaled down by to estimate the board's brightness.
+const BACKGROUND_SCALE: u32 = 8;
+/// Radius, in pixels of the scaled-down frame, of the window the brightest
+/// pixel is taken from, which must be wider than any marker stroke.
+const BACKGROUND_RADIUS: i32 = 3;
+/// Blur, in pixels of the scaled-down frame, smoothing the board's brightness.
+const BACKGROUND_BLUR: f32 = 2.0;
+/// Brightness, once the lighting is evened out, above which a pixel is board.
+const BOARD_LEVEL: u8 = 220;
+/// Factor the difference between a stroke's colour and grey is multiplied by.
+const SATURATION_BOOST: f32 = 1.6;
+/// Gamma darkening the strokes.
+const STROKE_GAMMA: f32 = 1.8;
+
+/// Estimates the brightness of the board at every pixel: the frame scaled
+/// down, with each pixel replaced by the brightest around it, which removes
+/// the strokes, then blurred and scaled back up.
+pub fn background(frame: &RgbImage) -> RgbImage {
+    let (width, height) = frame.dimensions();
+    let (small_w, small_h) = (width.div_ceil(BACKGROUND_SCALE).max(1), height.div_ceil(BACKGROUND_SCALE).max(1));
+    let small = imageops::resize(frame, small_w, small_h, FilterType::Triangle);
+    let brightest = ImageBuffer::from_fn(small_w, small_h, |x, y| {
+        let mut max = [0u8; 3];
+        for dy in -BACKGROUND_RADIUS..=BACKGROUND_RADIUS {
+            for dx in -BACKGROUND_RADIUS..=BACKGROUND_RADIUS {
+                let sx = (x as i32 + dx).clamp(0, small_w as i32 - 1) as u32;
+                let sy = (y as i32 + dy).clamp(0, small_h as i32 - 1) as u32;
+                let pixel = small.get_pixel(sx, sy).0;
+                for channel in 0..3 {
+                    max[channel] = max[channel].max(pixel[channel]);
+                }
+            }
+        }
+        Rgb(max)
+    });
+    let smooth = imageops::blur(&brightest, BACKGROUND_BLUR);
+    imageops::resize(&smooth, width, height, FilterType::Triangle)
+}
+
+/// Evens out the lighting of a frame by dividing each pixel by the
+/// [`background`] there, so the board or paper is white everywhere.
+pub fn even_out(frame: &RgbImage) -> RgbImage {
+    let background = background(frame);
+    ImageBuffer::from_fn(frame.width(), frame.height(), |x, y| {
+        let (pixel, board) = (frame.get_pixel(x, y).0, background.get_pixel(x, y).0);
+        Rgb([0, 1, 2].map(|channel| {
+            ((pixel[channel] as f32 / board[channel].max(1) as f32).min(1.0) * 255.0).round() as u8
+        }))
+    })
+}
+
+/// Cleans up a whiteboard frame: evens out its lighting, turns every pixel
+/// brighter than [`BOARD_LEVEL`] white, and saturates the rest, the marker
+/// strokes, stretching their levels up to [`BOARD_LEVEL`] over the whole range
+/// before darkening them, so they fade into the board smoothly.
+pub fn enhance(frame: &RgbImage) -> RgbImage {
+    let mut enhanced = even_out(frame);
+    for pixel in enhanced.pixels_mut() {
+        let even = pixel.0.map(|channel| channel as f32);
+        let level = 0.299 * even[0] + 0.587 * even[1] + 0.114 * even[2];
+        if level >= BOARD_LEVEL as f32 {
+            *pixel = Rgb([255, 255, 255]);
+            continue;
+        }
+        let grey = (even[0] + even[1] + even[2]) / 3.0;
+        *pixel = Rgb(even.map(|value| {
+            let saturated = (grey + (value - grey) * SATURATION_BOOST).clamp(0.0, BOARD_LEVEL as f32);
+            ((saturated / BOARD_LEVEL as f32).powf(STROKE_GAMMA) * 255.0).round() as u8
+        }));
+    }
+    enhanced
+}
@@ -0,0 +1,252 @@
+//! XMP Metadata Module
+//!
+//! Handles building XMP packets that describe an exported page (title, OCR text,
+//! source video, page number, timestamp) and embedding them in PNG files, so
+//! desktop search tools and asset managers can index standalone images.
+
+use anyhow::{Context, Result};
+use image::{ImageBuffer, Rgb};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+// Keyword of the iTXt chunk that holds an XMP packet, as defined by the XMP specification.
+const PNG_XMP_KEYWORD: &str = "XML:com.adobe.xmp";
+
+/// Namespace for the page properties that have no standard XMP equivalent.
+pub const VDP_NAMESPACE: &str = "https://github.com/fabiomolinar/videodocparser/ns/1.0/";
+
+/// Metadata describing one exported page.
+#[derive(Debug, Default)]
+pub struct PageMetadata {
+    pub title: Option<String>,
+    /// The OCR text of the page, one line per reconstructed text line.
+    pub text: String,
+    /// File name of the source video.
+    pub source: String,
+    /// 1-based page number within the output.
+    pub page: usize,
+    /// When the page first appeared in the source video, in seconds.
+    pub timestamp: Option<f64>,
+}
+
+/// Serializes the metadata as a complete XMP packet.
+pub fn packet(metadata: &PageMetadata) -> String {
+    let mut properties = String::new();
+    if let Some(title) = &metadata.title {
+        properties.push_str(&lang_alt("dc:title", title));
+    }
+    if !metadata.text.is_empty() {
+        properties.push_str(&lang_alt("dc:description", &metadata.text));
+    }
+    properties.push_str(&format!("   <dc:source>{}</dc:source>\n", escape(&metadata.source)));
+    properties.push_str(&format!(
+        "   <xmp:CreatorTool>videodocparser {}</xmp:CreatorTool>\n",
+        env!("CARGO_PKG_VERSION")
+    ));
+    properties.push_str(&format!("   <vdp:page>{}</vdp:page>\n", metadata.page));
+    if let Some(timestamp) = metadata.timestamp {
+        properties.push_str(&format!("   <vdp:timestamp>{:.3}</vdp:timestamp>\n", timestamp));
+    }
+
+    let namespaces = format!(
+        "xmlns:xmp=\"http://ns.adobe.com/xap/1.0/\"\n    xmlns:vdp=\"{}\"",
+        VDP_NAMESPACE
+    );
+    wrap(&namespaces, &properties)
+}
+
+/// A UTC date and time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateTime {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+}
+
+impl DateTime {
+    /// Parses the `YYYY-MM-DD[T ]HH:MM:SS` prefix of an ISO 8601 timestamp, as found in
+    /// container metadata. Fractional seconds and zone designators are ignored; the
+    /// time is taken as UTC.
+    pub fn parse_iso8601(text: &str) -> Option<Self> {
+        let field = |range: std::ops::Range<usize>| text.get(range)?.parse::<u16>().ok();
+        let bytes = text.as_bytes();
+        if bytes.len() < 19
+            || bytes[4] != b'-'
+            || bytes[7] != b'-'
+            || !matches!(bytes[10], b'T' | b' ')
+            || bytes[13] != b':'
+            || bytes[16] != b':'
+        {
+            return None;
+        }
+        let date_time = Self {
+            year: field(0..4)?,
+            month: field(5..7)? as u8,
+            day: field(8..10)? as u8,
+            hour: field(11..13)? as u8,
+            minute: field(14..16)? as u8,
+            second: field(17..19)? as u8,
+        };
+        let valid = (1..=12).contains(&date_time.month)
+            && (1..=31).contains(&date_time.day)
+            && date_time.hour < 24
+            && date_time.minute < 60
+            && date_time.second < 60;
+        valid.then_some(date_time)
+    }
+
+    /// Converts seconds since the Unix epoch, as in `SOURCE_DATE_EPOCH`. Returns
+    /// `None` for dates after the year 9999.
+    pub fn from_unix_timestamp(seconds: u64) -> Option<Self> {
+        let days = seconds / 86_400;
+        let time = seconds % 86_400;
+        // Civil date from a day count, shifted to start the year in March so the
+        // leap day comes last (Howard Hinnant's algorithm).
+        let z = days + 719_468;
+        let era = z / 146_097;
+        let day_of_era = z % 146_097;
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+        let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+        let year = year_of_era + era * 400 + u64::from(month <= 2);
+        (year <= 9999).then_some(Self {
+            year: year as u16,
+            month: month as u8,
+            day: day as u8,
+            hour: (time / 3600) as u8,
+            minute: (time / 60 % 60) as u8,
+            second: (time % 60) as u8,
+        })
+    }
+
+    /// Formats the date as `YYYY-MM-DDTHH:MM:SSZ`.
+    pub fn to_iso8601(&self) -> String {
+        format!(
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+            self.year, self.month, self.day, self.hour, self.minute, self.second
+        )
+    }
+}
+
+/// Document-level properties of a generated PDF.
+#[derive(Debug, Default, Clone)]
+pub struct DocumentMetadata {
+    pub title: String,
+    pub author: Option<String>,
+    /// Comma-separated keywords.
+    pub keywords: Option<String>,
+    /// When the recording was made, taken from the video's container metadata
+    /// unless `SOURCE_DATE_EPOCH` is set.
+    pub creation_date: Option<DateTime>,
+}
+
+/// Serializes the document packet of a PDF. With `pdfa` set to a part number and
+/// conformance level, the packet also identifies the file as PDF/A.
+pub fn document_packet(metadata: &DocumentMetadata, pdfa: Option<(u8, &str)>, pdfua: Option<u8>) -> String {
+    let mut properties = lang_alt("dc:title", &metadata.title);
+    if let Some(author) = &metadata.author {
+        properties.push_str(&format!(
+            "   <dc:creator>\n    <rdf:Seq>\n     <rdf:li>{}</rdf:li>\n    </rdf:Seq>\n   </dc:creator>\n",
+            escape(author)
+        ));
+    }
+    if let Some(keywords) = &metadata.keywords {
+        properties.push_str(&format!("   <pdf:Keywords>{}</pdf:Keywords>\n", escape(keywords)));
+    }
+    if let Some(date) = &metadata.creation_date {
+        properties.push_str(&format!("   <xmp:CreateDate>{}</xmp:CreateDate>\n", date.to_iso8601()));
+    }
+    properties.push_str(&format!(
+        "   <xmp:CreatorTool>videodocparser {0}</xmp:CreatorTool>\n   <pdf:Producer>videodocparser {0}</pdf:Producer>\n",
+        env!("CARGO_PKG_VERSION")
+    ));
+    if let Some((part, conformance)) = pdfa {
+        properties.push_str(&format!(
+            "   <pdfaid:part>{}</pdfaid:part>\n   <pdfaid:conformance>{}</pdfaid:conformance>\n",
+            part,
+            escape(conformance)
+        ));
+    }
+    if let Some(part) = pdfua {
+        properties.push_str(&format!("   <pdfuaid:part>{}</pdfuaid:part>\n", part));
+    }
+    wrap(
+        "xmlns:xmp=\"http://ns.adobe.com/xap/1.0/\"\n    \
+         xmlns:pdf=\"http://ns.adobe.com/pdf/1.3/\"\n    \
+         xmlns:pdfaid=\"http://www.aiim.org/pdfa/ns/id/\"\n    \
+         xmlns:pdfuaid=\"http://www.aiim.org/pdfua/ns/id/\"",
+        &properties,
+    )
+}
+
+/// Wraps properties in a complete packet with a single description. The Dublin
+/// Core namespace is always declared; `namespaces` adds further declarations.
+fn wrap(namespaces: &str, properties: &str) -> String {
+    format!(
+        "<?xpacket begin=\"\u{feff}\" id=\"W5M0MpCehiHzreSzNTczkc9d\"?>\n\
+         <x:xmpmeta xmlns:x=\"adobe:ns:meta/\">\n\
+         <rdf:RDF xmlns:rdf=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\">\n  \
+         <rdf:Description rdf:about=\"\"\n    \
+         xmlns:dc=\"http://purl.org/dc/elements/1.1/\"\n    \
+         {}>\n\
+         {}  </rdf:Description>\n\
+         </rdf:RDF>\n\
+         </x:xmpmeta>\n\
+         <?xpacket end=\"w\"?>",
+        namespaces, properties
+    )
+}
+
+/// Saves an RGB image as PNG with the XMP packet in an uncompressed iTXt chunk.
+pub fn save_png_with_xmp(
+    image: &ImageBuffer<Rgb<u8>, Vec<u8>>,
+    path: &Path,
+    xmp_packet: &str,
+) -> Result<()> {
+    let file = File::create(path).with_context(|| format!("Failed to create {:?}", path))?;
+    let mut encoder = png::Encoder::new(BufWriter::new(file), image.width(), image.height());
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+        .add_itxt_chunk(PNG_XMP_KEYWORD.to_string(), xmp_packet.to_string())
+        .context("Failed to add XMP chunk")?;
+    let mut writer = encoder.write_header().context("Failed to write PNG header")?;
+    writer
+        .write_image_data(image.as_raw())
+        .with_context(|| format!("Failed to write PNG data to {:?}", path))?;
+    writer.finish().context("Failed to finish PNG file")?;
+    Ok(())
+}
+
+/// Formats a language-alternative property with a single default-language value.
+fn lang_alt(name: &str, value: &str) -> String {
+    format!(
+        "   <{name}>\n    <rdf:Alt>\n     <rdf:li xml:lang=\"x-default\">{}</rdf:li>\n    </rdf:Alt>\n   </{name}>\n",
+        escape(value)
+    )
+}
+
+/// Escapes text for use in XML element content and attribute values.
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Control characters other than tab and newlines are not allowed in XML 1.0.
+            c if c.is_control() && !matches!(c, '\t' | '\n' | '\r') => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,37 @@
+use image::{ImageBuffer, Rgb};
+use videodocparser::alto::{alto_path, build_alto};
+use videodocparser::document_builder::Page;
+use videodocparser::ocr::{OcrFrameResult, OcrWord};
+
+#[test]
+fn alto_lists_lines_and_words() {
+    let image = ImageBuffer::from_pixel(200, 100, Rgb([255u8, 255, 255]));
+    let word = |text: &str, bbox| OcrWord { text: text.to_string(), bbox, confidence: 90.0 };
+    let ocr = OcrFrameResult {
+        frame_index: 0,
+        words: vec![
+            word("R&D", (60, 10, 100, 30)),
+            word("Budget", (10, 10, 50, 30)),
+            word("2024", (10, 50, 40, 70)),
+        ],
+        blocks: Vec::new(),
+        strategy: None,
+        timestamp: None,
+    };
+    let pages = vec![Page { image: &image, ocr: Some(&ocr), title: None, timestamp: None, end: None }];
+
+    let dir = std::env::temp_dir().join(format!("vdp-alto-{}", std::process::id()));
+    build_alto(&pages, &dir).unwrap();
+
+    let xml = std::fs::read_to_string(dir.join(alto_path(0))).unwrap();
+    assert!(xml.contains("<fileName>../images/page_001.png</fileName>"));
+    assert!(xml.contains("<Page ID=\"PAGE_1\" PHYSICAL_IMG_NR=\"1\" WIDTH=\"200\" HEIGHT=\"100\">"));
+    assert!(xml.contains("<TextLine ID=\"LINE_1\" HPOS=\"10\" VPOS=\"10\" WIDTH=\"90\" HEIGHT=\"20\">"));
+    let budget = xml.find("CONTENT=\"Budget\"").unwrap();
+    let rnd = xml.find("CONTENT=\"R&amp;D\" HPOS=\"60\" VPOS=\"10\" WIDTH=\"40\" HEIGHT=\"20\" WC=\"0.90\"").unwrap();
+    assert!(budget < rnd);
+    assert!(xml.contains("<TextLine ID=\"LINE_2\""));
+    assert!(dir.join("images/page_001.png").exists());
+
+    std::fs::remove_dir_all(dir).ok();
+}
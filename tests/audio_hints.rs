@@ -0,0 +1,47 @@
+//! Checks finding pauses in the audio and biasing frame analysis toward them.
+
+use videodocparser::audio_hints::{find_pauses, AudioHints, Pause, PAUSE_SCALE, SAMPLE_RATE, SPEECH_SCALE};
+
+/// A voiced 150 Hz tone standing in for speech.
+fn speech(seconds: f64) -> Vec<f32> {
+    (0..(seconds * SAMPLE_RATE as f64) as usize)
+        .map(|i| (0.2 * (2.0 * std::f64::consts::PI * 150.0 * i as f64 / SAMPLE_RATE as f64).sin()) as f32)
+        .collect()
+}
+
+/// Loud pseudo-random noise standing in for applause.
+fn applause(seconds: f64) -> Vec<f32> {
+    let mut state = 12345u32;
+    (0..(seconds * SAMPLE_RATE as f64) as usize)
+        .map(|_| {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            ((state >> 16) as f32 / 32768.0 - 1.0) * 0.5
+        })
+        .collect()
+}
+
+#[test]
+fn silences_and_applause_are_pauses() {
+    let mut audio = speech(3.0);
+    audio.extend(vec![0.0; SAMPLE_RATE as usize]); // 3-4 s: silence
+    audio.extend(speech(3.0));
+    audio.extend(vec![0.0; SAMPLE_RATE as usize / 5]); // 7-7.2 s: too short to count
+    audio.extend(speech(2.0));
+    audio.extend(applause(2.0)); // 9.2-11.2 s: applause
+    audio.extend(speech(1.0));
+
+    let pauses = find_pauses(&audio, SAMPLE_RATE);
+    assert_eq!(pauses.len(), 2, "{:?}", pauses);
+    assert!((pauses[0].start - 3.0).abs() < 0.1 && (pauses[0].end - 4.0).abs() < 0.1, "{:?}", pauses[0]);
+    assert!((pauses[1].start - 9.2).abs() < 0.1 && (pauses[1].end - 11.2).abs() < 0.1, "{:?}", pauses[1]);
+}
+
+#[test]
+fn threshold_is_lower_near_pauses() {
+    let hints = AudioHints::new(vec![Pause { start: 10.0, end: 11.0 }], 25.0);
+    assert_eq!(hints.threshold_scale(25 * 10), PAUSE_SCALE);
+    // Within the margin after the pause.
+    assert_eq!(hints.threshold_scale(25 * 11 + 10), PAUSE_SCALE);
+    assert_eq!(hints.threshold_scale(25 * 5), SPEECH_SCALE);
+    assert_eq!(AudioHints::new(Vec::new(), 25.0).threshold_scale(0), 1.0);
+}
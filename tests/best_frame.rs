@@ -0,0 +1,74 @@
+//! Checks taking each page's image from the best frame of its stable segment.
+
+use image::imageops;
+use image::{DynamicImage, ImageBuffer, Rgb, RgbImage};
+use videodocparser::frame_analyzer::FrameAnalyzer;
+use videodocparser::quality::score;
+
+/// A white slide with lines of text-like dashes, placed by `n`.
+fn slide(n: u32) -> RgbImage {
+    ImageBuffer::from_fn(320, 180, |x, y| {
+        let line = (y / 12) % (2 + n) == 0 && y >= 24 + n * 30;
+        let dash = (x / 6) % 3 != 0 && (24..296).contains(&x);
+        if line && dash && y % 12 < 5 { Rgb([20, 20, 20]) } else { Rgb([245, 245, 245]) }
+    })
+}
+
+fn blurred(frame: &RgbImage) -> RgbImage {
+    imageops::blur(frame, 1.5)
+}
+
+/// The frame as in a fade to black.
+fn dark(frame: &RgbImage) -> RgbImage {
+    let mut dark = frame.clone();
+    for pixel in dark.pixels_mut() {
+        *pixel = Rgb(pixel.0.map(|channel| channel / 16));
+    }
+    dark
+}
+
+/// Slide 0 comes into focus, then slide 1 fades in from black.
+fn talk() -> Vec<RgbImage> {
+    vec![blurred(&slide(0)), slide(0), slide(0), dark(&slide(1)), slide(1), blurred(&slide(1))]
+}
+
+#[test]
+fn sharp_well_exposed_frames_score_higher() {
+    let sharp = score(&DynamicImage::ImageRgb8(slide(0)));
+    assert!(sharp > 0.0);
+    assert!(score(&DynamicImage::ImageRgb8(blurred(&slide(0)))) < sharp / 2.0);
+    assert_eq!(score(&DynamicImage::ImageRgb8(dark(&slide(0)))), 0.0);
+    assert_eq!(score(&DynamicImage::ImageRgb8(RgbImage::from_pixel(64, 64, Rgb([250, 250, 250])))), 0.0);
+}
+
+#[test]
+fn pages_show_the_best_frame_of_their_segment() {
+    let dir = std::env::temp_dir().join(format!("vdp-best-frame-{}", std::process::id()));
+    let frames = talk();
+
+    let mut first = FrameAnalyzer::new(0.9, &dir).unwrap();
+    for frame in frames.clone() {
+        first.process_frame(frame).unwrap();
+    }
+    assert_eq!(first.kept_indices(), &[0, 3]);
+    assert_eq!(first.source_indices(), &[0, 3]);
+    assert!(first.quality_series().is_empty());
+
+    let mut best = FrameAnalyzer::new(0.9, &dir).unwrap();
+    best.set_best_frame(true);
+    for frame in frames.clone() {
+        best.process_frame(frame).unwrap();
+    }
+    assert_eq!(best.kept_indices(), &[0, 3]);
+    assert_eq!(best.source_indices(), &[1, 4]);
+    assert_eq!(best.quality_series().len(), frames.len());
+
+    // The cached series select the same frames without decoding any.
+    let quality = best.quality_series().to_vec();
+    let result = best.finish().unwrap();
+    assert_eq!(result.kept_frames, vec![frames[1].clone(), frames[4].clone()]);
+    let mut replay = FrameAnalyzer::new(0.9, &dir).unwrap();
+    replay.replay(result.hash_series, Some(quality)).unwrap();
+    assert_eq!(replay.source_indices(), &[1, 4]);
+    let _ = std::fs::remove_dir_all(&dir);
+}
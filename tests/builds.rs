@@ -0,0 +1,107 @@
+//! Checks the handling of slides that are built up step by step.
+
+use image::{ImageBuffer, Rgb};
+use videodocparser::builds::{final_builds, keep_results, new_lines};
+use videodocparser::document_builder::{
+    build_jsonl, build_markdown, ConfidencePolicy, JsonlOptions, MarkdownOptions, Page,
+};
+use videodocparser::ocr::{save_report, OcrFrameResult, OcrWord};
+
+/// OCR result with one line per entry, each word 60 px wide.
+fn ocr(frame_index: usize, lines: &[&str]) -> OcrFrameResult {
+    let mut words = Vec::new();
+    for (row, line) in lines.iter().enumerate() {
+        let top = 20 + 40 * row as i32;
+        for (i, word) in line.split_whitespace().enumerate() {
+            let left = 20 + 70 * i as i32;
+            words.push(OcrWord { text: word.to_string(), bbox: (left, top, left + 60, top + 20), confidence: 90.0 });
+        }
+    }
+    OcrFrameResult { frame_index, words, blocks: Vec::new(), strategy: None, timestamp: None }
+}
+
+#[test]
+fn only_new_lines_are_written() {
+    let image = ImageBuffer::from_pixel(640, 360, Rgb([255u8, 255, 255]));
+    let results = [
+        ocr(0, &["Agenda", "Ownership basics"]),
+        ocr(1, &["Agenda", "Ownership basics", "Borrowing rules"]),
+        ocr(2, &["Summary", "Ownership basics"]),
+    ];
+    let pages: Vec<Page> = results
+        .iter()
+        .map(|result| Page { image: &image, ocr: Some(result), title: None, timestamp: None, end: None })
+        .collect();
+
+    assert_eq!(
+        new_lines(&pages),
+        vec![vec!["Agenda", "Ownership basics"], vec!["Borrowing rules"], vec!["Summary"]]
+    );
+    assert_eq!(final_builds(&pages), vec![false, true, true]);
+
+    let dir = std::env::temp_dir().join(format!("vdp-builds-{}", std::process::id()));
+    let options = MarkdownOptions {
+        heading_level: 2,
+        document_title: "Rust".to_string(),
+        video_url: None,
+        speech: Vec::new(),
+        keyword_index: false,
+        tags: Vec::new(),
+        url_links: false,
+        figures: false,
+        formulas: false,
+        new_content_only: true,
+        translations: Vec::new(),
+        confidence: ConfidencePolicy::default(),
+    };
+    build_markdown(&pages, &options, &dir.join("document.md")).unwrap();
+    let markdown = std::fs::read_to_string(dir.join("document.md")).unwrap();
+    assert_eq!(markdown.matches("Ownership basics").count(), 1);
+    assert!(markdown.contains("![Page 2](images/page_002.png)\n\nBorrowing rules\n"));
+
+    let options = JsonlOptions { new_content_only: true, ..JsonlOptions::default() };
+    build_jsonl(&pages, &options, &dir.join("document.jsonl")).unwrap();
+    let jsonl = std::fs::read_to_string(dir.join("document.jsonl")).unwrap();
+    let texts: Vec<String> = jsonl
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap()["text"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(texts, ["Agenda\nOwnership basics", "Borrowing rules", "Summary"]);
+    std::fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn ocr_report_lists_only_the_final_builds() {
+    let image = ImageBuffer::from_pixel(640, 360, Rgb([255u8, 255, 255]));
+    // Page 2 has no text, so it has no OCR result.
+    let results = vec![
+        ocr(0, &["Agenda"]),
+        ocr(1, &["Agenda", "Ownership basics"]),
+        ocr(3, &["Summary"]),
+        ocr(4, &["Summary", "Questions"]),
+    ];
+    let pages: Vec<Page> = (0..5)
+        .map(|i| Page {
+            image: &image,
+            ocr: results.iter().find(|r| r.frame_index == i),
+            title: None,
+            timestamp: None,
+            end: None,
+        })
+        .collect();
+    let keep = final_builds(&pages);
+    assert_eq!(keep, vec![false, true, true, false, true]);
+
+    let kept = keep_results(results.clone(), &keep);
+    let dir = std::env::temp_dir().join(format!("vdp-builds-report-{}", std::process::id()));
+    save_report(&kept, &dir, "both").unwrap();
+    let json = std::fs::read_to_string(dir.join("ocr").join("ocr_results.json")).unwrap();
+    let report: Vec<OcrFrameResult> = serde_json::from_str(&json).unwrap();
+    // Three pages are left, and the middle one has no text.
+    assert_eq!(report.iter().map(|r| r.frame_index).collect::<Vec<_>>(), [0, 2]);
+    assert_eq!(report[1].words.len(), 2);
+    let csv = std::fs::read_to_string(dir.join("ocr").join("ocr_results.csv")).unwrap();
+    assert!(!csv.contains("\n1,") && !csv.contains("\n3,"), "{csv}");
+
+    std::fs::remove_dir_all(dir).ok();
+}
@@ -0,0 +1,39 @@
+#![cfg(feature = "bundle")]
+use std::fs::{self, File};
+use std::io::Read;
+use videodocparser::bundle::{write_zip, Manifest, MANIFEST_NAME};
+
+#[test]
+fn bundle_contains_results_and_manifest() {
+    let dir = std::env::temp_dir().join(format!("vdp-bundle-{}", std::process::id()));
+    fs::create_dir_all(dir.join("result/images")).unwrap();
+    fs::create_dir_all(dir.join("ocr")).unwrap();
+    fs::write(dir.join("result/document.md"), "# Talk\n").unwrap();
+    fs::write(dir.join("result/images/page_001.png"), [0u8; 16]).unwrap();
+    fs::write(dir.join("ocr/ocr_results.json"), "[]").unwrap();
+
+    let manifest = Manifest {
+        source: "talk.mp4".to_string(),
+        format: "md".to_string(),
+        generator: "videodocparser".to_string(),
+        files: Vec::new(),
+    };
+    let zip_path = dir.join("bundle.zip");
+    write_zip(&dir, &["result", "ocr", "analysis/frame_analysis.json"], manifest, &zip_path).unwrap();
+
+    let mut archive = zip::ZipArchive::new(File::open(&zip_path).unwrap()).unwrap();
+    let mut names: Vec<String> =
+        archive.file_names().map(|name| name.unwrap().into_owned()).collect();
+    names.sort();
+    assert_eq!(
+        names,
+        ["manifest.json", "ocr/ocr_results.json", "result/document.md", "result/images/page_001.png"]
+    );
+    let mut manifest = String::new();
+    archive.by_name(MANIFEST_NAME).unwrap().read_to_string(&mut manifest).unwrap();
+    let manifest: serde_json::Value = serde_json::from_str(&manifest).unwrap();
+    assert_eq!(manifest["files"][1]["path"], "result/document.md");
+    assert_eq!(manifest["files"][1]["size"], 7);
+
+    fs::remove_dir_all(dir).ok();
+}
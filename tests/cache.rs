@@ -0,0 +1,50 @@
+//! Checks that cache entries are reused only by the run they were written for.
+
+use serde_json::json;
+use videodocparser::cache::{self, FORMAT_VERSION, InputFingerprint};
+
+fn fingerprint() -> InputFingerprint {
+    InputFingerprint { file_name: "talk.mp4".to_string(), size: 1024, modified_secs: 1_700_000_000 }
+}
+
+#[test]
+fn entries_round_trip_for_the_same_run() {
+    let dir = std::env::temp_dir().join(format!("vdp-cache-round-trip-{}", std::process::id()));
+    let path = dir.join("analysis/series.json");
+    let settings = json!({ "hash_size": 16 });
+    cache::write(&path, "series", &fingerprint(), settings.clone(), &vec![1u64, 2, 3]).unwrap();
+    assert!(!path.with_extension("tmp").exists());
+
+    let read: Option<Vec<u64>> = cache::read(&path, "series", &fingerprint(), &settings).unwrap();
+    assert_eq!(read, Some(vec![1, 2, 3]));
+
+    // Another kind, input, or settings recomputes the entry.
+    assert_eq!(cache::read::<Vec<u64>>(&path, "quality", &fingerprint(), &settings).unwrap(), None);
+    let other = InputFingerprint { size: 2048, ..fingerprint() };
+    assert_eq!(cache::read::<Vec<u64>>(&path, "series", &other, &settings).unwrap(), None);
+    assert_eq!(cache::read::<Vec<u64>>(&path, "series", &fingerprint(), &json!({ "hash_size": 8 })).unwrap(), None);
+    // So does a payload of another shape.
+    assert_eq!(cache::read::<Vec<String>>(&path, "series", &fingerprint(), &settings).unwrap(), None);
+    // A missing file is no error.
+    assert_eq!(cache::read::<Vec<u64>>(&dir.join("missing.json"), "series", &fingerprint(), &settings).unwrap(), None);
+
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn entries_of_other_format_versions_are_recomputed() {
+    let dir = std::env::temp_dir().join(format!("vdp-cache-version-{}", std::process::id()));
+    let path = dir.join("series.json");
+    let settings = json!({});
+    cache::write(&path, "series", &fingerprint(), settings.clone(), &vec![1u64]).unwrap();
+
+    let mut envelope: serde_json::Value = serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+    assert_eq!(envelope["header"]["format_version"], FORMAT_VERSION);
+    for version in [FORMAT_VERSION.saturating_sub(1), FORMAT_VERSION + 1] {
+        envelope["header"]["format_version"] = json!(version);
+        std::fs::write(&path, serde_json::to_vec(&envelope).unwrap()).unwrap();
+        assert_eq!(cache::read::<Vec<u64>>(&path, "series", &fingerprint(), &settings).unwrap(), None);
+    }
+
+    std::fs::remove_dir_all(&dir).ok();
+}
@@ -0,0 +1,65 @@
+//! Checks that `--sensitivity auto` tells changes of slide from noise.
+
+use videodocparser::calibration::{calibrate, otsu_split};
+use videodocparser::frame_analyzer::{FrameAnalyzer, FrameHash};
+
+const BITS: u32 = 256;
+
+/// The hash of slide `slide`, with the bits in `noise` flipped.
+fn hash(slide: u64, noise: std::ops::Range<usize>) -> FrameHash {
+    let mut state = slide.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+    let mut bytes: Vec<u8> = (0..BITS / 8)
+        .map(|_| {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (state >> 56) as u8
+        })
+        .collect();
+    for bit in noise {
+        bytes[bit / 8] ^= 1 << (bit % 8);
+    }
+    FrameHash::try_from(bytes.iter().map(|byte| format!("{:02x}", byte)).collect::<String>()).unwrap()
+}
+
+fn kept_indices(series: Vec<FrameHash>, sensitivity: f64) -> Vec<usize> {
+    let dir = std::env::temp_dir().join(format!("vdp-calibration-{}", std::process::id()));
+    let mut analyzer = FrameAnalyzer::new(sensitivity, &dir).unwrap();
+    analyzer.replay(series, None).unwrap();
+    let kept = analyzer.kept_indices().to_vec();
+    let _ = std::fs::remove_dir_all(&dir);
+    kept
+}
+
+#[test]
+fn otsu_splits_between_the_two_classes() {
+    assert_eq!(otsu_split(&[1, 2, 1, 0, 2, 1, 40, 1, 2, 38]), Some(2));
+    assert_eq!(otsu_split(&[5, 5, 5]), None);
+    assert_eq!(otsu_split(&[]), None);
+}
+
+#[test]
+fn a_noisy_camera_lecture_keeps_only_the_changes_of_slide() {
+    // Three slides of nine frames each, with 32 bits of flicker between frames.
+    let flicker = [0..16, 16..32, 32..48];
+    let series: Vec<FrameHash> = (0..27).map(|i| hash(i / 9, flicker[i as usize % 3].clone())).collect();
+    assert_eq!(kept_indices(series.clone(), 0.9).len(), 27);
+
+    let sensitivity = calibrate(&series, BITS).unwrap().unwrap();
+    assert!((sensitivity - (1.0 - 32.5 / 256.0)).abs() < 1e-9, "{}", sensitivity);
+    assert_eq!(kept_indices(series, sensitivity), vec![0, 9, 18]);
+}
+
+#[test]
+fn a_single_slide_of_noise_is_one_page() {
+    let noise = [0..10, 10..24, 24..44, 44..70];
+    let series: Vec<FrameHash> = [0, 1, 2, 3, 0, 2, 1, 3, 0].iter().map(|&n| hash(0, noise[n].clone())).collect();
+    assert!(kept_indices(series.clone(), 0.9).len() > 1);
+
+    let sensitivity = calibrate(&series, BITS).unwrap().unwrap();
+    assert_eq!(kept_indices(series, sensitivity), vec![0]);
+}
+
+#[test]
+fn uniform_or_short_series_are_not_calibrated() {
+    assert_eq!(calibrate(&[hash(0, 0..0)], BITS).unwrap(), None);
+    assert_eq!(calibrate(&vec![hash(0, 0..0); 5], BITS).unwrap(), None);
+}
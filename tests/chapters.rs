@@ -0,0 +1,70 @@
+//! Checks the chapter lists built from the slide boundaries, and splitting the
+//! pages by the chapter markers of the video.
+
+use image::{ImageBuffer, Rgb};
+use videodocparser::chapters::{ffmetadata, from_pages, split_pages, youtube_description, ChapterMark};
+use videodocparser::document_builder::Page;
+use videodocparser::video_processor::Chapter;
+
+#[test]
+fn chapters_follow_slide_titles() {
+    let image = ImageBuffer::from_pixel(32, 18, Rgb([255u8, 255, 255]));
+    let titles = ["Intro", "Ownership", "Ownership", "Borrowing", "Q&A", "Thanks"];
+    let times = [3.0, 65.0, 90.0, 200.0, 3700.0, 3705.0, 3710.0];
+    let pages: Vec<Page> = titles
+        .iter()
+        .zip(times.windows(2))
+        .map(|(title, time)| Page {
+            image: &image,
+            ocr: None,
+            title: Some(title.to_string()),
+            timestamp: Some(time[0]),
+            end: Some(time[1]),
+        })
+        .collect();
+    // The builds of "Ownership" form one chapter, and "Q&A" and "Thanks" are
+    // too short and join "Borrowing".
+    let chapters = from_pages(&pages, 3712.0);
+    let mark = |start: f64, end: f64, title: &str| ChapterMark { start, end, title: title.to_string() };
+    assert_eq!(
+        chapters,
+        [mark(0.0, 65.0, "Intro"), mark(65.0, 200.0, "Ownership"), mark(200.0, 3710.0, "Borrowing")]
+    );
+
+    assert_eq!(youtube_description(&chapters), "00:00 Intro\n01:05 Ownership\n03:20 Borrowing\n");
+
+    // Without segment ends, the last chapter runs to the end of the video.
+    let pages: Vec<Page> = pages.into_iter().map(|page| Page { end: None, ..page }).collect();
+    assert_eq!(from_pages(&pages, 3712.0).last(), Some(&mark(200.0, 3712.0, "Borrowing")));
+    let late = [mark(0.0, 3600.0, "Intro"), mark(3725.0, 3800.0, "Wrap-up")];
+    assert_eq!(youtube_description(&late), "0:00:00 Intro\n1:02:05 Wrap-up\n");
+}
+
+#[test]
+fn ffmetadata_escapes_titles() {
+    let chapters = [ChapterMark { start: 0.0, end: 12.5, title: "a=b; #1".to_string() }];
+    assert_eq!(
+        ffmetadata(&chapters),
+        ";FFMETADATA1\n\n[CHAPTER]\nTIMEBASE=1/1000\nSTART=0\nEND=12500\ntitle=a\\=b\\; \\#1\n"
+    );
+}
+
+#[test]
+fn pages_are_split_by_chapter_markers() {
+    let image = ImageBuffer::from_pixel(32, 18, Rgb([255u8, 255, 255]));
+    let page = |timestamp: Option<f64>| Page { image: &image, ocr: None, title: None, timestamp, end: None };
+    let chapter = |start: f64, end: f64| Chapter { title: None, start, end };
+    let pages: Vec<Page> = [2.0, 12.0, 15.0, 70.0, 130.0].into_iter().map(|t| page(Some(t))).collect();
+
+    // The first page, shown before the first chapter, joins it; the third has no
+    // pages; the last page, shown after the last chapter ended, stays in it.
+    let chapters = [chapter(5.0, 10.0), chapter(10.0, 60.0), chapter(60.0, 65.0), chapter(65.0, 100.0)];
+    assert_eq!(split_pages(&pages, &chapters), Some(vec![(0, 0..1), (1, 1..3), (3, 3..5)]));
+    // Chapters that all start before the first page leave the earlier ones empty.
+    let chapters = [chapter(0.0, 1.0), chapter(1.0, 2.0), chapter(2.0, 200.0)];
+    assert_eq!(split_pages(&pages, &chapters), Some(vec![(2, 0..5)]));
+
+    assert_eq!(split_pages(&pages, &[]), Some(Vec::new()));
+    let untimed = [page(Some(1.0)), page(None)];
+    assert_eq!(split_pages(&untimed, &chapters), None);
+}
@@ -0,0 +1,134 @@
+#![cfg(feature = "cloud")]
+//! Checks the cloud OCR backends on recorded replies, and their retry and request signing helpers.
+
+use serde_json::json;
+use std::time::Duration;
+use videodocparser::cloud_ocr::{
+    Provider, amz_date, backoff, hmac_sha256, is_retryable, parse_azure, parse_google, parse_textract, signing_key,
+    sigv4_authorization,
+};
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[test]
+fn providers_are_named_by_engine() {
+    assert_eq!(Provider::from_name("google"), Some(Provider::Google));
+    assert_eq!(Provider::from_name("azure"), Some(Provider::Azure));
+    assert_eq!(Provider::from_name("textract"), Some(Provider::Textract));
+    assert_eq!(Provider::from_name("tesseract"), None);
+}
+
+#[test]
+fn google_replies_give_words_and_blocks_per_image() {
+    let word = |text: &str, x: i64, confidence: f64| {
+        json!({
+            "boundingBox": { "vertices": [{ "x": x, "y": 10 }, { "x": x + 40, "y": 10 }, { "x": x + 40, "y": 30 }, { "y": 30 }] },
+            "symbols": text.chars().map(|c| json!({ "text": c.to_string() })).collect::<Vec<_>>(),
+            "confidence": confidence,
+        })
+    };
+    let reply = json!({ "responses": [
+        { "fullTextAnnotation": { "pages": [{ "blocks": [{
+            "boundingBox": { "vertices": [{ "x": 5, "y": 8 }, { "x": 110, "y": 8 }, { "x": 110, "y": 32 }, { "x": 5, "y": 32 }] },
+            "confidence": 0.9,
+            "paragraphs": [{ "words": [word("Hello", 5, 0.98), word("world", 60, 0.8)] }],
+        }] }] } },
+        {},
+    ] });
+    let recognitions = parse_google(&reply).unwrap();
+    assert_eq!(recognitions.len(), 2);
+    let words = &recognitions[0].words;
+    assert_eq!(words.iter().map(|w| w.text.as_str()).collect::<Vec<_>>(), ["Hello", "world"]);
+    // A vertex without "x" is at x = 0.
+    assert_eq!(words[1].bbox, (0, 10, 100, 30));
+    assert!((words[0].confidence - 98.0).abs() < 1e-3);
+    assert_eq!(recognitions[0].blocks[0].text, "Hello world");
+    assert_eq!(recognitions[0].blocks[0].bbox, (5, 8, 110, 32));
+    assert!(recognitions[1].words.is_empty());
+
+    let failed = json!({ "responses": [{}, { "error": { "code": 3, "message": "Bad image data." } }] });
+    assert!(format!("{:#}", parse_google(&failed).unwrap_err()).contains("Bad image data."));
+    assert!(parse_google(&json!({})).is_err());
+}
+
+#[test]
+fn azure_replies_give_lines_and_words() {
+    let reply = json!({ "readResult": { "blocks": [{ "lines": [{
+        "text": "Total: 42",
+        "boundingPolygon": [{ "x": 10, "y": 20 }, { "x": 90, "y": 20 }, { "x": 90, "y": 40 }, { "x": 10, "y": 40 }],
+        "words": [
+            { "text": "Total:", "boundingPolygon": [{ "x": 10, "y": 20 }, { "x": 60, "y": 21 }, { "x": 60, "y": 40 }, { "x": 11, "y": 39 }], "confidence": 0.99 },
+            { "text": "42", "boundingPolygon": [{ "x": 70, "y": 20 }, { "x": 90, "y": 20 }, { "x": 90, "y": 40 }, { "x": 70, "y": 40 }], "confidence": 0.75 },
+        ],
+    }] }] } });
+    let recognition = parse_azure(&reply).unwrap();
+    assert_eq!(recognition.words.len(), 2);
+    assert_eq!(recognition.words[0].bbox, (10, 20, 60, 40));
+    assert!((recognition.words[1].confidence - 75.0).abs() < 1e-3);
+    assert_eq!(recognition.lines[0].text, "Total: 42");
+    assert!((recognition.lines[0].confidence - 87.0).abs() < 1e-3);
+    assert!(parse_azure(&json!({ "error": { "message": "Unauthorized" } })).is_err());
+}
+
+#[test]
+fn textract_boxes_are_scaled_to_the_image() {
+    let geometry = |left: f64, top: f64| json!({ "BoundingBox": { "Left": left, "Top": top, "Width": 0.25, "Height": 0.1 } });
+    let reply = json!({ "Blocks": [
+        { "BlockType": "PAGE", "Geometry": geometry(0.0, 0.0) },
+        { "BlockType": "LINE", "Text": "handwritten note", "Confidence": 91.5, "Geometry": geometry(0.1, 0.2) },
+        { "BlockType": "WORD", "Text": "handwritten", "Confidence": 93.0, "Geometry": geometry(0.1, 0.2) },
+        { "BlockType": "WORD", "Text": "note", "Confidence": 90.0, "Geometry": geometry(0.4, 0.2) },
+    ] });
+    let recognition = parse_textract(&reply, 800, 600).unwrap();
+    assert_eq!(recognition.lines.len(), 1);
+    assert_eq!(recognition.words.len(), 2);
+    assert_eq!(recognition.words[1].bbox, (320, 120, 520, 180));
+    assert_eq!(recognition.words[0].confidence, 93.0);
+}
+
+#[test]
+fn throttling_and_server_errors_are_retried_with_backoff() {
+    for status in [408, 429, 500, 503] {
+        assert!(is_retryable(status), "{status}");
+    }
+    for status in [400, 401, 403, 404, 413] {
+        assert!(!is_retryable(status), "{status}");
+    }
+    assert_eq!(backoff(0, None), Duration::from_secs(1));
+    assert_eq!(backoff(3, None), Duration::from_secs(8));
+    assert_eq!(backoff(30, None), Duration::from_secs(60));
+    assert_eq!(backoff(0, Some(7)), Duration::from_secs(7));
+    assert_eq!(backoff(0, Some(3600)), Duration::from_secs(60));
+}
+
+#[test]
+fn aws_requests_are_signed() {
+    // RFC 4231, test case 2.
+    assert_eq!(
+        hex(&hmac_sha256(b"Jefe", b"what do ya want for nothing?")),
+        "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+    );
+    // The signing key example of the AWS Signature Version 4 documentation.
+    assert_eq!(
+        hex(&signing_key("wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY", "20120215", "us-east-1", "iam")),
+        "f4780e2d9f65fa895f9c67b32ce1baf0b0d8a43505a000a1a9e090d414db404d"
+    );
+    assert_eq!(amz_date(1_700_000_000), "20231114T221320Z");
+
+    let headers = [
+        ("content-type", "application/x-amz-json-1.1"),
+        ("host", "textract.eu-west-1.amazonaws.com"),
+        ("x-amz-date", "20231114T221320Z"),
+        ("x-amz-target", "Textract.DetectDocumentText"),
+    ];
+    let authorization = sigv4_authorization("AKIDEXAMPLE", "secret", "eu-west-1", "textract", &headers, b"{}");
+    assert!(authorization.starts_with(
+        "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20231114/eu-west-1/textract/aws4_request, \
+         SignedHeaders=content-type;host;x-amz-date;x-amz-target, Signature="
+    ));
+    let signature = authorization.rsplit('=').next().unwrap();
+    assert_eq!(signature.len(), 64);
+    assert_ne!(authorization, sigv4_authorization("AKIDEXAMPLE", "secret", "eu-west-1", "textract", &headers, b"{ }"));
+}
@@ -0,0 +1,91 @@
+//! Checks that code on slides is told apart from prose and fenced in Markdown.
+
+use image::{ImageBuffer, Rgb};
+use videodocparser::document_builder::{build_markdown, ConfidencePolicy, MarkdownOptions, Page};
+use videodocparser::layout::{group_blocks, TextBlock};
+use videodocparser::ocr::{OcrFrameResult, OcrWord};
+
+/// Words of a line of monospaced text, 10 px per character.
+fn code_line(row: i32, text: &str) -> Vec<OcrWord> {
+    let mut words = Vec::new();
+    let mut column = 0;
+    for (i, word) in text.split(' ').enumerate() {
+        if i > 0 {
+            column += 1;
+        }
+        if !word.is_empty() {
+            let x = 20 + column * 10;
+            let bbox = (x, 40 * row, x + word.len() as i32 * 10, 40 * row + 20);
+            words.push(OcrWord { text: word.to_string(), bbox, confidence: 90.0 });
+        }
+        column += word.len() as i32;
+    }
+    words
+}
+
+/// Words of a line of proportional text, whose letters differ in width.
+fn prose_line(row: i32, text: &str) -> Vec<OcrWord> {
+    let mut words = Vec::new();
+    let mut x = 20;
+    for word in text.split_whitespace() {
+        let width: i32 = word
+            .chars()
+            .map(|c| match c {
+                'i' | 'l' | 't' | 'f' | 'r' => 5,
+                'm' | 'w' => 14,
+                c if c.is_uppercase() => 12,
+                _ => 9,
+            })
+            .sum();
+        words.push(OcrWord { text: word.to_string(), bbox: (x, 40 * row, x + width, 40 * row + 20), confidence: 90.0 });
+        x += width + 8;
+    }
+    words
+}
+
+#[test]
+fn monospaced_code_becomes_a_fenced_block() {
+    let mut words = prose_line(0, "Returns the sum of two numbers");
+    words.extend(code_line(1, "fn add(a: i32, b: i32) -> i32 {"));
+    words.extend(code_line(2, "    let sum = a + b;"));
+    words.extend(code_line(3, "    sum"));
+    words.extend(code_line(4, "}"));
+    words.extend(prose_line(5, "Call it with two integers"));
+    // A formula alone is not a code block.
+    words.extend(prose_line(6, "f(x) = (a + b) * c"));
+    let ocr = OcrFrameResult { frame_index: 0, words, blocks: Vec::new(), strategy: None, timestamp: None };
+
+    let blocks = group_blocks(&ocr);
+    assert_eq!(blocks.len(), 3);
+    assert!(matches!(&blocks[0], TextBlock::Prose(lines) if lines.len() == 1));
+    let TextBlock::Code(code) = &blocks[1] else { panic!("expected code, got {:?}", blocks[1]) };
+    assert_eq!(code, &["fn add(a: i32, b: i32) -> i32 {", "    let sum = a + b;", "    sum", "}"]);
+    assert!(matches!(&blocks[2], TextBlock::Prose(lines) if lines.len() == 2));
+
+    let image = ImageBuffer::from_pixel(640, 360, Rgb([255u8, 255, 255]));
+    let title = Some("Adding".to_string());
+    let pages = vec![Page { image: &image, ocr: Some(&ocr), title, timestamp: None, end: None }];
+    let options = MarkdownOptions {
+        heading_level: 2,
+        document_title: "Code".to_string(),
+        video_url: None,
+        speech: Vec::new(),
+        keyword_index: false,
+        tags: Vec::new(),
+        url_links: false,
+        figures: false,
+        formulas: false,
+        new_content_only: false,
+        translations: Vec::new(),
+        confidence: ConfidencePolicy::default(),
+    };
+    let dir = std::env::temp_dir().join(format!("vdp-code-blocks-{}", std::process::id()));
+    let md_path = dir.join("document.md");
+    build_markdown(&pages, &options, &md_path).unwrap();
+    let markdown = std::fs::read_to_string(&md_path).unwrap();
+    assert!(markdown.contains(
+        "Returns the sum of two numbers\n\n```\nfn add(a: i32, b: i32) -> i32 {\n    let sum = a + b;\n    sum\n}\n```\n\n\
+         Call it with two integers  \nf(x) = (a + b) \\* c\n"
+    ));
+    std::fs::remove_dir_all(dir).ok();
+}
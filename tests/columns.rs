@@ -0,0 +1,66 @@
+//! Checks that side-by-side columns are read one after the other.
+
+use videodocparser::layout::{TextBlock, group_blocks, group_regions, page_text};
+use videodocparser::ocr::{OcrFrameResult, OcrWord};
+
+/// Words of a line 20 px tall at `top`, starting at `left`, in a proportional font.
+fn line(left: i32, top: i32, text: &str) -> Vec<OcrWord> {
+    let mut x = left;
+    let mut words = Vec::new();
+    for word in text.split_whitespace() {
+        let width = word.len() as i32 * 9 + 12;
+        words.push(OcrWord { text: word.to_string(), bbox: (x, top, x + width, top + 20), confidence: 90.0 });
+        x += width + 8;
+    }
+    words
+}
+
+#[test]
+fn two_columns_below_a_title_are_not_interleaved() {
+    let mut words = line(20, 10, "Memory safety without garbage collection");
+    // Both columns share a baseline grid, so every left line has a right one level with it.
+    words.extend(line(20, 60, "Ownership gives every value"));
+    words.extend(line(20, 90, "a single owner that drops it"));
+    words.extend(line(20, 120, "when it goes out of scope"));
+    words.extend(line(360, 60, "Borrowing lends a value"));
+    words.extend(line(360, 90, "without moving it, checked"));
+    words.extend(line(360, 120, "by the compiler at build time"));
+    words.extend(line(20, 170, "Slides licensed under CC BY 4.0"));
+    let ocr = OcrFrameResult { frame_index: 0, words, blocks: Vec::new(), strategy: None, timestamp: None };
+
+    assert_eq!(
+        page_text(&ocr),
+        "Memory safety without garbage collection\n\
+         Ownership gives every value\na single owner that drops it\nwhen it goes out of scope\n\
+         Borrowing lends a value\nwithout moving it, checked\nby the compiler at build time\n\
+         Slides licensed under CC BY 4.0"
+    );
+    let blocks = group_blocks(&ocr);
+    let sizes: Vec<usize> = blocks
+        .iter()
+        .map(|block| match block {
+            TextBlock::Prose(lines) => lines.len(),
+            other => panic!("unexpected block {:?}", other),
+        })
+        .collect();
+    assert_eq!(sizes, vec![1, 3, 3, 1]);
+}
+
+#[test]
+fn single_columns_and_narrow_cells_stay_one_region() {
+    let mut words = line(20, 10, "Agenda");
+    words.extend(line(20, 40, "Why ownership matters"));
+    words.extend(line(20, 70, "Borrowing in practice"));
+    assert_eq!(group_regions(words.iter()).len(), 1);
+
+    // A word or two per row on each side of the gap reads as a table, row by row.
+    let mut cells = line(20, 10, "Region");
+    cells.extend(line(300, 10, "Revenue"));
+    cells.extend(line(20, 40, "Europe"));
+    cells.extend(line(300, 40, "1,200"));
+    cells.extend(line(20, 70, "Asia"));
+    cells.extend(line(300, 70, "800"));
+    assert_eq!(group_regions(cells.iter()).len(), 1);
+    let ocr = OcrFrameResult { frame_index: 0, words: cells, blocks: Vec::new(), strategy: None, timestamp: None };
+    assert_eq!(page_text(&ocr), "Region Revenue\nEurope 1,200\nAsia 800");
+}
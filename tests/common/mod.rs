@@ -0,0 +1,45 @@
+//! Fixtures shared by the frame analysis tests.
+
+// Each test crate uses only some of the fixtures.
+#![allow(dead_code)]
+
+use image::{ImageBuffer, Rgb, RgbImage};
+use std::path::Path;
+use videodocparser::frame_analyzer::FrameAnalyzer;
+use videodocparser::video_processor::FrameMeta;
+
+/// A white slide with a dark block whose place depends on `n`.
+pub fn slide(n: u32) -> RgbImage {
+    ImageBuffer::from_fn(256, 144, |x, y| {
+        let (left, top) = (20 + n % 3 * 80, 20 + n / 3 * 60);
+        if (left..left + 60).contains(&x) && (top..top + 40).contains(&y) {
+            Rgb([20, 20, 20])
+        } else {
+            Rgb([240, 240, 240])
+        }
+    })
+}
+
+/// Runs `frames` through an analyzer that writes its reports to `dir`, set up
+/// by `configure` first.
+pub fn analyze(
+    dir: &Path,
+    frames: impl IntoIterator<Item = RgbImage>,
+    configure: impl FnOnce(&mut FrameAnalyzer),
+) -> FrameAnalyzer {
+    analyze_timed(dir, frames.into_iter().enumerate().map(|(i, frame)| (frame, FrameMeta::untimed(i))), configure)
+}
+
+/// Like [`analyze`], for frames with their timing as decoded.
+pub fn analyze_timed(
+    dir: &Path,
+    frames: impl IntoIterator<Item = (RgbImage, FrameMeta)>,
+    configure: impl FnOnce(&mut FrameAnalyzer),
+) -> FrameAnalyzer {
+    let mut analyzer = FrameAnalyzer::new(0.9, dir).unwrap();
+    configure(&mut analyzer);
+    for (frame, meta) in frames {
+        analyzer.process_timed_frame(frame, meta).unwrap();
+    }
+    analyzer
+}
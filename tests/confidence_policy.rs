@@ -0,0 +1,100 @@
+//! Checks dropping, flagging, and keeping the words recognized below the confidence threshold.
+
+use image::{ImageBuffer, Rgb};
+use videodocparser::document_builder::{
+    build_jsonl, build_markdown, ConfidencePolicy, JsonlOptions, LowConfidence, MarkdownOptions, Page,
+};
+use videodocparser::layout::page_text;
+use videodocparser::ocr::{OcrBlock, OcrFrameResult, OcrParagraph, OcrTextLine, OcrWord};
+
+fn word(text: &str, left: i32, top: i32, confidence: f32) -> OcrWord {
+    OcrWord { text: text.to_string(), bbox: (left, top, left + 60, top + 20), confidence }
+}
+
+/// Two lines, the engine's, with an unsure word in the middle of the first.
+fn smudged() -> OcrFrameResult {
+    let line = |words| OcrTextLine { bbox: (10, 10, 220, 50), words, vertical: false };
+    OcrFrameResult {
+        frame_index: 0,
+        words: vec![
+            word("Ownership", 10, 10, 90.0),
+            word("r#les", 80, 10, 20.0),
+            word("apply", 150, 10, 85.0),
+            word("Borrowing", 10, 40, 80.0),
+        ],
+        blocks: vec![OcrBlock {
+            bbox: (10, 10, 220, 60),
+            paragraphs: vec![OcrParagraph { bbox: (10, 10, 220, 60), lines: vec![line(vec![0, 1, 2]), line(vec![3])] }],
+        }],
+        strategy: None,
+        timestamp: None,
+    }
+}
+
+fn policy(low_confidence: LowConfidence) -> ConfidencePolicy {
+    ConfidencePolicy { threshold: 50.0, low_confidence }
+}
+
+#[test]
+fn dropping_leaves_unsure_words_out_of_the_engine_lines() {
+    let ocr = smudged();
+    let dropped = policy(LowConfidence::Drop).apply(&ocr);
+    assert_eq!(dropped.words.len(), 3);
+    assert_eq!(dropped.blocks[0].paragraphs[0].lines[0].words, vec![0, 1]);
+    assert_eq!(dropped.blocks[0].paragraphs[0].lines[1].words, vec![2]);
+    assert_eq!(page_text(&dropped), "Ownership apply\nBorrowing");
+
+    // Flagging and keeping show every word.
+    for low_confidence in [LowConfidence::Flag, LowConfidence::Keep] {
+        assert_eq!(page_text(&policy(low_confidence).apply(&ocr)), "Ownership r#les apply\nBorrowing");
+    }
+    assert_eq!(policy(LowConfidence::Flag).flagged(&ocr).len(), 1);
+    assert!(policy(LowConfidence::Keep).flagged(&ocr).is_empty());
+}
+
+#[test]
+fn outputs_follow_the_policy() {
+    let image = ImageBuffer::from_pixel(320, 180, Rgb([255u8, 255, 255]));
+    let ocr = smudged();
+    let pages = vec![Page { image: &image, ocr: Some(&ocr), title: None, timestamp: None, end: None }];
+    let dir = std::env::temp_dir().join(format!("vdp-confidence-{}", std::process::id()));
+    let markdown = |low_confidence| {
+        let options = MarkdownOptions {
+            heading_level: 2,
+            document_title: "Rust".to_string(),
+            video_url: None,
+            speech: Vec::new(),
+            keyword_index: false,
+            tags: Vec::new(),
+            url_links: false,
+            figures: false,
+            formulas: false,
+            new_content_only: false,
+            translations: Vec::new(),
+            confidence: policy(low_confidence),
+        };
+        build_markdown(&pages, &options, &dir.join("document.md")).unwrap();
+        std::fs::read_to_string(dir.join("document.md")).unwrap()
+    };
+    let dropped = markdown(LowConfidence::Drop);
+    assert!(dropped.contains("Ownership apply  \nBorrowing\n"));
+    assert!(!dropped.contains("r#les"));
+    let flagged = markdown(LowConfidence::Flag);
+    assert!(flagged.contains("Ownership r#les apply  \nBorrowing\n\n*Low confidence:* r#les\n"));
+    assert!(!markdown(LowConfidence::Keep).contains("Low confidence"));
+
+    let jsonl = |low_confidence| {
+        let options = JsonlOptions { confidence: policy(low_confidence), ..JsonlOptions::default() };
+        build_jsonl(&pages, &options, &dir.join("document.jsonl")).unwrap();
+        let jsonl = std::fs::read_to_string(dir.join("document.jsonl")).unwrap();
+        serde_json::from_str::<serde_json::Value>(jsonl.lines().next().unwrap()).unwrap()
+    };
+    let dropped = jsonl(LowConfidence::Drop);
+    assert_eq!(dropped["text"], "Ownership apply\nBorrowing");
+    assert_eq!(dropped["confidence"], 85.0);
+    assert!(dropped.get("low_confidence").is_none());
+    let flagged = jsonl(LowConfidence::Flag);
+    assert_eq!(flagged["text"], "Ownership r#les apply\nBorrowing");
+    assert_eq!(flagged["low_confidence"], serde_json::json!(["r#les"]));
+    std::fs::remove_dir_all(dir).ok();
+}
@@ -0,0 +1,93 @@
+//! Checks merging the words read on several samples of a page by voting.
+
+use videodocparser::ocr::{OcrBlock, OcrFrameResult, OcrParagraph, OcrTextLine, OcrWord, merge_by_vote};
+
+fn word(text: &str, bbox: (i32, i32, i32, i32), confidence: f32) -> OcrWord {
+    OcrWord { text: text.to_string(), bbox, confidence }
+}
+
+/// A sample reading two words on one line, "Rust" and `second`.
+fn sample(frame_index: usize, second: &str, confidence: f32) -> OcrFrameResult {
+    let words = vec![word("Rust", (10, 10, 60, 30), 90.0), word(second, (70, 10, 150, 30), confidence)];
+    let line = OcrTextLine { bbox: (10, 10, 150, 30), words: vec![0, 1], vertical: false };
+    let paragraph = OcrParagraph { bbox: line.bbox, lines: vec![line] };
+    OcrFrameResult {
+        frame_index,
+        words,
+        blocks: vec![OcrBlock { bbox: paragraph.bbox, paragraphs: vec![paragraph] }],
+        strategy: Some(format!("sample {frame_index}")),
+        timestamp: Some(frame_index as f64),
+    }
+}
+
+fn texts(result: &OcrFrameResult) -> Vec<&str> {
+    result.words.iter().map(|w| w.text.as_str()).collect()
+}
+
+#[test]
+fn the_majority_reading_wins_and_keeps_its_lines() {
+    let kept = sample(4, "0wnership", 60.0);
+    let samples = vec![sample(9, "ownership", 80.0), sample(7, "ownership", 70.0)];
+    let merged = merge_by_vote(kept, samples);
+
+    assert_eq!(texts(&merged), ["Rust", "ownership"]);
+    assert!((merged.words[1].confidence - 75.0).abs() < 1e-3);
+    // The earlier of the two agreeing samples lends its lines and strategy.
+    assert_eq!(merged.blocks, sample(7, "", 0.0).blocks);
+    assert_eq!(merged.strategy.as_deref(), Some("sample 7"));
+    // The page keeps the kept frame's place.
+    assert_eq!(merged.frame_index, 4);
+    assert_eq!(merged.timestamp, Some(4.0));
+}
+
+#[test]
+fn ties_go_to_confidence_then_to_the_kept_frame() {
+    let merged = merge_by_vote(sample(0, "borrow", 70.0), vec![sample(1, "barrow", 90.0)]);
+    assert_eq!(texts(&merged), ["Rust", "barrow"]);
+    assert_eq!(merged.strategy.as_deref(), Some("sample 1"));
+
+    let merged = merge_by_vote(sample(0, "borrow", 80.0), vec![sample(1, "barrow", 80.0)]);
+    assert_eq!(texts(&merged), ["Rust", "borrow"]);
+    assert_eq!(merged.strategy.as_deref(), Some("sample 0"));
+}
+
+#[test]
+fn words_seen_on_a_minority_of_samples_are_dropped() {
+    let mut kept = sample(0, "ownership", 80.0);
+    kept.words.push(word("glare", (300, 200, 340, 220), 40.0));
+    let merged = merge_by_vote(kept, vec![sample(1, "ownership", 80.0), sample(2, "ownership", 80.0)]);
+
+    assert_eq!(texts(&merged), ["Rust", "ownership"]);
+    assert_eq!(merged.strategy.as_deref(), Some("sample 0"));
+    assert_eq!(merged.blocks[0].paragraphs[0].lines[0].words, [0, 1]);
+}
+
+#[test]
+fn words_the_winner_missed_are_kept_without_its_lines() {
+    // The kept frame misses the second word, which both other samples read.
+    let mut kept = sample(0, "ownership", 80.0);
+    kept.words.truncate(1);
+    kept.blocks[0].paragraphs[0].lines[0].words.truncate(1);
+    let merged = merge_by_vote(kept, vec![sample(1, "ownership", 80.0), sample(2, "ownersh1p", 80.0)]);
+
+    // Sample 1 agrees with both voted words; ties on "ownership" go to its reading.
+    assert_eq!(texts(&merged), ["Rust", "ownership"]);
+    assert_eq!(merged.strategy.as_deref(), Some("sample 1"));
+    assert_eq!(merged.frame_index, 0);
+
+    // Three words, each read on two of the three samples.
+    let line = ["Rust", "ownership", "rules"];
+    let reading = |frame_index: usize, seen: [usize; 2]| {
+        let mut result = sample(frame_index, "ownership", 80.0);
+        let x = |i: usize| 10 + 100 * i as i32;
+        result.words = seen.iter().map(|&i| word(line[i], (x(i), 10, x(i) + 80, 30), 80.0)).collect();
+        result.blocks[0].paragraphs[0].lines[0].words = vec![0, 1];
+        result
+    };
+    let merged = merge_by_vote(reading(0, [0, 1]), vec![reading(1, [1, 2]), reading(2, [0, 2])]);
+
+    // No sample reads all three, so they are laid out by their boxes.
+    assert_eq!(texts(&merged), line);
+    assert!(merged.blocks.is_empty());
+    assert!(merged.strategy.is_none());
+}
@@ -0,0 +1,36 @@
+//! Checks the contact sheet image and page.
+
+use image::{ImageBuffer, Rgb};
+use videodocparser::contact_sheet::{build_contact_sheet, SHEET_HTML, SHEET_IMAGE};
+use videodocparser::document_builder::Page;
+
+#[test]
+fn contact_sheet_shows_every_kept_frame() {
+    let image = ImageBuffer::from_pixel(640, 360, Rgb([200u8, 220, 240]));
+    let pages: Vec<Page> = (0..8)
+        .map(|i| Page {
+            image: &image,
+            ocr: None,
+            title: Some(format!("Slide <{}>", i + 1)),
+            timestamp: (i != 7).then_some(i as f64 * 65.0),
+            end: None,
+        })
+        .collect();
+    let frame_indices: Vec<usize> = (0..8).map(|i| i * 120).collect();
+
+    let dir = std::env::temp_dir().join(format!("vdp-contact-sheet-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    build_contact_sheet(&pages, &frame_indices, "Talk & Demo", &dir).unwrap();
+
+    // 8 thumbnails of 240x135 in rows of 6, with 8px gaps.
+    let sheet = image::open(dir.join(SHEET_IMAGE)).unwrap();
+    assert_eq!((sheet.width(), sheet.height()), (8 + 6 * 248, 8 + 2 * 143));
+
+    let html = std::fs::read_to_string(dir.join(SHEET_HTML)).unwrap();
+    assert!(html.contains("<title>Talk &amp; Demo</title>"));
+    assert_eq!(html.matches("<figure").count(), 8);
+    assert!(html.contains("Slide &lt;2&gt;<br><span class=\"meta\">Page 2 · 00:01:05 · frame 120</span>"));
+    assert!(html.contains("Page 8 · frame 840</span>"));
+
+    std::fs::remove_dir_all(dir).ok();
+}
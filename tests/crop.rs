@@ -0,0 +1,45 @@
+//! Checks restricting the analysis and OCR to a region of the frame.
+
+use image::{ImageBuffer, Rgb};
+use videodocparser::crop::{CropRect, crop_frame, detect_borders, sample_indices};
+use videodocparser::frame_analyzer::HashSettings;
+
+#[test]
+fn regions_are_parsed_and_cut_out() {
+    let region = CropRect::parse("10, 20,300,200").unwrap();
+    assert_eq!(region, CropRect { x: 10, y: 20, width: 300, height: 200 });
+    for bad in ["10,20,300", "a,b,c,d", "0,0,0,10", "1,2,3,4,5", "-1,0,10,10"] {
+        assert!(CropRect::parse(bad).is_err(), "{bad}");
+    }
+
+    let frame = ImageBuffer::from_fn(640, 360, |x, y| Rgb([(x % 256) as u8, (y % 256) as u8, 0]));
+    let cropped = crop_frame(frame.clone(), Some(&region)).unwrap();
+    assert_eq!(cropped.dimensions(), (300, 200));
+    assert_eq!(cropped.get_pixel(0, 0), &Rgb([10, 20, 0]));
+    assert_eq!(crop_frame(frame.clone(), None).unwrap(), frame);
+    let too_big = CropRect::parse("400,0,300,100").unwrap();
+    assert!(crop_frame(frame, Some(&too_big)).is_err());
+
+    assert!(HashSettings::default().to_json().get("crop").is_none());
+    let settings = HashSettings { crop: Some(&region), ..Default::default() };
+    assert_eq!(settings.to_json()["crop"], serde_json::json!([10, 20, 300, 200]));
+}
+
+#[test]
+fn black_bars_are_trimmed() {
+    // A 4:3 picture pillarboxed in a 16:9 frame, with one sample fading in from black.
+    let pillarboxed = |level: u8| {
+        ImageBuffer::from_fn(640, 360, move |x, _| {
+            if (80..560).contains(&x) { Rgb([level, level, level]) } else { Rgb([8, 8, 8]) }
+        })
+    };
+    let samples = [pillarboxed(0), pillarboxed(200), pillarboxed(120)];
+    assert_eq!(detect_borders(&samples), Some(CropRect { x: 80, y: 0, width: 480, height: 360 }));
+    assert_eq!(detect_borders(&[ImageBuffer::from_pixel(64, 36, Rgb([90u8, 90, 90]))]), None);
+    assert_eq!(detect_borders(&[ImageBuffer::from_pixel(64, 36, Rgb([0u8, 0, 0]))]), None);
+    assert_eq!(detect_borders(&[]), None);
+
+    assert_eq!(sample_indices(100), vec![5, 15, 25, 35, 45, 55, 65, 75, 85, 95]);
+    assert_eq!(sample_indices(3), vec![0, 1, 2]);
+    assert!(sample_indices(0).is_empty());
+}
@@ -0,0 +1,74 @@
+#![cfg(feature = "sqlite")]
+use image::{ImageBuffer, Rgb};
+use rusqlite::Connection;
+use videodocparser::database::{build_database, DatabaseOptions};
+use videodocparser::document_builder::Page;
+use videodocparser::frame_analyzer::AnalysisResult;
+use videodocparser::ocr::{OcrFrameResult, OcrWord};
+
+#[test]
+fn database_pages_are_full_text_searchable() {
+    let image = ImageBuffer::from_pixel(32, 24, Rgb([255u8, 255, 255]));
+    let ocr = OcrFrameResult {
+        frame_index: 1,
+        words: vec![
+            OcrWord { text: "Quarterly".to_string(), bbox: (1, 1, 14, 8), confidence: 90.0 },
+            OcrWord { text: "revenue".to_string(), bbox: (16, 1, 30, 8), confidence: 70.0 },
+        ],
+        blocks: Vec::new(),
+        strategy: None,
+        timestamp: None,
+    };
+    let analysis = AnalysisResult {
+        sensitivity: 0.9,
+        total_frames: 3,
+        kept_frames: vec![image.clone(), image.clone()],
+        differences: vec![0, 40],
+        removed_indices: vec![1],
+        hash_series: Vec::new(),
+        frame_meta: Vec::new(),
+        kept_indices: vec![0, 2],
+        source_indices: vec![0, 2],
+        kept_timestamps: vec![Some(0.0), Some(2.0)],
+        revisits: vec![Vec::new(), Vec::new()],
+        revisit_timestamps: vec![Vec::new(), Vec::new()],
+        segments: Vec::new(),
+    };
+    let pages = vec![
+        Page { image: &image, ocr: None, title: None, timestamp: Some(0.0), end: None },
+        Page { image: &image, ocr: Some(&ocr), title: None, timestamp: Some(2.0), end: None },
+    ];
+    let options = DatabaseOptions {
+        document_title: "Report".to_string(),
+        source: "report.mp4".to_string(),
+        sensitivity: 0.9,
+        link_images: false,
+    };
+
+    let dir = std::env::temp_dir().join(format!("vdp-sqlite-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("document.db");
+    build_database(&pages, &analysis, &options, &path).unwrap();
+
+    let conn = Connection::open(&path).unwrap();
+    let (page, frame_index, confidence): (i64, i64, f64) = conn
+        .query_row(
+            "SELECT p.page, p.frame_index, p.mean_confidence FROM pages_fts \
+             JOIN pages p ON p.page = pages_fts.rowid WHERE pages_fts MATCH 'revenue'",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .unwrap();
+    assert_eq!((page, frame_index, confidence), (2, 2, 80.0));
+
+    let kept: Vec<bool> = conn
+        .prepare("SELECT kept FROM frames ORDER BY frame_index")
+        .unwrap()
+        .query_map([], |row| row.get(0))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(kept, [true, false, true]);
+
+    std::fs::remove_dir_all(dir).ok();
+}
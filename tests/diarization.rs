@@ -0,0 +1,29 @@
+//! Checks telling speakers apart by pitch.
+
+use videodocparser::diarize::{label_turns, median_pitch};
+
+const RATE: u32 = 16_000;
+
+fn tone(hz: f64, seconds: f64) -> Vec<f32> {
+    (0..(seconds * RATE as f64) as usize)
+        .map(|i| (0.3 * (2.0 * std::f64::consts::PI * hz * i as f64 / RATE as f64).sin()) as f32)
+        .collect()
+}
+
+#[test]
+fn pitch_of_a_voiced_tone() {
+    let pitch = median_pitch(&tone(150.0, 1.0), RATE).unwrap();
+    assert!((pitch - 150.0).abs() < 3.0, "pitch {}", pitch);
+    assert_eq!(median_pitch(&vec![0.0; RATE as usize], RATE), None);
+}
+
+#[test]
+fn alternating_voices_get_two_speakers() {
+    // Low voice, high voice, low voice again (slightly higher), then silence.
+    let mut audio = tone(120.0, 2.0);
+    audio.extend(tone(230.0, 2.0));
+    audio.extend(tone(128.0, 2.0));
+    audio.extend(vec![0.0; 2 * RATE as usize]);
+    let turns = [(0.0, 2.0), (2.0, 4.0), (4.0, 6.0), (6.0, 8.0)];
+    assert_eq!(label_turns(&audio, RATE, &turns), [0, 1, 0, 0]);
+}
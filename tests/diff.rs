@@ -0,0 +1,111 @@
+//! Checks comparing the pages of two runs from their OCR reports.
+
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use videodocparser::diff::{DiffStatus, diff_runs};
+use videodocparser::ocr::{OcrFrameResult, OcrWord, words_csv};
+
+fn page(frame_index: usize, text: &str) -> OcrFrameResult {
+    let words = text
+        .split(' ')
+        .enumerate()
+        .map(|(i, word)| OcrWord {
+            text: word.to_string(),
+            bbox: (10 + 60 * i as i32, 40, 60 + 60 * i as i32, 60),
+            confidence: 90.0,
+        })
+        .collect();
+    let timestamp = Some(frame_index as f64 * 30.0);
+    OcrFrameResult { frame_index, words, blocks: Vec::new(), strategy: None, timestamp }
+}
+
+/// Writes a run's OCR report in `format`, and its `run.json` unless `manifest` is `None`.
+fn write_run(name: &str, results: &[OcrFrameResult], format: &str, manifest: Option<serde_json::Value>) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("vdp-diff-{}-{}", name, std::process::id()));
+    let ocr_dir = dir.join("ocr");
+    std::fs::create_dir_all(&ocr_dir).unwrap();
+    match format {
+        "csv" => std::fs::write(ocr_dir.join("ocr_results.csv"), words_csv(results)).unwrap(),
+        _ => std::fs::write(ocr_dir.join("ocr_results.json"), serde_json::to_string(results).unwrap()).unwrap(),
+    }
+    if let Some(manifest) = manifest {
+        std::fs::write(dir.join("run.json"), manifest.to_string()).unwrap();
+    }
+    dir
+}
+
+fn statuses(old: &Path, new: &Path) -> Vec<(DiffStatus, Option<usize>, Option<usize>)> {
+    let report = diff_runs(old, new, 0.5).unwrap();
+    report.entries.iter().map(|entry| (entry.status, entry.old_page, entry.new_page)).collect()
+}
+
+#[test]
+fn pages_are_matched_by_their_text() {
+    let old_pages = [page(0, "intro to rust"), page(1, "ownership and borrowing rules")];
+    let old = write_run("json-old", &old_pages, "json", None);
+    let new = write_run(
+        "json-new",
+        &[page(0, "intro to rust"), page(1, "ownership and borrowing rules today"), page(2, "lifetimes")],
+        "json",
+        None,
+    );
+    assert_eq!(
+        statuses(&old, &new),
+        vec![
+            (DiffStatus::Unchanged, Some(1), Some(1)),
+            (DiffStatus::Changed, Some(2), Some(2)),
+            (DiffStatus::Added, None, Some(3)),
+        ]
+    );
+    assert_eq!(statuses(&new, &old)[2], (DiffStatus::Removed, Some(3), None));
+    std::fs::remove_dir_all(old).ok();
+    std::fs::remove_dir_all(new).ok();
+}
+
+#[test]
+fn csv_reports_are_read_as_the_manifest_says() {
+    let results = [page(0, "Hello, \"quoted\" world"), page(1, "second slide")];
+    let json_run = write_run("csv-json", &results, "json", None);
+    let csv_run =
+        write_run("csv-csv", &results, "csv", Some(json!({ "pages": 2, "config": { "ocr_report": "csv" } })));
+    // A run without a manifest is read from the report it has.
+    let bare_csv = write_run("csv-bare", &results, "csv", None);
+    for run in [&csv_run, &bare_csv] {
+        assert_eq!(
+            statuses(&json_run, run),
+            vec![(DiffStatus::Unchanged, Some(1), Some(1)), (DiffStatus::Unchanged, Some(2), Some(2))]
+        );
+    }
+    for dir in [json_run, csv_run, bare_csv] {
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
+
+#[test]
+fn pages_without_ocr_are_reported_as_skipped() {
+    let manifest = json!({ "pages": 3, "config": { "ocr_report": "json", "skip_textless": true } });
+    let old = write_run("skip-old", &[page(0, "title slide"), page(1, "a diagram"), page(2, "summary")], "json", None);
+    let new = write_run("skip-new", &[page(0, "title slide"), page(2, "summary")], "json", Some(manifest));
+    assert_eq!(
+        statuses(&old, &new),
+        vec![
+            (DiffStatus::Unchanged, Some(1), Some(1)),
+            (DiffStatus::Skipped, None, Some(2)),
+            (DiffStatus::Unchanged, Some(3), Some(3)),
+            (DiffStatus::Removed, Some(2), None),
+        ]
+    );
+    let report = diff_runs(&old, &new, 0.5).unwrap();
+    assert_eq!((report.old_pages, report.new_pages), (3, 3));
+
+    // Without --skip-textless, a CSV run's pages without rows had no words.
+    let manifest = json!({ "pages": 2, "config": { "ocr_report": "csv", "skip_textless": false } });
+    let blank = write_run("skip-blank", &[page(0, "title slide")], "csv", Some(manifest));
+    let report = diff_runs(&blank, &blank, 0.5).unwrap();
+    assert_eq!(report.count(DiffStatus::Skipped), 0);
+    assert_eq!(report.count(DiffStatus::Unchanged), 2);
+
+    for dir in [old, new, blank] {
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
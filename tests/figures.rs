@@ -0,0 +1,71 @@
+//! Checks that charts and diagrams are cropped out of the slides.
+
+use image::{ImageBuffer, Rgb};
+use videodocparser::document_builder::{
+    build_html, build_markdown, ConfidencePolicy, HtmlOptions, MarkdownOptions, Page,
+};
+use videodocparser::figures::{detect, figure_path};
+use videodocparser::ocr::{OcrFrameResult, OcrWord};
+
+fn fill(image: &mut ImageBuffer<Rgb<u8>, Vec<u8>>, (x1, y1, x2, y2): (u32, u32, u32, u32), colour: [u8; 3]) {
+    for y in y1..y2 {
+        for x in x1..x2 {
+            image.put_pixel(x, y, Rgb(colour));
+        }
+    }
+}
+
+#[test]
+fn charts_are_cropped_and_text_is_not() {
+    let mut image = ImageBuffer::from_pixel(640, 360, Rgb([255u8, 255, 255]));
+    // A title bar, some text, and a bar chart.
+    fill(&mut image, (0, 0, 640, 40), [30, 60, 120]);
+    let mut words = Vec::new();
+    for (i, text) in ["Sales", "grew", "again"].iter().enumerate() {
+        let bbox = (20 + 90 * i as i32, 100, 100 + 90 * i as i32, 120);
+        fill(&mut image, (bbox.0 as u32, 102, bbox.2 as u32, 118), [0, 0, 0]);
+        words.push(OcrWord { text: text.to_string(), bbox, confidence: 90.0 });
+    }
+    fill(&mut image, (400, 200, 456, 320), [200, 40, 40]);
+    fill(&mut image, (464, 160, 528, 320), [40, 160, 40]);
+    fill(&mut image, (536, 120, 600, 320), [40, 40, 200]);
+    let ocr = OcrFrameResult { frame_index: 0, words, blocks: Vec::new(), strategy: None, timestamp: None };
+
+    assert_eq!(detect(&image, Some(&ocr)), vec![(400, 120, 200, 200)]);
+    assert_eq!(figure_path(0, 0), "figures/page_001_figure_1.png");
+
+    let pages = vec![Page { image: &image, ocr: Some(&ocr), title: None, timestamp: None, end: None }];
+    let dir = std::env::temp_dir().join(format!("vdp-figures-{}", std::process::id()));
+    let options = MarkdownOptions {
+        heading_level: 2,
+        document_title: "Sales".to_string(),
+        video_url: None,
+        speech: Vec::new(),
+        keyword_index: false,
+        tags: Vec::new(),
+        url_links: false,
+        figures: true,
+        formulas: false,
+        new_content_only: false,
+        translations: Vec::new(),
+        confidence: ConfidencePolicy::default(),
+    };
+    build_markdown(&pages, &options, &dir.join("document.md")).unwrap();
+    let markdown = std::fs::read_to_string(dir.join("document.md")).unwrap();
+    assert!(markdown.contains("\n![Figure 1](figures/page_001_figure_1.png)\n"));
+    let crop = image::open(dir.join(figure_path(0, 0))).unwrap();
+    assert_eq!((crop.width(), crop.height()), (200, 200));
+
+    let options = HtmlOptions {
+        document_title: "Sales".to_string(),
+        video_url: None,
+        embed_images: true,
+        figures: true,
+        formulas: false,
+        speech: Vec::new(),
+    };
+    build_html(&pages, &options, &dir.join("document.html")).unwrap();
+    let html = std::fs::read_to_string(dir.join("document.html")).unwrap();
+    assert_eq!(html.matches("<figure class=\"ocr-figure\"><img src=\"data:image/png;base64,").count(), 1);
+    std::fs::remove_dir_all(dir).ok();
+}
@@ -0,0 +1,120 @@
+//! Checks telling formulas apart from prose and code, and keeping them as images in the outputs.
+
+use image::{ImageBuffer, Rgb};
+use lopdf::Document;
+use videodocparser::document_builder::{
+    build_html, build_markdown, build_pdf, ConfidencePolicy, HtmlOptions, MarkdownOptions, Page, PdfOptions,
+};
+use videodocparser::formulas::{detect, is_formula, strip};
+use videodocparser::layout::{group_blocks_with_formulas, TextBlock};
+use videodocparser::ocr::{OcrFrameResult, OcrWord};
+
+/// Words spaced out along a line at `top`, with the given confidence.
+fn line(top: i32, text: &str, confidence: f32) -> Vec<OcrWord> {
+    let mut x = 20;
+    text.split_whitespace()
+        .map(|word| {
+            let width = word.chars().count() as i32 * 12;
+            let bbox = (x, top, x + width, top + 24);
+            x += width + 12;
+            OcrWord { text: word.to_string(), bbox, confidence }
+        })
+        .collect()
+}
+
+fn formula(text: &str, confidence: f32) -> bool {
+    let words = line(0, text, confidence);
+    is_formula(&words.iter().collect::<Vec<_>>())
+}
+
+#[test]
+fn formulas_are_told_apart_by_their_symbols_and_failures() {
+    assert!(formula("E = mc²", 90.0));
+    assert!(formula("x² + y² = r²", 90.0));
+    assert!(formula("f(x) = sin(x) + α", 90.0));
+    assert!(!formula("Ownership rules apply", 90.0));
+    assert!(!formula("let total = price * count;", 90.0));
+    assert!(!formula("2024-05-01", 90.0));
+    assert!(!formula("C++", 90.0));
+    // Short, unsure fragments around a stray operator are a formula
+    // Tesseract failed to read; read with confidence, they are not.
+    assert!(formula("Xi ab + yz 12 cd ef", 30.0));
+    assert!(!formula("Xi ab + yz 12 cd ef", 90.0));
+}
+
+/// A page with a formula between two lines of prose.
+fn page() -> OcrFrameResult {
+    let mut words = line(20, "Mass and energy are equivalent", 90.0);
+    words.extend(line(80, "E = mc²", 60.0));
+    words.extend(line(140, "Einstein published it in 1905", 90.0));
+    OcrFrameResult { frame_index: 0, words, blocks: Vec::new(), strategy: None, timestamp: None }
+}
+
+#[test]
+fn formulas_are_set_apart_in_place() {
+    let ocr = page();
+    let blocks = group_blocks_with_formulas(&ocr);
+    assert_eq!(blocks.len(), 3, "{blocks:?}");
+    assert!(matches!(&blocks[0], TextBlock::Prose(lines) if lines[0].text == "Mass and energy are equivalent"));
+    let TextBlock::Formula(formula) = &blocks[1] else { panic!("expected a formula, got {:?}", blocks[1]) };
+    assert_eq!(formula.text, "E = mc²");
+    assert_eq!(formula.bbox, (20, 80, 104, 104));
+    assert!(matches!(&blocks[2], TextBlock::Prose(_)));
+
+    assert_eq!(detect(&ocr).len(), 1);
+    let stripped = strip(&ocr);
+    assert_eq!(stripped.words.len(), ocr.words.len() - 3);
+    assert!(stripped.words.iter().all(|word| word.text != "mc²"));
+}
+
+#[test]
+fn outputs_show_formulas_as_images() {
+    let image = ImageBuffer::from_pixel(400, 200, Rgb([255u8, 255, 255]));
+    let ocr = page();
+    let title = Some("Energy".to_string());
+    let pages = vec![Page { image: &image, ocr: Some(&ocr), title, timestamp: None, end: None }];
+    let dir = std::env::temp_dir().join(format!("vdp-formulas-{}", std::process::id()));
+
+    let options = MarkdownOptions {
+        heading_level: 2,
+        document_title: "Physics".to_string(),
+        video_url: None,
+        speech: Vec::new(),
+        keyword_index: false,
+        tags: Vec::new(),
+        url_links: false,
+        figures: false,
+        formulas: true,
+        new_content_only: false,
+        translations: Vec::new(),
+        confidence: ConfidencePolicy::default(),
+    };
+    build_markdown(&pages, &options, &dir.join("document.md")).unwrap();
+    let markdown = std::fs::read_to_string(dir.join("document.md")).unwrap();
+    assert!(
+        markdown.contains("equivalent\n\n![E = mc²](formulas/page_001_formula_1.png)\n\nEinstein"),
+        "{markdown}"
+    );
+    let crop = image::open(dir.join("formulas/page_001_formula_1.png")).unwrap();
+    assert_eq!((crop.width(), crop.height()), (100, 40));
+
+    let options = HtmlOptions {
+        document_title: "Physics".to_string(),
+        video_url: None,
+        embed_images: true,
+        figures: false,
+        formulas: true,
+        speech: Vec::new(),
+    };
+    build_html(&pages, &options, &dir.join("document.html")).unwrap();
+    let html = std::fs::read_to_string(dir.join("document.html")).unwrap();
+    assert!(html.contains("<figure class=\"ocr-formula\"><img src=\"data:image/png;base64,"));
+    assert!(html.contains("alt=\"E = mc²\">"));
+    assert!(!html.contains(">mc²</span>"));
+
+    let pdf = dir.join("document.pdf");
+    build_pdf(&pages, &PdfOptions { formulas: true, ..PdfOptions::default() }, &pdf).unwrap();
+    let text = Document::load(&pdf).unwrap().extract_text(&[1]).unwrap();
+    assert!(text.contains("Einstein") && !text.contains("mc"), "{text:?}");
+    std::fs::remove_dir_all(dir).ok();
+}
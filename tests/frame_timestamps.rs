@@ -0,0 +1,63 @@
+//! Checks that pages are timed by the presentation timestamps of their frames.
+
+use image::RgbImage;
+use videodocparser::frame_analyzer::{FrameAnalyzer, RevisitPolicy};
+use videodocparser::video_processor::FrameMeta;
+
+mod common;
+use common::slide;
+
+/// A screen recording that only sends a frame when the screen changes: slide 0,
+/// slide 1 from 4 s, slide 0 again from 30 s, and slide 2 from 31.5 s to 40 s.
+fn recording() -> Vec<(RgbImage, FrameMeta)> {
+    [(0, 0.0), (0, 0.5), (1, 4.0), (1, 4.5), (0, 30.0), (2, 31.5), (2, 32.0)]
+        .into_iter()
+        .enumerate()
+        .map(|(index, (n, pts))| {
+            let duration = if index == 6 { 8.0 } else { 0.5 };
+            (slide(n), FrameMeta { index, pts: Some(pts), duration: Some(duration) })
+        })
+        .collect()
+}
+
+fn analyze(dir: &std::path::Path) -> FrameAnalyzer {
+    common::analyze_timed(dir, recording(), |analyzer| {
+        analyzer.set_revisit_policy(RevisitPolicy::Merge);
+        // The nominal frame rate is far from the actual one.
+        analyzer.set_frame_rate(2.0);
+    })
+}
+
+#[test]
+fn pages_appear_at_their_presentation_timestamps() {
+    let dir = std::env::temp_dir().join(format!("vdp-timestamps-{}", std::process::id()));
+    let result = analyze(&dir).finish().unwrap();
+    assert_eq!(result.kept_indices, vec![0, 2, 5]);
+    assert_eq!(result.kept_timestamps, vec![Some(0.0), Some(4.0), Some(31.5)]);
+    assert_eq!(result.revisit_timestamps, vec![vec![30.0], vec![], vec![]]);
+    let ends: Vec<Option<f64>> = result.segments.iter().map(|segment| segment.end).collect();
+    // The last segment lasts as long as its last frame is shown.
+    assert_eq!(ends, vec![Some(4.0), Some(30.0), Some(40.0)]);
+    // The video runs 40 s, not its 7 frames over the nominal frame rate.
+    assert_eq!(result.duration(), Some(40.0));
+    assert_eq!(result.frame_meta, recording().into_iter().map(|(_, meta)| meta).collect::<Vec<_>>());
+
+    // Replaying the cached series keeps the timestamps.
+    let mut replay = FrameAnalyzer::new(0.9, &dir).unwrap();
+    replay.set_revisit_policy(RevisitPolicy::Merge);
+    replay.set_frame_meta(result.frame_meta.clone());
+    replay.replay(result.hash_series.clone(), None).unwrap();
+    assert_eq!(replay.finish().unwrap().kept_timestamps, result.kept_timestamps);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn untimed_frames_fall_back_to_the_frame_rate() {
+    let dir = std::env::temp_dir().join(format!("vdp-timestamps-untimed-{}", std::process::id()));
+    let frames = recording().into_iter().map(|(frame, _)| frame);
+    let result = common::analyze(&dir, frames, |analyzer| analyzer.set_frame_rate(2.0)).finish().unwrap();
+    assert_eq!(result.kept_timestamps, vec![Some(0.0), Some(1.0), Some(2.0), Some(2.5)]);
+    assert_eq!(result.segments.last().and_then(|segment| segment.end), Some(3.5));
+    assert_eq!(result.frame_meta[3], FrameMeta::untimed(3));
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,71 @@
+//! Checks finding lines of handwriting on boards and handing unreadable frames to the handwriting engine.
+
+use anyhow::Result;
+use image::{DynamicImage, GrayImage, Luma};
+use videodocparser::handwriting::{ink_lines, HandwritingFallback};
+use videodocparser::ocr::{OcrEngine, OcrWord, Recognition};
+
+/// A board of the given brightness with strokes of `ink` in the given boxes.
+fn board(level: u8, ink: u8, strokes: &[(u32, u32, u32, u32)]) -> GrayImage {
+    GrayImage::from_fn(200, 120, |x, y| {
+        let inked = strokes.iter().any(|&(x1, y1, x2, y2)| (x1..x2).contains(&x) && (y1..y2).contains(&y));
+        Luma([if inked { ink } else { level }])
+    })
+}
+
+#[test]
+fn lines_are_the_bands_of_ink() {
+    // Two words on one line, a gap in the letters of the first, and a far
+    // away word on the other half of the board.
+    let strokes = [(10, 10, 40, 30), (10, 32, 40, 34), (50, 10, 80, 30), (160, 10, 190, 30), (10, 60, 100, 80)];
+    let lines = vec![(10, 10, 80, 34), (160, 10, 190, 34), (10, 60, 100, 80)];
+    assert_eq!(ink_lines(&board(240, 30, &strokes)), lines);
+    // Chalk on a blackboard is found the same way.
+    assert_eq!(ink_lines(&board(40, 220, &strokes)), lines);
+    // Specks are not lines.
+    assert!(ink_lines(&board(240, 30, &[(10, 10, 14, 14)])).is_empty());
+    assert!(ink_lines(&board(240, 230, &strokes)).is_empty());
+}
+
+/// Reads one word whose confidence is the brightness of the image's first pixel.
+struct Printed;
+
+impl OcrEngine for Printed {
+    fn name(&self) -> &'static str {
+        "printed"
+    }
+
+    fn recognize(&self, image: &DynamicImage) -> Result<Recognition> {
+        let confidence = image.to_luma8().get_pixel(0, 0).0[0] as f32 / 2.55;
+        let word = OcrWord { text: "printed".to_string(), bbox: (0, 0, 10, 10), confidence };
+        Ok(Recognition { words: vec![word], ..Recognition::default() })
+    }
+}
+
+/// Reads one handwritten word on every image.
+struct Handwritten;
+
+impl OcrEngine for Handwritten {
+    fn name(&self) -> &'static str {
+        "handwritten"
+    }
+
+    fn recognize(&self, _image: &DynamicImage) -> Result<Recognition> {
+        let word = OcrWord { text: "handwritten".to_string(), bbox: (0, 0, 10, 10), confidence: 70.0 };
+        Ok(Recognition { words: vec![word], ..Recognition::default() })
+    }
+}
+
+#[test]
+fn frames_read_with_low_confidence_go_to_the_handwriting_engine() {
+    let engine = HandwritingFallback::new(Box::new(Printed), Box::new(Handwritten), 40.0);
+    assert_eq!(engine.name(), "printed");
+    let image = |level| DynamicImage::ImageLuma8(board(level, 0, &[]));
+    let read = |recognition: Recognition| recognition.words[0].text.clone();
+    assert_eq!(read(engine.recognize(&image(204)).unwrap()), "printed");
+    assert_eq!(read(engine.recognize(&image(51)).unwrap()), "handwritten");
+
+    let batch = engine.recognize_batch(&[image(51), image(255), image(0)]).unwrap();
+    let texts: Vec<String> = batch.into_iter().map(read).collect();
+    assert_eq!(texts, ["handwritten", "printed", "handwritten"]);
+}
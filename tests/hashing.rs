@@ -0,0 +1,97 @@
+//! Checks hashing frames with each algorithm and hash size.
+
+use image::{DynamicImage, ImageBuffer, Rgb, RgbImage};
+use imagehash::PerceptualHash;
+use videodocparser::frame_analyzer::{
+    FrameAnalyzer, FrameHash, FrameHasher, HASH_ALGORITHMS, HashAlgorithm, HashSettings,
+};
+
+/// A white slide with a title bar and `lines` lines of text, indented by
+/// `indent` pixels.
+fn slide(lines: u32, indent: u32) -> RgbImage {
+    ImageBuffer::from_fn(320, 180, |x, y| {
+        let title = (20..36).contains(&y) && (20..200).contains(&x);
+        let line = y >= 50 && (y - 50) / 16 < lines && (y - 50) % 16 < 6 && (indent..280).contains(&x);
+        if title || line { Rgb([30, 30, 30]) } else { Rgb([250, 250, 250]) }
+    })
+}
+
+/// The frame with a little deterministic noise, as left by video compression.
+fn noisy(frame: &RgbImage) -> RgbImage {
+    let mut noisy = frame.clone();
+    for (x, y, pixel) in noisy.enumerate_pixels_mut() {
+        let offset = ((x * 7 + y * 13) % 11) as i32 - 5;
+        *pixel = Rgb(pixel.0.map(|channel| (channel as i32 + offset).clamp(0, 255) as u8));
+    }
+    noisy
+}
+
+#[test]
+fn the_default_hasher_is_the_original_16x16_phash() {
+    let frame = DynamicImage::ImageRgb8(slide(4, 40));
+    let original = PerceptualHash::new().with_image_size(16, 16).with_hash_size(16, 16).hash(&frame);
+    let hex: String = original.to_bytes().iter().map(|byte| format!("{:02x}", byte)).collect();
+    assert_eq!(String::from(FrameHasher::default().hash(&frame)), hex);
+}
+
+#[test]
+fn every_algorithm_tells_slides_apart_through_noise() {
+    let (slide_a, slide_b) = (slide(4, 40), slide(7, 120));
+    for name in HASH_ALGORITHMS {
+        let algorithm = HashAlgorithm::from_name(name).unwrap();
+        assert_eq!(algorithm.name(), name);
+        for size in [8, 16, 32] {
+            let hasher = FrameHasher::new(algorithm, size);
+            let hash = |frame: &RgbImage| hasher.hash(&DynamicImage::ImageRgb8(frame.clone()));
+            let (a, b) = (hash(&slide_a), hash(&slide_b));
+            let bits = if algorithm == HashAlgorithm::Perceptual { size * (size - 1) } else { size * size };
+            assert_eq!(String::from(a.clone()).len(), bits.div_ceil(8) * 2, "{} {}", name, size);
+
+            let share = |distance: u32| distance as f64 / hasher.bits() as f64;
+            let same = share(a.distance(&hash(&noisy(&slide_a))).unwrap());
+            let different = share(a.distance(&b).unwrap());
+            assert!(same < 0.1, "{} {}: noise moved the hash by {}", name, size, same);
+            assert!(different > 2.0 * same, "{} {}: other slide only {} away", name, size, different);
+        }
+    }
+    assert_eq!(HashAlgorithm::from_name("md5"), None);
+}
+
+#[test]
+fn the_analyzer_uses_the_hasher_given() {
+    let dir = std::env::temp_dir().join(format!("vdp-hashing-{}", std::process::id()));
+    let frames = [slide(4, 40), noisy(&slide(4, 40)), slide(7, 120), slide(7, 120)];
+    for name in HASH_ALGORITHMS {
+        let mut analyzer = FrameAnalyzer::new(0.9, &dir).unwrap();
+        analyzer.set_hasher(FrameHasher::new(HashAlgorithm::from_name(name).unwrap(), 8));
+        for frame in frames.clone() {
+            analyzer.process_frame(frame).unwrap();
+        }
+        assert_eq!(analyzer.kept_indices(), &[0, 2], "{}", name);
+        let result = analyzer.finish().unwrap();
+        assert_eq!(result.hash_series.len(), frames.len());
+    }
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn the_algorithm_and_size_are_part_of_the_hash_settings() {
+    let settings = HashSettings::default().to_json();
+    assert_eq!(settings, serde_json::json!({ "algorithm": "phash", "hash_size": [16, 16] }));
+    let hasher = FrameHasher::new(HashAlgorithm::Wavelet, 8);
+    let settings = HashSettings { hasher, ..Default::default() }.to_json();
+    assert_eq!(settings, serde_json::json!({ "algorithm": "whash", "hash_size": [8, 8] }));
+}
+
+#[test]
+fn hashes_round_trip_through_their_hex_form() {
+    let hash = FrameHasher::default().hash(&DynamicImage::ImageRgb8(slide(4, 40)));
+    let json = serde_json::to_string(&vec![hash.clone()]).unwrap();
+    assert_eq!(json, format!("[\"{}\"]", String::from(hash.clone())));
+    let read: Vec<FrameHash> = serde_json::from_str(&json).unwrap();
+    assert_eq!(read, vec![hash.clone()]);
+    assert_eq!(read[0].distance(&hash).unwrap(), 0);
+
+    assert!(serde_json::from_str::<FrameHash>("\"abc\"").is_err());
+    assert!(serde_json::from_str::<FrameHash>("\"zz\"").is_err());
+}
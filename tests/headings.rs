@@ -0,0 +1,78 @@
+//! Checks that lines are classified as headings by their size.
+
+use image::{ImageBuffer, Rgb};
+use videodocparser::document_builder::{
+    build_html, build_markdown, ConfidencePolicy, HtmlOptions, MarkdownOptions, Page,
+};
+use videodocparser::layout::{body_height, group_lines, heading_level};
+use videodocparser::ocr::{OcrFrameResult, OcrWord};
+
+/// Words of a line `height` pixels tall, starting at `top`.
+fn line(top: i32, height: i32, text: &str) -> Vec<OcrWord> {
+    text.split_whitespace()
+        .enumerate()
+        .map(|(i, word)| OcrWord {
+            text: word.to_string(),
+            bbox: (20 + 90 * i as i32, top, 100 + 90 * i as i32, top + height),
+            confidence: 90.0,
+        })
+        .collect()
+}
+
+#[test]
+fn larger_lines_become_headings() {
+    let mut words = line(10, 60, "Memory Safety");
+    words.extend(line(100, 20, "Rust checks every borrow"));
+    words.extend(line(130, 30, "Ownership rules"));
+    words.extend(line(170, 20, "Each value has one owner"));
+    words.extend(line(200, 20, "Owners drop their values"));
+    let ocr = OcrFrameResult { frame_index: 0, words, blocks: Vec::new(), strategy: None, timestamp: None };
+
+    let lines = group_lines(&ocr);
+    let body = body_height(&lines).unwrap();
+    assert_eq!(body, 20);
+    let levels: Vec<Option<usize>> = lines.iter().map(|line| heading_level(line, body)).collect();
+    assert_eq!(levels, vec![Some(1), None, Some(2), None, None]);
+
+    let image = ImageBuffer::from_pixel(640, 360, Rgb([255u8, 255, 255]));
+    let title = Some("Memory Safety".to_string());
+    let pages = vec![Page { image: &image, ocr: Some(&ocr), title, timestamp: None, end: None }];
+    let dir = std::env::temp_dir().join(format!("vdp-headings-{}", std::process::id()));
+
+    let options = MarkdownOptions {
+        heading_level: 2,
+        document_title: "Rust".to_string(),
+        video_url: None,
+        speech: Vec::new(),
+        keyword_index: false,
+        tags: Vec::new(),
+        url_links: false,
+        figures: false,
+        formulas: false,
+        new_content_only: false,
+        translations: Vec::new(),
+        confidence: ConfidencePolicy::default(),
+    };
+    let md_path = dir.join("document.md");
+    build_markdown(&pages, &options, &md_path).unwrap();
+    let markdown = std::fs::read_to_string(&md_path).unwrap();
+    // The slide title heads the section and is not repeated.
+    assert!(markdown.contains(
+        "## Memory Safety\n\n![Page 1](images/page_001.png)\n\nRust checks every borrow\n\n#### Ownership rules\n\n\
+         Each value has one owner  \nOwners drop their values\n"
+    ));
+
+    let options = HtmlOptions {
+        document_title: "Rust".to_string(),
+        video_url: None,
+        embed_images: true,
+        figures: false,
+        formulas: false,
+        speech: Vec::new(),
+    };
+    let html_path = dir.join("document.html");
+    build_html(&pages, &options, &html_path).unwrap();
+    let html = std::fs::read_to_string(&html_path).unwrap();
+    assert!(html.contains("<div class=\"ocr-text\">Rust checks every borrow</div>\n<h4>Ownership rules</h4>\n"));
+    std::fs::remove_dir_all(dir).ok();
+}
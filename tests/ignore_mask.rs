@@ -0,0 +1,86 @@
+//! Checks ignoring dynamic regions of the frame when hashing and recognizing it.
+
+use image::{GrayImage, ImageBuffer, Luma, Rgb};
+use videodocparser::crop::CropRect;
+use videodocparser::frame_analyzer::{FrameAnalyzer, HashSettings};
+use videodocparser::mask::IgnoreMask;
+
+/// A slide whose left half shows a "clock" that is either dark or light.
+fn slide(clock: u8) -> ImageBuffer<Rgb<u8>, Vec<u8>> {
+    ImageBuffer::from_fn(256, 144, |x, y| {
+        if x < 128 {
+            Rgb([clock, clock, clock])
+        } else if (y / 12) % 2 == 0 {
+            Rgb([240, 240, 240])
+        } else {
+            Rgb([30, 30, 30])
+        }
+    })
+}
+
+#[test]
+fn regions_and_png_masks_are_combined() {
+    assert!(IgnoreMask::load(&[], None).unwrap().is_none());
+    assert!(IgnoreMask::load(&["1,2,3".to_string()], None).is_err());
+    assert!(IgnoreMask::load(&[], Some(std::path::Path::new("/nonexistent/mask.png"))).is_err());
+
+    let dir = std::env::temp_dir().join(format!("vdp-ignore-mask-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("mask.png");
+    GrayImage::from_fn(8, 4, |x, _| if x >= 6 { Luma([255]) } else { Luma([100]) }).save(&path).unwrap();
+    let mask = IgnoreMask::load(&["0,0,2,2".to_string()], Some(&path)).unwrap().unwrap();
+    std::fs::remove_dir_all(&dir).ok();
+
+    assert!(mask.is_ignored(1, 1));
+    assert!(mask.is_ignored(7, 3));
+    assert!(!mask.is_ignored(3, 1));
+    assert!(!mask.is_ignored(1, 2));
+    assert!(mask.check_size(8, 4).is_ok());
+    assert!(mask.check_size(16, 8).is_err());
+
+    let frame = ImageBuffer::from_pixel(8, 4, Rgb([200u8, 200, 200]));
+    let masked = mask.apply(&frame, Rgb([1, 2, 3]));
+    assert_eq!(masked.get_pixel(0, 0), &Rgb([1, 2, 3]));
+    assert_eq!(masked.get_pixel(6, 0), &Rgb([1, 2, 3]));
+    assert_eq!(masked.get_pixel(4, 2), &Rgb([200, 200, 200]));
+}
+
+#[test]
+fn the_mask_is_part_of_the_hash_settings() {
+    let regions = IgnoreMask::new(vec![CropRect::parse("0,0,10,10").unwrap()], None);
+    let other = IgnoreMask::new(vec![CropRect::parse("0,0,10,20").unwrap()], None);
+    let image = IgnoreMask::new(Vec::new(), Some(GrayImage::from_pixel(4, 4, Luma([255]))));
+
+    let masked = |mask: &IgnoreMask| HashSettings { ignore_mask: Some(mask), ..Default::default() }.to_json();
+    assert!(HashSettings::default().to_json().get("ignore").is_none());
+    assert_eq!(masked(&regions)["ignore"]["regions"], serde_json::json!([[0, 0, 10, 10]]));
+    assert_ne!(masked(&regions), masked(&other));
+    assert!(masked(&image)["ignore"]["image"].is_string());
+}
+
+#[test]
+fn changes_inside_ignored_regions_do_not_start_pages() {
+    let dir = std::env::temp_dir().join(format!("vdp-ignore-analysis-{}", std::process::id()));
+    let frames = [slide(0), slide(255), slide(0), slide(255)];
+
+    let mut plain = FrameAnalyzer::new(0.9, &dir).unwrap();
+    for frame in frames.clone() {
+        plain.process_frame(frame).unwrap();
+    }
+    assert_eq!(plain.kept_indices(), &[0, 1, 2, 3]);
+
+    let mut masked = FrameAnalyzer::new(0.9, &dir).unwrap();
+    masked.set_ignore_mask(IgnoreMask::new(vec![CropRect::parse("0,0,128,144").unwrap()], None));
+    for frame in frames.clone() {
+        masked.process_frame(frame).unwrap();
+    }
+    assert_eq!(masked.kept_indices(), &[0]);
+    // The kept page image still shows the ignored region.
+    let result = masked.finish().unwrap();
+    assert_eq!(result.kept_frames[0], frames[0]);
+    std::fs::remove_dir_all(&dir).ok();
+
+    let mut wrong_size = FrameAnalyzer::new(0.9, &dir).unwrap();
+    wrong_size.set_ignore_mask(IgnoreMask::new(Vec::new(), Some(GrayImage::new(10, 10))));
+    assert!(wrong_size.process_frame(slide(0)).is_err());
+}
@@ -0,0 +1,78 @@
+//! Checks the IIIF manifest and level-0 image tiles.
+
+use image::{ImageBuffer, Rgb};
+use videodocparser::document_builder::Page;
+use videodocparser::iiif::{build_iiif, scale_factors, IiifOptions};
+use videodocparser::ocr::{OcrFrameResult, OcrWord};
+
+#[test]
+fn manifest_references_tiles_and_ocr_annotations() {
+    assert_eq!(scale_factors(512, 300), vec![1]);
+    assert_eq!(scale_factors(1200, 700), vec![1, 2, 4]);
+
+    let image = ImageBuffer::from_pixel(1200, 700, Rgb([255u8, 255, 255]));
+    let ocr = OcrFrameResult {
+        frame_index: 0,
+        words: vec![
+            OcrWord { text: "Agenda".to_string(), bbox: (100, 50, 300, 90), confidence: 90.0 },
+            OcrWord { text: "today".to_string(), bbox: (320, 50, 450, 90), confidence: 85.0 },
+        ],
+        blocks: Vec::new(),
+        strategy: None,
+        timestamp: None,
+    };
+    let pages = vec![
+        Page { image: &image, ocr: Some(&ocr), title: Some("Agenda".to_string()), timestamp: Some(5.0), end: None },
+        Page { image: &image, ocr: None, title: None, timestamp: Some(65.0), end: None },
+    ];
+    let options = IiifOptions {
+        base_url: "https://example.org/talk/".to_string(),
+        document_title: "Talk".to_string(),
+        source: "talk.mp4".to_string(),
+    };
+    let dir = std::env::temp_dir().join(format!("vdp-iiif-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    build_iiif(&pages, &options, &dir).unwrap();
+
+    let service = dir.join("iiif/images/page_001");
+    for tile in [
+        "full/max/0/default.jpg",
+        "full/1200,700/0/default.jpg",
+        "1024,512,176,188/176,188/0/default.jpg",
+        "0,0,1024,700/512,350/0/default.jpg",
+        "0,0,1200,700/300,175/0/default.jpg",
+    ] {
+        assert!(service.join(tile).exists(), "missing tile {}", tile);
+    }
+    let tile = image::open(service.join("1024,0,176,700/88,350/0/default.jpg")).unwrap();
+    assert_eq!((tile.width(), tile.height()), (88, 350));
+
+    let info: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(service.join("info.json")).unwrap()).unwrap();
+    assert_eq!(info["id"], "https://example.org/talk/images/page_001");
+    assert_eq!(info["profile"], "level0");
+    assert_eq!(info["tiles"][0]["scaleFactors"], serde_json::json!([1, 2, 4]));
+
+    let manifest: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(dir.join("iiif/manifest.json")).unwrap()).unwrap();
+    assert_eq!(manifest["type"], "Manifest");
+    assert_eq!(manifest["id"], "https://example.org/talk/manifest.json");
+    assert_eq!(manifest["label"]["none"][0], "Talk");
+    let canvases = manifest["items"].as_array().unwrap();
+    assert_eq!(canvases.len(), 2);
+    let canvas = &canvases[0];
+    assert_eq!(canvas["width"], 1200);
+    let painting = &canvas["items"][0]["items"][0];
+    assert_eq!(painting["motivation"], "painting");
+    assert_eq!(painting["body"]["id"], "https://example.org/talk/images/page_001/full/max/0/default.jpg");
+    assert_eq!(painting["body"]["service"][0]["type"], "ImageService3");
+    assert_eq!(painting["target"], "https://example.org/talk/canvas/1");
+
+    let annotation = &canvas["annotations"][0]["items"][0];
+    assert_eq!(annotation["motivation"], "supplementing");
+    assert_eq!(annotation["body"]["value"], "Agenda today");
+    assert_eq!(annotation["target"], "https://example.org/talk/canvas/1#xywh=100,50,350,40");
+    assert!(canvases[1].get("annotations").is_none());
+
+    std::fs::remove_dir_all(dir).ok();
+}
@@ -0,0 +1,62 @@
+//! Checks the JSON Lines export.
+
+use image::{ImageBuffer, Rgb};
+use videodocparser::document_builder::{build_jsonl, JsonlOptions, Page};
+use videodocparser::ocr::{OcrFrameResult, OcrWord};
+
+#[test]
+fn jsonl_has_one_record_per_page() {
+    let image = ImageBuffer::from_pixel(320, 180, Rgb([255u8, 255, 255]));
+    let ocr = OcrFrameResult {
+        frame_index: 0,
+        words: vec![
+            OcrWord { text: "Ownership".to_string(), bbox: (10, 10, 90, 30), confidence: 90.0 },
+            OcrWord { text: "rules".to_string(), bbox: (100, 10, 150, 30), confidence: 85.0 },
+            OcrWord { text: "Borrowing".to_string(), bbox: (10, 60, 90, 80), confidence: 80.0 },
+        ],
+        blocks: Vec::new(),
+        strategy: None,
+        timestamp: None,
+    };
+    let pages = vec![
+        Page {
+            image: &image,
+            ocr: Some(&ocr),
+            title: Some("Ownership rules".to_string()),
+            timestamp: Some(12.5),
+            end: Some(40.0),
+        },
+        Page { image: &image, ocr: None, title: None, timestamp: None, end: None },
+    ];
+    let dir = std::env::temp_dir().join(format!("vdp-jsonl-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    build_jsonl(&pages, &JsonlOptions::default(), &dir.join("document.jsonl")).unwrap();
+
+    let jsonl = std::fs::read_to_string(dir.join("document.jsonl")).unwrap();
+    let records: Vec<serde_json::Value> =
+        jsonl.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+    assert_eq!(records.len(), 2);
+    assert_eq!(
+        records[0],
+        serde_json::json!({
+            "timestamp": 12.5,
+            "end": 40.0,
+            "text": "Ownership rules\nBorrowing",
+            "image_path": "images/page_001.png",
+            "confidence": 85.0,
+        })
+    );
+    assert_eq!(
+        records[1],
+        serde_json::json!({
+            "timestamp": null,
+            "end": null,
+            "text": "",
+            "image_path": "images/page_002.png",
+            "confidence": null,
+        })
+    );
+    assert!(dir.join("images/page_002.png").exists());
+
+    std::fs::remove_dir_all(dir).ok();
+}
@@ -0,0 +1,81 @@
+//! Checks the back-of-document keyword index.
+
+use image::{ImageBuffer, Rgb};
+use lopdf::Document;
+use videodocparser::document_builder::{build_markdown, build_pdf, ConfidencePolicy, MarkdownOptions, Page, PdfOptions};
+use videodocparser::keywords::{build_keyword_index, KeywordEntry};
+use videodocparser::ocr::{OcrFrameResult, OcrWord};
+
+fn ocr(frame_index: usize, text: &str) -> OcrFrameResult {
+    let words = text
+        .split_whitespace()
+        .enumerate()
+        .map(|(i, word)| OcrWord {
+            text: word.to_string(),
+            bbox: (20 + 70 * i as i32, 20, 80 + 70 * i as i32, 40),
+            confidence: 90.0,
+        })
+        .collect();
+    OcrFrameResult { frame_index, words, blocks: Vec::new(), strategy: None, timestamp: None }
+}
+
+#[test]
+fn index_lists_significant_terms_with_their_pages() {
+    let image = ImageBuffer::from_pixel(640, 360, Rgb([255u8, 255, 255]));
+    let results = [
+        ocr(0, "Course Overview: Rust ownership"),
+        ocr(1, "Course Borrowing with lifetimes"),
+        ocr(2, "Course Lifetimes (in depth) 2024"),
+        ocr(3, "Course Traits and generics"),
+        ocr(4, "Course Summary: Ownership"),
+    ];
+    let pages: Vec<Page> = results
+        .iter()
+        .map(|result| Page { image: &image, ocr: Some(result), title: None, timestamp: None, end: None })
+        .collect();
+
+    // "Course" is on every page, "with" is a stopword, "2024" a number, "Rust" appears once.
+    // Spellings tie for "ownership", so the lowercase one is shown.
+    let entries = build_keyword_index(&pages);
+    assert_eq!(
+        entries,
+        vec![
+            KeywordEntry { term: "lifetimes".to_string(), pages: vec![1, 2] },
+            KeywordEntry { term: "ownership".to_string(), pages: vec![0, 4] },
+        ]
+    );
+
+    let dir = std::env::temp_dir().join(format!("vdp-keyword-index-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let options = MarkdownOptions {
+        heading_level: 2,
+        document_title: "Rust".to_string(),
+        video_url: None,
+        speech: Vec::new(),
+        keyword_index: true,
+        tags: Vec::new(),
+        url_links: false,
+        figures: false,
+        formulas: false,
+        new_content_only: false,
+        translations: Vec::new(),
+        confidence: ConfidencePolicy::default(),
+    };
+    let md_path = dir.join("document.md");
+    build_markdown(&pages, &options, &md_path).unwrap();
+    let markdown = std::fs::read_to_string(&md_path).unwrap();
+    assert!(markdown.contains("<a id=\"page-2\"></a>\n\n## Course Borrowing"));
+    assert!(markdown.ends_with("## Index\n\n- **lifetimes**: [2](#page-2), [3](#page-3)\n- **ownership**: [1](#page-1), [5](#page-5)\n\n"));
+
+    let options = PdfOptions { keyword_index: true, table_of_contents: true, ..Default::default() };
+    let pdf_path = dir.join("document.pdf");
+    build_pdf(&pages, &options, &pdf_path).unwrap();
+    let document = Document::load(&pdf_path).unwrap();
+    assert_eq!(document.get_pages().len(), 1 + 5 + 1);
+    let index = document.extract_text(&[7]).unwrap();
+    assert!(index.contains("lifetimes") && index.contains("3, 4") && index.contains("2, 6"), "{index:?}");
+    let index_page = document.get_dictionary(document.get_pages()[&7]).unwrap();
+    assert_eq!(index_page.get(b"Annots").unwrap().as_array().unwrap().len(), 4);
+
+    std::fs::remove_dir_all(dir).ok();
+}
@@ -0,0 +1,35 @@
+use image::{ImageBuffer, Rgb};
+use videodocparser::document_builder::{build_latex, LatexOptions, Page};
+use videodocparser::ocr::{OcrFrameResult, OcrWord};
+
+#[test]
+fn latex_has_a_section_per_page_with_escaped_text() {
+    let image = ImageBuffer::from_pixel(16, 16, Rgb([255u8, 255, 255]));
+    let ocr = OcrFrameResult {
+        frame_index: 0,
+        words: vec![OcrWord { text: "50%_of_R&D".to_string(), bbox: (0, 0, 10, 10), confidence: 90.0 }],
+        blocks: Vec::new(),
+        strategy: None,
+        timestamp: None,
+    };
+    let title = Some("Q#3".to_string());
+    let pages = vec![Page { image: &image, ocr: Some(&ocr), title, timestamp: Some(61.0), end: None }];
+    let options = LatexOptions {
+        document_title: "Talk".to_string(),
+        author: None,
+        video_url: Some("https://example.com/v#x".to_string()),
+    };
+
+    let dir = std::env::temp_dir().join(format!("vdp-latex-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("document.tex");
+    build_latex(&pages, &options, &path).unwrap();
+
+    let latex = std::fs::read_to_string(&path).unwrap();
+    assert!(latex.contains("\\section{Q\\#3 (\\href{https://example.com/v?t=61\\#x}{00:01:01})}"), "{latex}");
+    assert!(latex.contains("\\includegraphics[width=\\linewidth]{images/page_001.png}"));
+    assert!(latex.contains("\n50\\%\\_of\\_R\\&D\n"));
+    assert!(latex.trim_end().ends_with("\\end{document}"));
+
+    std::fs::remove_dir_all(dir).ok();
+}
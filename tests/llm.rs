@@ -0,0 +1,12 @@
+#![cfg(feature = "llm")]
+//! Checks reading replies of the OpenAI-compatible backend.
+
+use serde_json::json;
+use videodocparser::llm::reply_text;
+
+#[test]
+fn reply_text_is_the_first_choice() {
+    let reply = json!({ "choices": [{ "message": { "role": "assistant", "content": " Cleaned text\n" } }] });
+    assert_eq!(reply_text(&reply).unwrap(), "Cleaned text");
+    assert!(reply_text(&json!({ "error": { "message": "model not found" } })).is_err());
+}
@@ -0,0 +1,56 @@
+//! Checks that content on screen for too short a time never becomes a page.
+
+use image::RgbImage;
+use videodocparser::frame_analyzer::FrameAnalyzer;
+
+mod common;
+use common::slide;
+
+/// Slide 0 with a popup over it at frames 6 and 7, slide 4 from frame 12, and
+/// a glimpse of slide 2 for the last two frames.
+fn recording() -> Vec<RgbImage> {
+    [(0, 6), (2, 2), (0, 4), (4, 6), (2, 2)]
+        .into_iter()
+        .flat_map(|(n, frames)| vec![slide(n); frames])
+        .collect()
+}
+
+fn analyze(min_frames: Option<usize>) -> FrameAnalyzer {
+    let dir = std::env::temp_dir().join(format!("vdp-min-duration-{}", std::process::id()));
+    common::analyze(&dir, recording(), |analyzer| {
+        if let Some(frames) = min_frames {
+            analyzer.set_min_frames(frames);
+        }
+    })
+}
+
+#[test]
+fn every_change_is_a_page_without_a_minimum() {
+    let result = analyze(None).finish().unwrap();
+    assert_eq!(result.kept_indices, vec![0, 6, 8, 12, 18]);
+}
+
+#[test]
+fn short_lived_content_is_passed_over() {
+    let analyzer = analyze(Some(4));
+    // The page the popup interrupted goes on after it.
+    assert_eq!(analyzer.kept_indices(), &[0, 12]);
+    assert_eq!(analyzer.source_indices(), &[0, 12]);
+
+    // Content still short of the minimum when the video ends is dropped too.
+    let result = analyzer.finish().unwrap();
+    assert_eq!(result.kept_indices, vec![0, 12]);
+    assert_eq!(result.kept_frames, vec![slide(0), slide(4)]);
+    assert_eq!(result.removed_indices.len(), recording().len() - 2);
+}
+
+#[test]
+fn cached_hash_series_replay_the_same_pages() {
+    let dir = std::env::temp_dir().join(format!("vdp-min-duration-replay-{}", std::process::id()));
+    let series = analyze(Some(4)).finish().unwrap().hash_series;
+    let mut replay = FrameAnalyzer::new(0.9, &dir).unwrap();
+    replay.set_min_frames(4);
+    replay.replay(series, None).unwrap();
+    assert_eq!(replay.kept_indices(), &[0, 12]);
+    let _ = std::fs::remove_dir_all(&dir);
+}
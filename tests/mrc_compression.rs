@@ -0,0 +1,81 @@
+//! Checks mixed raster content compression of PDF page images.
+
+use image::{ImageBuffer, Rgb};
+use lopdf::{Document, Object};
+use videodocparser::document_builder::{build_pdf, ImageCompression, Page, PdfOptions};
+use videodocparser::mrc::{encode_g4, separate, BACKGROUND_SCALE};
+
+#[test]
+fn g4_codes_vertical_and_horizontal_modes() {
+    // A white line: one V0 code, then the end-of-block.
+    assert_eq!(encode_g4(&[false; 8], 8, 1), vec![0x80, 0x08, 0x00, 0x80]);
+    // Two white, four black, two white pixels: a horizontal code with runs of 2 and 4, then V0.
+    let line = [false, false, true, true, true, true, false, false];
+    assert_eq!(encode_g4(&line, 8, 1), vec![0x2e, 0xe0, 0x02, 0x00, 0x20]);
+    // The same line repeated below is three V0 codes.
+    let mut two_lines = line.to_vec();
+    two_lines.extend_from_slice(&line);
+    assert_eq!(encode_g4(&two_lines, 8, 2)[1] >> 5, 0b111);
+}
+
+#[test]
+fn light_text_on_dark_slides_becomes_the_mask() {
+    let mut frame = ImageBuffer::from_pixel(90, 60, Rgb([20u8, 30, 60]));
+    for x in 10..50 {
+        for y in 20..26 {
+            frame.put_pixel(x, y, Rgb([250, 250, 240]));
+        }
+    }
+    let layers = separate(&frame);
+    assert_eq!(layers.foreground, [250, 250, 240]);
+    assert_eq!((layers.width, layers.height), (90, 60));
+    assert_eq!(layers.background.dimensions(), (90 / BACKGROUND_SCALE, 60 / BACKGROUND_SCALE));
+    // The text is painted over with the slide's own colour.
+    assert_eq!(layers.background.get_pixel(10, 7), &Rgb([20, 30, 60]));
+}
+
+#[test]
+fn mrc_pages_paint_a_ccitt_mask_over_a_small_background() {
+    let mut image = ImageBuffer::from_pixel(600, 400, Rgb([255u8, 255, 250]));
+    for line in 0..8 {
+        for x in (40..560).filter(|x| x % 12 < 8) {
+            for y in 40 + line * 40..56 + line * 40 {
+                image.put_pixel(x, y, Rgb([0, 0, 128]));
+            }
+        }
+    }
+    let pages = vec![Page { image: &image, ocr: None, title: None, timestamp: None, end: None }];
+    let options = PdfOptions { image_compression: ImageCompression::Mrc { quality: 85 }, ..Default::default() };
+
+    let dir = std::env::temp_dir().join(format!("vdp-pdf-mrc-{}", std::process::id()));
+    let path = dir.join("document.pdf");
+    build_pdf(&pages, &options, &path).unwrap();
+
+    let document = Document::load(&path).unwrap();
+    let images: Vec<_> = document
+        .objects
+        .values()
+        .filter_map(|object| object.as_stream().ok())
+        .filter(|stream| stream.dict.get(b"Subtype").and_then(|s| s.as_name()).ok() == Some(b"Image"))
+        .collect();
+    assert_eq!(images.len(), 2);
+    let width = |stream: &lopdf::Stream| stream.dict.get(b"Width").unwrap().as_i64().unwrap();
+
+    let background = images.iter().find(|stream| stream.dict.get(b"ImageMask").is_err()).unwrap();
+    assert_eq!(background.dict.get(b"Filter").unwrap().as_name().unwrap(), b"DCTDecode");
+    assert_eq!(width(background), 200);
+
+    let mask = images.iter().find(|stream| stream.dict.get(b"ImageMask").is_ok()).unwrap();
+    assert_eq!(mask.dict.get(b"ImageMask").unwrap(), &Object::Boolean(true));
+    assert_eq!(mask.dict.get(b"Filter").unwrap().as_name().unwrap(), b"CCITTFaxDecode");
+    assert_eq!(width(mask), 600);
+    let parms = mask.dict.get(b"DecodeParms").unwrap().as_dict().unwrap();
+    assert_eq!(parms.get(b"K").unwrap().as_i64().unwrap(), -1);
+    assert_eq!(parms.get(b"Rows").unwrap().as_i64().unwrap(), 400);
+
+    let page_id = document.get_pages()[&1];
+    let content = String::from_utf8_lossy(&document.get_page_content(page_id).unwrap()).into_owned();
+    assert!(content.contains("0 0 0.5019608 rg"), "{}", content);
+
+    std::fs::remove_dir_all(dir).ok();
+}
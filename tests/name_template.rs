@@ -0,0 +1,41 @@
+//! Checks the rendering of `--name-template`.
+
+use videodocparser::naming::{ensure_unique, NameFields, NameTemplate};
+
+fn fields(index: usize) -> NameFields {
+    NameFields {
+        stem: "lecture 3".to_string(),
+        title: "Intro: Rust/Go?".to_string(),
+        date: "2024-05-17".to_string(),
+        index,
+    }
+}
+
+#[test]
+fn templates_render_sanitized_relative_paths() {
+    let template = NameTemplate::parse("{stem}/{date}/slide_{index:04}").unwrap();
+    assert_eq!(template.render(&fields(7)), "lecture 3/2024-05-17/slide_0007");
+
+    // Values never add folders, and components are made safe as file names.
+    let template = NameTemplate::parse("notes//{title}.").unwrap();
+    assert_eq!(template.render(&fields(1)), "notes/Intro_ Rust_Go_");
+
+    let template = NameTemplate::parse("{index}").unwrap();
+    assert_eq!(template.render(&fields(12)), "12");
+    let template = NameTemplate::parse("./{title}").unwrap();
+    assert_eq!(template.render(&NameFields::default()), "document");
+}
+
+#[test]
+fn invalid_templates_are_rejected() {
+    for template in ["", "/abs/{stem}", "../{stem}", "{stem}/../x", "{page}", "{stem:3}", "{index:x}", "{stem", "stem}"] {
+        assert!(NameTemplate::parse(template).is_err(), "{:?} was accepted", template);
+    }
+}
+
+#[test]
+fn colliding_names_are_reported() {
+    assert!(ensure_unique(&["a.png".to_string(), "b.png".to_string()]).is_ok());
+    let error = ensure_unique(&["a.png".to_string(), "a.png".to_string()]).unwrap_err();
+    assert!(error.to_string().contains("{index}"));
+}
@@ -0,0 +1,59 @@
+//! Checks the Obsidian vault export.
+
+use image::{ImageBuffer, Rgb};
+use videodocparser::document_builder::Page;
+use videodocparser::obsidian::{attachment_path, build_vault, note_names, VaultOptions};
+use videodocparser::ocr::{OcrFrameResult, OcrWord};
+
+#[test]
+fn vault_has_linked_notes_with_front_matter() {
+    let image = ImageBuffer::from_pixel(320, 180, Rgb([255u8, 255, 255]));
+    let ocr = OcrFrameResult {
+        frame_index: 0,
+        words: vec![OcrWord { text: "Agenda".to_string(), bbox: (10, 10, 90, 30), confidence: 80.0 }],
+        blocks: Vec::new(),
+        strategy: None,
+        timestamp: None,
+    };
+    let pages = vec![
+        Page {
+            image: &image,
+            ocr: Some(&ocr),
+            title: Some("Intro: \"Why\" [draft]".to_string()),
+            timestamp: Some(0.0),
+            end: None,
+        },
+        Page { image: &image, ocr: None, title: None, timestamp: Some(95.5), end: None },
+        Page { image: &image, ocr: None, title: Some("Q&A".to_string()), timestamp: Some(300.0), end: None },
+    ];
+    let names = note_names(&pages);
+    assert_eq!(names, vec!["001 Intro_ _Why_ _draft_", "002 Page 2", "003 Q&A"]);
+
+    let options = VaultOptions {
+        document_title: "Talk".to_string(),
+        source: "talk.mp4".to_string(),
+        video_url: Some("https://example.com/talk".to_string()),
+        tags: Vec::new(),
+    };
+    let dir = std::env::temp_dir().join(format!("vdp-obsidian-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let files = build_vault(&pages, &options, &dir).unwrap();
+    assert_eq!(files[1], "002 Page 2.md");
+    assert!(dir.join(attachment_path(2)).exists());
+
+    let first = std::fs::read_to_string(dir.join(&files[0])).unwrap();
+    assert!(first.starts_with("---\ntitle: \"Intro: \\\"Why\\\" [draft]\"\npage: 1\ntimestamp: 0.000\n"));
+    assert!(first.contains("confidence: 80.0\ntags: [slide]\n---\n"));
+    assert!(first.contains("![[page_001.png]]\n\nAgenda\n"));
+    assert!(first.ends_with("---\nNext: [[002 Page 2]] · Up: [[Talk]]\n"));
+
+    let second = std::fs::read_to_string(dir.join(&files[1])).unwrap();
+    assert!(second.contains("time: \"00:01:35\"\nsource: \"talk.mp4\"\nvideo_url: \"https://example.com/talk?t=95\"\n"));
+    assert!(!second.contains("confidence:"));
+    assert!(second.ends_with("Previous: [[001 Intro_ _Why_ _draft_]] · Next: [[003 Q&A]] · Up: [[Talk]]\n"));
+
+    let overview = std::fs::read_to_string(dir.join("Talk.md")).unwrap();
+    assert!(overview.contains("pages: 3\n---\n\n# Talk\n\n- [[001 Intro_ _Why_ _draft_]] (00:00:00)\n"));
+
+    std::fs::remove_dir_all(dir).ok();
+}
@@ -0,0 +1,48 @@
+//! Checks the per-page OCR text files and confidence overlays.
+
+use image::{ImageBuffer, Rgb};
+use videodocparser::document_builder::Page;
+use videodocparser::ocr::{OcrFrameResult, OcrWord};
+use videodocparser::ocr_debug::{confidence_colour, overlay, write_debug, DEBUG_DIR};
+
+#[test]
+fn colours_run_from_red_to_green() {
+    assert_eq!(confidence_colour(0.0), Rgb([255, 0, 0]));
+    assert_eq!(confidence_colour(50.0), Rgb([255, 255, 0]));
+    assert_eq!(confidence_colour(100.0), Rgb([0, 255, 0]));
+}
+
+#[test]
+fn boxes_are_drawn_and_text_written_per_page() {
+    let image = ImageBuffer::from_pixel(200, 100, Rgb([255u8, 255, 255]));
+    let ocr = OcrFrameResult {
+        frame_index: 0,
+        words: vec![
+            OcrWord { text: "Ownership".to_string(), bbox: (10, 10, 90, 30), confidence: 100.0 },
+            OcrWord { text: "rul3s".to_string(), bbox: (100, 10, 150, 30), confidence: 0.0 },
+        ],
+        blocks: Vec::new(),
+        strategy: None,
+        timestamp: None,
+    };
+    let pages = vec![
+        Page { image: &image, ocr: Some(&ocr), title: None, timestamp: None, end: None },
+        Page { image: &image, ocr: None, title: None, timestamp: None, end: None },
+    ];
+
+    let drawn = overlay(&pages[0]);
+    assert_eq!(*drawn.get_pixel(10, 20), Rgb([0, 255, 0]));
+    assert_eq!(*drawn.get_pixel(149, 20), Rgb([255, 0, 0]));
+    // Inside, the frame shows through the tint; outside the boxes it is untouched.
+    assert_eq!(*drawn.get_pixel(120, 20), Rgb([255, 191, 191]));
+    assert_eq!(*drawn.get_pixel(50, 60), Rgb([255, 255, 255]));
+
+    let dir = std::env::temp_dir().join(format!("vdp-ocr-debug-{}", std::process::id()));
+    write_debug(&pages, &dir).unwrap();
+    let debug_dir = dir.join(DEBUG_DIR);
+    assert_eq!(std::fs::read_to_string(debug_dir.join("page_001.txt")).unwrap(), "Ownership rul3s");
+    assert_eq!(std::fs::read_to_string(debug_dir.join("page_002.txt")).unwrap(), "");
+    assert_eq!(image::open(debug_dir.join("page_002_overlay.png")).unwrap().to_rgb8(), image);
+    assert!(debug_dir.join("page_001_overlay.png").exists());
+    std::fs::remove_dir_all(dir).ok();
+}
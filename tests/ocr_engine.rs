@@ -0,0 +1,154 @@
+//! Checks recognizing frames through the OCR engine trait with a stand-in engine.
+
+use anyhow::{Result, bail};
+use image::{DynamicImage, GenericImageView, ImageBuffer, Rgb};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use videodocparser::crop::CropRect;
+use videodocparser::mask::IgnoreMask;
+use videodocparser::ocr::{OcrEngine, OcrWord, Recognition, recognize_frames, recognize_on_threads};
+
+/// Reads the box around the dark pixels of an image as one word, named after
+/// the colour type it was given, and fails on images without dark pixels.
+struct DarkBox;
+
+impl OcrEngine for DarkBox {
+    fn name(&self) -> &'static str {
+        "dark-box"
+    }
+
+    fn recognize(&self, image: &DynamicImage) -> Result<Recognition> {
+        let dark: Vec<(u32, u32)> =
+            image.pixels().filter(|(_, _, pixel)| pixel.0[0] < 128).map(|(x, y, _)| (x, y)).collect();
+        if dark.is_empty() {
+            bail!("nothing to read");
+        }
+        let x1 = dark.iter().map(|p| p.0).min().unwrap() as i32;
+        let y1 = dark.iter().map(|p| p.1).min().unwrap() as i32;
+        let x2 = dark.iter().map(|p| p.0).max().unwrap() as i32 + 1;
+        let y2 = dark.iter().map(|p| p.1).max().unwrap() as i32 + 1;
+        let text = format!("{:?}", image.color());
+        let word = OcrWord { text, bbox: (x1, y1, x2, y2), confidence: 90.0 };
+        Ok(Recognition { words: vec![word], ..Recognition::default() })
+    }
+}
+
+/// A white frame with dark squares at the given top left corners.
+fn frame(squares: &[(u32, u32)]) -> ImageBuffer<Rgb<u8>, Vec<u8>> {
+    ImageBuffer::from_fn(120, 80, |x, y| {
+        if squares.iter().any(|&(sx, sy)| (sx..sx + 10).contains(&x) && (sy..sy + 10).contains(&y)) {
+            Rgb([0, 0, 0])
+        } else {
+            Rgb([255, 255, 255])
+        }
+    })
+}
+
+#[test]
+fn frames_are_recognized_by_the_engine() {
+    let frames = [frame(&[(10, 10)]), frame(&[]), frame(&[(10, 10), (100, 60)])];
+    let refs: Vec<_> = frames.iter().collect();
+    let mut results = recognize_frames(&DarkBox, &refs, &[], &[], None);
+    results.sort_by_key(|result| result.frame_index);
+
+    // The blank frame fails and is skipped.
+    assert_eq!(results.iter().map(|r| r.frame_index).collect::<Vec<_>>(), vec![0, 2]);
+    assert_eq!(results[0].words[0].text, "Rgb8");
+    assert_eq!(results[0].words[0].bbox, (10, 10, 20, 20));
+    assert_eq!(results[1].words[0].bbox, (10, 10, 110, 70));
+}
+
+#[test]
+fn masks_and_preprocessing_come_before_the_engine() {
+    let frames = [frame(&[(10, 10), (100, 60)]), frame(&[(10, 10), (100, 60)])];
+    let refs: Vec<_> = frames.iter().collect();
+    let mask = IgnoreMask::new(vec![CropRect::parse("90,50,30,30").unwrap()], None);
+    let mut results = recognize_frames(&DarkBox, &refs, &[Some(&mask), None], &["binarize".to_string()], None);
+    results.sort_by_key(|result| result.frame_index);
+
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].words[0].text, "L8");
+    assert_eq!(results[0].words[0].bbox, (10, 10, 20, 20));
+    assert_eq!(results[1].words[0].bbox, (10, 10, 110, 70));
+}
+
+/// Reads the dark box of every image of a batch as "batch <n>", and fails
+/// batches holding a blank image.
+struct Batched {
+    calls: std::sync::atomic::AtomicUsize,
+}
+
+impl OcrEngine for Batched {
+    fn name(&self) -> &'static str {
+        "batched"
+    }
+
+    fn recognize(&self, image: &DynamicImage) -> Result<Recognition> {
+        DarkBox.recognize(image)
+    }
+
+    fn batch_size(&self) -> usize {
+        2
+    }
+
+    fn recognize_batch(&self, images: &[DynamicImage]) -> Result<Vec<Recognition>> {
+        let call = self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        images
+            .iter()
+            .map(|image| {
+                let mut recognition = self.recognize(image)?;
+                recognition.words[0].text = format!("batch {call}");
+                Ok(recognition)
+            })
+            .collect()
+    }
+}
+
+#[test]
+fn frames_are_sent_in_batches() {
+    let frames = [frame(&[(0, 0)]), frame(&[(10, 10)]), frame(&[(20, 20)]), frame(&[]), frame(&[(30, 30)])];
+    let refs: Vec<_> = frames.iter().collect();
+    let engine = Batched { calls: std::sync::atomic::AtomicUsize::new(0) };
+    let mut results = recognize_frames(&engine, &refs, &[], &[], None);
+    results.sort_by_key(|result| result.frame_index);
+
+    assert_eq!(engine.calls.load(std::sync::atomic::Ordering::SeqCst), 3);
+    // Frames 2 and 3 share a batch, which fails on the blank frame 3.
+    assert_eq!(results.iter().map(|r| r.frame_index).collect::<Vec<_>>(), vec![0, 1, 4]);
+    assert_eq!(results[0].words[0].text, results[1].words[0].text);
+    assert_ne!(results[0].words[0].text, results[2].words[0].text);
+    assert_eq!(results[2].words[0].bbox, (30, 30, 40, 40));
+}
+
+/// Counts how many images it is recognizing at once.
+#[derive(Default)]
+struct Counting {
+    busy: AtomicUsize,
+    most: AtomicUsize,
+}
+
+impl OcrEngine for Counting {
+    fn name(&self) -> &'static str {
+        "counting"
+    }
+
+    fn recognize(&self, _image: &DynamicImage) -> Result<Recognition> {
+        let busy = self.busy.fetch_add(1, Ordering::SeqCst) + 1;
+        self.most.fetch_max(busy, Ordering::SeqCst);
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        self.busy.fetch_sub(1, Ordering::SeqCst);
+        Ok(Recognition::default())
+    }
+}
+
+#[test]
+fn ocr_threads_bound_the_frames_recognized_at_once() {
+    let frames: Vec<_> = (0..8).map(|_| frame(&[(10, 10)])).collect();
+    let refs: Vec<&ImageBuffer<Rgb<u8>, Vec<u8>>> = frames.iter().collect();
+    let masks = vec![None; refs.len()];
+    for threads in [1, 3] {
+        let engine = Counting::default();
+        let results = recognize_on_threads(&engine, &refs, &masks, &[], None, Some(threads)).unwrap();
+        assert_eq!(results.len(), 8);
+        assert!(engine.most.load(Ordering::SeqCst) <= threads);
+    }
+}
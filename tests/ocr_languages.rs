@@ -0,0 +1,22 @@
+//! Checks combining several OCR languages in one run.
+
+use videodocparser::ocr::{check_language_packs, language_codes, language_tag};
+
+#[test]
+fn combined_languages_need_every_pack() {
+    assert_eq!(language_codes("eng+deu+equ").unwrap(), ["eng", "deu", "equ"]);
+    assert_eq!(language_codes("eng").unwrap(), ["eng"]);
+    assert!(language_codes("eng++deu").is_err());
+    assert!(language_codes("").is_err());
+    assert_eq!(language_tag("deu+eng"), "de");
+
+    let dir = std::env::temp_dir().join(format!("vdp-ocr-languages-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("eng.traineddata"), b"").unwrap();
+    std::fs::write(dir.join("deu.traineddata"), b"").unwrap();
+
+    check_language_packs("eng+deu", &dir).unwrap();
+    let error = check_language_packs("eng+deu+equ+fra", &dir).unwrap_err().to_string();
+    assert!(error.contains("equ, fra"), "{error}");
+    std::fs::remove_dir_all(dir).ok();
+}
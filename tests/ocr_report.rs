@@ -0,0 +1,25 @@
+use videodocparser::ocr::{words_csv, OcrFrameResult, OcrWord};
+
+#[test]
+fn csv_report_has_one_row_per_word() {
+    let mut results = vec![OcrFrameResult {
+        frame_index: 1,
+        words: vec![
+            OcrWord { text: "Total,".to_string(), bbox: (10, 20, 60, 40), confidence: 91.5 },
+            OcrWord { text: "\"42\"".to_string(), bbox: (70, 20, 90, 40), confidence: 88.0 },
+        ],
+        blocks: Vec::new(),
+        strategy: None,
+        timestamp: Some(12.5),
+    }];
+
+    let csv = words_csv(&results);
+    assert_eq!(
+        csv,
+        "frame_index,timestamp,text,x1,y1,x2,y2,confidence\n\
+         1,12.500,\"Total,\",10,20,60,40,91.50\n\
+         1,12.500,\"\"\"42\"\"\",70,20,90,40,88.00\n"
+    );
+    results[0].timestamp = None;
+    assert!(words_csv(&results).contains("\n1,,\"Total,\""));
+}
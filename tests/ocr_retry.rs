@@ -0,0 +1,85 @@
+//! Checks retrying frames read with low confidence with other settings.
+
+use anyhow::Result;
+use image::{DynamicImage, GenericImageView, ImageBuffer, Rgb};
+use videodocparser::ocr::{DEFAULT_STRATEGY, OcrEngine, OcrWord, Recognition, recognize_frames, retry};
+
+/// Reads one word over the whole image, confidently only on dark images (as
+/// an inverted white slide is) when `dark_sure`, or on images at least
+/// `sharp_width` wide. Its alternative `many` reads three middling words, and
+/// `one` a single fairly sure word.
+struct Picky {
+    dark_sure: bool,
+    sharp_width: u32,
+}
+
+impl OcrEngine for Picky {
+    fn name(&self) -> &'static str {
+        "picky"
+    }
+
+    fn recognize(&self, image: &DynamicImage) -> Result<Recognition> {
+        let dark = image.get_pixel(0, 0).0[0] < 128;
+        let confidence = if (dark && self.dark_sure) || image.width() >= self.sharp_width { 90.0 } else { 30.0 };
+        let (width, height) = (image.width() as i32, image.height() as i32);
+        let word = OcrWord { text: "slide".to_string(), bbox: (0, 0, width, height), confidence };
+        Ok(Recognition { words: vec![word], ..Recognition::default() })
+    }
+
+    fn alternatives(&self) -> Vec<String> {
+        vec!["many".to_string(), "one".to_string()]
+    }
+
+    fn recognize_alternative(&self, _image: &DynamicImage, alternative: &str) -> Result<Recognition> {
+        let word = |confidence| OcrWord { text: "word".to_string(), bbox: (0, 0, 4, 4), confidence };
+        let words = match alternative {
+            "many" => vec![word(50.0), word(50.0), word(50.0)],
+            _ => vec![word(70.0)],
+        };
+        Ok(Recognition { words, ..Recognition::default() })
+    }
+}
+
+fn white(width: u32, height: u32) -> DynamicImage {
+    DynamicImage::ImageRgb8(ImageBuffer::from_pixel(width, height, Rgb([250, 250, 250])))
+}
+
+#[test]
+fn the_most_confident_reading_wins() {
+    let engine = Picky { dark_sure: true, sharp_width: u32::MAX };
+    let first = engine.recognize(&white(40, 20)).unwrap();
+    let (best, strategy) = retry(&engine, &white(40, 20), first);
+    assert_eq!(strategy, "invert");
+    assert_eq!(best.mean_confidence(), Some(90.0));
+
+    // Scaled readings are given in the pixels of the image.
+    let engine = Picky { dark_sure: false, sharp_width: 80 };
+    let first = engine.recognize(&white(40, 20)).unwrap();
+    let (best, strategy) = retry(&engine, &white(40, 20), first);
+    assert_eq!(strategy, "scale-2x");
+    assert_eq!(best.words[0].bbox, (0, 0, 40, 20));
+
+    // Readings with fewer than half the words of the first do not count,
+    // however sure they are.
+    let many = engine.recognize_alternative(&white(40, 20), "many").unwrap();
+    let (best, strategy) = retry(&engine, &white(40, 20), many);
+    assert_eq!(strategy, DEFAULT_STRATEGY);
+    assert_eq!(best.words.len(), 3);
+}
+
+#[test]
+fn only_low_confidence_frames_are_retried() {
+    let slide = ImageBuffer::from_pixel(40, 20, Rgb([250u8, 250, 250]));
+    let dark = ImageBuffer::from_pixel(40, 20, Rgb([10u8, 10, 10]));
+    let refs = vec![&slide, &dark];
+    let engine = Picky { dark_sure: true, sharp_width: u32::MAX };
+
+    let mut results = recognize_frames(&engine, &refs, &[], &[], Some(60.0));
+    results.sort_by_key(|result| result.frame_index);
+    assert_eq!(results[0].strategy.as_deref(), Some("invert"));
+    assert_eq!(results[0].words[0].confidence, 90.0);
+    assert_eq!(results[1].strategy, None);
+
+    let unretried = recognize_frames(&engine, &refs, &[], &[], None);
+    assert!(unretried.iter().all(|result| result.strategy.is_none()));
+}
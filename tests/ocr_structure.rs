@@ -0,0 +1,101 @@
+//! Checks nesting the engine's lines, paragraphs, and blocks, and laying out pages by them.
+
+use videodocparser::layout::{TextBlock, engine_paragraphs, group_blocks, page_text};
+use videodocparser::ocr::{OcrBlock, OcrFrameResult, OcrParagraph, OcrTextLine, OcrWord, Recognition};
+
+fn element(text: &str, bbox: (i32, i32, i32, i32)) -> OcrWord {
+    OcrWord { text: text.to_string(), bbox, confidence: 90.0 }
+}
+
+/// Two columns side by side: the left one with two paragraphs, the right one
+/// with a single line level with the first line on the left.
+fn two_columns() -> Recognition {
+    Recognition {
+        words: vec![
+            element("Ownership", (10, 10, 90, 30)),
+            element("rules", (100, 10, 150, 30)),
+            element("Borrowing", (10, 40, 90, 60)),
+            element("Lifetimes", (10, 90, 90, 110)),
+            element("Sidebar", (300, 10, 380, 30)),
+        ],
+        lines: vec![
+            element("Ownership rules", (10, 10, 150, 30)),
+            element("Borrowing", (10, 40, 90, 60)),
+            element("Lifetimes", (10, 90, 90, 110)),
+            element("Sidebar", (300, 10, 380, 30)),
+        ],
+        paragraphs: vec![
+            element("Ownership rules Borrowing", (10, 10, 150, 60)),
+            element("Lifetimes", (10, 90, 90, 110)),
+            element("Sidebar", (300, 10, 380, 30)),
+        ],
+        blocks: vec![
+            element("Ownership rules Borrowing Lifetimes", (10, 10, 150, 110)),
+            element("Sidebar", (300, 10, 380, 30)),
+        ],
+        vertical_lines: Vec::new(),
+    }
+}
+
+#[test]
+fn words_nest_into_lines_paragraphs_and_blocks() {
+    let line = |bbox, words: Vec<usize>| OcrTextLine { bbox, words, vertical: false };
+    assert_eq!(
+        two_columns().structure(),
+        vec![
+            OcrBlock {
+                bbox: (10, 10, 150, 110),
+                paragraphs: vec![
+                    OcrParagraph {
+                        bbox: (10, 10, 150, 60),
+                        lines: vec![line((10, 10, 150, 30), vec![0, 1]), line((10, 40, 90, 60), vec![2])],
+                    },
+                    OcrParagraph { bbox: (10, 90, 90, 110), lines: vec![line((10, 90, 90, 110), vec![3])] },
+                ],
+            },
+            OcrBlock {
+                bbox: (300, 10, 380, 30),
+                paragraphs: vec![OcrParagraph {
+                    bbox: (300, 10, 380, 30),
+                    lines: vec![line((300, 10, 380, 30), vec![4])],
+                }],
+            },
+        ]
+    );
+
+    // Missing levels take the elements of the level below; without lines there is no structure.
+    let lines_only = Recognition { paragraphs: Vec::new(), blocks: Vec::new(), ..two_columns() };
+    assert_eq!(lines_only.structure().len(), 4);
+    let words_only = Recognition { words: two_columns().words, ..Recognition::default() };
+    assert!(words_only.structure().is_empty());
+}
+
+#[test]
+fn pages_follow_the_engine_reading_order_and_paragraphs() {
+    let recognition = two_columns();
+    let blocks = recognition.structure();
+    let ocr = OcrFrameResult { frame_index: 0, words: recognition.words, blocks, strategy: None, timestamp: None };
+
+    // The right column comes after the whole left one, although it is level with its first line.
+    assert_eq!(page_text(&ocr), "Ownership rules\nBorrowing\nLifetimes\nSidebar");
+    assert_eq!(engine_paragraphs(&ocr).unwrap().len(), 3);
+    let paragraphs: Vec<Vec<String>> = group_blocks(&ocr)
+        .into_iter()
+        .map(|block| match block {
+            TextBlock::Prose(lines) => lines.into_iter().map(|line| line.text).collect(),
+            other => panic!("unexpected block {:?}", other),
+        })
+        .collect();
+    assert_eq!(paragraphs, vec![vec!["Ownership rules", "Borrowing"], vec!["Lifetimes"], vec!["Sidebar"]]);
+
+    // Without the engine's structure, lines are rebuilt from the boxes into one prose block.
+    let flat = OcrFrameResult {
+        frame_index: 0,
+        words: two_columns().words,
+        blocks: Vec::new(),
+        strategy: None,
+        timestamp: None,
+    };
+    assert_eq!(page_text(&flat), "Ownership rules Sidebar\nBorrowing\nLifetimes");
+    assert_eq!(group_blocks(&flat).len(), 1);
+}
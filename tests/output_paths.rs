@@ -0,0 +1,100 @@
+//! Checks the path helpers, and that document builders create their folders
+//! and report what they wrote.
+
+use image::{ImageBuffer, Rgb};
+use videodocparser::document_builder::{
+    build_html, build_markdown, build_pdf, ConfidencePolicy, HtmlOptions, MarkdownOptions, Page, PdfOptions,
+};
+use videodocparser::paths::{prepare_dir, sanitize_file_name, to_utf8};
+
+#[test]
+fn builders_create_parents_and_return_written_files() {
+    let image = ImageBuffer::from_pixel(64, 48, Rgb([255u8, 255, 255]));
+    let pages = vec![
+        Page { image: &image, ocr: None, title: None, timestamp: None, end: None },
+        Page { image: &image, ocr: None, title: None, timestamp: None, end: None },
+    ];
+    let dir = std::env::temp_dir().join(format!("vdp-output-paths-{}", std::process::id()));
+
+    let pdf_path = dir.join("talks/2024/deck.pdf");
+    let written = build_pdf(&pages, &PdfOptions::default(), &pdf_path).unwrap();
+    assert_eq!(written.document, pdf_path);
+    assert!(written.images.is_empty());
+    assert!(pdf_path.exists());
+
+    let md_path = dir.join("notes/deck.md");
+    let options = MarkdownOptions {
+        heading_level: 2,
+        document_title: "Deck".to_string(),
+        video_url: None,
+        speech: Vec::new(),
+        keyword_index: false,
+        tags: Vec::new(),
+        url_links: false,
+        figures: false,
+        formulas: false,
+        new_content_only: false,
+        translations: Vec::new(),
+        confidence: ConfidencePolicy::default(),
+    };
+    let written = build_markdown(&pages, &options, &md_path).unwrap();
+    assert_eq!(written.document, md_path);
+    assert_eq!(written.images, vec![dir.join("notes/images/page_001.png"), dir.join("notes/images/page_002.png")]);
+    assert!(written.images.iter().all(|image| image.exists()));
+
+    let html_options = HtmlOptions {
+        document_title: "Deck".to_string(),
+        video_url: None,
+        embed_images: true,
+        figures: false,
+        formulas: false,
+        speech: Vec::new(),
+    };
+    let written = build_html(&pages, &html_options, &dir.join("web/index.html")).unwrap();
+    assert!(written.document.exists());
+    assert!(written.images.is_empty());
+
+    std::fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn file_names_are_sanitized_for_every_platform() {
+    assert_eq!(sanitize_file_name("Q3: plans/ideas?"), "Q3_ plans_ideas_");
+    assert_eq!(sanitize_file_name("tab\there"), "tab_here");
+    assert_eq!(sanitize_file_name("slides. . "), "slides");
+    assert_eq!(sanitize_file_name("  "), "untitled");
+    assert_eq!(sanitize_file_name("..."), "untitled");
+    assert_eq!(sanitize_file_name("con"), "con_");
+    assert_eq!(sanitize_file_name("LPT1.txt"), "LPT1.txt_");
+    assert_eq!(sanitize_file_name("console"), "console");
+
+    // Long names are cut on a character boundary.
+    let long = sanitize_file_name(&"é".repeat(100));
+    assert!(long.len() <= 120);
+    assert_eq!(long, "é".repeat(60));
+}
+
+#[test]
+fn output_dirs_are_made_absolute_without_being_created() {
+    let relative = std::path::Path::new(&format!("vdp-prepare-dir-{}", std::process::id())).join("out");
+    let prepared = prepare_dir(&relative).unwrap();
+    assert!(prepared.is_absolute());
+    assert!(prepared.ends_with(&relative));
+    assert!(!prepared.exists());
+
+    let absolute = std::env::temp_dir().join("vdp-prepare-dir");
+    assert!(prepare_dir(&absolute).unwrap().ends_with("vdp-prepare-dir"));
+}
+
+#[test]
+fn paths_for_native_libraries_must_be_utf8() {
+    assert_eq!(to_utf8(std::path::Path::new("talks/deck.mp4")).unwrap(), "talks/deck.mp4");
+
+    #[cfg(unix)]
+    {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+        let invalid = std::path::Path::new(OsStr::from_bytes(b"deck\xff.mp4"));
+        assert!(to_utf8(invalid).is_err());
+    }
+}
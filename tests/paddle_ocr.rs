@@ -0,0 +1,91 @@
+#![cfg(feature = "onnx")]
+//! Checks the decoding steps of the PaddleOCR engine on hand-made model outputs.
+
+use videodocparser::paddle::{
+    DecodedChar, ctc_decode, detection_size, group_words, load_dictionary, recognition_batches, step_width, text_boxes,
+};
+
+#[test]
+fn dictionaries_gain_a_space_class() {
+    let dir = std::env::temp_dir().join(format!("vdp-paddle-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("dict.txt");
+    std::fs::write(&path, "a\nb\nc\n").unwrap();
+    assert_eq!(load_dictionary(&path).unwrap(), ["a", "b", "c", " "]);
+    std::fs::write(&path, "").unwrap();
+    assert!(load_dictionary(&path).is_err());
+    std::fs::remove_dir_all(&dir).ok();
+    assert!(load_dictionary(&dir.join("missing.txt")).is_err());
+}
+
+#[test]
+fn frames_are_scaled_to_multiples_of_32() {
+    assert_eq!(detection_size(1920, 1080), (960, 544));
+    assert_eq!(detection_size(640, 360), (640, 352));
+    assert_eq!(detection_size(10, 10), (32, 32));
+}
+
+#[test]
+fn text_lines_are_found_on_the_detection_map() {
+    // Two confident lines, a faint one, and a speck, on a 40x20 map.
+    let (width, height) = (40u32, 20u32);
+    let mut map = vec![0f32; (width * height) as usize];
+    let mut fill = |x1: u32, y1: u32, x2: u32, y2: u32, p: f32| {
+        for y in y1..y2 {
+            for x in x1..x2 {
+                map[(y * width + x) as usize] = p;
+            }
+        }
+    };
+    fill(20, 12, 36, 16, 0.9);
+    fill(4, 2, 24, 6, 0.9);
+    fill(4, 12, 12, 16, 0.4);
+    fill(38, 0, 39, 1, 0.9);
+
+    // The 20x4 line grows by round(80 * 1.5 / 48) = 3 pixels, the 16x4 one by round(64 * 1.5 / 40) = 2.
+    assert_eq!(text_boxes(&map, width, height), vec![(1, 0, 27, 9), (18, 10, 38, 18)]);
+}
+
+#[test]
+fn ctc_output_is_decoded_and_grouped_into_words() {
+    let dictionary: Vec<String> = ["h", "i", "o", " "].iter().map(|c| c.to_string()).collect();
+    // Classes: blank, h, i, o, space. Steps read "h h - i _ o o - o".
+    let steps = [1, 1, 0, 2, 4, 3, 3, 0, 3];
+    let mut probabilities = Vec::new();
+    for &best in &steps {
+        let mut row = vec![0.05f32; 5];
+        row[best] = 0.8;
+        probabilities.extend(row);
+    }
+    let chars = ctc_decode(&probabilities, 5, &dictionary);
+    let text: String = chars.iter().map(|c| c.text.as_str()).collect();
+    assert_eq!(text, "hi oo");
+    assert_eq!(chars.iter().map(|c| c.step).collect::<Vec<_>>(), vec![0, 3, 4, 5, 8]);
+
+    let words = group_words(&chars, (100, 50, 190, 70), 10.0);
+    assert_eq!(words.len(), 2);
+    assert_eq!(words[0].text, "hi");
+    assert_eq!(words[0].bbox, (100, 50, 140, 70));
+    assert_eq!(words[1].text, "oo");
+    assert_eq!(words[1].bbox, (150, 50, 190, 70));
+    assert!((words[1].confidence - 80.0).abs() < 1e-3);
+
+    let lone = [DecodedChar { text: " ".to_string(), step: 0, confidence: 0.9 }];
+    assert!(group_words(&lone, (0, 0, 10, 10), 1.0).is_empty());
+}
+
+#[test]
+fn lines_are_read_in_batches_of_similar_width() {
+    let widths = [300, 40, 1200, 80, 310];
+    assert_eq!(recognition_batches(&widths, 2), vec![vec![1, 3], vec![0, 4], vec![2]]);
+    assert_eq!(recognition_batches(&widths, 32), vec![vec![1, 3, 0, 4, 2]]);
+    assert!(recognition_batches(&[], 32).is_empty());
+}
+
+#[test]
+fn padded_lines_keep_their_step_width() {
+    // A 100 pixel line scaled to 200 and padded to 400: 100 steps cover 4 input pixels, 2 of the frame each.
+    assert!((step_width(100, 200, 400, 100) - 2.0).abs() < 1e-6);
+    // Unpadded, the steps span the line.
+    assert!((step_width(100, 200, 200, 50) - 2.0).abs() < 1e-6);
+}
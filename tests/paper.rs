@@ -0,0 +1,54 @@
+//! Checks filling in the glare and evening out the shadows of filmed paper pages.
+
+use image::{Rgb, RgbImage};
+use videodocparser::paper::{enhance, glare_mask, inpaint};
+
+/// A page shaded on its left, from 140 to 210, with a line of dark print and
+/// a lamp's reflection, clipped to white, on its right.
+fn page() -> RgbImage {
+    RgbImage::from_fn(320, 160, |x, y| {
+        let lit = (140 + x * 70 / 320) as u8;
+        if (20..300).contains(&x) && (40..46).contains(&y) {
+            Rgb([30, 30, 60])
+        } else if (x as i32 - 240).pow(2) + (y as i32 - 110).pow(2) < 20 * 20 {
+            Rgb([255, 255, 252])
+        } else {
+            Rgb([lit, lit, lit])
+        }
+    })
+}
+
+#[test]
+fn the_glare_is_found_and_filled_in() {
+    let frame = page();
+    let glare = glare_mask(&frame);
+    let at = |x: usize, y: usize| glare[y * 320 + x];
+    assert!(at(240, 110) && at(240, 129) && at(260, 110));
+    assert!(!at(240, 40) && !at(100, 110) && !at(5, 5));
+
+    // The reflection takes the paper's shade around it.
+    let filled = inpaint(&frame, &glare);
+    let centre = filled.get_pixel(240, 110).0[0];
+    assert!((190..=200).contains(&centre), "{}", centre);
+    assert_eq!(filled.get_pixel(40, 42), frame.get_pixel(40, 42));
+}
+
+#[test]
+fn the_page_turns_evenly_white_and_the_print_stays() {
+    let enhanced = enhance(&page());
+    for (x, y) in [(5, 5), (160, 100), (315, 155), (240, 110)] {
+        let level = enhanced.get_pixel(x, y).0;
+        assert!(level.iter().all(|&channel| channel >= 235), "paper at ({}, {}): {:?}", x, y, level);
+    }
+    for x in [30, 290] {
+        let print = enhanced.get_pixel(x, 42).0;
+        assert!(print[0] < 80 && print[2] > print[0], "print at {}: {:?}", x, print);
+    }
+}
+
+#[test]
+fn evenly_lit_white_paper_has_no_glare() {
+    let frame = RgbImage::from_pixel(40, 30, Rgb([250, 250, 250]));
+    assert!(glare_mask(&frame).iter().all(|&glare| !glare));
+    assert!(enhance(&frame).pixels().all(|pixel| pixel.0 == [255, 255, 255]));
+}
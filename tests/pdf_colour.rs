@@ -0,0 +1,68 @@
+//! Checks embedding PDF page images without colour in grayscale or with one bit per pixel.
+
+use image::{ImageBuffer, Rgb, RgbImage};
+use lopdf::{Document, Object};
+use videodocparser::document_builder::{ImageCompression, Page, PdfOptions, build_pdf};
+use videodocparser::mrc::{PageColour, page_colour};
+
+/// A white page with lines of text in `ink`, anti-aliased on their top edge,
+/// and a `shade` band across its bottom when given.
+fn page(ink: [u8; 3], shade: Option<u8>) -> RgbImage {
+    ImageBuffer::from_fn(400, 300, |x, y| match (shade.filter(|_| y >= 240), y % 40, x) {
+        (Some(shade), _, _) => Rgb([shade; 3]),
+        (None, 20, 20..380) => Rgb([128, 128, 128]),
+        (None, 21..=30, 20..380) if x % 10 < 7 => Rgb(ink),
+        _ => Rgb([255, 255, 255]),
+    })
+}
+
+#[test]
+fn pages_are_told_apart_by_their_colours() {
+    assert_eq!(page_colour(&page([0, 0, 0], None)), PageColour::Bilevel);
+    assert_eq!(page_colour(&page([0, 0, 0], Some(128))), PageColour::Grayscale);
+    assert_eq!(page_colour(&page([200, 0, 0], None)), PageColour::Colour);
+    // White text on black is not bilevel.
+    assert_eq!(page_colour(&ImageBuffer::from_pixel(40, 30, Rgb([0, 0, 0]))), PageColour::Grayscale);
+}
+
+/// Builds a one-page PDF of `image` and returns its image XObject.
+fn embedded_image(image: &RgbImage, compression: ImageCompression, name: &str) -> lopdf::Dictionary {
+    let pages = vec![Page { image, ocr: None, title: None, timestamp: None, end: None }];
+    let options = PdfOptions { image_compression: compression, auto_colour: true, ..Default::default() };
+    let dir = std::env::temp_dir().join(format!("vdp-pdf-colour-{}-{}", name, std::process::id()));
+    let path = dir.join("document.pdf");
+    build_pdf(&pages, &options, &path).unwrap();
+    let document = Document::load(&path).unwrap();
+    std::fs::remove_dir_all(dir).ok();
+    document
+        .objects
+        .values()
+        .filter_map(|object| object.as_stream().ok())
+        .find(|stream| stream.dict.get(b"Subtype").and_then(|s| s.as_name()).ok() == Some(b"Image"))
+        .unwrap()
+        .dict
+        .clone()
+}
+
+#[test]
+fn text_pages_are_embedded_with_one_bit_per_pixel() {
+    let image = embedded_image(&page([0, 0, 0], None), ImageCompression::default(), "bilevel");
+    assert_eq!(image.get(b"Filter").unwrap().as_name().unwrap(), b"CCITTFaxDecode");
+    assert_eq!(image.get(b"ColorSpace").unwrap().as_name().unwrap(), b"DeviceGray");
+    assert_eq!(image.get(b"BitsPerComponent").unwrap(), &Object::Integer(1));
+    let parms = image.get(b"DecodeParms").unwrap().as_dict().unwrap();
+    assert_eq!(parms.get(b"K").unwrap().as_i64().unwrap(), -1);
+    assert_eq!(parms.get(b"Columns").unwrap().as_i64().unwrap(), 400);
+}
+
+#[test]
+fn grey_pages_are_embedded_in_grayscale() {
+    let image = embedded_image(&page([0, 0, 0], Some(128)), ImageCompression::Flate, "grey");
+    assert_eq!(image.get(b"Filter").unwrap().as_name().unwrap(), b"FlateDecode");
+    assert_eq!(image.get(b"ColorSpace").unwrap().as_name().unwrap(), b"DeviceGray");
+    assert_eq!(image.get(b"BitsPerComponent").unwrap(), &Object::Integer(8));
+
+    let image = embedded_image(&page([200, 0, 0], None), ImageCompression::default(), "colour");
+    assert_eq!(image.get(b"Filter").unwrap().as_name().unwrap(), b"DCTDecode");
+    assert_eq!(image.get(b"ColorSpace").unwrap().as_name().unwrap(), b"DeviceRGB");
+}
@@ -0,0 +1,404 @@
+//! Checks the structure of generated PDFs.
+
+use image::{ImageBuffer, Rgb};
+use lopdf::Document;
+use videodocparser::document_builder::{
+    build_pdf, deep_link, ImageCompression, Page, PageSize, PdfOptions,
+};
+use videodocparser::ocr::{OcrFrameResult, OcrWord};
+use videodocparser::pdf_encryption::PdfEncryption;
+use videodocparser::xmp::{DateTime, DocumentMetadata};
+
+fn word(text: &str, bbox: (i32, i32, i32, i32)) -> OcrWord {
+    OcrWord { text: text.to_string(), bbox, confidence: 90.0 }
+}
+
+#[test]
+fn pdfa_profile_adds_archival_objects() {
+    let image = ImageBuffer::from_pixel(320, 240, Rgb([40u8, 80, 120]));
+    let pages = vec![Page { image: &image, ocr: None, title: None, timestamp: None, end: None }];
+    let options = PdfOptions {
+        metadata: DocumentMetadata {
+            title: "Lecture <1>".to_string(),
+            author: Some("Ada".to_string()),
+            keywords: None,
+            creation_date: DateTime::parse_iso8601("2024-03-05T09:08:07.000000Z"),
+        },
+        pdfa: true,
+        ..Default::default()
+    };
+
+    let dir = std::env::temp_dir().join(format!("vdp-pdfa-{}", std::process::id()));
+    let path = dir.join("document.pdf");
+    build_pdf(&pages, &options, &path).unwrap();
+
+    let document = Document::load(&path).unwrap();
+    assert!(document.trailer.get(b"ID").is_ok());
+    let catalog = document.catalog().unwrap();
+    let metadata_ref = catalog.get(b"Metadata").unwrap().as_reference().unwrap();
+    let metadata = document.get_object(metadata_ref).unwrap().as_stream().unwrap();
+    let packet = String::from_utf8_lossy(&metadata.content);
+    assert!(packet.contains("<pdfaid:part>2</pdfaid:part>"));
+    assert!(packet.contains("<pdfaid:conformance>B</pdfaid:conformance>"));
+    assert!(packet.contains("Lecture &lt;1&gt;"));
+    assert!(packet.contains("<xmp:CreateDate>2024-03-05T09:08:07Z</xmp:CreateDate>"));
+
+    let info_ref = document.trailer.get(b"Info").unwrap().as_reference().unwrap();
+    let info = document.get_dictionary(info_ref).unwrap();
+    assert_eq!(info.get(b"Author").unwrap().as_str().unwrap(), b"Ada");
+    assert!(info.get(b"CreationDate").is_ok());
+
+    let intents = catalog.get(b"OutputIntents").unwrap().as_array().unwrap();
+    let intent = intents[0].as_dict().unwrap();
+    assert_eq!(intent.get(b"S").unwrap().as_name().unwrap(), b"GTS_PDFA1");
+    let profile_ref = intent.get(b"DestOutputProfile").unwrap().as_reference().unwrap();
+    let profile = document.get_object(profile_ref).unwrap().as_stream().unwrap();
+    assert_eq!(&profile.content[36..40], b"acsp");
+    assert_eq!(
+        u32::from_be_bytes(profile.content[..4].try_into().unwrap()) as usize,
+        profile.content.len()
+    );
+
+    std::fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn flate_images_are_downscaled_to_max_dpi() {
+    let image = ImageBuffer::from_pixel(1000, 700, Rgb([200u8, 10, 10]));
+    let pages = vec![Page { image: &image, ocr: None, title: None, timestamp: None, end: None }];
+    let options = PdfOptions {
+        image_compression: ImageCompression::Flate,
+        max_image_dpi: Some(36.0),
+        ..Default::default()
+    };
+
+    let dir = std::env::temp_dir().join(format!("vdp-pdf-flate-{}", std::process::id()));
+    let path = dir.join("document.pdf");
+    build_pdf(&pages, &options, &path).unwrap();
+
+    let document = Document::load(&path).unwrap();
+    let image_stream = document
+        .objects
+        .values()
+        .filter_map(|object| object.as_stream().ok())
+        .find(|stream| stream.dict.get(b"Subtype").and_then(|s| s.as_name()).ok() == Some(b"Image"))
+        .unwrap();
+    assert_eq!(image_stream.dict.get(b"Filter").unwrap().as_name().unwrap(), b"FlateDecode");
+    // A landscape A4 page is 842pt (11.7in) wide, so at 36 DPI the image is 421 pixels wide.
+    assert_eq!(image_stream.dict.get(b"Width").unwrap().as_i64().unwrap(), 421);
+
+    std::fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn native_pages_match_the_frame_size() {
+    let image = ImageBuffer::from_pixel(192, 108, Rgb([0u8, 0, 0]));
+    let pages = vec![Page { image: &image, ocr: None, title: None, timestamp: None, end: None }];
+    let options = PdfOptions { page_size: PageSize::Native { dpi: 96.0 }, ..Default::default() };
+
+    let dir = std::env::temp_dir().join(format!("vdp-pdf-native-{}", std::process::id()));
+    let path = dir.join("document.pdf");
+    build_pdf(&pages, &options, &path).unwrap();
+
+    let document = Document::load(&path).unwrap();
+    let page_id = document.get_pages()[&1];
+    let media_box = document.get_dictionary(page_id).unwrap().get(b"MediaBox").unwrap();
+    let corners: Vec<f32> = media_box
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|value| value.as_float().unwrap())
+        .collect();
+    assert_eq!(corners, [0.0, 0.0, 144.0, 81.0]);
+
+    std::fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn table_of_contents_links_to_every_page() {
+    let image = ImageBuffer::from_pixel(64, 48, Rgb([0u8, 0, 0]));
+    let pages = vec![
+        Page { image: &image, ocr: None, title: Some("Intro".to_string()), timestamp: Some(0.0), end: None },
+        Page { image: &image, ocr: None, title: Some("Agenda".to_string()), timestamp: Some(75.0), end: None },
+    ];
+    let options = PdfOptions { table_of_contents: true, ..Default::default() };
+
+    let dir = std::env::temp_dir().join(format!("vdp-pdf-toc-{}", std::process::id()));
+    let path = dir.join("document.pdf");
+    build_pdf(&pages, &options, &path).unwrap();
+
+    let document = Document::load(&path).unwrap();
+    let page_ids = document.get_pages();
+    assert_eq!(page_ids.len(), 3);
+    let text = document.extract_text(&[1]).unwrap();
+    assert!(text.contains("00:01:15") && text.contains("Agenda"), "{text:?}");
+
+    let toc = document.get_dictionary(page_ids[&1]).unwrap();
+    let annotations = toc.get(b"Annots").unwrap().as_array().unwrap();
+    let targets: Vec<_> = annotations
+        .iter()
+        .map(|annotation| {
+            let annotation = document.get_dictionary(annotation.as_reference().unwrap()).unwrap();
+            let action = annotation.get(b"A").unwrap().as_dict().unwrap();
+            action.get(b"D").unwrap().as_array().unwrap()[0].as_reference().unwrap()
+        })
+        .collect();
+    assert_eq!(targets, [page_ids[&2], page_ids[&3]]);
+
+    std::fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn video_url_links_each_page_to_its_timestamp() {
+    assert_eq!(deep_link("https://example.com/v.mp4", 75.4), "https://example.com/v.mp4?t=75");
+    assert_eq!(deep_link("https://youtu.be/x?si=a#top", 9.0), "https://youtu.be/x?si=a&t=9#top");
+
+    let image = ImageBuffer::from_pixel(320, 180, Rgb([255u8, 255, 255]));
+    let pages = vec![Page { image: &image, ocr: None, title: None, timestamp: Some(75.0), end: None }];
+    let options = PdfOptions {
+        video_url: Some("https://example.com/talk".to_string()),
+        ..Default::default()
+    };
+
+    let dir = std::env::temp_dir().join(format!("vdp-pdf-link-{}", std::process::id()));
+    let path = dir.join("document.pdf");
+    build_pdf(&pages, &options, &path).unwrap();
+
+    let document = Document::load(&path).unwrap();
+    let page = document.get_dictionary(document.get_pages()[&1]).unwrap();
+    let annotations = page.get(b"Annots").unwrap().as_array().unwrap();
+    let annotation = document.get_dictionary(annotations[0].as_reference().unwrap()).unwrap();
+    let action = annotation.get(b"A").unwrap().as_dict().unwrap();
+    assert_eq!(action.get(b"URI").unwrap().as_str().unwrap(), b"https://example.com/talk?t=75");
+
+    std::fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn cross_reference_table_points_at_every_object() {
+    let image = ImageBuffer::from_pixel(320, 180, Rgb([90u8, 90, 90]));
+    let pages: Vec<Page> = (0..5)
+        .map(|i| Page { image: &image, ocr: None, title: None, timestamp: Some(i as f64 * 30.0), end: None })
+        .collect();
+    let options = PdfOptions {
+        table_of_contents: true,
+        video_url: Some("https://example.com/talk".to_string()),
+        ..Default::default()
+    };
+
+    let dir = std::env::temp_dir().join(format!("vdp-pdf-xref-{}", std::process::id()));
+    let path = dir.join("document.pdf");
+    build_pdf(&pages, &options, &path).unwrap();
+
+    // The pages are written after the document-level objects, so check the table
+    // by hand rather than relying on a reader that may rebuild a broken one.
+    let bytes = std::fs::read(&path).unwrap();
+    let tail = String::from_utf8_lossy(&bytes[bytes.len() - 32..]).into_owned();
+    let startxref: usize =
+        tail.rsplit("startxref\n").next().unwrap().lines().next().unwrap().parse().unwrap();
+    let table = std::str::from_utf8(&bytes[startxref..]).unwrap();
+    assert!(table.starts_with("xref\n0 "));
+    let mut in_use = 0;
+    for (id, entry) in table.split("\r\n").enumerate() {
+        let entry = if id == 0 { entry.rsplit('\n').next().unwrap() } else { entry };
+        if entry.len() != 18 {
+            break;
+        }
+        if entry.ends_with('n') {
+            let offset: usize = entry[..10].parse().unwrap();
+            let object = format!("{} 0 obj", id);
+            assert!(bytes[offset..].starts_with(object.as_bytes()), "bad offset for object {id}");
+            in_use += 1;
+        }
+    }
+    assert!(in_use > 5 * 3);
+
+    let document = Document::load(&path).unwrap();
+    assert_eq!(document.get_pages().len(), 6);
+
+    std::fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn same_input_produces_identical_bytes() {
+    assert_eq!(
+        DateTime::from_unix_timestamp(1_709_629_687),
+        DateTime::parse_iso8601("2024-03-05T09:08:07Z")
+    );
+    let leap_day = DateTime::from_unix_timestamp(951_782_400).unwrap();
+    assert_eq!(leap_day.to_iso8601(), "2000-02-29T00:00:00Z");
+
+    let image = ImageBuffer::from_fn(320, 240, |x, y| Rgb([x as u8, y as u8, 128]));
+    let ocr = OcrFrameResult {
+        frame_index: 0,
+        words: vec![word("Agenda", (20, 20, 120, 50))],
+        blocks: Vec::new(),
+        strategy: None,
+        timestamp: None,
+    };
+    let pages = vec![Page { image: &image, ocr: Some(&ocr), title: None, timestamp: Some(12.0), end: None }];
+    let options = PdfOptions {
+        metadata: DocumentMetadata {
+            title: "Talk".to_string(),
+            creation_date: DateTime::from_unix_timestamp(1_709_629_687),
+            ..Default::default()
+        },
+        pdfa: true,
+        ..Default::default()
+    };
+
+    let dir = std::env::temp_dir().join(format!("vdp-pdf-reproducible-{}", std::process::id()));
+    let first = dir.join("first.pdf");
+    let second = dir.join("second.pdf");
+    build_pdf(&pages, &options, &first).unwrap();
+    build_pdf(&pages, &options, &second).unwrap();
+    assert_eq!(std::fs::read(&first).unwrap(), std::fs::read(&second).unwrap());
+
+    let metadata = DocumentMetadata { title: "Other".to_string(), ..Default::default() };
+    let other = PdfOptions { metadata, ..options };
+    let third = dir.join("third.pdf");
+    build_pdf(&pages, &other, &third).unwrap();
+    let id = |path| Document::load(path).unwrap().trailer.get(b"ID").unwrap().clone();
+    assert_ne!(id(&first), id(&third));
+
+    std::fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn page_labels_show_video_timestamps() {
+    let image = ImageBuffer::from_pixel(320, 180, Rgb([255u8, 255, 255]));
+    let pages = vec![
+        Page { image: &image, ocr: None, title: None, timestamp: Some(754.0), end: None },
+        Page { image: &image, ocr: None, title: None, timestamp: None, end: None },
+    ];
+    let options = PdfOptions { table_of_contents: true, ..Default::default() };
+
+    let dir = std::env::temp_dir().join(format!("vdp-pdf-labels-{}", std::process::id()));
+    let path = dir.join("document.pdf");
+    build_pdf(&pages, &options, &path).unwrap();
+
+    let document = Document::load(&path).unwrap();
+    let labels = document.catalog().unwrap().get(b"PageLabels").unwrap().as_dict().unwrap();
+    let nums = labels.get(b"Nums").unwrap().as_array().unwrap();
+    assert_eq!(nums.len(), 6);
+    let label = |i: usize| nums[i].as_dict().unwrap();
+    assert_eq!(nums[0].as_i64().unwrap(), 0);
+    assert_eq!(label(1).get(b"S").unwrap().as_name().unwrap(), b"r");
+    assert_eq!(nums[2].as_i64().unwrap(), 1);
+    assert_eq!(label(3).get(b"P").unwrap().as_str().unwrap(), b"00:12:34");
+    assert!(label(3).get(b"S").is_err());
+    assert_eq!(nums[4].as_i64().unwrap(), 2);
+    assert_eq!(label(5).get(b"S").unwrap().as_name().unwrap(), b"D");
+    assert_eq!(label(5).get(b"St").unwrap().as_i64().unwrap(), 2);
+
+    std::fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn passwords_encrypt_the_pdf() {
+    let image = ImageBuffer::from_pixel(320, 240, Rgb([255u8, 255, 255]));
+    let ocr = OcrFrameResult {
+        frame_index: 0,
+        words: vec![word("Confidential", (20, 20, 200, 60))],
+        blocks: Vec::new(),
+        strategy: None,
+        timestamp: None,
+    };
+    let pages = vec![Page { image: &image, ocr: Some(&ocr), title: None, timestamp: None, end: None }];
+    let options = PdfOptions {
+        encryption: Some(PdfEncryption {
+            user_password: "reader".to_string(),
+            owner_password: Some("author".to_string()),
+        }),
+        ..Default::default()
+    };
+
+    let dir = std::env::temp_dir().join(format!("vdp-pdf-encrypted-{}", std::process::id()));
+    let path = dir.join("document.pdf");
+    build_pdf(&pages, &options, &path).unwrap();
+
+    let bytes = std::fs::read(&path).unwrap();
+    assert!(!bytes.windows(12).any(|window| window == b"Confidential"));
+    let document = Document::load(&path).unwrap();
+    assert!(document.is_encrypted());
+    assert!(document.authenticate_user_password("reader").is_ok());
+    assert!(document.authenticate_owner_password("author").is_ok());
+    assert!(document.authenticate_user_password("wrong").is_err());
+
+    // With only an owner password, anyone can open and read the document.
+    let owner_only = PdfOptions {
+        encryption: Some(PdfEncryption { owner_password: Some("author".to_string()), ..Default::default() }),
+        ..Default::default()
+    };
+    let open_path = dir.join("open.pdf");
+    build_pdf(&pages, &owner_only, &open_path).unwrap();
+    let document = Document::load(&open_path).unwrap();
+    assert!(document.extract_text(&[1]).unwrap().contains("Confidential"));
+
+    let pdfa = PdfOptions { pdfa: true, ..options };
+    assert!(build_pdf(&pages, &pdfa, &dir.join("archival.pdf")).is_err());
+
+    std::fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn pdfua_profile_tags_image_text_and_link() {
+    let image = ImageBuffer::from_pixel(640, 360, Rgb([255u8, 255, 255]));
+    let ocr = OcrFrameResult {
+        frame_index: 0,
+        words: vec![
+            word("Agenda", (40, 40, 200, 80)),
+            word("First", (40, 120, 120, 150)),
+            word("point", (130, 120, 220, 150)),
+        ],
+        blocks: Vec::new(),
+        strategy: None,
+        timestamp: None,
+    };
+    let pages = vec![
+        Page { image: &image, ocr: Some(&ocr), title: None, timestamp: Some(12.0), end: None },
+        Page { image: &image, ocr: None, title: None, timestamp: Some(40.0), end: None },
+    ];
+    let options = PdfOptions {
+        tagged: true,
+        language: Some("en".to_string()),
+        video_url: Some("https://example.com/talk".to_string()),
+        table_of_contents: true,
+        ..Default::default()
+    };
+
+    let dir = std::env::temp_dir().join(format!("vdp-pdf-tags-{}", std::process::id()));
+    let path = dir.join("document.pdf");
+    build_pdf(&pages, &options, &path).unwrap();
+
+    let document = Document::load(&path).unwrap();
+    // The contents pages would use an untagged, unembedded font.
+    assert_eq!(document.get_pages().len(), 2);
+    let catalog = document.catalog().unwrap();
+    assert_eq!(catalog.get(b"Lang").unwrap().as_str().unwrap(), b"en");
+    let mark_info = catalog.get(b"MarkInfo").unwrap().as_dict().unwrap();
+    assert!(mark_info.get(b"Marked").unwrap().as_bool().unwrap());
+    let root_ref = catalog.get(b"StructTreeRoot").unwrap().as_reference().unwrap();
+    let root = document.get_dictionary(root_ref).unwrap();
+    let parent_tree = root.get(b"ParentTree").unwrap().as_dict().unwrap();
+    // Content and link annotation of both pages.
+    assert_eq!(parent_tree.get(b"Nums").unwrap().as_array().unwrap().len(), 2 * 4);
+    assert_eq!(root.get(b"ParentTreeNextKey").unwrap().as_i64().unwrap(), 4);
+
+    let page_id = document.get_pages()[&1];
+    let page = document.get_dictionary(page_id).unwrap();
+    assert_eq!(page.get(b"StructParents").unwrap().as_i64().unwrap(), 0);
+    assert_eq!(page.get(b"Tabs").unwrap().as_name().unwrap(), b"S");
+    let content = String::from_utf8_lossy(&document.get_page_content(page_id).unwrap())
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    for tag in ["/Figure << /MCID 0 >> BDC", "/Link << /MCID 1 >> BDC", "/P << /MCID 2 >> BDC", "/P << /MCID 3 >> BDC"] {
+        assert!(content.contains(tag), "{tag:?} missing");
+    }
+    assert!(!content.contains("/MCID 4"));
+    let text = document.extract_text(&[1]).unwrap();
+    assert!(text.contains("Agenda") && text.contains("point"));
+
+    std::fs::remove_dir_all(dir).ok();
+}
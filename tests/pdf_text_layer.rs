@@ -0,0 +1,71 @@
+//! Checks that generated PDFs carry an extractable OCR text layer.
+
+use image::{ImageBuffer, Rgb};
+use lopdf::Document;
+use videodocparser::document_builder::{Page, PdfOptions, build_pdf};
+use videodocparser::ocr::{OcrFrameResult, OcrWord};
+
+fn word(text: &str, bbox: (i32, i32, i32, i32)) -> OcrWord {
+    OcrWord { text: text.to_string(), bbox, confidence: 90.0 }
+}
+
+fn two_pages(dir_name: &str) -> (std::path::PathBuf, Document) {
+    let image = ImageBuffer::from_pixel(640, 480, Rgb([255u8, 255, 255]));
+    let ocr = OcrFrameResult {
+        frame_index: 0,
+        words: vec![
+            word("Quarterly", (40, 40, 200, 80)),
+            word("Results", (210, 40, 340, 80)),
+            word("Café", (40, 120, 120, 150)),
+            word("Ελληνικά", (140, 120, 300, 150)),
+        ],
+        blocks: Vec::new(),
+        strategy: None,
+        timestamp: None,
+    };
+    let pages = vec![
+        Page { image: &image, ocr: Some(&ocr), title: None, timestamp: None, end: None },
+        Page { image: &image, ocr: None, title: None, timestamp: None, end: None },
+    ];
+
+    let dir = std::env::temp_dir().join(format!("{dir_name}-{}", std::process::id()));
+    let path = dir.join("document.pdf");
+    build_pdf(&pages, &PdfOptions::default(), &path).unwrap();
+    (dir, Document::load(&path).unwrap())
+}
+
+#[test]
+fn pdf_text_layer_is_extractable() {
+    let (dir, document) = two_pages("vdp-pdf-text");
+    assert_eq!(document.get_pages().len(), 2);
+    let text = document.extract_text(&[1]).unwrap();
+    for expected in ["Quarterly", "Results", "Café", "Ελληνικά"] {
+        assert!(text.contains(expected), "{expected:?} missing from {text:?}");
+    }
+    assert!(document.extract_text(&[2]).unwrap().trim().is_empty());
+
+    std::fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn text_layer_is_invisible_and_drawn_over_the_image() {
+    let (dir, document) = two_pages("vdp-pdf-text-order");
+    let content = |number: u32| {
+        let page_id = document.get_pages()[&number];
+        String::from_utf8_lossy(&document.get_page_content(page_id).unwrap())
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+
+    let first = content(1);
+    let image = first.find(" Do").expect("page image missing");
+    let text = first.find("BT").expect("text layer missing");
+    assert!(image < text, "text drawn before the image: {first:?}");
+    assert!(first[text..].starts_with("BT 3 Tr"), "{first:?}");
+    // A page without OCR results has the image only.
+    let second = content(2);
+    assert!(second.contains(" Do") && !second.contains("BT"), "{second:?}");
+
+    std::fs::remove_dir_all(dir).ok();
+}
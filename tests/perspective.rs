@@ -0,0 +1,50 @@
+//! Checks the keystone correction of pages filmed at an angle.
+
+use image::{ImageBuffer, Rgb};
+use videodocparser::perspective::{Quad, detect_quad, output_size, warp};
+
+/// Whether (x, y) lies inside the convex quadrilateral.
+fn inside(quad: &Quad, x: f32, y: f32) -> bool {
+    (0..4).all(|i| {
+        let (a, b) = (quad[i], quad[(i + 1) % 4]);
+        (b.0 - a.0) * (y - a.1) - (b.1 - a.1) * (x - a.0) >= 0.0
+    })
+}
+
+#[test]
+fn a_page_filmed_at_an_angle_is_made_upright() {
+    // A white page on a dark desk, narrower at the top as if filmed from below, with a
+    // black mark near its top left corner.
+    let page: Quad = [(200.0, 80.0), (440.0, 90.0), (560.0, 420.0), (90.0, 400.0)];
+    let frame = ImageBuffer::from_fn(640, 480, |x, y| {
+        let (x, y) = (x as f32 + 0.5, y as f32 + 0.5);
+        if (215.0..235.0).contains(&x) && (100.0..120.0).contains(&y) {
+            Rgb([0, 0, 0])
+        } else if inside(&page, x, y) {
+            Rgb([240, 240, 235])
+        } else {
+            Rgb([40, 35, 30])
+        }
+    });
+
+    let quad = detect_quad(&frame).unwrap();
+    for (found, expected) in quad.iter().zip(page) {
+        assert!((found.0 - expected.0).hypot(found.1 - expected.1) < 8.0, "{found:?} {expected:?}");
+    }
+
+    let (width, height) = output_size(&quad);
+    let upright = warp(&frame, &quad);
+    assert_eq!(upright.dimensions(), (width, height));
+    assert!((465..=480).contains(&width) && (335..=350).contains(&height), "{width}x{height}");
+    // The page fills the result, and the mark stays in its top left corner.
+    for (x, y) in [(6, 6), (width - 6, 6), (width - 6, height - 6), (6, height - 6)] {
+        assert!(upright.get_pixel(x, y)[0] > 200, "({x}, {y})");
+    }
+    let mark = (0..height / 3).flat_map(|y| (0..width / 3).map(move |x| (x, y)));
+    assert!(mark.into_iter().any(|(x, y)| upright.get_pixel(x, y)[0] < 50));
+
+    // A page that already fills the frame, or no page at all, is left alone.
+    assert_eq!(detect_quad(&ImageBuffer::from_pixel(320, 240, Rgb([250u8, 250, 250]))), None);
+    let dark = ImageBuffer::from_fn(320, 240, |x, _| if x < 10 { Rgb([255u8, 255, 255]) } else { Rgb([20, 20, 20]) });
+    assert_eq!(detect_quad(&dark), None);
+}
@@ -0,0 +1,46 @@
+//! Checks the OCR text post-processing stage with a stand-in backend.
+
+use anyhow::{bail, Result};
+use videodocparser::postprocess::{dehyphenate, process_pages, ProcessedText, TextPostProcessor};
+
+/// Uppercases the text, summarizes it as its first word, and fails on "offline".
+struct Shouting;
+
+impl TextPostProcessor for Shouting {
+    fn clean(&self, text: &str) -> Result<String> {
+        if text.contains("offline") {
+            bail!("backend unavailable");
+        }
+        Ok(text.to_uppercase())
+    }
+
+    fn summarize(&self, text: &str) -> Result<String> {
+        Ok(text.split_whitespace().next().unwrap_or_default().to_string())
+    }
+}
+
+#[test]
+fn hyphenated_words_are_rejoined() {
+    assert_eq!(dehyphenate("Optical charac-\nter recog-\nnition"), "Optical character\nrecognition");
+    // Compounds before a capital or a number keep their hyphen and line break.
+    assert_eq!(dehyphenate("Pre-\nCambrian\nCOVID-\n19"), "Pre-\nCambrian\nCOVID-\n19");
+}
+
+#[test]
+fn pages_are_cleaned_and_summarized() {
+    let texts = ["recog-\nnition rate".to_string(), String::new(), "offline".to_string()];
+    let processed = process_pages(&Shouting, &texts, true);
+    assert_eq!(
+        processed,
+        vec![
+            Some(ProcessedText {
+                clean_text: "RECOGNITION\nRATE".to_string(),
+                summary: Some("RECOGNITION".to_string()),
+            }),
+            None,
+            None,
+        ]
+    );
+    let unsummarized = process_pages(&Shouting, &texts[..1], false);
+    assert_eq!(unsummarized[0].as_ref().unwrap().summary, None);
+}
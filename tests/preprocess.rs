@@ -0,0 +1,58 @@
+//! Checks the cleanup of frames before OCR.
+
+use image::{GrayImage, ImageBuffer, Luma, Rgb};
+use videodocparser::preprocess::{binarize, denoise, equalize_contrast, estimate_skew, prepare, unrotate_bbox};
+
+/// White frame with three dark bars, like lines of text, rising by `degrees`.
+fn skewed_lines(degrees: f32) -> ImageBuffer<Rgb<u8>, Vec<u8>> {
+    let slope = degrees.to_radians().tan();
+    ImageBuffer::from_fn(400, 300, |x, y| {
+        let centre = |row: f32| row + (x as f32 - 200.0) * slope;
+        let on_line = [90.0, 150.0, 210.0].iter().any(|&row| (y as f32 - centre(row)).abs() < 6.0);
+        let inked = (50..350).contains(&x) && on_line;
+        if inked { Rgb([20, 20, 20]) } else { Rgb([235, 235, 235]) }
+    })
+}
+
+#[test]
+fn skew_is_found_and_boxes_map_back() {
+    let frame = skewed_lines(3.0);
+    let gray = image::imageops::grayscale(&frame);
+    let skew = estimate_skew(&gray);
+    assert!((skew - 3.0).abs() <= 0.2, "{skew}");
+    assert_eq!(estimate_skew(&image::imageops::grayscale(&skewed_lines(0.0))), 0.0);
+
+    let prepared = prepare(&frame, &["deskew".to_string(), "binarize".to_string()]);
+    assert_eq!(prepared.skew, skew);
+    // The middle bar is level after deskewing: its row is ink from end to end.
+    assert!((60..340).all(|x| prepared.image.get_pixel(x, 150)[0] == 0));
+    assert!(prepared.image.pixels().all(|pixel| pixel[0] == 0 || pixel[0] == 255));
+
+    // A box on the level bar covers the tilted bar on the frame as recorded.
+    let (x1, y1, x2, y2) = unrotate_bbox((50, 144, 350, 156), skew, 400, 300);
+    assert!(x1 <= 50 && x2 >= 349, "{x1} {x2}");
+    assert!(y1 <= 144 - 7 && y2 >= 156 + 7, "{y1} {y2}");
+    assert_eq!(unrotate_bbox((1, 2, 3, 4), 0.0, 400, 300), (1, 2, 3, 4));
+}
+
+#[test]
+fn noise_contrast_and_polarity() {
+    let mut speckled = GrayImage::from_pixel(20, 20, Luma([255]));
+    speckled.put_pixel(5, 5, Luma([0]));
+    assert!(denoise(&speckled).pixels().all(|pixel| pixel[0] == 255));
+
+    // A washed-out frame using only grey levels 100 to 140 is stretched over a wider range.
+    let faint = GrayImage::from_fn(256, 256, |x, y| Luma([100 + ((x * 7 + y * 13) % 41) as u8]));
+    let equalized = equalize_contrast(&faint);
+    let range = |image: &GrayImage| {
+        let values = image.pixels().map(|pixel| pixel[0]);
+        values.clone().max().unwrap() - values.min().unwrap()
+    };
+    assert!(range(&equalized) > 2 * range(&faint), "{}", range(&equalized));
+
+    // White writing on a blackboard comes out as black on white.
+    let blackboard = GrayImage::from_fn(80, 80, |x, y| Luma([if (30..34).contains(&x) && y > 10 { 230 } else { 25 }]));
+    let binary = binarize(&blackboard);
+    assert_eq!(binary.get_pixel(31, 40)[0], 0);
+    assert_eq!(binary.get_pixel(60, 40)[0], 255);
+}
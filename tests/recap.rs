@@ -0,0 +1,37 @@
+//! Checks the frames of the recap video.
+
+use image::{ImageBuffer, Rgb, RgbImage};
+use videodocparser::document_builder::Page;
+use videodocparser::recap::{RecapOptions, render_page};
+
+fn page(image: &RgbImage, timestamp: Option<f64>) -> Page<'_> {
+    Page { image, ocr: None, title: None, timestamp, end: None }
+}
+
+#[test]
+fn pages_are_labelled_with_their_own_timestamps() {
+    let image = ImageBuffer::from_pixel(160, 90, Rgb([255u8, 255, 255]));
+    let burn = RecapOptions { page_seconds: 2.0, burn_timestamps: true };
+    let plain = RecapOptions { burn_timestamps: false, ..burn.clone() };
+
+    let start = render_page(&page(&image, Some(0.0)), &burn, 160, 90);
+    let later = render_page(&page(&image, Some(125.5)), &burn, 160, 90);
+    assert_ne!(start, image);
+    assert_ne!(start, later);
+    // The same time gives the same label, whatever frame the page came from.
+    assert_eq!(render_page(&page(&image, Some(125.5)), &burn, 160, 90), later);
+    // Pages without a time, or without burning, are left as they are.
+    assert_eq!(render_page(&page(&image, None), &burn, 160, 90), image);
+    assert_eq!(render_page(&page(&image, Some(125.5)), &plain, 160, 90), image);
+}
+
+#[test]
+fn pages_of_other_sizes_are_letterboxed() {
+    let image = ImageBuffer::from_pixel(80, 90, Rgb([255u8, 255, 255]));
+    let options = RecapOptions { page_seconds: 2.0, burn_timestamps: false };
+    let frame = render_page(&page(&image, None), &options, 160, 90);
+    assert_eq!(frame.dimensions(), (160, 90));
+    assert_eq!(frame.get_pixel(5, 45), &Rgb([0, 0, 0]));
+    assert_eq!(frame.get_pixel(80, 45), &Rgb([255, 255, 255]));
+    assert_eq!(frame.get_pixel(155, 45), &Rgb([0, 0, 0]));
+}
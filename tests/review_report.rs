@@ -0,0 +1,70 @@
+//! Checks the report of low-confidence words.
+
+use image::{ImageBuffer, Rgb};
+use lopdf::Document;
+use videodocparser::document_builder::Page;
+use videodocparser::ocr::{OcrFrameResult, OcrWord};
+use videodocparser::review::{build_review, ReviewFormat, ReviewOptions, REVIEW_DIR, REVIEW_MARKDOWN, REVIEW_PDF};
+
+fn word(text: &str, bbox: (i32, i32, i32, i32), confidence: f32) -> OcrWord {
+    OcrWord { text: text.to_string(), bbox, confidence }
+}
+
+#[test]
+fn review_lists_dubious_words_with_crops() {
+    let image = ImageBuffer::from_pixel(640, 360, Rgb([255u8, 255, 255]));
+    let first = OcrFrameResult {
+        frame_index: 0,
+        words: vec![
+            word("Roadmap", (40, 40, 200, 80), 95.0),
+            word("Q3*", (210, 40, 260, 80), 41.0),
+            word("edge", (600, 330, 640, 360), 20.0),
+        ],
+        blocks: Vec::new(),
+        strategy: None,
+        timestamp: None,
+    };
+    let second = OcrFrameResult {
+        frame_index: 1,
+        words: vec![word("Thanks", (40, 40, 200, 80), 90.0)],
+        blocks: Vec::new(),
+        strategy: None,
+        timestamp: None,
+    };
+    let pages = vec![
+        Page { image: &image, ocr: Some(&first), title: None, timestamp: Some(61.0), end: None },
+        Page { image: &image, ocr: Some(&second), title: None, timestamp: Some(120.0), end: None },
+    ];
+    let mut options = ReviewOptions {
+        format: ReviewFormat::Markdown,
+        threshold: 60.0,
+        document_title: "Planning".to_string(),
+        name: None,
+    };
+
+    let dir = std::env::temp_dir().join(format!("vdp-review-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let (file, words) = build_review(&pages, &options, &dir).unwrap();
+    assert_eq!(file, format!("{}/{}", REVIEW_DIR, REVIEW_MARKDOWN));
+    assert_eq!(words, 2);
+
+    let markdown = std::fs::read_to_string(dir.join(&file)).unwrap();
+    assert!(markdown.contains("2 words below 60% confidence on 1 pages."));
+    assert!(markdown.contains("## Page 1 (00:01:01): Roadmap"));
+    assert!(markdown.contains("| ![Q3\\*](crops/word_00000.png) | Q3\\* | 41% |"));
+    assert!(!markdown.contains("Thanks") && !markdown.contains("Page 2"));
+    // The margin is clipped at the frame edge.
+    let crop = image::open(dir.join(REVIEW_DIR).join("crops/word_00001.png")).unwrap();
+    assert_eq!((crop.width(), crop.height()), (52, 42));
+
+    options.format = ReviewFormat::Pdf;
+    let (file, _) = build_review(&pages, &options, &dir).unwrap();
+    assert_eq!(file, REVIEW_PDF);
+    let document = Document::load(dir.join(REVIEW_PDF)).unwrap();
+    let text = document.extract_text(&[1]).unwrap();
+    for expected in ["Review: Planning", "Page 1 (00:01:01)", "Q3*", "(41%)", "edge"] {
+        assert!(text.contains(expected), "{expected:?} missing from {text:?}");
+    }
+
+    std::fs::remove_dir_all(dir).ok();
+}
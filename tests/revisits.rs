@@ -0,0 +1,82 @@
+//! Checks recognizing slides shown again long after they were first kept.
+
+use image::{DynamicImage, RgbImage};
+use videodocparser::bk_tree::BkTree;
+use videodocparser::frame_analyzer::{FrameAnalyzer, FrameHasher, RevisitPolicy};
+
+mod common;
+use common::slide;
+
+/// The presenter goes through slides 0, 1 and 2, flipping back to 0 and 1.
+fn talk() -> Vec<RgbImage> {
+    [0, 0, 1, 0, 0, 1, 2, 0].into_iter().map(slide).collect()
+}
+
+fn analyze(policy: Option<RevisitPolicy>) -> FrameAnalyzer {
+    let dir = std::env::temp_dir().join(format!("vdp-revisits-{}", std::process::id()));
+    common::analyze(&dir, talk(), |analyzer| {
+        if let Some(policy) = policy {
+            analyzer.set_revisit_policy(policy);
+        }
+    })
+}
+
+#[test]
+fn slides_shown_again_are_only_kept_without_a_policy() {
+    assert_eq!(analyze(None).kept_indices(), &[0, 2, 3, 5, 6, 7]);
+    for policy in [RevisitPolicy::Drop, RevisitPolicy::Merge] {
+        assert_eq!(analyze(Some(policy)).kept_indices(), &[0, 2, 6], "{:?}", policy);
+    }
+}
+
+#[test]
+fn merged_revisits_are_listed_under_the_first_page() {
+    let mut analyzer = analyze(Some(RevisitPolicy::Merge));
+    analyzer.set_frame_rate(2.0);
+    let result = analyzer.finish().unwrap();
+    assert_eq!(result.revisits, vec![vec![3, 7], vec![5], vec![]]);
+    assert_eq!(result.revisit_timestamps, vec![vec![1.5, 3.5], vec![2.5], vec![]]);
+    assert_eq!(result.removed_indices, vec![1, 3, 4, 5, 7]);
+
+    let result = analyze(Some(RevisitPolicy::Drop)).finish().unwrap();
+    assert_eq!(result.revisits, vec![Vec::<usize>::new(); 3]);
+
+    // Dropping a page later drops its revisits with it.
+    let mut result = analyze(Some(RevisitPolicy::Merge)).finish().unwrap();
+    result.retain_frames(&[false, true, true]);
+    assert_eq!(result.revisits, vec![vec![5], vec![]]);
+}
+
+#[test]
+fn cached_hash_series_replay_the_same_revisits() {
+    let dir = std::env::temp_dir().join(format!("vdp-revisits-replay-{}", std::process::id()));
+    let series = analyze(Some(RevisitPolicy::Merge)).finish().unwrap().hash_series;
+    let mut replay = FrameAnalyzer::new(0.9, &dir).unwrap();
+    replay.set_revisit_policy(RevisitPolicy::Merge);
+    replay.replay(series, None).unwrap();
+    assert_eq!(replay.kept_indices(), &[0, 2, 6]);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn the_tree_finds_the_nearest_hash_within_the_radius() {
+    let hasher = FrameHasher::default();
+    let hashes: Vec<_> = (0..6).map(|n| hasher.hash(&DynamicImage::ImageRgb8(slide(n)))).collect();
+    let mut tree = BkTree::new();
+    assert!(tree.nearest(&hashes[0], 256).unwrap().is_none());
+    for (n, hash) in hashes.iter().enumerate() {
+        tree.insert(hash.clone(), n).unwrap();
+    }
+    assert_eq!(tree.len(), 6);
+    for (n, hash) in hashes.iter().enumerate() {
+        assert_eq!(tree.nearest(hash, 0).unwrap(), Some((&n, 0)));
+    }
+
+    // A slide not in the tree finds the nearest of all, or none when it is further than the radius.
+    let query = hasher.hash(&DynamicImage::ImageRgb8(slide(7)));
+    let distances = hashes.iter().map(|hash| hash.distance(&query).unwrap());
+    let (closest, distance) = distances.enumerate().min_by_key(|&(n, d)| (d, n)).unwrap();
+    assert_eq!(tree.nearest(&query, 256).unwrap(), Some((&closest, distance)));
+    assert_eq!(tree.nearest(&query, distance).unwrap(), Some((&closest, distance)));
+    assert!(tree.nearest(&query, distance - 1).unwrap().is_none());
+}
@@ -0,0 +1,120 @@
+//! Checks that Arabic and Hebrew are kept in logical order and set right to left in the outputs.
+
+use image::{ImageBuffer, Rgb};
+use lopdf::Document;
+use videodocparser::document_builder::{
+    build_html, build_markdown, build_pdf, ConfidencePolicy, HtmlOptions, MarkdownOptions, Page, PdfOptions,
+};
+use videodocparser::layout::{group_blocks, is_rtl, page_text, TextBlock};
+use videodocparser::ocr::{OcrFrameResult, OcrWord};
+
+fn word(text: &str, bbox: (i32, i32, i32, i32)) -> OcrWord {
+    OcrWord { text: text.to_string(), bbox, confidence: 90.0 }
+}
+
+/// A Hebrew line, found left to right as the boxes lie, above a Hebrew table.
+fn hebrew_page() -> OcrFrameResult {
+    OcrFrameResult {
+        frame_index: 0,
+        words: vec![
+            word("עולם", (200, 20, 280, 50)),
+            word("שלום", (300, 20, 380, 50)),
+            word("מחיר", (20, 80, 100, 100)),
+            word("מוצר", (300, 80, 380, 100)),
+            word("10", (20, 120, 60, 140)),
+            word("תפוח", (300, 120, 380, 140)),
+            word("8", (20, 160, 40, 180)),
+            word("בננה", (300, 160, 380, 180)),
+        ],
+        blocks: Vec::new(),
+        strategy: None,
+        timestamp: None,
+    }
+}
+
+#[test]
+fn right_to_left_lines_are_read_from_the_right() {
+    assert!(is_rtl("שלום עולם"));
+    assert!(is_rtl("مرحبا Rust 2024 بالعالم"));
+    assert!(!is_rtl("Ownership مرحبا"));
+    assert!(!is_rtl("2024"));
+
+    let ocr = hebrew_page();
+    assert!(page_text(&ocr).starts_with("שלום עולם\n"), "{:?}", page_text(&ocr));
+    let tables: Vec<Vec<Vec<String>>> = group_blocks(&ocr)
+        .into_iter()
+        .filter_map(|block| match block {
+            TextBlock::Table(rows) => Some(rows),
+            _ => None,
+        })
+        .collect();
+    // The first column of a right-to-left table is its rightmost.
+    let rows = |cells: [[&str; 2]; 3]| cells.map(|row| row.map(String::from).to_vec()).to_vec();
+    assert_eq!(tables, vec![rows([["מוצר", "מחיר"], ["תפוח", "10"], ["בננה", "8"]])]);
+}
+
+#[test]
+fn markdown_and_html_set_the_direction() {
+    let image = ImageBuffer::from_pixel(400, 240, Rgb([255u8, 255, 255]));
+    let ocr = hebrew_page();
+    let pages = vec![Page { image: &image, ocr: Some(&ocr), title: None, timestamp: None, end: None }];
+    let dir = std::env::temp_dir().join(format!("vdp-rtl-{}", std::process::id()));
+
+    let options = MarkdownOptions {
+        heading_level: 2,
+        document_title: "Hebrew".to_string(),
+        video_url: None,
+        speech: Vec::new(),
+        keyword_index: false,
+        tags: Vec::new(),
+        url_links: false,
+        figures: false,
+        formulas: false,
+        new_content_only: false,
+        translations: Vec::new(),
+        confidence: ConfidencePolicy::default(),
+    };
+    build_markdown(&pages, &options, &dir.join("document.md")).unwrap();
+    let markdown = std::fs::read_to_string(dir.join("document.md")).unwrap();
+    // The line is the page's title.
+    assert!(markdown.contains("<div dir=\"rtl\">\n\n## שלום עולם\n\n</div>\n"), "{markdown}");
+
+    let options = HtmlOptions {
+        document_title: "Hebrew".to_string(),
+        video_url: None,
+        embed_images: true,
+        figures: false,
+        formulas: false,
+        speech: Vec::new(),
+    };
+    build_html(&pages, &options, &dir.join("document.html")).unwrap();
+    let html = std::fs::read_to_string(dir.join("document.html")).unwrap();
+    assert!(html.contains("<h2 dir=\"rtl\">שלום עולם</h2>"), "{html}");
+    assert!(html.contains("<table class=\"ocr-table\" dir=\"rtl\">"));
+    assert!(html.contains("<span dir=\"rtl\" style="));
+    // Numbers keep their direction.
+    assert!(html.contains("<span style=\"left:5.00%;top:50.00%"));
+    std::fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn right_to_left_words_run_leftwards_in_the_pdf_text_layer() {
+    let image = ImageBuffer::from_pixel(400, 240, Rgb([255u8, 255, 255]));
+    let ocr = hebrew_page();
+    let pages = vec![Page { image: &image, ocr: Some(&ocr), title: None, timestamp: None, end: None }];
+    let dir = std::env::temp_dir().join(format!("vdp-rtl-pdf-{}", std::process::id()));
+    let path = dir.join("document.pdf");
+    build_pdf(&pages, &PdfOptions::default(), &path).unwrap();
+
+    let document = Document::load(&path).unwrap();
+    let page_id = document.get_pages()[&1];
+    let content = String::from_utf8_lossy(&document.get_page_content(page_id).unwrap())
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    assert_eq!(content.matches(" -1 0 0 1 ").count(), 6, "{content}");
+    let text = document.extract_text(&[1]).unwrap();
+    let first = text.find("שלום").unwrap();
+    assert!(first < text.find("עולם").unwrap(), "{text:?}");
+    std::fs::remove_dir_all(dir).ok();
+}
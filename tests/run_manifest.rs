@@ -0,0 +1,33 @@
+//! Checks the provenance records of `run.json`.
+
+use videodocparser::run_manifest::{collect_artifacts, FileRecord};
+
+#[test]
+fn file_record_hashes_contents() {
+    let dir = std::env::temp_dir().join(format!("vdp-run-hash-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("input.bin");
+    std::fs::write(&path, b"abc").unwrap();
+
+    let record = FileRecord::of(&path, "input.bin".to_string()).unwrap();
+    assert_eq!(record.size, 3);
+    assert_eq!(record.sha256, "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn artifacts_are_relative_and_sorted() {
+    let dir = std::env::temp_dir().join(format!("vdp-run-artifacts-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("result/images")).unwrap();
+    std::fs::create_dir_all(dir.join("ocr")).unwrap();
+    std::fs::write(dir.join("result/images/page_002.png"), b"2").unwrap();
+    std::fs::write(dir.join("result/document.md"), b"# Doc").unwrap();
+    std::fs::write(dir.join("ocr/report.json"), b"{}").unwrap();
+    std::fs::write(dir.join("unrelated.txt"), b"skip").unwrap();
+
+    let artifacts = collect_artifacts(&dir, &["result", "ocr", "analysis", "bundle.zip"]).unwrap();
+    let paths: Vec<&str> = artifacts.iter().map(|record| record.path.as_str()).collect();
+    assert_eq!(paths, ["ocr/report.json", "result/document.md", "result/images/page_002.png"]);
+    assert_eq!(artifacts[1].size, 5);
+    std::fs::remove_dir_all(&dir).ok();
+}
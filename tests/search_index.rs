@@ -0,0 +1,43 @@
+#![cfg(feature = "search")]
+
+use image::{ImageBuffer, Rgb};
+use videodocparser::document_builder::Page;
+use videodocparser::ocr::{OcrFrameResult, OcrWord};
+use videodocparser::search::{build_index, search};
+
+fn word(text: &str) -> OcrWord {
+    OcrWord { text: text.to_string(), bbox: (0, 0, 10, 10), confidence: 90.0 }
+}
+
+#[test]
+fn search_finds_pages_by_ocr_text() {
+    let image = ImageBuffer::from_pixel(16, 16, Rgb([255u8, 255, 255]));
+    let agenda = OcrFrameResult {
+        frame_index: 0,
+        words: vec![word("Agenda"), word("overview")],
+        blocks: Vec::new(),
+        strategy: None,
+        timestamp: None,
+    };
+    let revenue = OcrFrameResult {
+        frame_index: 1,
+        words: vec![word("Quarterly"), word("revenue")],
+        blocks: Vec::new(),
+        strategy: None,
+        timestamp: None,
+    };
+    let pages = vec![
+        Page { image: &image, ocr: Some(&agenda), title: Some("Agenda".to_string()), timestamp: Some(0.0), end: None },
+        Page { image: &image, ocr: Some(&revenue), title: None, timestamp: Some(42.0), end: None },
+    ];
+
+    let dir = std::env::temp_dir().join(format!("vdp-search-{}", std::process::id()));
+    build_index(&pages, &[0, 1260], &dir).unwrap();
+
+    let hits = search(&dir, "revenue", 10).unwrap();
+    assert_eq!(hits.len(), 1);
+    assert_eq!((hits[0].page, hits[0].frame_index, hits[0].timestamp), (2, 1260, Some(42.0)));
+    assert!(hits[0].snippet.contains("revenue"), "{:?}", hits[0].snippet);
+
+    std::fs::remove_dir_all(dir).ok();
+}
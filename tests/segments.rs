@@ -0,0 +1,90 @@
+//! Checks the segments of the video each page covers.
+
+use image::RgbImage;
+use videodocparser::document_builder::{build_markdown, ConfidencePolicy, MarkdownOptions, Page};
+use videodocparser::frame_analyzer::{RevisitPolicy, Segment};
+
+mod common;
+use common::slide;
+
+/// Slide 1 interrupts slide 0, which comes back before slide 2.
+fn talk() -> Vec<RgbImage> {
+    [0, 0, 0, 1, 1, 0, 0, 2, 2, 2].into_iter().map(slide).collect()
+}
+
+fn segment(start_index: usize, end_index: usize, representative_index: usize) -> Segment {
+    Segment {
+        start_index,
+        end_index,
+        representative_index,
+        frame_count: end_index - start_index,
+        start: Some(start_index as f64 / 2.0),
+        end: Some(end_index as f64 / 2.0),
+    }
+}
+
+#[test]
+fn segments_end_at_the_next_change() {
+    let dir = std::env::temp_dir().join(format!("vdp-segments-{}", std::process::id()));
+    let analyzer = common::analyze(&dir, talk(), |analyzer| {
+        analyzer.set_revisit_policy(RevisitPolicy::Merge);
+        analyzer.set_frame_rate(2.0);
+    });
+    let mut result = analyzer.finish().unwrap();
+    assert_eq!(result.kept_indices, vec![0, 3, 7]);
+    // Slide 0 shown again ends the segment of slide 1, but does not extend its own.
+    assert_eq!(result.segments, vec![segment(0, 3, 0), segment(3, 5, 3), segment(7, 10, 7)]);
+
+    result.retain_frames(&[true, false, true]);
+    assert_eq!(result.segments, vec![segment(0, 3, 0), segment(7, 10, 7)]);
+
+    let report = std::fs::read_to_string(dir.join("analysis/frame_analysis.json")).unwrap();
+    let report: serde_json::Value = serde_json::from_str(&report).unwrap();
+    assert_eq!(report["segments"][1]["end_index"], 5);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn headings_show_the_time_range() {
+    let image = slide(0);
+    let pages = vec![
+        Page { image: &image, ocr: None, title: Some("Intro".to_string()), timestamp: Some(0.0), end: Some(65.0) },
+        Page { image: &image, ocr: None, title: Some("Agenda".to_string()), timestamp: Some(65.0), end: None },
+    ];
+    let dir = std::env::temp_dir().join(format!("vdp-segments-md-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let options = MarkdownOptions {
+        heading_level: 2,
+        document_title: "Talk".to_string(),
+        video_url: None,
+        speech: Vec::new(),
+        keyword_index: false,
+        tags: Vec::new(),
+        url_links: false,
+        figures: false,
+        formulas: false,
+        new_content_only: false,
+        translations: Vec::new(),
+        confidence: ConfidencePolicy::default(),
+    };
+    let path = dir.join("document.md");
+    build_markdown(&pages, &options, &path).unwrap();
+    let markdown = std::fs::read_to_string(&path).unwrap();
+    assert!(markdown.contains("Intro (00:00:00–00:01:05)"), "{}", markdown);
+    assert!(markdown.contains("Agenda (00:01:05)\n"), "{}", markdown);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn consensus_samples_stay_within_their_segment() {
+    let dir = std::env::temp_dir().join(format!("vdp-segments-samples-{}", std::process::id()));
+    let analyzer = common::analyze(&dir, talk(), |analyzer| analyzer.set_revisit_policy(RevisitPolicy::Merge));
+    // Slide 0 comes back after slide 1, and its frames must not vote on slide 1.
+    let mut result = analyzer.finish().unwrap();
+    assert_eq!(result.segment_samples(3), vec![vec![1, 2], vec![4], vec![8, 9]]);
+    assert_eq!(result.segment_samples(1), vec![Vec::<usize>::new(); 3]);
+
+    result.retain_frames(&[false, true, true]);
+    assert_eq!(result.segment_samples(3), vec![vec![4], vec![8, 9]]);
+    let _ = std::fs::remove_dir_all(&dir);
+}
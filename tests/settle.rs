@@ -0,0 +1,72 @@
+//! Checks waiting for the content to settle before a change starts a page.
+
+use image::{ImageBuffer, Rgb, RgbImage};
+use videodocparser::frame_analyzer::FrameAnalyzer;
+
+mod common;
+use common::slide;
+
+/// `from` faded `share` of the way to `to`.
+fn blend(from: &RgbImage, to: &RgbImage, share: f32) -> RgbImage {
+    ImageBuffer::from_fn(from.width(), from.height(), |x, y| {
+        let (a, b) = (from.get_pixel(x, y).0, to.get_pixel(x, y).0);
+        Rgb([0, 1, 2].map(|c| (a[c] as f32 * (1.0 - share) + b[c] as f32 * share).round() as u8))
+    })
+}
+
+/// Slide 0, a cross-fade to slide 4 from frame 4, slide 4 from frame 8 with one
+/// stray frame of slide 2, and slide 0 again for the last two frames, 18 and 19.
+fn talk() -> Vec<RgbImage> {
+    let (a, b) = (slide(0), slide(4));
+    let mut frames = vec![a.clone(); 4];
+    frames.extend([0.2, 0.4, 0.6, 0.8].map(|share| blend(&a, &b, share)));
+    frames.extend(vec![b.clone(); 5]);
+    frames.push(slide(2));
+    frames.extend(vec![b.clone(); 4]);
+    frames.extend(vec![a.clone(); 2]);
+    frames
+}
+
+fn analyze(settle: Option<usize>) -> FrameAnalyzer {
+    let dir = std::env::temp_dir().join(format!("vdp-settle-{}", std::process::id()));
+    common::analyze(&dir, talk(), |analyzer| {
+        if let Some(frames) = settle {
+            analyzer.set_settle_frames(frames);
+        }
+    })
+}
+
+#[test]
+fn transitions_and_stray_frames_start_pages_without_settling() {
+    let kept = analyze(None).kept_indices().to_vec();
+    assert!(kept.iter().any(|index| (4..8).contains(index)), "{:?}", kept);
+    assert!(kept.contains(&13), "{:?}", kept);
+    assert_eq!(analyze(Some(1)).kept_indices(), kept);
+}
+
+#[test]
+fn pages_start_once_the_content_has_settled() {
+    let analyzer = analyze(Some(4));
+    assert_eq!(analyzer.kept_indices(), &[0, 8]);
+    // Pages start where the content settled from, with the image it settled on.
+    assert_eq!(analyzer.source_indices(), &[3, 11]);
+
+    // The slide still settling at the end of the video starts a page too.
+    let result = analyzer.finish().unwrap();
+    assert_eq!(result.kept_indices, vec![0, 8, 18]);
+    assert_eq!(result.kept_frames, vec![slide(0), slide(4), slide(0)]);
+    assert_eq!(result.removed_indices.len(), talk().len() - 3);
+    assert_eq!(result.differences.len(), talk().len() - 1);
+}
+
+#[test]
+fn cached_hash_series_replay_the_same_settling() {
+    let dir = std::env::temp_dir().join(format!("vdp-settle-replay-{}", std::process::id()));
+    let series = analyze(Some(4)).finish().unwrap().hash_series;
+    let mut replay = FrameAnalyzer::new(0.9, &dir).unwrap();
+    replay.set_settle_frames(4);
+    replay.replay(series, None).unwrap();
+    assert_eq!(replay.kept_indices(), &[0, 8, 18]);
+    assert_eq!(replay.source_indices(), &[3, 11, 19]);
+    let _ = std::fs::remove_dir_all(&dir);
+}
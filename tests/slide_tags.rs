@@ -0,0 +1,82 @@
+//! Checks the per-slide tags and where they are written.
+
+use image::{ImageBuffer, Rgb};
+use videodocparser::document_builder::{build_markdown, ConfidencePolicy, MarkdownOptions, Page};
+use videodocparser::keywords::page_tags;
+use videodocparser::obsidian::{build_vault, VaultOptions};
+use videodocparser::ocr::{OcrFrameResult, OcrWord};
+
+fn ocr(frame_index: usize, text: &str) -> OcrFrameResult {
+    let words = text
+        .split_whitespace()
+        .enumerate()
+        .map(|(i, word)| OcrWord {
+            text: word.to_string(),
+            bbox: (20 + 70 * i as i32, 20, 80 + 70 * i as i32, 40),
+            confidence: 90.0,
+        })
+        .collect();
+    OcrFrameResult { frame_index, words, blocks: Vec::new(), strategy: None, timestamp: None }
+}
+
+#[test]
+fn slides_are_tagged_with_their_distinctive_terms() {
+    let image = ImageBuffer::from_pixel(640, 360, Rgb([255u8, 255, 255]));
+    let results = [
+        ocr(0, "Course Overview: Rust ownership"),
+        ocr(1, "Course Borrowing with lifetimes"),
+        ocr(2, "Course Lifetimes (in depth) 2024"),
+        ocr(3, "Course Traits and generics"),
+        ocr(4, "Course Summary: Ownership"),
+    ];
+    let mut pages: Vec<Page> = results
+        .iter()
+        .map(|result| Page { image: &image, ocr: Some(result), title: None, timestamp: None, end: None })
+        .collect();
+    pages[0].title = Some("Overview".to_string());
+
+    // "Course" is on every page, so it tells none of them apart; terms found on
+    // one page only rank above "ownership" and "lifetimes", found on two.
+    let tags = page_tags(&pages);
+    let expected: Vec<Vec<&str>> = vec![
+        vec!["overview", "rust", "ownership"],
+        vec!["borrowing", "lifetimes"],
+        vec!["depth", "lifetimes"],
+        vec!["generics", "traits"],
+        vec!["summary", "ownership"],
+    ];
+    assert_eq!(tags, expected);
+
+    let dir = std::env::temp_dir().join(format!("vdp-slide-tags-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let options = MarkdownOptions {
+        heading_level: 2,
+        document_title: "Rust".to_string(),
+        video_url: None,
+        speech: Vec::new(),
+        keyword_index: false,
+        tags: tags[..2].to_vec(),
+        url_links: false,
+        figures: false,
+        formulas: false,
+        new_content_only: false,
+        translations: Vec::new(),
+        confidence: ConfidencePolicy::default(),
+    };
+    let md_path = dir.join("document.md");
+    build_markdown(&pages[..2], &options, &md_path).unwrap();
+    let markdown = std::fs::read_to_string(&md_path).unwrap();
+    assert!(markdown.starts_with(
+        "---\ntitle: \"Rust\"\ntags: [\"overview\", \"rust\", \"ownership\", \"borrowing\", \"lifetimes\"]\npages:\n  \
+         - page: 1\n    title: \"Overview\"\n    tags: [\"overview\", \"rust\", \"ownership\"]\n  \
+         - page: 2\n    title: \"Course Borrowing with lifetimes\"\n    tags: [\"borrowing\", \"lifetimes\"]\n---\n\n# Rust\n"
+    ));
+
+    let vault = dir.join("vault");
+    let options =
+        VaultOptions { document_title: "Rust".to_string(), source: "rust.mp4".to_string(), video_url: None, tags };
+    let files = build_vault(&pages, &options, &vault).unwrap();
+    let note = std::fs::read_to_string(vault.join(&files[3])).unwrap();
+    assert!(note.contains("tags: [slide, \"generics\", \"traits\"]\n"));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
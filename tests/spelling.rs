@@ -0,0 +1,49 @@
+#![cfg(feature = "spellcheck")]
+//! Checks the dictionary-based correction of the OCR text.
+
+use videodocparser::ocr::{OcrFrameResult, OcrWord};
+use videodocparser::spelling::{Correction, SpellChecker};
+
+#[test]
+fn ocr_confusions_are_corrected() {
+    let dir = std::env::temp_dir().join(format!("vdp-spelling-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let dictionary = dir.join("en.dic");
+    std::fs::write(&dictionary, "7\nhello/MS\nmodel/SM\nmodern\nwould\nlearning\nclear\ndata\n").unwrap();
+    let wordlist = dir.join("domain.txt");
+    std::fs::write(&wordlist, "tokenizer\nrustc\n").unwrap();
+
+    let mut checker = SpellChecker::new();
+    assert_eq!(checker.load_dictionary(&dictionary).unwrap(), 7);
+    assert_eq!(checker.load_wordlist(&wordlist).unwrap(), 2);
+
+    assert_eq!(checker.correct("rnodel,").as_deref(), Some("model,"));
+    assert_eq!(checker.correct("He1lo").as_deref(), Some("Hello"));
+    assert_eq!(checker.correct("(vvould)").as_deref(), Some("(would)"));
+    assert_eq!(checker.correct("DATA").as_deref(), None);
+    assert_eq!(checker.correct("1O0%").as_deref(), Some("100%"));
+    assert_eq!(checker.correct("2O24").as_deref(), Some("2024"));
+    // One edit away from a single known word, and long enough to look up.
+    assert_eq!(checker.correct("learnig").as_deref(), Some("learning"));
+    assert_eq!(checker.correct("tokenlzer").as_deref(), Some("tokenizer"));
+    // Too short to guess, or not a word.
+    assert_eq!(checker.correct("dat"), None);
+    assert_eq!(checker.correct("v2.0"), None);
+    assert_eq!(checker.correct("—"), None);
+
+    let word = |text: &str| OcrWord { text: text.to_string(), bbox: (0, 0, 10, 10), confidence: 80.0 };
+    let mut results = vec![OcrFrameResult {
+        frame_index: 1,
+        words: vec![word("Clear"), word("rnodern"), word("rustc")],
+        blocks: Vec::new(),
+        strategy: None,
+        timestamp: None,
+    }];
+    let corrections = checker.correct_pages(&mut results);
+    assert_eq!(
+        corrections,
+        vec![Correction { page: 2, original: "rnodern".to_string(), corrected: "modern".to_string() }]
+    );
+    assert_eq!(results[0].words[1].text, "modern");
+    std::fs::remove_dir_all(dir).ok();
+}
@@ -0,0 +1,56 @@
+use image::{ImageBuffer, Rgb};
+use videodocparser::document_builder::Page;
+use videodocparser::subtitles::{build_subtitles, SubtitleFormat, SubtitleOptions};
+
+#[test]
+fn cues_span_each_page_until_the_next() {
+    let image = ImageBuffer::from_pixel(16, 16, Rgb([255u8, 255, 255]));
+    let pages = vec![
+        Page { image: &image, ocr: None, title: Some("Intro".to_string()), timestamp: Some(0.0), end: None },
+        Page { image: &image, ocr: None, title: Some("Agenda".to_string()), timestamp: Some(75.25), end: None },
+    ];
+    let dir = std::env::temp_dir().join(format!("vdp-subtitles-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let srt_path = dir.join("slides.srt");
+    let options = SubtitleOptions { format: SubtitleFormat::Srt, titles_only: true, duration: 3725.5 };
+    build_subtitles(&pages, &options, &srt_path).unwrap();
+    assert_eq!(
+        std::fs::read_to_string(&srt_path).unwrap(),
+        "1\n00:00:00,000 --> 00:01:15,250\nIntro\n\n2\n00:01:15,250 --> 01:02:05,500\nAgenda\n\n"
+    );
+
+    let vtt_path = dir.join("slides.vtt");
+    let options = SubtitleOptions { format: SubtitleFormat::Vtt, ..options };
+    build_subtitles(&pages, &options, &vtt_path).unwrap();
+    let vtt = std::fs::read_to_string(&vtt_path).unwrap();
+    assert!(vtt.starts_with("WEBVTT\n\n00:00:00.000 --> 00:01:15.250\nIntro\n"), "{vtt}");
+
+    std::fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn cues_end_with_their_segment() {
+    let image = ImageBuffer::from_pixel(16, 16, Rgb([255u8, 255, 255]));
+    // A clip played between the two slides leaves a gap after the first.
+    let pages = vec![
+        Page { image: &image, ocr: None, title: Some("Intro".to_string()), timestamp: Some(0.0), end: Some(30.0) },
+        Page { image: &image, ocr: None, title: Some("Agenda".to_string()), timestamp: Some(75.25), end: None },
+    ];
+    let dir = std::env::temp_dir().join(format!("vdp-subtitles-end-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let path = dir.join("slides.srt");
+    let options = SubtitleOptions { format: SubtitleFormat::Srt, titles_only: true, duration: 90.0 };
+    build_subtitles(&pages, &options, &path).unwrap();
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "1\n00:00:00,000 --> 00:00:30,000\nIntro\n\n2\n00:01:15,250 --> 00:01:30,000\nAgenda\n\n"
+    );
+
+    let untimed = [Page { image: &image, ocr: None, title: None, timestamp: None, end: None }];
+    let error = build_subtitles(&untimed, &options, &path).unwrap_err();
+    assert!(error.to_string().contains("Page timestamps are unavailable"), "{error}");
+
+    std::fs::remove_dir_all(dir).ok();
+}
@@ -0,0 +1,78 @@
+//! Checks that tabular slides come out as tables.
+
+use image::{ImageBuffer, Rgb};
+use videodocparser::document_builder::{build_markdown, table_csv_path, ConfidencePolicy, MarkdownOptions, Page};
+use videodocparser::layout::{group_blocks, TextBlock};
+use videodocparser::ocr::{OcrFrameResult, OcrWord};
+
+/// Words of a line at `row`, given as (left edge, text) cells whose words are
+/// 10 px apart, in a proportional font.
+fn row(row: i32, cells: &[(i32, &str)]) -> Vec<OcrWord> {
+    let mut words = Vec::new();
+    for &(left, text) in cells {
+        let mut x = left;
+        for word in text.split_whitespace() {
+            let width = word.len() as i32 * 9 + 12;
+            words.push(OcrWord { text: word.to_string(), bbox: (x, 40 * row, x + width, 40 * row + 20), confidence: 90.0 });
+            x += width + 10;
+        }
+    }
+    words
+}
+
+#[test]
+fn aligned_cells_become_a_table() {
+    let mut words = row(0, &[(20, "Quarterly results")]);
+    words.extend(row(1, &[(20, "Region"), (250, "Revenue"), (450, "Growth")]));
+    words.extend(row(2, &[(20, "North America"), (250, "1,200"), (450, "+4%")]));
+    words.extend(row(3, &[(20, "Europe"), (450, "+2%")]));
+    words.extend(row(4, &[(20, "Asia \"Pacific\""), (250, "800"), (450, "+9%")]));
+    words.extend(row(5, &[(20, "Figures in millions")]));
+    let ocr = OcrFrameResult { frame_index: 0, words, blocks: Vec::new(), strategy: None, timestamp: None };
+
+    let blocks = group_blocks(&ocr);
+    assert_eq!(blocks.len(), 3);
+    let TextBlock::Table(rows) = &blocks[1] else { panic!("expected a table, got {:?}", blocks[1]) };
+    assert_eq!(
+        rows,
+        &[
+            ["Region", "Revenue", "Growth"],
+            ["North America", "1,200", "+4%"],
+            ["Europe", "", "+2%"],
+            ["Asia \"Pacific\"", "800", "+9%"],
+        ]
+    );
+    assert!(matches!(&blocks[2], TextBlock::Prose(lines) if lines.len() == 1));
+
+    let image = ImageBuffer::from_pixel(640, 360, Rgb([255u8, 255, 255]));
+    let title = Some("Quarterly results".to_string());
+    let pages = vec![Page { image: &image, ocr: Some(&ocr), title, timestamp: None, end: None }];
+    let options = MarkdownOptions {
+        heading_level: 2,
+        document_title: "Report".to_string(),
+        video_url: None,
+        speech: Vec::new(),
+        keyword_index: false,
+        tags: Vec::new(),
+        url_links: false,
+        figures: false,
+        formulas: false,
+        new_content_only: false,
+        translations: Vec::new(),
+        confidence: ConfidencePolicy::default(),
+    };
+    let dir = std::env::temp_dir().join(format!("vdp-tables-{}", std::process::id()));
+    let md_path = dir.join("document.md");
+    build_markdown(&pages, &options, &md_path).unwrap();
+    let markdown = std::fs::read_to_string(&md_path).unwrap();
+    assert!(markdown.contains(
+        "| Region | Revenue | Growth |\n| --- | --- | --- |\n| North America | 1,200 | \\+4% |\n| Europe |  | \\+2% |\n\
+         | Asia \"Pacific\" | 800 | \\+9% |\n\n[CSV](tables/page_001_table_1.csv)\n"
+    ));
+    let csv = std::fs::read_to_string(dir.join(table_csv_path(0, 0))).unwrap();
+    assert_eq!(
+        csv,
+        "Region,Revenue,Growth\r\nNorth America,\"1,200\",+4%\r\nEurope,,+2%\r\n\"Asia \"\"Pacific\"\"\",800,+9%\r\n"
+    );
+    std::fs::remove_dir_all(dir).ok();
+}
@@ -0,0 +1,43 @@
+#![cfg(feature = "templates")]
+use image::{ImageBuffer, Rgb};
+use videodocparser::document_builder::Page;
+use videodocparser::ocr::{OcrFrameResult, OcrWord};
+use videodocparser::template::{render, TemplateOptions};
+
+#[test]
+fn template_sees_pages_and_confidence() {
+    let dir = std::env::temp_dir().join(format!("vdp-template-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let template_path = dir.join("slides.hbs");
+    std::fs::write(
+        &template_path,
+        "# {{title}}\n{{#each pages}}## {{number}}. {{heading}} ({{time}})\n\
+         {{#if (lt mean_confidence 60)}}> low confidence\n{{/if}}{{text}}\n{{/each}}",
+    )
+    .unwrap();
+
+    let image = ImageBuffer::from_pixel(32, 24, Rgb([255u8, 255, 255]));
+    let ocr = OcrFrameResult {
+        frame_index: 0,
+        words: vec![OcrWord { text: "<Agenda>".to_string(), bbox: (1, 1, 20, 8), confidence: 40.0 }],
+        blocks: Vec::new(),
+        strategy: None,
+        timestamp: None,
+    };
+    let pages = vec![Page { image: &image, ocr: Some(&ocr), title: None, timestamp: Some(62.0), end: None }];
+    let options = TemplateOptions {
+        document_title: "Talk".to_string(),
+        source: "talk.mp4".to_string(),
+        video_url: None,
+        html: false,
+        embed_images: false,
+    };
+    let output_path = dir.join("document.md");
+    render(&pages, &options, &template_path, &output_path).unwrap();
+
+    let markdown = std::fs::read_to_string(&output_path).unwrap();
+    assert_eq!(markdown, "# Talk\n## 1. <Agenda> (00:01:02)\n> low confidence\n<Agenda>\n");
+    assert!(dir.join("images/page_001.png").exists());
+
+    std::fs::remove_dir_all(dir).ok();
+}
@@ -0,0 +1,31 @@
+//! Checks where the Tesseract language packs are looked for.
+
+use videodocparser::ocr::{find_tessdata_dir, tessdata_candidates};
+
+#[test]
+fn tessdata_prefix_comes_first_and_explicit_dir_wins() {
+    let dir = std::env::temp_dir().join(format!("vdp-tessdata-{}", std::process::id()));
+    let prefix_packs = dir.join("prefix").join("tessdata");
+    let explicit = dir.join("custom");
+    std::fs::create_dir_all(&prefix_packs).unwrap();
+    std::fs::create_dir_all(&explicit).unwrap();
+    std::fs::write(prefix_packs.join("eng.traineddata"), b"").unwrap();
+    std::fs::write(explicit.join("deu.traineddata"), b"").unwrap();
+
+    // SAFETY: this is the only test in this binary touching the environment.
+    unsafe { std::env::set_var("TESSDATA_PREFIX", dir.join("prefix")) };
+    let candidates = tessdata_candidates();
+    assert_eq!(candidates[..2], [dir.join("prefix"), prefix_packs.clone()]);
+    assert!(candidates.iter().any(|candidate| candidate.ends_with("tesseract-ocr/5/tessdata")));
+
+    // The packs sit in the older layout's subdirectory, so the prefix itself is passed over.
+    assert_eq!(find_tessdata_dir(None, "eng").unwrap(), prefix_packs);
+    assert_eq!(find_tessdata_dir(Some(&explicit), "deu").unwrap(), explicit);
+    let error = find_tessdata_dir(Some(&explicit), "eng").unwrap_err().to_string();
+    assert!(error.contains("eng"), "{error}");
+    assert!(find_tessdata_dir(Some(&dir.join("missing")), "eng").is_err());
+    let error = find_tessdata_dir(None, "vdpx").unwrap_err().to_string();
+    assert!(error.contains("searched") && error.contains("TESSDATA_PREFIX"), "{error}");
+
+    std::fs::remove_dir_all(dir).ok();
+}
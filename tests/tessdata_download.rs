@@ -0,0 +1,20 @@
+#![cfg(feature = "download")]
+//! Checks the verification of downloaded language packs.
+
+use videodocparser::tessdata::{git_blob_sha1, repository, verify_pack};
+
+#[test]
+fn packs_are_checked_against_their_blob_hash() {
+    // `git hash-object` of these contents.
+    assert_eq!(git_blob_sha1(b""), "e69de29bb2d1d6434b8b29ae775ad8c2e48c5391");
+    assert_eq!(git_blob_sha1(b"hello world\n"), "3b18e512dba79e4c8300dd08aeb37f8e728b8dad");
+
+    verify_pack(b"hello world\n", 12, "3B18E512DBA79E4C8300DD08AEB37F8E728B8DAD").unwrap();
+    let error = verify_pack(b"hello world!", 12, "3b18e512dba79e4c8300dd08aeb37f8e728b8dad").unwrap_err();
+    assert!(error.to_string().contains("Checksum mismatch"), "{error}");
+    assert!(verify_pack(b"hello", 12, "3b18e512dba79e4c8300dd08aeb37f8e728b8dad").is_err());
+
+    assert_eq!(repository("fast").unwrap(), "tessdata_fast");
+    assert_eq!(repository("best").unwrap(), "tessdata_best");
+    assert!(repository("legacy").is_err());
+}
@@ -0,0 +1,40 @@
+//! Checks telling frames with text from empty ones before OCR.
+
+use image::{ImageBuffer, Rgb};
+use videodocparser::text_presence::{has_text, stroke_cells};
+
+/// A white frame with a row of dark letter-like outlines, 6 by 10 pixels and
+/// 2 pixels thick, starting at `(left, top)`.
+fn lettered(count: u32, left: u32, top: u32) -> ImageBuffer<Rgb<u8>, Vec<u8>> {
+    ImageBuffer::from_fn(320, 180, |x, y| {
+        let letter = x.checked_sub(left).map(|dx| (dx / 9, dx % 9));
+        let stroke = match (letter, y.checked_sub(top)) {
+            (Some((n, dx)), Some(dy)) if n < count && dx < 6 && dy < 10 => {
+                !(2..4).contains(&dx) || !(2..8).contains(&dy)
+            }
+            _ => false,
+        };
+        if stroke { Rgb([20, 20, 20]) } else { Rgb([250, 250, 250]) }
+    })
+}
+
+#[test]
+fn frames_with_letters_have_text() {
+    let line = lettered(12, 40, 60);
+    assert!(stroke_cells(&line) >= 2);
+    assert!(has_text(&line));
+}
+
+#[test]
+fn empty_frames_have_none() {
+    let blank = ImageBuffer::from_pixel(320, 180, Rgb([250u8, 250, 250]));
+    assert_eq!(stroke_cells(&blank), 0);
+    assert!(!has_text(&blank));
+
+    // A soft gradient, as in out-of-focus video, has no sharp steps.
+    let gradient = ImageBuffer::from_fn(320, 180, |x, y| Rgb([(x / 2) as u8, (y / 2) as u8, 128]));
+    assert!(!has_text(&gradient));
+
+    // A single letter is more likely a speck or the mouse pointer.
+    assert!(!has_text(&lettered(1, 1, 1)));
+}
@@ -0,0 +1,16 @@
+#![cfg(feature = "whisper")]
+
+use videodocparser::transcribe::whisper_language;
+
+#[test]
+fn ocr_language_maps_to_whisper_code() {
+    assert_eq!(whisper_language("eng"), "en");
+    assert_eq!(whisper_language("por"), "pt");
+    assert_eq!(whisper_language("chi_sim"), "zh");
+}
+
+#[test]
+fn several_or_unknown_languages_are_detected() {
+    assert_eq!(whisper_language("eng+por"), "auto");
+    assert_eq!(whisper_language("lat"), "auto");
+}
@@ -0,0 +1,189 @@
+//! Checks reading a transcript and placing its speech after each slide.
+
+use image::{ImageBuffer, Rgb};
+use lopdf::Document;
+use videodocparser::document_builder::{
+    build_html, build_markdown, build_pdf, ConfidencePolicy, HtmlOptions, MarkdownOptions, Page, PdfOptions,
+};
+use videodocparser::transcript::{align, find_sidecar, packet_text, parse, speech_by_page, CaptionCodec, Cue};
+
+const SRT: &str = "\u{feff}1\r\n00:00:01,000 --> 00:00:04,500\r\nWelcome to the\r\ncourse.\r\n\r\n\
+2\r\n00:00:04,500 --> 00:00:06,000\r\nWelcome to the course.\r\n\r\n\
+3\r\n00:01:10,250 --> 00:01:12,000\r\nNow the results.\r\n";
+
+const VTT: &str = "WEBVTT\n\nNOTE recorded live\n\n\
+intro\n00:01.000 --> 00:04.500 align:start\n<v Ada>Welcome</v> to the course.\n\n\
+01:10.250 --> 01:12.000\nNow the <c.yellow>results</c>.\n";
+
+#[test]
+fn srt_and_vtt_give_the_same_cues() {
+    let expected = vec![
+        Cue { start: 1.0, end: 4.5, text: "Welcome to the course.".to_string(), speaker: None },
+        Cue { start: 70.25, end: 72.0, text: "Now the results.".to_string(), speaker: None },
+    ];
+    let srt = parse(SRT);
+    assert_eq!(srt.len(), 3);
+    assert_eq!(srt[0], expected[0]);
+    assert_eq!(srt[2], expected[1]);
+    assert_eq!(parse(VTT), expected);
+
+    // The repeated caption is dropped; speech before the first slide goes to it.
+    let speech = speech_by_page(&srt, &[2.0, 60.0, 120.0]);
+    assert_eq!(speech, vec!["Welcome to the course.", "Now the results.", ""]);
+}
+
+#[test]
+fn speech_follows_its_slide() {
+    let image = ImageBuffer::from_pixel(320, 180, Rgb([255u8, 255, 255]));
+    let pages: Vec<Page> = (0..3)
+        .map(|i| Page { image: &image, ocr: None, title: None, timestamp: Some(i as f64 * 60.0), end: None })
+        .collect();
+    let long_speech = "and so on ".repeat(600);
+    let speech = vec!["Welcome to the course.".to_string(), String::new(), long_speech];
+
+    let dir = std::env::temp_dir().join(format!("vdp-transcript-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let options = MarkdownOptions {
+        heading_level: 2,
+        document_title: "Course".to_string(),
+        video_url: None,
+        speech: speech.clone(),
+        keyword_index: false,
+        tags: Vec::new(),
+        url_links: false,
+        figures: false,
+        formulas: false,
+        new_content_only: false,
+        translations: Vec::new(),
+        confidence: ConfidencePolicy::default(),
+    };
+    let md_path = dir.join("document.md");
+    build_markdown(&pages, &options, &md_path).unwrap();
+    let markdown = std::fs::read_to_string(&md_path).unwrap();
+    let welcome = markdown.find("> Welcome to the course.").unwrap();
+    assert!(markdown.find("## Page 1").unwrap() < welcome && welcome < markdown.find("## Page 2").unwrap());
+    assert_eq!(markdown.matches("\n> ").count(), 2);
+
+    // Slide 1, its transcript page, slide 2, slide 3, and its two transcript pages.
+    let options = PdfOptions { speech, table_of_contents: true, ..Default::default() };
+    let pdf_path = dir.join("document.pdf");
+    build_pdf(&pages, &options, &pdf_path).unwrap();
+    let document = Document::load(&pdf_path).unwrap();
+    assert_eq!(document.get_pages().len(), 1 + 6);
+    let notes = document.extract_text(&[3]).unwrap();
+    assert!(notes.contains("Transcript: 00:00:00 - Page 1") && notes.contains("Welcome to the course."));
+    assert!(document.extract_text(&[6]).unwrap().contains("Transcript: 00:02:00 - Page 3"));
+    // The contents list the slides at their positions in the document.
+    let contents = document.extract_text(&[1]).unwrap();
+    for entry in ["Page 12", "Page 24", "Page 35"] {
+        assert!(contents.contains(entry), "{entry:?} missing from {contents:?}");
+    }
+
+    std::fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn html_sections_end_with_speaker_notes() {
+    let image = ImageBuffer::from_pixel(320, 180, Rgb([255u8, 255, 255]));
+    let pages: Vec<Page> = (0..3)
+        .map(|i| Page { image: &image, ocr: None, title: None, timestamp: Some(i as f64 * 60.0), end: None })
+        .collect();
+    let dir = std::env::temp_dir().join(format!("vdp-transcript-html-{}", std::process::id()));
+    let options = HtmlOptions {
+        document_title: "Course".to_string(),
+        video_url: None,
+        embed_images: true,
+        figures: false,
+        formulas: false,
+        speech: vec!["Welcome to the <course>.".to_string(), "  ".to_string(), "שלום לכולם".to_string()],
+    };
+    let html_path = dir.join("document.html");
+    build_html(&pages, &options, &html_path).unwrap();
+    let html = std::fs::read_to_string(&html_path).unwrap();
+
+    let sections: Vec<&str> = html.split("<section id=").skip(1).collect();
+    assert_eq!(sections.len(), 3);
+    let notes = "<aside class=\"notes\" aria-label=\"Speaker notes\"";
+    assert!(sections[0].contains(&format!("{notes}>Welcome to the &lt;course&gt;.</aside>")));
+    // Blank speech leaves no notes.
+    assert!(!sections[1].contains("class=\"notes\""));
+    assert!(sections[2].contains(&format!("{notes} dir=\"rtl\">")));
+
+    std::fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn subtitle_packets_lose_their_markup() {
+    assert_eq!(packet_text(CaptionCodec::Text, b"<i>Welcome</i> to\nthe course."), "Welcome to the course.");
+    assert_eq!(
+        packet_text(CaptionCodec::Ass, b"3,0,Default,,0,0,0,,{\\i1}Now{\\i0} the\\Nresults, finally."),
+        "Now the results, finally."
+    );
+    assert_eq!(packet_text(CaptionCodec::MovText, b"\x00\x05Hello\x00\x00\x00\x0cstyl"), "Hello");
+}
+
+#[test]
+fn sidecar_prefers_the_untagged_file() {
+    let dir = std::env::temp_dir().join(format!("vdp-sidecar-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let video = dir.join("talk.mp4");
+    assert_eq!(find_sidecar(&video), None);
+
+    std::fs::write(dir.join("talk2.srt"), SRT).unwrap();
+    std::fs::write(dir.join("talk.pt.vtt"), VTT).unwrap();
+    std::fs::write(dir.join("talk.en.srt"), SRT).unwrap();
+    assert_eq!(find_sidecar(&video), Some(dir.join("talk.en.srt")));
+
+    std::fs::write(dir.join("talk.srt"), SRT).unwrap();
+    assert_eq!(find_sidecar(&video), Some(dir.join("talk.srt")));
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn speaker_changes_are_labelled() {
+    let cue = |start: f64, text: &str, speaker: &str| Cue {
+        start,
+        end: start + 2.0,
+        text: text.to_string(),
+        speaker: Some(speaker.to_string()),
+    };
+    let cues = [
+        cue(1.0, "Any questions?", "Speaker 1"),
+        cue(3.0, "Yes, about borrowing.", "Speaker 2"),
+        cue(5.0, "Go ahead.", "Speaker 1"),
+        cue(6.0, "Thanks.", "Speaker 1"),
+        cue(12.0, "Next slide.", "Speaker 1"),
+    ];
+    let speech = speech_by_page(&cues, &[0.0, 10.0]);
+    assert_eq!(
+        speech,
+        [
+            "Speaker 1: Any questions? Speaker 2: Yes, about borrowing. Speaker 1: Go ahead. Thanks.",
+            "Speaker 1: Next slide.",
+        ]
+    );
+}
+
+#[test]
+fn cues_go_to_the_slide_shown_for_most_of_them() {
+    let cue = |start: f64, end: f64, text: &str| Cue { start, end, text: text.to_string(), speaker: None };
+    let cues = [
+        cue(0.0, 1.0, "Before the first slide."),
+        // Starts on slide 1 but is mostly spoken over slide 2.
+        cue(9.0, 15.0, "Moving on to ownership."),
+        // Equal overlap goes to the earlier slide.
+        cue(18.0, 22.0, "Halfway."),
+        cue(25.0, 25.0, "Instant."),
+    ];
+    let aligned = align(&cues, &[2.0, 10.0, 20.0]);
+    let texts: Vec<Vec<&str>> =
+        aligned.iter().map(|page| page.iter().map(|cue| cue.text.as_str()).collect()).collect();
+    assert_eq!(
+        texts,
+        [vec!["Before the first slide."], vec!["Moving on to ownership.", "Halfway."], vec!["Instant."]]
+    );
+
+    let json = serde_json::to_value(&aligned[1][0]).unwrap();
+    assert_eq!(json, serde_json::json!({ "start": 9.0, "end": 15.0, "text": "Moving on to ownership." }));
+}
@@ -0,0 +1,62 @@
+//! Checks translating the OCR text and printing it after each slide.
+
+use anyhow::{bail, Result};
+use image::{ImageBuffer, Rgb};
+use lopdf::Document;
+use videodocparser::document_builder::{build_markdown, build_pdf, ConfidencePolicy, MarkdownOptions, Page, PdfOptions};
+use videodocparser::translate::{translate_pages, Translator};
+
+/// Translates by uppercasing, and fails on text mentioning an error.
+struct Shouting;
+
+impl Translator for Shouting {
+    fn translate(&self, text: &str, language: &str) -> Result<String> {
+        assert_eq!(language, "English");
+        if text.contains("error") {
+            bail!("backend error");
+        }
+        Ok(text.to_uppercase())
+    }
+}
+
+#[test]
+fn translations_follow_each_slide() {
+    let texts = ["所有権\nrecog-\nnition".to_string(), String::new(), "an error".to_string()];
+    let translations = translate_pages(&Shouting, &texts, "English");
+    assert_eq!(translations, vec![Some("所有権\nRECOGNITION".to_string()), None, None]);
+
+    let image = ImageBuffer::from_pixel(320, 180, Rgb([255u8, 255, 255]));
+    let pages: Vec<Page> =
+        (0..2).map(|_| Page { image: &image, ocr: None, title: None, timestamp: None, end: None }).collect();
+    let translated = vec!["Ownership\nBorrowing".to_string(), String::new()];
+    let dir = std::env::temp_dir().join(format!("vdp-translate-{}", std::process::id()));
+    let options = MarkdownOptions {
+        heading_level: 2,
+        document_title: "Rust".to_string(),
+        video_url: None,
+        speech: Vec::new(),
+        keyword_index: false,
+        tags: Vec::new(),
+        url_links: false,
+        figures: false,
+        formulas: false,
+        new_content_only: false,
+        translations: translated.clone(),
+        confidence: ConfidencePolicy::default(),
+    };
+    build_markdown(&pages, &options, &dir.join("document.md")).unwrap();
+    let markdown = std::fs::read_to_string(dir.join("document.md")).unwrap();
+    assert!(markdown.contains("![Page 1](images/page_001.png)\n\n*Translation:*  \nOwnership  \nBorrowing\n\n## Page 2"));
+    assert_eq!(markdown.matches("*Translation:*").count(), 1);
+
+    // Slide 1, its translation page, and slide 2.
+    let options = PdfOptions { translations: translated, ..Default::default() };
+    let pdf_path = dir.join("document.pdf");
+    build_pdf(&pages, &options, &pdf_path).unwrap();
+    let document = Document::load(&pdf_path).unwrap();
+    assert_eq!(document.get_pages().len(), 3);
+    let notes = document.extract_text(&[2]).unwrap();
+    assert!(notes.contains("Translation: Page 1") && notes.contains("Borrowing"), "{notes:?}");
+
+    std::fs::remove_dir_all(dir).ok();
+}
@@ -0,0 +1,21 @@
+//! Checks how the interface language is found from the locale variables.
+
+use videodocparser::i18n::Lang;
+
+fn locale(vars: &[(&str, &str)]) -> Lang {
+    Lang::from_locale(|name| vars.iter().find(|(var, _)| *var == name).map(|(_, value)| value.to_string()))
+}
+
+#[test]
+fn lc_all_and_lc_messages_take_precedence_over_lang() {
+    assert_eq!(locale(&[("LANG", "de_DE.UTF-8")]), Lang::De);
+    assert_eq!(locale(&[("LANG", "de_DE.UTF-8"), ("LC_MESSAGES", "fr_FR.UTF-8")]), Lang::Fr);
+    assert_eq!(locale(&[("LANG", "de_DE.UTF-8"), ("LC_MESSAGES", "fr_FR"), ("LC_ALL", "pt_BR.UTF-8")]), Lang::Pt);
+}
+
+#[test]
+fn empty_variables_are_skipped_and_c_means_english() {
+    assert_eq!(locale(&[("LC_ALL", ""), ("LANG", "es_ES.UTF-8")]), Lang::Es);
+    assert_eq!(locale(&[("LC_ALL", "C"), ("LANG", "es_ES.UTF-8")]), Lang::En);
+    assert_eq!(locale(&[]), Lang::En);
+}
@@ -0,0 +1,78 @@
+//! Checks reading frames enlarged by a super-resolution model, with boxes mapped back onto the frames.
+
+use anyhow::{Result, bail};
+use image::imageops::FilterType;
+use image::{DynamicImage, GenericImageView, ImageBuffer, Rgb, RgbImage};
+use videodocparser::ocr::{OcrEngine, OcrWord, Recognition, recognize_frames};
+use videodocparser::upscale::{Upscaled, Upscaler};
+
+/// Reads the box around the dark pixels of an image as one word, named after
+/// its size and colour type.
+struct DarkBox;
+
+impl OcrEngine for DarkBox {
+    fn name(&self) -> &'static str {
+        "dark-box"
+    }
+
+    fn recognize(&self, image: &DynamicImage) -> Result<Recognition> {
+        let dark: Vec<(u32, u32)> =
+            image.pixels().filter(|(_, _, pixel)| pixel.0[0] < 128).map(|(x, y, _)| (x, y)).collect();
+        if dark.is_empty() {
+            bail!("nothing to read");
+        }
+        let x1 = dark.iter().map(|p| p.0).min().unwrap() as i32;
+        let y1 = dark.iter().map(|p| p.1).min().unwrap() as i32;
+        let x2 = dark.iter().map(|p| p.0).max().unwrap() as i32 + 1;
+        let y2 = dark.iter().map(|p| p.1).max().unwrap() as i32 + 1;
+        let text = format!("{}x{} {:?}", image.width(), image.height(), image.color());
+        let word = OcrWord { text, bbox: (x1, y1, x2, y2), confidence: 90.0 };
+        Ok(Recognition { words: vec![word], ..Recognition::default() })
+    }
+}
+
+/// Enlarges images `factor` times by repeating their pixels.
+struct Nearest {
+    factor: u32,
+}
+
+impl Upscaler for Nearest {
+    fn name(&self) -> &'static str {
+        "nearest"
+    }
+
+    fn upscale(&self, image: &RgbImage) -> Result<RgbImage> {
+        let (width, height) = (image.width() * self.factor, image.height() * self.factor);
+        Ok(image::imageops::resize(image, width, height, FilterType::Nearest))
+    }
+}
+
+/// A white frame with a dark square at (10, 10).
+fn frame() -> ImageBuffer<Rgb<u8>, Vec<u8>> {
+    ImageBuffer::from_fn(60, 40, |x, y| {
+        if (10..20).contains(&x) && (10..20).contains(&y) { Rgb([0, 0, 0]) } else { Rgb([255, 255, 255]) }
+    })
+}
+
+#[test]
+fn frames_are_read_enlarged_with_boxes_on_the_frame() {
+    let engine = Upscaled::new(Box::new(DarkBox), Box::new(Nearest { factor: 4 }), 4);
+    let frames = [frame()];
+    let refs: Vec<_> = frames.iter().collect();
+    let results = recognize_frames(&engine, &refs, &[], &[], None);
+    assert_eq!(results[0].words[0].text, "240x160 Rgb8");
+    assert_eq!(results[0].words[0].bbox, (10, 10, 20, 20));
+
+    // Preprocessed frames stay grayscale.
+    let results = recognize_frames(&engine, &refs, &[], &["binarize".to_string()], None);
+    assert_eq!(results[0].words[0].text, "240x160 L8");
+    assert_eq!(results[0].words[0].bbox, (10, 10, 20, 20));
+}
+
+#[test]
+fn models_enlarging_by_another_factor_are_resized() {
+    let engine = Upscaled::new(Box::new(DarkBox), Box::new(Nearest { factor: 4 }), 2);
+    let recognition = engine.recognize(&DynamicImage::ImageRgb8(frame())).unwrap();
+    assert_eq!(recognition.words[0].text, "120x80 Rgb8");
+    assert_eq!(recognition.words[0].bbox, (10, 10, 20, 20));
+}
@@ -0,0 +1,110 @@
+//! Checks the URLs recognized in the OCR text and where they are linked.
+
+use image::{ImageBuffer, Rgb};
+use lopdf::Document;
+use videodocparser::document_builder::{build_markdown, build_pdf, ConfidencePolicy, MarkdownOptions, Page, PdfOptions};
+use videodocparser::links::{collect_links, normalize_url, LinkEntry, LinkOccurrence};
+use videodocparser::ocr::{OcrFrameResult, OcrWord};
+
+fn ocr(frame_index: usize, text: &str) -> OcrFrameResult {
+    let words = text
+        .split_whitespace()
+        .enumerate()
+        .map(|(i, word)| OcrWord {
+            text: word.to_string(),
+            bbox: (20 + 70 * i as i32, 20, 80 + 70 * i as i32, 40),
+            confidence: 90.0,
+        })
+        .collect();
+    OcrFrameResult { frame_index, words, blocks: Vec::new(), strategy: None, timestamp: None }
+}
+
+#[test]
+fn urls_are_validated_and_normalized() {
+    assert_eq!(normalize_url("HTTPS://Example.COM/Path_A").as_deref(), Some("https://example.com/Path_A"));
+    assert_eq!(normalize_url("(www.rust-lang.org).").as_deref(), Some("https://www.rust-lang.org"));
+    assert_eq!(normalize_url("github.com/rust-lang/rust,").as_deref(), Some("https://github.com/rust-lang/rust"));
+    assert_eq!(
+        normalize_url("http://localhost.dev:8080/a_(b)").as_deref(),
+        Some("http://localhost.dev:8080/a_(b)")
+    );
+    for word in ["node.js", "slides.pdf", "e.g.", "http://", "https://-bad-.com", "www.example.c0m", "v1.2.3"] {
+        assert_eq!(normalize_url(word), None, "{word:?}");
+    }
+}
+
+#[test]
+fn links_are_listed_and_clickable() {
+    let image = ImageBuffer::from_pixel(640, 360, Rgb([255u8, 255, 255]));
+    let results = [
+        ocr(0, "Docs: crates.io and www.rust-lang.org."),
+        ocr(1, "No links here"),
+        ocr(2, "See https://crates.io twice: crates.io"),
+    ];
+    let pages: Vec<Page> = results
+        .iter()
+        .enumerate()
+        .map(|(i, result)| {
+            Page { image: &image, ocr: Some(result), title: None, timestamp: Some(i as f64 * 30.0), end: None }
+        })
+        .collect();
+
+    let links = collect_links(&pages);
+    assert_eq!(
+        links,
+        vec![
+            LinkEntry {
+                url: "https://crates.io".to_string(),
+                occurrences: vec![
+                    LinkOccurrence { page: 1, timestamp: Some(0.0) },
+                    LinkOccurrence { page: 3, timestamp: Some(60.0) },
+                ],
+            },
+            LinkEntry {
+                url: "https://www.rust-lang.org".to_string(),
+                occurrences: vec![LinkOccurrence { page: 1, timestamp: Some(0.0) }],
+            },
+        ]
+    );
+
+    let dir = std::env::temp_dir().join(format!("vdp-url-links-{}", std::process::id()));
+    let pdf_path = dir.join("document.pdf");
+    build_pdf(&pages[..1], &PdfOptions { url_links: true, ..Default::default() }, &pdf_path).unwrap();
+    let document = Document::load(&pdf_path).unwrap();
+    let page = document.get_dictionary(document.get_pages()[&1]).unwrap();
+    let uris: Vec<Vec<u8>> = page
+        .get(b"Annots")
+        .unwrap()
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|annotation| {
+            let annotation = document.get_dictionary(annotation.as_reference().unwrap()).unwrap();
+            let action = annotation.get(b"A").unwrap().as_dict().unwrap();
+            action.get(b"URI").unwrap().as_str().unwrap().to_vec()
+        })
+        .collect();
+    assert_eq!(uris, vec![b"https://crates.io".to_vec(), b"https://www.rust-lang.org".to_vec()]);
+
+    let options = MarkdownOptions {
+        heading_level: 2,
+        document_title: "Links".to_string(),
+        video_url: None,
+        speech: Vec::new(),
+        keyword_index: false,
+        tags: Vec::new(),
+        url_links: true,
+        figures: false,
+        formulas: false,
+        new_content_only: false,
+        translations: Vec::new(),
+        confidence: ConfidencePolicy::default(),
+    };
+    let md_path = dir.join("document.md");
+    build_markdown(&pages[..1], &options, &md_path).unwrap();
+    let markdown = std::fs::read_to_string(&md_path).unwrap();
+    assert!(markdown.contains(
+        "Docs: [crates.io](<https://crates.io>) and [www.rust-lang.org.](<https://www.rust-lang.org>)"
+    ));
+    std::fs::remove_dir_all(dir).ok();
+}
@@ -0,0 +1,20 @@
+//! Checks the Tesseract config file that loads the user words list.
+
+use videodocparser::ocr::{ENGINE_CONFIG_NAME, write_engine_config};
+
+#[test]
+fn user_words_are_loaded_through_a_config_file() {
+    let dir = std::env::temp_dir().join(format!("vdp-user-words-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    assert_eq!(write_engine_config(None, &dir.join("ocr")).unwrap(), None);
+
+    let words = dir.join("medical words.txt");
+    std::fs::write(&words, "tachycardia\nbradycardia\n").unwrap();
+    let path = write_engine_config(Some(&words), &dir.join("ocr")).unwrap().unwrap();
+    assert_eq!(path, dir.join("ocr").join(ENGINE_CONFIG_NAME));
+    let absolute = std::fs::canonicalize(&words).unwrap();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), format!("user_words_file {}\n", absolute.display()));
+
+    assert!(write_engine_config(Some(&dir.join("missing.txt")), &dir.join("ocr")).is_err());
+    std::fs::remove_dir_all(dir).ok();
+}
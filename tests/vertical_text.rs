@@ -0,0 +1,86 @@
+//! Checks vertical lines and Chinese and Japanese text in layout and the PDF text layer.
+
+use image::{ImageBuffer, Rgb};
+use lopdf::Document;
+use videodocparser::document_builder::{build_pdf, Page, PdfOptions};
+use videodocparser::layout::{group_lines, join_words, page_text};
+use videodocparser::ocr::{language_tag, OcrFrameResult, OcrWord, Recognition};
+
+fn element(text: &str, bbox: (i32, i32, i32, i32)) -> OcrWord {
+    OcrWord { text: text.to_string(), bbox, confidence: 90.0 }
+}
+
+/// Two vertical lines of Japanese, read right to left, as `jpn_vert` gives them.
+fn vertical_page() -> Recognition {
+    Recognition {
+        words: vec![
+            element("縦書き", (300, 40, 340, 160)),
+            element("の", (300, 160, 340, 200)),
+            element("文章", (240, 40, 280, 120)),
+        ],
+        lines: vec![element("縦書きの", (300, 40, 340, 200)), element("文章", (240, 40, 280, 120))],
+        // Only the first line is flagged; the second is told apart by its shape.
+        vertical_lines: vec![true],
+        ..Recognition::default()
+    }
+}
+
+#[test]
+fn vertical_lines_are_carried_into_the_structure() {
+    assert!(element("縦書き", (300, 40, 340, 160)).is_vertical());
+    // A single character, or a wide box, says nothing.
+    assert!(!element("の", (300, 160, 340, 200)).is_vertical());
+    assert!(!element("Agenda", (10, 10, 90, 30)).is_vertical());
+
+    let vertical = |recognition: Recognition| -> Vec<bool> {
+        let blocks = recognition.structure();
+        blocks.iter().flat_map(|block| &block.paragraphs).flat_map(|p| &p.lines).map(|line| line.vertical).collect()
+    };
+    assert_eq!(vertical(vertical_page()), vec![true, true]);
+    // Without the engine's flags, both lines are told apart by their shape.
+    assert_eq!(vertical(Recognition { vertical_lines: Vec::new(), ..vertical_page() }), vec![true, true]);
+}
+
+#[test]
+fn chinese_and_japanese_words_are_joined_without_spaces() {
+    assert_eq!(join_words(["縦書き", "の"]), "縦書きの");
+    assert_eq!(join_words(["Rust", "入門", "講座"]), "Rust 入門講座");
+    assert_eq!(join_words(["한국어", "강의"]), "한국어 강의");
+
+    let recognition = vertical_page();
+    let blocks = recognition.structure();
+    let ocr = OcrFrameResult { frame_index: 0, words: recognition.words, blocks, strategy: None, timestamp: None };
+    assert_eq!(page_text(&ocr), "縦書きの\n文章");
+    // Characters of vertical lines are as large as the lines are wide.
+    let lines = group_lines(&ocr);
+    assert!(lines.iter().all(|line| line.vertical));
+    assert_eq!(lines[0].height(), 40);
+
+    assert_eq!(language_tag("jpn_vert"), "ja");
+    assert_eq!(language_tag("chi_tra_vert+eng"), "zh-Hant");
+}
+
+#[test]
+fn vertical_words_run_down_the_pdf_text_layer() {
+    let image = ImageBuffer::from_pixel(640, 480, Rgb([255u8, 255, 255]));
+    let recognition = vertical_page();
+    let blocks = recognition.structure();
+    let ocr = OcrFrameResult { frame_index: 0, words: recognition.words, blocks, strategy: None, timestamp: None };
+    let pages = vec![Page { image: &image, ocr: Some(&ocr), title: None, timestamp: None, end: None }];
+
+    let dir = std::env::temp_dir().join(format!("vdp-vertical-{}", std::process::id()));
+    let path = dir.join("document.pdf");
+    build_pdf(&pages, &PdfOptions::default(), &path).unwrap();
+
+    let document = Document::load(&path).unwrap();
+    let page_id = document.get_pages()[&1];
+    let content = String::from_utf8_lossy(&document.get_page_content(page_id).unwrap())
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    assert_eq!(content.matches(" 0 -1 1 0 ").count(), 3, "{content}");
+    let text: String = document.extract_text(&[1]).unwrap().split_whitespace().collect();
+    assert!(text.contains("縦書きの") && text.contains("文章"), "{text:?}");
+
+    std::fs::remove_dir_all(dir).ok();
+}
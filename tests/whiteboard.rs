@@ -0,0 +1,47 @@
+//! Checks cleaning up frames of whiteboards filmed under uneven lighting.
+
+use image::{Rgb, RgbImage};
+use videodocparser::frame_analyzer::HashSettings;
+use videodocparser::whiteboard::enhance;
+
+/// A board lit from the right, going from grey to near white, with a black
+/// and a red stroke.
+fn board() -> RgbImage {
+    RgbImage::from_fn(320, 160, |x, y| {
+        let lit = (150 + x * 90 / 320) as u8;
+        if (40..44).contains(&x) && (20..140).contains(&y) {
+            Rgb([lit / 5, lit / 5, lit / 5])
+        } else if (20..300).contains(&x) && (100..104).contains(&y) {
+            Rgb([lit, lit / 4, lit / 4])
+        } else {
+            Rgb([lit, lit, lit])
+        }
+    })
+}
+
+#[test]
+fn the_board_turns_white_and_the_strokes_stay() {
+    let frame = board();
+    let enhanced = enhance(&frame);
+    assert_eq!(enhanced.dimensions(), frame.dimensions());
+
+    // The board is white however it was lit, away from the strokes and next to them.
+    for (x, y) in [(5, 5), (160, 40), (315, 155), (36, 60), (48, 60), (250, 96)] {
+        assert_eq!(enhanced.get_pixel(x, y).0, [255, 255, 255], "board at ({}, {})", x, y);
+    }
+    // The black stroke is at least as dark as it was.
+    let black = enhanced.get_pixel(42, 60).0;
+    assert!(black.iter().all(|&channel| channel <= frame.get_pixel(42, 60).0[0]), "{:?}", black);
+    // The red stroke is as red on both sides of the board.
+    for x in [30, 290] {
+        let red = enhanced.get_pixel(x, 102).0;
+        assert!(red[0] > 200 && red[1] < 30 && red[2] < 30, "red at {}: {:?}", x, red);
+    }
+}
+
+#[test]
+fn the_profile_is_part_of_the_hash_settings() {
+    assert!(HashSettings::default().to_json().get("profile").is_none());
+    let settings = HashSettings { profile: Some("whiteboard"), ..Default::default() };
+    assert_eq!(settings.to_json()["profile"], "whiteboard");
+}